@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+use takumi::{layout::ColorScheme, rendering::ImageOutputFormat};
+
+/// Command line arguments for rendering a node tree into an image file.
+#[derive(Parser, Debug, Default)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+  /// Path to the node tree JSON file, or `-` to read it from stdin.
+  pub input: PathBuf,
+
+  /// Path to write the image to, or `-` to write it to stdout.
+  #[arg(short, long)]
+  pub output: PathBuf,
+
+  /// The image format, inferred from the output extension when omitted.
+  #[arg(short, long, value_enum)]
+  pub format: Option<OutputFormat>,
+
+  /// The encoding quality (0-100), for `webp`, `jpeg` and `avif`.
+  #[arg(short, long)]
+  pub quality: Option<u8>,
+
+  /// The viewport width, fits the content when omitted.
+  #[arg(long)]
+  pub width: Option<u32>,
+
+  /// The viewport height, fits the content when omitted.
+  #[arg(long)]
+  pub height: Option<u32>,
+
+  /// The device pixel ratio.
+  #[arg(long, default_value_t = 1.0)]
+  pub device_pixel_ratio: f32,
+
+  /// The color scheme that selects `dark` and `light` style blocks.
+  #[arg(long, value_enum, default_value_t = Scheme::Light)]
+  pub color_scheme: Scheme,
+
+  /// The BCP 47 locale that text node values are formatted for.
+  #[arg(long)]
+  pub locale: Option<String>,
+
+  /// Directory to load every font file from, searched recursively. Can be repeated.
+  #[arg(long = "fonts", value_name = "DIR")]
+  pub font_dirs: Vec<PathBuf>,
+
+  /// Directory to load every image file from, searched recursively. Images are matched by
+  /// their path relative to the directory, earlier directories win. Can be repeated.
+  #[arg(long = "images", value_name = "DIR")]
+  pub image_dirs: Vec<PathBuf>,
+
+  /// CSS stylesheet file to apply. Can be repeated.
+  #[arg(long = "stylesheet", value_name = "FILE")]
+  pub stylesheets: Vec<PathBuf>,
+
+  /// Whether to draw debug borders around layout elements.
+  #[arg(long)]
+  pub draw_debug_border: bool,
+}
+
+/// Image formats the CLI can write.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+  Png,
+  Webp,
+  Jpeg,
+  Avif,
+}
+
+impl OutputFormat {
+  /// Infers the format from a file extension, ignoring case.
+  pub fn from_extension(extension: &str) -> Option<Self> {
+    match extension.to_ascii_lowercase().as_str() {
+      "png" => Some(Self::Png),
+      "webp" => Some(Self::Webp),
+      "jpg" | "jpeg" => Some(Self::Jpeg),
+      "avif" => Some(Self::Avif),
+      _ => None,
+    }
+  }
+}
+
+impl From<OutputFormat> for ImageOutputFormat {
+  fn from(format: OutputFormat) -> Self {
+    match format {
+      OutputFormat::Png => ImageOutputFormat::Png,
+      OutputFormat::Webp => ImageOutputFormat::WebP,
+      OutputFormat::Jpeg => ImageOutputFormat::Jpeg,
+      OutputFormat::Avif => ImageOutputFormat::Avif,
+    }
+  }
+}
+
+/// Color schemes a render can be made in.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Scheme {
+  #[default]
+  Light,
+  Dark,
+}
+
+impl From<Scheme> for ColorScheme {
+  fn from(scheme: Scheme) -> Self {
+    match scheme {
+      Scheme::Light => ColorScheme::Light,
+      Scheme::Dark => ColorScheme::Dark,
+    }
+  }
+}
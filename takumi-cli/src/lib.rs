@@ -0,0 +1,268 @@
+use std::{
+  borrow::Cow,
+  collections::HashMap,
+  fs::{File, read, read_to_string},
+  io::{BufWriter, Read, Write, stdin, stdout},
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use globwalk::GlobWalkerBuilder;
+use serde_json::Value;
+use takumi::{
+  GlobalContext,
+  layout::{
+    Viewport,
+    node::{Node, NodeDeserializeError},
+    style::StyleSheet,
+  },
+  rendering::{RenderOptions, render, write_image},
+  resources::{
+    font::{FontError, FontResource},
+    image::{ImageResourceError, ImageSource},
+  },
+};
+use thiserror::Error;
+
+pub use crate::args::{Args, OutputFormat, Scheme};
+
+pub mod args;
+
+/// Font files loaded from `--fonts` directories.
+const FONT_PATTERNS: &[&str] = &["**/*.{ttf,otf,ttc,woff,woff2}"];
+
+/// Image files loaded from `--images` directories.
+const IMAGE_PATTERNS: &[&str] = &["**/*.{png,jpg,jpeg,webp,gif,avif,svg}"];
+
+/// Images keyed by the `src` nodes reference them with.
+pub type FetchedResources = HashMap<Arc<str>, Arc<ImageSource>>;
+
+/// Errors that stop the CLI from writing an image.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum CliError {
+  /// A file could not be read or written.
+  #[error("{}: {source}", path.display())]
+  Io {
+    /// The file being accessed.
+    path: PathBuf,
+    /// The underlying IO error.
+    source: std::io::Error,
+  },
+  /// The input is not valid JSON.
+  #[error("failed to parse the input as JSON: {0}")]
+  Json(#[from] serde_json::Error),
+  /// The input JSON is not a valid node tree.
+  #[error("{0}")]
+  Node(#[from] NodeDeserializeError),
+  /// A font file failed to load.
+  #[error("{}: {source}", path.display())]
+  Font {
+    /// The font file.
+    path: PathBuf,
+    /// The load failure.
+    source: FontError,
+  },
+  /// An image file failed to decode.
+  #[error("{}: {source}", path.display())]
+  Image {
+    /// The image file.
+    path: PathBuf,
+    /// The decode failure.
+    source: ImageResourceError,
+  },
+  /// A `--fonts` or `--images` directory could not be walked.
+  #[error("failed to search {}: {reason}", path.display())]
+  Directory {
+    /// The directory passed on the command line.
+    path: PathBuf,
+    /// The walk failure rendered as text.
+    reason: String,
+  },
+  /// The output format can't be inferred from the output path.
+  #[error("can't infer the image format of {}, pass --format", path.display())]
+  UnknownFormat {
+    /// The output path.
+    path: PathBuf,
+  },
+  /// Rendering or encoding failed.
+  #[error("{0}")]
+  Render(#[from] takumi::Error),
+}
+
+/// Renders the node tree named by `args` and writes the encoded image.
+pub fn run(args: Args) -> Result<(), CliError> {
+  let format = match args.format {
+    Some(format) => format,
+    None if args.output == Path::new("-") => OutputFormat::Png,
+    None => args
+      .output
+      .extension()
+      .and_then(|extension| extension.to_str())
+      .and_then(OutputFormat::from_extension)
+      .ok_or_else(|| CliError::UnknownFormat {
+        path: args.output.clone(),
+      })?,
+  };
+
+  let node = read_node(&args.input)?;
+  let stylesheet = read_stylesheets(&args.stylesheets)?;
+
+  let mut global = GlobalContext::default();
+  for dir in &args.font_dirs {
+    load_fonts(&mut global, dir)?;
+  }
+
+  let mut fetched_resources = FetchedResources::new();
+  for dir in &args.image_dirs {
+    load_images(&mut fetched_resources, dir)?;
+  }
+
+  let options = RenderOptions::builder()
+    .viewport(
+      Viewport::new((args.width, args.height))
+        .with_device_pixel_ratio(args.device_pixel_ratio)
+        .with_color_scheme(args.color_scheme.into()),
+    )
+    .node(node)
+    .global(&global)
+    .stylesheet(stylesheet)
+    .fetched_resources(fetched_resources)
+    .locale(args.locale)
+    .draw_debug_border(args.draw_debug_border)
+    .build();
+
+  let image = render(options)?;
+
+  if args.output == Path::new("-") {
+    let mut output = BufWriter::new(stdout().lock());
+    write_image(Cow::Owned(image), &mut output, format.into(), args.quality)?;
+    return output.flush().map_err(|source| CliError::Io {
+      path: args.output,
+      source,
+    });
+  }
+
+  let file = File::create(&args.output).map_err(|source| CliError::Io {
+    path: args.output.clone(),
+    source,
+  })?;
+  let mut output = BufWriter::new(file);
+  write_image(Cow::Owned(image), &mut output, format.into(), args.quality)?;
+
+  output.flush().map_err(|source| CliError::Io {
+    path: args.output,
+    source,
+  })
+}
+
+/// Reads and deserializes the node tree at `path`, or from stdin for `-`.
+pub fn read_node(path: &Path) -> Result<Node, CliError> {
+  let source = if path == Path::new("-") {
+    let mut source = String::new();
+    stdin()
+      .read_to_string(&mut source)
+      .map_err(|source| CliError::Io {
+        path: path.to_path_buf(),
+        source,
+      })?;
+    source
+  } else {
+    read_to_string(path).map_err(|source| CliError::Io {
+      path: path.to_path_buf(),
+      source,
+    })?
+  };
+
+  let value: Value = serde_json::from_str(&source)?;
+
+  Ok(Node::from_json_value(&value)?)
+}
+
+/// Reads every stylesheet file into one stylesheet, in order, discarding invalid rules like the
+/// bindings do.
+pub fn read_stylesheets(paths: &[PathBuf]) -> Result<StyleSheet, CliError> {
+  let sources = paths
+    .iter()
+    .map(|path| {
+      read_to_string(path).map_err(|source| CliError::Io {
+        path: path.clone(),
+        source,
+      })
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  Ok(StyleSheet::parse_owned_list_loosy(sources))
+}
+
+/// Loads every font file under `dir` into `global`.
+pub fn load_fonts(global: &mut GlobalContext, dir: &Path) -> Result<(), CliError> {
+  for path in walk(dir, FONT_PATTERNS)? {
+    let data = read_file(&path)?;
+
+    global
+      .font_context_mut()
+      .load_and_store(FontResource::new(data))
+      .map_err(|source| CliError::Font { path, source })?;
+  }
+
+  Ok(())
+}
+
+/// Decodes every image file under `dir` into `resources`, keyed by its path relative to `dir`
+/// with `/` separators. Images already in `resources` are kept, so earlier directories win.
+pub fn load_images(resources: &mut FetchedResources, dir: &Path) -> Result<(), CliError> {
+  for path in walk(dir, IMAGE_PATTERNS)? {
+    let Ok(relative) = path.strip_prefix(dir) else {
+      continue;
+    };
+    let src = relative
+      .components()
+      .map(|component| component.as_os_str().to_string_lossy())
+      .collect::<Vec<_>>()
+      .join("/");
+
+    if resources.contains_key(src.as_str()) {
+      continue;
+    }
+
+    let image = ImageSource::from_bytes(&read_file(&path)?)
+      .map_err(|source| CliError::Image { path, source })?;
+
+    resources.insert(src.into(), image);
+  }
+
+  Ok(())
+}
+
+fn walk(dir: &Path, patterns: &[&str]) -> Result<Vec<PathBuf>, CliError> {
+  let directory_error = |error: &dyn std::fmt::Display| CliError::Directory {
+    path: dir.to_path_buf(),
+    reason: error.to_string(),
+  };
+
+  let walker = GlobWalkerBuilder::from_patterns(dir, patterns)
+    .case_insensitive(true)
+    .build()
+    .map_err(|error| directory_error(&error))?;
+
+  let mut paths = walker
+    .map(|entry| {
+      entry
+        .map(|entry| entry.into_path())
+        .map_err(|error| directory_error(&error))
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  // Walk order depends on the file system, sort so loading is reproducible.
+  paths.sort();
+
+  Ok(paths)
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, CliError> {
+  read(path).map_err(|source| CliError::Io {
+    path: path.to_path_buf(),
+    source,
+  })
+}
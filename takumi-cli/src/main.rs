@@ -0,0 +1,15 @@
+use std::process::ExitCode;
+
+use clap::Parser;
+
+use takumi_cli::{Args, run};
+
+fn main() -> ExitCode {
+  match run(Args::parse()) {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(error) => {
+      eprintln!("error: {error}");
+      ExitCode::FAILURE
+    }
+  }
+}
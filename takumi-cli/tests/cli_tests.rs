@@ -0,0 +1,143 @@
+use std::{
+  env::temp_dir,
+  fs::{create_dir_all, write},
+  path::{Path, PathBuf},
+  process::id,
+};
+
+use clap::Parser;
+use takumi::image::{self, GenericImageView};
+use takumi_cli::{Args, CliError, OutputFormat, run};
+
+fn work_dir(name: &str) -> PathBuf {
+  let dir = temp_dir().join(format!("takumi-cli-{name}-{}", id()));
+  create_dir_all(&dir).unwrap();
+  dir
+}
+
+fn args(input: &Path, output: &Path, extra: &[&str]) -> Args {
+  Args::parse_from(
+    [
+      "takumi",
+      input.to_str().unwrap(),
+      "-o",
+      output.to_str().unwrap(),
+    ]
+    .into_iter()
+    .chain(extra.iter().copied()),
+  )
+}
+
+#[test]
+fn test_renders_node_tree_with_images_and_fonts() {
+  let dir = work_dir("render");
+  let input = dir.join("card.json");
+  let output = dir.join("card.png");
+
+  write(
+    &input,
+    r#"{
+      "type": "container",
+      "style": { "display": "flex", "width": 120, "height": 60 },
+      "children": [
+        { "type": "image", "src": "takumi.svg", "style": { "width": 60, "height": 60 } },
+        { "type": "text", "text": "Hi", "style": { "fontFamily": "Geist" } }
+      ]
+    }"#,
+  )
+  .unwrap();
+
+  run(args(
+    &input,
+    &output,
+    &[
+      "--images",
+      "../assets/images",
+      "--fonts",
+      "../assets/fonts/geist",
+      "--device-pixel-ratio",
+      "2",
+    ],
+  ))
+  .unwrap();
+
+  assert_eq!(image::open(&output).unwrap().dimensions(), (240, 120));
+}
+
+#[test]
+fn test_prefers_images_from_earlier_directories() {
+  let dir = work_dir("images");
+  let first = dir.join("first");
+  let second = dir.join("second");
+  let input = dir.join("card.json");
+  let output = dir.join("card.png");
+
+  create_dir_all(first.join("icons")).unwrap();
+  create_dir_all(second.join("icons")).unwrap();
+  write(
+    first.join("icons/dot.svg"),
+    r#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"><rect width="4" height="4" fill="red"/></svg>"#,
+  )
+  .unwrap();
+  write(
+    second.join("icons/dot.svg"),
+    r#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"><rect width="4" height="4" fill="blue"/></svg>"#,
+  )
+  .unwrap();
+  write(&input, r#"{ "type": "image", "src": "icons/dot.svg" }"#).unwrap();
+
+  run(args(
+    &input,
+    &output,
+    &[
+      "--images",
+      first.to_str().unwrap(),
+      "--images",
+      second.to_str().unwrap(),
+      "--width",
+      "4",
+      "--height",
+      "4",
+    ],
+  ))
+  .unwrap();
+
+  let image = image::open(&output).unwrap().to_rgba8();
+
+  assert_eq!(image.dimensions(), (4, 4));
+  assert_eq!(image.get_pixel(2, 2).0, [255, 0, 0, 255]);
+}
+
+#[test]
+fn test_reports_invalid_node_trees() {
+  let dir = work_dir("invalid");
+  let input = dir.join("card.json");
+
+  write(
+    &input,
+    r#"{ "type": "container", "style": { "width": "wide" } }"#,
+  )
+  .unwrap();
+
+  assert!(matches!(
+    run(args(&input, &dir.join("card.png"), &[])),
+    Err(CliError::Node(_))
+  ));
+}
+
+#[test]
+fn test_requires_a_known_output_format() {
+  let dir = work_dir("format");
+  let input = dir.join("card.json");
+
+  write(&input, r#"{ "type": "container" }"#).unwrap();
+
+  assert!(matches!(
+    run(args(&input, &dir.join("card.bmp"), &[])),
+    Err(CliError::UnknownFormat { .. })
+  ));
+  assert_eq!(
+    OutputFormat::from_extension("JPG"),
+    Some(OutputFormat::Jpeg)
+  );
+}
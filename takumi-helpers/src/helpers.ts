@@ -1,19 +1,24 @@
-import type { CSSProperties } from "react";
 import type {
+  AvatarGroupNode,
   ContainerNode,
+  CustomNode,
   ImageNode,
+  MapNode,
+  MarkdownNode,
   Node,
   NodeMetadata,
+  NodeStyle,
+  SvgNode,
   TextNode,
 } from "./types";
 
-function applyStyle(node: Node, style?: CSSProperties) {
+function applyStyle(node: Node, style?: NodeStyle) {
   if (style && Object.keys(style).length > 0) {
     node.style = style;
   }
 }
 
-function applyPreset(node: Node, preset?: CSSProperties) {
+function applyPreset(node: Node, preset?: NodeStyle) {
   if (preset && Object.keys(preset).length > 0) {
     node.preset = preset;
   }
@@ -54,12 +59,12 @@ export function container(props: Omit<ContainerNode, "type">): ContainerNode {
   return node;
 }
 
-export function text(text: string, style?: CSSProperties): TextNode;
+export function text(text: string, style?: NodeStyle): TextNode;
 export function text(props: Omit<TextNode, "type">): TextNode;
 
 export function text(
   props: Omit<TextNode, "type"> | string,
-  style?: CSSProperties,
+  style?: NodeStyle,
 ): TextNode {
   if (typeof props === "string") {
     const node: TextNode = {
@@ -77,6 +82,11 @@ export function text(
     text: props.text,
   };
 
+  if (props.value !== undefined) {
+    node.value = props.value;
+    node.format = props.format;
+  }
+
   if (props.tw) {
     node.tw = props.tw;
   }
@@ -107,7 +117,110 @@ export function image(props: Omit<ImageNode, "type">): ImageNode {
   return node;
 }
 
-export function style(style: CSSProperties) {
+export function svg(props: Omit<SvgNode, "type">): SvgNode {
+  const node: SvgNode = {
+    type: "svg",
+    svg: props.svg,
+    width: props.width,
+    height: props.height,
+  };
+
+  if (props.tw) {
+    node.tw = props.tw;
+  }
+
+  applyMetadata(node, props);
+  applyPreset(node, props.preset);
+  applyStyle(node, props.style);
+
+  return node;
+}
+
+export function avatarGroup(
+  props: Omit<AvatarGroupNode, "type">,
+): AvatarGroupNode {
+  const node: AvatarGroupNode = {
+    type: "avatarGroup",
+    srcs: props.srcs,
+    size: props.size,
+    max: props.max,
+    overlap: props.overlap,
+    ringWidth: props.ringWidth,
+    ringColor: props.ringColor,
+    badgeColor: props.badgeColor,
+    badgeTextColor: props.badgeTextColor,
+  };
+
+  if (props.tw) {
+    node.tw = props.tw;
+  }
+
+  applyMetadata(node, props);
+  applyPreset(node, props.preset);
+  applyStyle(node, props.style);
+
+  return node;
+}
+
+export function markdown(props: Omit<MarkdownNode, "type">): MarkdownNode {
+  const node: MarkdownNode = {
+    type: "markdown",
+    markdown: props.markdown,
+  };
+
+  if (props.tw) {
+    node.tw = props.tw;
+  }
+
+  applyMetadata(node, props);
+  applyPreset(node, props.preset);
+  applyStyle(node, props.style);
+
+  return node;
+}
+
+export function map(props: Omit<MapNode, "type">): MapNode {
+  const node: MapNode = {
+    type: "map",
+    lat: props.lat,
+    lng: props.lng,
+    zoom: props.zoom,
+    tileUrl: props.tileUrl,
+    width: props.width,
+    height: props.height,
+    tileSize: props.tileSize,
+    markers: props.markers,
+  };
+
+  if (props.tw) {
+    node.tw = props.tw;
+  }
+
+  applyMetadata(node, props);
+  applyPreset(node, props.preset);
+  applyStyle(node, props.style);
+
+  return node;
+}
+
+export function custom(props: Omit<CustomNode, "type">): CustomNode {
+  const node: CustomNode = {
+    type: "custom",
+    name: props.name,
+  };
+
+  if (props.tw) {
+    node.tw = props.tw;
+  }
+
+  applyMetadata(node, props);
+  applyPreset(node, props.preset);
+  applyStyle(node, props.style);
+
+  return node;
+}
+
+export function style(style: NodeStyle) {
   return style;
 }
 
@@ -2,26 +2,68 @@ import type { CSSProperties } from "react";
 
 export type NodeAttributes = Record<string, string>;
 
+/**
+ * Inline style of a node. `dark` and `light` hold declarations applied on top only when
+ * rendering with that `colorScheme`.
+ */
+export type NodeStyle = CSSProperties & {
+  dark?: CSSProperties;
+  light?: CSSProperties;
+};
+
 export type NodeMetadata = {
   tagName?: string;
   className?: string;
   id?: string;
   attributes?: NodeAttributes;
   tw?: string;
-  style?: CSSProperties;
-  preset?: CSSProperties;
+  style?: NodeStyle;
+  preset?: NodeStyle;
 };
 
-export type Node = ContainerNode | TextNode | ImageNode;
+export type Node =
+  | ContainerNode
+  | TextNode
+  | ImageNode
+  | CustomNode
+  | MapNode
+  | SvgNode
+  | AvatarGroupNode
+  | MarkdownNode;
 
 export type ContainerNode = NodeMetadata & {
   type: "container";
   children?: Node[];
 };
 
+export type NumberFormat = {
+  /** @default 0 */
+  minimumFractionDigits?: number;
+  /** Defaults to 3 for numbers and 0 for percentages. */
+  maximumFractionDigits?: number;
+  /** @default true */
+  useGrouping?: boolean;
+};
+
+/** How a text node `value` is formatted for the render's `locale`. */
+export type TextFormat =
+  | ({ type: "number" } & NumberFormat)
+  | ({ type: "percent" } & NumberFormat)
+  | {
+      /** A Unix timestamp in milliseconds, shown as a UTC date. */
+      type: "date";
+      /** @default "medium" */
+      dateStyle?: "short" | "medium" | "long";
+      /** Whether to show the hour and minute after the date. */
+      time?: boolean;
+    };
+
 export type TextNode = NodeMetadata & {
   type: "text";
   text: string;
+  /** A raw number that replaces `text` once formatted with `format`. */
+  value?: number;
+  format?: TextFormat;
 };
 
 export type ImageNode = NodeMetadata & {
@@ -30,3 +72,65 @@ export type ImageNode = NodeMetadata & {
   width?: number;
   height?: number;
 };
+
+/** Inline SVG markup, rasterized at the laid out size of the node. */
+export type SvgNode = NodeMetadata & {
+  type: "svg";
+  svg: string;
+  width?: number;
+  height?: number;
+};
+
+/** Expands into a row of overlapping circular avatars with an optional `+K` overflow badge. */
+export type AvatarGroupNode = NodeMetadata & {
+  type: "avatarGroup";
+  srcs: string[];
+  /** Diameter of each avatar in pixels, defaults to 40. */
+  size?: number;
+  /** Maximum number of avatars shown before the overflow badge. */
+  max?: number;
+  /** How far each avatar overlaps the previous one, defaults to a quarter of `size`. */
+  overlap?: number;
+  /** Width of the ring around each avatar, defaults to 2. */
+  ringWidth?: number;
+  /** CSS color of the ring, defaults to white. */
+  ringColor?: string;
+  badgeColor?: string;
+  badgeTextColor?: string;
+};
+
+/**
+ * Expands into headings, paragraphs, lists, block quotes and code blocks with default styles.
+ * Generated nodes carry their HTML tag names, so stylesheets can restyle them.
+ */
+export type MarkdownNode = NodeMetadata & {
+  type: "markdown";
+  markdown: string;
+};
+
+export type MapMarker = {
+  lat: number;
+  lng: number;
+  /** CSS color of the marker, defaults to red. */
+  color?: string;
+};
+
+export type MapNode = NodeMetadata & {
+  type: "map";
+  lat: number;
+  lng: number;
+  zoom: number;
+  /** Tile URL template with `{z}`, `{x}` and `{y}` placeholders. */
+  tileUrl: string;
+  width: number;
+  height: number;
+  /** Size of the tiles served by `tileUrl`, defaults to 256. */
+  tileSize?: number;
+  markers?: MapMarker[];
+};
+
+export type CustomNode = NodeMetadata & {
+  type: "custom";
+  /** Name of the drawer registered on the renderer. */
+  name: string;
+};
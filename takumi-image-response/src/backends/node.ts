@@ -115,11 +115,17 @@ function createStream(component: ReactNode, options?: ImageResponseOptions) {
         const mergedOptions = {
           width: options?.width,
           height: options?.height,
+          maxWidth: options?.maxWidth,
+          maxHeight: options?.maxHeight,
           format: options?.format,
           quality: options?.quality,
           dithering: options?.dithering,
+          now: options?.now,
+          seed: options?.seed,
+          locale: options?.locale,
           drawDebugBorder: options?.drawDebugBorder,
           devicePixelRatio: options?.devicePixelRatio,
+          colorScheme: options?.colorScheme,
           fetchedResources,
           stylesheets: [...(options?.stylesheets ?? []), ...stylesheets],
         };
@@ -143,6 +149,7 @@ const contentTypeMapping = {
   webp: "image/webp",
   png: "image/png",
   jpeg: "image/jpeg",
+  avif: "image/avif",
   raw: "application/octet-stream",
 };
 
@@ -170,6 +170,7 @@ const contentTypeMapping = {
   png: "image/png",
   jpeg: "image/jpeg",
   webp: "image/webp",
+  avif: "image/avif",
   raw: "application/octet-stream",
 };
 
@@ -0,0 +1,68 @@
+use std::sync::mpsc;
+
+use napi::{
+  Status,
+  bindgen_prelude::*,
+  threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode},
+};
+use takumi::{
+  Error as TakumiError, Result as TakumiResult,
+  image::RgbaImage,
+  layout::node::{CustomCanvas, CustomDraw},
+};
+
+pub(crate) type CustomDrawCallback =
+  ThreadsafeFunction<FnArgs<(u32, u32)>, Uint8Array, FnArgs<(u32, u32)>, Status, false, true>;
+
+/// Draws a custom node by asking a JavaScript callback for raw RGBA pixels of the content box.
+pub(crate) struct JsCustomDraw {
+  pub(crate) callback: CustomDrawCallback,
+}
+
+impl CustomDraw for JsCustomDraw {
+  fn draw(&self, canvas: &mut CustomCanvas<'_>) -> TakumiResult<()> {
+    let size = canvas.size();
+    let (width, height) = (size.width.round() as u32, size.height.round() as u32);
+
+    if width == 0 || height == 0 {
+      return Ok(());
+    }
+
+    // The callback runs on the JS thread, block the render thread until it returns.
+    let (sender, receiver) = mpsc::channel();
+    let status = self.callback.call_with_return_value(
+      FnArgs::from((width, height)),
+      ThreadsafeFunctionCallMode::Blocking,
+      move |result: Result<Uint8Array>, _| {
+        let _ = sender.send(
+          result
+            .map(|data| data.to_vec())
+            .map_err(|e| e.reason.clone()),
+        );
+        Ok(())
+      },
+    );
+
+    if status != Status::Ok {
+      return Err(TakumiError::CustomDrawError(format!(
+        "Failed to call custom draw callback: {status}"
+      )));
+    }
+
+    let data = receiver
+      .recv()
+      .map_err(|_| TakumiError::CustomDrawError("Custom draw callback was dropped".to_owned()))?
+      .map_err(TakumiError::CustomDrawError)?;
+
+    let Some(image) = RgbaImage::from_raw(width, height, data) else {
+      return Err(TakumiError::CustomDrawError(format!(
+        "Custom draw callback must return {} bytes of RGBA data for {width}x{height}",
+        width as usize * height as usize * 4
+      )));
+    };
+
+    canvas.draw_image(&image, 0.0, 0.0);
+
+    Ok(())
+  }
+}
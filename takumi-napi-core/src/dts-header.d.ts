@@ -1,9 +1,15 @@
-import type { Node } from "@takumi-rs/helpers";
+import type { Node, NodeMetadata } from "@takumi-rs/helpers";
 
 export type {
+  AvatarGroupNode,
   ContainerNode,
+  CustomNode,
   ImageNode,
+  MapMarker,
+  MapNode,
+  MarkdownNode,
   NodeMetadata,
+  SvgNode,
   TextNode,
 } from "@takumi-rs/helpers";
 
@@ -31,8 +37,49 @@ export interface FontDetails {
     | "oblique"
     | `oblique ${number}deg`
     | (string & {});
+  /**
+   * Appends this font to the fallback chain of the given family, which is tried
+   * for characters that family has no glyphs for, e.g. `"Inter"`.
+   */
+  fallbackFor?: string;
+  /**
+   * Loads only the face at this index of a TrueType Collection (`.ttc`), instead of every face in it.
+   */
+  faceIndex?: number;
 }
 
 export type Font = FontDetails | Uint8Array | ArrayBuffer;
 
 export type Keyframes = Record<string, Record<string, Record<string, unknown>>>;
+
+/**
+ * Machine readable classification attached to errors thrown by the renderer.
+ */
+export type ErrorCode =
+  | "RESOURCE_FETCH_FAILED"
+  | "IMAGE_DECODE_ERROR"
+  | "FONT_PARSE_ERROR"
+  | "INVALID_NODE"
+  | "ENCODE_ERROR"
+  | "ABORTED";
+
+export interface TakumiError extends Error {
+  code: ErrorCode;
+  /**
+   * Whether retrying the same request may succeed.
+   */
+  retriable: boolean;
+  /**
+   * Location of the offending value for `INVALID_NODE` errors, e.g. `children[2].style.background`.
+   * Empty for the root node.
+   */
+  path?: string;
+  /**
+   * The style property that failed to parse, if any.
+   */
+  property?: string | null;
+  /**
+   * The token the CSS parser stopped at, if any.
+   */
+  token?: string | null;
+}
@@ -7,28 +7,32 @@ use std::{
 use napi::bindgen_prelude::*;
 use rayon::prelude::*;
 use takumi::{
-  layout::{DEFAULT_DEVICE_PIXEL_RATIO, Viewport, node::Node},
+  ErrorCode,
+  layout::{DEFAULT_DEVICE_PIXEL_RATIO, Viewport, node::Node, style::Color},
   rendering::{
     AnimatedGifOptions, AnimatedPngOptions, AnimatedWebpOptions, AnimationFrame,
     encode_animated_gif, encode_animated_png, encode_animated_webp, render,
   },
-  resources::image::ImageSource as LoadedImageSource,
 };
 
 use crate::{
-  ExternalMemoryAccountable, buffer_from_object, map_error, parse_stylesheet,
-  renderer::{AnimationOutputFormat, EncodeFramesOptions, ImageSource, RendererState},
+  ExternalMemoryAccountable, PendingImage, parse_matte, parse_stylesheet, pending_images,
+  record_error_code, reject_with_code,
+  renderer::{AnimationOutputFormat, CustomDrawers, EncodeFramesOptions, Renderer, RendererState},
 };
 
 pub struct EncodeFramesTask {
   pub frames: Option<Vec<(Node, u32)>>,
   pub(crate) state: Arc<RwLock<RendererState>>,
+  pub(crate) custom_drawers: CustomDrawers,
   pub viewport: Viewport,
   pub format: AnimationOutputFormat,
   pub quality: Option<u8>,
+  pub matte: Option<Color>,
   pub draw_debug_border: bool,
   pub stylesheets: Option<Vec<String>>,
-  pub fetched_resources: HashMap<Arc<str>, Buffer>,
+  pub(crate) fetched_resources: HashMap<Arc<str>, PendingImage>,
+  pub error_code: Option<ErrorCode>,
 }
 
 impl EncodeFramesTask {
@@ -36,29 +40,27 @@ impl EncodeFramesTask {
     env: Env,
     frames: Vec<(Node, u32)>,
     options: EncodeFramesOptions,
-    state: Arc<RwLock<RendererState>>,
+    renderer: &Renderer,
   ) -> Result<Self> {
     Ok(Self {
       frames: Some(frames),
-      state,
-      viewport: Viewport::new((options.width, options.height)).with_device_pixel_ratio(
-        options
-          .device_pixel_ratio
-          .map(|ratio| ratio as f32)
-          .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
-      ),
+      state: Arc::clone(&renderer.state),
+      custom_drawers: renderer.custom_drawers()?,
+      viewport: Viewport::new((options.width, options.height))
+        .with_device_pixel_ratio(
+          options
+            .device_pixel_ratio
+            .map(|ratio| ratio as f32)
+            .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
+        )
+        .with_color_scheme(options.color_scheme.map(Into::into).unwrap_or_default()),
       format: options.format.unwrap_or(AnimationOutputFormat::WebP),
       quality: options.quality,
+      matte: parse_matte(options.matte)?,
       draw_debug_border: options.draw_debug_border.unwrap_or_default(),
       stylesheets: options.stylesheets,
-      fetched_resources: options
-        .fetched_resources
-        .unwrap_or_default()
-        .into_iter()
-        .map(|image: ImageSource<'_>| {
-          Ok((Arc::from(image.src), buffer_from_object(env, image.data)?))
-        })
-        .collect::<Result<_>>()?,
+      fetched_resources: pending_images(env, options.fetched_resources)?,
+      error_code: None,
     })
   }
 }
@@ -75,20 +77,22 @@ impl Task for EncodeFramesTask {
     let Some(frames) = self.frames.take() else {
       unreachable!()
     };
+    let state = self
+      .state
+      .read()
+      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
     let initialized_images = self
       .fetched_resources
       .iter()
       .map(|(key, value)| {
         Ok((
           key.clone(),
-          LoadedImageSource::from_bytes(value).map_err(map_error)?,
+          value
+            .decode(state.global.image_cache())
+            .map_err(record_error_code(&mut self.error_code))?,
         ))
       })
       .collect::<Result<HashMap<_, _>, _>>()?;
-    let state = self
-      .state
-      .read()
-      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
 
     let viewport = self.viewport;
     let draw_debug_border = self.draw_debug_border;
@@ -104,14 +108,15 @@ impl Task for EncodeFramesTask {
               .stylesheet(stylesheet.clone())
               .node(node)
               .global(&state.global)
+              .custom_drawers(self.custom_drawers.clone())
               .draw_debug_border(draw_debug_border)
               .build(),
-          )
-          .map_err(map_error)?,
+          )?,
           duration_ms,
         ))
       })
-      .collect::<Result<Vec<_>, _>>()?;
+      .collect::<takumi::Result<Vec<_>>>()
+      .map_err(record_error_code(&mut self.error_code))?;
 
     let estimated_capacity = if let Some(first) = frames.first() {
       let width = first.image.width() as usize;
@@ -145,19 +150,19 @@ impl Task for EncodeFramesTask {
         }
 
         encode_animated_webp(Cow::Owned(frames), &mut buffer, options)
-          .map_err(|e| Error::from_reason(e.to_string()))?;
+          .map_err(record_error_code(&mut self.error_code))?;
       }
       AnimationOutputFormat::Apng => {
         encode_animated_png(&frames, &mut buffer, AnimatedPngOptions::default())
-          .map_err(|e| Error::from_reason(e.to_string()))?;
+          .map_err(record_error_code(&mut self.error_code))?;
       }
       AnimationOutputFormat::Gif => {
         encode_animated_gif(
           Cow::Owned(frames),
           &mut buffer,
-          AnimatedGifOptions::default(),
+          AnimatedGifOptions::builder().matte(self.matte).build(),
         )
-        .map_err(|e| Error::from_reason(e.to_string()))?;
+        .map_err(record_error_code(&mut self.error_code))?;
       }
     }
 
@@ -168,4 +173,8 @@ impl Task for EncodeFramesTask {
     output.account_external_memory(&mut env)?;
     Ok(output.into())
   }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.error_code)
+  }
 }
@@ -1,8 +1,19 @@
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
-use takumi::{layout::node::Node, resources::task::FetchTaskCollection};
+use takumi::{
+  layout::{
+    node::{Node, ValidationLimits},
+    style::{FromCss, ObjectFit},
+  },
+  rendering::{TransformImageOptions as CoreTransformImageOptions, check_rgba_buffer},
+  resources::task::FetchTaskCollection,
+};
 
-use crate::deserialize_with_tracing;
+use crate::{
+  buffer_from_object, coded_error, deserialize_with_tracing,
+  image_codec_task::{DecodeImageTask, EncodeImageTask, TransformImageTask},
+  renderer::{LayoutRegion, MeasuredNode, OutputFormat},
+};
 
 /// Collects the fetch task urls from the node.
 #[napi(ts_args_type = "node: Node")]
@@ -22,3 +33,213 @@ pub fn extract_resource_urls(node: Object) -> Result<Vec<String>> {
       .collect(),
   )
 }
+
+/// Hashes the node tree with equivalent style spellings normalized, as a hex string.
+///
+/// Equal hashes mean the trees are equivalent. Options like the viewport, stylesheets, fonts and
+/// image contents aren't covered, use `Renderer.renderHash` for those.
+#[napi(ts_args_type = "node: Node")]
+pub fn canonical_hash(node: Object) -> Result<String> {
+  let node: Node = deserialize_with_tracing(node)?;
+
+  Ok(format!("{:016x}", node.canonical_hash()))
+}
+
+/// Compares two layouts returned by `measure` and returns the regions of the output that differ.
+///
+/// Only layout changes (size, position, transform and text) are detected, paint-only changes are not.
+#[napi]
+pub fn diff_layouts(before: MeasuredNode, after: MeasuredNode) -> Vec<LayoutRegion> {
+  takumi::rendering::diff_layouts(&before.into(), &after.into())
+    .into_iter()
+    .map(Into::into)
+    .collect()
+}
+
+/// Returns the child index path to the topmost node of a `measure` result at the given point.
+///
+/// Returns `null` when the point doesn't hit any node.
+#[napi]
+pub fn hit_test(layout: MeasuredNode, x: f64, y: f64) -> Option<Vec<u32>> {
+  let layout: takumi::rendering::MeasuredNode = layout.into();
+
+  layout
+    .hit_test(x as f32, y as f32)
+    .map(|path| path.into_iter().map(|index| index as u32).collect())
+}
+
+/// Options for validating a node tree.
+#[napi(object)]
+#[derive(Default)]
+pub struct ValidateNodeOptions {
+  /// Maximum nesting depth, where the root node has depth 0.
+  pub max_depth: Option<u32>,
+  /// Maximum total number of nodes in the tree.
+  pub max_nodes: Option<u32>,
+}
+
+impl From<ValidateNodeOptions> for ValidationLimits {
+  fn from(options: ValidateNodeOptions) -> Self {
+    let mut limits = ValidationLimits::default();
+    limits.max_depth = options.max_depth.map(|depth| depth as usize);
+    limits.max_nodes = options.max_nodes.map(|nodes| nodes as usize);
+    limits
+  }
+}
+
+/// A problem found while validating a node tree.
+#[napi(object)]
+pub struct NodeValidationIssue {
+  /// Location of the node, e.g. `children[2].children[0]`. Empty for the root.
+  pub path: String,
+  /// Human readable description of the problem.
+  pub message: String,
+}
+
+/// Validates the node tree without rendering, returning every problem found.
+///
+/// A node that fails to deserialize is reported as a single issue at the root.
+#[napi(ts_args_type = "node: Node, options?: ValidateNodeOptions")]
+pub fn validate_node(
+  node: Object,
+  options: Option<ValidateNodeOptions>,
+) -> Vec<NodeValidationIssue> {
+  let node: Node = match deserialize_with_tracing(node) {
+    Ok(node) => node,
+    Err(err) => {
+      return vec![NodeValidationIssue {
+        path: String::new(),
+        message: err.reason.clone(),
+      }];
+    }
+  };
+
+  node
+    .validate(options.unwrap_or_default().into())
+    .into_iter()
+    .map(|issue| NodeValidationIssue {
+      message: issue.kind.to_string(),
+      path: issue.path,
+    })
+    .collect()
+}
+
+/// Options for encoding raw pixels.
+#[napi(object)]
+#[derive(Default)]
+pub struct EncodeImageOptions {
+  /// The quality of JPEG, WebP and AVIF formats (0-100).
+  pub quality: Option<u8>,
+}
+
+/// Encodes raw RGBA pixels, such as the output of `renderRaw`, into an image format.
+///
+/// `raw` returns the pixels unchanged.
+#[napi(
+  ts_args_type = "rgba: Uint8Array | ArrayBuffer, width: number, height: number, format: OutputFormat, options?: EncodeImageOptions, signal?: AbortSignal",
+  ts_return_type = "Promise<Buffer>"
+)]
+pub fn encode_image(
+  env: Env,
+  rgba: Object,
+  width: u32,
+  height: u32,
+  format: OutputFormat,
+  options: Option<EncodeImageOptions>,
+  signal: Option<AbortSignal>,
+) -> Result<AsyncTask<EncodeImageTask>> {
+  let pixels = buffer_from_object(env, rgba)?;
+
+  check_rgba_buffer(width, height, pixels.len())
+    .map_err(|err| coded_error(&env, err.code(), err))?;
+
+  Ok(AsyncTask::with_optional_signal(
+    EncodeImageTask {
+      width,
+      height,
+      pixels,
+      format,
+      quality: options.unwrap_or_default().quality,
+      error_code: None,
+    },
+    signal,
+  ))
+}
+
+/// Decodes an encoded image into raw RGBA pixels. SVGs are rasterized at their declared size.
+#[napi(
+  ts_args_type = "bytes: Uint8Array | ArrayBuffer, signal?: AbortSignal",
+  ts_return_type = "Promise<RawImage>"
+)]
+pub fn decode_image(
+  env: Env,
+  bytes: Object,
+  signal: Option<AbortSignal>,
+) -> Result<AsyncTask<DecodeImageTask>> {
+  Ok(AsyncTask::with_optional_signal(
+    DecodeImageTask {
+      buffer: buffer_from_object(env, bytes)?,
+      error_code: None,
+    },
+    signal,
+  ))
+}
+
+/// Options for transforming an image.
+#[napi(object)]
+#[derive(Default)]
+pub struct TransformImageOptions {
+  /// The output width in pixels. Derived from the aspect ratio when only `height` is set.
+  pub width: Option<u32>,
+  /// The output height in pixels. Derived from the aspect ratio when only `width` is set.
+  pub height: Option<u32>,
+  /// How the image fits the output box when both `width` and `height` are set, like CSS `object-fit`.
+  /// @default "fill"
+  #[napi(ts_type = "'fill' | 'contain' | 'cover' | 'scale-down' | 'none'")]
+  pub fit: Option<String>,
+  /// The output format.
+  /// @default "png"
+  pub format: Option<OutputFormat>,
+  /// The quality of JPEG, WebP and AVIF formats (0-100).
+  pub quality: Option<u8>,
+}
+
+/// Decodes an image, resizes it with a Lanczos3 resampler and encodes the result.
+///
+/// SVGs are rasterized at the target size. Areas the image doesn't cover, as with `contain`,
+/// are left transparent.
+#[napi(
+  ts_args_type = "bytes: Uint8Array | ArrayBuffer, options?: TransformImageOptions, signal?: AbortSignal",
+  ts_return_type = "Promise<Buffer>"
+)]
+pub fn transform_image(
+  env: Env,
+  bytes: Object,
+  options: Option<TransformImageOptions>,
+  signal: Option<AbortSignal>,
+) -> Result<AsyncTask<TransformImageTask>> {
+  let options = options.unwrap_or_default();
+  let fit = options
+    .fit
+    .map(|fit| {
+      <ObjectFit as FromCss>::from_str(&fit)
+        .map_err(|_| Error::new(Status::InvalidArg, format!("Invalid fit {fit:?}")))
+    })
+    .transpose()?
+    .unwrap_or_default();
+
+  Ok(AsyncTask::with_optional_signal(
+    TransformImageTask {
+      buffer: buffer_from_object(env, bytes)?,
+      options: CoreTransformImageOptions::builder()
+        .width(options.width)
+        .height(options.height)
+        .fit(fit)
+        .build(),
+      format: options.format.unwrap_or(OutputFormat::Png),
+      quality: options.quality,
+      error_code: None,
+    },
+    signal,
+  ))
+}
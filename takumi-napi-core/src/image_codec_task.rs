@@ -0,0 +1,136 @@
+use std::borrow::Cow;
+
+use napi::bindgen_prelude::*;
+use takumi::{
+  ErrorCode,
+  image::RgbaImage,
+  rendering::{TransformImageOptions, rgba_image_from_raw, transform_image, write_image},
+  resources::image::ImageSource as LoadedImageSource,
+};
+
+use crate::{
+  ExternalMemoryAccountable, record_error_code, reject_with_code,
+  renderer::{OutputFormat, RawImage},
+};
+
+/// Encodes raw RGBA pixels into an image format.
+pub struct EncodeImageTask {
+  pub width: u32,
+  pub height: u32,
+  pub pixels: Buffer,
+  pub format: OutputFormat,
+  pub quality: Option<u8>,
+  pub error_code: Option<ErrorCode>,
+}
+
+impl Task for EncodeImageTask {
+  type Output = Vec<u8>;
+  type JsValue = Buffer;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    if self.format == OutputFormat::Raw {
+      return Ok(self.pixels.to_vec());
+    }
+
+    let image = rgba_image_from_raw(self.width, self.height, self.pixels.to_vec())
+      .map_err(record_error_code(&mut self.error_code))?;
+
+    encode(image, self.format, self.quality, &mut self.error_code)
+  }
+
+  fn resolve(&mut self, mut env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    output.account_external_memory(&mut env)?;
+    Ok(output.into())
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.error_code)
+  }
+}
+
+/// Decodes an encoded image into raw RGBA pixels.
+pub struct DecodeImageTask {
+  pub buffer: Buffer,
+  pub error_code: Option<ErrorCode>,
+}
+
+impl Task for DecodeImageTask {
+  type Output = (u32, u32, Vec<u8>);
+  type JsValue = RawImage;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let source = LoadedImageSource::from_bytes(&self.buffer)
+      .map_err(record_error_code(&mut self.error_code))?;
+    let image = source
+      .to_rgba_image()
+      .map_err(record_error_code(&mut self.error_code))?;
+    let (width, height) = image.dimensions();
+
+    Ok((width, height, image.into_owned().into_raw()))
+  }
+
+  fn resolve(&mut self, mut env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    let (width, height, data) = output;
+    data.account_external_memory(&mut env)?;
+
+    Ok(RawImage {
+      width,
+      height,
+      data: data.into(),
+    })
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.error_code)
+  }
+}
+
+/// Decodes an image, resizes it and encodes the result.
+pub struct TransformImageTask {
+  pub buffer: Buffer,
+  pub options: TransformImageOptions,
+  pub format: OutputFormat,
+  pub quality: Option<u8>,
+  pub error_code: Option<ErrorCode>,
+}
+
+impl Task for TransformImageTask {
+  type Output = Vec<u8>;
+  type JsValue = Buffer;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let source = LoadedImageSource::from_bytes(&self.buffer)
+      .map_err(record_error_code(&mut self.error_code))?;
+    let image =
+      transform_image(&source, self.options).map_err(record_error_code(&mut self.error_code))?;
+
+    if self.format == OutputFormat::Raw {
+      return Ok(image.into_raw());
+    }
+
+    encode(image, self.format, self.quality, &mut self.error_code)
+  }
+
+  fn resolve(&mut self, mut env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    output.account_external_memory(&mut env)?;
+    Ok(output.into())
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.error_code)
+  }
+}
+
+fn encode(
+  image: RgbaImage,
+  format: OutputFormat,
+  quality: Option<u8>,
+  error_code: &mut Option<ErrorCode>,
+) -> Result<Vec<u8>> {
+  let mut buffer = Vec::new();
+
+  write_image(Cow::Owned(image), &mut buffer, format.into(), quality)
+    .map_err(record_error_code(error_code))?;
+
+  Ok(buffer)
+}
@@ -9,33 +9,53 @@
   clippy::must_use_candidate
 )]
 
+mod custom_draw;
 mod encode_frames_task;
 mod helper;
+mod image_codec_task;
 mod load_font_task;
 mod measure_task;
 mod put_persistent_image_task;
 mod render_animation_task;
+mod render_changes_task;
+mod render_hash_task;
+mod render_layers_task;
+mod render_profile_task;
+mod render_raw_task;
+mod render_stream_task;
 mod render_task;
 pub(crate) mod renderer;
+mod replace_font_task;
+mod warmup_task;
 
-use std::{fmt::Display, ops::Deref};
+use std::{collections::HashMap, fmt::Display, ops::Deref, sync::Arc};
 
-use napi::{De, Env, Error, bindgen_prelude::*};
+use napi::{De, Env, Error, JsValue, bindgen_prelude::*};
 use serde::{Deserialize, Deserializer, de::DeserializeOwned};
 use takumi::{
-  layout::style::{KeyframesRule, StyleSheet},
+  ErrorCode, GlobalContext, ScopedGlobalContext,
+  layout::node::Node,
+  layout::style::{Color, FromCss, KeyframesRule, StyleSheet},
   parley::{FontStyle, FontWeight, fontique::FontInfoOverride},
-  resources::font::FontResource,
+  resources::{
+    font::FontResource,
+    image::{ImageResourceError, ImageSource as LoadedImageSource},
+    image_cache::ImageDecodeCache,
+  },
 };
 
 pub use helper::*;
+use renderer::ImageSource;
 pub use renderer::Renderer;
 
 #[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct FontInput {
   pub name: Option<String>,
   pub weight: Option<f64>,
   pub style: Option<FontStyleInput>,
+  pub fallback_for: Option<String>,
+  pub face_index: Option<u32>,
 }
 
 #[derive(Clone, Copy)]
@@ -51,6 +71,51 @@ impl<'de> Deserialize<'de> for FontStyleInput {
   }
 }
 
+/// Encoded image bytes kept until the task decodes them off the main thread.
+pub(crate) struct PendingImage {
+  data: Buffer,
+  max_decode_size: Option<u32>,
+}
+
+impl PendingImage {
+  pub(crate) fn decode(
+    &self,
+    cache: &ImageDecodeCache,
+  ) -> std::result::Result<Arc<LoadedImageSource>, ImageResourceError> {
+    cache.decode(&self.data, self.max_decode_size)
+  }
+}
+
+/// Collects the buffers of the fetched resources passed to a render.
+pub(crate) fn pending_images(
+  env: Env,
+  images: Option<Vec<ImageSource>>,
+) -> Result<HashMap<Arc<str>, PendingImage>> {
+  images
+    .unwrap_or_default()
+    .into_iter()
+    .map(|image| {
+      Ok((
+        Arc::from(image.src),
+        PendingImage {
+          data: buffer_from_object(env, image.data)?,
+          max_decode_size: image.max_decode_size,
+        },
+      ))
+    })
+    .collect()
+}
+
+pub(crate) fn decode_image_source(
+  data: &[u8],
+  max_decode_size: Option<u32>,
+) -> std::result::Result<Arc<LoadedImageSource>, ImageResourceError> {
+  match max_decode_size {
+    Some(max_size) => LoadedImageSource::from_bytes_with_max_size(data, max_size),
+    None => LoadedImageSource::from_bytes(data),
+  }
+}
+
 pub(crate) fn buffer_from_object(env: Env, value: Object) -> Result<Buffer> {
   if value.is_buffer()? {
     let buffer = unsafe { BufferSlice::from_napi_value(env.raw(), value.raw()) }?;
@@ -78,18 +143,61 @@ pub(crate) fn resolve_font_resource<'a>(
   font: &'a FontInput,
   buffer: &'a [u8],
 ) -> Result<FontResource<'a>> {
-  FontResource::new(buffer)
-    .override_info(FontInfoOverride {
-      family_name: font.name.as_deref(),
-      width: None,
-      style: font.style.map(|style| style.0),
-      weight: font.weight.map(|weight| FontWeight::new(weight as f32)),
-      axes: None,
-    })
+  let mut resource = FontResource::new(buffer).override_info(FontInfoOverride {
+    family_name: font.name.as_deref(),
+    width: None,
+    style: font.style.map(|style| style.0),
+    weight: font.weight.map(|weight| FontWeight::new(weight as f32)),
+    axes: None,
+  });
+
+  if let Some(family) = font.fallback_for.as_deref() {
+    resource = resource.fallback_for(family);
+  }
+
+  if let Some(index) = font.face_index {
+    resource = resource.face_index(index);
+  }
+
+  resource
     .into_resolved()
     .map_err(|e| Error::from_reason(format!("Failed to load font: {e}")))
 }
 
+/// Copies the fonts of `RenderOptions.fonts` so they can be loaded off the main thread.
+pub(crate) fn parse_scoped_fonts(
+  env: Env,
+  fonts: Option<Vec<Object>>,
+) -> Result<Vec<(FontInput, Buffer)>> {
+  fonts
+    .unwrap_or_default()
+    .into_iter()
+    .map(|font| parse_font_input(env, font))
+    .collect()
+}
+
+/// Loads render-scoped fonts on top of `global`, returning `None` when there are none.
+pub(crate) fn scoped_context(
+  global: &GlobalContext,
+  fonts: &[(FontInput, Buffer)],
+  error_code: &mut Option<ErrorCode>,
+) -> Result<Option<ScopedGlobalContext>> {
+  if fonts.is_empty() {
+    return Ok(None);
+  }
+
+  let resources = fonts
+    .iter()
+    .map(|(font, buffer)| resolve_font_resource(font, buffer.as_ref()))
+    .collect::<Result<Vec<_>>>()
+    .inspect_err(|_| *error_code = Some(ErrorCode::FontParseError))?;
+
+  global
+    .with_scoped_fonts(resources)
+    .map(Some)
+    .map_err(record_error_code(error_code))
+}
+
 pub(crate) enum BufferOrSlice<'env> {
   ArrayBuffer(ArrayBuffer<'env>),
   Buffer(BufferSlice<'env>),
@@ -143,10 +251,81 @@ pub(crate) fn deserialize_with_tracing<T: DeserializeOwned>(value: Object) -> Re
   T::deserialize(&mut de).map_err(|e| Error::from_reason(e.to_string()))
 }
 
+/// Deserializes a node tree, throwing an `INVALID_NODE` error with the `path`, `property` and
+/// `token` of the offending value attached.
+pub(crate) fn deserialize_node(env: &Env, source: Object) -> Result<Node> {
+  deserialize_with_tracing(source).map_err(|err| {
+    let located = serde_json::Value::deserialize(&mut De::new(&source))
+      .ok()
+      .and_then(|value| Node::locate_deserialize_error(&value));
+
+    let create = || -> Result<napi::Error> {
+      let mut error = env.create_error(napi::Error::from_reason(err.reason.as_str()))?;
+      error.set_named_property("code", ErrorCode::InvalidNode.as_str())?;
+      error.set_named_property("retriable", ErrorCode::InvalidNode.is_retriable())?;
+
+      if let Some(located) = located {
+        error.set_named_property("path", located.path)?;
+        error.set_named_property("property", located.property)?;
+        error.set_named_property("token", located.token)?;
+      }
+
+      Ok(napi::Error::from(error.to_unknown()))
+    };
+
+    create().unwrap_or_else(|_| coded_error(env, ErrorCode::InvalidNode, &err.reason))
+  })
+}
+
 pub(crate) fn map_error<E: Display>(err: E) -> napi::Error {
   napi::Error::from_reason(err.to_string())
 }
 
+/// Creates an error whose JS value carries `code` and `retriable` properties.
+pub(crate) fn coded_error<E: Display>(env: &Env, code: ErrorCode, err: E) -> napi::Error {
+  let reason = err.to_string();
+
+  let create = || -> Result<napi::Error> {
+    let mut error = env.create_error(napi::Error::from_reason(reason.as_str()))?;
+    error.set_named_property("code", code.as_str())?;
+    error.set_named_property("retriable", code.is_retriable())?;
+    Ok(napi::Error::from(error.to_unknown()))
+  };
+
+  create().unwrap_or_else(|_| napi::Error::from_reason(reason))
+}
+
+/// Returns a closure that maps a core error to a napi error, recording its [`ErrorCode`] into `slot`.
+///
+/// Tasks can't access `Env` while computing, so the recorded code is attached in [`reject_with_code`].
+pub(crate) fn record_error_code<E: Into<takumi::Error>>(
+  slot: &mut Option<ErrorCode>,
+) -> impl FnOnce(E) -> napi::Error + '_ {
+  move |err| {
+    let err = err.into();
+    *slot = Some(err.code());
+    map_error(err)
+  }
+}
+
+/// Rejects a task with the recorded [`ErrorCode`] attached to the thrown error.
+pub(crate) fn reject_with_code<T>(
+  env: &Env,
+  err: napi::Error,
+  code: Option<ErrorCode>,
+) -> Result<T> {
+  let code = if err.status == Status::Cancelled {
+    Some(ErrorCode::Aborted)
+  } else {
+    code
+  };
+
+  match code {
+    Some(code) => Err(coded_error(env, code, &err.reason)),
+    None => Err(err),
+  }
+}
+
 pub(crate) fn parse_stylesheet(
   stylesheets: Option<Vec<String>>,
   keyframes: Vec<KeyframesRule>,
@@ -156,6 +335,15 @@ pub(crate) fn parse_stylesheet(
   Ok(stylesheet)
 }
 
+pub(crate) fn parse_matte(matte: Option<String>) -> Result<Option<Color>> {
+  matte
+    .map(|matte| {
+      <Color as FromCss>::from_str(&matte)
+        .map_err(|_| Error::new(Status::InvalidArg, format!("Invalid matte color {matte:?}")))
+    })
+    .transpose()
+}
+
 /// Trait for accounting external memory to V8's garbage collector.
 ///
 /// Similar to the optimization in resvg-js PR #393:
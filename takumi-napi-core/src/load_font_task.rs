@@ -2,12 +2,14 @@ use std::sync::{Arc, RwLock};
 
 use napi::bindgen_prelude::*;
 use rayon::prelude::*;
+use takumi::ErrorCode;
 
-use crate::{FontInput, renderer::RendererState, resolve_font_resource};
+use crate::{FontInput, reject_with_code, renderer::RendererState, resolve_font_resource};
 
 pub struct LoadFontTask {
   pub(crate) state: Arc<RwLock<RendererState>>,
   pub(crate) buffers: Vec<(FontInput, Buffer)>,
+  pub(crate) error_code: Option<ErrorCode>,
 }
 
 impl Task for LoadFontTask {
@@ -26,7 +28,8 @@ impl Task for LoadFontTask {
       .par_iter()
       .with_min_len(2)
       .map(|(font, buffer): &(FontInput, Buffer)| resolve_font_resource(font, buffer.as_ref()))
-      .collect::<Result<Vec<_>>>()?;
+      .collect::<Result<Vec<_>>>()
+      .inspect_err(|_| self.error_code = Some(ErrorCode::FontParseError))?;
 
     let mut state = self
       .state
@@ -50,4 +53,8 @@ impl Task for LoadFontTask {
   fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
     Ok(output as u32)
   }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.error_code)
+  }
 }
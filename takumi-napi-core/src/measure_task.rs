@@ -4,24 +4,34 @@ use std::{collections::HashMap, sync::Arc};
 
 use napi::bindgen_prelude::*;
 use takumi::{
+  ErrorCode,
   layout::style::StyleSheet,
   layout::{DEFAULT_DEVICE_PIXEL_RATIO, Viewport, node::Node},
-  rendering::measure_layout,
-  resources::image::ImageSource as LoadedImageSource,
+  rendering::{measure_layout, outline_text},
 };
 
 use crate::{
-  buffer_from_object, map_error, parse_stylesheet,
-  renderer::{MeasuredNode, RenderOptions, RendererState, deserialize_keyframes},
+  FontInput, PendingImage, parse_scoped_fonts, parse_stylesheet, pending_images, record_error_code,
+  reject_with_code,
+  renderer::{
+    CustomDrawers, GlyphOutline, MeasuredNode, RenderOptions, Renderer, RendererState,
+    deserialize_keyframes,
+  },
+  scoped_context,
 };
 
 pub struct MeasureTask {
   pub node: Option<Node>,
   pub(crate) state: Arc<RwLock<RendererState>>,
+  pub(crate) custom_drawers: CustomDrawers,
   pub viewport: Viewport,
   pub time_ms: u64,
+  pub locale: Option<String>,
   pub stylesheet: StyleSheet,
-  pub fetched_resources: HashMap<Arc<str>, Buffer>,
+  pub(crate) fetched_resources: HashMap<Arc<str>, PendingImage>,
+  /// Fonts loaded for this render only.
+  pub(crate) fonts: Vec<(FontInput, Buffer)>,
+  pub error_code: Option<ErrorCode>,
 }
 
 impl MeasureTask {
@@ -29,70 +39,110 @@ impl MeasureTask {
     env: Env,
     node: Node,
     options: RenderOptions,
-    state: Arc<RwLock<RendererState>>,
+    renderer: &Renderer,
   ) -> Result<Self> {
     Ok(MeasureTask {
       node: Some(node),
-      state,
-      viewport: Viewport::new((options.width, options.height)).with_device_pixel_ratio(
-        options
-          .device_pixel_ratio
-          .map(|ratio| ratio as f32)
-          .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
-      ),
+      state: Arc::clone(&renderer.state),
+      custom_drawers: renderer.custom_drawers()?,
+      viewport: Viewport::new((options.width, options.height))
+        .with_device_pixel_ratio(
+          options
+            .device_pixel_ratio
+            .map(|ratio| ratio as f32)
+            .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
+        )
+        .with_color_scheme(options.color_scheme.map(Into::into).unwrap_or_default()),
       time_ms: options.time_ms.unwrap_or_default().max(0) as u64,
+      locale: options.locale,
       stylesheet: parse_stylesheet(
         options.stylesheets,
         deserialize_keyframes(options.keyframes)?,
       )?,
-      fetched_resources: options
-        .fetched_resources
-        .unwrap_or_default()
-        .into_iter()
-        .map(|image| Ok((Arc::from(image.src), buffer_from_object(env, image.data)?)))
-        .collect::<Result<_>>()?,
+      fetched_resources: pending_images(env, options.fetched_resources)?,
+      fonts: parse_scoped_fonts(env, options.fonts)?,
+      error_code: None,
     })
   }
 }
 
-impl Task for MeasureTask {
-  type Output = takumi::rendering::MeasuredNode;
-  type JsValue = MeasuredNode;
-
-  fn compute(&mut self) -> Result<Self::Output> {
+impl MeasureTask {
+  /// Builds the core render options for this task and runs `f` with them.
+  fn run<T>(
+    &mut self,
+    f: impl FnOnce(takumi::rendering::RenderOptions) -> takumi::Result<T>,
+  ) -> Result<T> {
     let Some(node) = self.node.take() else {
       unreachable!()
     };
 
+    let state = self
+      .state
+      .read()
+      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
+
     let initialized_images = self
       .fetched_resources
       .iter()
       .map(|(k, v)| {
         Ok((
           k.clone(),
-          LoadedImageSource::from_bytes(v).map_err(map_error)?,
+          v.decode(state.global.image_cache())
+            .map_err(record_error_code(&mut self.error_code))?,
         ))
       })
       .collect::<Result<HashMap<_, _>, _>>()?;
-
-    let state = self
-      .state
-      .read()
-      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
+    let scoped = scoped_context(&state.global, &self.fonts, &mut self.error_code)?;
+    let global = scoped.as_deref().unwrap_or(&state.global);
 
     let options = takumi::rendering::RenderOptions::builder()
       .viewport(self.viewport)
       .fetched_resources(initialized_images)
       .stylesheet(take(&mut self.stylesheet))
       .time_ms(self.time_ms)
+      .locale(self.locale.take())
       .node(node)
-      .global(&state.global)
+      .global(global)
+      .custom_drawers(self.custom_drawers.clone())
       .build();
 
-    measure_layout(options).map_err(map_error)
+    f(options).map_err(record_error_code(&mut self.error_code))
+  }
+}
+
+impl Task for MeasureTask {
+  type Output = takumi::rendering::MeasuredNode;
+  type JsValue = MeasuredNode;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    self.run(measure_layout)
   }
 
   fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
     Ok(output.into())
   }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.error_code)
+  }
+}
+
+/// Renders a node tree and collects the outlines of every drawn glyph.
+pub struct OutlineTextTask(pub MeasureTask);
+
+impl Task for OutlineTextTask {
+  type Output = Vec<takumi::rendering::GlyphOutline>;
+  type JsValue = Vec<GlyphOutline>;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    self.0.run(outline_text)
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output.into_iter().map(Into::into).collect())
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.0.error_code)
+  }
 }
@@ -1,11 +1,11 @@
 use std::sync::{Arc, RwLock};
 
 use napi::bindgen_prelude::*;
-use takumi::resources::image::ImageSource as LoadedImageSource;
-use xxhash_rust::xxh3::xxh3_64;
+use takumi::{ErrorCode, resources::image::ImageSource as LoadedImageSource};
+use xxhash_rust::xxh3::xxh3_128;
 
 use crate::{
-  map_error,
+  record_error_code, reject_with_code,
   renderer::{ImageCacheKey, RendererState},
 };
 
@@ -13,6 +13,7 @@ pub struct PutPersistentImageTask {
   pub src: Option<String>,
   pub(crate) state: Arc<RwLock<RendererState>>,
   pub buffer: Buffer,
+  pub error_code: Option<ErrorCode>,
 }
 
 impl Task for PutPersistentImageTask {
@@ -26,7 +27,7 @@ impl Task for PutPersistentImageTask {
 
     let cache_key = ImageCacheKey {
       src: src.as_str().into(),
-      data_hash: xxh3_64(&self.buffer),
+      data_hash: xxh3_128(&self.buffer),
     };
 
     let mut state = self
@@ -37,7 +38,8 @@ impl Task for PutPersistentImageTask {
       return Ok(());
     }
     state.persistent_image_cache.insert(cache_key);
-    let image = LoadedImageSource::from_bytes(&self.buffer).map_err(map_error)?;
+    let image = LoadedImageSource::from_bytes(&self.buffer)
+      .map_err(record_error_code(&mut self.error_code))?;
     state.global.persistent_image_store_mut().insert(src, image);
 
     Ok(())
@@ -46,4 +48,8 @@ impl Task for PutPersistentImageTask {
   fn resolve(&mut self, _env: napi::Env, _output: Self::Output) -> napi::Result<Self::JsValue> {
     Ok(())
   }
+
+  fn reject(&mut self, env: napi::Env, err: napi::Error) -> napi::Result<Self::JsValue> {
+    reject_with_code(&env, err, self.error_code)
+  }
 }
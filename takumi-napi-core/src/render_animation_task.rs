@@ -7,37 +7,42 @@ use std::{
 
 use napi::bindgen_prelude::*;
 use takumi::{
-  layout::{DEFAULT_DEVICE_PIXEL_RATIO, Viewport, node::Node},
+  ErrorCode,
+  layout::{DEFAULT_DEVICE_PIXEL_RATIO, Viewport, node::Node, style::Color},
   rendering::{
     AnimatedGifOptions, AnimatedPngOptions, AnimatedWebpOptions, RenderOptions, SequentialScene,
     encode_animated_gif, encode_animated_png, encode_animated_webp, render_sequence_animation,
   },
-  resources::image::ImageSource as LoadedImageSource,
 };
 
 use crate::{
-  ExternalMemoryAccountable, buffer_from_object, deserialize_with_tracing, map_error,
-  parse_stylesheet,
-  renderer::{AnimationOutputFormat, ImageSource, RenderAnimationOptions, RendererState},
+  ExternalMemoryAccountable, PendingImage, deserialize_node, parse_matte, parse_stylesheet,
+  pending_images, record_error_code, reject_with_code,
+  renderer::{
+    AnimationOutputFormat, CustomDrawers, RenderAnimationOptions, Renderer, RendererState,
+  },
 };
 
 pub struct RenderAnimationTask {
   pub scenes: Option<Vec<(Node, u32)>>,
   pub(crate) state: Arc<RwLock<RendererState>>,
+  pub(crate) custom_drawers: CustomDrawers,
   pub viewport: Viewport,
   pub format: AnimationOutputFormat,
   pub quality: Option<u8>,
+  pub matte: Option<Color>,
   pub draw_debug_border: bool,
   pub stylesheets: Option<Vec<String>>,
-  pub fetched_resources: HashMap<Arc<str>, Buffer>,
+  pub(crate) fetched_resources: HashMap<Arc<str>, PendingImage>,
   pub fps: u32,
+  pub error_code: Option<ErrorCode>,
 }
 
 impl RenderAnimationTask {
   pub(crate) fn from_options(
     env: Env,
     options: RenderAnimationOptions,
-    state: Arc<RwLock<RendererState>>,
+    renderer: &Renderer,
   ) -> Result<Self> {
     let RenderAnimationOptions {
       scenes,
@@ -46,14 +51,19 @@ impl RenderAnimationTask {
       height,
       format,
       quality,
+      matte,
       fps,
       fetched_resources,
       stylesheets,
       device_pixel_ratio,
+      color_scheme,
     } = options;
     let scenes = scenes
       .into_iter()
-      .map(|scene| Ok((deserialize_with_tracing(scene.node)?, scene.duration_ms)))
+      .map(|scene| {
+        let node = deserialize_node(&env, scene.node)?;
+        Ok((node, scene.duration_ms))
+      })
       .collect::<Result<Vec<(Node, u32)>>>()?;
 
     if scenes.is_empty() {
@@ -72,24 +82,23 @@ impl RenderAnimationTask {
 
     Ok(Self {
       scenes: Some(scenes),
-      state,
-      viewport: Viewport::new((width, height)).with_device_pixel_ratio(
-        device_pixel_ratio
-          .map(|ratio| ratio as f32)
-          .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
-      ),
+      state: Arc::clone(&renderer.state),
+      custom_drawers: renderer.custom_drawers()?,
+      viewport: Viewport::new((width, height))
+        .with_device_pixel_ratio(
+          device_pixel_ratio
+            .map(|ratio| ratio as f32)
+            .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
+        )
+        .with_color_scheme(color_scheme.map(Into::into).unwrap_or_default()),
       format: format.unwrap_or(AnimationOutputFormat::WebP),
       quality,
+      matte: parse_matte(matte)?,
       draw_debug_border: draw_debug_border.unwrap_or_default(),
       stylesheets,
-      fetched_resources: fetched_resources
-        .unwrap_or_default()
-        .into_iter()
-        .map(|image: ImageSource<'_>| {
-          Ok((Arc::from(image.src), buffer_from_object(env, image.data)?))
-        })
-        .collect::<Result<_>>()?,
+      fetched_resources: pending_images(env, fetched_resources)?,
       fps,
+      error_code: None,
     })
   }
 }
@@ -102,20 +111,22 @@ impl Task for RenderAnimationTask {
     let Some(scenes) = self.scenes.take() else {
       unreachable!()
     };
+    let state = self
+      .state
+      .read()
+      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
     let initialized_images = self
       .fetched_resources
       .iter()
       .map(|(key, value)| {
         Ok((
           key.clone(),
-          LoadedImageSource::from_bytes(value).map_err(map_error)?,
+          value
+            .decode(state.global.image_cache())
+            .map_err(record_error_code(&mut self.error_code))?,
         ))
       })
       .collect::<Result<HashMap<_, _>, _>>()?;
-    let state = self
-      .state
-      .read()
-      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
     let stylesheet = parse_stylesheet(take(&mut self.stylesheets), Vec::new())?;
     let scene_options = scenes
       .into_iter()
@@ -129,13 +140,15 @@ impl Task for RenderAnimationTask {
               .stylesheet(stylesheet.clone())
               .node(node)
               .global(&state.global)
+              .custom_drawers(self.custom_drawers.clone())
               .draw_debug_border(self.draw_debug_border)
               .build(),
           )
           .build()
       })
       .collect::<Vec<_>>();
-    let frames = render_sequence_animation(&scene_options, self.fps).map_err(map_error)?;
+    let frames = render_sequence_animation(&scene_options, self.fps)
+      .map_err(record_error_code(&mut self.error_code))?;
 
     if let Some(quality) = self.quality
       && quality > 100
@@ -155,19 +168,19 @@ impl Task for RenderAnimationTask {
         }
 
         encode_animated_webp(Cow::Owned(frames), &mut buffer, options)
-          .map_err(|e| Error::from_reason(e.to_string()))?;
+          .map_err(record_error_code(&mut self.error_code))?;
       }
       AnimationOutputFormat::Apng => {
         encode_animated_png(&frames, &mut buffer, AnimatedPngOptions::default())
-          .map_err(|e| Error::from_reason(e.to_string()))?;
+          .map_err(record_error_code(&mut self.error_code))?;
       }
       AnimationOutputFormat::Gif => {
         encode_animated_gif(
           Cow::Owned(frames),
           &mut buffer,
-          AnimatedGifOptions::default(),
+          AnimatedGifOptions::builder().matte(self.matte).build(),
         )
-        .map_err(|e| Error::from_reason(e.to_string()))?;
+        .map_err(record_error_code(&mut self.error_code))?;
       }
     }
 
@@ -178,4 +191,8 @@ impl Task for RenderAnimationTask {
     output.account_external_memory(&mut env)?;
     Ok(output.into())
   }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.error_code)
+  }
 }
@@ -0,0 +1,100 @@
+use std::borrow::Cow;
+
+use napi::bindgen_prelude::*;
+use takumi::{
+  image::RgbaImage,
+  rendering::{
+    LayoutRegion as CoreLayoutRegion, RenderChanges, diff_frames, render, write_image_with_options,
+  },
+};
+
+use crate::{
+  ExternalMemoryAccountable, record_error_code, reject_with_code,
+  render_task::RenderTask,
+  renderer::{OutputFormat, RenderedChanges, RenderedPatch},
+};
+
+/// Renders a node tree and compares it against the raw RGBA pixels of a previous frame.
+pub struct RenderChangesTask {
+  pub task: RenderTask,
+  pub previous: Buffer,
+}
+
+/// The changed area of a render, encoded in the requested format.
+pub struct EncodedPatch {
+  region: CoreLayoutRegion,
+  data: Vec<u8>,
+}
+
+impl Task for RenderChangesTask {
+  type Output = (u32, u32, Vec<CoreLayoutRegion>, Option<EncodedPatch>);
+  type JsValue = RenderedChanges;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let image = self.task.run(render)?;
+    let (width, height) = image.dimensions();
+
+    // A buffer that doesn't match the new size counts as a different frame.
+    let previous = (self.previous.len() == image.as_raw().len())
+      .then(|| RgbaImage::from_raw(width, height, self.previous.to_vec()))
+      .flatten()
+      .unwrap_or_default();
+    let changes = RenderChanges {
+      regions: diff_frames(&previous, &image),
+      image,
+    };
+
+    let patch = match changes.patch() {
+      Some((region, patch)) => {
+        let data = if self.task.format == OutputFormat::Raw {
+          patch.into_raw()
+        } else {
+          let mut buffer = Vec::new();
+          write_image_with_options(
+            Cow::Owned(patch),
+            &mut buffer,
+            self.task.format.into(),
+            self.task.encode_options,
+          )
+          .map_err(record_error_code(&mut self.task.error_code))?;
+          buffer
+        };
+
+        Some(EncodedPatch { region, data })
+      }
+      None => None,
+    };
+
+    Ok((width, height, changes.regions, patch))
+  }
+
+  fn resolve(&mut self, mut env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    let (width, height, regions, patch) = output;
+
+    let patch = match patch {
+      Some(patch) => {
+        patch.data.account_external_memory(&mut env)?;
+
+        Some(RenderedPatch {
+          x: patch.region.x as u32,
+          y: patch.region.y as u32,
+          width: patch.region.width as u32,
+          height: patch.region.height as u32,
+          data: patch.data.into(),
+        })
+      }
+      None => None,
+    };
+
+    Ok(RenderedChanges {
+      width,
+      height,
+      regions: regions.into_iter().map(Into::into).collect(),
+      patch,
+    })
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.task.error_code)
+  }
+}
@@ -0,0 +1,24 @@
+use napi::bindgen_prelude::*;
+use takumi::rendering::render_hash;
+
+use crate::{reject_with_code, render_task::RenderTask};
+
+/// Hashes a node tree and its render inputs without rasterizing it.
+pub struct RenderHashTask(pub RenderTask);
+
+impl Task for RenderHashTask {
+  type Output = u64;
+  type JsValue = String;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    self.0.run(render_hash)
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(format!("{output:016x}"))
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.0.error_code)
+  }
+}
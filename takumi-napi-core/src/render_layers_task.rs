@@ -0,0 +1,92 @@
+use std::borrow::Cow;
+
+use napi::bindgen_prelude::*;
+use takumi::rendering::{render_layers, write_image_with_options};
+
+use crate::{
+  ExternalMemoryAccountable, record_error_code, reject_with_code,
+  render_task::RenderTask,
+  renderer::{OutputFormat, RenderedLayer, RenderedLayers},
+};
+
+/// Renders a node tree into separately encoded layers.
+pub struct RenderLayersTask(pub RenderTask);
+
+/// A layer with its pixels encoded in the requested format.
+pub struct EncodedLayer {
+  name: String,
+  path: Vec<u32>,
+  x: u32,
+  y: u32,
+  width: u32,
+  height: u32,
+  data: Vec<u8>,
+}
+
+impl Task for RenderLayersTask {
+  type Output = (u32, u32, Vec<EncodedLayer>);
+  type JsValue = RenderedLayers;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let layered = self.0.run(render_layers)?;
+    let mut layers = Vec::with_capacity(layered.layers.len());
+
+    for layer in layered.layers {
+      let (width, height) = layer.image.dimensions();
+      let data = if self.0.format == OutputFormat::Raw {
+        layer.image.into_raw()
+      } else {
+        let mut buffer = Vec::new();
+        write_image_with_options(
+          Cow::Owned(layer.image),
+          &mut buffer,
+          self.0.format.into(),
+          self.0.encode_options,
+        )
+        .map_err(record_error_code(&mut self.0.error_code))?;
+        buffer
+      };
+
+      layers.push(EncodedLayer {
+        name: layer.name,
+        path: layer.path.into_iter().map(|index| index as u32).collect(),
+        x: layer.x,
+        y: layer.y,
+        width,
+        height,
+        data,
+      });
+    }
+
+    Ok((layered.width, layered.height, layers))
+  }
+
+  fn resolve(&mut self, mut env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    let (width, height, layers) = output;
+
+    Ok(RenderedLayers {
+      width,
+      height,
+      layers: layers
+        .into_iter()
+        .map(|layer| {
+          layer.data.account_external_memory(&mut env)?;
+
+          Ok(RenderedLayer {
+            name: layer.name,
+            path: layer.path,
+            x: layer.x,
+            y: layer.y,
+            width: layer.width,
+            height: layer.height,
+            data: layer.data.into(),
+          })
+        })
+        .collect::<Result<_>>()?,
+    })
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.0.error_code)
+  }
+}
@@ -0,0 +1,54 @@
+use std::borrow::Cow;
+
+use napi::bindgen_prelude::*;
+use takumi::rendering::{render_with_profile, write_image_with_options};
+
+use crate::{
+  ExternalMemoryAccountable, record_error_code, reject_with_code,
+  render_task::RenderTask,
+  renderer::{OutputFormat, ProfiledRender},
+};
+
+/// Renders a node tree while timing every node.
+pub struct RenderProfileTask(pub RenderTask);
+
+impl Task for RenderProfileTask {
+  type Output = (Vec<u8>, takumi::rendering::RenderProfile);
+  type JsValue = ProfiledRender;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let (image, profile) = self.0.run(render_with_profile)?;
+    let Some(profile) = profile else {
+      unreachable!()
+    };
+
+    if self.0.format == OutputFormat::Raw {
+      return Ok((image.into_raw(), profile));
+    }
+
+    let mut buffer = Vec::new();
+    write_image_with_options(
+      Cow::Owned(image),
+      &mut buffer,
+      self.0.format.into(),
+      self.0.encode_options,
+    )
+    .map_err(record_error_code(&mut self.0.error_code))?;
+
+    Ok((buffer, profile))
+  }
+
+  fn resolve(&mut self, mut env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    let (image, profile) = output;
+    image.account_external_memory(&mut env)?;
+
+    Ok(ProfiledRender {
+      image: image.into(),
+      profile: profile.into(),
+    })
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.0.error_code)
+  }
+}
@@ -0,0 +1,35 @@
+use napi::bindgen_prelude::*;
+
+use crate::{
+  ExternalMemoryAccountable, reject_with_code, render_task::RenderTask, renderer::RawImage,
+};
+
+/// Renders a node tree into raw RGBA pixels along with the final dimensions.
+pub struct RenderRawTask(pub RenderTask);
+
+impl Task for RenderRawTask {
+  type Output = (u32, u32, Vec<u8>);
+  type JsValue = RawImage;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let image = self.0.render_image()?;
+    let (width, height) = image.dimensions();
+
+    Ok((width, height, image.into_raw()))
+  }
+
+  fn resolve(&mut self, mut env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    let (width, height, data) = output;
+    data.account_external_memory(&mut env)?;
+
+    Ok(RawImage {
+      width,
+      height,
+      data: data.into(),
+    })
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.0.error_code)
+  }
+}
@@ -0,0 +1,138 @@
+use std::{
+  io::{self, Write},
+  mem::{replace, take},
+  sync::mpsc,
+};
+
+use napi::{
+  bindgen_prelude::*,
+  threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode},
+};
+use takumi::rendering::write_image_streaming_with_options;
+
+use crate::{record_error_code, reject_with_code, render_task::RenderTask, renderer::OutputFormat};
+
+pub(crate) type ChunkCallback =
+  ThreadsafeFunction<Buffer, (), Buffer, Status, false, false, MAX_QUEUED_CHUNKS>;
+
+/// Encoded bytes are handed to JavaScript in chunks of this size.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Chunks waiting for JavaScript at most, encoding blocks once the queue is full so a slow
+/// consumer doesn't let the whole output pile up in memory.
+pub(crate) const MAX_QUEUED_CHUNKS: usize = 16;
+
+pub struct RenderStreamTask {
+  pub(crate) task: RenderTask,
+  pub(crate) on_chunk: ChunkCallback,
+}
+
+impl Task for RenderStreamTask {
+  type Output = ();
+  type JsValue = ();
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let image = self.task.render_image()?;
+    let mut writer = ChunkWriter::new(&self.on_chunk);
+
+    if self.task.format == OutputFormat::Raw {
+      writer.write_all(image.as_raw())?;
+    } else {
+      write_image_streaming_with_options(
+        &image,
+        &mut writer,
+        self.task.format.into(),
+        self.task.encode_options,
+      )
+      .map_err(record_error_code(&mut self.task.error_code))?;
+    }
+
+    writer.finish()?;
+
+    Ok(())
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output)
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.task.error_code)
+  }
+}
+
+/// Buffers encoded bytes and forwards them to the JavaScript chunk callback.
+struct ChunkWriter<'a> {
+  callback: &'a ChunkCallback,
+  buffer: Vec<u8>,
+}
+
+impl<'a> ChunkWriter<'a> {
+  fn new(callback: &'a ChunkCallback) -> Self {
+    Self {
+      callback,
+      buffer: Vec::with_capacity(CHUNK_SIZE),
+    }
+  }
+
+  fn emit(&mut self) -> io::Result<()> {
+    let chunk = replace(&mut self.buffer, Vec::with_capacity(CHUNK_SIZE));
+    let status = self
+      .callback
+      .call(chunk.into(), ThreadsafeFunctionCallMode::Blocking);
+
+    if status != Status::Ok {
+      return Err(io::Error::other(format!(
+        "Failed to call chunk callback: {status}"
+      )));
+    }
+
+    Ok(())
+  }
+
+  /// Sends the remaining bytes and blocks until JavaScript has received every chunk,
+  /// so the promise never resolves ahead of the data.
+  fn finish(mut self) -> Result<()> {
+    let chunk = take(&mut self.buffer);
+    let (sender, receiver) = mpsc::channel();
+    let status = self.callback.call_with_return_value(
+      chunk.into(),
+      ThreadsafeFunctionCallMode::Blocking,
+      move |result: Result<()>, _| {
+        let _ = sender.send(result.map_err(|e| e.reason.clone()));
+        Ok(())
+      },
+    );
+
+    if status != Status::Ok {
+      return Err(Error::from_reason(format!(
+        "Failed to call chunk callback: {status}"
+      )));
+    }
+
+    receiver
+      .recv()
+      .map_err(|_| Error::from_reason("Chunk callback was dropped"))?
+      .map_err(Error::from_reason)
+  }
+}
+
+impl Write for ChunkWriter<'_> {
+  fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+    // Emit before appending so the tail is never empty when `finish` sends it.
+    if self.buffer.len() >= CHUNK_SIZE {
+      self.emit()?;
+    }
+
+    // Take at most what fits in the chunk, `write_all` hands over the rest in later calls.
+    let len = bytes.len().min(CHUNK_SIZE - self.buffer.len());
+    self.buffer.extend_from_slice(&bytes[..len]);
+
+    Ok(len)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    // The tail is delivered by `finish`, which also waits for JavaScript to catch up.
+    Ok(())
+  }
+}
@@ -5,28 +5,46 @@ use std::{collections::HashMap, sync::Arc};
 use napi::bindgen_prelude::*;
 use takumi::layout::node::Node;
 use takumi::{
+  ErrorCode,
+  image::RgbaImage,
   layout::style::StyleSheet,
   layout::{DEFAULT_DEVICE_PIXEL_RATIO, Viewport},
-  rendering::{DitheringAlgorithm, render, write_image},
-  resources::image::ImageSource as LoadedImageSource,
+  rendering::{DitheringAlgorithm, EncodeOptions, render, write_image_with_options},
+  resources::font::MissingFontPolicy,
 };
 
 use crate::{
-  ExternalMemoryAccountable, buffer_from_object, map_error, parse_stylesheet,
-  renderer::{OutputFormat, RenderOptions, RendererState, deserialize_keyframes},
+  ExternalMemoryAccountable, FontInput, PendingImage, parse_scoped_fonts, parse_stylesheet,
+  pending_images, record_error_code, reject_with_code,
+  renderer::{
+    CustomDrawers, OutputFormat, RenderOptions, Renderer, RendererState, deserialize_keyframes,
+  },
+  scoped_context,
 };
 
 pub struct RenderTask {
   pub draw_debug_border: bool,
   pub node: Option<Node>,
   pub(crate) state: Arc<RwLock<RendererState>>,
+  pub(crate) custom_drawers: CustomDrawers,
   pub viewport: Viewport,
+  pub max_width: Option<u32>,
+  pub max_height: Option<u32>,
   pub format: OutputFormat,
-  pub quality: Option<u8>,
+  pub encode_options: EncodeOptions,
   pub dithering: DitheringAlgorithm,
   pub time_ms: u64,
+  pub now_ms: Option<u64>,
+  pub seed: u64,
+  pub locale: Option<String>,
+  pub missing_font_policy: Option<MissingFontPolicy>,
+  /// Whether to record per-node timings, see [`takumi::rendering::render_with_profile`].
+  pub profile: bool,
   pub stylesheet: StyleSheet,
-  pub fetched_resources: HashMap<Arc<str>, Buffer>,
+  pub(crate) fetched_resources: HashMap<Arc<str>, PendingImage>,
+  /// Fonts loaded for this render only.
+  pub(crate) fonts: Vec<(FontInput, Buffer)>,
+  pub error_code: Option<ErrorCode>,
 }
 
 impl RenderTask {
@@ -34,74 +52,104 @@ impl RenderTask {
     env: Env,
     node: Node,
     options: RenderOptions,
-    state: Arc<RwLock<RendererState>>,
+    renderer: &Renderer,
   ) -> Result<Self> {
     Ok(RenderTask {
       node: Some(node),
-      state,
-      viewport: Viewport::new((options.width, options.height)).with_device_pixel_ratio(
-        options
-          .device_pixel_ratio
-          .map(|ratio| ratio as f32)
-          .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
-      ),
+      state: Arc::clone(&renderer.state),
+      custom_drawers: renderer.custom_drawers()?,
+      viewport: Viewport::new((options.width, options.height))
+        .with_device_pixel_ratio(
+          options
+            .device_pixel_ratio
+            .map(|ratio| ratio as f32)
+            .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
+        )
+        .with_color_scheme(options.color_scheme.map(Into::into).unwrap_or_default()),
+      max_width: options.max_width,
+      max_height: options.max_height,
       format: options.format.unwrap_or(OutputFormat::Png),
-      quality: options.quality,
+      encode_options: options.encode_options()?,
       dithering: options.dithering.map(Into::into).unwrap_or_default(),
       time_ms: options.time_ms.unwrap_or_default().max(0) as u64,
+      now_ms: options.now.map(|now| now.max(0) as u64),
+      seed: options.seed.unwrap_or_default().into(),
+      locale: options.locale,
+      missing_font_policy: options.missing_font_policy.map(Into::into),
+      profile: false,
       draw_debug_border: options.draw_debug_border.unwrap_or_default(),
       stylesheet: parse_stylesheet(
         options.stylesheets,
         deserialize_keyframes(options.keyframes)?,
       )?,
-      fetched_resources: options
-        .fetched_resources
-        .unwrap_or_default()
-        .into_iter()
-        .map(|image| Ok((Arc::from(image.src), buffer_from_object(env, image.data)?)))
-        .collect::<Result<_>>()?,
+      fetched_resources: pending_images(env, options.fetched_resources)?,
+      fonts: parse_scoped_fonts(env, options.fonts)?,
+      error_code: None,
     })
   }
 }
 
-impl Task for RenderTask {
-  type Output = Vec<u8>;
-  type JsValue = Buffer;
+impl RenderTask {
+  pub(crate) fn render_image(&mut self) -> Result<RgbaImage> {
+    self.run(render)
+  }
 
-  fn compute(&mut self) -> Result<Self::Output> {
+  /// Builds the core render options for this task and runs `f` with them.
+  pub(crate) fn run<T>(
+    &mut self,
+    f: impl FnOnce(takumi::rendering::RenderOptions) -> takumi::Result<T>,
+  ) -> Result<T> {
     let Some(node) = self.node.take() else {
       unreachable!()
     };
 
+    let state = self
+      .state
+      .read()
+      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
+
     let initialized_images = self
       .fetched_resources
       .iter()
       .map(|(k, v)| {
         Ok((
           k.clone(),
-          LoadedImageSource::from_bytes(v).map_err(map_error)?,
+          v.decode(state.global.image_cache())
+            .map_err(record_error_code(&mut self.error_code))?,
         ))
       })
       .collect::<Result<HashMap<_, _>, _>>()?;
+    let scoped = scoped_context(&state.global, &self.fonts, &mut self.error_code)?;
+    let global = scoped.as_deref().unwrap_or(&state.global);
 
-    let state = self
-      .state
-      .read()
-      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
+    f(takumi::rendering::RenderOptions::builder()
+      .viewport(self.viewport)
+      .fetched_resources(initialized_images)
+      .stylesheet(take(&mut self.stylesheet))
+      .time_ms(self.time_ms)
+      .now_ms(self.now_ms)
+      .seed(self.seed)
+      .locale(self.locale.take())
+      .missing_font_policy(self.missing_font_policy)
+      .dithering(self.dithering)
+      .node(node)
+      .global(global)
+      .custom_drawers(self.custom_drawers.clone())
+      .draw_debug_border(self.draw_debug_border)
+      .max_width(self.max_width)
+      .max_height(self.max_height)
+      .profile(self.profile)
+      .build())
+    .map_err(record_error_code(&mut self.error_code))
+  }
+}
 
-    let image = render(
-      takumi::rendering::RenderOptions::builder()
-        .viewport(self.viewport)
-        .fetched_resources(initialized_images)
-        .stylesheet(take(&mut self.stylesheet))
-        .time_ms(self.time_ms)
-        .dithering(self.dithering)
-        .node(node)
-        .global(&state.global)
-        .draw_debug_border(self.draw_debug_border)
-        .build(),
-    )
-    .map_err(map_error)?;
+impl Task for RenderTask {
+  type Output = Vec<u8>;
+  type JsValue = Buffer;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let image = self.render_image()?;
 
     if self.format == OutputFormat::Raw {
       return Ok(image.into_raw());
@@ -109,13 +157,13 @@ impl Task for RenderTask {
 
     let mut buffer = Vec::new();
 
-    write_image(
+    write_image_with_options(
       Cow::Owned(image),
       &mut buffer,
       self.format.into(),
-      self.quality,
+      self.encode_options,
     )
-    .map_err(map_error)?;
+    .map_err(record_error_code(&mut self.error_code))?;
 
     Ok(buffer)
   }
@@ -126,4 +174,8 @@ impl Task for RenderTask {
     output.account_external_memory(&mut env)?;
     Ok(output.into())
   }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.error_code)
+  }
 }
@@ -1,5 +1,5 @@
 use std::{
-  collections::HashSet,
+  collections::{HashMap, HashSet},
   sync::{Arc, RwLock},
 };
 
@@ -7,19 +7,49 @@ use napi::bindgen_prelude::*;
 use napi_derive::napi;
 use rayon::prelude::*;
 use takumi::{
-  GlobalContext,
-  layout::{node::Node, style::KeyframesRule as CoreKeyframesRule},
+  ErrorCode, GlobalContext,
+  layout::{
+    ColorScheme as CoreColorScheme,
+    node::{CustomDraw, Node},
+    style::{KeyframesRule as CoreKeyframesRule, Style},
+  },
   parley::{GenericFamily, fontique::FontInfoOverride},
-  rendering::{DitheringAlgorithm as CoreDitheringAlgorithm, ImageOutputFormat},
-  resources::{font::FontResource, image::ImageSource as LoadedImageSource},
+  rendering::{
+    AvifBitDepth, AvifEncodeOptions as CoreAvifEncodeOptions,
+    DitheringAlgorithm as CoreDitheringAlgorithm, EncodeOptions, EncodePreset as CoreEncodePreset,
+    ImageOutputFormat, PngCompression as CorePngCompression,
+    PngEncodeOptions as CorePngEncodeOptions, PngFilter as CorePngFilter,
+    TextRenderingOptions as CoreTextRenderingOptions, WebpEncodeOptions as CoreWebpEncodeOptions,
+  },
+  resources::{
+    font::{FontResource, MissingFontPolicy as CoreMissingFontPolicy},
+    image::ImageResourceError,
+    palette::DEFAULT_PALETTE_SIZE,
+    placeholder::{DEFAULT_BLURHASH_COMPONENTS, PlaceholderKind as CorePlaceholderKind},
+  },
 };
 use xxhash_rust::xxh3::Xxh3DefaultBuilder;
 
 use crate::{
-  De, FontInput, buffer_from_object, buffer_slice_from_object, deserialize_with_tracing,
-  encode_frames_task::EncodeFramesTask, load_font_task::LoadFontTask, map_error,
-  measure_task::MeasureTask, parse_font_input, put_persistent_image_task::PutPersistentImageTask,
-  render_animation_task::RenderAnimationTask, render_task::RenderTask, resolve_font_resource,
+  De, FontInput, buffer_from_object, buffer_slice_from_object, coded_error,
+  custom_draw::{CustomDrawCallback, JsCustomDraw},
+  decode_image_source, deserialize_node, deserialize_with_tracing,
+  encode_frames_task::EncodeFramesTask,
+  load_font_task::LoadFontTask,
+  measure_task::{MeasureTask, OutlineTextTask},
+  parse_font_input,
+  put_persistent_image_task::PutPersistentImageTask,
+  render_animation_task::RenderAnimationTask,
+  render_changes_task::RenderChangesTask,
+  render_hash_task::RenderHashTask,
+  render_layers_task::RenderLayersTask,
+  render_profile_task::RenderProfileTask,
+  render_raw_task::RenderRawTask,
+  render_stream_task::{ChunkCallback, MAX_QUEUED_CHUNKS, RenderStreamTask},
+  render_task::RenderTask,
+  replace_font_task::ReplaceFontTask,
+  resolve_font_resource,
+  warmup_task::WarmupTask,
 };
 
 /// Represents a single run of text in a measured node.
@@ -49,6 +79,36 @@ impl From<takumi::rendering::MeasuredTextRun> for MeasuredTextRun {
   }
 }
 
+/// Represents a line of an inline layout in a measured node.
+#[napi(object)]
+pub struct MeasuredTextLine {
+  /// The inline x-coordinate of the line content.
+  pub x: f64,
+  /// The inline y-coordinate of the top of the line box.
+  pub y: f64,
+  /// The width of the line content, excluding trailing whitespace.
+  pub width: f64,
+  /// The height of the line box.
+  pub height: f64,
+  /// The inline y-coordinate of the baseline.
+  pub baseline: f64,
+  /// The largest cap height among the text runs on the line, `0` when it has none.
+  pub cap_height: f64,
+}
+
+impl From<takumi::rendering::MeasuredTextLine> for MeasuredTextLine {
+  fn from(line: takumi::rendering::MeasuredTextLine) -> Self {
+    Self {
+      x: line.x as f64,
+      y: line.y as f64,
+      width: line.width as f64,
+      height: line.height as f64,
+      baseline: line.baseline as f64,
+      cap_height: line.cap_height as f64,
+    }
+  }
+}
+
 /// Represents a node that has been measured, including its layout information.
 #[napi(object)]
 pub struct MeasuredNode {
@@ -63,6 +123,8 @@ pub struct MeasuredNode {
   pub children: Vec<MeasuredNode>,
   /// The text runs within the node.
   pub runs: Vec<MeasuredTextRun>,
+  /// The lines of the node's inline layout.
+  pub lines: Vec<MeasuredTextLine>,
 }
 
 impl From<takumi::rendering::MeasuredNode> for MeasuredNode {
@@ -73,6 +135,286 @@ impl From<takumi::rendering::MeasuredNode> for MeasuredNode {
       transform: node.transform.iter().map(|&x| x as f64).collect(),
       children: node.children.into_iter().map(Into::into).collect(),
       runs: node.runs.into_iter().map(Into::into).collect(),
+      lines: node.lines.into_iter().map(Into::into).collect(),
+    }
+  }
+}
+
+impl From<MeasuredTextRun> for takumi::rendering::MeasuredTextRun {
+  fn from(run: MeasuredTextRun) -> Self {
+    Self {
+      text: run.text,
+      x: run.x as f32,
+      y: run.y as f32,
+      width: run.width as f32,
+      height: run.height as f32,
+    }
+  }
+}
+
+impl From<MeasuredTextLine> for takumi::rendering::MeasuredTextLine {
+  fn from(line: MeasuredTextLine) -> Self {
+    Self {
+      x: line.x as f32,
+      y: line.y as f32,
+      width: line.width as f32,
+      height: line.height as f32,
+      baseline: line.baseline as f32,
+      cap_height: line.cap_height as f32,
+    }
+  }
+}
+
+impl From<MeasuredNode> for takumi::rendering::MeasuredNode {
+  fn from(node: MeasuredNode) -> Self {
+    let mut transform = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+    for (target, value) in transform.iter_mut().zip(node.transform) {
+      *target = value as f32;
+    }
+
+    Self {
+      width: node.width as f32,
+      height: node.height as f32,
+      transform,
+      children: node.children.into_iter().map(Into::into).collect(),
+      runs: node.runs.into_iter().map(Into::into).collect(),
+      lines: node.lines.into_iter().map(Into::into).collect(),
+    }
+  }
+}
+
+/// The option array an asset that failed to load came from.
+#[napi(string_enum = "lowercase")]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AssetKind {
+  /// An entry of `fonts`.
+  Font,
+  /// An entry of `persistentImages`.
+  Image,
+}
+
+/// An asset passed to the [`Renderer`] constructor that failed to load.
+#[napi(object)]
+#[derive(Clone)]
+pub struct AssetLoadFailure {
+  /// Whether the asset came from `fonts` or `persistentImages`.
+  pub kind: AssetKind,
+  /// The index of the asset within its option array.
+  pub index: u32,
+  /// The font family name or image `src`, when known.
+  pub name: Option<String>,
+  /// The error code, `FONT_PARSE_ERROR` for fonts and `IMAGE_DECODE_ERROR` for images.
+  pub code: String,
+  /// Why the asset failed to load.
+  pub reason: String,
+}
+
+impl AssetLoadFailure {
+  fn font(index: usize, name: Option<String>, reason: String) -> Self {
+    Self {
+      kind: AssetKind::Font,
+      index: index as u32,
+      name,
+      code: ErrorCode::FontParseError.as_str().to_owned(),
+      reason,
+    }
+  }
+
+  fn image(index: usize, src: String, reason: String) -> Self {
+    Self {
+      kind: AssetKind::Image,
+      index: index as u32,
+      name: Some(src),
+      code: ErrorCode::ImageDecodeError.as_str().to_owned(),
+      reason,
+    }
+  }
+}
+
+/// A separately painted layer of a render.
+#[napi(object)]
+pub struct RenderedLayer {
+  /// The node's `id`, falling back to its tag name and then to `layer-<index>`.
+  pub name: String,
+  /// Child indices leading from the root to the node the layer was painted from.
+  pub path: Vec<u32>,
+  /// Horizontal offset of the layer within the full render.
+  pub x: u32,
+  /// Vertical offset of the layer within the full render.
+  pub y: u32,
+  /// The width of the layer image.
+  pub width: u32,
+  /// The height of the layer image.
+  pub height: u32,
+  /// The encoded layer image.
+  pub data: Buffer,
+}
+
+/// A render split into layers, ordered from bottom to top.
+#[napi(object)]
+pub struct RenderedLayers {
+  /// The width of the full render.
+  pub width: u32,
+  /// The height of the full render.
+  pub height: u32,
+  /// The layers in paint order.
+  pub layers: Vec<RenderedLayer>,
+}
+
+/// Time spent on a single node of a profiled render, in milliseconds.
+#[napi(object)]
+pub struct NodeProfile {
+  /// The node's tag name, if any.
+  pub tag_name: Option<String>,
+  /// The node's `id`, if any.
+  pub id: Option<String>,
+  /// Child indices leading from the root to the node.
+  pub path: Vec<u32>,
+  /// Time spent measuring the node's own content, such as shaping its text.
+  pub measure_ms: f64,
+  /// Time spent laying out the node, including its descendants.
+  pub layout_ms: f64,
+  /// Time spent painting the node itself, excluding its descendants.
+  pub draw_ms: f64,
+  /// The profiles of the node's children. Inline content is accounted to its inline root.
+  pub children: Vec<NodeProfile>,
+}
+
+impl From<takumi::rendering::NodeProfile> for NodeProfile {
+  fn from(profile: takumi::rendering::NodeProfile) -> Self {
+    Self {
+      tag_name: profile.tag_name,
+      id: profile.id,
+      path: profile.path.into_iter().map(|index| index as u32).collect(),
+      measure_ms: profile.measure_ms,
+      layout_ms: profile.layout_ms,
+      draw_ms: profile.draw_ms,
+      children: profile.children.into_iter().map(Into::into).collect(),
+    }
+  }
+}
+
+/// Where the time of a profiled render went, in milliseconds.
+#[napi(object)]
+pub struct RenderProfile {
+  /// Time spent resolving styles and building the render tree.
+  pub prepare_ms: f64,
+  /// Time spent laying out the tree.
+  pub layout_ms: f64,
+  /// Time spent painting the tree.
+  pub draw_ms: f64,
+  /// Time spent on the whole render, excluding encoding.
+  pub total_ms: f64,
+  /// The profile of the root node and its descendants.
+  pub root: NodeProfile,
+}
+
+impl From<takumi::rendering::RenderProfile> for RenderProfile {
+  fn from(profile: takumi::rendering::RenderProfile) -> Self {
+    Self {
+      prepare_ms: profile.prepare_ms,
+      layout_ms: profile.layout_ms,
+      draw_ms: profile.draw_ms,
+      total_ms: profile.total_ms,
+      root: profile.root.into(),
+    }
+  }
+}
+
+/// An encoded render along with where its time went.
+#[napi(object)]
+pub struct ProfiledRender {
+  /// The encoded image.
+  pub image: Buffer,
+  /// Timings of the render and of every node.
+  pub profile: RenderProfile,
+}
+
+/// Raw RGBA pixels of a render with its final dimensions.
+#[napi(object)]
+pub struct RawImage {
+  /// The width of the render, measured from the content when not set.
+  pub width: u32,
+  /// The height of the render, measured from the content when not set.
+  pub height: u32,
+  /// The RGBA pixels, row by row.
+  pub data: Buffer,
+}
+
+/// The changed area of a render, cropped to the bounds of every change.
+#[napi(object)]
+pub struct RenderedPatch {
+  /// Horizontal offset of the patch within the full render.
+  pub x: u32,
+  /// Vertical offset of the patch within the full render.
+  pub y: u32,
+  /// The width of the patch image.
+  pub width: u32,
+  /// The height of the patch image.
+  pub height: u32,
+  /// The encoded patch image.
+  pub data: Buffer,
+}
+
+/// A render compared against a previous frame.
+#[napi(object)]
+pub struct RenderedChanges {
+  /// The width of the full render.
+  pub width: u32,
+  /// The height of the full render.
+  pub height: u32,
+  /// Areas whose pixels differ from the previous frame, empty when nothing changed.
+  pub regions: Vec<LayoutRegion>,
+  /// The changed area to draw over the previous frame, absent when nothing changed.
+  pub patch: Option<RenderedPatch>,
+}
+
+/// A drawn glyph as a vector outline.
+#[napi(object)]
+pub struct GlyphOutline {
+  /// The glyph id within its font.
+  pub glyph_id: u32,
+  /// The transform from glyph space to output pixels.
+  #[napi(ts_type = "[number, number, number, number, number, number]")]
+  pub transform: Vec<f64>,
+  /// SVG path data of the glyph in pixels, with the origin on the baseline and y pointing down.
+  pub path: String,
+  /// The fill color as RGBA.
+  #[napi(ts_type = "[number, number, number, number]")]
+  pub color: Vec<u8>,
+}
+
+impl From<takumi::rendering::GlyphOutline> for GlyphOutline {
+  fn from(outline: takumi::rendering::GlyphOutline) -> Self {
+    Self {
+      glyph_id: outline.glyph_id,
+      transform: outline.transform.iter().map(|&x| x as f64).collect(),
+      path: outline.path,
+      color: outline.color.to_vec(),
+    }
+  }
+}
+
+/// An axis-aligned region of the output image.
+#[napi(object)]
+pub struct LayoutRegion {
+  /// The left edge of the region.
+  pub x: f64,
+  /// The top edge of the region.
+  pub y: f64,
+  /// The width of the region.
+  pub width: f64,
+  /// The height of the region.
+  pub height: f64,
+}
+
+impl From<takumi::rendering::LayoutRegion> for LayoutRegion {
+  fn from(region: takumi::rendering::LayoutRegion) -> Self {
+    Self {
+      x: region.x as f64,
+      y: region.y as f64,
+      width: region.width as f64,
+      height: region.height as f64,
     }
   }
 }
@@ -80,13 +422,25 @@ impl From<takumi::rendering::MeasuredNode> for MeasuredNode {
 #[derive(PartialEq, Eq, Hash)]
 pub(crate) struct ImageCacheKey {
   pub src: Box<str>,
-  pub data_hash: u64,
+  pub data_hash: u128,
 }
 
 /// The main renderer for Takumi image rendering engine (Node.js version).
 #[napi]
 pub struct Renderer {
   pub(crate) state: Arc<RwLock<RendererState>>,
+  pub(crate) custom_drawers: RwLock<CustomDrawers>,
+  asset_load_failures: Vec<AssetLoadFailure>,
+}
+
+/// Fonts and persistent images that can be shared between multiple [`Renderer`] instances.
+///
+/// Pass it as `sharedAssets` when constructing a renderer. Fonts and images loaded through any
+/// renderer using it become visible to all of them, custom drawers stay per renderer.
+#[napi]
+pub struct SharedAssets {
+  pub(crate) state: Arc<RwLock<RendererState>>,
+  asset_load_failures: Vec<AssetLoadFailure>,
 }
 
 pub(crate) struct RendererState {
@@ -94,6 +448,8 @@ pub(crate) struct RendererState {
   pub(crate) persistent_image_cache: HashSet<ImageCacheKey, Xxh3DefaultBuilder>,
 }
 
+pub(crate) type CustomDrawers = HashMap<Arc<str>, Arc<dyn CustomDraw>>;
+
 pub(crate) fn deserialize_keyframes(keyframes: Option<Object>) -> Result<Vec<CoreKeyframesRule>> {
   match keyframes {
     Some(keyframes) => {
@@ -113,10 +469,23 @@ pub struct RenderOptions<'env> {
   pub width: Option<u32>,
   /// The height of the image. If not provided, the height will be automatically calculated based on the content.
   pub height: Option<u32>,
+  /// The largest width an auto-sized width can grow to, wider content wraps to fit. Ignored when `width` is set.
+  pub max_width: Option<u32>,
+  /// The largest height an auto-sized height can grow to, content below it is cut off. Ignored when `height` is set.
+  pub max_height: Option<u32>,
   /// The format of the image.
   pub format: Option<OutputFormat>,
-  /// The quality of JPEG format (0-100).
+  /// The quality of JPEG, WebP and AVIF formats (0-100).
   pub quality: Option<u8>,
+  /// How much encoding time to trade for smaller files, for encoder options that aren't set.
+  /// @default "balanced"
+  pub preset: Option<EncodePreset>,
+  /// WebP encoder options.
+  pub webp: Option<WebpEncodeOptions>,
+  /// AVIF encoder options.
+  pub avif: Option<AvifEncodeOptions>,
+  /// PNG encoder options.
+  pub png: Option<PngEncodeOptions>,
   /// Whether to draw debug borders.
   pub draw_debug_border: Option<bool>,
   /// The fetched resources to use.
@@ -131,10 +500,28 @@ pub struct RenderOptions<'env> {
   /// The device pixel ratio.
   /// @default 1.0
   pub device_pixel_ratio: Option<f64>,
+  /// The color scheme that selects `dark` and `light` style blocks and matches
+  /// `prefers-color-scheme` media queries.
+  /// @default "light"
+  pub color_scheme: Option<ColorScheme>,
   /// The animation timeline time in milliseconds.
   pub time_ms: Option<i64>,
+  /// The Unix timestamp in milliseconds that templates see as the current time, for reproducible renders.
+  pub now: Option<i64>,
+  /// The seed for random variation in templates. The same seed always renders the same image.
+  /// @default 0
+  pub seed: Option<u32>,
+  /// The BCP 47 locale that text node `value`s are formatted for, like `"de-DE"`.
+  /// @default "en-US"
+  pub locale: Option<String>,
   /// The output dithering algorithm.
   pub dithering: Option<DitheringAlgorithm>,
+  /// What happens to text no loaded font covers, overriding the renderer's `missingFontPolicy`.
+  pub missing_font_policy: Option<MissingFontPolicy>,
+  /// Fonts visible to this render only, on top of the renderer's fonts. They are released once
+  /// the render finishes, so tenant uploaded fonts don't leak into other renders.
+  #[napi(ts_type = "Font[] | undefined")]
+  pub fonts: Option<Vec<Object<'env>>>,
 }
 
 #[napi(string_enum)]
@@ -157,6 +544,91 @@ impl From<DitheringAlgorithm> for CoreDitheringAlgorithm {
   }
 }
 
+/// What happens to text no loaded font or fallback covers.
+#[napi(string_enum = "lowercase")]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MissingFontPolicy {
+  /// Draw the font's `.notdef` glyph, usually a box or nothing.
+  Notdef,
+  /// Reject the render with an `INVALID_NODE` error.
+  Error,
+  /// Leave the space of missing characters empty.
+  Skip,
+  /// Draw a hollow box in the text color for every missing character.
+  Replace,
+}
+
+impl From<MissingFontPolicy> for CoreMissingFontPolicy {
+  fn from(policy: MissingFontPolicy) -> Self {
+    match policy {
+      MissingFontPolicy::Notdef => Self::Notdef,
+      MissingFontPolicy::Error => Self::Error,
+      MissingFontPolicy::Skip => Self::Skip,
+      MissingFontPolicy::Replace => Self::Replace,
+    }
+  }
+}
+
+/// The preferred color scheme of a render.
+#[napi(string_enum = "lowercase")]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ColorScheme {
+  /// A light theme.
+  Light,
+  /// A dark theme.
+  Dark,
+}
+
+impl From<ColorScheme> for CoreColorScheme {
+  fn from(scheme: ColorScheme) -> Self {
+    match scheme {
+      ColorScheme::Light => Self::Light,
+      ColorScheme::Dark => Self::Dark,
+    }
+  }
+}
+
+/// The kind of placeholder generated by `Renderer.placeholder()`.
+#[napi(string_enum = "lowercase")]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PlaceholderKind {
+  /// The dominant color, as a CSS color.
+  Solid,
+  /// A top to bottom CSS `linear-gradient()` between the dominant colors of each half.
+  Gradient,
+  /// A blurhash string.
+  Blurhash,
+}
+
+/// Options for `Renderer.placeholder()`.
+#[napi(object)]
+#[derive(Default)]
+pub struct PlaceholderOptions {
+  /// The kind of placeholder, defaults to `solid`.
+  pub kind: Option<PlaceholderKind>,
+  /// The number of horizontal blurhash components from 1 to 9, defaults to 4.
+  pub components_x: Option<u32>,
+  /// The number of vertical blurhash components from 1 to 9, defaults to 3.
+  pub components_y: Option<u32>,
+}
+
+impl From<PlaceholderOptions> for CorePlaceholderKind {
+  fn from(options: PlaceholderOptions) -> Self {
+    match options.kind.unwrap_or(PlaceholderKind::Solid) {
+      PlaceholderKind::Solid => Self::Solid,
+      PlaceholderKind::Gradient => Self::Gradient,
+      PlaceholderKind::Blurhash => Self::Blurhash {
+        components_x: options
+          .components_x
+          .unwrap_or(DEFAULT_BLURHASH_COMPONENTS.0),
+        components_y: options
+          .components_y
+          .unwrap_or(DEFAULT_BLURHASH_COMPONENTS.1),
+      },
+    }
+  }
+}
+
 /// Represents a single frame in a precomputed animation sequence.
 #[napi(object)]
 pub struct AnimationFrameSource<'ctx> {
@@ -192,6 +664,9 @@ pub struct RenderAnimationOptions<'env> {
   pub format: Option<AnimationOutputFormat>,
   /// The quality of WebP format (0-100). Ignored for APNG and GIF.
   pub quality: Option<u8>,
+  /// The CSS color that GIF frames are composited onto, so semi-transparent pixels blend
+  /// instead of being thresholded. Ignored for WebP and APNG.
+  pub matte: Option<String>,
   /// Frames per second for timeline sampling.
   pub fps: u32,
   /// The fetched resources to use.
@@ -201,6 +676,10 @@ pub struct RenderAnimationOptions<'env> {
   /// The device pixel ratio.
   /// @default 1.0
   pub device_pixel_ratio: Option<f64>,
+  /// The color scheme that selects `dark` and `light` style blocks and matches
+  /// `prefers-color-scheme` media queries.
+  /// @default "light"
+  pub color_scheme: Option<ColorScheme>,
 }
 
 /// Options for encoding a precomputed frame sequence.
@@ -216,6 +695,9 @@ pub struct EncodeFramesOptions<'env> {
   pub format: Option<AnimationOutputFormat>,
   /// The quality of WebP format (0-100). Ignored for APNG and GIF.
   pub quality: Option<u8>,
+  /// The CSS color that GIF frames are composited onto, so semi-transparent pixels blend
+  /// instead of being thresholded. Ignored for WebP and APNG.
+  pub matte: Option<String>,
   /// The fetched resources to use.
   pub fetched_resources: Option<Vec<ImageSource<'env>>>,
   /// CSS stylesheets to apply before rendering.
@@ -223,6 +705,166 @@ pub struct EncodeFramesOptions<'env> {
   /// The device pixel ratio.
   /// @default 1.0
   pub device_pixel_ratio: Option<f64>,
+  /// The color scheme that selects `dark` and `light` style blocks and matches
+  /// `prefers-color-scheme` media queries.
+  /// @default "light"
+  pub color_scheme: Option<ColorScheme>,
+}
+
+impl RenderOptions<'_> {
+  /// Collects the encoder options, rejecting values the encoders can't use.
+  pub(crate) fn encode_options(&self) -> Result<EncodeOptions> {
+    let avif = self.avif.as_ref();
+    let bit_depth = match avif.and_then(|avif| avif.bit_depth) {
+      None | Some(8) => AvifBitDepth::Eight,
+      Some(10) => AvifBitDepth::Ten,
+      Some(bit_depth) => {
+        return Err(Error::new(
+          Status::InvalidArg,
+          format!("Invalid AVIF bit depth {bit_depth}; expected 8 or 10"),
+        ));
+      }
+    };
+
+    Ok(
+      EncodeOptions::builder()
+        .quality(self.quality)
+        .preset(self.preset.map(Into::into).unwrap_or_default())
+        .webp(
+          CoreWebpEncodeOptions::builder()
+            .lossless(self.webp.as_ref().and_then(|webp| webp.lossless))
+            .method(self.webp.as_ref().and_then(|webp| webp.method))
+            .build(),
+        )
+        .avif(
+          CoreAvifEncodeOptions::builder()
+            .speed(avif.and_then(|avif| avif.speed))
+            .bit_depth(bit_depth)
+            .build(),
+        )
+        .png(
+          CorePngEncodeOptions::builder()
+            .compression(
+              self
+                .png
+                .as_ref()
+                .and_then(|png| png.compression)
+                .map(Into::into),
+            )
+            .filter(self.png.as_ref().and_then(|png| png.filter).map(Into::into))
+            .build(),
+        )
+        .build(),
+    )
+  }
+}
+
+/// How much encoding time to trade for smaller files.
+#[napi(string_enum = "lowercase")]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum EncodePreset {
+  /// Encode as fast as possible at the cost of larger files.
+  Fast,
+  /// Fast enough to encode on request while keeping files reasonably small.
+  Balanced,
+  /// Spend much more time to produce smaller files, for outputs that are cached.
+  Small,
+}
+
+impl From<EncodePreset> for CoreEncodePreset {
+  fn from(preset: EncodePreset) -> Self {
+    match preset {
+      EncodePreset::Fast => Self::Fast,
+      EncodePreset::Balanced => Self::Balanced,
+      EncodePreset::Small => Self::Small,
+    }
+  }
+}
+
+/// WebP encoder options.
+#[napi(object)]
+pub struct WebpEncodeOptions {
+  /// Whether to encode losslessly. Defaults to lossless when `quality` is 100 or unset.
+  pub lossless: Option<bool>,
+  /// Compression method from 0 (fastest) to 6 (smallest files).
+  pub method: Option<u8>,
+}
+
+/// AVIF encoder options.
+#[napi(object)]
+pub struct AvifEncodeOptions {
+  /// Encoder speed from 1 (slowest, smallest files) to 10 (fastest).
+  pub speed: Option<u8>,
+  /// Bits per channel. 10 bits reduce banding in gradients.
+  /// @default 8
+  #[napi(ts_type = "8 | 10")]
+  pub bit_depth: Option<u8>,
+}
+
+/// PNG encoder options.
+#[napi(object)]
+pub struct PngEncodeOptions {
+  /// Deflate compression level.
+  pub compression: Option<PngCompression>,
+  /// Row filter strategy. Picked by the compression level when unset.
+  pub filter: Option<PngFilter>,
+}
+
+/// PNG deflate compression level.
+#[napi(string_enum = "lowercase")]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PngCompression {
+  None,
+  Fastest,
+  Fast,
+  Balanced,
+  High,
+}
+
+impl From<PngCompression> for CorePngCompression {
+  fn from(compression: PngCompression) -> Self {
+    match compression {
+      PngCompression::None => Self::None,
+      PngCompression::Fastest => Self::Fastest,
+      PngCompression::Fast => Self::Fast,
+      PngCompression::Balanced => Self::Balanced,
+      PngCompression::High => Self::High,
+    }
+  }
+}
+
+/// PNG row filter strategy.
+#[napi(string_enum)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PngFilter {
+  #[napi(value = "none")]
+  None,
+  #[napi(value = "sub")]
+  Sub,
+  #[napi(value = "up")]
+  Up,
+  #[napi(value = "avg")]
+  Avg,
+  #[napi(value = "paeth")]
+  Paeth,
+  #[napi(value = "adaptive")]
+  Adaptive,
+  #[napi(value = "min-entropy")]
+  MinEntropy,
+}
+
+impl From<PngFilter> for CorePngFilter {
+  fn from(filter: PngFilter) -> Self {
+    match filter {
+      PngFilter::None => Self::None,
+      PngFilter::Sub => Self::Sub,
+      PngFilter::Up => Self::Up,
+      PngFilter::Avg => Self::Avg,
+      PngFilter::Paeth => Self::Paeth,
+      PngFilter::Adaptive => Self::Adaptive,
+      PngFilter::MinEntropy => Self::MinEntropy,
+    }
+  }
 }
 
 /// Output format for animated images.
@@ -247,6 +889,8 @@ pub enum OutputFormat {
   Png,
   /// JPEG format.
   Jpeg,
+  /// AVIF format.
+  Avif,
   /// Raw pixels format.
   Raw,
 }
@@ -257,6 +901,7 @@ impl From<OutputFormat> for ImageOutputFormat {
       OutputFormat::WebP => ImageOutputFormat::WebP,
       OutputFormat::Jpeg => ImageOutputFormat::Jpeg,
       OutputFormat::Png => ImageOutputFormat::Png,
+      OutputFormat::Avif => ImageOutputFormat::Avif,
       // SAFETY: It's handled in the render task
       OutputFormat::Raw => unreachable!(),
     }
@@ -271,6 +916,19 @@ pub struct ImageSource<'ctx> {
   /// The raw image data (Uint8Array or ArrayBuffer).
   #[napi(ts_type = "Uint8Array | ArrayBuffer")]
   pub data: Object<'ctx>,
+  /// Shrinks bitmaps whose longer side exceeds this many pixels while decoding, keeping only
+  /// the reduced image in memory. The intrinsic size stays that of the encoded image.
+  pub max_decode_size: Option<u32>,
+}
+
+/// Texts and styles to shape ahead of time with `Renderer.warmup`.
+#[napi(object)]
+pub struct WarmupOptions<'ctx> {
+  /// The strings to shape.
+  pub texts: Vec<String>,
+  /// The styles to shape every text with. The default style is used when omitted.
+  #[napi(ts_type = "NonNullable<NodeMetadata[\"style\"]>[] | undefined")]
+  pub styles: Option<Vec<Object<'ctx>>>,
 }
 
 /// Options for constructing a Renderer instance.
@@ -285,6 +943,120 @@ pub struct ConstructRendererOptions<'ctx> {
   /// Whether to load the default fonts.
   /// If `fonts` are provided, this will be `false` by default.
   pub load_default_fonts: Option<bool>,
+  /// Assets shared with other renderers. `fonts` and `persistentImages` are loaded into them,
+  /// and `loadDefaultFonts` is ignored.
+  #[napi(ts_type = "SharedAssets | undefined")]
+  pub shared_assets: Option<ClassInstance<'ctx, SharedAssets>>,
+  /// Maximum bytes of scaled glyphs kept between renders, 16 MiB by default.
+  /// `0` disables the glyph cache.
+  pub glyph_cache_budget: Option<u32>,
+  /// Maximum bytes of decoded fetched images kept between renders, 32 MiB by default.
+  /// Identical bytes are decoded once even when fetched from different URLs.
+  /// `0` disables the image cache.
+  pub image_cache_budget: Option<u32>,
+  /// What happens to text no loaded font covers. Renders can override it.
+  /// @default "notdef"
+  pub missing_font_policy: Option<MissingFontPolicy>,
+  /// How glyphs are rasterized.
+  pub text_rendering: Option<TextRenderingOptions>,
+}
+
+/// How glyphs are rasterized.
+#[napi(object)]
+pub struct TextRenderingOptions {
+  /// Whether outlines are hinted, fitting stems to the pixel grid so small text looks sharper.
+  /// @default false
+  pub hinting: Option<bool>,
+  /// How many horizontal positions per device pixel glyphs snap to, `0` keeps them unquantized.
+  /// @default 0
+  pub subpixel_positions: Option<u32>,
+  /// The gamma glyph coverage is corrected with before blending. Values above `1` darken the
+  /// antialiased edges of thin stems.
+  /// @default 1
+  pub gamma: Option<f64>,
+}
+
+impl From<TextRenderingOptions> for CoreTextRenderingOptions {
+  fn from(options: TextRenderingOptions) -> Self {
+    let defaults = Self::default();
+
+    Self {
+      hinting: options.hinting.unwrap_or(defaults.hinting),
+      subpixel_positions: options
+        .subpixel_positions
+        .map_or(defaults.subpixel_positions, |positions| {
+          positions.min(u8::MAX as u32) as u8
+        }),
+      gamma: options.gamma.map_or(defaults.gamma, |gamma| gamma as f32),
+    }
+  }
+}
+
+/// Counters of the glyph cache.
+#[napi(object)]
+pub struct GlyphCacheStats {
+  /// Number of cached glyphs.
+  pub entries: u32,
+  /// Estimated memory held by the cached glyphs, in bytes.
+  pub bytes: f64,
+  /// Maximum number of bytes the cache may hold.
+  pub budget: f64,
+  /// Glyph lookups served from the cache.
+  pub hits: f64,
+  /// Glyph lookups that had to scale the glyph.
+  pub misses: f64,
+  /// Glyphs dropped to stay within the budget.
+  pub evictions: f64,
+}
+
+/// Counters of the image decode cache.
+#[napi(object)]
+pub struct ImageCacheStats {
+  /// Number of cached images.
+  pub entries: u32,
+  /// Estimated memory held by the decoded images, in bytes.
+  pub bytes: f64,
+  /// Maximum number of bytes the cache may hold.
+  pub budget: f64,
+  /// Decodes served from the cache.
+  pub hits: f64,
+  /// Decodes that had to decode the bytes.
+  pub misses: f64,
+  /// Images dropped to stay within the budget.
+  pub evictions: f64,
+}
+
+/// Statistics of the caches kept across renders.
+#[napi(object)]
+pub struct CacheStats {
+  pub glyphs: GlyphCacheStats,
+  pub images: ImageCacheStats,
+}
+
+impl From<takumi::rendering::CacheStats> for CacheStats {
+  fn from(stats: takumi::rendering::CacheStats) -> Self {
+    let glyphs = stats.glyphs;
+    let images = stats.images;
+
+    Self {
+      glyphs: GlyphCacheStats {
+        entries: glyphs.entries as u32,
+        bytes: glyphs.bytes as f64,
+        budget: glyphs.budget as f64,
+        hits: glyphs.hits as f64,
+        misses: glyphs.misses as f64,
+        evictions: glyphs.evictions as f64,
+      },
+      images: ImageCacheStats {
+        entries: images.entries as u32,
+        bytes: images.bytes as f64,
+        budget: images.budget as f64,
+        hits: images.hits as f64,
+        misses: images.misses as f64,
+        evictions: images.evictions as f64,
+      },
+    }
+  }
 }
 
 const EMBEDDED_FONTS: &[(&[u8], &str, GenericFamily)] = &[
@@ -300,21 +1072,10 @@ const EMBEDDED_FONTS: &[(&[u8], &str, GenericFamily)] = &[
   ),
 ];
 
-#[napi]
-impl Renderer {
-  /// Creates a new Renderer instance.
-  #[napi(constructor)]
-  pub fn new(env: Env, options: Option<ConstructRendererOptions>) -> Result<Self> {
-    let options = options.unwrap_or_default();
-
-    let load_default_fonts = options
-      .load_default_fonts
-      .unwrap_or_else(|| options.fonts.is_none());
-
-    let mut global = GlobalContext::default();
-
-    if load_default_fonts {
-      let default_fonts_resources = EMBEDDED_FONTS
+impl RendererState {
+  fn new(env: Env, load_default_fonts: bool) -> Result<Self> {
+    let fonts = if load_default_fonts {
+      EMBEDDED_FONTS
         .par_iter()
         .map(|(font, name, generic)| {
           FontResource::new(*font)
@@ -324,48 +1085,168 @@ impl Renderer {
             })
             .generic_family(*generic)
             .into_resolved()
-            .map_err(|e| Error::from_reason(format!("Failed to load default font: {e}")))
+            .map_err(|e| format!("Failed to load default font: {e}"))
         })
-        .collect::<Result<Vec<_>>>()?;
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| coded_error(&env, ErrorCode::FontParseError, e))?
+    } else {
+      Vec::new()
+    };
+
+    let global = GlobalContext::builder()
+      .fonts(fonts)
+      .build()
+      .map_err(|e| coded_error(&env, ErrorCode::FontParseError, e))?;
+
+    Ok(Self {
+      global,
+      persistent_image_cache: HashSet::default(),
+    })
+  }
+}
 
-      for resource in default_fonts_resources {
-        global
-          .font_context_mut()
-          .load_and_store(resource)
-          .map_err(map_error)?;
+#[napi]
+impl SharedAssets {
+  /// Creates a new asset store to share between renderers.
+  #[napi(
+    constructor,
+    ts_args_type = "options?: Omit<ConstructRendererOptions, \"sharedAssets\">"
+  )]
+  pub fn new(env: Env, options: Option<ConstructRendererOptions>) -> Result<Self> {
+    let options = options.map(|options| ConstructRendererOptions {
+      shared_assets: None,
+      ..options
+    });
+
+    let renderer = Renderer::new(env, options)?;
+
+    Ok(Self {
+      state: renderer.state,
+      asset_load_failures: renderer.asset_load_failures,
+    })
+  }
+
+  /// The fonts and persistent images passed to the constructor that failed to load.
+  #[napi(getter)]
+  pub fn asset_load_failures(&self) -> Vec<AssetLoadFailure> {
+    self.asset_load_failures.clone()
+  }
+}
+
+impl Renderer {
+  /// Snapshots the registered custom drawers for a task.
+  pub(crate) fn custom_drawers(&self) -> Result<CustomDrawers> {
+    self
+      .custom_drawers
+      .read()
+      .map(|drawers| drawers.clone())
+      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))
+  }
+}
+
+#[napi]
+impl Renderer {
+  /// Creates a new Renderer instance.
+  #[napi(constructor)]
+  pub fn new(env: Env, options: Option<ConstructRendererOptions>) -> Result<Self> {
+    let options = options.unwrap_or_default();
+
+    let state = match &options.shared_assets {
+      Some(assets) => Arc::clone(&assets.state),
+      None => {
+        let load_default_fonts = options
+          .load_default_fonts
+          .unwrap_or_else(|| options.fonts.is_none());
+
+        Arc::new(RwLock::new(RendererState::new(env, load_default_fonts)?))
       }
-    }
+    };
 
-    let renderer = Self {
-      state: Arc::new(RwLock::new(RendererState {
-        global,
-        persistent_image_cache: HashSet::default(),
-      })),
+    let mut renderer = Self {
+      state,
+      custom_drawers: RwLock::default(),
+      asset_load_failures: Vec::new(),
     };
 
+    if let Some(budget) = options.glyph_cache_budget {
+      renderer
+        .state
+        .read()
+        .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?
+        .global
+        .glyph_cache()
+        .set_budget(budget as usize);
+    }
+
+    if let Some(budget) = options.image_cache_budget {
+      renderer
+        .state
+        .read()
+        .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?
+        .global
+        .image_cache()
+        .set_budget(budget as usize);
+    }
+
+    if let Some(policy) = options.missing_font_policy {
+      renderer
+        .state
+        .write()
+        .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?
+        .global
+        .set_missing_font_policy(policy.into());
+    }
+
+    if let Some(text_rendering) = options.text_rendering {
+      renderer
+        .state
+        .write()
+        .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?
+        .global
+        .set_text_rendering(text_rendering.into());
+    }
+
+    let mut failures = Vec::new();
+
     if let Some(fonts) = options.fonts {
-      let buffers = fonts
-        .into_iter()
-        .map(|font| parse_font_input(env, font))
-        .collect::<Result<Vec<_>>>()?;
+      let mut buffers = Vec::with_capacity(fonts.len());
+
+      for (index, font) in fonts.into_iter().enumerate() {
+        match parse_font_input(env, font) {
+          Ok(input) => buffers.push((index, input)),
+          Err(e) => failures.push(AssetLoadFailure::font(index, None, e.reason.clone())),
+        }
+      }
 
       let custom_fonts_resources = buffers
         .par_iter()
         .with_min_len(2)
-        .map(|(font, buffer): &(FontInput, Buffer)| resolve_font_resource(font, buffer.as_ref()))
-        .collect::<Result<Vec<_>>>()?;
+        .map(|(index, (font, buffer))| {
+          (
+            *index,
+            font,
+            resolve_font_resource(font, buffer.as_ref()).map_err(|e| e.reason.clone()),
+          )
+        })
+        .collect::<Vec<_>>();
 
       let mut state = renderer
         .state
         .write()
         .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
 
-      for resource in custom_fonts_resources {
-        state
-          .global
-          .font_context_mut()
-          .load_and_store(resource)
-          .map_err(map_error)?;
+      for (index, font, resource) in custom_fonts_resources {
+        let loaded = resource.and_then(|resource| {
+          state
+            .global
+            .font_context_mut()
+            .load_and_store(resource)
+            .map_err(|e| e.to_string())
+        });
+
+        if let Err(reason) = loaded {
+          failures.push(AssetLoadFailure::font(index, font.name.clone(), reason));
+        }
       }
     }
 
@@ -374,20 +1255,38 @@ impl Renderer {
         .state
         .write()
         .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
-      for image in images {
-        let buffer = buffer_slice_from_object(env, image.data)?;
-        let image_source = LoadedImageSource::from_bytes(&buffer).map_err(map_error)?;
-
-        state
-          .global
-          .persistent_image_store_mut()
-          .insert(image.src, image_source);
+      for (index, image) in images.into_iter().enumerate() {
+        let image_source = buffer_slice_from_object(env, image.data)
+          .map_err(|e| e.reason.clone())
+          .and_then(|buffer| {
+            decode_image_source(&buffer, image.max_decode_size).map_err(|e| e.to_string())
+          });
+
+        match image_source {
+          Ok(image_source) => {
+            state
+              .global
+              .persistent_image_store_mut()
+              .insert(image.src, image_source);
+          }
+          Err(reason) => failures.push(AssetLoadFailure::image(index, image.src, reason)),
+        }
       }
     }
 
+    renderer.asset_load_failures = failures;
+
     Ok(renderer)
   }
 
+  /// The fonts and persistent images passed to the constructor that failed to load.
+  ///
+  /// The constructor loads every valid asset and records the rest here instead of throwing.
+  #[napi(getter)]
+  pub fn asset_load_failures(&self) -> Vec<AssetLoadFailure> {
+    self.asset_load_failures.clone()
+  }
+
   /// Puts a persistent image into the renderer's internal store asynchronously.
   #[napi(
     ts_args_type = "src: string, data: Uint8Array | ArrayBuffer, signal?: AbortSignal",
@@ -407,6 +1306,7 @@ impl Renderer {
         src: Some(src),
         state: Arc::clone(&self.state),
         buffer,
+        error_code: None,
       },
       signal,
     ))
@@ -418,7 +1318,7 @@ impl Renderer {
     if let Ok(buffer) = buffer_slice_from_object(env, font) {
       let resource = FontResource::new(buffer.as_ref())
         .into_resolved()
-        .map_err(map_error)?;
+        .map_err(|e| coded_error(&env, ErrorCode::FontParseError, e))?;
 
       let mut state = self
         .state
@@ -429,7 +1329,7 @@ impl Renderer {
         .global
         .font_context_mut()
         .load_and_store(resource)
-        .map_err(map_error)?;
+        .map_err(|e| coded_error(&env, ErrorCode::FontParseError, e))?;
 
       return Ok(());
     }
@@ -439,7 +1339,8 @@ impl Renderer {
       .and_then(|buffer| buffer_slice_from_object(env, buffer))?;
     let font_input: FontInput = deserialize_with_tracing(font)?;
 
-    let resource = resolve_font_resource(&font_input, buffer.as_ref())?;
+    let resource = resolve_font_resource(&font_input, buffer.as_ref())
+      .map_err(|e| coded_error(&env, ErrorCode::FontParseError, &e.reason))?;
 
     let mut state = self
       .state
@@ -450,7 +1351,7 @@ impl Renderer {
       .global
       .font_context_mut()
       .load_and_store(resource)
-      .map_err(map_error)?;
+      .map_err(|e| coded_error(&env, ErrorCode::FontParseError, e))?;
 
     Ok(())
   }
@@ -489,11 +1390,93 @@ impl Renderer {
       LoadFontTask {
         state: Arc::clone(&self.state),
         buffers,
+        error_code: None,
+      },
+      signal,
+    ))
+  }
+
+  /// Unloads every font of a family, along with the glyphs cached for it.
+  /// Returns whether the family had any fonts loaded.
+  #[napi]
+  pub fn unload_font(&self, family: String) -> Result<bool> {
+    let mut state = self
+      .state
+      .write()
+      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
+
+    Ok(state.global.unload_font_family(&family))
+  }
+
+  /// Replaces the fonts of a family with another font, registered under the same family name.
+  /// The current fonts are kept if the new one fails to load.
+  #[napi(
+    ts_args_type = "family: string, font: Font, signal?: AbortSignal",
+    ts_return_type = "Promise<void>"
+  )]
+  pub fn replace_font(
+    &self,
+    env: Env,
+    family: String,
+    font: Object,
+    signal: Option<AbortSignal>,
+  ) -> Result<AsyncTask<ReplaceFontTask>> {
+    Ok(AsyncTask::with_optional_signal(
+      ReplaceFontTask {
+        state: Arc::clone(&self.state),
+        family,
+        font: parse_font_input(env, font)?,
+        error_code: None,
+      },
+      signal,
+    ))
+  }
+
+  /// Shapes the given texts with the given styles ahead of time, so the first render after a
+  /// cold start does not pay the full text shaping cost.
+  #[napi(
+    ts_args_type = "options: WarmupOptions, signal?: AbortSignal",
+    ts_return_type = "Promise<void>"
+  )]
+  pub fn warmup(
+    &self,
+    env: Env,
+    options: WarmupOptions,
+    signal: Option<AbortSignal>,
+  ) -> Result<AsyncTask<WarmupTask>> {
+    let styles = options
+      .styles
+      .unwrap_or_default()
+      .into_iter()
+      .map(deserialize_with_tracing)
+      .collect::<Result<Vec<Style>>>()
+      .map_err(|e| coded_error(&env, ErrorCode::InvalidNode, &e.reason))?;
+
+    Ok(AsyncTask::with_optional_signal(
+      WarmupTask {
+        state: Arc::clone(&self.state),
+        texts: options.texts,
+        styles,
+        error_code: None,
       },
       signal,
     ))
   }
 
+  /// Returns the statistics of the caches kept across renders, for tuning memory use.
+  #[napi]
+  pub fn cache_stats(&self) -> Result<CacheStats> {
+    Ok(
+      self
+        .state
+        .read()
+        .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?
+        .global
+        .cache_stats()
+        .into(),
+    )
+  }
+
   /// Clears the renderer's internal image store.
   #[napi]
   pub fn clear_image_store(&self) {
@@ -502,6 +1485,83 @@ impl Renderer {
     }
   }
 
+  /// Extracts up to `count` (default 8) representative colors from a persistent image as RGBA,
+  /// most common first.
+  ///
+  /// Styles can reference the same colors with `palette(src, index)`.
+  #[napi(ts_return_type = "[number, number, number, number][]")]
+  pub fn extract_palette(&self, env: Env, src: String, count: Option<u32>) -> Result<Vec<Vec<u8>>> {
+    let image = self
+      .state
+      .read()
+      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?
+      .global
+      .persistent_image_store()
+      .get(&src)
+      .ok_or(ImageResourceError::Unknown);
+
+    let palette = image
+      .and_then(|image| image.palette(count.map_or(DEFAULT_PALETTE_SIZE, |count| count as usize)))
+      .map_err(|err| {
+        let err = takumi::Error::from(err);
+        coded_error(&env, err.code(), err)
+      })?;
+
+    Ok(palette.into_iter().map(|color| color.0.to_vec()).collect())
+  }
+
+  /// Generates a tiny placeholder for a persistent image, such as its dominant color or a blurhash,
+  /// to show while a render using it is being generated.
+  #[napi]
+  pub fn placeholder(
+    &self,
+    env: Env,
+    src: String,
+    options: Option<PlaceholderOptions>,
+  ) -> Result<String> {
+    let image = self
+      .state
+      .read()
+      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?
+      .global
+      .persistent_image_store()
+      .get(&src)
+      .ok_or(ImageResourceError::Unknown);
+
+    image
+      .and_then(|image| image.placeholder(options.unwrap_or_default().into()))
+      .map_err(|err| {
+        let err = takumi::Error::from(err);
+        coded_error(&env, err.code(), err)
+      })
+  }
+
+  /// Registers a drawer for custom nodes with the given name, replacing any existing one.
+  ///
+  /// The callback receives the content box size in device pixels and must return
+  /// `width * height * 4` bytes of non-premultiplied RGBA data.
+  #[napi(ts_args_type = "name: string, draw: (width: number, height: number) => Uint8Array")]
+  pub fn register_custom_draw(
+    &self,
+    name: String,
+    draw: Function<FnArgs<(u32, u32)>, Uint8Array>,
+  ) -> Result<()> {
+    let callback: CustomDrawCallback = draw
+      .build_threadsafe_function()
+      .callee_handled::<false>()
+      .weak::<true>()
+      .build()?;
+
+    let mut drawers = self
+      .custom_drawers
+      .write()
+      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
+
+    drawers.insert(Arc::from(name), Arc::new(JsCustomDraw { callback }));
+
+    Ok(())
+  }
+
   /// Renders a node tree into an image buffer asynchronously.
   #[napi(
     ts_args_type = "source: Node, options?: RenderOptions, signal?: AbortSignal",
@@ -514,15 +1574,91 @@ impl Renderer {
     options: Option<RenderOptions>,
     signal: Option<AbortSignal>,
   ) -> Result<AsyncTask<RenderTask>> {
-    let node: Node = deserialize_with_tracing(source)?;
+    let node: Node = deserialize_node(&env, source)?;
 
     Ok(AsyncTask::with_optional_signal(
-      RenderTask::from_options(
+      RenderTask::from_options(env, node, options.unwrap_or_default(), self)?,
+      signal,
+    ))
+  }
+
+  /// Renders a node tree into an image buffer on the calling thread.
+  ///
+  /// Blocks the thread until the render finishes, so prefer `render` in servers. Custom nodes
+  /// registered with `registerCustomDraw` are left blank, since their callbacks can't run while
+  /// the thread is blocked.
+  #[napi(ts_args_type = "source: Node, options?: RenderOptions")]
+  pub fn render_sync(
+    &self,
+    env: Env,
+    source: Object,
+    options: Option<RenderOptions>,
+  ) -> Result<Buffer> {
+    let node: Node = deserialize_node(&env, source)?;
+
+    let mut task = RenderTask::from_options(env, node, options.unwrap_or_default(), self)?;
+    task.custom_drawers.clear();
+
+    match task.compute() {
+      Ok(output) => task.resolve(env, output),
+      Err(err) => task.reject(env, err),
+    }
+  }
+
+  /// Renders a node tree into raw RGBA pixels asynchronously, along with the final width and
+  /// height of auto-sized renders. `format` and `quality` are ignored.
+  #[napi(
+    ts_args_type = "source: Node, options?: RenderOptions, signal?: AbortSignal",
+    ts_return_type = "Promise<RawImage>"
+  )]
+  pub fn render_raw(
+    &self,
+    env: Env,
+    source: Object,
+    options: Option<RenderOptions>,
+    signal: Option<AbortSignal>,
+  ) -> Result<AsyncTask<RenderRawTask>> {
+    let node: Node = deserialize_node(&env, source)?;
+
+    Ok(AsyncTask::with_optional_signal(
+      RenderRawTask(RenderTask::from_options(
         env,
         node,
         options.unwrap_or_default(),
-        Arc::clone(&self.state),
-      )?,
+        self,
+      )?),
+      signal,
+    ))
+  }
+
+  /// Renders a node tree and streams the encoded image to `onChunk` as it is produced.
+  ///
+  /// PNG and JPEG are encoded incrementally, so the full encoded output is never held in
+  /// memory. The returned promise resolves after the last chunk has been delivered.
+  #[napi(
+    ts_args_type = "source: Node, onChunk: (chunk: Buffer) => void, options?: RenderOptions, signal?: AbortSignal",
+    ts_return_type = "Promise<void>"
+  )]
+  pub fn render_stream(
+    &self,
+    env: Env,
+    source: Object,
+    on_chunk: Function<Buffer, ()>,
+    options: Option<RenderOptions>,
+    signal: Option<AbortSignal>,
+  ) -> Result<AsyncTask<RenderStreamTask>> {
+    let node: Node = deserialize_node(&env, source)?;
+    let on_chunk: ChunkCallback = on_chunk
+      .build_threadsafe_function()
+      .callee_handled::<false>()
+      .max_queue_size::<MAX_QUEUED_CHUNKS>()
+      .build()?;
+
+    Ok(AsyncTask::with_optional_signal(
+      RenderStreamTask {
+        task: RenderTask::from_options(env, node, options.unwrap_or_default(), self)?,
+        on_chunk,
+      },
       signal,
     ))
   }
@@ -539,15 +1675,144 @@ impl Renderer {
     options: Option<RenderOptions>,
     signal: Option<AbortSignal>,
   ) -> Result<AsyncTask<MeasureTask>> {
-    let node: Node = deserialize_with_tracing(source)?;
+    let node: Node = deserialize_node(&env, source)?;
+
+    Ok(AsyncTask::with_optional_signal(
+      MeasureTask::from_options(env, node, options.unwrap_or_default(), self)?,
+      signal,
+    ))
+  }
+
+  /// Renders a node tree and returns the vector outline of every drawn glyph asynchronously.
+  #[napi(
+    ts_args_type = "source: Node, options?: RenderOptions, signal?: AbortSignal",
+    ts_return_type = "Promise<GlyphOutline[]>"
+  )]
+  pub fn outline_text(
+    &self,
+    env: Env,
+    source: Object,
+    options: Option<RenderOptions>,
+    signal: Option<AbortSignal>,
+  ) -> Result<AsyncTask<OutlineTextTask>> {
+    let node: Node = deserialize_node(&env, source)?;
+
+    Ok(AsyncTask::with_optional_signal(
+      OutlineTextTask(MeasureTask::from_options(
+        env,
+        node,
+        options.unwrap_or_default(),
+        self,
+      )?),
+      signal,
+    ))
+  }
+
+  /// Renders a node tree into separately encoded layers asynchronously.
+  ///
+  /// The root paints the first layer, followed by each child of the root and each descendant
+  /// with a `data-layer` attribute, cropped to the area they paint.
+  #[napi(
+    ts_args_type = "source: Node, options?: RenderOptions, signal?: AbortSignal",
+    ts_return_type = "Promise<RenderedLayers>"
+  )]
+  pub fn render_layers(
+    &self,
+    env: Env,
+    source: Object,
+    options: Option<RenderOptions>,
+    signal: Option<AbortSignal>,
+  ) -> Result<AsyncTask<RenderLayersTask>> {
+    let node: Node = deserialize_node(&env, source)?;
+
+    Ok(AsyncTask::with_optional_signal(
+      RenderLayersTask(RenderTask::from_options(
+        env,
+        node,
+        options.unwrap_or_default(),
+        self,
+      )?),
+      signal,
+    ))
+  }
+
+  /// Renders a node tree asynchronously while recording the time spent measuring, laying out
+  /// and drawing every node.
+  ///
+  /// Profiling adds a little overhead, so use `render` for production renders.
+  #[napi(
+    ts_args_type = "source: Node, options?: RenderOptions, signal?: AbortSignal",
+    ts_return_type = "Promise<ProfiledRender>"
+  )]
+  pub fn render_with_profile(
+    &self,
+    env: Env,
+    source: Object,
+    options: Option<RenderOptions>,
+    signal: Option<AbortSignal>,
+  ) -> Result<AsyncTask<RenderProfileTask>> {
+    let node: Node = deserialize_node(&env, source)?;
+
+    let mut task = RenderTask::from_options(env, node, options.unwrap_or_default(), self)?;
+    task.profile = true;
+
+    Ok(AsyncTask::with_optional_signal(
+      RenderProfileTask(task),
+      signal,
+    ))
+  }
+
+  /// Hashes a node tree with its styles, images and fonts without rendering it.
+  ///
+  /// Resolves to 16 hex digits that are equal exactly when `render` would produce the same
+  /// image, suitable as an `ETag` or cache key.
+  #[napi(
+    ts_args_type = "source: Node, options?: RenderOptions, signal?: AbortSignal",
+    ts_return_type = "Promise<string>"
+  )]
+  pub fn render_hash(
+    &self,
+    env: Env,
+    source: Object,
+    options: Option<RenderOptions>,
+    signal: Option<AbortSignal>,
+  ) -> Result<AsyncTask<RenderHashTask>> {
+    let node: Node = deserialize_node(&env, source)?;
 
     Ok(AsyncTask::with_optional_signal(
-      MeasureTask::from_options(
+      RenderHashTask(RenderTask::from_options(
         env,
         node,
         options.unwrap_or_default(),
-        Arc::clone(&self.state),
-      )?,
+        self,
+      )?),
+      signal,
+    ))
+  }
+
+  /// Renders a node tree and compares it against the previous frame asynchronously.
+  ///
+  /// `previous` holds the raw RGBA pixels of an earlier render, such as one made with the `raw`
+  /// format. A frame of a different size counts as changed everywhere.
+  #[napi(
+    ts_args_type = "source: Node, previous: Buffer | Uint8Array, options?: RenderOptions, signal?: AbortSignal",
+    ts_return_type = "Promise<RenderedChanges>"
+  )]
+  pub fn render_changes(
+    &self,
+    env: Env,
+    source: Object,
+    previous: Object,
+    options: Option<RenderOptions>,
+    signal: Option<AbortSignal>,
+  ) -> Result<AsyncTask<RenderChangesTask>> {
+    let node: Node = deserialize_node(&env, source)?;
+
+    Ok(AsyncTask::with_optional_signal(
+      RenderChangesTask {
+        task: RenderTask::from_options(env, node, options.unwrap_or_default(), self)?,
+        previous: buffer_from_object(env, previous)?,
+      },
       signal,
     ))
   }
@@ -564,7 +1829,7 @@ impl Renderer {
     signal: Option<AbortSignal>,
   ) -> Result<AsyncTask<RenderAnimationTask>> {
     Ok(AsyncTask::with_optional_signal(
-      RenderAnimationTask::from_options(env, options, Arc::clone(&self.state))?,
+      RenderAnimationTask::from_options(env, options, self)?,
       signal,
     ))
   }
@@ -583,11 +1848,14 @@ impl Renderer {
   ) -> Result<AsyncTask<EncodeFramesTask>> {
     let frames = source
       .into_iter()
-      .map(|frame| Ok((deserialize_with_tracing(frame.node)?, frame.duration_ms)))
+      .map(|frame| {
+        let node = deserialize_node(&env, frame.node)?;
+        Ok((node, frame.duration_ms))
+      })
       .collect::<Result<Vec<_>>>()?;
 
     Ok(AsyncTask::with_optional_signal(
-      EncodeFramesTask::from_options(env, frames, options, Arc::clone(&self.state))?,
+      EncodeFramesTask::from_options(env, frames, options, self)?,
       signal,
     ))
   }
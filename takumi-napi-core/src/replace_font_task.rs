@@ -0,0 +1,45 @@
+use std::sync::{Arc, RwLock};
+
+use napi::bindgen_prelude::*;
+use takumi::ErrorCode;
+
+use crate::{FontInput, reject_with_code, renderer::RendererState, resolve_font_resource};
+
+pub struct ReplaceFontTask {
+  pub(crate) state: Arc<RwLock<RendererState>>,
+  pub(crate) family: String,
+  pub(crate) font: (FontInput, Buffer),
+  pub(crate) error_code: Option<ErrorCode>,
+}
+
+impl Task for ReplaceFontTask {
+  type Output = ();
+  type JsValue = ();
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let (font, buffer) = &self.font;
+    let resource = resolve_font_resource(font, buffer.as_ref())
+      .inspect_err(|_| self.error_code = Some(ErrorCode::FontParseError))?;
+
+    let mut state = self
+      .state
+      .write()
+      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
+
+    state
+      .global
+      .replace_font_family(&self.family, resource)
+      .map_err(|e| {
+        self.error_code = Some(ErrorCode::FontParseError);
+        Error::from_reason(format!("Failed to load font: {e}"))
+      })
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output)
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.error_code)
+  }
+}
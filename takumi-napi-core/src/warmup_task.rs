@@ -0,0 +1,36 @@
+use std::sync::{Arc, RwLock};
+
+use napi::bindgen_prelude::*;
+use takumi::{ErrorCode, layout::style::Style, rendering::warmup};
+
+use crate::{record_error_code, reject_with_code, renderer::RendererState};
+
+pub struct WarmupTask {
+  pub(crate) state: Arc<RwLock<RendererState>>,
+  pub(crate) texts: Vec<String>,
+  pub(crate) styles: Vec<Style>,
+  pub(crate) error_code: Option<ErrorCode>,
+}
+
+impl Task for WarmupTask {
+  type Output = ();
+  type JsValue = ();
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let state = self
+      .state
+      .read()
+      .map_err(|e| Error::from_reason(format!("Renderer lock poisoned: {e}")))?;
+
+    warmup(&state.global, &self.texts, &self.styles)
+      .map_err(record_error_code(&mut self.error_code))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output)
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_with_code(&env, err, self.error_code)
+  }
+}
@@ -0,0 +1,58 @@
+import { describe, expect, it } from "bun:test";
+import { container, image, text } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+describe("Renderer.cacheStats", () => {
+  const node = text({ text: "Hello, world!", style: { fontSize: 48 } });
+  const options = { width: 400, height: 100, format: "png" } as const;
+
+  it("should serve repeated glyphs from the cache", async () => {
+    const renderer = new Renderer();
+
+    await renderer.render(node, options);
+    const first = renderer.cacheStats().glyphs;
+
+    await renderer.render(node, options);
+    const second = renderer.cacheStats().glyphs;
+
+    expect(first.entries).toBeGreaterThan(0);
+    expect(second.misses).toBe(first.misses);
+    expect(second.hits).toBeGreaterThan(first.hits);
+  });
+
+  it("should disable the cache with a zero budget", async () => {
+    const renderer = new Renderer({ glyphCacheBudget: 0 });
+
+    await renderer.render(node, options);
+
+    expect(renderer.cacheStats().glyphs).toMatchObject({
+      entries: 0,
+      bytes: 0,
+      budget: 0,
+    });
+  });
+
+  it("should decode identical image bytes once across URLs", async () => {
+    const renderer = new Renderer();
+    const data = await Bun.file("../assets/images/yeecord.png").arrayBuffer();
+    const imageNode = (src: string) =>
+      container({
+        children: [image({ src, style: { width: 64, height: 64 } })],
+      });
+
+    await renderer.render(imageNode("https://a.test/logo.png"), {
+      ...options,
+      fetchedResources: [{ src: "https://a.test/logo.png", data }],
+    });
+    await renderer.render(imageNode("https://b.test/logo.png"), {
+      ...options,
+      fetchedResources: [{ src: "https://b.test/logo.png", data }],
+    });
+
+    expect(renderer.cacheStats().images).toMatchObject({
+      entries: 1,
+      hits: 1,
+      misses: 1,
+    });
+  });
+});
@@ -0,0 +1,19 @@
+import { describe, expect, it } from "bun:test";
+import { container } from "@takumi-rs/helpers";
+import { canonicalHash } from "../index.js";
+
+describe("canonicalHash", () => {
+  it("should hash equivalent style spellings equally", () => {
+    const hash = canonicalHash(
+      container({ style: { color: "#fff", padding: "0" } }),
+    );
+
+    expect(hash).toMatch(/^[0-9a-f]{16}$/);
+    expect(
+      canonicalHash(container({ style: { padding: "0px", color: "#ffffff" } })),
+    ).toBe(hash);
+    expect(canonicalHash(container({ style: { color: "#000" } }))).not.toBe(
+      hash,
+    );
+  });
+});
@@ -0,0 +1,48 @@
+import { describe, expect, it } from "bun:test";
+import { container } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+describe("colorScheme", () => {
+  const renderer = new Renderer();
+
+  const card = container({
+    style: {
+      width: 4,
+      height: 4,
+      backgroundColor: "white",
+      dark: { backgroundColor: "black" },
+    },
+  });
+
+  it("should apply the base style in the light scheme", async () => {
+    const image = await renderer.renderRaw(card, { width: 4, height: 4 });
+
+    expect([...image.data.subarray(0, 4)]).toEqual([255, 255, 255, 255]);
+  });
+
+  it("should apply the dark block in the dark scheme", async () => {
+    const image = await renderer.renderRaw(card, {
+      width: 4,
+      height: 4,
+      colorScheme: "dark",
+    });
+
+    expect([...image.data.subarray(0, 4)]).toEqual([0, 0, 0, 255]);
+  });
+
+  it("should match prefers-color-scheme media queries", async () => {
+    const image = await renderer.renderRaw(
+      container({ className: "card", style: { width: 4, height: 4 } }),
+      {
+        width: 4,
+        height: 4,
+        colorScheme: "dark",
+        stylesheets: [
+          "@media (prefers-color-scheme: dark) { .card { background-color: black } }",
+        ],
+      },
+    );
+
+    expect([...image.data.subarray(0, 4)]).toEqual([0, 0, 0, 255]);
+  });
+});
@@ -0,0 +1,49 @@
+import { describe, expect, it } from "bun:test";
+import { container } from "@takumi-rs/helpers";
+import { Renderer, SharedAssets } from "../index.js";
+
+const broken = new Uint8Array([0x00, 0x01, 0x00, 0x00, 0xff, 0xff]);
+
+describe("Renderer constructor", () => {
+  it("should list every asset that failed to load", () => {
+    const renderer = new Renderer({
+      loadDefaultFonts: false,
+      fonts: [{ name: "Broken", data: broken }, broken],
+      persistentImages: [{ src: "missing.png", data: new Uint8Array([1]) }],
+    });
+
+    expect(renderer.assetLoadFailures).toMatchObject([
+      { kind: "font", index: 0, name: "Broken", code: "FONT_PARSE_ERROR" },
+      { kind: "font", index: 1, code: "FONT_PARSE_ERROR" },
+      { kind: "image", index: 0, name: "missing.png", code: "IMAGE_DECODE_ERROR" },
+    ]);
+  });
+
+  it("should return a renderer with the valid assets loaded", async () => {
+    const photo = await new Renderer().render(
+      container({ style: { width: 10, height: 10, backgroundColor: "red" } }),
+    );
+
+    const renderer = new Renderer({
+      persistentImages: [
+        { src: "photo.png", data: photo },
+        { src: "broken.png", data: new Uint8Array([1]) },
+      ],
+    });
+
+    expect(renderer.assetLoadFailures).toHaveLength(1);
+    expect(() => renderer.extractPalette("photo.png")).not.toThrow();
+  });
+
+  it("should report failures of shared assets", () => {
+    const assets = new SharedAssets({
+      persistentImages: [{ src: "broken.png", data: new Uint8Array([1]) }],
+    });
+
+    expect(assets.assetLoadFailures).toMatchObject([{ kind: "image", name: "broken.png" }]);
+  });
+
+  it("should have no failures when every asset loads", () => {
+    expect(new Renderer().assetLoadFailures).toEqual([]);
+  });
+});
@@ -0,0 +1,45 @@
+import { expect, test } from "bun:test";
+import { container, custom } from "@takumi-rs/helpers";
+import { Renderer } from "../index";
+
+test("draws custom nodes with a registered callback", async () => {
+  const renderer = new Renderer();
+  const sizes: [number, number][] = [];
+
+  renderer.registerCustomDraw("checker", (width, height) => {
+    sizes.push([width, height]);
+
+    const pixels = new Uint8Array(width * height * 4);
+    for (let i = 0; i < pixels.length; i += 4) {
+      pixels.set([255, 0, 0, 255], i);
+    }
+
+    return pixels;
+  });
+
+  const output = await renderer.render(
+    container({
+      children: [custom({ name: "checker", style: { width: 4, height: 2 } })],
+    }),
+    { width: 4, height: 4, format: "raw" },
+  );
+
+  expect(sizes).toEqual([[4, 2]]);
+  expect([...output.subarray(0, 4)]).toEqual([255, 0, 0, 255]);
+  expect([...output.subarray(4 * 4 * 3, 4 * 4 * 3 + 4)]).toEqual([0, 0, 0, 0]);
+});
+
+test("rejects when the callback returns the wrong amount of data", async () => {
+  const renderer = new Renderer();
+
+  renderer.registerCustomDraw("broken", () => new Uint8Array(3));
+
+  expect(
+    renderer.render(
+      container({
+        children: [custom({ name: "broken", style: { width: 2, height: 2 } })],
+      }),
+      { width: 2, height: 2 },
+    ),
+  ).rejects.toThrow("RGBA");
+});
@@ -427,3 +427,79 @@ test("report deserialize error for textDecorationLine with invalid string value"
     "a value of 'underline', 'line-through' or 'overline' or 'none'; also accepts 'initial', 'unset' or 'inherit'.",
   );
 });
+
+test("attach error code to invalid node errors", () => {
+  try {
+    renderer.render(
+      {
+        type: "container",
+        children: [],
+        style: {
+          // @ts-expect-error: invalid type test
+          width: true,
+        },
+      },
+      {
+        width: 100,
+        height: 100,
+      },
+    );
+    expect.unreachable();
+  } catch (error) {
+    expect(error).toMatchObject({ code: "INVALID_NODE", retriable: false });
+  }
+});
+
+test("attach non-retriable error code to undecodable resources", async () => {
+  const promise = renderer.render(
+    {
+      type: "image",
+      src: "https://example.com/broken.png",
+    },
+    {
+      width: 100,
+      height: 100,
+      fetchedResources: [
+        {
+          src: "https://example.com/broken.png",
+          data: new Uint8Array([0, 1, 2, 3]),
+        },
+      ],
+    },
+  );
+
+  await expect(promise).rejects.toMatchObject({
+    code: "IMAGE_DECODE_ERROR",
+    retriable: false,
+  });
+});
+
+test("attach the path of the offending value to invalid node errors", () => {
+  try {
+    renderer.render(
+      {
+        type: "container",
+        children: [
+          { type: "text", text: "ok" },
+          {
+            type: "container",
+            children: [],
+            style: { backgroundColor: "notacolor" },
+          },
+        ],
+      },
+      {
+        width: 100,
+        height: 100,
+      },
+    );
+    expect.unreachable();
+  } catch (error) {
+    expect(error).toMatchObject({
+      code: "INVALID_NODE",
+      path: "children[1].style.backgroundColor",
+      property: "backgroundColor",
+      token: "notacolor",
+    });
+  }
+});
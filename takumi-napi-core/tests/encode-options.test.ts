@@ -0,0 +1,45 @@
+import { describe, expect, it } from "bun:test";
+import { container } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+describe("encoder options", () => {
+  const renderer = new Renderer();
+  const node = container({
+    style: {
+      width: 200,
+      height: 100,
+      backgroundImage: "linear-gradient(90deg, red, blue)",
+    },
+  });
+
+  it("should apply png compression levels", async () => {
+    const stored = await renderer.render(node, {
+      format: "png",
+      png: { compression: "none", filter: "none" },
+    });
+    const small = await renderer.render(node, {
+      format: "png",
+      preset: "small",
+    });
+
+    expect(small.length).toBeLessThan(stored.length);
+  });
+
+  it("should encode lossy webp at full quality when lossless is disabled", async () => {
+    const webp = await renderer.render(node, {
+      format: "webp",
+      webp: { lossless: false, method: 6 },
+    });
+
+    expect(webp.subarray(12, 16).toString()).toBe("VP8 ");
+  });
+
+  it("should reject unsupported avif bit depths", async () => {
+    expect(() =>
+      renderer.render(node, {
+        format: "avif",
+        avif: { bitDepth: 12 as 10 },
+      }),
+    ).toThrow("Invalid AVIF bit depth 12");
+  });
+});
@@ -0,0 +1,31 @@
+import { describe, expect, it } from "bun:test";
+import { Renderer } from "../index.js";
+
+const ubuntu = await Bun.file(
+  new URL("../../assets/fonts/ubuntu/Ubuntu.ttc", import.meta.url),
+).arrayBuffer();
+
+describe("font formats", () => {
+  it("should name the detected format when font data is broken", async () => {
+    const renderer = new Renderer({ loadDefaultFonts: false });
+    const truncated = new Uint8Array([0x00, 0x01, 0x00, 0x00, 0xff, 0xff]);
+
+    await expect(renderer.loadFonts([truncated])).rejects.toMatchObject({
+      code: "FONT_PARSE_ERROR",
+    });
+    expect(() => renderer.loadFontSync(truncated)).toThrow(
+      "Failed to parse TrueType font data",
+    );
+  });
+
+  it("should load a single face of a collection", async () => {
+    const renderer = new Renderer({ loadDefaultFonts: false });
+
+    await expect(
+      renderer.loadFont({ data: ubuntu, faceIndex: 1 }),
+    ).resolves.toBe(1);
+    expect(() =>
+      renderer.loadFontSync({ data: ubuntu, faceIndex: 99 }),
+    ).toThrow("Face index 99 is out of range");
+  });
+});
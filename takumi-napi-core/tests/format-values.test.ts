@@ -0,0 +1,54 @@
+import { describe, expect, it } from "bun:test";
+import { text } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+describe("text node values", () => {
+  const renderer = new Renderer();
+
+  const measureText = async (
+    node: ReturnType<typeof text>,
+    locale?: string,
+  ) => {
+    const result = await renderer.measure(node, {
+      width: 400,
+      height: 100,
+      locale,
+    });
+
+    return result.runs.map((run) => run.text).join("");
+  };
+
+  it("should format numbers like en-US by default", async () => {
+    const node = text({
+      text: "",
+      value: 1234567.891,
+      format: { type: "number", maximumFractionDigits: 1 },
+    });
+
+    expect(await measureText(node)).toBe("1,234,567.9");
+  });
+
+  it("should format numbers for the render locale", async () => {
+    const node = text({ text: "", value: 1234.5 });
+
+    expect(await measureText(node, "de")).toBe("1.234,5");
+  });
+
+  it("should format percentages and dates", async () => {
+    expect(
+      await measureText(
+        text({ text: "", value: 0.256, format: { type: "percent" } }),
+      ),
+    ).toBe("26%");
+    expect(
+      await measureText(
+        text({
+          text: "",
+          value: Date.UTC(2025, 0, 15),
+          format: { type: "date", dateStyle: "long" },
+        }),
+        "fr",
+      ),
+    ).toBe("15 janvier 2025");
+  });
+});
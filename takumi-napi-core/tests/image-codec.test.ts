@@ -0,0 +1,45 @@
+import { describe, expect, it } from "bun:test";
+import { container } from "@takumi-rs/helpers";
+import { decodeImage, encodeImage, Renderer } from "../index.js";
+
+describe("encodeImage / decodeImage", () => {
+  const renderer = new Renderer();
+
+  const node = container({
+    style: { width: 20, height: 10, backgroundColor: "red" },
+  });
+
+  it("should round-trip raw pixels through png", async () => {
+    const raw = await renderer.renderRaw(node, { width: 20, height: 10 });
+    const png = await encodeImage(raw.data, raw.width, raw.height, "png");
+
+    expect(png.subarray(1, 4).toString()).toBe("PNG");
+
+    const decoded = await decodeImage(png);
+
+    expect(decoded.width).toBe(20);
+    expect(decoded.height).toBe(10);
+    expect(Buffer.compare(decoded.data, raw.data)).toBe(0);
+  });
+
+  it("should reject pixels that don't match the dimensions", async () => {
+    expect(() =>
+      encodeImage(new Uint8Array(12), 2, 2, "webp", { quality: 80 }),
+    ).toThrow(expect.objectContaining({ code: "ENCODE_ERROR" }));
+    expect(() =>
+      encodeImage(new Uint8Array(20), 2, 2, "webp", { quality: 80 }),
+    ).toThrow(expect.objectContaining({ code: "ENCODE_ERROR" }));
+  });
+
+  it("should rasterize svgs at their declared size", async () => {
+    const svg = Buffer.from(
+      '<svg xmlns="http://www.w3.org/2000/svg" width="8" height="4"><rect width="8" height="4" fill="blue"/></svg>',
+    );
+    const decoded = await decodeImage(svg);
+
+    expect(decoded.width).toBe(8);
+    expect(decoded.data.subarray(0, 4)).toEqual(
+      Buffer.from([0, 0, 255, 255]),
+    );
+  });
+});
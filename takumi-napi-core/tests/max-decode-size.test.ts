@@ -0,0 +1,19 @@
+import { describe, expect, it } from "bun:test";
+import { container, image } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+describe("ImageSource.maxDecodeSize", () => {
+  it("should keep the encoded size as the intrinsic size", async () => {
+    const renderer = new Renderer();
+    const photo = await renderer.render(
+      container({ style: { width: 400, height: 200, backgroundColor: "red" } }),
+    );
+
+    const measured = await renderer.measure(image({ src: "photo.png" }), {
+      fetchedResources: [{ src: "photo.png", data: photo, maxDecodeSize: 100 }],
+    });
+
+    expect(measured.width).toBe(400);
+    expect(measured.height).toBe(200);
+  });
+});
@@ -1,6 +1,6 @@
 import { describe, expect, it } from "bun:test";
 import { container, text } from "@takumi-rs/helpers";
-import { Renderer } from "../index.js";
+import { diffLayouts, hitTest, Renderer } from "../index.js";
 
 describe("Renderer.measure", () => {
   const renderer = new Renderer();
@@ -23,6 +23,7 @@ describe("Renderer.measure", () => {
       transform: [1, 0, 0, 1, 0, 0],
       children: [],
       runs: [],
+      lines: [],
     });
   });
 
@@ -101,6 +102,62 @@ describe("Renderer.measure", () => {
       transform: [2, 0, 0, 2, 50, 0],
       children: [],
       runs: [],
+      lines: [],
     });
   });
+
+  it("should report changed regions between two layouts", async () => {
+    const box = (width: number) =>
+      container({
+        style: { width: 100, height: 100 },
+        children: [container({ style: { width, height: 10 }, children: [] })],
+      });
+
+    const before = await renderer.measure(box(10));
+    const after = await renderer.measure(box(40));
+
+    expect(diffLayouts(before, before)).toEqual([]);
+    expect(diffLayouts(before, after)).toEqual([
+      { x: 0, y: 0, width: 40, height: 10 },
+    ]);
+  });
+
+  it("should report cap height bounds per line", async () => {
+    const result = await renderer.measure(
+      text({
+        text: "Takumi",
+        style: { display: "flex", fontSize: 100, lineHeight: 1.5 },
+      }),
+    );
+
+    const [line] = result.children[0]?.lines ?? [];
+
+    expect(line).toBeDefined();
+    expect(line?.height).toBe(150);
+    expect(line?.capHeight).toBeGreaterThan(0);
+    expect(line?.capHeight).toBeLessThan(100);
+
+    // The capitals sit between the top of the line box and the baseline.
+    const capTop = (line?.baseline ?? 0) - (line?.capHeight ?? 0);
+    expect(capTop).toBeGreaterThan(line?.y ?? 0);
+    expect(line?.baseline).toBeLessThan((line?.y ?? 0) + 150);
+  });
+
+  it("should hit test a measured layout", async () => {
+    const layout = await renderer.measure(
+      container({
+        style: { width: 100, height: 100 },
+        children: [
+          container({
+            style: { width: 50, height: 50, transform: "translate(25px, 25px)" },
+            children: [],
+          }),
+        ],
+      }),
+    );
+
+    expect(hitTest(layout, 10, 10)).toEqual([]);
+    expect(hitTest(layout, 60, 60)).toEqual([0]);
+    expect(hitTest(layout, 200, 200)).toBeFalsy();
+  });
 });
@@ -0,0 +1,33 @@
+import { describe, expect, it } from "bun:test";
+import { text } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+describe("missingFontPolicy", () => {
+  // No default font has Egyptian hieroglyphs.
+  const node = text({ text: "\u{13000}", style: { fontSize: 40 } });
+  const options = { width: 80, height: 60, format: "raw" as const };
+
+  it("should reject renders when set to error", async () => {
+    const renderer = new Renderer({ missingFontPolicy: "error" });
+
+    await expect(renderer.render(node, options)).rejects.toMatchObject({
+      code: "INVALID_NODE",
+    });
+  });
+
+  it("should let render options override the renderer policy", async () => {
+    const renderer = new Renderer({ missingFontPolicy: "error" });
+
+    const skipped = await renderer.render(node, {
+      ...options,
+      missingFontPolicy: "skip",
+    });
+    const replaced = await renderer.render(node, {
+      ...options,
+      missingFontPolicy: "replace",
+    });
+
+    expect(skipped.every((byte) => byte === 0)).toBe(true);
+    expect(replaced.some((byte) => byte !== 0)).toBe(true);
+  });
+});
@@ -0,0 +1,34 @@
+import { expect, test } from "bun:test";
+import { container } from "@takumi-rs/helpers";
+import { Renderer } from "../index";
+
+const swatch = new TextEncoder().encode(
+  '<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"><rect width="4" height="4" fill="#336699"/></svg>',
+);
+
+test("extracts the dominant color of a persistent image", async () => {
+  const renderer = new Renderer();
+  await renderer.putPersistentImage("swatch", swatch);
+
+  expect(renderer.extractPalette("swatch")).toEqual([[51, 102, 153, 255]]);
+});
+
+test("resolves palette() references in styles", async () => {
+  const renderer = new Renderer();
+  await renderer.putPersistentImage("swatch", swatch);
+
+  const output = await renderer.render(
+    container({
+      style: { width: 2, height: 2, backgroundColor: 'palette("swatch", 0)' },
+    }),
+    { width: 2, height: 2, format: "raw" },
+  );
+
+  expect([...output.subarray(0, 4)]).toEqual([51, 102, 153, 255]);
+});
+
+test("throws for unknown images", () => {
+  const renderer = new Renderer();
+
+  expect(() => renderer.extractPalette("missing")).toThrow();
+});
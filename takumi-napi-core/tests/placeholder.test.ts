@@ -0,0 +1,38 @@
+import { expect, test } from "bun:test";
+import { Renderer } from "../index";
+
+const swatch = new TextEncoder().encode(
+  '<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"><rect width="4" height="4" fill="#336699"/></svg>',
+);
+
+test("generates css placeholders for a persistent image", async () => {
+  const renderer = new Renderer();
+  await renderer.putPersistentImage("swatch", swatch);
+
+  expect(renderer.placeholder("swatch")).toBe("rgb(51, 102, 153)");
+  expect(renderer.placeholder("swatch", { kind: "gradient" })).toBe(
+    "linear-gradient(to bottom, rgb(51, 102, 153), rgb(51, 102, 153))",
+  );
+});
+
+test("generates a blurhash for a persistent image", async () => {
+  const renderer = new Renderer();
+  await renderer.putPersistentImage("swatch", swatch);
+
+  expect(renderer.placeholder("swatch", { kind: "blurhash" })).toBe(
+    "LP5?}kt:fQt:t:kYfQkYfQfQfQfQ",
+  );
+  expect(
+    renderer.placeholder("swatch", {
+      kind: "blurhash",
+      componentsX: 1,
+      componentsY: 1,
+    }),
+  ).toHaveLength(6);
+});
+
+test("throws for unknown images", () => {
+  const renderer = new Renderer();
+
+  expect(() => renderer.placeholder("missing")).toThrow();
+});
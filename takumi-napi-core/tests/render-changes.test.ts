@@ -0,0 +1,47 @@
+import { describe, expect, it } from "bun:test";
+import { container } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+describe("Renderer.renderChanges", () => {
+  const renderer = new Renderer();
+
+  const scene = (color: string) =>
+    container({
+      style: {
+        display: "flex",
+        width: 100,
+        height: 50,
+        backgroundColor: "white",
+      },
+      children: [
+        container({
+          style: { width: 20, height: 10, backgroundColor: color },
+        }),
+      ],
+    });
+
+  it("should report the area that changed since the previous frame", async () => {
+    const previous = await renderer.render(scene("red"), {
+      width: 100,
+      height: 50,
+      format: "raw",
+    });
+
+    const unchanged = await renderer.renderChanges(scene("red"), previous, {
+      width: 100,
+      height: 50,
+    });
+
+    expect(unchanged.regions).toEqual([]);
+    expect(unchanged.patch).toBeUndefined();
+
+    const changed = await renderer.renderChanges(scene("blue"), previous, {
+      width: 100,
+      height: 50,
+    });
+
+    expect(changed.regions).toEqual([{ x: 0, y: 0, width: 20, height: 10 }]);
+    expect(changed.patch?.width).toBe(20);
+    expect(changed.patch?.data.subarray(1, 4).toString()).toBe("PNG");
+  });
+});
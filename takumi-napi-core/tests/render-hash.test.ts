@@ -0,0 +1,24 @@
+import { describe, expect, it } from "bun:test";
+import { container } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+describe("Renderer.renderHash", () => {
+  const renderer = new Renderer();
+
+  const scene = (color: string) =>
+    container({
+      style: { width: 100, height: 50, backgroundColor: color },
+    });
+
+  it("should hash equal scenes equally without rendering", async () => {
+    const options = { width: 100, height: 50 };
+    const first = await renderer.renderHash(scene("red"), options);
+
+    expect(first).toMatch(/^[0-9a-f]{16}$/);
+    expect(await renderer.renderHash(scene("red"), options)).toBe(first);
+    expect(await renderer.renderHash(scene("blue"), options)).not.toBe(first);
+    expect(
+      await renderer.renderHash(scene("red"), { width: 200, height: 50 }),
+    ).not.toBe(first);
+  });
+});
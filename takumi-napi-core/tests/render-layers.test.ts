@@ -0,0 +1,46 @@
+import { describe, expect, it } from "bun:test";
+import { container } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+describe("Renderer.renderLayers", () => {
+  const renderer = new Renderer();
+
+  it("should split the root and its children into cropped layers", async () => {
+    const node = container({
+      style: {
+        display: "flex",
+        width: 100,
+        height: 50,
+        backgroundColor: "white",
+      },
+      children: [
+        container({
+          style: { width: 20, height: 10, backgroundColor: "red" },
+        }),
+        container({
+          style: { width: 30, height: 15, backgroundColor: "blue" },
+        }),
+      ],
+    });
+
+    const result = await renderer.renderLayers(node, { width: 100, height: 50 });
+
+    expect(result.width).toBe(100);
+    expect(result.height).toBe(50);
+    expect(
+      result.layers.map(({ name, path, x, y, width, height }) => ({
+        name,
+        path,
+        x,
+        y,
+        width,
+        height,
+      })),
+    ).toEqual([
+      { name: "layer-0", path: [], x: 0, y: 0, width: 100, height: 50 },
+      { name: "layer-1", path: [0], x: 0, y: 0, width: 20, height: 10 },
+      { name: "layer-2", path: [1], x: 20, y: 0, width: 30, height: 15 },
+    ]);
+    expect(result.layers[0]?.data.subarray(1, 4).toString()).toBe("PNG");
+  });
+});
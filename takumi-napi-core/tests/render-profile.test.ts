@@ -0,0 +1,32 @@
+import { describe, expect, it } from "bun:test";
+import { container, text } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+describe("Renderer.renderWithProfile", () => {
+  const renderer = new Renderer();
+
+  it("should time every node alongside the image", async () => {
+    const node = container({
+      style: { display: "flex", width: 100, height: 50 },
+      children: [
+        text({ tagName: "p", text: "Hello" }),
+        container({ id: "box", style: { width: 20, height: 10 } }),
+      ],
+    });
+
+    const { image, profile } = await renderer.renderWithProfile(node, {
+      width: 100,
+      height: 50,
+    });
+
+    expect(image.subarray(1, 4).toString()).toBe("PNG");
+    expect(profile.totalMs).toBeGreaterThanOrEqual(profile.drawMs);
+    expect(profile.root.path).toEqual([]);
+    expect(
+      profile.root.children.map(({ tagName, id, path }) => ({ tagName, id, path })),
+    ).toEqual([
+      { tagName: "p", id: undefined, path: [0] },
+      { tagName: undefined, id: "box", path: [1] },
+    ]);
+  });
+});
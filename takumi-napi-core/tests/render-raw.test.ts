@@ -0,0 +1,26 @@
+import { describe, expect, it } from "bun:test";
+import { container } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+describe("Renderer.renderRaw", () => {
+  const renderer = new Renderer();
+
+  const tall = container({
+    style: { width: 40, height: 120, backgroundColor: "red" },
+  });
+
+  it("should return the measured height with the pixels", async () => {
+    const image = await renderer.renderRaw(tall, { width: 40 });
+
+    expect(image.width).toBe(40);
+    expect(image.height).toBe(120);
+    expect(image.data.length).toBe(40 * 120 * 4);
+  });
+
+  it("should clamp auto height to maxHeight", async () => {
+    const image = await renderer.renderRaw(tall, { width: 40, maxHeight: 50 });
+
+    expect(image.height).toBe(50);
+    expect(image.data.length).toBe(40 * 50 * 4);
+  });
+});
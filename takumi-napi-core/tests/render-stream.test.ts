@@ -0,0 +1,47 @@
+import { expect, test } from "bun:test";
+import { container } from "@takumi-rs/helpers";
+import { Renderer } from "../index";
+
+const node = container({
+  style: { width: "100%", height: "100%", backgroundColor: "red" },
+});
+
+test("streams an encoded png", async () => {
+  const renderer = new Renderer();
+  const chunks: Buffer[] = [];
+
+  await renderer.renderStream(node, (chunk) => chunks.push(chunk), {
+    width: 1200,
+    height: 630,
+    format: "png",
+  });
+
+  const streamed = Buffer.concat(chunks);
+
+  expect(chunks.length).toBeGreaterThan(0);
+  for (const chunk of chunks) {
+    expect(chunk.length).toBeLessThanOrEqual(64 * 1024);
+  }
+  expect([...streamed.subarray(1, 4)]).toEqual([0x50, 0x4e, 0x47]);
+  expect([...streamed.subarray(-8, -4)]).toEqual([0x49, 0x45, 0x4e, 0x44]);
+});
+
+test("streams raw pixels in multiple chunks", async () => {
+  const renderer = new Renderer();
+  const chunks: Buffer[] = [];
+
+  await renderer.renderStream(node, (chunk) => chunks.push(chunk), {
+    width: 1200,
+    height: 630,
+    format: "raw",
+  });
+
+  const streamed = Buffer.concat(chunks);
+
+  expect(chunks.length).toBeGreaterThan(1);
+  for (const chunk of chunks) {
+    expect(chunk.length).toBeLessThanOrEqual(64 * 1024);
+  }
+  expect(streamed.length).toBe(1200 * 630 * 4);
+  expect([...streamed.subarray(0, 4)]).toEqual([255, 0, 0, 255]);
+});
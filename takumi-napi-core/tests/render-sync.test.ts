@@ -0,0 +1,22 @@
+import { describe, expect, it } from "bun:test";
+import { container } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+describe("Renderer.renderSync", () => {
+  const renderer = new Renderer();
+  const node = container({
+    style: { width: 40, height: 20, backgroundColor: "red" },
+  });
+
+  it("should match the async render", async () => {
+    const options = { width: 40, height: 20, format: "png" } as const;
+
+    expect(renderer.renderSync(node, options)).toEqual(
+      await renderer.render(node, options),
+    );
+  });
+
+  it("should throw coded errors", () => {
+    expect(() => renderer.renderSync({ type: "unknown" } as never)).toThrow();
+  });
+});
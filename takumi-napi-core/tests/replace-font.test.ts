@@ -0,0 +1,58 @@
+import { describe, expect, it } from "bun:test";
+import { text } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+const geist = await Bun.file(
+  new URL("../../assets/fonts/geist/Geist[wght].woff2", import.meta.url),
+).arrayBuffer();
+const archivo = await Bun.file(
+  new URL(
+    "../../assets/fonts/archivo/Archivo-VariableFont_wdth,wght.ttf",
+    import.meta.url,
+  ),
+).arrayBuffer();
+
+describe("font replacement", () => {
+  const node = text({
+    text: "Brand",
+    style: { fontFamily: "Brand", fontSize: 32 },
+  });
+  const options = {
+    width: 200,
+    height: 60,
+    format: "raw" as const,
+    missingFontPolicy: "error" as const,
+  };
+
+  it("should unload a family", async () => {
+    const renderer = new Renderer({ loadDefaultFonts: false });
+    await renderer.loadFont({ name: "Brand", data: geist });
+
+    expect(renderer.unloadFont("Brand")).toBe(true);
+    expect(renderer.unloadFont("Brand")).toBe(false);
+    await expect(renderer.render(node, options)).rejects.toMatchObject({
+      code: "INVALID_NODE",
+    });
+  });
+
+  it("should swap the fonts of a family", async () => {
+    const renderer = new Renderer({ loadDefaultFonts: false });
+    await renderer.loadFont({ name: "Brand", data: geist });
+
+    const before = await renderer.render(node, options);
+    await renderer.replaceFont("Brand", { data: archivo });
+    const after = await renderer.render(node, options);
+
+    expect(after.equals(before)).toBe(false);
+  });
+
+  it("should keep the current fonts when the replacement fails to load", async () => {
+    const renderer = new Renderer({ loadDefaultFonts: false });
+    await renderer.loadFont({ name: "Brand", data: geist });
+
+    await expect(
+      renderer.replaceFont("Brand", { data: new Uint8Array(16) }),
+    ).rejects.toMatchObject({ code: "FONT_PARSE_ERROR" });
+    expect(await renderer.render(node, options)).toBeInstanceOf(Buffer);
+  });
+});
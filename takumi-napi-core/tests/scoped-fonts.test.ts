@@ -0,0 +1,46 @@
+import { describe, expect, it } from "bun:test";
+import { text } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+const geist = await Bun.file(
+  new URL("../../assets/fonts/geist/Geist[wght].woff2", import.meta.url),
+).arrayBuffer();
+
+describe("render-scoped fonts", () => {
+  const node = text({
+    text: "Tenant",
+    style: { fontFamily: "Tenant", fontSize: 32 },
+  });
+  const options = {
+    width: 200,
+    height: 60,
+    format: "raw" as const,
+    missingFontPolicy: "error" as const,
+  };
+
+  it("should only expose fonts to the render they were passed to", async () => {
+    const renderer = new Renderer({ loadDefaultFonts: false });
+
+    await expect(
+      renderer.render(node, {
+        ...options,
+        fonts: [{ name: "Tenant", data: geist }],
+      }),
+    ).resolves.toBeInstanceOf(Buffer);
+    await expect(renderer.render(node, options)).rejects.toMatchObject({
+      code: "INVALID_NODE",
+    });
+    expect(renderer.cacheStats().glyphs.entries).toBe(0);
+  });
+
+  it("should reject renders with broken scoped fonts", async () => {
+    const renderer = new Renderer({ loadDefaultFonts: false });
+
+    await expect(
+      renderer.render(node, {
+        ...options,
+        fonts: [new Uint8Array([0x00, 0x01, 0x00, 0x00, 0xff, 0xff])],
+      }),
+    ).rejects.toMatchObject({ code: "FONT_PARSE_ERROR" });
+  });
+});
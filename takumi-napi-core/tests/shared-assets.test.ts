@@ -0,0 +1,34 @@
+import { describe, expect, it } from "bun:test";
+import { container, image } from "@takumi-rs/helpers";
+import { Renderer, SharedAssets } from "../index.js";
+
+describe("SharedAssets", () => {
+  it("should share persistent images between renderers", async () => {
+    const assets = new SharedAssets();
+    const first = new Renderer({ sharedAssets: assets });
+    const second = new Renderer({ sharedAssets: assets });
+
+    const photo = await first.render(
+      container({ style: { width: 40, height: 20, backgroundColor: "red" } }),
+    );
+    await first.putPersistentImage("photo.png", photo);
+
+    const measured = await second.measure(image({ src: "photo.png" }));
+
+    expect(measured.width).toBe(40);
+    expect(measured.height).toBe(20);
+  });
+
+  it("should keep images of unshared renderers separate", async () => {
+    const assets = new SharedAssets();
+    const shared = new Renderer({ sharedAssets: assets });
+    const separate = new Renderer();
+
+    const photo = await separate.render(
+      container({ style: { width: 40, height: 20, backgroundColor: "red" } }),
+    );
+    await separate.putPersistentImage("photo.png", photo);
+
+    expect(() => shared.extractPalette("photo.png")).toThrow();
+  });
+});
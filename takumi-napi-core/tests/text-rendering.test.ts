@@ -0,0 +1,29 @@
+import { describe, expect, it } from "bun:test";
+import { text } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+describe("textRendering", () => {
+  const node = text({ text: "Small text", style: { fontSize: 11 } });
+  const options = { width: 120, height: 24, format: "raw" as const };
+
+  const coverage = (pixels: Uint8Array) =>
+    pixels.reduce((sum, byte, index) => (index % 4 === 3 ? sum + byte : sum), 0);
+
+  it("should darken antialiased edges with a gamma above one", async () => {
+    const linear = await new Renderer().render(node, options);
+    const corrected = await new Renderer({
+      textRendering: { gamma: 2.2 },
+    }).render(node, options);
+
+    expect(coverage(corrected)).toBeGreaterThan(coverage(linear));
+  });
+
+  it("should snap glyphs to whole pixels", async () => {
+    const exact = await new Renderer().render(node, options);
+    const snapped = await new Renderer({
+      textRendering: { hinting: true, subpixelPositions: 1 },
+    }).render(node, options);
+
+    expect(snapped).not.toEqual(exact);
+  });
+});
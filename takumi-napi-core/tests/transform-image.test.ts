@@ -0,0 +1,35 @@
+import { describe, expect, it } from "bun:test";
+import { readFile } from "node:fs/promises";
+import { decodeImage, transformImage } from "../index.js";
+
+const photo = await readFile("../assets/images/yeecord.png");
+
+describe("transformImage", () => {
+  it("should keep the aspect ratio when only the width is set", async () => {
+    const source = await decodeImage(photo);
+    const raw = await transformImage(photo, { width: 64, format: "raw" });
+
+    expect(raw.length).toBe(
+      64 * Math.round((64 * source.height) / source.width) * 4,
+    );
+  });
+
+  it("should encode into the requested box", async () => {
+    const webp = await transformImage(photo, {
+      width: 40,
+      height: 20,
+      fit: "cover",
+      format: "webp",
+    });
+    const decoded = await decodeImage(webp);
+
+    expect(decoded.width).toBe(40);
+    expect(decoded.height).toBe(20);
+  });
+
+  it("should reject unknown fit values", () => {
+    expect(() =>
+      transformImage(photo, { width: 40, fit: "stretch" as "fill" }),
+    ).toThrow("Invalid fit");
+  });
+});
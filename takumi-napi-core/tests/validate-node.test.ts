@@ -0,0 +1,40 @@
+import { expect, test } from "bun:test";
+import { container, image, text } from "@takumi-rs/helpers";
+import { validateNode } from "../index";
+
+test("returns no issues for a valid node", () => {
+  const node = container({ tw: "flex", children: [text("ok")] });
+
+  expect(validateNode(node)).toEqual([]);
+});
+
+test("collects every issue with its path", () => {
+  const issues = validateNode(
+    container({
+      children: [
+        text("ok"),
+        container({
+          children: [image({ src: "", tw: "mt-4 not-a-class" })],
+        }),
+      ],
+    }),
+    { maxNodes: 2 },
+  );
+
+  expect(issues).toEqual([
+    {
+      path: "children[1].children[0]",
+      message: "unsupported tailwind class 'not-a-class'",
+    },
+    { path: "children[1].children[0]", message: "image source is empty" },
+    { path: "", message: "node count 4 exceeds the limit of 2" },
+  ]);
+});
+
+test("reports deserialization failures as a single issue", () => {
+  // @ts-expect-error: invalid node type test
+  const issues = validateNode({ type: "unknown" });
+
+  expect(issues).toHaveLength(1);
+  expect(issues[0]?.path).toBe("");
+});
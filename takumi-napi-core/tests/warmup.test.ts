@@ -0,0 +1,17 @@
+import { expect, test } from "bun:test";
+import { text } from "@takumi-rs/helpers";
+import { Renderer } from "../index.js";
+
+test("warmup shapes texts without changing later layouts", async () => {
+  const node = text({ text: "Hello, world!", style: { fontSize: 48 } });
+
+  const cold = await new Renderer().measure(node);
+
+  const renderer = new Renderer();
+  await renderer.warmup({
+    texts: ["Hello, world!"],
+    styles: [{ fontSize: 48 }, { fontSize: 24, fontWeight: 700 }],
+  });
+
+  expect(await renderer.measure(node)).toEqual(cold);
+});
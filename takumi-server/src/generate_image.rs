@@ -29,12 +29,15 @@ pub async fn generate_image_handler(
   Query(query): Query<GenerateImageQuery>,
   State(state): AxumState,
 ) -> AxumResult<Response> {
-  let root_node: Node = from_str(&query.payload).map_err(|err| {
-    (
-      StatusCode::BAD_REQUEST,
-      format!("Failed to parse node: {err}"),
-    )
-  })?;
+  let root_node = from_str(&query.payload)
+    .map_err(|err| err.to_string())
+    .and_then(|value| Node::from_json_value(&value).map_err(|err| err.to_string()))
+    .map_err(|err| {
+      (
+        StatusCode::BAD_REQUEST,
+        format!("Failed to parse node: {err}"),
+      )
+    })?;
 
   let format = query.format.unwrap_or(ImageOutputFormat::WebP);
 
@@ -1,10 +1,16 @@
-import type { Node } from "@takumi-rs/helpers";
+import type { Node, NodeMetadata } from "@takumi-rs/helpers";
 
 export {
+  AvatarGroupNode,
   ContainerNode,
+  CustomNode,
   ImageNode,
+  MapMarker,
+  MapNode,
+  MarkdownNode,
   Node,
   NodeMetadata,
+  SvgNode,
   TextNode,
 } from "@takumi-rs/helpers";
 
@@ -21,15 +27,68 @@ export type RenderOptions = {
    * The height of the image. If not provided, the height will be automatically calculated based on the content.
    */
   height?: number;
+  /**
+   * The largest width an auto-sized width can grow to, wider content wraps to fit. Ignored when `width` is set.
+   */
+  maxWidth?: number;
+  /**
+   * The largest height an auto-sized height can grow to, content below it is cut off. Ignored when `height` is set.
+   */
+  maxHeight?: number;
   /**
    * The format of the image.
    * @default "png"
    */
-  format?: "png" | "jpeg" | "webp" | "raw";
+  format?: OutputFormat;
   /**
-   * The quality of JPEG format (0-100).
+   * The quality of JPEG, WebP and AVIF formats (0-100).
    */
   quality?: number;
+  /**
+   * How much encoding time to trade for smaller files, for encoder options that aren't set.
+   * @default "balanced"
+   */
+  preset?: "fast" | "balanced" | "small";
+  /**
+   * WebP encoder options.
+   */
+  webp?: {
+    /**
+     * Whether to encode losslessly. The WASM encoder is always lossless.
+     */
+    lossless?: boolean;
+    /**
+     * Compression method from 0 (fastest) to 6 (smallest files). Ignored by the WASM encoder.
+     */
+    method?: number;
+  };
+  /**
+   * AVIF encoder options.
+   */
+  avif?: {
+    /**
+     * Encoder speed from 1 (slowest, smallest files) to 10 (fastest).
+     */
+    speed?: number;
+    /**
+     * Bits per channel. 10 bits reduce banding in gradients.
+     * @default 8
+     */
+    bitDepth?: 8 | 10;
+  };
+  /**
+   * PNG encoder options.
+   */
+  png?: {
+    /**
+     * Deflate compression level.
+     */
+    compression?: "none" | "fastest" | "fast" | "balanced" | "high";
+    /**
+     * Row filter strategy. Picked by the compression level when unset.
+     */
+    filter?: "none" | "sub" | "up" | "avg" | "paeth" | "adaptive" | "min-entropy";
+  };
   /**
    * The resources fetched externally. You should collect the fetch tasks first using `extractResourceUrls` and then pass the resources here.
    */
@@ -51,15 +110,44 @@ export type RenderOptions = {
    * @default 1.0
    */
   devicePixelRatio?: number;
+  /**
+   * The color scheme that selects `dark` and `light` style blocks and matches
+   * `prefers-color-scheme` media queries.
+   * @default "light"
+   */
+  colorScheme?: ColorScheme;
   /**
    * The animation timeline time in milliseconds.
    */
   timeMs?: number;
+  /**
+   * The Unix timestamp in milliseconds that templates see as the current time, for reproducible renders.
+   */
+  now?: number;
+  /**
+   * The seed for random variation in templates. The same seed always renders the same image.
+   * @default 0
+   */
+  seed?: number;
+  /**
+   * The BCP 47 locale that text node `value`s are formatted for. The WebAssembly build
+   * ships without locale data, so every locale is formatted like `en-US`.
+   * @default "en-US"
+   */
+  locale?: string;
   /**
    * The output dithering algorithm.
    * @default "none"
    */
   dithering?: "none" | "ordered-bayer" | "floyd-steinberg";
+  /**
+   * What happens to text no loaded font covers, overriding the renderer's `missingFontPolicy`.
+   */
+  missingFontPolicy?: MissingFontPolicy;
+  /**
+   * Fonts visible to this render only, on top of the renderer's fonts. They are released once the render finishes.
+   */
+  fonts?: Font[];
 };
 
 export type RenderAnimationOptions = {
@@ -71,6 +159,10 @@ export type RenderAnimationOptions = {
    * The quality of WebP format (0-100). Ignored for APNG and GIF.
    */
   quality?: number;
+  /**
+   * The CSS color that GIF frames are composited onto, so semi-transparent pixels blend instead of being thresholded. Ignored for WebP and APNG.
+   */
+  matte?: string;
   /**
    * The resources fetched externally. You should collect the fetch tasks first using `extractResourceUrls` and then pass the resources here.
    */
@@ -85,6 +177,12 @@ export type RenderAnimationOptions = {
    * @default 1.0
    */
   devicePixelRatio?: number;
+  /**
+   * The color scheme that selects `dark` and `light` style blocks and matches
+   * `prefers-color-scheme` media queries.
+   * @default "light"
+   */
+  colorScheme?: ColorScheme;
   /**
    * Frames per second for timeline sampling.
    */
@@ -99,6 +197,10 @@ export type EncodeFramesOptions = {
    * The quality of WebP format (0-100). Ignored for APNG and GIF.
    */
   quality?: number;
+  /**
+   * The CSS color that GIF frames are composited onto, so semi-transparent pixels blend instead of being thresholded. Ignored for WebP and APNG.
+   */
+  matte?: string;
   /**
    * The resources fetched externally. You should collect the fetch tasks first using `extractResourceUrls` and then pass the resources here.
    */
@@ -113,6 +215,12 @@ export type EncodeFramesOptions = {
    * @default 1.0
    */
   devicePixelRatio?: number;
+  /**
+   * The color scheme that selects `dark` and `light` style blocks and matches
+   * `prefers-color-scheme` media queries.
+   * @default "light"
+   */
+  colorScheme?: ColorScheme;
 };
 
 export type FontDetails = {
@@ -120,11 +228,23 @@ export type FontDetails = {
   data: ByteBuf;
   weight?: number;
   style?: "normal" | "italic" | "oblique";
+  /**
+   * The family whose fallback chain this font is appended to, e.g. `"Inter"`.
+   */
+  fallbackFor?: string;
+  /**
+   * Loads only the face at this index of a TrueType Collection (`.ttc`), instead of every face in it.
+   */
+  faceIndex?: number;
 };
 
 export type ImageSource = {
   src: string;
   data: ByteBuf;
+  /**
+   * Shrinks bitmaps whose longer side exceeds this many pixels while decoding, keeping only the reduced image in memory. The intrinsic size stays that of the encoded image.
+   */
+  maxDecodeSize?: number;
 };
 
 export type KeyframeRule = {
@@ -148,6 +268,116 @@ export type ConstructRendererOptions = {
    * The fonts being used.
    */
   fonts?: Font[];
+  /**
+   * Maximum bytes of scaled glyphs kept between renders, 16 MiB by default.
+   * `0` disables the glyph cache.
+   */
+  glyphCacheBudget?: number;
+  /**
+   * Maximum bytes of decoded fetched images kept between renders, 32 MiB by default.
+   * Identical bytes are decoded once even when fetched from different URLs.
+   * `0` disables the image cache.
+   */
+  imageCacheBudget?: number;
+  /**
+   * What happens to text no loaded font covers. Renders can override it.
+   * @default "notdef"
+   */
+  missingFontPolicy?: MissingFontPolicy;
+};
+
+/**
+ * - `notdef`: draw the font's `.notdef` glyph, usually a box or nothing.
+ * - `error`: reject the render with an `INVALID_NODE` error.
+ * - `skip`: leave the space of missing characters empty.
+ * - `replace`: draw a hollow box in the text color for every missing character.
+ */
+export type MissingFontPolicy = "notdef" | "error" | "skip" | "replace";
+
+export type ColorScheme = "light" | "dark";
+
+export type CacheStats = {
+  glyphs: {
+    /**
+     * Number of cached glyphs.
+     */
+    entries: number;
+    /**
+     * Estimated memory held by the cached glyphs, in bytes.
+     */
+    bytes: number;
+    /**
+     * Maximum number of bytes the cache may hold.
+     */
+    budget: number;
+    /**
+     * Glyph lookups served from the cache.
+     */
+    hits: number;
+    /**
+     * Glyph lookups that had to scale the glyph.
+     */
+    misses: number;
+    /**
+     * Glyphs dropped to stay within the budget.
+     */
+    evictions: number;
+  };
+  images: {
+    /**
+     * Number of cached images.
+     */
+    entries: number;
+    /**
+     * Estimated memory held by the decoded images, in bytes.
+     */
+    bytes: number;
+    /**
+     * Maximum number of bytes the cache may hold.
+     */
+    budget: number;
+    /**
+     * Decodes served from the cache.
+     */
+    hits: number;
+    /**
+     * Decodes that had to decode the bytes.
+     */
+    misses: number;
+    /**
+     * Images dropped to stay within the budget.
+     */
+    evictions: number;
+  };
+};
+
+export type PlaceholderOptions = {
+  /**
+   * `solid` returns the dominant CSS color, `gradient` a top to bottom `linear-gradient()` and `blurhash` a blurhash string.
+   * @default "solid"
+   */
+  kind?: "solid" | "gradient" | "blurhash";
+  /**
+   * The number of horizontal blurhash components, from 1 to 9.
+   * @default 4
+   */
+  componentsX?: number;
+  /**
+   * The number of vertical blurhash components, from 1 to 9.
+   * @default 3
+   */
+  componentsY?: number;
+};
+
+export type WarmupOptions = {
+  /**
+   * The strings to shape.
+   */
+  texts: string[];
+  /**
+   * The styles to shape every text with. The default style is used when omitted.
+   */
+  styles?: NonNullable<NodeMetadata["style"]>[];
 };
 
 export type MeasuredTextRun = {
@@ -158,12 +388,104 @@ export type MeasuredTextRun = {
   height: number;
 };
 
+export type MeasuredTextLine = {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  baseline: number;
+  /**
+   * The largest cap height among the text runs on the line, `0` when it has none.
+   * Capitals are optically centered at `baseline - capHeight / 2`.
+   */
+  capHeight: number;
+};
+
 export type MeasuredNode = {
   width: number;
   height: number;
   transform: [number, number, number, number, number, number];
   children: MeasuredNode[];
   runs: MeasuredTextRun[];
+  lines: MeasuredTextLine[];
+};
+
+export type GlyphOutline = {
+  glyphId: number;
+  /**
+   * The transform from glyph space to output pixels.
+   */
+  transform: [number, number, number, number, number, number];
+  /**
+   * SVG path data of the glyph in pixels, with the origin on the baseline and y pointing down.
+   */
+  path: string;
+  color: [number, number, number, number];
+};
+
+export type RenderedLayer = {
+  /**
+   * The node's `id`, falling back to its tag name and then to `layer-<index>`.
+   */
+  name: string;
+  /**
+   * Child indices leading from the root to the node the layer was painted from.
+   */
+  path: number[];
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  data: Uint8Array;
+};
+
+export type RenderedLayers = {
+  width: number;
+  height: number;
+  /**
+   * The layers in paint order, from bottom to top.
+   */
+  layers: RenderedLayer[];
+};
+
+export type RawImage = {
+  width: number;
+  height: number;
+  data: Uint8Array;
+};
+
+export type CanvasImageData = {
+  width: number;
+  height: number;
+  data: Uint8ClampedArray;
+};
+
+export type RenderedPatch = {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  data: Uint8Array;
+};
+
+export type RenderedChanges = {
+  width: number;
+  height: number;
+  /**
+   * Areas whose pixels differ from the previous frame, empty when nothing changed.
+   */
+  regions: LayoutRegion[];
+  /**
+   * The changed area to draw over the previous frame, absent when nothing changed.
+   */
+  patch?: RenderedPatch;
+};
+
+export type LayoutRegion = {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
 };
 
 export type AnimationFrameSource = {
@@ -175,3 +497,88 @@ export type AnimationSceneSource = {
   node: Node;
   durationMs: number;
 };
+
+export type ValidateNodeOptions = {
+  /**
+   * Maximum nesting depth, where the root node has depth 0.
+   */
+  maxDepth?: number;
+  /**
+   * Maximum total number of nodes in the tree.
+   */
+  maxNodes?: number;
+};
+
+export type OutputFormat = "png" | "jpeg" | "webp" | "avif" | "raw";
+
+export type EncodeImageOptions = {
+  /**
+   * The quality of JPEG, WebP and AVIF formats (0-100).
+   */
+  quality?: number;
+};
+
+export type TransformImageOptions = {
+  /**
+   * The output width in pixels. Derived from the aspect ratio when only `height` is set.
+   */
+  width?: number;
+  /**
+   * The output height in pixels. Derived from the aspect ratio when only `width` is set.
+   */
+  height?: number;
+  /**
+   * How the image fits the output box when both `width` and `height` are set, like CSS `object-fit`.
+   * @default "fill"
+   */
+  fit?: "fill" | "contain" | "cover" | "scale-down" | "none";
+  /**
+   * The output format.
+   * @default "png"
+   */
+  format?: OutputFormat;
+  /**
+   * The quality of JPEG, WebP and AVIF formats (0-100).
+   */
+  quality?: number;
+};
+
+export type NodeValidationIssue = {
+  /**
+   * Location of the node, e.g. `children[2].children[0]`. Empty for the root.
+   */
+  path: string;
+  message: string;
+};
+
+/**
+ * Machine readable classification attached to errors thrown by the renderer.
+ */
+export type ErrorCode =
+  | "RESOURCE_FETCH_FAILED"
+  | "IMAGE_DECODE_ERROR"
+  | "FONT_PARSE_ERROR"
+  | "INVALID_NODE"
+  | "ENCODE_ERROR"
+  | "ABORTED";
+
+export type TakumiError = Error & {
+  code: ErrorCode;
+  /**
+   * Whether retrying the same request may succeed.
+   */
+  retriable: boolean;
+  /**
+   * Location of the offending value for `INVALID_NODE` errors, e.g. `children[2].style.background`.
+   * Empty for the root node.
+   */
+  path?: string;
+  /**
+   * The style property that failed to parse, if any.
+   */
+  property?: string | null;
+  /**
+   * The token the CSS parser stopped at, if any.
+   */
+  token?: string | null;
+};
@@ -1,9 +1,27 @@
 //! Helper functions and utilities for the WebAssembly bindings.
 
-use crate::model::NodeType;
-use serde_wasm_bindgen::from_value;
-use std::fmt::Display;
-use takumi::{layout::node::Node, resources::task::FetchTaskCollection};
+use crate::model::{
+  EncodeImageOptions, EncodeImageOptionsType, LayoutRegionsType, MeasuredNodeType, NodeType,
+  NodeValidationIssue, NodeValidationIssuesType, OutputFormat, OutputFormatType, RawImage,
+  RawImageType, TransformImageOptions, TransformImageOptionsType, ValidateNodeOptions,
+  ValidateNodeOptionsType,
+};
+use serde_bytes::ByteBuf;
+use serde_wasm_bindgen::{from_value, to_value};
+use std::{borrow::Cow, fmt::Display};
+use takumi::{
+  ErrorCode,
+  image::RgbaImage,
+  layout::{
+    node::Node,
+    style::{FromCss, ObjectFit},
+  },
+  rendering::{
+    EncodeOptions, MeasuredNode, TransformImageOptions as CoreTransformImageOptions,
+    rgba_image_from_raw, transform_image, write_image_with_options,
+  },
+  resources::{image::ImageSource, task::FetchTaskCollection},
+};
 use wasm_bindgen::prelude::*;
 
 /// Maps any error to a JavaScript Error object.
@@ -11,13 +29,69 @@ pub fn map_error<E: Display>(err: E) -> js_sys::Error {
   js_sys::Error::new(&err.to_string())
 }
 
+/// Maps any error to a JavaScript Error object with `code` and `retriable` properties.
+pub fn coded_error<E: Display>(code: ErrorCode, err: E) -> js_sys::Error {
+  let error = map_error(err);
+
+  // Setting properties on a freshly created `Error` object can't fail.
+  let _ = js_sys::Reflect::set(&error, &"code".into(), &code.as_str().into());
+  let _ = js_sys::Reflect::set(&error, &"retriable".into(), &code.is_retriable().into());
+
+  error
+}
+
+/// Maps a core error to a JavaScript Error object, attaching its [`ErrorCode`].
+pub fn map_takumi_error<E: Into<takumi::Error>>(err: E) -> js_sys::Error {
+  let err = err.into();
+  coded_error(err.code(), err)
+}
+
+/// Deserializes a node tree, throwing an `INVALID_NODE` error with the `path`, `property` and
+/// `token` of the offending value attached.
+pub fn deserialize_node(node: JsValue) -> JsResult<Node> {
+  from_value(node.clone()).map_err(|err| {
+    let error = coded_error(ErrorCode::InvalidNode, err);
+
+    let located = from_value::<serde_json::Value>(node)
+      .ok()
+      .and_then(|value| Node::locate_deserialize_error(&value));
+
+    if let Some(located) = located {
+      // Setting properties on a freshly created `Error` object can't fail.
+      let _ = js_sys::Reflect::set(&error, &"path".into(), &located.path.into());
+      let _ = js_sys::Reflect::set(&error, &"property".into(), &located.property.into());
+      let _ = js_sys::Reflect::set(&error, &"token".into(), &located.token.into());
+    }
+
+    error
+  })
+}
+
 /// Type alias for JavaScript result.
 pub type JsResult<T> = Result<T, js_sys::Error>;
 
+/// Encodes an image into `format`, returning the raw pixels for [`OutputFormat::Raw`].
+pub(crate) fn encode_image(
+  image: RgbaImage,
+  format: OutputFormat,
+  options: EncodeOptions,
+) -> JsResult<Vec<u8>> {
+  if format == OutputFormat::Raw {
+    return Ok(image.into_raw());
+  }
+
+  let mut buffer = Vec::new();
+
+  write_image_with_options(Cow::Owned(image), &mut buffer, format.into(), options)
+    .map_err(map_takumi_error)?;
+
+  Ok(buffer)
+}
+
 /// Collects the fetch task urls from the node.
 #[wasm_bindgen(js_name = extractResourceUrls)]
 pub fn extract_resource_urls(node: NodeType) -> JsResult<Vec<String>> {
-  let node: Node = from_value(node.into()).map_err(map_error)?;
+  let node = deserialize_node(node.into())?;
 
   let mut collection = FetchTaskCollection::default();
 
@@ -32,3 +106,153 @@ pub fn extract_resource_urls(node: NodeType) -> JsResult<Vec<String>> {
       .collect(),
   )
 }
+
+/// Hashes the node tree with equivalent style spellings normalized, as a hex string.
+///
+/// Equal hashes mean the trees are equivalent. Options like the viewport, stylesheets, fonts and
+/// image contents aren't covered, use `Renderer.renderHash` for those.
+#[wasm_bindgen(js_name = canonicalHash)]
+pub fn canonical_hash(node: NodeType) -> JsResult<String> {
+  let node = deserialize_node(node.into())?;
+
+  Ok(format!("{:016x}", node.canonical_hash()))
+}
+
+/// Compares two layouts returned by `measure` and returns the regions of the output that differ.
+///
+/// Only layout changes (size, position, transform and text) are detected, paint-only changes are not.
+#[wasm_bindgen(js_name = diffLayouts)]
+pub fn diff_layouts(
+  before: MeasuredNodeType,
+  after: MeasuredNodeType,
+) -> JsResult<LayoutRegionsType> {
+  let before: MeasuredNode = from_value(before.into()).map_err(map_error)?;
+  let after: MeasuredNode = from_value(after.into()).map_err(map_error)?;
+
+  let regions = takumi::rendering::diff_layouts(&before, &after);
+
+  Ok(to_value(&regions).map_err(map_error)?.into())
+}
+
+/// Returns the child index path to the topmost node of a `measure` result at the given point.
+///
+/// Returns `undefined` when the point doesn't hit any node.
+#[wasm_bindgen(js_name = hitTest)]
+pub fn hit_test(layout: MeasuredNodeType, x: f32, y: f32) -> JsResult<Option<Vec<u32>>> {
+  let layout: MeasuredNode = from_value(layout.into()).map_err(map_error)?;
+
+  Ok(
+    layout
+      .hit_test(x, y)
+      .map(|path| path.into_iter().map(|index| index as u32).collect()),
+  )
+}
+
+/// Validates the node tree without rendering, returning every problem found.
+///
+/// A node that fails to deserialize is reported as a single issue at the root.
+#[wasm_bindgen(js_name = validateNode)]
+pub fn validate_node(
+  node: NodeType,
+  options: Option<ValidateNodeOptionsType>,
+) -> JsResult<NodeValidationIssuesType> {
+  let options: ValidateNodeOptions = options
+    .map(|options| from_value(options.into()))
+    .transpose()
+    .map_err(map_error)?
+    .unwrap_or_default();
+
+  let issues = match from_value::<Node>(node.into()) {
+    Ok(node) => node
+      .validate(options.into())
+      .into_iter()
+      .map(NodeValidationIssue::from)
+      .collect(),
+    Err(err) => vec![NodeValidationIssue {
+      path: String::new(),
+      message: err.to_string(),
+    }],
+  };
+
+  Ok(to_value(&issues).map_err(map_error)?.into())
+}
+
+/// Encodes raw RGBA pixels, such as the output of `renderRaw`, into an image format.
+///
+/// `raw` returns the pixels unchanged.
+#[wasm_bindgen(js_name = encodeImage)]
+pub fn encode_image_pixels(
+  rgba: &[u8],
+  width: u32,
+  height: u32,
+  format: OutputFormatType,
+  options: Option<EncodeImageOptionsType>,
+) -> JsResult<Vec<u8>> {
+  let format: OutputFormat = from_value(format.into()).map_err(map_error)?;
+  let options: EncodeImageOptions = options
+    .map(|options| from_value(options.into()).map_err(map_error))
+    .transpose()?
+    .unwrap_or_default();
+
+  let image = rgba_image_from_raw(width, height, rgba.to_vec()).map_err(map_takumi_error)?;
+
+  encode_image(image, format, options.quality.into())
+}
+
+/// Decodes an encoded image into raw RGBA pixels. SVGs are rasterized at their declared size.
+#[wasm_bindgen(js_name = decodeImage)]
+pub fn decode_image(bytes: &[u8]) -> JsResult<RawImageType> {
+  let source = ImageSource::from_bytes(bytes).map_err(map_takumi_error)?;
+  let image = source.to_rgba_image().map_err(map_takumi_error)?;
+  let (width, height) = image.dimensions();
+
+  Ok(
+    to_value(&RawImage {
+      width,
+      height,
+      data: ByteBuf::from(image.into_owned().into_raw()),
+    })
+    .map_err(map_error)?
+    .into(),
+  )
+}
+
+/// Decodes an image, resizes it with a Lanczos3 resampler and encodes the result.
+///
+/// SVGs are rasterized at the target size. Areas the image doesn't cover, as with `contain`,
+/// are left transparent.
+#[wasm_bindgen(js_name = transformImage)]
+pub fn transform_image_bytes(
+  bytes: &[u8],
+  options: Option<TransformImageOptionsType>,
+) -> JsResult<Vec<u8>> {
+  let options: TransformImageOptions = options
+    .map(|options| from_value(options.into()).map_err(map_error))
+    .transpose()?
+    .unwrap_or_default();
+  let fit = options
+    .fit
+    .as_deref()
+    .map(|fit| {
+      <ObjectFit as FromCss>::from_str(fit).map_err(|_| map_error(format!("Invalid fit {fit:?}")))
+    })
+    .transpose()?
+    .unwrap_or_default();
+
+  let source = ImageSource::from_bytes(bytes).map_err(map_takumi_error)?;
+  let image = transform_image(
+    &source,
+    CoreTransformImageOptions::builder()
+      .width(options.width)
+      .height(options.height)
+      .fit(fit)
+      .build(),
+  )
+  .map_err(map_takumi_error)?;
+
+  encode_image(
+    image,
+    options.format.unwrap_or(OutputFormat::Png),
+    options.quality.into(),
+  )
+}
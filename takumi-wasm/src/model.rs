@@ -1,14 +1,27 @@
 //! Data models and types for the WebAssembly bindings.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_bytes::ByteBuf;
 use std::sync::Arc;
 use takumi::{
-  keyframes::deserialize_optional_keyframes, layout::node::Node, layout::style::KeyframesRule,
-  rendering::DitheringAlgorithm,
+  keyframes::deserialize_optional_keyframes,
+  layout::ColorScheme,
+  layout::node::{Node, ValidationIssue, ValidationLimits},
+  layout::style::{KeyframesRule, Style},
+  rendering::{
+    AvifBitDepth, AvifEncodeOptions as CoreAvifEncodeOptions, DitheringAlgorithm, EncodeOptions,
+    EncodePreset, LayoutRegion, PngCompression, PngEncodeOptions as CorePngEncodeOptions,
+    PngFilter, WebpEncodeOptions as CoreWebpEncodeOptions,
+  },
+  resources::{
+    font::MissingFontPolicy,
+    placeholder::{DEFAULT_BLURHASH_COMPONENTS, PlaceholderKind as CorePlaceholderKind},
+  },
 };
 use wasm_bindgen::prelude::*;
 
+use crate::helper::{JsResult, map_error};
+
 #[wasm_bindgen]
 extern "C" {
   /// JavaScript object representing a layout node.
@@ -40,10 +53,34 @@ extern "C" {
   #[wasm_bindgen(typescript_type = "ConstructRendererOptions")]
   pub type ConstructRendererOptionsType;
 
+  /// JavaScript object representing placeholder options.
+  #[wasm_bindgen(typescript_type = "PlaceholderOptions")]
+  pub type PlaceholderOptionsType;
+
+  /// JavaScript object representing warmup options.
+  #[wasm_bindgen(typescript_type = "WarmupOptions")]
+  pub type WarmupOptionsType;
+
   /// JavaScript object representing an image source.
   #[wasm_bindgen(typescript_type = "ImageSource")]
   pub type ImageSourceType;
 
+  /// JavaScript object representing a render split into layers.
+  #[wasm_bindgen(typescript_type = "RenderedLayers")]
+  pub type RenderedLayersType;
+
+  /// JavaScript object representing a render compared against a previous frame.
+  #[wasm_bindgen(typescript_type = "RenderedChanges")]
+  pub type RenderedChangesType;
+
+  /// JavaScript object representing raw RGBA pixels with their dimensions.
+  #[wasm_bindgen(typescript_type = "RawImage")]
+  pub type RawImageType;
+
+  /// JavaScript object holding pixels laid out for `new ImageData(...)`.
+  #[wasm_bindgen(typescript_type = "CanvasImageData")]
+  pub type CanvasImageDataType;
+
   /// JavaScript object representing a measured node tree.
   #[wasm_bindgen(typescript_type = "MeasuredNode")]
   pub type MeasuredNodeType;
@@ -55,6 +92,42 @@ extern "C" {
   /// JavaScript object representing an animation scene source.
   #[wasm_bindgen(typescript_type = "AnimationSceneSource")]
   pub type AnimationSceneSourceType;
+
+  /// JavaScript object representing cache statistics.
+  #[wasm_bindgen(typescript_type = "CacheStats")]
+  pub type CacheStatsType;
+
+  /// JavaScript array of RGBA palette colors.
+  #[wasm_bindgen(typescript_type = "[number, number, number, number][]")]
+  pub type PaletteColorsType;
+
+  /// JavaScript array of glyph outlines.
+  #[wasm_bindgen(typescript_type = "GlyphOutline[]")]
+  pub type GlyphOutlinesType;
+
+  /// JavaScript array of changed layout regions.
+  #[wasm_bindgen(typescript_type = "LayoutRegion[]")]
+  pub type LayoutRegionsType;
+
+  /// JavaScript object representing node validation options.
+  #[wasm_bindgen(typescript_type = "ValidateNodeOptions")]
+  pub type ValidateNodeOptionsType;
+
+  /// JavaScript array of node validation issues.
+  #[wasm_bindgen(typescript_type = "NodeValidationIssue[]")]
+  pub type NodeValidationIssuesType;
+
+  /// JavaScript string representing a static output format.
+  #[wasm_bindgen(typescript_type = "OutputFormat")]
+  pub type OutputFormatType;
+
+  /// JavaScript object representing image encoding options.
+  #[wasm_bindgen(typescript_type = "EncodeImageOptions")]
+  pub type EncodeImageOptionsType;
+
+  /// JavaScript object representing image transform options.
+  #[wasm_bindgen(typescript_type = "TransformImageOptions")]
+  pub type TransformImageOptionsType;
 }
 
 /// Options for rendering an image.
@@ -65,10 +138,22 @@ pub struct RenderOptions {
   pub width: Option<u32>,
   /// The height of the image in pixels.
   pub height: Option<u32>,
+  /// The largest width in pixels an auto-sized width can grow to.
+  pub max_width: Option<u32>,
+  /// The largest height in pixels an auto-sized height can grow to.
+  pub max_height: Option<u32>,
   /// The output image format (PNG, JPEG, or WebP).
   pub format: Option<OutputFormat>,
   /// The JPEG quality (0-100), if applicable.
   pub quality: Option<u8>,
+  /// How much encoding time to trade for smaller files.
+  pub preset: Option<EncodePreset>,
+  /// WebP encoder options.
+  pub webp: Option<WebpEncodeOptions>,
+  /// AVIF encoder options.
+  pub avif: Option<AvifEncodeOptions>,
+  /// PNG encoder options.
+  pub png: Option<PngEncodeOptions>,
   /// Pre-fetched image resources to use during rendering.
   pub fetched_resources: Option<Vec<ImageSource>>,
   /// CSS stylesheets to apply before rendering.
@@ -80,10 +165,94 @@ pub struct RenderOptions {
   pub draw_debug_border: Option<bool>,
   /// The device pixel ratio for scaling.
   pub device_pixel_ratio: Option<f32>,
+  /// The color scheme that selects `dark` and `light` style blocks.
+  pub color_scheme: Option<ColorScheme>,
   /// The animation timeline time in milliseconds.
   pub time_ms: Option<i64>,
+  /// The Unix timestamp in milliseconds that templates see as the current time.
+  pub now: Option<i64>,
+  /// The seed for random variation in templates.
+  pub seed: Option<u32>,
+  /// The BCP 47 locale that text node values are formatted for.
+  pub locale: Option<String>,
   /// The output dithering algorithm.
   pub dithering: Option<DitheringAlgorithm>,
+  /// What happens to text no loaded font covers, overriding the renderer's policy.
+  pub missing_font_policy: Option<MissingFontPolicy>,
+  /// Fonts visible to this render only, released once it finishes.
+  pub fonts: Option<Vec<Font>>,
+}
+
+impl RenderOptions {
+  /// Collects the encoder options, rejecting values the encoders can't use.
+  pub(crate) fn encode_options(&self) -> JsResult<EncodeOptions> {
+    let bit_depth = match self.avif.and_then(|avif| avif.bit_depth) {
+      None | Some(8) => AvifBitDepth::Eight,
+      Some(10) => AvifBitDepth::Ten,
+      Some(bit_depth) => {
+        return Err(map_error(format!(
+          "Invalid AVIF bit depth {bit_depth}; expected 8 or 10"
+        )));
+      }
+    };
+    let webp = self.webp.unwrap_or_default();
+    let png = self.png.unwrap_or_default();
+
+    Ok(
+      EncodeOptions::builder()
+        .quality(self.quality)
+        .preset(self.preset.unwrap_or_default())
+        .webp(
+          CoreWebpEncodeOptions::builder()
+            .lossless(webp.lossless)
+            .method(webp.method)
+            .build(),
+        )
+        .avif(
+          CoreAvifEncodeOptions::builder()
+            .speed(self.avif.and_then(|avif| avif.speed))
+            .bit_depth(bit_depth)
+            .build(),
+        )
+        .png(
+          CorePngEncodeOptions::builder()
+            .compression(png.compression)
+            .filter(png.filter)
+            .build(),
+        )
+        .build(),
+    )
+  }
+}
+
+/// WebP encoder options.
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct WebpEncodeOptions {
+  /// Whether to encode losslessly. The WASM encoder is always lossless.
+  pub lossless: Option<bool>,
+  /// Compression method from 0 (fastest) to 6 (smallest files). Ignored by the WASM encoder.
+  pub method: Option<u8>,
+}
+
+/// AVIF encoder options.
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct AvifEncodeOptions {
+  /// Encoder speed from 1 (slowest, smallest files) to 10 (fastest).
+  pub speed: Option<u8>,
+  /// Bits per channel, 8 or 10.
+  pub bit_depth: Option<u8>,
+}
+
+/// PNG encoder options.
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct PngEncodeOptions {
+  /// Deflate compression level.
+  pub compression: Option<PngCompression>,
+  /// Row filter strategy.
+  pub filter: Option<PngFilter>,
 }
 
 /// Options for rendering an animated image.
@@ -100,6 +269,8 @@ pub struct RenderAnimationOptions {
   pub format: Option<AnimationOutputFormat>,
   /// The WebP quality (0-100). Ignored for APNG and GIF.
   pub quality: Option<u8>,
+  /// The CSS color that GIF frames are composited onto. Ignored for WebP and APNG.
+  pub matte: Option<String>,
   /// Pre-fetched image resources to use during rendering.
   pub fetched_resources: Option<Vec<ImageSource>>,
   /// Whether to draw debug borders around layout elements.
@@ -108,6 +279,8 @@ pub struct RenderAnimationOptions {
   pub stylesheets: Option<Vec<String>>,
   /// The device pixel ratio for scaling.
   pub device_pixel_ratio: Option<f32>,
+  /// The color scheme that selects `dark` and `light` style blocks.
+  pub color_scheme: Option<ColorScheme>,
   /// Frames per second for timeline sampling.
   pub fps: u32,
 }
@@ -124,6 +297,8 @@ pub struct EncodeFramesOptions {
   pub format: Option<AnimationOutputFormat>,
   /// The WebP quality (0-100). Ignored for APNG and GIF.
   pub quality: Option<u8>,
+  /// The CSS color that GIF frames are composited onto. Ignored for WebP and APNG.
+  pub matte: Option<String>,
   /// Pre-fetched image resources to use during rendering.
   pub fetched_resources: Option<Vec<ImageSource>>,
   /// Whether to draw debug borders around layout elements.
@@ -132,6 +307,140 @@ pub struct EncodeFramesOptions {
   pub stylesheets: Option<Vec<String>>,
   /// The device pixel ratio for scaling.
   pub device_pixel_ratio: Option<f32>,
+  /// The color scheme that selects `dark` and `light` style blocks.
+  pub color_scheme: Option<ColorScheme>,
+}
+
+/// Options for validating a node tree.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateNodeOptions {
+  /// Maximum nesting depth, where the root node has depth 0.
+  pub max_depth: Option<usize>,
+  /// Maximum total number of nodes in the tree.
+  pub max_nodes: Option<usize>,
+}
+
+/// Options for encoding raw pixels.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodeImageOptions {
+  /// The quality of JPEG, WebP and AVIF formats (0-100).
+  pub quality: Option<u8>,
+}
+
+/// Options for transforming an image.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformImageOptions {
+  /// The output width in pixels.
+  pub width: Option<u32>,
+  /// The output height in pixels.
+  pub height: Option<u32>,
+  /// How the image fits the output box, like CSS `object-fit`.
+  pub fit: Option<String>,
+  /// The output format.
+  pub format: Option<OutputFormat>,
+  /// The quality of JPEG, WebP and AVIF formats (0-100).
+  pub quality: Option<u8>,
+}
+
+impl From<ValidateNodeOptions> for ValidationLimits {
+  fn from(options: ValidateNodeOptions) -> Self {
+    let mut limits = ValidationLimits::default();
+    limits.max_depth = options.max_depth;
+    limits.max_nodes = options.max_nodes;
+    limits
+  }
+}
+
+/// A problem found while validating a node tree.
+#[derive(Serialize)]
+pub struct NodeValidationIssue {
+  /// Location of the node, e.g. `children[2].children[0]`. Empty for the root.
+  pub path: String,
+  /// Human readable description of the problem.
+  pub message: String,
+}
+
+impl From<ValidationIssue> for NodeValidationIssue {
+  fn from(issue: ValidationIssue) -> Self {
+    Self {
+      message: issue.kind.to_string(),
+      path: issue.path,
+    }
+  }
+}
+
+/// A separately painted layer of a render.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedLayer {
+  /// The node's `id`, falling back to its tag name and then to `layer-<index>`.
+  pub name: String,
+  /// Child indices leading from the root to the node the layer was painted from.
+  pub path: Vec<usize>,
+  /// Horizontal offset of the layer within the full render.
+  pub x: u32,
+  /// Vertical offset of the layer within the full render.
+  pub y: u32,
+  /// The width of the layer image.
+  pub width: u32,
+  /// The height of the layer image.
+  pub height: u32,
+  /// The encoded layer image.
+  pub data: ByteBuf,
+}
+
+/// A render split into layers, ordered from bottom to top.
+#[derive(Serialize)]
+pub struct RenderedLayers {
+  /// The width of the full render.
+  pub width: u32,
+  /// The height of the full render.
+  pub height: u32,
+  /// The layers in paint order.
+  pub layers: Vec<RenderedLayer>,
+}
+
+/// Raw RGBA pixels of a render with its final dimensions.
+#[derive(Serialize)]
+pub struct RawImage {
+  /// The width of the render, measured from the content when not set.
+  pub width: u32,
+  /// The height of the render, measured from the content when not set.
+  pub height: u32,
+  /// The RGBA pixels, row by row.
+  pub data: ByteBuf,
+}
+
+/// The changed area of a render, cropped to the bounds of every change.
+#[derive(Serialize)]
+pub struct RenderedPatch {
+  /// Horizontal offset of the patch within the full render.
+  pub x: u32,
+  /// Vertical offset of the patch within the full render.
+  pub y: u32,
+  /// The width of the patch image.
+  pub width: u32,
+  /// The height of the patch image.
+  pub height: u32,
+  /// The encoded patch image.
+  pub data: ByteBuf,
+}
+
+/// A render compared against a previous frame.
+#[derive(Serialize)]
+pub struct RenderedChanges {
+  /// The width of the full render.
+  pub width: u32,
+  /// The height of the full render.
+  pub height: u32,
+  /// Areas whose pixels differ from the previous frame, empty when nothing changed.
+  pub regions: Vec<LayoutRegion>,
+  /// The changed area to draw over the previous frame, absent when nothing changed.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub patch: Option<RenderedPatch>,
 }
 
 /// Details for loading a custom font.
@@ -146,6 +455,10 @@ pub struct FontDetails {
   pub weight: Option<f64>,
   /// The font style (normal, italic, or oblique).
   pub style: Option<FontStyle>,
+  /// The family whose fallback chain this font is appended to.
+  pub fallback_for: Option<String>,
+  /// The only face of a TrueType Collection to load.
+  pub face_index: Option<u32>,
 }
 
 /// Font input, either as detailed object or raw buffer.
@@ -166,6 +479,135 @@ pub struct ConstructRendererOptions {
   pub persistent_images: Option<Vec<ImageSource>>,
   /// The fonts being used.
   pub fonts: Option<Vec<Font>>,
+  /// Maximum bytes of scaled glyphs kept between renders.
+  pub glyph_cache_budget: Option<u32>,
+  /// Maximum bytes of decoded fetched images kept between renders.
+  pub image_cache_budget: Option<u32>,
+  /// What happens to text no loaded font covers.
+  pub missing_font_policy: Option<MissingFontPolicy>,
+}
+
+/// Counters of the glyph cache.
+#[derive(Serialize)]
+pub struct GlyphCacheStats {
+  /// Number of cached glyphs.
+  pub entries: u32,
+  /// Estimated memory held by the cached glyphs, in bytes.
+  pub bytes: f64,
+  /// Maximum number of bytes the cache may hold.
+  pub budget: f64,
+  /// Glyph lookups served from the cache.
+  pub hits: f64,
+  /// Glyph lookups that had to scale the glyph.
+  pub misses: f64,
+  /// Glyphs dropped to stay within the budget.
+  pub evictions: f64,
+}
+
+/// Counters of the image decode cache.
+#[derive(Serialize)]
+pub struct ImageCacheStats {
+  /// Number of cached images.
+  pub entries: u32,
+  /// Estimated memory held by the decoded images, in bytes.
+  pub bytes: f64,
+  /// Maximum number of bytes the cache may hold.
+  pub budget: f64,
+  /// Decodes served from the cache.
+  pub hits: f64,
+  /// Decodes that had to decode the bytes.
+  pub misses: f64,
+  /// Images dropped to stay within the budget.
+  pub evictions: f64,
+}
+
+/// Statistics of the caches kept across renders.
+#[derive(Serialize)]
+pub struct CacheStats {
+  /// Statistics of the glyph cache.
+  pub glyphs: GlyphCacheStats,
+  /// Statistics of the image decode cache.
+  pub images: ImageCacheStats,
+}
+
+impl From<takumi::rendering::CacheStats> for CacheStats {
+  fn from(stats: takumi::rendering::CacheStats) -> Self {
+    let glyphs = stats.glyphs;
+    let images = stats.images;
+
+    Self {
+      glyphs: GlyphCacheStats {
+        entries: glyphs.entries as u32,
+        bytes: glyphs.bytes as f64,
+        budget: glyphs.budget as f64,
+        hits: glyphs.hits as f64,
+        misses: glyphs.misses as f64,
+        evictions: glyphs.evictions as f64,
+      },
+      images: ImageCacheStats {
+        entries: images.entries as u32,
+        bytes: images.bytes as f64,
+        budget: images.budget as f64,
+        hits: images.hits as f64,
+        misses: images.misses as f64,
+        evictions: images.evictions as f64,
+      },
+    }
+  }
+}
+
+/// Texts and styles to shape ahead of time.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WarmupOptions {
+  /// The strings to shape.
+  pub texts: Vec<String>,
+  /// The styles to shape every text with.
+  #[serde(default)]
+  pub styles: Vec<Style>,
+}
+
+/// The kind of placeholder generated by `Renderer.placeholder()`.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaceholderKind {
+  /// The dominant color, as a CSS color.
+  #[default]
+  Solid,
+  /// A top to bottom CSS `linear-gradient()` between the dominant colors of each half.
+  Gradient,
+  /// A blurhash string.
+  Blurhash,
+}
+
+/// Options for `Renderer.placeholder()`.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaceholderOptions {
+  /// The kind of placeholder.
+  #[serde(default)]
+  pub kind: PlaceholderKind,
+  /// The number of horizontal blurhash components.
+  pub components_x: Option<u32>,
+  /// The number of vertical blurhash components.
+  pub components_y: Option<u32>,
+}
+
+impl From<PlaceholderOptions> for CorePlaceholderKind {
+  fn from(options: PlaceholderOptions) -> Self {
+    match options.kind {
+      PlaceholderKind::Solid => Self::Solid,
+      PlaceholderKind::Gradient => Self::Gradient,
+      PlaceholderKind::Blurhash => Self::Blurhash {
+        components_x: options
+          .components_x
+          .unwrap_or(DEFAULT_BLURHASH_COMPONENTS.0),
+        components_y: options
+          .components_y
+          .unwrap_or(DEFAULT_BLURHASH_COMPONENTS.1),
+      },
+    }
+  }
 }
 
 /// An image source with its URL and raw data.
@@ -176,6 +618,8 @@ pub struct ImageSource {
   pub src: Arc<str>,
   /// The raw image data bytes.
   pub data: ByteBuf,
+  /// Shrinks bitmaps whose longer side exceeds this many pixels while decoding.
+  pub max_decode_size: Option<u32>,
 }
 
 /// Output format for static images.
@@ -188,6 +632,8 @@ pub enum OutputFormat {
   Jpeg,
   /// WebP format.
   WebP,
+  /// AVIF format.
+  Avif,
   /// Raw pixels format.
   Raw,
 }
@@ -198,6 +644,7 @@ impl From<OutputFormat> for takumi::rendering::ImageOutputFormat {
       OutputFormat::Png => takumi::rendering::ImageOutputFormat::Png,
       OutputFormat::Jpeg => takumi::rendering::ImageOutputFormat::Jpeg,
       OutputFormat::WebP => takumi::rendering::ImageOutputFormat::WebP,
+      OutputFormat::Avif => takumi::rendering::ImageOutputFormat::Avif,
       OutputFormat::Raw => unreachable!("Raw format should be handled separately"),
     }
   }
@@ -261,5 +708,5 @@ pub struct AnimationSceneSource {
 #[derive(PartialEq, Eq, Hash)]
 pub struct ImageCacheKey {
   pub(crate) src: Box<str>,
-  pub(crate) data_hash: u64,
+  pub(crate) data_hash: u128,
 }
@@ -1,7 +1,11 @@
 //! The main renderer for Takumi image rendering engine.
 
-use crate::{helper::map_error, model::*};
+use crate::{
+  helper::{JsResult, coded_error, deserialize_node, encode_image, map_error, map_takumi_error},
+  model::*,
+};
 use base64::{Engine, prelude::BASE64_STANDARD};
+use serde_bytes::ByteBuf;
 use serde_wasm_bindgen::{from_value, to_value};
 use std::{
   borrow::Cow,
@@ -9,22 +13,28 @@ use std::{
   sync::Arc,
 };
 use takumi::{
-  GlobalContext,
+  ErrorCode, GlobalContext, ScopedGlobalContext,
+  image::RgbaImage,
   layout::{
     DEFAULT_DEVICE_PIXEL_RATIO, Viewport,
     node::Node,
-    style::{KeyframesRule, StyleSheet},
+    style::{Color, FromCss, KeyframesRule, StyleSheet},
   },
   parley::{FontWeight, fontique::FontInfoOverride},
   rendering::{
     AnimatedGifOptions, AnimatedPngOptions, AnimatedWebpOptions, AnimationFrame, ImageOutputFormat,
-    SequentialScene, encode_animated_gif, encode_animated_png, encode_animated_webp,
-    measure_layout, render, render_sequence_animation, write_image,
+    RenderChanges, SequentialScene, diff_frames, encode_animated_gif, encode_animated_png,
+    encode_animated_webp, measure_layout, outline_text, render, render_hash, render_layers,
+    render_sequence_animation, warmup,
+  },
+  resources::{
+    font::FontResource,
+    image::{ImageResourceError, ImageSource as LoadedImageSource},
+    palette::DEFAULT_PALETTE_SIZE,
   },
-  resources::{font::FontResource, image::ImageSource as LoadedImageSource},
 };
 use wasm_bindgen::prelude::*;
-use xxhash_rust::xxh3::{Xxh3DefaultBuilder, xxh3_64};
+use xxhash_rust::xxh3::{Xxh3DefaultBuilder, xxh3_128};
 
 /// The main renderer for Takumi image rendering engine.
 #[wasm_bindgen]
@@ -56,7 +66,11 @@ impl Renderer {
         resources
           .iter()
           .map(|source| {
-            let image = LoadedImageSource::from_bytes(&source.data).map_err(map_error)?;
+            let image = self
+              .context
+              .image_cache()
+              .decode(&source.data, source.max_decode_size)
+              .map_err(map_takumi_error)?;
             Ok((source.src.clone(), image))
           })
           .collect::<Result<_, js_sys::Error>>()
@@ -70,6 +84,7 @@ impl Renderer {
     frames: Vec<AnimationFrame>,
     format: Option<AnimationOutputFormat>,
     quality: Option<u8>,
+    matte: Option<&str>,
   ) -> Result<Vec<u8>, JsValue> {
     if let Some(quality) = quality
       && quality > 100
@@ -79,6 +94,13 @@ impl Renderer {
       )));
     }
 
+    let matte = matte
+      .map(|matte| {
+        <Color as FromCss>::from_str(matte)
+          .map_err(|_| JsValue::from_str(&format!("Invalid matte color {matte:?}")))
+      })
+      .transpose()?;
+
     let mut buffer = Vec::new();
 
     match format.unwrap_or(AnimationOutputFormat::WebP) {
@@ -88,19 +110,20 @@ impl Renderer {
           webp_options.quality = quality;
         }
 
-        encode_animated_webp(Cow::Owned(frames), &mut buffer, webp_options).map_err(map_error)?;
+        encode_animated_webp(Cow::Owned(frames), &mut buffer, webp_options)
+          .map_err(map_takumi_error)?;
       }
       AnimationOutputFormat::APng => {
         encode_animated_png(&frames, &mut buffer, AnimatedPngOptions::default())
-          .map_err(map_error)?;
+          .map_err(map_takumi_error)?;
       }
       AnimationOutputFormat::Gif => {
         encode_animated_gif(
           Cow::Owned(frames),
           &mut buffer,
-          AnimatedGifOptions::default(),
+          AnimatedGifOptions::builder().matte(matte).build(),
         )
-        .map_err(map_error)?;
+        .map_err(map_takumi_error)?;
       }
     }
 
@@ -117,6 +140,18 @@ impl Renderer {
 
     let mut renderer = Self::default();
 
+    if let Some(budget) = options.glyph_cache_budget {
+      renderer.context.glyph_cache().set_budget(budget as usize);
+    }
+
+    if let Some(budget) = options.image_cache_budget {
+      renderer.context.image_cache().set_budget(budget as usize);
+    }
+
+    if let Some(policy) = options.missing_font_policy {
+      renderer.context.set_missing_font_policy(policy);
+    }
+
     if let Some(fonts) = options.fonts {
       for font in fonts {
         renderer.load_font_internal(font)?;
@@ -133,45 +168,46 @@ impl Renderer {
   }
 
   fn load_font_internal(&mut self, font: Font) -> Result<(), js_sys::Error> {
-    match font {
-      Font::Buffer(buffer) => {
-        self
-          .context
-          .font_context_mut()
-          .load_and_store(FontResource::new(buffer.into_vec()))
-          .map_err(map_error)?;
-      }
-      Font::Object(details) => {
-        self
-          .context
-          .font_context_mut()
-          .load_and_store(FontResource::new(details.data.into_vec()).override_info(
-            FontInfoOverride {
-              family_name: details.name.as_deref(),
-              style: details.style.map(Into::into),
-              weight: details.weight.map(|weight| FontWeight::new(weight as f32)),
-              axes: None,
-              width: None,
-            },
-          ))
-          .map_err(map_error)?;
-      }
-    }
-    Ok(())
+    self
+      .context
+      .font_context_mut()
+      .load_and_store(font_resource(&font))
+      .map_err(map_takumi_error)
   }
 
   /// Loads a font into the renderer.
   #[wasm_bindgen(js_name = loadFont)]
   pub fn load_font(&mut self, font: FontType) -> Result<(), js_sys::Error> {
-    let input: Font = from_value(font.into()).map_err(map_error)?;
+    let input: Font =
+      from_value(font.into()).map_err(|err| coded_error(ErrorCode::FontParseError, err))?;
     self.load_font_internal(input)
   }
 
+  /// Unloads every font of a family, along with the glyphs cached for it.
+  /// Returns whether the family had any fonts loaded.
+  #[wasm_bindgen(js_name = unloadFont)]
+  pub fn unload_font(&mut self, family: &str) -> bool {
+    self.context.unload_font_family(family)
+  }
+
+  /// Replaces the fonts of a family with another font, registered under the same family name.
+  /// The current fonts are kept if the new one fails to load.
+  #[wasm_bindgen(js_name = replaceFont)]
+  pub fn replace_font(&mut self, family: &str, font: FontType) -> Result<(), js_sys::Error> {
+    let input: Font =
+      from_value(font.into()).map_err(|err| coded_error(ErrorCode::FontParseError, err))?;
+
+    self
+      .context
+      .replace_font_family(family, font_resource(&input))
+      .map_err(map_takumi_error)
+  }
+
   /// Puts a persistent image into the renderer's internal store (internal version without JS conversion).
   fn put_persistent_image_internal(&mut self, data: &ImageSource) -> Result<(), js_sys::Error> {
     let key = ImageCacheKey {
       src: data.src.as_ref().into(),
-      data_hash: xxh3_64(&data.data),
+      data_hash: xxh3_128(&data.data),
     };
 
     if self.persistent_image_cache.contains(&key) {
@@ -180,7 +216,7 @@ impl Renderer {
 
     self.persistent_image_cache.insert(key);
 
-    let image = LoadedImageSource::from_bytes(&data.data).map_err(map_error)?;
+    let image = decode_image(data)?;
     self
       .context
       .persistent_image_store_mut()
@@ -196,12 +232,81 @@ impl Renderer {
     self.put_persistent_image_internal(&data)
   }
 
+  /// Shapes the given texts with the given styles ahead of time, so the first render does not
+  /// pay the full text shaping cost.
+  #[wasm_bindgen]
+  pub fn warmup(&self, options: WarmupOptionsType) -> Result<(), js_sys::Error> {
+    let options: WarmupOptions =
+      from_value(options.into()).map_err(|err| coded_error(ErrorCode::InvalidNode, err))?;
+
+    warmup(&self.context, &options.texts, &options.styles).map_err(map_takumi_error)
+  }
+
+  /// Returns the statistics of the caches kept across renders, for tuning memory use.
+  #[wasm_bindgen(js_name = cacheStats)]
+  pub fn cache_stats(&self) -> Result<CacheStatsType, js_sys::Error> {
+    Ok(
+      to_value(&CacheStats::from(self.context.cache_stats()))
+        .map_err(map_error)?
+        .into(),
+    )
+  }
+
   /// Clears the renderer's internal image store.
   #[wasm_bindgen(js_name = clearImageStore)]
   pub fn clear_image_store(&mut self) {
     self.context.persistent_image_store_mut().clear();
   }
 
+  /// Extracts up to `count` (default 8) representative colors from a persistent image as RGBA,
+  /// most common first.
+  ///
+  /// Styles can reference the same colors with `palette(src, index)`.
+  #[wasm_bindgen(js_name = extractPalette)]
+  pub fn extract_palette(
+    &self,
+    src: &str,
+    count: Option<u32>,
+  ) -> Result<PaletteColorsType, js_sys::Error> {
+    let image = self
+      .context
+      .persistent_image_store()
+      .get(src)
+      .ok_or(ImageResourceError::Unknown)
+      .map_err(map_takumi_error)?;
+    let palette = image
+      .palette(count.map_or(DEFAULT_PALETTE_SIZE, |count| count as usize))
+      .map_err(map_takumi_error)?;
+
+    Ok(
+      to_value(&palette.iter().map(|color| color.0).collect::<Vec<_>>())
+        .map_err(map_error)?
+        .into(),
+    )
+  }
+
+  /// Generates a tiny placeholder for a persistent image, such as its dominant color or a blurhash,
+  /// to show while a render using it is being generated.
+  #[wasm_bindgen]
+  pub fn placeholder(
+    &self,
+    src: &str,
+    options: Option<PlaceholderOptionsType>,
+  ) -> Result<String, js_sys::Error> {
+    let options: PlaceholderOptions = options
+      .map(|options| from_value(options.into()).map_err(map_error))
+      .transpose()?
+      .unwrap_or_default();
+
+    self
+      .context
+      .persistent_image_store()
+      .get(src)
+      .ok_or(ImageResourceError::Unknown)
+      .and_then(|image| image.placeholder(options.into()))
+      .map_err(map_takumi_error)
+  }
+
   /// Renders a node tree into an image buffer.
   #[wasm_bindgen]
   pub fn render(
@@ -209,7 +314,7 @@ impl Renderer {
     node: NodeType,
     options: Option<RenderOptionsType>,
   ) -> Result<Vec<u8>, JsValue> {
-    let node: Node = from_value(node.into()).map_err(map_error)?;
+    let node = deserialize_node(node.into())?;
     let options: RenderOptions = options
       .map(|options| from_value(options.into()).map_err(map_error))
       .transpose()?
@@ -218,48 +323,265 @@ impl Renderer {
     self.render_internal(node, options)
   }
 
+  /// Renders a node tree into raw RGBA pixels, along with the final width and height of
+  /// auto-sized renders. `format` and `quality` are ignored.
+  #[wasm_bindgen(js_name = renderRaw)]
+  pub fn render_raw(
+    &self,
+    node: NodeType,
+    options: Option<RenderOptionsType>,
+  ) -> Result<RawImageType, JsValue> {
+    let node = deserialize_node(node.into())?;
+    let options: RenderOptions = options
+      .map(|options| from_value(options.into()).map_err(map_error))
+      .transpose()?
+      .unwrap_or_default();
+
+    let image = self.render_image(node, options)?;
+    let (width, height) = image.dimensions();
+
+    Ok(
+      to_value(&RawImage {
+        width,
+        height,
+        data: ByteBuf::from(image.into_raw()),
+      })
+      .map_err(map_error)?
+      .into(),
+    )
+  }
+
+  /// Renders a node tree into an object that can be passed to `new ImageData(data, width)`.
+  ///
+  /// The pixels are copied once out of WASM memory into a fresh `Uint8ClampedArray`, as a view
+  /// into the memory would be detached the next time it grows. `format` and `quality` are ignored.
+  #[wasm_bindgen(js_name = renderToCanvasImageData)]
+  pub fn render_to_canvas_image_data(
+    &self,
+    node: NodeType,
+    options: Option<RenderOptionsType>,
+  ) -> Result<CanvasImageDataType, JsValue> {
+    let node = deserialize_node(node.into())?;
+    let options: RenderOptions = options
+      .map(|options| from_value(options.into()).map_err(map_error))
+      .transpose()?
+      .unwrap_or_default();
+
+    let image = self.render_image(node, options)?;
+    let (width, height) = image.dimensions();
+
+    let data = js_sys::Uint8ClampedArray::new_with_length(image.as_raw().len() as u32);
+    data.copy_from(image.as_raw());
+
+    let result = js_sys::Object::new();
+    js_sys::Reflect::set(&result, &"width".into(), &width.into())?;
+    js_sys::Reflect::set(&result, &"height".into(), &height.into())?;
+    js_sys::Reflect::set(&result, &"data".into(), &data)?;
+
+    Ok(result.unchecked_into())
+  }
+
+  fn render_image(&self, node: Node, options: RenderOptions) -> Result<RgbaImage, JsValue> {
+    self.run(node, options, render)
+  }
+
   fn render_internal(&self, node: Node, options: RenderOptions) -> Result<Vec<u8>, JsValue> {
+    let format = options.format.unwrap_or(OutputFormat::Png);
+    let encode_options = options.encode_options()?;
+    let image = self.render_image(node, options)?;
+
+    Ok(encode_image(image, format, encode_options)?)
+  }
+
+  /// Loads the render-scoped fonts of `options`, returning `None` when there are none.
+  fn scoped_context(&self, fonts: Option<&[Font]>) -> JsResult<Option<ScopedGlobalContext>> {
+    match fonts {
+      Some(fonts) if !fonts.is_empty() => self
+        .context
+        .with_scoped_fonts(fonts.iter().map(font_resource))
+        .map(Some)
+        .map_err(map_takumi_error),
+      _ => Ok(None),
+    }
+  }
+
+  /// Builds the core render options and runs `f` with them, along with any render-scoped fonts.
+  fn run<T>(
+    &self,
+    node: Node,
+    options: RenderOptions,
+    f: impl FnOnce(takumi::rendering::RenderOptions) -> takumi::Result<T>,
+  ) -> Result<T, JsValue> {
+    let scoped = self.scoped_context(options.fonts.as_deref())?;
+    let global = scoped.as_deref().unwrap_or(&self.context);
+
+    Ok(f(self.image_render_options(node, options, global)?).map_err(map_takumi_error)?)
+  }
+
+  fn image_render_options<'a>(
+    &'a self,
+    node: Node,
+    options: RenderOptions,
+    global: &'a GlobalContext,
+  ) -> Result<takumi::rendering::RenderOptions<'a>, JsValue> {
     let fetched_resources = self.fetch_resources_map(options.fetched_resources.as_deref())?;
     let dithering = options.dithering.unwrap_or_default();
     let stylesheet =
       self.parse_stylesheet(options.stylesheets, options.keyframes.unwrap_or_default())?;
 
-    let render_options = takumi::rendering::RenderOptions::builder()
-      .viewport(
-        Viewport::new((options.width, options.height)).with_device_pixel_ratio(
-          options
-            .device_pixel_ratio
-            .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
-        ),
-      )
-      .draw_debug_border(options.draw_debug_border.unwrap_or_default())
-      .fetched_resources(fetched_resources)
-      .stylesheet(stylesheet)
-      .time_ms(options.time_ms.unwrap_or_default().max(0) as u64)
-      .dithering(dithering)
-      .node(node)
-      .global(&self.context)
-      .build();
+    Ok(
+      takumi::rendering::RenderOptions::builder()
+        .viewport(
+          Viewport::new((options.width, options.height))
+            .with_device_pixel_ratio(
+              options
+                .device_pixel_ratio
+                .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
+            )
+            .with_color_scheme(options.color_scheme.unwrap_or_default()),
+        )
+        .draw_debug_border(options.draw_debug_border.unwrap_or_default())
+        .fetched_resources(fetched_resources)
+        .stylesheet(stylesheet)
+        .time_ms(options.time_ms.unwrap_or_default().max(0) as u64)
+        .now_ms(options.now.map(|now| now.max(0) as u64))
+        .seed(options.seed.unwrap_or_default().into())
+        .locale(options.locale)
+        .missing_font_policy(options.missing_font_policy)
+        .dithering(dithering)
+        .node(node)
+        .global(global)
+        .max_width(options.max_width)
+        .max_height(options.max_height)
+        .build(),
+    )
+  }
 
-    let image = render(render_options).map_err(map_error)?;
+  /// Renders a node tree into separately encoded layers.
+  ///
+  /// The root paints the first layer, followed by each child of the root and each descendant
+  /// with a `data-layer` attribute, cropped to the area they paint.
+  #[wasm_bindgen(js_name = renderLayers)]
+  pub fn render_layers(
+    &self,
+    node: NodeType,
+    options: Option<RenderOptionsType>,
+  ) -> Result<RenderedLayersType, JsValue> {
+    let node = deserialize_node(node.into())?;
+    let options: RenderOptions = options
+      .map(|options| from_value(options.into()).map_err(map_error))
+      .transpose()?
+      .unwrap_or_default();
 
     let format = options.format.unwrap_or(OutputFormat::Png);
+    let encode_options = options.encode_options()?;
+    let layered = self.run(node, options, render_layers)?;
 
-    if format == OutputFormat::Raw {
-      return Ok(image.into_raw());
-    }
-
-    let mut buffer = Vec::new();
+    let layers = layered
+      .layers
+      .into_iter()
+      .map(|layer| {
+        let (width, height) = layer.image.dimensions();
+
+        Ok(RenderedLayer {
+          name: layer.name,
+          path: layer.path,
+          x: layer.x,
+          y: layer.y,
+          width,
+          height,
+          data: ByteBuf::from(encode_image(layer.image, format, encode_options)?),
+        })
+      })
+      .collect::<Result<_, js_sys::Error>>()?;
 
-    write_image(
-      Cow::Owned(image),
-      &mut buffer,
-      format.into(),
-      options.quality,
+    Ok(
+      to_value(&RenderedLayers {
+        width: layered.width,
+        height: layered.height,
+        layers,
+      })
+      .map_err(map_error)?
+      .into(),
     )
-    .map_err(map_error)?;
+  }
 
-    Ok(buffer)
+  /// Hashes a node tree with its styles, images and fonts without rendering it.
+  ///
+  /// Returns 16 hex digits that are equal exactly when `render` would produce the same image,
+  /// suitable as an `ETag` or cache key.
+  #[wasm_bindgen(js_name = renderHash)]
+  pub fn render_hash(
+    &self,
+    node: NodeType,
+    options: Option<RenderOptionsType>,
+  ) -> Result<String, JsValue> {
+    let node = deserialize_node(node.into())?;
+    let options: RenderOptions = options
+      .map(|options| from_value(options.into()).map_err(map_error))
+      .transpose()?
+      .unwrap_or_default();
+
+    let hash = self.run(node, options, render_hash)?;
+
+    Ok(format!("{hash:016x}"))
+  }
+
+  /// Renders a node tree and compares it against the previous frame.
+  ///
+  /// `previous` holds the raw RGBA pixels of an earlier render, such as one made with the `raw`
+  /// format. A frame of a different size counts as changed everywhere.
+  #[wasm_bindgen(js_name = renderChanges)]
+  pub fn render_changes(
+    &self,
+    node: NodeType,
+    previous: &[u8],
+    options: Option<RenderOptionsType>,
+  ) -> Result<RenderedChangesType, JsValue> {
+    let node = deserialize_node(node.into())?;
+    let options: RenderOptions = options
+      .map(|options| from_value(options.into()).map_err(map_error))
+      .transpose()?
+      .unwrap_or_default();
+
+    let format = options.format.unwrap_or(OutputFormat::Png);
+    let encode_options = options.encode_options()?;
+    let image = self.run(node, options, render)?;
+    let (width, height) = image.dimensions();
+
+    // A buffer that doesn't match the new size counts as a different frame.
+    let previous = (previous.len() == image.as_raw().len())
+      .then(|| RgbaImage::from_raw(width, height, previous.to_vec()))
+      .flatten()
+      .unwrap_or_default();
+    let changes = RenderChanges {
+      regions: diff_frames(&previous, &image),
+      image,
+    };
+
+    let patch = changes
+      .patch()
+      .map(|(region, patch)| {
+        Ok::<_, js_sys::Error>(RenderedPatch {
+          x: region.x as u32,
+          y: region.y as u32,
+          width: region.width as u32,
+          height: region.height as u32,
+          data: ByteBuf::from(encode_image(patch, format, encode_options)?),
+        })
+      })
+      .transpose()?;
+
+    Ok(
+      to_value(&RenderedChanges {
+        width,
+        height,
+        regions: changes.regions,
+        patch,
+      })
+      .map_err(map_error)?
+      .into(),
+    )
   }
 
   /// Measures a node tree and returns layout information.
@@ -269,35 +591,63 @@ impl Renderer {
     node: NodeType,
     options: Option<RenderOptionsType>,
   ) -> Result<MeasuredNodeType, JsValue> {
-    let node: Node = from_value(node.into()).map_err(map_error)?;
+    let layout = self.run_layout(node, options, measure_layout)?;
+
+    Ok(to_value(&layout).map_err(map_error)?.into())
+  }
+
+  /// Renders a node tree and returns the vector outline of every drawn glyph.
+  #[wasm_bindgen(js_name = outlineText)]
+  pub fn outline_text(
+    &self,
+    node: NodeType,
+    options: Option<RenderOptionsType>,
+  ) -> Result<GlyphOutlinesType, JsValue> {
+    let outlines = self.run_layout(node, options, outline_text)?;
+
+    Ok(to_value(&outlines).map_err(map_error)?.into())
+  }
+
+  /// Builds the core options for measuring and runs `f` with them, along with any render-scoped fonts.
+  fn run_layout<T>(
+    &self,
+    node: NodeType,
+    options: Option<RenderOptionsType>,
+    f: impl FnOnce(takumi::rendering::RenderOptions) -> takumi::Result<T>,
+  ) -> Result<T, JsValue> {
+    let node = deserialize_node(node.into())?;
     let options: RenderOptions = options
       .map(|options| from_value(options.into()).map_err(map_error))
       .transpose()?
       .unwrap_or_default();
 
+    let scoped = self.scoped_context(options.fonts.as_deref())?;
+    let global = scoped.as_deref().unwrap_or(&self.context);
+
     let fetched_resources = self.fetch_resources_map(options.fetched_resources.as_deref())?;
     let stylesheet =
       self.parse_stylesheet(options.stylesheets, options.keyframes.unwrap_or_default())?;
 
-    let render_options = takumi::rendering::RenderOptions::builder()
+    let options = takumi::rendering::RenderOptions::builder()
       .viewport(
-        Viewport::new((options.width, options.height)).with_device_pixel_ratio(
-          options
-            .device_pixel_ratio
-            .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
-        ),
+        Viewport::new((options.width, options.height))
+          .with_device_pixel_ratio(
+            options
+              .device_pixel_ratio
+              .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
+          )
+          .with_color_scheme(options.color_scheme.unwrap_or_default()),
       )
       .draw_debug_border(options.draw_debug_border.unwrap_or_default())
       .fetched_resources(fetched_resources)
       .stylesheet(stylesheet)
       .time_ms(options.time_ms.unwrap_or_default().max(0) as u64)
+      .locale(options.locale)
       .node(node)
-      .global(&self.context)
+      .global(global)
       .build();
 
-    let layout = measure_layout(render_options).map_err(map_error)?;
-
-    Ok(to_value(&layout).map_err(map_error)?.into())
+    Ok(f(options).map_err(map_takumi_error)?)
   }
 
   /// Renders a node tree into a data URL.
@@ -309,7 +659,7 @@ impl Renderer {
     node: NodeType,
     options: RenderOptionsType,
   ) -> Result<String, js_sys::Error> {
-    let node: Node = from_value(node.into()).map_err(map_error)?;
+    let node = deserialize_node(node.into())?;
     let options: RenderOptions = from_value(options.into()).map_err(map_error)?;
 
     let format = options.format.unwrap_or(OutputFormat::Png);
@@ -341,12 +691,14 @@ impl Renderer {
       height,
       format,
       quality,
+      matte,
       fetched_resources,
       draw_debug_border,
       stylesheets,
       device_pixel_ratio,
+      color_scheme,
       fps,
-    } = from_value(options.into()).map_err(map_error)?;
+    } = from_value(options.into()).map_err(|err| coded_error(ErrorCode::InvalidNode, err))?;
     let fetched_resources = self.fetch_resources_map(fetched_resources.as_deref())?;
 
     if scenes.is_empty() {
@@ -358,7 +710,8 @@ impl Renderer {
     }
 
     let viewport = Viewport::new((width, height))
-      .with_device_pixel_ratio(device_pixel_ratio.unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO));
+      .with_device_pixel_ratio(device_pixel_ratio.unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO))
+      .with_color_scheme(color_scheme.unwrap_or_default());
     let draw_debug_border = draw_debug_border.unwrap_or_default();
     let stylesheet = StyleSheet::parse_owned_list_loosy(stylesheets.unwrap_or_default());
     let scene_options = scenes
@@ -379,9 +732,10 @@ impl Renderer {
           .build()
       })
       .collect::<Vec<_>>();
-    let rendered_frames = render_sequence_animation(&scene_options, fps).map_err(map_error)?;
+    let rendered_frames =
+      render_sequence_animation(&scene_options, fps).map_err(map_takumi_error)?;
 
-    self.encode_animation(rendered_frames, format, quality)
+    self.encode_animation(rendered_frames, format, quality, matte.as_deref())
   }
 
   /// Encodes a precomputed frame sequence into an animated image buffer.
@@ -391,14 +745,17 @@ impl Renderer {
     frames: Vec<AnimationFrameSourceType>,
     options: EncodeFramesOptionsType,
   ) -> Result<Vec<u8>, JsValue> {
-    let frames: Vec<AnimationFrameSource> = from_value(frames.into()).map_err(map_error)?;
+    let frames: Vec<AnimationFrameSource> =
+      from_value(frames.into()).map_err(|err| coded_error(ErrorCode::InvalidNode, err))?;
     let options: EncodeFramesOptions = from_value(options.into()).map_err(map_error)?;
     let fetched_resources = self.fetch_resources_map(options.fetched_resources.as_deref())?;
-    let viewport = Viewport::new((options.width, options.height)).with_device_pixel_ratio(
-      options
-        .device_pixel_ratio
-        .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
-    );
+    let viewport = Viewport::new((options.width, options.height))
+      .with_device_pixel_ratio(
+        options
+          .device_pixel_ratio
+          .unwrap_or(DEFAULT_DEVICE_PIXEL_RATIO),
+      )
+      .with_color_scheme(options.color_scheme.unwrap_or_default());
     let stylesheet = StyleSheet::parse_owned_list_loosy(options.stylesheets.unwrap_or_default());
     let rendered_frames = frames
       .into_iter()
@@ -412,11 +769,49 @@ impl Renderer {
           .stylesheet(stylesheet.clone())
           .build();
 
-        let image = render(render_options).map_err(map_error)?;
+        let image = render(render_options).map_err(map_takumi_error)?;
         Ok(AnimationFrame::new(image, frame.duration_ms))
       })
       .collect::<Result<Vec<_>, JsValue>>()?;
 
-    self.encode_animation(rendered_frames, options.format, options.quality)
+    self.encode_animation(
+      rendered_frames,
+      options.format,
+      options.quality,
+      options.matte.as_deref(),
+    )
+  }
+}
+
+fn decode_image(source: &ImageSource) -> JsResult<Arc<LoadedImageSource>> {
+  match source.max_decode_size {
+    Some(max_size) => LoadedImageSource::from_bytes_with_max_size(&source.data, max_size),
+    None => LoadedImageSource::from_bytes(&source.data),
+  }
+  .map_err(map_takumi_error)
+}
+
+fn font_resource(font: &Font) -> FontResource<'_> {
+  match font {
+    Font::Buffer(buffer) => FontResource::new(&buffer[..]),
+    Font::Object(details) => {
+      let resource = FontResource::new(&details.data[..]).override_info(FontInfoOverride {
+        family_name: details.name.as_deref(),
+        style: details.style.map(Into::into),
+        weight: details.weight.map(|weight| FontWeight::new(weight as f32)),
+        axes: None,
+        width: None,
+      });
+
+      let resource = match details.fallback_for.as_deref() {
+        Some(family) => resource.fallback_for(family),
+        None => resource,
+      };
+
+      match details.face_index {
+        Some(index) => resource.face_index(index),
+        None => resource,
+      }
+    }
   }
 }
@@ -418,3 +418,24 @@ test("report deserialize error for textDecorationLine with invalid string value"
     "a value of 'underline', 'line-through' or 'overline' or 'none'; also accepts 'initial', 'unset' or 'inherit'.",
   );
 });
+
+test("attach error code to invalid node errors", () => {
+  try {
+    renderer.render(
+      container({
+        children: [],
+        style: {
+          // @ts-expect-error: invalid type test
+          width: true,
+        },
+      }),
+      {
+        width: 100,
+        height: 100,
+      },
+    );
+    expect.unreachable();
+  } catch (error) {
+    expect(error).toMatchObject({ code: "INVALID_NODE", retriable: false });
+  }
+});
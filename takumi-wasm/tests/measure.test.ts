@@ -1,6 +1,6 @@
 import { describe, expect, it } from "bun:test";
 import { container, text } from "@takumi-rs/helpers";
-import { Renderer } from "../bundlers/node";
+import { diffLayouts, hitTest, Renderer } from "../bundlers/node";
 
 describe("Renderer.measure", () => {
   const renderer = new Renderer();
@@ -23,6 +23,7 @@ describe("Renderer.measure", () => {
       transform: [1, 0, 0, 1, 0, 0],
       children: [],
       runs: [],
+      lines: [],
     });
   });
 
@@ -82,4 +83,38 @@ describe("Renderer.measure", () => {
       children: [],
     });
   });
+
+  it("should report changed regions between two layouts", () => {
+    const box = (width: number) =>
+      container({
+        style: { width: 100, height: 100 },
+        children: [container({ style: { width, height: 10 }, children: [] })],
+      });
+
+    const before = renderer.measure(box(10));
+    const after = renderer.measure(box(40));
+
+    expect(diffLayouts(before, before)).toEqual([]);
+    expect(diffLayouts(before, after)).toEqual([
+      { x: 0, y: 0, width: 40, height: 10 },
+    ]);
+  });
+
+  it("should hit test a measured layout", () => {
+    const layout = renderer.measure(
+      container({
+        style: { width: 100, height: 100 },
+        children: [
+          container({
+            style: { width: 50, height: 50, transform: "translate(25px, 25px)" },
+            children: [],
+          }),
+        ],
+      }),
+    );
+
+    expect(hitTest(layout, 10, 10)).toEqual([]);
+    expect(hitTest(layout, 60, 60)).toEqual([0]);
+    expect(hitTest(layout, 200, 200)).toBeFalsy();
+  });
 });
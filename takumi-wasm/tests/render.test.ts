@@ -159,6 +159,20 @@ describe("render", () => {
   });
 });
 
+describe("renderToCanvasImageData", () => {
+  test("returns pixels laid out for ImageData", () => {
+    const { width, height, data } = renderer.renderToCanvasImageData(node, {
+      width: 120,
+      height: 80,
+    });
+
+    expect(width).toBe(120);
+    expect(height).toBe(80);
+    expect(data).toBeInstanceOf(Uint8ClampedArray);
+    expect(data.length).toBe(width * height * 4);
+  });
+});
+
 describe("renderAsDataUrl", () => {
   test("with timeMs applied to stylesheet animation", () => {
     const animated = renderer.measure(
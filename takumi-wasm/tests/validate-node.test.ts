@@ -0,0 +1,24 @@
+import { expect, test } from "bun:test";
+import { container, image } from "@takumi-rs/helpers";
+import { validateNode } from "../bundlers/node";
+
+test("collects every issue with its path", () => {
+  const issues = validateNode(
+    container({
+      children: [image({ src: "data:", tw: "not-a-class" })],
+    }),
+    { maxDepth: 0 },
+  );
+
+  expect(issues).toEqual([
+    {
+      path: "children[0]",
+      message: "unsupported tailwind class 'not-a-class'",
+    },
+    {
+      path: "children[0]",
+      message: "image source is not a valid data URI",
+    },
+    { path: "children[0]", message: "node depth 1 exceeds the limit of 0" },
+  ]);
+});
@@ -1,11 +1,13 @@
 use cssparser::{BasicParseErrorKind, ParseError, ParseErrorKind};
 use selectors::parser::SelectorParseErrorKind;
-use std::borrow::Cow;
+use std::{borrow::Cow, sync::Arc};
 
 use crate::{
   keyframes::KeyframePreludeParseError,
+  layout::node::NodeDeserializeError,
   resources::{font::FontError, image::ImageResourceError},
 };
+use serde::Serialize;
 use thiserror::Error;
 
 /// Alias to [`TakumiError`].
@@ -39,6 +41,21 @@ pub struct StyleSheetParseError {
   pub kind: StyleSheetParseErrorKind,
 }
 
+/// Errors raised while converting HTML markup into nodes.
+#[cfg(feature = "html")]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HtmlImportError {
+  /// The `style` attribute of an element could not be parsed.
+  #[error("invalid style attribute on <{tag_name}>: {source}")]
+  InvalidStyle {
+    /// The tag name of the element.
+    tag_name: Box<str>,
+    /// The CSS parse error.
+    source: StyleDeclarationBlockParseError,
+  },
+}
+
 /// The specific stylesheet parse failure.
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -326,6 +343,15 @@ pub enum TakumiError {
   #[error("Image resolution error: {0}")]
   ImageResolveError(#[from] ImageResourceError),
 
+  /// A [`ResourceLoader`](crate::resources::loader::ResourceLoader) failed to load a resource.
+  #[error("Failed to load resource '{url}': {reason}")]
+  ResourceLoadError {
+    /// The URL of the resource.
+    url: Arc<str>,
+    /// The loader error rendered as text.
+    reason: String,
+  },
+
   /// Standard IO error.
   #[error("IO error: {0}")]
   IoError(#[from] std::io::Error),
@@ -380,13 +406,66 @@ pub enum TakumiError {
     max: u16,
   },
 
+  /// A raw RGBA buffer does not hold exactly `width * height` pixels.
+  #[error("Expected {width}x{height} RGBA pixels (4 bytes each), got {len} bytes")]
+  InvalidPixelBuffer {
+    /// The image width.
+    width: u32,
+    /// The image height.
+    height: u32,
+    /// The length of the buffer in bytes.
+    len: usize,
+  },
+
+  /// A transformed image would be larger than allowed.
+  #[error("Transformed image dimensions must be <= {max}x{max}, got {width}x{height}")]
+  TransformTooLarge {
+    /// The requested width.
+    width: u32,
+    /// The requested height.
+    height: u32,
+    /// The maximum accepted dimension value.
+    max: u32,
+  },
+
   /// Error related to font processing.
   #[error("Font error: {0}")]
   FontError(#[from] FontError),
 
+  /// No loaded font or fallback covers some text, raised under
+  /// [`MissingFontPolicy::Error`](crate::resources::font::MissingFontPolicy::Error).
+  #[error("No font covers the text '{text}'")]
+  MissingGlyphs {
+    /// The characters that couldn't be drawn.
+    text: String,
+  },
+
   /// Error during layout computation.
   #[error("Layout error: {0}")]
   LayoutError(taffy::TaffyError),
+
+  /// A node tree failed to deserialize.
+  #[error("Invalid node: {0}")]
+  NodeDeserializeError(#[from] NodeDeserializeError),
+
+  /// A node has a `type` that is neither built in nor registered as a plugin.
+  #[error("Unknown node type '{0}'")]
+  UnknownNodeType(Box<str>),
+
+  /// A map node is larger, or covered by more tiles, than allowed.
+  #[error("Map of {width}x{height} pixels with {tile_size}px tiles exceeds the map size limits")]
+  MapTooLarge {
+    /// The map width in CSS pixels.
+    width: f32,
+    /// The map height in CSS pixels.
+    height: f32,
+    /// The tile size of the map.
+    tile_size: u32,
+  },
+
+  /// Error reported by a custom node drawer.
+  #[error("Custom draw error: {0}")]
+  CustomDrawError(String),
 }
 
 impl From<taffy::TaffyError> for TakumiError {
@@ -395,5 +474,142 @@ impl From<taffy::TaffyError> for TakumiError {
   }
 }
 
+impl TakumiError {
+  /// Returns the stable [`ErrorCode`] classifying this error.
+  pub fn code(&self) -> ErrorCode {
+    match self {
+      Self::ImageResolveError(ImageResourceError::Unknown) | Self::ResourceLoadError { .. } => {
+        ErrorCode::ResourceFetchFailed
+      }
+      Self::ImageResolveError(_) => ErrorCode::ImageDecodeError,
+      Self::FontError(_) => ErrorCode::FontParseError,
+      Self::InvalidViewport
+      | Self::LayoutError(_)
+      | Self::NodeDeserializeError(_)
+      | Self::MissingGlyphs { .. }
+      | Self::UnknownNodeType(_)
+      | Self::MapTooLarge { .. }
+      | Self::CustomDrawError(_) => ErrorCode::InvalidNode,
+      Self::IoError(_)
+      | Self::PngError(_)
+      | Self::WebPError(_)
+      | Self::GifEncodingError(_)
+      | Self::ImageError(_)
+      | Self::EmptyAnimationFrames { .. }
+      | Self::MixedAnimationFrameDimensions { .. }
+      | Self::GifFrameDimensionsTooLarge { .. }
+      | Self::InvalidPixelBuffer { .. }
+      | Self::TransformTooLarge { .. } => ErrorCode::EncodeError,
+      #[cfg(target_arch = "wasm32")]
+      Self::WebPEncodingError(_) => ErrorCode::EncodeError,
+    }
+  }
+}
+
+/// Machine readable classification of errors, shared by the bindings.
+///
+/// Bindings attach [`ErrorCode::as_str`] as the `code` property of thrown errors,
+/// so servers can decide between retrying and responding with a client error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[non_exhaustive]
+pub enum ErrorCode {
+  /// An external resource (e.g. image) could not be fetched.
+  ResourceFetchFailed,
+  /// Image data could not be decoded, or is in an unsupported format.
+  ImageDecodeError,
+  /// A font could not be parsed or loaded.
+  FontParseError,
+  /// The node tree, its styles or the viewport are invalid.
+  InvalidNode,
+  /// The output image could not be encoded.
+  EncodeError,
+  /// The operation was aborted before completion.
+  Aborted,
+}
+
+impl ErrorCode {
+  /// Returns the `SCREAMING_SNAKE_CASE` representation of the code.
+  pub const fn as_str(self) -> &'static str {
+    match self {
+      Self::ResourceFetchFailed => "RESOURCE_FETCH_FAILED",
+      Self::ImageDecodeError => "IMAGE_DECODE_ERROR",
+      Self::FontParseError => "FONT_PARSE_ERROR",
+      Self::InvalidNode => "INVALID_NODE",
+      Self::EncodeError => "ENCODE_ERROR",
+      Self::Aborted => "ABORTED",
+    }
+  }
+
+  /// Whether retrying the same request may succeed.
+  ///
+  /// Resource fetches and aborted operations are transient, everything else
+  /// is caused by the input and would fail again.
+  pub const fn is_retriable(self) -> bool {
+    matches!(self, Self::ResourceFetchFailed | Self::Aborted)
+  }
+}
+
+impl std::fmt::Display for ErrorCode {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
 /// A specialized Result type for Takumi operations.
 pub type Result<T> = std::result::Result<T, TakumiError>;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn classifies_errors_by_code() {
+    assert_eq!(
+      TakumiError::from(ImageResourceError::Unknown).code(),
+      ErrorCode::ResourceFetchFailed
+    );
+    assert_eq!(
+      TakumiError::from(ImageResourceError::MalformedDataUri).code(),
+      ErrorCode::ImageDecodeError
+    );
+    assert_eq!(
+      TakumiError::from(ImageResourceError::DecodeError(
+        image::ImageError::Unsupported(image::error::UnsupportedError::from_format_and_kind(
+          image::error::ImageFormatHint::Unknown,
+          image::error::UnsupportedErrorKind::GenericFeature("heif".to_owned()),
+        ))
+      ))
+      .code(),
+      ErrorCode::ImageDecodeError
+    );
+    assert_eq!(
+      TakumiError::from(FontError::UnsupportedFormat).code(),
+      ErrorCode::FontParseError
+    );
+    assert_eq!(TakumiError::InvalidViewport.code(), ErrorCode::InvalidNode);
+    assert_eq!(
+      TakumiError::EmptyAnimationFrames { format: "gif" }.code(),
+      ErrorCode::EncodeError
+    );
+  }
+
+  #[test]
+  fn only_transient_codes_are_retriable() {
+    assert!(ErrorCode::ResourceFetchFailed.is_retriable());
+    assert!(ErrorCode::Aborted.is_retriable());
+    assert!(!ErrorCode::ImageDecodeError.is_retriable());
+    assert!(!ErrorCode::InvalidNode.is_retriable());
+    assert!(!ErrorCode::FontParseError.is_retriable());
+    assert!(!ErrorCode::EncodeError.is_retriable());
+  }
+
+  #[test]
+  fn serializes_as_screaming_snake_case() {
+    assert_eq!(
+      serde_json::to_string(&ErrorCode::ResourceFetchFailed).ok(),
+      Some("\"RESOURCE_FETCH_FAILED\"".to_owned())
+    );
+    assert_eq!(ErrorCode::EncodeError.to_string(), "ENCODE_ERROR");
+  }
+}
@@ -0,0 +1,606 @@
+use std::{borrow::Cow, collections::BTreeMap, str::FromStr};
+
+use crate::{
+  error::HtmlImportError,
+  layout::{
+    node::{ImageData, Node, NodeKind, NodeMetadata},
+    style::{Style, StyleDeclarationBlock, tw::TailwindValues},
+  },
+};
+
+/// Elements that never have children or an end tag.
+const VOID_ELEMENTS: &[&str] = &[
+  "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+  "wbr",
+];
+
+/// Elements whose content isn't markup, dropped together with their content.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "template", "title", "textarea"];
+
+/// Elements dropped without their content being rendered.
+const HIDDEN_ELEMENTS: &[&str] = &["head", "meta", "link", "base"];
+
+/// Document wrappers whose children take their place.
+const TRANSPARENT_ELEMENTS: &[&str] = &["html", "body"];
+
+/// Default element styles, matching the presets applied to JSX elements by `@takumi-rs/helpers`.
+fn preset_css(tag_name: &str) -> Option<&'static str> {
+  Some(match tag_name {
+    "div" => "display: block",
+    "p" => "display: block; margin-top: 1em; margin-bottom: 1em",
+    "blockquote" => "display: block; margin: 1em 40px",
+    "center" => "display: block; text-align: center",
+    "hr" => "display: block; margin: 0.5em auto; border-width: 1px",
+    "h1" => "display: block; font-size: 2em; margin: 0.67em 0; font-weight: bold",
+    "h2" => "display: block; font-size: 1.5em; margin: 0.83em 0; font-weight: bold",
+    "h3" => "display: block; font-size: 1.17em; margin: 1em 0; font-weight: bold",
+    "h4" => "display: block; margin: 1.33em 0; font-weight: bold",
+    "h5" => "display: block; font-size: 0.83em; margin: 1.67em 0; font-weight: bold",
+    "h6" => "display: block; font-size: 0.67em; margin: 2.33em 0; font-weight: bold",
+    "u" => "text-decoration: underline",
+    "strong" | "b" => "font-weight: bold",
+    "i" | "em" => "font-style: italic",
+    "code" | "kbd" => "font-family: monospace",
+    "pre" => "display: block; font-family: monospace; margin: 1em 0",
+    "mark" => "background-color: yellow; color: black",
+    "big" => "font-size: 1.2em",
+    "small" => "font-size: 0.8em",
+    "s" => "text-decoration: line-through",
+    _ => return None,
+  })
+}
+
+/// Converts HTML markup into a node tree.
+///
+/// A forgiving subset of HTML, or JSX markup with string attributes, is accepted:
+///
+/// - Elements become containers carrying their tag name, with the default styles of the JSX
+///   presets, so `div` and `p` are blocks and `span` is inline.
+/// - `img` becomes an image node, sized by its `width` and `height` attributes.
+/// - `style` is parsed as inline CSS, and `class` (or `className`) is kept as the class name and
+///   parsed as Tailwind utilities, alongside an optional `tw` attribute.
+/// - `id` and the remaining attributes are kept for stylesheet selectors.
+///
+/// Comments, doctypes, `head`, `script` and `style` elements are dropped, and `html` and `body`
+/// are replaced by their children. Whitespace-only text spanning lines, such as indentation
+/// between tags, is dropped. Unclosed elements are closed at the end of their parent, and
+/// stray end tags are ignored.
+///
+/// Several top-level nodes are wrapped in a container.
+pub fn html_to_node(html: &str) -> Result<Node, HtmlImportError> {
+  let mut parser = HtmlParser {
+    input: html,
+    stack: Vec::new(),
+    root: Vec::new(),
+  };
+
+  parser.parse()?;
+
+  let mut nodes = parser.root;
+
+  if nodes.len() == 1
+    && let Some(node) = nodes.pop()
+  {
+    return Ok(node);
+  }
+
+  Ok(Node::container(nodes))
+}
+
+struct OpenElement {
+  tag_name: String,
+  attributes: Vec<(String, String)>,
+  children: Vec<Node>,
+}
+
+struct HtmlParser<'a> {
+  input: &'a str,
+  stack: Vec<OpenElement>,
+  root: Vec<Node>,
+}
+
+impl HtmlParser<'_> {
+  fn parse(&mut self) -> Result<(), HtmlImportError> {
+    let mut rest = self.input;
+
+    while !rest.is_empty() {
+      if let Some(comment) = rest.strip_prefix("<!--") {
+        rest = comment
+          .find("-->")
+          .map_or("", |end| &comment[end + "-->".len()..]);
+        continue;
+      }
+
+      if rest.starts_with("<!") || rest.starts_with("<?") {
+        rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+        continue;
+      }
+
+      if let Some(end_tag) = rest.strip_prefix("</") {
+        let end = end_tag.find('>').unwrap_or(end_tag.len());
+        self.close(&end_tag[..end].trim().to_ascii_lowercase())?;
+        rest = end_tag.get(end + 1..).unwrap_or_default();
+        continue;
+      }
+
+      if rest.starts_with('<') && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+        let (tag, after) = parse_start_tag(&rest[1..]);
+        rest = after;
+
+        if RAW_TEXT_ELEMENTS.contains(&tag.tag_name.as_str()) {
+          if !tag.self_closing {
+            rest = skip_raw_text(rest, &tag.tag_name);
+          }
+          continue;
+        }
+
+        let element = OpenElement {
+          tag_name: tag.tag_name,
+          attributes: tag.attributes,
+          children: Vec::new(),
+        };
+
+        if tag.self_closing || VOID_ELEMENTS.contains(&element.tag_name.as_str()) {
+          self.finish(element)?;
+        } else {
+          self.stack.push(element);
+        }
+
+        continue;
+      }
+
+      // A `<` that doesn't start a tag is text.
+      let first_len = rest.chars().next().map_or(1, char::len_utf8);
+      let end = rest[first_len..]
+        .find('<')
+        .map_or(rest.len(), |end| end + first_len);
+      self.text(&rest[..end]);
+      rest = &rest[end..];
+    }
+
+    while let Some(element) = self.stack.pop() {
+      self.finish(element)?;
+    }
+
+    Ok(())
+  }
+
+  fn children(&mut self) -> &mut Vec<Node> {
+    match self.stack.last_mut() {
+      Some(element) => &mut element.children,
+      None => &mut self.root,
+    }
+  }
+
+  fn text(&mut self, text: &str) {
+    if text.trim().is_empty() && text.contains('\n') {
+      return;
+    }
+
+    let text = decode_entities(text).into_owned();
+    self.children().push(Node::text(text));
+  }
+
+  /// Closes the innermost open element named `tag_name` and every element opened inside it.
+  fn close(&mut self, tag_name: &str) -> Result<(), HtmlImportError> {
+    let Some(index) = self
+      .stack
+      .iter()
+      .rposition(|element| element.tag_name == tag_name)
+    else {
+      return Ok(());
+    };
+
+    while self.stack.len() > index {
+      let Some(element) = self.stack.pop() else {
+        break;
+      };
+      self.finish(element)?;
+    }
+
+    Ok(())
+  }
+
+  /// Converts a closed element into nodes appended to its parent.
+  fn finish(&mut self, element: OpenElement) -> Result<(), HtmlImportError> {
+    let tag_name = element.tag_name.as_str();
+
+    if HIDDEN_ELEMENTS.contains(&tag_name) {
+      return Ok(());
+    }
+
+    if TRANSPARENT_ELEMENTS.contains(&tag_name) {
+      self.children().extend(element.children);
+      return Ok(());
+    }
+
+    let node = element_node(element)?;
+    self.children().push(node);
+
+    Ok(())
+  }
+}
+
+struct StartTag {
+  tag_name: String,
+  attributes: Vec<(String, String)>,
+  self_closing: bool,
+}
+
+/// Parses a start tag after its `<`, returning the tag and the input following it.
+fn parse_start_tag(input: &str) -> (StartTag, &str) {
+  let name_end = input
+    .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+    .unwrap_or(input.len());
+
+  let mut tag = StartTag {
+    tag_name: input[..name_end].to_ascii_lowercase(),
+    attributes: Vec::new(),
+    self_closing: false,
+  };
+  let mut rest = &input[name_end..];
+
+  loop {
+    rest = rest.trim_start();
+
+    if let Some(after) = rest.strip_prefix('>') {
+      return (tag, after);
+    }
+
+    if let Some(after) = rest.strip_prefix("/>") {
+      tag.self_closing = true;
+      return (tag, after);
+    }
+
+    if let Some(after) = rest.strip_prefix('/') {
+      rest = after;
+      continue;
+    }
+
+    if rest.is_empty() {
+      return (tag, rest);
+    }
+
+    let name_end = rest
+      .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/'))
+      .unwrap_or(rest.len());
+    let name = rest[..name_end].to_ascii_lowercase();
+    rest = rest[name_end..].trim_start();
+
+    let mut value = String::new();
+    if let Some(after) = rest.strip_prefix('=') {
+      let after = after.trim_start();
+
+      let (raw, remaining) = match after.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+          let quoted = &after[1..];
+          let end = quoted.find(quote).unwrap_or(quoted.len());
+          (&quoted[..end], quoted.get(end + 1..).unwrap_or_default())
+        }
+        _ => {
+          let end = after
+            .find(|c: char| c.is_whitespace() || c == '>')
+            .unwrap_or(after.len());
+          (&after[..end], &after[end..])
+        }
+      };
+
+      value = decode_entities(raw).into_owned();
+      rest = remaining;
+    }
+
+    tag.attributes.push((name, value));
+  }
+}
+
+/// Skips the content of a raw text element up to and including its end tag.
+fn skip_raw_text<'a>(input: &'a str, tag_name: &str) -> &'a str {
+  let lowercase = input.to_ascii_lowercase();
+  let end_tag = format!("</{tag_name}");
+
+  let Some(start) = lowercase.find(&end_tag) else {
+    return "";
+  };
+
+  let after = &input[start..];
+  after.find('>').map_or("", |end| &after[end + 1..])
+}
+
+fn element_node(element: OpenElement) -> Result<Node, HtmlImportError> {
+  let OpenElement {
+    tag_name,
+    attributes,
+    children,
+  } = element;
+
+  let mut metadata = NodeMetadata {
+    preset: preset_css(&tag_name)
+      .map(|css| Style::from(StyleDeclarationBlock::from_str(css).unwrap_or_default())),
+    ..Default::default()
+  };
+  let mut tw = Vec::new();
+  let mut src = None;
+  let mut width = None;
+  let mut height = None;
+  let mut extra = BTreeMap::new();
+
+  for (name, value) in attributes {
+    match name.as_str() {
+      "style" => {
+        let declarations = StyleDeclarationBlock::from_str(&value).map_err(|source| {
+          HtmlImportError::InvalidStyle {
+            tag_name: tag_name.as_str().into(),
+            source,
+          }
+        })?;
+        metadata.style = Some(declarations.into());
+      }
+      "class" | "classname" => {
+        tw.push(value.clone());
+        metadata.class_name = Some(value.into());
+      }
+      "tw" => tw.push(value),
+      "id" => metadata.id = Some(value.into()),
+      "src" if tag_name == "img" => src = Some(value),
+      "width" if tag_name == "img" => width = parse_dimension(&value),
+      "height" if tag_name == "img" => height = parse_dimension(&value),
+      _ => {
+        extra.insert(name.into_boxed_str(), value.into_boxed_str());
+      }
+    }
+  }
+
+  if !tw.is_empty() {
+    metadata.tw = TailwindValues::from_str(&tw.join(" ")).ok();
+  }
+  if !extra.is_empty() {
+    metadata.attributes = Some(extra);
+  }
+
+  let kind = match src {
+    Some(src) => NodeKind::Image(ImageData {
+      src: src.into(),
+      width,
+      height,
+    }),
+    None => NodeKind::Container { children },
+  };
+
+  metadata.tag_name = Some(tag_name.into_boxed_str());
+
+  Ok(Node { metadata, kind })
+}
+
+/// Parses a `width` or `height` attribute, which is in pixels with an optional `px` unit.
+fn parse_dimension(value: &str) -> Option<f32> {
+  let value = value.trim();
+  value
+    .strip_suffix("px")
+    .unwrap_or(value)
+    .parse()
+    .ok()
+    .filter(|value: &f32| value.is_finite() && *value >= 0.0)
+}
+
+fn decode_entities(text: &str) -> Cow<'_, str> {
+  if !text.contains('&') {
+    return Cow::Borrowed(text);
+  }
+
+  let mut decoded = String::with_capacity(text.len());
+  let mut rest = text;
+
+  while let Some(start) = rest.find('&') {
+    decoded.push_str(&rest[..start]);
+    rest = &rest[start..];
+
+    let entity = rest[1..]
+      .find(';')
+      .filter(|&end| end <= 10)
+      .and_then(|end| Some((decode_entity(&rest[1..=end])?, end + 2)));
+
+    match entity {
+      Some((c, length)) => {
+        decoded.push(c);
+        rest = &rest[length..];
+      }
+      None => {
+        decoded.push('&');
+        rest = &rest[1..];
+      }
+    }
+  }
+
+  decoded.push_str(rest);
+  Cow::Owned(decoded)
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+  if let Some(code) = entity.strip_prefix('#') {
+    let code = match code.strip_prefix(['x', 'X']) {
+      Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+      None => code.parse().ok()?,
+    };
+
+    return char::from_u32(code);
+  }
+
+  Some(match entity {
+    "amp" => '&',
+    "lt" => '<',
+    "gt" => '>',
+    "quot" => '"',
+    "apos" => '\'',
+    "nbsp" => '\u{a0}',
+    "copy" => '©',
+    "reg" => '®',
+    "trade" => '™',
+    "hellip" => '…',
+    "mdash" => '—',
+    "ndash" => '–',
+    "middot" => '·',
+    "bull" => '•',
+    _ => return None,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(html: &str) -> Node {
+    let node = html_to_node(html);
+    assert!(node.is_ok(), "{node:?}");
+    let Ok(node) = node else { unreachable!() };
+    node
+  }
+
+  fn children(node: &Node) -> &[Node] {
+    node.children_ref().unwrap_or_default()
+  }
+
+  fn text(node: &Node) -> Option<&str> {
+    match &node.kind {
+      NodeKind::Text(text) => Some(&text.text),
+      _ => None,
+    }
+  }
+
+  #[test]
+  fn presets_parse() {
+    for tag_name in [
+      "div",
+      "p",
+      "blockquote",
+      "center",
+      "hr",
+      "h1",
+      "h2",
+      "h3",
+      "h4",
+      "h5",
+      "h6",
+      "u",
+      "strong",
+      "i",
+      "code",
+      "pre",
+      "mark",
+      "big",
+      "small",
+      "s",
+    ] {
+      let Some(css) = preset_css(tag_name) else {
+        unreachable!()
+      };
+      assert!(StyleDeclarationBlock::from_str(css).is_ok(), "{css}");
+    }
+  }
+
+  #[test]
+  fn converts_elements_and_attributes() {
+    let node = parse(
+      r#"<div id="card" class="flex p-4 card" style="color: red" data-kind="og">
+        <p>Hello &amp; <span>welcome</span></p>
+        <img src="https://example.com/a.png" width="64" height="32px">
+      </div>"#,
+    );
+
+    assert_eq!(node.metadata.tag_name.as_deref(), Some("div"));
+    assert_eq!(node.metadata.id.as_deref(), Some("card"));
+    assert_eq!(node.metadata.class_name.as_deref(), Some("flex p-4 card"));
+    assert!(node.metadata.style.is_some());
+    assert!(node.metadata.preset.is_some());
+    assert_eq!(
+      node.metadata.tw.as_ref().map(|tw| tw.iter().count()),
+      Some(2)
+    );
+    assert_eq!(
+      node
+        .metadata
+        .attributes
+        .as_ref()
+        .and_then(|attributes| attributes.get("data-kind"))
+        .map(AsRef::as_ref),
+      Some("og")
+    );
+
+    let [paragraph, image] = children(&node) else {
+      unreachable!()
+    };
+
+    assert_eq!(paragraph.metadata.tag_name.as_deref(), Some("p"));
+    assert_eq!(text(&children(paragraph)[0]), Some("Hello & "));
+    assert_eq!(
+      children(paragraph)[1].metadata.tag_name.as_deref(),
+      Some("span")
+    );
+
+    let NodeKind::Image(image) = &image.kind else {
+      unreachable!()
+    };
+    assert_eq!(&*image.src, "https://example.com/a.png");
+    assert_eq!((image.width, image.height), (Some(64.0), Some(32.0)));
+  }
+
+  #[test]
+  fn drops_document_wrappers_and_non_content() {
+    let node = parse(
+      "<!doctype html><html><head><title>x</title></head><body><!-- note --><script>if (a < b) {}</script><style>p { color: red }</style><p>Only</p></body></html>",
+    );
+
+    assert_eq!(node.metadata.tag_name.as_deref(), Some("p"));
+    assert_eq!(children(&node).len(), 1);
+  }
+
+  #[test]
+  fn recovers_from_unbalanced_markup() {
+    let node = parse("<div><span>a<b>b</span></i>c<div/>d");
+
+    let [span, c, empty, d] = children(&node) else {
+      unreachable!()
+    };
+
+    assert_eq!(children(span).len(), 2);
+    assert_eq!(text(c), Some("c"));
+    assert!(children(empty).is_empty());
+    assert_eq!(text(d), Some("d"));
+  }
+
+  #[test]
+  fn wraps_multiple_top_level_nodes() {
+    let node = parse("<p>a</p> <p>b</p>");
+
+    assert_eq!(node.metadata.tag_name, None);
+    assert_eq!(children(&node).len(), 3);
+  }
+
+  #[test]
+  fn parses_non_ascii_text() {
+    assert_eq!(text(&parse("é")), Some("é"));
+
+    let node = parse("日本<b>x</b>");
+    let [japanese, bold] = children(&node) else {
+      unreachable!()
+    };
+
+    assert_eq!(text(japanese), Some("日本"));
+    assert_eq!(text(&children(bold)[0]), Some("x"));
+  }
+
+  #[test]
+  fn decodes_entities() {
+    assert_eq!(
+      decode_entities("&lt;a&gt; &#169; &#x1F600; &unknown; & x"),
+      "<a> © 😀 &unknown; & x"
+    );
+  }
+
+  #[test]
+  fn reports_invalid_styles() {
+    assert!(matches!(
+      html_to_node(r#"<div style="color: {"></div>"#),
+      Err(HtmlImportError::InvalidStyle { .. })
+    ));
+  }
+}
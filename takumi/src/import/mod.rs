@@ -0,0 +1,3 @@
+mod html;
+
+pub use self::html::html_to_node;
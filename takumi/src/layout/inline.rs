@@ -1,6 +1,6 @@
 use std::{borrow::Cow, ops::Range};
 
-use parley::{InlineBox, PositionedLayoutItem, TextStyle};
+use parley::{InlineBox, PositionedLayoutItem, StyleProperty, TextStyle, TreeBuilder};
 use taffy::{AvailableSpace, Layout, Rect, Size};
 
 use crate::{
@@ -8,14 +8,17 @@ use crate::{
   layout::{
     node::Node,
     style::{
-      Color, FontSynthesis, ResolvedVerticalAlign, SizedFontStyle, SizedTextDecorationThickness,
-      TextDecorationLines, TextDecorationSkipInk, TextOverflow, TextWrapStyle, VerticalAlign,
+      Color, FontSynthesis, FontVariantCaps, PercentageNumber, ResolvedVerticalAlign,
+      SizedFontStyle, SizedTextDecorationThickness, TabSize, TextDecorationLines,
+      TextDecorationSkipInk, TextDecorationStyle, TextOverflow, TextWrapMode, TextWrapStyle,
+      VerticalAlign, WhiteSpaceCollapse,
     },
     tree::RenderNode,
   },
   rendering::{
-    MaxHeight, RenderContext, apply_text_transform, apply_white_space_collapse, make_balanced_text,
-    make_pretty_text,
+    MaxHeight, RenderContext, apply_line_break, apply_synthetic_small_caps, apply_tab_size,
+    apply_text_autospace, apply_text_transform, apply_url_breaks, apply_white_space_collapse,
+    floor_grapheme_boundary, make_balanced_text, make_pretty_text, text_autospace_spacers,
   },
 };
 
@@ -32,6 +35,7 @@ pub(crate) struct InlineBoxItem<'c, 'g> {
   pub(crate) padding: Rect<f32>,
   pub(crate) border: Rect<f32>,
   pub(crate) vertical_align: ResolvedVerticalAlign,
+  pub(crate) opacity_group: Option<usize>,
 }
 
 impl From<&InlineBoxItem<'_, '_>> for Layout {
@@ -54,7 +58,8 @@ pub(crate) enum ProcessedInlineSpan<'c, 'g> {
     span_id: u64,
     byte_range: Range<usize>,
     text: String,
-    style: SizedFontStyle<'c>,
+    style: Box<SizedFontStyle<'c>>,
+    opacity_group: Option<usize>,
   },
   Box(InlineBoxItem<'c, 'g>),
 }
@@ -62,49 +67,88 @@ pub(crate) enum ProcessedInlineSpan<'c, 'g> {
 pub(crate) enum InlineItem<'c, 'g> {
   RenderNode {
     render_node: &'c RenderNode<'g>,
+    opacity_group: Option<usize>,
   },
   Text {
     text: Cow<'c, str>,
     context: &'c RenderContext<'g>,
+    opacity_group: Option<usize>,
   },
 }
 
+/// An inline span with `opacity` below 1, whose content is painted into one layer
+/// and composited as a group.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct InlineOpacityGroup {
+  /// The enclosing group, which always has a lower index.
+  pub(crate) parent: Option<usize>,
+  pub(crate) opacity: PercentageNumber,
+}
+
 pub(crate) fn collect_inline_items<'n, 'g>(root: &'n RenderNode<'g>) -> Vec<InlineItem<'n, 'g>> {
+  collect_inline_items_with_opacity_groups(root).0
+}
+
+/// Collects the inline items along with the opacity groups they are painted into.
+pub(crate) fn collect_inline_items_with_opacity_groups<'n, 'g>(
+  root: &'n RenderNode<'g>,
+) -> (Vec<InlineItem<'n, 'g>>, Vec<InlineOpacityGroup>) {
   let mut items = Vec::new();
-  collect_inline_items_impl(root, 0, &mut items);
-  items
+  let mut opacity_groups = Vec::new();
+  collect_inline_items_impl(root, 0, None, &mut items, &mut opacity_groups);
+  (items, opacity_groups)
 }
 
 fn collect_inline_items_impl<'n, 'g>(
   node: &'n RenderNode<'g>,
   depth: usize,
+  mut opacity_group: Option<usize>,
   items: &mut Vec<InlineItem<'n, 'g>>,
+  opacity_groups: &mut Vec<InlineOpacityGroup>,
 ) {
+  // Atomic containers composite their own opacity when their subtree is rendered.
   if depth > 0 && node.is_inline_atomic_container() {
-    items.push(InlineItem::RenderNode { render_node: node });
+    items.push(InlineItem::RenderNode {
+      render_node: node,
+      opacity_group,
+    });
     return;
   }
 
+  // The root's opacity is applied by the layer it is rendered into.
+  if depth > 0 && node.context.style.opacity.0 < 1.0 {
+    opacity_groups.push(InlineOpacityGroup {
+      parent: opacity_group,
+      opacity: node.context.style.opacity,
+    });
+    opacity_group = Some(opacity_groups.len() - 1);
+  }
+
   if let Some(text) = node.anonymous_text_content.as_deref() {
     items.push(InlineItem::Text {
       text: Cow::Borrowed(text),
       context: &node.context,
+      opacity_group,
     });
   }
 
   if let Some(inline_content) = node.node.as_ref().and_then(Node::inline_content) {
     match inline_content {
-      InlineContentKind::Box => items.push(InlineItem::RenderNode { render_node: node }),
+      InlineContentKind::Box => items.push(InlineItem::RenderNode {
+        render_node: node,
+        opacity_group,
+      }),
       InlineContentKind::Text(text) => items.push(InlineItem::Text {
         text,
         context: &node.context,
+        opacity_group,
       }),
     }
   }
 
   if let Some(children) = &node.children {
     for child in children {
-      collect_inline_items_impl(child, depth + 1, items);
+      collect_inline_items_impl(child, depth + 1, opacity_group, items, opacity_groups);
     }
   }
 }
@@ -123,7 +167,9 @@ pub(crate) struct InlineBrush {
   pub decoration_color: Color,
   pub decoration_thickness: SizedTextDecorationThickness,
   pub decoration_line: TextDecorationLines,
+  pub decoration_style: TextDecorationStyle,
   pub decoration_skip_ink: TextDecorationSkipInk,
+  pub stroke_width: f32,
   pub stroke_color: Color,
   pub font_synthesis: FontSynthesis,
   pub vertical_align: VerticalAlign,
@@ -137,7 +183,9 @@ impl Default for InlineBrush {
       decoration_color: Color::black(),
       decoration_thickness: SizedTextDecorationThickness::Value(0.0),
       decoration_line: TextDecorationLines::empty(),
+      decoration_style: TextDecorationStyle::Solid,
       decoration_skip_ink: TextDecorationSkipInk::default(),
+      stroke_width: 0.0,
       stroke_color: Color::black(),
       font_synthesis: FontSynthesis::default(),
       vertical_align: VerticalAlign::default(),
@@ -154,6 +202,81 @@ fn text_style_with_span_id<'s>(
   text_style
 }
 
+/// Spacing added by `text-autospace`, as a fraction of the font size (1/8 of an ideograph).
+const TEXT_AUTOSPACE_RATIO: f32 = 0.125;
+
+/// Pushes a text span, widening the spacers inserted by `text-autospace` and drawing
+/// `font-variant-caps` capitals at a reduced size when the font can't provide them.
+fn push_text_span(
+  builder: &mut TreeBuilder<'_, InlineBrush>,
+  global: &GlobalContext,
+  style: &SizedFontStyle<'_>,
+  span_id: Option<u64>,
+  text: &str,
+  next: Option<char>,
+) {
+  builder.push_style_span(text_style_with_span_id(style, span_id));
+
+  let caps = style.parent.font_variant_caps;
+  let synthesize_caps = caps.required_feature().is_some_and(|tag| {
+    !global
+      .font_context
+      .primary_font_has_feature(style.into(), tag)
+  });
+  let (text, caps_ranges) = if synthesize_caps {
+    apply_synthetic_small_caps(text, caps)
+  } else {
+    (Cow::Borrowed(text), Vec::new())
+  };
+
+  let spacing = [StyleProperty::LetterSpacing(
+    style.letter_spacing + style.sizing.font_size * TEXT_AUTOSPACE_RATIO,
+  )];
+  let small_caps = [StyleProperty::FontSize(
+    style.sizing.font_size * FontVariantCaps::SYNTHETIC_SCALE,
+  )];
+
+  let mut ranges: Vec<(Range<usize>, &[StyleProperty<'_, InlineBrush>])> =
+    text_autospace_spacers(&text, next, style.parent.text_autospace)
+      .into_iter()
+      .map(|range| (range, &spacing[..]))
+      .chain(
+        caps_ranges
+          .into_iter()
+          .map(|range| (range, &small_caps[..])),
+      )
+      .collect();
+  ranges.sort_by_key(|(range, _)| range.start);
+
+  let mut start = 0;
+
+  for (range, properties) in ranges {
+    builder.push_text(&text[start..range.start]);
+    builder.push_style_modification_span(properties);
+    builder.push_text(&text[range.clone()]);
+    builder.pop_style_span();
+    start = range.end;
+  }
+
+  builder.push_text(&text[start..]);
+  builder.pop_style_span();
+}
+
+/// Resolves `tab-size` to the number of space characters between tab stops.
+fn tab_stop_spaces(global: &GlobalContext, style: &SizedFontStyle) -> usize {
+  match style.parent.tab_size {
+    TabSize::Spaces(spaces) => spaces.round() as usize,
+    TabSize::Length(length) => {
+      let space_width = measure_text_width(global, style, " ");
+      if space_width <= 0.0 {
+        return 0;
+      }
+
+      (length.to_px(&style.sizing, 0.0) / space_width).round() as usize
+    }
+  }
+}
+
 fn refresh_text_span_ranges(spans: &mut [ProcessedInlineSpan<'_, '_>]) {
   let mut byte_offset = 0;
 
@@ -173,24 +296,17 @@ fn tail_text_span<'a, 'c, 'g>(
   spans: &'a [ProcessedInlineSpan<'c, 'g>],
 ) -> Option<(&'a SizedFontStyle<'c>, u64)> {
   spans.iter().rev().find_map(|span| match span {
-    ProcessedInlineSpan::Text { span_id, style, .. } => Some((style, *span_id)),
+    ProcessedInlineSpan::Text { span_id, style, .. } => Some((&**style, *span_id)),
     ProcessedInlineSpan::Box(_) => None,
   })
 }
 
-fn measure_ellipsis_width(
-  global: &GlobalContext,
-  ellipsis_style: &SizedFontStyle,
-  ellipsis_char: &str,
-) -> f32 {
-  let (mut ellipsis_layout, _) =
-    global
-      .font_context
-      .tree_builder(ellipsis_style.into(), |builder| {
-        builder.push_text(ellipsis_char);
-      });
-  ellipsis_layout.break_all_lines(None);
-  ellipsis_layout
+fn measure_text_width(global: &GlobalContext, style: &SizedFontStyle, text: &str) -> f32 {
+  let (mut layout, _) = global.font_context.tree_builder(style.into(), |builder| {
+    builder.push_text(text);
+  });
+  layout.break_all_lines(None);
+  layout
     .lines()
     .next()
     .map(|line| line.runs().map(|run| run.advance()).sum::<f32>())
@@ -258,7 +374,7 @@ fn truncation_plan<'c, 'g>(
         ProcessedInlineSpan::Text { text, .. } => {
           let len = text.len();
           if remaining <= len {
-            let safe_cut = text.floor_char_boundary(remaining.min(len));
+            let safe_cut = floor_grapheme_boundary(text, remaining);
             text_cut = Some((index, safe_cut));
             span_cut_idx = index + 1;
             break;
@@ -289,7 +405,7 @@ fn text_span_style_by_id<'a, 'c, 'g>(
       span_id: current_span_id,
       style,
       ..
-    } if *current_span_id == span_id => Some(style),
+    } if *current_span_id == span_id => Some(&**style),
     ProcessedInlineSpan::Text { .. } | ProcessedInlineSpan::Box(_) => None,
   })
 }
@@ -323,15 +439,32 @@ fn apply_truncation_plan<'c, 'g>(
   }
 }
 
-pub(crate) fn measure_inline_layout(layout: &mut InlineLayout, max_width: f32) -> Size<f32> {
+pub(crate) fn measure_inline_layout(
+  layout: &mut InlineLayout,
+  max_width: f32,
+  style: &SizedFontStyle,
+) -> Size<f32> {
+  // Spaces at the end of a line hang past it, except with preserved spaces that never wrap
+  // (`white-space: pre`), where they are part of the line.
+  let hang_trailing_spaces = style.parent.text_wrap_mode == TextWrapMode::Wrap
+    || !matches!(
+      style.parent.white_space_collapse,
+      WhiteSpaceCollapse::Preserve | WhiteSpaceCollapse::PreserveSpaces
+    );
+
   let (max_run_width, total_height) =
     layout
       .lines()
       .fold((0.0, 0.0), |(max_run_width, total_height), line| {
         let metrics = line.metrics();
+        let advance = if hang_trailing_spaces {
+          metrics.advance - metrics.trailing_whitespace
+        } else {
+          metrics.advance
+        };
 
         (
-          metrics.advance.max(max_run_width),
+          advance.max(max_run_width),
           total_height + metrics.line_height,
         )
       });
@@ -356,21 +489,50 @@ pub(crate) fn create_inline_layout<'c, 'g: 'c>(
 
   let (mut layout, text) = global.font_context.tree_builder(style.into(), |builder| {
     let mut index_pos = 0;
+    let mut column = 0;
+    let mut after_space = true;
+    let mut items = items.peekable();
 
-    for item in items {
+    while let Some(item) = items.next() {
       match item {
-        InlineItem::Text { text, context } => {
+        InlineItem::Text {
+          text,
+          context,
+          opacity_group,
+        } => {
           let span_style = context.style.to_sized_font_style(context);
           let transformed = apply_text_transform(&text, context.style.text_transform);
-          let collapsed =
-            apply_white_space_collapse(&transformed, style.parent.white_space_collapse);
+          let collapsed = apply_white_space_collapse(
+            &transformed,
+            style.parent.white_space_collapse,
+            &mut after_space,
+            items.peek().is_none(),
+          );
+          let tab_spaces = if collapsed.contains('\t') {
+            tab_stop_spaces(global, &span_style)
+          } else {
+            0
+          };
+          let collapsed = apply_tab_size(&collapsed, tab_spaces, &mut column);
+          let collapsed = apply_line_break(&collapsed, context.style.line_break);
+          let collapsed = apply_url_breaks(&collapsed, context.style.word_break);
+          let next = match items.peek() {
+            Some(InlineItem::Text { text, .. }) => text.chars().next(),
+            _ => None,
+          };
+          let collapsed = apply_text_autospace(&collapsed, next, context.style.text_autospace);
           let span_id = spans.len() as u64;
           let start = index_pos;
           let end = start + collapsed.len();
 
-          builder.push_style_span(text_style_with_span_id(&span_style, Some(span_id)));
-          builder.push_text(&collapsed);
-          builder.pop_style_span();
+          push_text_span(
+            builder,
+            global,
+            &span_style,
+            Some(span_id),
+            &collapsed,
+            next,
+          );
 
           index_pos = end;
 
@@ -378,10 +540,14 @@ pub(crate) fn create_inline_layout<'c, 'g: 'c>(
             span_id,
             byte_range: start..end,
             text: collapsed.into_owned(),
-            style: span_style,
+            style: Box::new(span_style),
+            opacity_group,
           });
         }
-        InlineItem::RenderNode { render_node } => {
+        InlineItem::RenderNode {
+          render_node,
+          opacity_group,
+        } => {
           let context = &render_node.context;
           let vertical_align = context.style.vertical_align.resolve(
             &context.sizing,
@@ -451,9 +617,11 @@ pub(crate) fn create_inline_layout<'c, 'g: 'c>(
             padding,
             border,
             vertical_align,
+            opacity_group,
           }));
 
           builder.push_inline_box(inline_box);
+          after_space = false;
         }
       }
     }
@@ -592,7 +760,7 @@ fn make_ellipsis_layout<'c, 'g: 'c>(
     let ellipsis_style = ellipsis_span_id
       .and_then(|span_id| text_span_style_by_id(spans, span_id))
       .unwrap_or(root_style);
-    let ellipsis_w = measure_ellipsis_width(global, ellipsis_style, ellipsis_char);
+    let ellipsis_w = measure_text_width(global, ellipsis_style, ellipsis_char);
 
     let plan = truncation_plan(&checkpoints, spans, (max_width - ellipsis_w).max(0.0));
     let next_ellipsis_span_id = truncated_tail_text_span_id(spans, plan.0);
@@ -611,7 +779,7 @@ fn make_ellipsis_layout<'c, 'g: 'c>(
   let (mut final_layout, _) = global
     .font_context
     .tree_builder(root_style.into(), |builder| {
-      for span in spans.iter() {
+      for (index, span) in spans.iter().enumerate() {
         match span {
           ProcessedInlineSpan::Text {
             span_id,
@@ -619,9 +787,12 @@ fn make_ellipsis_layout<'c, 'g: 'c>(
             style,
             ..
           } => {
-            builder.push_style_span(text_style_with_span_id(style, Some(*span_id)));
-            builder.push_text(text);
-            builder.pop_style_span();
+            let next = match spans.get(index + 1) {
+              Some(ProcessedInlineSpan::Text { text, .. }) => text.chars().next(),
+              _ => None,
+            };
+
+            push_text_span(builder, global, style, Some(*span_id), text, next);
           }
           ProcessedInlineSpan::Box(item) => {
             builder.push_inline_box(item.inline_box.clone());
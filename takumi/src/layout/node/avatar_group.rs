@@ -0,0 +1,265 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::layout::{
+  node::{ImageData, Node, NodeKind, NodeMetadata, deserialize_color_input},
+  style::{
+    AlignItems, BorderRadius, BorderStyle, BoxSizing, Color, ColorInput, Display, FlexDirection,
+    FlexGrow, FontSize, FontWeight, JustifyContent, Length, LineHeight, ObjectFit, Overflow, Sides,
+    SpacePair, Style, StyleDeclaration,
+  },
+};
+
+const DEFAULT_AVATAR_SIZE: f32 = 40.0;
+const DEFAULT_RING_WIDTH: f32 = 2.0;
+const DEFAULT_RING_COLOR: Color = Color([255, 255, 255, 255]);
+const DEFAULT_BADGE_COLOR: Color = Color([229, 231, 235, 255]);
+const DEFAULT_BADGE_TEXT_COLOR: Color = Color([55, 65, 81, 255]);
+
+fn default_avatar_size() -> f32 {
+  DEFAULT_AVATAR_SIZE
+}
+
+fn default_ring_width() -> f32 {
+  DEFAULT_RING_WIDTH
+}
+
+/// Variant-specific avatar group node data.
+///
+/// An avatar group expands into a row of overlapping circular images with border rings,
+/// followed by a `+K` badge when there are more sources than `max`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct AvatarGroupData {
+  pub(crate) srcs: Vec<Arc<str>>,
+  /// Diameter of each avatar in CSS pixels, defaults to 40.
+  #[serde(default = "default_avatar_size")]
+  pub(crate) size: f32,
+  /// Maximum number of avatars shown before the overflow badge.
+  pub(crate) max: Option<usize>,
+  /// How far each avatar overlaps the previous one, defaults to a quarter of `size`.
+  pub(crate) overlap: Option<f32>,
+  #[serde(default = "default_ring_width")]
+  pub(crate) ring_width: f32,
+  #[serde(default, deserialize_with = "deserialize_color_input")]
+  pub(crate) ring_color: Option<ColorInput>,
+  #[serde(default, deserialize_with = "deserialize_color_input")]
+  pub(crate) badge_color: Option<ColorInput<false>>,
+  #[serde(default, deserialize_with = "deserialize_color_input")]
+  pub(crate) badge_text_color: Option<ColorInput>,
+}
+
+impl AvatarGroupData {
+  /// Creates an avatar group showing every source.
+  pub fn new<S: Into<Arc<str>>>(srcs: impl IntoIterator<Item = S>) -> Self {
+    Self {
+      srcs: srcs.into_iter().map(Into::into).collect(),
+      size: DEFAULT_AVATAR_SIZE,
+      max: None,
+      overlap: None,
+      ring_width: DEFAULT_RING_WIDTH,
+      ring_color: None,
+      badge_color: None,
+      badge_text_color: None,
+    }
+  }
+
+  /// Sets the avatar diameter in CSS pixels.
+  pub fn with_size(mut self, size: f32) -> Self {
+    self.size = size;
+    self
+  }
+
+  /// Limits the number of avatars shown, the rest are counted in the overflow badge.
+  pub fn with_max(mut self, max: usize) -> Self {
+    self.max = Some(max);
+    self
+  }
+
+  /// Sets how far each avatar overlaps the previous one in CSS pixels.
+  pub fn with_overlap(mut self, overlap: f32) -> Self {
+    self.overlap = Some(overlap);
+    self
+  }
+
+  /// Sets the width and color of the ring around each avatar.
+  pub fn with_ring(mut self, width: f32, color: Color) -> Self {
+    self.ring_width = width;
+    self.ring_color = Some(color.into());
+    self
+  }
+
+  /// Sets the background and text colors of the overflow badge.
+  pub fn with_badge_colors(mut self, background: Color, text: Color) -> Self {
+    self.badge_color = Some(background.into());
+    self.badge_text_color = Some(text.into());
+    self
+  }
+
+  /// Expands the group into a container with one child per avatar and the overflow badge.
+  pub(crate) fn into_container(self, mut metadata: NodeMetadata) -> Node {
+    let visible = self.max.unwrap_or(usize::MAX).min(self.srcs.len());
+    let hidden = self.srcs.len() - visible;
+    let overlap = self.overlap.unwrap_or(self.size / 4.0);
+
+    let mut children = self
+      .srcs
+      .iter()
+      .take(visible)
+      .map(|src| Node {
+        metadata: NodeMetadata {
+          preset: Some(self.item_style()),
+          ..Default::default()
+        },
+        kind: NodeKind::Image(ImageData {
+          src: src.clone(),
+          width: None,
+          height: None,
+        }),
+      })
+      .collect::<Vec<_>>();
+
+    if hidden > 0 {
+      children
+        .push(Node::container([Node::text(format!("+{hidden}"))]).with_preset(self.badge_style()));
+    }
+
+    // Every item but the first is pulled over its predecessor.
+    for child in children.iter_mut().skip(1) {
+      if let Some(preset) = child.metadata.preset.as_mut() {
+        preset.push(StyleDeclaration::margin_left(Length::Px(-overlap)), false);
+      }
+    }
+
+    let mut preset = Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::flex_direction(FlexDirection::Row))
+      .with(StyleDeclaration::align_items(AlignItems::Center));
+    if let Some(author_preset) = metadata.preset.take() {
+      preset.declarations.append(author_preset.declarations);
+      preset.color_schemes = author_preset.color_schemes;
+    }
+    metadata.preset = Some(preset);
+
+    Node {
+      metadata,
+      kind: NodeKind::Container { children },
+    }
+  }
+
+  fn item_style(&self) -> Style {
+    Style::default()
+      .with(StyleDeclaration::width(Length::Px(self.size)))
+      .with(StyleDeclaration::height(Length::Px(self.size)))
+      .with(StyleDeclaration::flex_shrink(Some(FlexGrow(0.0))))
+      .with(StyleDeclaration::box_sizing(BoxSizing::BorderBox))
+      .with_border_radius(Box::new(BorderRadius(Sides::from(SpacePair::from_single(
+        Length::Percentage(50.0),
+      )))))
+      .with_border_width(Sides::from(Length::Px(self.ring_width)))
+      .with(StyleDeclaration::border_style(BorderStyle::Solid))
+      .with(StyleDeclaration::border_color(
+        self.ring_color.unwrap_or(DEFAULT_RING_COLOR.into()),
+      ))
+      .with(StyleDeclaration::object_fit(ObjectFit::Cover))
+      .with_overflow(SpacePair::from_single(Overflow::Hidden))
+  }
+
+  fn badge_style(&self) -> Style {
+    self
+      .item_style()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::align_items(AlignItems::Center))
+      .with(StyleDeclaration::justify_content(JustifyContent::Center))
+      .with(StyleDeclaration::font_size(FontSize::Length(Length::Px(
+        self.size * 0.375,
+      ))))
+      .with(StyleDeclaration::font_weight(FontWeight::from(600.0)))
+      .with(StyleDeclaration::line_height(LineHeight::Unitless(1.0)))
+      .with(StyleDeclaration::background_color(
+        self.badge_color.unwrap_or(DEFAULT_BADGE_COLOR.into()),
+      ))
+      .with(StyleDeclaration::color(
+        self
+          .badge_text_color
+          .unwrap_or(DEFAULT_BADGE_TEXT_COLOR.into()),
+      ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use super::*;
+  use crate::layout::style::StyleDeclarationBlock;
+
+  fn texts(node: &Node) -> Vec<String> {
+    let mut texts = Vec::new();
+    let mut stack = vec![node];
+
+    while let Some(node) = stack.pop() {
+      if let NodeKind::Text(text) = &node.kind {
+        texts.push(text.text.clone());
+      }
+      stack.extend(node.children_ref().into_iter().flatten());
+    }
+
+    texts
+  }
+
+  #[test]
+  fn generated_styles_match_css() {
+    let data = AvatarGroupData::new(["a"]).with_size(28.5);
+
+    assert_eq!(
+      data.item_style().declarations,
+      StyleDeclarationBlock::from_str(
+        "width: 28.5px; height: 28.5px; flex-shrink: 0; box-sizing: border-box; border-radius: 50%; border-width: 2px; border-style: solid; border-color: white; object-fit: cover; overflow: hidden"
+      )
+      .unwrap_or_default()
+    );
+  }
+
+  #[test]
+  fn shows_overflow_badge() {
+    let node = Node::avatar_group(AvatarGroupData::new(["a", "b", "c", "d", "e"]).with_max(3));
+
+    let Some(children) = node.children_ref() else {
+      unreachable!()
+    };
+
+    assert_eq!(children.len(), 4);
+    assert!(
+      children[..3]
+        .iter()
+        .all(|child| matches!(child.kind, NodeKind::Image(_)))
+    );
+    assert_eq!(texts(&node), vec!["+2"]);
+  }
+
+  #[test]
+  fn omits_badge_when_everything_fits() {
+    let node = Node::avatar_group(AvatarGroupData::new(["a", "b"]).with_max(3));
+
+    assert_eq!(node.children_ref().map(<[Node]>::len), Some(2));
+    assert!(texts(&node).is_empty());
+  }
+
+  #[test]
+  fn deserializes_as_container() {
+    let Ok(node) = serde_json::from_value::<Node>(serde_json::json!({
+      "type": "avatarGroup",
+      "srcs": ["a", "b", "c"],
+      "max": 1,
+      "ringColor": "red",
+    })) else {
+      unreachable!()
+    };
+
+    assert_eq!(node.children_ref().map(<[Node]>::len), Some(2));
+    assert_eq!(texts(&node), vec!["+2"]);
+  }
+}
@@ -0,0 +1,183 @@
+use std::hash::{Hash, Hasher};
+
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::layout::{
+  node::{Node, NodeKind},
+  style::Style,
+};
+
+impl Node {
+  /// Hashes the node tree with equivalent spellings normalized, for use as a cache key.
+  ///
+  /// Style values are compared as parsed, so `#fff` and `#ffffff` or `0` and `0px` hash the same.
+  /// Overridden declarations are ignored, declarations that don't affect each other can be in
+  /// any order, and class names can be in any order or repeated. Tag names are case-insensitive.
+  ///
+  /// Unlike [`render_hash`](crate::rendering::render_hash), nothing outside the tree is covered,
+  /// such as the viewport, stylesheets, fonts or image pixels. Hashes are stable across runs of the
+  /// same version but not across versions.
+  pub fn canonical_hash(&self) -> u64 {
+    let mut hasher = Xxh3::new();
+    let mut stack = vec![self];
+
+    while let Some(node) = stack.pop() {
+      node.hash_canonical(&mut hasher);
+
+      if let Some(children) = node.children_ref() {
+        stack.extend(children.iter().rev());
+      }
+    }
+
+    hasher.finish()
+  }
+
+  fn hash_canonical(&self, hasher: &mut Xxh3) {
+    let metadata = &self.metadata;
+
+    let mut class_names = metadata
+      .class_name
+      .as_deref()
+      .map(|class_name| class_name.split_whitespace().collect::<Vec<_>>())
+      .unwrap_or_default();
+    class_names.sort_unstable();
+    class_names.dedup();
+
+    match metadata.tag_name.as_deref() {
+      Some(tag_name) => {
+        hasher.write_u8(1);
+        for byte in tag_name.bytes() {
+          hasher.write_u8(byte.to_ascii_lowercase());
+        }
+        hasher.write_u8(0xff);
+      }
+      None => hasher.write_u8(0),
+    }
+    (class_names, &metadata.id, &metadata.attributes).hash(hasher);
+    hash_style(hasher, metadata.preset.as_ref());
+    hash_style(hasher, metadata.style.as_ref());
+    metadata.tw.hash(hasher);
+
+    std::mem::discriminant(&self.kind).hash(hasher);
+    match &self.kind {
+      NodeKind::Container { children } => children.len().hash(hasher),
+      NodeKind::Image(image) => {
+        image.src.hash(hasher);
+        hash_f32(hasher, image.width);
+        hash_f32(hasher, image.height);
+      }
+      NodeKind::Text(text) => {
+        text.text.hash(hasher);
+        text.value.map(f64::to_bits).hash(hasher);
+        text.format.hash(hasher);
+      }
+      NodeKind::Custom(custom) => {
+        custom.name.hash(hasher);
+        custom.drawer.is_some().hash(hasher);
+        custom
+          .plugin
+          .as_ref()
+          .map(|plugin| plugin.props())
+          .hash(hasher);
+      }
+      #[cfg(feature = "map")]
+      NodeKind::Map(map) => {
+        [map.lat, map.lng].map(f64::to_bits).hash(hasher);
+        (map.zoom, &map.tile_url, map.tile_size).hash(hasher);
+        [map.width, map.height].map(f32::to_bits).hash(hasher);
+        map.markers.len().hash(hasher);
+        for marker in &map.markers {
+          [marker.lat, marker.lng].map(f64::to_bits).hash(hasher);
+          marker.color.hash(hasher);
+        }
+      }
+      #[cfg(feature = "svg")]
+      NodeKind::Svg(svg) => {
+        svg.svg.hash(hasher);
+        hash_f32(hasher, svg.width);
+        hash_f32(hasher, svg.height);
+      }
+    }
+  }
+}
+
+fn hash_style(hasher: &mut Xxh3, style: Option<&Style>) {
+  style.is_some().hash(hasher);
+  if let Some(style) = style {
+    style.declarations.hash_canonical(hasher);
+  }
+}
+
+fn hash_f32(hasher: &mut Xxh3, value: Option<f32>) {
+  value.map(f32::to_bits).hash(hasher);
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use serde_json::json;
+
+  use crate::layout::{
+    node::{CustomData, Node},
+    style::{Style, StyleDeclarationBlock},
+  };
+
+  fn styled(css: &str) -> Node {
+    let Ok(declarations) = StyleDeclarationBlock::from_str(css) else {
+      unreachable!()
+    };
+
+    Node::container([Node::text("a")]).with_style(Style::from(declarations))
+  }
+
+  #[test]
+  fn equivalent_spellings_hash_equally() {
+    assert_eq!(
+      styled("color: #fff; padding: 0").canonical_hash(),
+      styled("padding: 0px; color: #ffffff").canonical_hash()
+    );
+    assert_eq!(
+      styled("color: red; color: blue").canonical_hash(),
+      styled("color: blue").canonical_hash()
+    );
+    assert_eq!(
+      styled("--gap: 1px  2px").canonical_hash(),
+      styled("--gap: 1px 2px").canonical_hash()
+    );
+    assert_eq!(
+      styled("").with_class_name("b a").canonical_hash(),
+      styled("").with_class_name("a  b a").canonical_hash()
+    );
+  }
+
+  #[test]
+  fn different_content_hashes_differently() {
+    assert_ne!(
+      styled("color: #fff").canonical_hash(),
+      styled("color: #000").canonical_hash()
+    );
+    assert_ne!(
+      styled("padding: 0; padding-left: 4px").canonical_hash(),
+      styled("padding-left: 4px; padding: 0").canonical_hash()
+    );
+    assert_ne!(
+      Node::text("a").canonical_hash(),
+      Node::text("b").canonical_hash()
+    );
+  }
+
+  #[test]
+  fn different_custom_nodes_hash_differently() {
+    let custom = |data| Node::custom(data).canonical_hash();
+
+    assert_ne!(
+      custom(CustomData::named("chart")),
+      custom(CustomData::named("badge"))
+    );
+    assert_ne!(
+      custom(CustomData::plugin("chart".into(), json!({ "value": 1 }))),
+      custom(CustomData::plugin("chart".into(), json!({ "value": 2 })))
+    );
+  }
+}
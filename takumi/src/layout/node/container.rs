@@ -1,16 +1,7 @@
 use std::mem::take;
 
-use serde::{Deserialize, Deserializer};
-
 use crate::layout::node::{Node, NodeKind, NodeStyleLayers};
 
-pub(crate) fn deserialize_children<'de, D>(deserializer: D) -> Result<Vec<Node>, D::Error>
-where
-  D: Deserializer<'de>,
-{
-  Option::<Vec<Node>>::deserialize(deserializer).map(Option::unwrap_or_default)
-}
-
 pub(crate) fn container_children_ref(kind: &NodeKind) -> Option<&[Node]> {
   let NodeKind::Container { children } = kind else {
     return None;
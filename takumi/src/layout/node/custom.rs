@@ -0,0 +1,482 @@
+use std::{fmt::Debug, sync::Arc};
+
+use image::{Rgba, RgbaImage};
+use serde::Deserialize;
+use serde_json::Value;
+use taffy::{AvailableSpace, Layout, Size};
+use zeno::Command;
+
+use crate::{
+  Result,
+  layout::{
+    inline::{
+      InlineContentKind, InlineLayoutStage, collect_inline_items,
+      collect_inline_items_with_opacity_groups, create_inline_layout, measure_inline_layout,
+    },
+    node::{Node, plugin::PluginNode},
+    style::{Affine, BlendMode, Color, ImageScalingAlgorithm, Length},
+    tree::RenderNode,
+  },
+  rendering::{
+    BorderProperties, Canvas, RenderContext, draw_mask,
+    inline_drawing::{InlineOpacityLayers, draw_inline_layout},
+  },
+};
+
+/// Draws the content of a custom node.
+///
+/// Implement this to add effects that the built-in nodes can't express, then attach it with
+/// [`Node::custom`] or register it by name in [`RenderOptions`](crate::rendering::RenderOptions).
+pub trait CustomDraw: Send + Sync {
+  /// Draws into the node's content box.
+  fn draw(&self, canvas: &mut CustomCanvas<'_>) -> Result<()>;
+
+  /// The natural size of the content in device pixels, used when the node has no explicit size.
+  fn intrinsic_size(&self) -> Option<Size<f32>> {
+    None
+  }
+}
+
+/// Variant-specific custom node data.
+#[derive(Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CustomData {
+  /// Name used to look up a drawer registered for the render.
+  pub(crate) name: Option<Box<str>>,
+  /// Drawer attached directly to the node, takes precedence over `name`.
+  #[serde(skip)]
+  pub(crate) drawer: Option<Arc<dyn CustomDraw>>,
+  /// Fields of a node whose `type` is handled by a [`NodePlugin`](super::NodePlugin), named by `name`.
+  #[serde(skip)]
+  pub(crate) plugin: Option<Arc<PluginNode>>,
+}
+
+impl Debug for CustomData {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("CustomData")
+      .field("name", &self.name)
+      .field("drawer", &self.drawer.is_some())
+      .field("plugin", &self.plugin.as_ref().map(|plugin| plugin.props()))
+      .finish()
+  }
+}
+
+impl CustomData {
+  /// Creates custom node data that resolves its drawer by name at render time.
+  pub fn named(name: impl Into<Box<str>>) -> Self {
+    Self {
+      name: Some(name.into()),
+      drawer: None,
+      plugin: None,
+    }
+  }
+
+  /// Creates custom node data with a drawer attached directly.
+  pub fn with_drawer(drawer: Arc<dyn CustomDraw>) -> Self {
+    Self {
+      name: None,
+      drawer: Some(drawer),
+      plugin: None,
+    }
+  }
+
+  pub(crate) fn plugin(type_name: Box<str>, props: Value) -> Self {
+    Self {
+      name: Some(type_name),
+      drawer: None,
+      plugin: Some(Arc::new(PluginNode::new(props))),
+    }
+  }
+
+  fn resolve_drawer(&self, context: &RenderContext) -> Result<Option<Arc<dyn CustomDraw>>> {
+    if let Some(drawer) = &self.drawer {
+      return Ok(Some(drawer.clone()));
+    }
+
+    let Some(name) = self.name.as_deref() else {
+      return Ok(None);
+    };
+
+    if let Some(plugin) = &self.plugin {
+      return plugin
+        .resolve_drawer(name, context.global.node_plugins())
+        .map(Some);
+    }
+
+    Ok(context.custom_drawers.get(name).cloned())
+  }
+}
+
+/// A drawing handle scoped to a custom node's content box.
+///
+/// Coordinates are in device pixels relative to the top-left of the content box. The node's
+/// transform, clipping and masking are applied to everything drawn.
+pub struct CustomCanvas<'c> {
+  canvas: &'c mut Canvas,
+  context: &'c RenderContext<'c>,
+  transform: Affine,
+  size: Size<f32>,
+}
+
+impl<'c> CustomCanvas<'c> {
+  pub(crate) fn new(
+    canvas: &'c mut Canvas,
+    context: &'c RenderContext<'c>,
+    transform: Affine,
+    size: Size<f32>,
+  ) -> Self {
+    Self {
+      canvas,
+      context,
+      transform,
+      size,
+    }
+  }
+
+  /// The size of the content box in device pixels.
+  pub fn size(&self) -> Size<f32> {
+    self.size
+  }
+
+  /// The current time injected through [`RenderOptions`](crate::rendering::RenderOptions) as a
+  /// Unix timestamp in milliseconds, if any. Prefer it over the system clock so renders stay
+  /// reproducible.
+  pub fn now_ms(&self) -> Option<u64> {
+    self.context.now_ms
+  }
+
+  /// The seed from [`RenderOptions`](crate::rendering::RenderOptions). Drawers producing noise or
+  /// other random patterns should derive them from it.
+  pub fn seed(&self) -> u64 {
+    self.context.seed
+  }
+
+  /// Fills a path with a solid color using the non-zero winding rule.
+  pub fn fill_path(&mut self, commands: &[Command], color: Color) {
+    let (mask, placement) = self.canvas.mask_memory.render(
+      commands,
+      Some(self.transform),
+      None,
+      &mut self.canvas.buffer_pool,
+    );
+
+    draw_mask(
+      &mut self.canvas.image,
+      &mask,
+      placement,
+      color,
+      BlendMode::Normal,
+      &self.canvas.constrains,
+    );
+
+    self.canvas.buffer_pool.release(mask);
+  }
+
+  /// Draws an image with its top-left corner at `(x, y)`.
+  pub fn draw_image(&mut self, image: &RgbaImage, x: f32, y: f32) {
+    self.canvas.overlay_image(
+      image,
+      BorderProperties::zero(),
+      self.transform * Affine::translation(x, y),
+      ImageScalingAlgorithm::Auto,
+      BlendMode::Normal,
+    );
+  }
+
+  /// Measures the text of `node` laid out within `max_width`, or on as few lines as possible
+  /// without it.
+  ///
+  /// The node is styled like a child of the custom node, see [`CustomCanvas::draw_text`].
+  pub fn measure_text(&self, node: Node, max_width: Option<f32>) -> Size<f32> {
+    let render_node = RenderNode::from_node(self.context, node);
+
+    measure_text_node(&render_node, max_width)
+  }
+
+  /// Draws the text of `node` with its top-left corner at `(x, y)`, wrapped within `max_width`,
+  /// and returns the size it covers.
+  ///
+  /// The node is styled like a child of the custom node, so it inherits the node's font and
+  /// color, and stylesheets apply to it. Text nodes and nested inline spans are laid out with the
+  /// same inline layout as built-in text, including decorations, shadows and strokes. Box
+  /// properties of `node` such as backgrounds and padding, and atomic inline boxes, aren't drawn.
+  pub fn draw_text(
+    &mut self,
+    node: Node,
+    x: f32,
+    y: f32,
+    max_width: Option<f32>,
+  ) -> Result<Size<f32>> {
+    let mut render_node = RenderNode::from_node(self.context, node);
+    let size = measure_text_node(&render_node, max_width);
+
+    render_node.context.transform = self.transform * Affine::translation(x, y);
+
+    let font_style = render_node
+      .context
+      .style
+      .to_sized_font_style(&render_node.context);
+    let (items, opacity_groups) = collect_inline_items_with_opacity_groups(&render_node);
+    let (inline_layout, text, spans) = create_inline_layout(
+      items.into_iter(),
+      size.map(AvailableSpace::Definite),
+      size.width,
+      None,
+      &font_style,
+      render_node.context.global,
+      InlineLayoutStage::Draw,
+    );
+
+    let mut opacity_layers = InlineOpacityLayers::new(&opacity_groups);
+    draw_inline_layout(
+      &render_node.context,
+      self.canvas,
+      Layout {
+        size,
+        ..Layout::new()
+      },
+      inline_layout,
+      &text,
+      &font_style,
+      &spans,
+      &mut opacity_layers,
+    )?;
+    opacity_layers.composite(self.canvas, &render_node.context)?;
+
+    Ok(size)
+  }
+
+  /// Blends a horizontal run of non-premultiplied RGBA pixels starting at `(x, y)`.
+  pub fn blend_span(&mut self, x: u32, y: u32, pixels: &[Rgba<u8>]) {
+    if pixels.is_empty() {
+      return;
+    }
+
+    let span = RgbaImage::from_fn(pixels.len() as u32, 1, |index, _| pixels[index as usize]);
+
+    self.draw_image(&span, x as f32, y as f32);
+  }
+}
+
+fn measure_text_node(render_node: &RenderNode<'_>, max_width: Option<f32>) -> Size<f32> {
+  let font_style = render_node
+    .context
+    .style
+    .to_sized_font_style(&render_node.context);
+  let available_width = max_width.map_or(AvailableSpace::MaxContent, AvailableSpace::Definite);
+  let max_width = max_width.unwrap_or(f32::MAX);
+
+  let (mut inline_layout, _, _) = create_inline_layout(
+    collect_inline_items(render_node).into_iter(),
+    Size {
+      width: available_width,
+      height: AvailableSpace::MaxContent,
+    },
+    max_width,
+    None,
+    &font_style,
+    render_node.context.global,
+    InlineLayoutStage::Measure,
+  );
+
+  measure_inline_layout(&mut inline_layout, max_width, &font_style)
+}
+
+pub(crate) fn custom_inline_content() -> Option<InlineContentKind<'static>> {
+  Some(InlineContentKind::Box)
+}
+
+pub(crate) fn measure_custom_node(
+  custom: &CustomData,
+  context: &RenderContext,
+  known_dimensions: Size<Option<f32>>,
+) -> Size<f32> {
+  // Unresolvable drawers are reported when drawing.
+  let intrinsic = custom
+    .resolve_drawer(context)
+    .ok()
+    .flatten()
+    .and_then(|drawer| drawer.intrinsic_size())
+    .unwrap_or(Size::ZERO);
+
+  // Inline boxes are measured without known dimensions, so fall back to absolute style sizes.
+  let style_size = |length: Length| length.resolve_to_dimension(&context.sizing).into_option();
+
+  Size {
+    width: known_dimensions
+      .width
+      .or_else(|| style_size(context.style.width))
+      .unwrap_or(intrinsic.width),
+    height: known_dimensions
+      .height
+      .or_else(|| style_size(context.style.height))
+      .unwrap_or(intrinsic.height),
+  }
+}
+
+pub(crate) fn draw_custom_node_content(
+  custom: &CustomData,
+  context: &RenderContext,
+  canvas: &mut Canvas,
+  layout: Layout,
+) -> Result<()> {
+  let Some(drawer) = custom.resolve_drawer(context)? else {
+    return Ok(());
+  };
+
+  let mut custom_canvas = CustomCanvas::new(
+    canvas,
+    context,
+    context.transform
+      * Affine::translation(
+        layout.border.left + layout.padding.left,
+        layout.border.top + layout.padding.top,
+      ),
+    layout.content_box_size(),
+  );
+
+  drawer.draw(&mut custom_canvas)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use crate::{
+    GlobalContext,
+    layout::{
+      Viewport,
+      node::Node,
+      style::{Length, Sides, Style, StyleDeclaration},
+    },
+    rendering::{RenderOptions, render},
+  };
+
+  use super::*;
+
+  struct FillRect(Color);
+
+  impl CustomDraw for FillRect {
+    fn draw(&self, canvas: &mut CustomCanvas<'_>) -> Result<()> {
+      let Size { width, height } = canvas.size();
+      canvas.fill_path(
+        &[
+          Command::MoveTo((0.0, 0.0).into()),
+          Command::LineTo((width, 0.0).into()),
+          Command::LineTo((width, height).into()),
+          Command::LineTo((0.0, height).into()),
+          Command::Close,
+        ],
+        self.0,
+      );
+      Ok(())
+    }
+
+    fn intrinsic_size(&self) -> Option<Size<f32>> {
+      Some(Size {
+        width: 4.0,
+        height: 4.0,
+      })
+    }
+  }
+
+  fn render_node(node: Node, custom_drawers: HashMap<Arc<str>, Arc<dyn CustomDraw>>) -> RgbaImage {
+    let global = GlobalContext::default();
+
+    let result = render(
+      RenderOptions::builder()
+        .viewport(Viewport::new((8, 8)))
+        .node(Node::container([node]))
+        .global(&global)
+        .custom_drawers(custom_drawers)
+        .build(),
+    );
+
+    assert!(result.is_ok());
+    let Ok(image) = result else { unreachable!() };
+
+    image
+  }
+
+  #[test]
+  fn draws_attached_drawer_inside_content_box() {
+    let node = Node::custom(CustomData::with_drawer(Arc::new(FillRect(Color([
+      255, 0, 0, 255,
+    ])))))
+    .with_style(Style::default().with_padding(Sides([Length::Px(1.0); 4])));
+
+    let image = render_node(node, HashMap::new());
+
+    // Intrinsic 4x4 content plus 1px padding on each side.
+    assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+    assert_eq!(image.get_pixel(1, 1).0, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(4, 4).0, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(5, 5).0, [0, 0, 0, 0]);
+  }
+
+  #[test]
+  fn resolves_named_drawer_from_render_options() {
+    let node = Node::custom(CustomData::named("fill")).with_style(
+      Style::default()
+        .with(StyleDeclaration::width(Length::Px(8.0)))
+        .with(StyleDeclaration::height(Length::Px(8.0))),
+    );
+    let drawers = HashMap::from([(
+      Arc::from("fill"),
+      Arc::new(FillRect(Color([0, 0, 255, 255]))) as Arc<dyn CustomDraw>,
+    )]);
+
+    let image = render_node(node, drawers);
+
+    assert_eq!(image.get_pixel(7, 7).0, [0, 0, 255, 255]);
+  }
+
+  #[test]
+  fn skips_unresolved_named_drawer() {
+    let node = Node::custom(CustomData::named("missing"));
+
+    let image = render_node(node, HashMap::new());
+
+    assert!(image.pixels().all(|pixel| pixel.0 == [0, 0, 0, 0]));
+  }
+
+  struct SeededFill;
+
+  impl CustomDraw for SeededFill {
+    fn draw(&self, canvas: &mut CustomCanvas<'_>) -> Result<()> {
+      let color = Color([
+        canvas.seed() as u8,
+        canvas.now_ms().unwrap_or_default() as u8,
+        0,
+        255,
+      ]);
+      FillRect(color).draw(canvas)
+    }
+  }
+
+  #[test]
+  fn exposes_injected_seed_and_time() {
+    let global = GlobalContext::default();
+    let node = Node::custom(CustomData::with_drawer(Arc::new(SeededFill))).with_style(
+      Style::default()
+        .with(StyleDeclaration::width(Length::Px(8.0)))
+        .with(StyleDeclaration::height(Length::Px(8.0))),
+    );
+
+    let result = render(
+      RenderOptions::builder()
+        .viewport(Viewport::new((8, 8)))
+        .node(node)
+        .global(&global)
+        .seed(7)
+        .now_ms(Some(9))
+        .build(),
+    );
+
+    assert!(result.is_ok());
+    let Ok(image) = result else { unreachable!() };
+
+    assert_eq!(image.get_pixel(4, 4).0, [7, 9, 0, 255]);
+  }
+}
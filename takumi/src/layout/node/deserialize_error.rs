@@ -0,0 +1,213 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::layout::{node::Node, style::style_property_error};
+
+/// A node tree that failed to deserialize, narrowed down to the offending value.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{}{message}", path_prefix(path))]
+#[non_exhaustive]
+pub struct NodeDeserializeError {
+  /// Location of the offending value, e.g. `children[2].style.background`. Empty for the root.
+  pub path: String,
+  /// The style property that failed to parse, as written in the node.
+  pub property: Option<String>,
+  /// The token the CSS parser stopped at, if the value was a string.
+  pub token: Option<String>,
+  /// Why the value was rejected.
+  pub message: String,
+}
+
+fn path_prefix(path: &str) -> String {
+  if path.is_empty() {
+    String::new()
+  } else {
+    format!("{path}: ")
+  }
+}
+
+impl Node {
+  /// Deserializes a node tree from JSON, pointing at the offending value when it's invalid.
+  pub fn from_json_value(value: &Value) -> Result<Self, NodeDeserializeError> {
+    Node::deserialize(value).map_err(|error| {
+      Self::locate_deserialize_error(value).unwrap_or_else(|| NodeDeserializeError {
+        path: String::new(),
+        property: None,
+        token: None,
+        message: error.to_string(),
+      })
+    })
+  }
+
+  /// Finds the first value that makes `value` fail to deserialize as a node tree.
+  ///
+  /// Returns `None` when `value` is a valid node tree. Bindings deserializing from other formats
+  /// can convert the input to JSON once they hit an error and call this to locate it.
+  pub fn locate_deserialize_error(value: &Value) -> Option<NodeDeserializeError> {
+    locate_in_node(value, &mut String::new())
+  }
+}
+
+/// Checks the children of `value` before the node itself, so the deepest failing node is reported.
+///
+/// Children deserialize on their own, so each node is checked once with its children left out.
+fn locate_in_node(value: &Value, path: &mut String) -> Option<NodeDeserializeError> {
+  let children = value.get("children").and_then(Value::as_array);
+
+  for (index, child) in children.into_iter().flatten().enumerate() {
+    let parent_len = path.len();
+    if !path.is_empty() {
+      path.push('.');
+    }
+    path.push_str(&format!("children[{index}]"));
+
+    if let Some(error) = locate_in_node(child, path) {
+      return Some(error);
+    }
+    path.truncate(parent_len);
+  }
+
+  let error = match (value, children) {
+    (Value::Object(fields), Some(_)) => {
+      let mut fields = fields
+        .iter()
+        .filter(|(key, _)| *key != "children")
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect::<Map<_, _>>();
+      fields.insert("children".to_owned(), Value::Array(Vec::new()));
+      Node::deserialize(&Value::Object(fields)).err()?
+    }
+    _ => Node::deserialize(value).err()?,
+  };
+
+  for field in ["style", "preset"] {
+    let Some(style) = value.get(field).and_then(Value::as_object) else {
+      continue;
+    };
+
+    // `dark` and `light` hold nested declarations for one color scheme.
+    let declarations = style.iter().flat_map(|(key, value)| {
+      match value
+        .as_object()
+        .filter(|_| key == "dark" || key == "light")
+      {
+        Some(scheme) => scheme
+          .iter()
+          .map(|(property, value)| (format!("{key}.{property}"), property, value))
+          .collect::<Vec<_>>(),
+        None => vec![(key.clone(), key, value)],
+      }
+    });
+
+    for (location, property, value) in declarations {
+      let Some(property_error) = style_property_error(property, value) else {
+        continue;
+      };
+
+      if !path.is_empty() {
+        path.push('.');
+      }
+      path.push_str(&format!("{field}.{location}"));
+
+      return Some(NodeDeserializeError {
+        path: std::mem::take(path),
+        property: Some(property.clone()),
+        token: property_error.token,
+        message: property_error.message,
+      });
+    }
+  }
+
+  Some(NodeDeserializeError {
+    path: std::mem::take(path),
+    property: None,
+    token: None,
+    message: error.to_string(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn locates_invalid_style_in_nested_child() {
+    let value = json!({
+      "type": "container",
+      "children": [
+        { "type": "text", "text": "ok" },
+        {
+          "type": "container",
+          "children": [
+            { "type": "container", "style": { "width": 10, "backgroundColor": "notacolor" } }
+          ]
+        }
+      ]
+    });
+
+    let Err(error) = Node::from_json_value(&value) else {
+      unreachable!()
+    };
+
+    assert_eq!(error.path, "children[1].children[0].style.backgroundColor");
+    assert_eq!(error.property.as_deref(), Some("backgroundColor"));
+    assert_eq!(error.token.as_deref(), Some("notacolor"));
+    assert!(
+      error
+        .to_string()
+        .starts_with("children[1].children[0].style.backgroundColor: invalid value")
+    );
+  }
+
+  #[test]
+  fn locates_invalid_style_in_color_scheme_block() {
+    let value = json!({
+      "type": "container",
+      "style": { "dark": { "color": "notacolor" } }
+    });
+
+    let Err(error) = Node::from_json_value(&value) else {
+      unreachable!()
+    };
+
+    assert_eq!(error.path, "style.dark.color");
+    assert_eq!(error.property.as_deref(), Some("color"));
+  }
+
+  #[test]
+  fn reports_non_style_errors_at_the_node() {
+    let value = json!({
+      "type": "container",
+      "children": [{ "type": "text", "text": 12 }]
+    });
+
+    let Some(error) = Node::locate_deserialize_error(&value) else {
+      unreachable!()
+    };
+
+    assert_eq!(error.path, "children[0]");
+    assert_eq!(error.property, None);
+    assert_eq!(
+      Node::locate_deserialize_error(&json!({ "type": "container" })),
+      None
+    );
+  }
+
+  #[test]
+  fn locates_errors_at_the_bottom_of_deep_trees() {
+    let mut value = json!({ "type": "text", "text": 12 });
+    for _ in 0..64 {
+      value = json!({ "type": "container", "children": [{ "type": "text", "text": "ok" }, value] });
+    }
+
+    let Some(error) = Node::locate_deserialize_error(&value) else {
+      unreachable!()
+    };
+
+    assert_eq!(error.path, vec!["children[1]"; 64].join("."));
+    assert_eq!(error.property, None);
+  }
+}
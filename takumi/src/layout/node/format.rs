@@ -0,0 +1,403 @@
+use serde::Deserialize;
+
+/// How the `value` of a text node is turned into its text.
+///
+/// Values are formatted at render time for the render's locale, so one template can be reused
+/// across locales. Locale aware output needs the `icu` feature, otherwise every locale is
+/// formatted like `en-US`.
+#[derive(Debug, Clone, Copy, PartialEq, Hash, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum TextFormat {
+  /// A decimal number, e.g. `1,234.5`.
+  Number(NumberFormat),
+  /// A ratio shown as a percentage, e.g. `0.25` as `25%`.
+  Percent(NumberFormat),
+  /// A Unix timestamp in milliseconds shown as a UTC date, e.g. `Jan 15, 2025`.
+  Date(DateFormat),
+}
+
+/// Options of [`TextFormat::Number`] and [`TextFormat::Percent`].
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NumberFormat {
+  /// The fewest digits shown after the decimal separator, `0` by default.
+  pub minimum_fraction_digits: Option<u8>,
+  /// The most digits shown after the decimal separator, `3` for numbers and `0` for
+  /// percentages by default.
+  pub maximum_fraction_digits: Option<u8>,
+  /// Whether to show grouping separators, `true` by default.
+  pub use_grouping: Option<bool>,
+}
+
+/// Options of [`TextFormat::Date`].
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DateFormat {
+  /// How long the date is written.
+  pub date_style: DateStyle,
+  /// Whether to show the hour and minute after the date.
+  pub time: bool,
+}
+
+/// The length of a formatted date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DateStyle {
+  /// Numeric, e.g. `1/15/25`.
+  Short,
+  /// Abbreviated month, e.g. `Jan 15, 2025`.
+  #[default]
+  Medium,
+  /// Full month, e.g. `January 15, 2025`.
+  Long,
+}
+
+/// Fraction digits beyond this are dropped.
+const MAX_FRACTION_DIGITS: u8 = 20;
+
+const MONTHS: [&str; 12] = [
+  "January",
+  "February",
+  "March",
+  "April",
+  "May",
+  "June",
+  "July",
+  "August",
+  "September",
+  "October",
+  "November",
+  "December",
+];
+
+impl TextFormat {
+  /// Formats `value` for `locale`, a BCP 47 language tag. Values without a locale are formatted
+  /// like `en-US`, unknown locales fall back to the root locale.
+  pub fn format(&self, value: f64, locale: Option<&str>) -> String {
+    match self {
+      Self::Number(format) => {
+        let (min, max) = format.fraction_digits(3);
+        format_number(value, min, max, format.use_grouping.unwrap_or(true), locale)
+      }
+      Self::Percent(format) => {
+        let (min, max) = format.fraction_digits(0);
+        let mut text = format_number(
+          value * 100.0,
+          min,
+          max,
+          format.use_grouping.unwrap_or(true),
+          locale,
+        );
+        text.push('%');
+        text
+      }
+      Self::Date(format) => {
+        let Some(civil) = CivilDateTime::from_unix_ms(value) else {
+          return format_non_finite(value);
+        };
+
+        format_date(civil, *format, locale)
+      }
+    }
+  }
+}
+
+impl NumberFormat {
+  fn fraction_digits(self, default_max: u8) -> (u8, u8) {
+    let min = self
+      .minimum_fraction_digits
+      .unwrap_or(0)
+      .min(MAX_FRACTION_DIGITS);
+    let max = self
+      .maximum_fraction_digits
+      .unwrap_or(default_max)
+      .clamp(min, MAX_FRACTION_DIGITS);
+
+    (min, max)
+  }
+}
+
+/// A UTC date and time split into its calendar fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CivilDateTime {
+  year: i32,
+  month: u8,
+  day: u8,
+  hour: u8,
+  minute: u8,
+}
+
+impl CivilDateTime {
+  fn from_unix_ms(ms: f64) -> Option<Self> {
+    if !ms.is_finite() {
+      return None;
+    }
+
+    let minutes = (ms / 60_000.0).floor() as i64;
+    let days = minutes.div_euclid(24 * 60);
+    let minute_of_day = minutes.rem_euclid(24 * 60);
+
+    // Days since the epoch to a proleptic Gregorian date, see
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+      (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    Some(Self {
+      year: i32::try_from(year).ok()?,
+      month: month as u8,
+      day: day as u8,
+      hour: (minute_of_day / 60) as u8,
+      minute: (minute_of_day % 60) as u8,
+    })
+  }
+}
+
+fn format_non_finite(value: f64) -> String {
+  if value.is_nan() {
+    "NaN".to_owned()
+  } else if value < 0.0 {
+    "-∞".to_owned()
+  } else {
+    "∞".to_owned()
+  }
+}
+
+#[cfg(feature = "icu")]
+fn parse_locale(locale: Option<&str>) -> icu_locale_core::Locale {
+  locale
+    .and_then(|locale| locale.parse().ok())
+    .unwrap_or(icu_locale_core::locale!("en-US"))
+}
+
+#[cfg(feature = "icu")]
+fn format_number(value: f64, min: u8, max: u8, grouping: bool, locale: Option<&str>) -> String {
+  use icu_decimal::{
+    DecimalFormatter,
+    input::{Decimal, FloatPrecision},
+    options::GroupingStrategy,
+  };
+
+  let Ok(mut decimal) = Decimal::try_from_f64(value, FloatPrecision::RoundTrip) else {
+    return format_non_finite(value);
+  };
+
+  decimal.round(-i16::from(max));
+  decimal.absolute.trim_end();
+  if decimal.absolute.is_zero() {
+    decimal = Decimal::from(0);
+  }
+  decimal.absolute.pad_end(-i16::from(min));
+
+  let grouping = if grouping {
+    GroupingStrategy::Auto
+  } else {
+    GroupingStrategy::Never
+  };
+
+  DecimalFormatter::try_new(parse_locale(locale).into(), grouping.into())
+    .map(|formatter| formatter.format(&decimal).to_string())
+    .unwrap_or_else(|_| format_number_en(value, min, max, grouping == GroupingStrategy::Auto))
+}
+
+#[cfg(not(feature = "icu"))]
+fn format_number(value: f64, min: u8, max: u8, grouping: bool, _locale: Option<&str>) -> String {
+  format_number_en(value, min, max, grouping)
+}
+
+fn format_number_en(value: f64, min: u8, max: u8, grouping: bool) -> String {
+  if !value.is_finite() {
+    return format_non_finite(value);
+  }
+
+  let rounded = format!("{:.*}", usize::from(max), value.abs());
+  let (integer, fraction) = rounded.split_once('.').unwrap_or((&rounded, ""));
+  let mut fraction = fraction.trim_end_matches('0').to_owned();
+  while fraction.len() < usize::from(min) {
+    fraction.push('0');
+  }
+
+  let is_zero = rounded.bytes().all(|byte| matches!(byte, b'0' | b'.'));
+  let mut text = String::with_capacity(rounded.len() + integer.len() / 3 + 1);
+
+  if value < 0.0 && !is_zero {
+    text.push('-');
+  }
+
+  for (index, digit) in integer.chars().enumerate() {
+    if grouping && index > 0 && (integer.len() - index) % 3 == 0 {
+      text.push(',');
+    }
+    text.push(digit);
+  }
+
+  if !fraction.is_empty() {
+    text.push('.');
+    text.push_str(&fraction);
+  }
+
+  text
+}
+
+#[cfg(feature = "icu")]
+fn format_date(civil: CivilDateTime, format: DateFormat, locale: Option<&str>) -> String {
+  use icu_datetime::{
+    DateTimeFormatter,
+    fieldsets::YMD,
+    input::{Date, DateTime, Time},
+  };
+
+  let (Ok(date), Ok(time)) = (
+    Date::try_new_iso(civil.year, civil.month, civil.day),
+    Time::try_new(civil.hour, civil.minute, 0, 0),
+  ) else {
+    return format_date_en(civil, format);
+  };
+  let input = DateTime { date, time };
+  let prefs = parse_locale(locale).into();
+
+  macro_rules! format_with {
+    ($field_set:expr) => {
+      DateTimeFormatter::try_new(prefs, $field_set)
+        .map(|formatter| formatter.format(&input).to_string())
+        .ok()
+    };
+  }
+
+  match (format.date_style, format.time) {
+    (DateStyle::Short, false) => format_with!(YMD::short()),
+    (DateStyle::Medium, false) => format_with!(YMD::medium()),
+    (DateStyle::Long, false) => format_with!(YMD::long()),
+    (DateStyle::Short, true) => format_with!(YMD::short().with_time_hm()),
+    (DateStyle::Medium, true) => format_with!(YMD::medium().with_time_hm()),
+    (DateStyle::Long, true) => format_with!(YMD::long().with_time_hm()),
+  }
+  .unwrap_or_else(|| format_date_en(civil, format))
+}
+
+#[cfg(not(feature = "icu"))]
+fn format_date(civil: CivilDateTime, format: DateFormat, _locale: Option<&str>) -> String {
+  format_date_en(civil, format)
+}
+
+fn format_date_en(civil: CivilDateTime, format: DateFormat) -> String {
+  let month = MONTHS[usize::from(civil.month - 1)];
+  let mut text = match format.date_style {
+    DateStyle::Short => format!(
+      "{}/{}/{:02}",
+      civil.month,
+      civil.day,
+      civil.year.rem_euclid(100)
+    ),
+    DateStyle::Medium => format!("{} {}, {}", &month[..3], civil.day, civil.year),
+    DateStyle::Long => format!("{month} {}, {}", civil.day, civil.year),
+  };
+
+  if format.time {
+    let hour = match civil.hour % 12 {
+      0 => 12,
+      hour => hour,
+    };
+    let period = if civil.hour < 12 { "AM" } else { "PM" };
+    let separator = if format.date_style == DateStyle::Long {
+      " at "
+    } else {
+      ", "
+    };
+
+    // CLDR separates the period with a narrow no-break space.
+    text.push_str(&format!(
+      "{separator}{hour}:{:02}\u{202f}{period}",
+      civil.minute
+    ));
+  }
+
+  text
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const JAN_15_2025_16_09: f64 = 1_736_957_340_000.0;
+
+  fn number(min: Option<u8>, max: Option<u8>) -> TextFormat {
+    TextFormat::Number(NumberFormat {
+      minimum_fraction_digits: min,
+      maximum_fraction_digits: max,
+      use_grouping: None,
+    })
+  }
+
+  #[test]
+  fn splits_unix_ms_into_utc_fields() {
+    assert_eq!(
+      CivilDateTime::from_unix_ms(JAN_15_2025_16_09),
+      Some(CivilDateTime {
+        year: 2025,
+        month: 1,
+        day: 15,
+        hour: 16,
+        minute: 9,
+      })
+    );
+    assert_eq!(
+      CivilDateTime::from_unix_ms(-1.0).map(|civil| (civil.year, civil.month, civil.day)),
+      Some((1969, 12, 31))
+    );
+  }
+
+  #[test]
+  fn formats_numbers_like_en_us() {
+    assert_eq!(
+      number(None, None).format(1_234_567.891_2, None),
+      "1,234,567.891"
+    );
+    assert_eq!(number(Some(2), None).format(-12.5, None), "-12.50");
+    assert_eq!(number(None, Some(0)).format(-0.2, None), "0");
+    assert_eq!(
+      TextFormat::Percent(NumberFormat::default()).format(0.256, None),
+      "26%"
+    );
+  }
+
+  #[test]
+  fn formats_dates_like_en_us() {
+    let date = |date_style, time| TextFormat::Date(DateFormat { date_style, time });
+
+    assert_eq!(
+      date(DateStyle::Short, false).format(JAN_15_2025_16_09, None),
+      "1/15/25"
+    );
+    assert_eq!(
+      date(DateStyle::Medium, true).format(JAN_15_2025_16_09, None),
+      "Jan 15, 2025, 4:09\u{202f}PM"
+    );
+    assert_eq!(
+      date(DateStyle::Long, false).format(JAN_15_2025_16_09, None),
+      "January 15, 2025"
+    );
+  }
+
+  #[cfg(feature = "icu")]
+  #[test]
+  fn formats_for_the_locale() {
+    assert_eq!(number(None, None).format(1_234.5, Some("de")), "1.234,5");
+    assert_eq!(
+      TextFormat::Date(DateFormat {
+        date_style: DateStyle::Long,
+        time: false,
+      })
+      .format(JAN_15_2025_16_09, Some("fr")),
+      "15 janvier 2025"
+    );
+  }
+}
@@ -62,21 +62,35 @@ pub(crate) fn measure_image_node(
     return Size::zero();
   };
 
-  let intrinsic_size = match &*image_source {
-    #[cfg(feature = "svg")]
-    ImageSource::Svg { tree, .. } => Size {
-      width: tree.size().width(),
-      height: tree.size().height(),
-    },
-    ImageSource::Bitmap(bitmap) => Size {
-      width: bitmap.width() as f32,
-      height: bitmap.height() as f32,
+  measure_image_source(
+    &image_source,
+    Size {
+      width: image.width,
+      height: image.height,
     },
-  };
+    context,
+    available_space,
+    known_dimensions,
+    style,
+  )
+}
+
+/// Measures a replaced element drawing `image_source`, with optional preferred dimensions in CSS
+/// pixels overriding the intrinsic size.
+pub(crate) fn measure_image_source(
+  image_source: &ImageSource,
+  preferred_dimensions: Size<Option<f32>>,
+  context: &RenderContext,
+  available_space: Size<AvailableSpace>,
+  known_dimensions: Size<Option<f32>>,
+  style: &taffy::Style,
+) -> Size<f32> {
+  let (width, height) = image_source.intrinsic_size();
+  let intrinsic_size = Size { width, height };
 
   let intrinsic_aspect_ratio =
     (intrinsic_size.height != 0.0).then_some(intrinsic_size.width / intrinsic_size.height);
-  let preferred_size = match (image.width, image.height) {
+  let preferred_size = match (preferred_dimensions.width, preferred_dimensions.height) {
     (Some(width), Some(height)) => Size { width, height },
     (Some(width), None) => Size {
       width,
@@ -92,7 +106,7 @@ pub(crate) fn measure_image_node(
     },
     (None, None) => intrinsic_size,
   }
-  .map(|value| value * context.sizing.viewport.device_pixel_ratio);
+  .map(|value| value * context.sizing.pixel_ratio());
 
   let style_known_dimensions = Size {
     width: if style.size.width.is_auto() {
@@ -119,7 +133,7 @@ pub(crate) fn measure_image_node(
   };
 
   let known_dimensions = if should_skip_intrinsic_probe_cross_axis_ratio_transfer(
-    image,
+    preferred_dimensions,
     available_space,
     known_dimensions,
     style,
@@ -158,13 +172,13 @@ pub(crate) fn draw_image_node_content(
 }
 
 fn should_skip_intrinsic_probe_cross_axis_ratio_transfer(
-  image: &ImageData,
+  preferred_dimensions: Size<Option<f32>>,
   available_space: Size<AvailableSpace>,
   known_dimensions: Size<Option<f32>>,
   style: &taffy::Style,
 ) -> bool {
-  image.width.is_none()
-    && image.height.is_none()
+  preferred_dimensions.width.is_none()
+    && preferred_dimensions.height.is_none()
     && style.size.width.is_auto()
     && style.size.height.is_auto()
     && ((matches!(
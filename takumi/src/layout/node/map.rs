@@ -0,0 +1,493 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use image::{RgbaImage, imageops};
+use serde::Deserialize;
+use taffy::{Layout, Size};
+use zeno::{Command, PathBuilder};
+
+use crate::{
+  Result,
+  error::TakumiError,
+  layout::{
+    inline::InlineContentKind,
+    node::{CustomCanvas, deserialize_color_input, resolve_image},
+    style::{Affine, BlendMode, Color, ColorInput, Length},
+  },
+  rendering::{BorderProperties, Canvas, RenderContext},
+  resources::task::FetchTaskCollection,
+};
+
+/// The tile size used by most slippy map tile servers.
+const DEFAULT_TILE_SIZE: u32 = 256;
+/// The deepest zoom level served by common tile servers.
+const MAX_ZOOM: u8 = 22;
+/// The largest map width or height, in CSS pixels.
+const MAX_MAP_SIZE: f32 = 4096.0;
+/// The largest tile size accepted from the tile URL.
+const MAX_TILE_SIZE: u32 = 1024;
+/// The most tiles a single map may fetch and draw.
+const MAX_TILES: i64 = 256;
+/// The latitude at which Web Mercator maps become square.
+const MAX_LATITUDE: f64 = 85.051_128_78;
+/// Radius of the white ring around markers, in CSS pixels.
+const MARKER_RADIUS: f32 = 6.0;
+/// Radius of the colored marker dot, in CSS pixels.
+const MARKER_DOT_RADIUS: f32 = 4.0;
+const DEFAULT_MARKER_COLOR: Color = Color([234, 67, 53, 255]);
+
+/// A pin drawn on a map node.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct MapMarker {
+  pub(crate) lat: f64,
+  pub(crate) lng: f64,
+  #[serde(default, deserialize_with = "deserialize_color_input")]
+  pub(crate) color: Option<ColorInput>,
+}
+
+impl MapMarker {
+  /// Creates a marker at the given coordinates with the default color.
+  pub fn new(lat: f64, lng: f64) -> Self {
+    Self {
+      lat,
+      lng,
+      color: None,
+    }
+  }
+
+  /// Sets the marker color.
+  pub fn with_color(mut self, color: Color) -> Self {
+    self.color = Some(color.into());
+    self
+  }
+}
+
+fn default_tile_size() -> u32 {
+  DEFAULT_TILE_SIZE
+}
+
+/// Variant-specific map node data.
+///
+/// The map is centered on `lat`/`lng` and covers `width` by `height` CSS pixels of Web Mercator
+/// tiles, which are fetched like image sources. Like image nodes with a source that isn't loaded,
+/// tiles that can't be resolved are left blank instead of failing the render.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct MapData {
+  pub(crate) lat: f64,
+  pub(crate) lng: f64,
+  pub(crate) zoom: u8,
+  /// Tile URL template with `{z}`, `{x}` and `{y}` placeholders.
+  pub(crate) tile_url: Arc<str>,
+  pub(crate) width: f32,
+  pub(crate) height: f32,
+  #[serde(default = "default_tile_size")]
+  pub(crate) tile_size: u32,
+  #[serde(default)]
+  pub(crate) markers: Vec<MapMarker>,
+}
+
+impl MapData {
+  /// Creates a map centered on the given coordinates, `width` by `height` CSS pixels in size.
+  pub fn new(
+    lat: f64,
+    lng: f64,
+    zoom: u8,
+    tile_url: impl Into<Arc<str>>,
+    width: f32,
+    height: f32,
+  ) -> Self {
+    Self {
+      lat,
+      lng,
+      zoom,
+      tile_url: tile_url.into(),
+      width,
+      height,
+      tile_size: DEFAULT_TILE_SIZE,
+      markers: Vec::new(),
+    }
+  }
+
+  /// Sets the size of the tiles served by the tile URL, defaults to 256.
+  pub fn with_tile_size(mut self, tile_size: u32) -> Self {
+    self.tile_size = tile_size;
+    self
+  }
+
+  /// Adds a marker to the map.
+  pub fn with_marker(mut self, marker: MapMarker) -> Self {
+    self.markers.push(marker);
+    self
+  }
+
+  fn zoom(&self) -> u8 {
+    self.zoom.min(MAX_ZOOM)
+  }
+
+  /// Projects coordinates to pixels relative to the map's top-left corner, in CSS pixels.
+  fn project(&self, lat: f64, lng: f64) -> (f64, f64) {
+    let (center_x, center_y) = world_pixel(self.lat, self.lng, self.zoom(), self.tile_size);
+    let (x, y) = world_pixel(lat, lng, self.zoom(), self.tile_size);
+
+    (
+      x - center_x + self.width as f64 / 2.0,
+      y - center_y + self.height as f64 / 2.0,
+    )
+  }
+
+  /// Returns the tiles covering the map with their offsets from its top-left corner.
+  ///
+  /// Maps larger than [`MAX_MAP_SIZE`], with tiles larger than [`MAX_TILE_SIZE`] or covered by
+  /// more than [`MAX_TILES`] tiles are rejected, since the node may come from untrusted JSON.
+  fn tiles(&self) -> Result<Vec<MapTile>> {
+    if self.tile_size == 0 || self.width <= 0.0 || self.height <= 0.0 {
+      return Ok(Vec::new());
+    }
+
+    let too_large = || TakumiError::MapTooLarge {
+      width: self.width,
+      height: self.height,
+      tile_size: self.tile_size,
+    };
+
+    if !(self.width <= MAX_MAP_SIZE && self.height <= MAX_MAP_SIZE)
+      || self.tile_size > MAX_TILE_SIZE
+    {
+      return Err(too_large());
+    }
+
+    let zoom = self.zoom();
+    let tile_count = 1_i64 << zoom;
+    let tile_size = self.tile_size as f64;
+    let (center_x, center_y) = world_pixel(self.lat, self.lng, zoom, self.tile_size);
+    let left = center_x - self.width as f64 / 2.0;
+    let top = center_y - self.height as f64 / 2.0;
+
+    let first_x = (left / tile_size).floor() as i64;
+    let last_x = ((left + self.width as f64) / tile_size).ceil() as i64;
+    let first_y = ((top / tile_size).floor() as i64).max(0);
+    let last_y = (((top + self.height as f64) / tile_size).ceil() as i64).min(tile_count);
+
+    if (last_x - first_x) * (last_y - first_y).max(0) > MAX_TILES {
+      return Err(too_large());
+    }
+
+    let mut tiles = Vec::new();
+
+    for y in first_y..last_y {
+      for x in first_x..last_x {
+        tiles.push(MapTile {
+          url: self.tile_url(zoom, x.rem_euclid(tile_count), y),
+          offset_x: x as f64 * tile_size - left,
+          offset_y: y as f64 * tile_size - top,
+        });
+      }
+    }
+
+    Ok(tiles)
+  }
+
+  fn tile_url(&self, zoom: u8, x: i64, y: i64) -> Arc<str> {
+    self
+      .tile_url
+      .replace("{z}", &zoom.to_string())
+      .replace("{x}", &x.to_string())
+      .replace("{y}", &y.to_string())
+      .into()
+  }
+}
+
+struct MapTile {
+  url: Arc<str>,
+  offset_x: f64,
+  offset_y: f64,
+}
+
+/// Projects coordinates to Web Mercator pixels at the given zoom level.
+fn world_pixel(lat: f64, lng: f64, zoom: u8, tile_size: u32) -> (f64, f64) {
+  let world_size = tile_size as f64 * (1_u64 << zoom) as f64;
+  let lat = lat.clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+
+  (
+    (lng + 180.0) / 360.0 * world_size,
+    (1.0 - lat.tan().asinh() / PI) / 2.0 * world_size,
+  )
+}
+
+pub(crate) fn map_collect_fetch_tasks(map: &MapData, collection: &mut FetchTaskCollection) {
+  // Oversized maps fail when they are drawn.
+  let Ok(tiles) = map.tiles() else {
+    return;
+  };
+
+  for tile in tiles {
+    if tile.url.starts_with("https://") || tile.url.starts_with("http://") {
+      collection.insert(tile.url);
+    }
+  }
+}
+
+pub(crate) fn map_inline_content() -> Option<InlineContentKind<'static>> {
+  Some(InlineContentKind::Box)
+}
+
+pub(crate) fn measure_map_node(
+  map: &MapData,
+  context: &RenderContext,
+  known_dimensions: Size<Option<f32>>,
+) -> Size<f32> {
+  let style_size = |length: Length| length.resolve_to_dimension(&context.sizing).into_option();
+  let device_pixel_ratio = context.sizing.pixel_ratio();
+
+  Size {
+    width: known_dimensions
+      .width
+      .or_else(|| style_size(context.style.width))
+      .unwrap_or(map.width * device_pixel_ratio),
+    height: known_dimensions
+      .height
+      .or_else(|| style_size(context.style.height))
+      .unwrap_or(map.height * device_pixel_ratio),
+  }
+}
+
+pub(crate) fn draw_map_node_content(
+  map: &MapData,
+  context: &RenderContext,
+  canvas: &mut Canvas,
+  layout: Layout,
+) -> Result<()> {
+  let size = layout.content_box_size();
+  let (width, height) = (size.width.round() as u32, size.height.round() as u32);
+
+  if width == 0 || height == 0 || map.width <= 0.0 || map.height <= 0.0 {
+    return Ok(());
+  }
+
+  let tiles = map.tiles()?;
+
+  // The tiles cover the map's CSS size, stretch them to the laid out content box.
+  let scale_x = size.width as f64 / map.width as f64;
+  let scale_y = size.height as f64 / map.height as f64;
+  let tile_width = (map.tile_size as f64 * scale_x).round() as u32;
+  let tile_height = (map.tile_size as f64 * scale_y).round() as u32;
+
+  let mut image = RgbaImage::new(width, height);
+
+  for tile in tiles {
+    // Missing tiles stay blank.
+    let Ok(source) = resolve_image(&tile.url, context) else {
+      continue;
+    };
+
+    let tile_image = source.render_to_rgba_image(
+      tile_width,
+      tile_height,
+      context.style.image_rendering,
+      context.current_color,
+    )?;
+
+    imageops::overlay(
+      &mut image,
+      &*tile_image,
+      (tile.offset_x * scale_x).round() as i64,
+      (tile.offset_y * scale_y).round() as i64,
+    );
+  }
+
+  let transform = context.transform
+    * Affine::translation(
+      layout.border.left + layout.padding.left,
+      layout.border.top + layout.padding.top,
+    );
+
+  let mut border = BorderProperties::from_context(context, layout.size, layout.border);
+  border.inset_by_border_width();
+
+  canvas.overlay_image(
+    &image,
+    border,
+    transform,
+    context.style.image_rendering,
+    BlendMode::Normal,
+  );
+
+  let marker_scale = context.sizing.pixel_ratio();
+  let mut markers = CustomCanvas::new(canvas, context, transform, size);
+
+  for marker in &map.markers {
+    let (x, y) = map.project(marker.lat, marker.lng);
+
+    if x < 0.0 || y < 0.0 || x > map.width as f64 || y > map.height as f64 {
+      continue;
+    }
+
+    let center = ((x * scale_x) as f32, (y * scale_y) as f32);
+    let color = marker.color.map_or(DEFAULT_MARKER_COLOR, |color| {
+      color.resolve(context.current_color)
+    });
+
+    markers.fill_path(
+      &circle(center, MARKER_RADIUS * marker_scale),
+      Color([255, 255, 255, 255]),
+    );
+    markers.fill_path(&circle(center, MARKER_DOT_RADIUS * marker_scale), color);
+  }
+
+  Ok(())
+}
+
+fn circle(center: (f32, f32), radius: f32) -> Vec<Command> {
+  let mut commands = Vec::new();
+  commands.add_circle(center, radius);
+  commands
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    GlobalContext,
+    layout::{Viewport, node::Node},
+    rendering::{RenderOptions, render},
+    resources::image::ImageSource,
+  };
+
+  use super::*;
+
+  fn tile_urls(map: &MapData) -> Vec<String> {
+    map
+      .tiles()
+      .unwrap_or_default()
+      .iter()
+      .map(|tile| tile.url.to_string())
+      .collect()
+  }
+
+  #[test]
+  fn world_zoom_uses_single_tile() {
+    let map = MapData::new(
+      0.0,
+      0.0,
+      0,
+      "https://tiles.test/{z}/{x}/{y}.png",
+      256.0,
+      256.0,
+    );
+
+    assert_eq!(tile_urls(&map), vec!["https://tiles.test/0/0/0.png"]);
+  }
+
+  #[test]
+  fn covers_viewport_around_center() {
+    // Centered on the corner shared by the four middle tiles at zoom 2.
+    let map = MapData::new(0.0, 0.0, 2, "{z}/{x}/{y}", 100.0, 100.0);
+
+    assert_eq!(tile_urls(&map), vec!["2/1/1", "2/2/1", "2/1/2", "2/2/2"]);
+
+    let tiles = map.tiles().unwrap_or_default();
+    assert_eq!((tiles[0].offset_x, tiles[0].offset_y), (-206.0, -206.0));
+  }
+
+  #[test]
+  fn wraps_longitude_and_clips_latitude() {
+    let map = MapData::new(MAX_LATITUDE, 180.0, 1, "{z}/{x}/{y}", 20.0, 20.0);
+
+    assert_eq!(tile_urls(&map), vec!["1/1/0", "1/0/0"]);
+  }
+
+  #[test]
+  fn rejects_oversized_maps() {
+    let map = |width: f32, tile_size: u32| {
+      MapData::new(0.0, 0.0, 22, "{z}/{x}/{y}", width, width).with_tile_size(tile_size)
+    };
+
+    assert!(map(1024.0, 256).tiles().is_ok());
+    assert!(matches!(
+      map(100_000.0, 256).tiles(),
+      Err(TakumiError::MapTooLarge { .. })
+    ));
+    assert!(matches!(
+      map(f32::NAN, 256).tiles(),
+      Err(TakumiError::MapTooLarge { .. })
+    ));
+    assert!(matches!(
+      map(4096.0, 1).tiles(),
+      Err(TakumiError::MapTooLarge { .. })
+    ));
+    assert!(matches!(
+      map(256.0, 65536).tiles(),
+      Err(TakumiError::MapTooLarge { .. })
+    ));
+  }
+
+  #[test]
+  fn projects_markers_relative_to_center() {
+    let map = MapData::new(0.0, 0.0, 1, "{z}/{x}/{y}", 200.0, 100.0);
+
+    assert_eq!(map.project(0.0, 0.0), (100.0, 50.0));
+    assert_eq!(map.project(0.0, 90.0), (228.0, 50.0));
+  }
+
+  #[test]
+  fn draws_tiles_and_markers() {
+    let global = GlobalContext::default();
+    global.persistent_image_store().insert(
+      "tile/0/0/0".to_owned(),
+      Arc::new(ImageSource::Bitmap(RgbaImage::from_pixel(
+        256,
+        256,
+        image::Rgba([0, 0, 255, 255]),
+      ))),
+    );
+
+    let map = MapData::new(0.0, 0.0, 0, "tile/{z}/{x}/{y}", 32.0, 32.0)
+      .with_marker(MapMarker::new(0.0, 0.0).with_color(Color([0, 255, 0, 255])));
+
+    let result = render(
+      RenderOptions::builder()
+        .viewport(Viewport::new((32, 32)))
+        .node(Node::map(map))
+        .global(&global)
+        .build(),
+    );
+
+    assert!(result.is_ok());
+    let Ok(image) = result else { unreachable!() };
+
+    assert_eq!(image.get_pixel(1, 1).0, [0, 0, 255, 255]);
+    assert_eq!(image.get_pixel(16, 16).0, [0, 255, 0, 255]);
+    assert_eq!(image.get_pixel(16, 11).0, [255, 255, 255, 255]);
+  }
+
+  #[test]
+  fn leaves_missing_tiles_blank() {
+    let global = GlobalContext::default();
+    global.persistent_image_store().insert(
+      "tile/1/0/0".to_owned(),
+      Arc::new(ImageSource::Bitmap(RgbaImage::from_pixel(
+        256,
+        256,
+        image::Rgba([0, 0, 255, 255]),
+      ))),
+    );
+
+    // At zoom 1 the four tiles meet at the center, only the top left one is loaded.
+    let map = MapData::new(0.0, 0.0, 1, "tile/{z}/{x}/{y}", 32.0, 32.0);
+
+    let result = render(
+      RenderOptions::builder()
+        .viewport(Viewport::new((32, 32)))
+        .node(Node::map(map))
+        .global(&global)
+        .build(),
+    );
+
+    let Ok(image) = result else { unreachable!() };
+
+    assert_eq!(image.get_pixel(4, 4).0, [0, 0, 255, 255]);
+    assert_eq!(image.get_pixel(28, 28).0, [0, 0, 0, 0]);
+  }
+}
@@ -0,0 +1,745 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::layout::{
+  node::{Node, NodeKind, NodeMetadata},
+  style::{Style, StyleDeclarationBlock},
+};
+
+const CONTAINER_CSS: &str = "display: flex; flex-direction: column; gap: 0.75em";
+const PARAGRAPH_CSS: &str = "display: block";
+const HEADING_CSS: [&str; 6] = [
+  "display: block; font-size: 2em; font-weight: bold; line-height: 1.2",
+  "display: block; font-size: 1.5em; font-weight: bold; line-height: 1.25",
+  "display: block; font-size: 1.25em; font-weight: bold; line-height: 1.3",
+  "display: block; font-size: 1em; font-weight: bold",
+  "display: block; font-size: 0.875em; font-weight: bold",
+  "display: block; font-size: 0.85em; font-weight: bold; opacity: 0.8",
+];
+const CODE_BLOCK_CSS: &str = "display: block; font-family: monospace; font-size: 0.875em; white-space: pre; padding: 0.75em 1em; border-radius: 0.375em; background-color: rgba(127, 127, 127, 0.12)";
+const QUOTE_CSS: &str = "display: flex; flex-direction: column; gap: 0.5em; padding-left: 1em; border-width: 0 0 0 0.25em; border-style: solid; border-color: rgba(127, 127, 127, 0.4); opacity: 0.8";
+const LIST_CSS: &str = "display: flex; flex-direction: column; gap: 0.25em";
+const LIST_ITEM_CSS: &str = "display: flex; flex-direction: row; gap: 0.5em";
+const LIST_MARKER_CSS: &str = "display: block; flex-shrink: 0";
+const LIST_CONTENT_CSS: &str = "display: block; flex-grow: 1; min-width: 0";
+const RULE_CSS: &str = "display: block; height: 1px; background-color: rgba(127, 127, 127, 0.3)";
+const STRONG_CSS: &str = "font-weight: bold";
+const EMPHASIS_CSS: &str = "font-style: italic";
+const CODE_CSS: &str = "display: inline-block; vertical-align: middle; font-family: monospace; font-size: 0.875em; padding: 0 0.25em; border-radius: 0.25em; background-color: rgba(127, 127, 127, 0.15)";
+const LINK_CSS: &str = "color: #2563eb";
+
+/// Deepest nesting of block quotes, and of inline spans, that is parsed. Markers nested deeper
+/// are kept as text, so untrusted markdown can't expand into a tree deep enough to overflow the
+/// stack.
+const MAX_NESTING_DEPTH: usize = 32;
+
+/// Variant-specific markdown node data.
+///
+/// A markdown node expands into a column of headings, paragraphs, lists, block quotes, code
+/// blocks and rules, with strong, emphasis, strikethrough, code and link spans inside them.
+/// Every generated node carries the matching HTML tag name, so stylesheets can restyle them.
+///
+/// Only this subset of CommonMark is recognized, anything else is kept as plain text. Quotes
+/// and spans nested more than 32 levels deep are kept as text too.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct MarkdownData {
+  pub(crate) markdown: String,
+}
+
+impl MarkdownData {
+  /// Creates markdown node data from the markdown source.
+  pub fn new(markdown: impl Into<String>) -> Self {
+    Self {
+      markdown: markdown.into(),
+    }
+  }
+
+  /// Expands the markdown into a container with one child per block.
+  pub(crate) fn into_container(self, mut metadata: NodeMetadata) -> Node {
+    let children = parse_blocks(&self.markdown, 0)
+      .into_iter()
+      .map(Block::into_node)
+      .collect::<Vec<_>>();
+
+    let mut preset = Style::from(parse_declarations(CONTAINER_CSS));
+    if let Some(author_preset) = metadata.preset.take() {
+      preset.declarations.append(author_preset.declarations);
+      preset.color_schemes = author_preset.color_schemes;
+    }
+    metadata.preset = Some(preset);
+
+    Node {
+      metadata,
+      kind: NodeKind::Container { children },
+    }
+  }
+}
+
+#[derive(Debug, PartialEq)]
+enum Block {
+  Heading(usize, String),
+  Paragraph(String),
+  Code(String),
+  Quote(Vec<Block>),
+  List {
+    start: Option<u64>,
+    items: Vec<String>,
+  },
+  Rule,
+}
+
+impl Block {
+  fn into_node(self) -> Node {
+    match self {
+      Block::Heading(level, text) => styled(
+        Node::container(parse_inline_nodes(&text)),
+        &format!("h{level}"),
+        HEADING_CSS[level - 1],
+      ),
+      Block::Paragraph(text) => styled(
+        Node::container(parse_inline_nodes(&text)),
+        "p",
+        PARAGRAPH_CSS,
+      ),
+      Block::Code(code) => styled(Node::container([Node::text(code)]), "pre", CODE_BLOCK_CSS),
+      Block::Quote(blocks) => styled(
+        Node::container(blocks.into_iter().map(Block::into_node).collect::<Vec<_>>()),
+        "blockquote",
+        QUOTE_CSS,
+      ),
+      Block::List { start, items } => {
+        let items = items
+          .into_iter()
+          .enumerate()
+          .map(|(index, item)| {
+            let marker = match start {
+              Some(start) => format!("{}.", start + index as u64),
+              None => "•".to_owned(),
+            };
+
+            styled(
+              Node::container([
+                styled(Node::text(marker), "span", LIST_MARKER_CSS),
+                styled(
+                  Node::container(parse_inline_nodes(&item)),
+                  "div",
+                  LIST_CONTENT_CSS,
+                ),
+              ]),
+              "li",
+              LIST_ITEM_CSS,
+            )
+          })
+          .collect::<Vec<_>>();
+
+        let tag_name = if start.is_some() { "ol" } else { "ul" };
+        styled(Node::container(items), tag_name, LIST_CSS)
+      }
+      Block::Rule => styled(Node::container([]), "hr", RULE_CSS),
+    }
+  }
+}
+
+#[derive(Debug, PartialEq)]
+enum Inline {
+  Text(String),
+  Strong(Vec<Inline>),
+  Emphasis(Vec<Inline>),
+  Strikethrough(Vec<Inline>),
+  Code(String),
+  Link(Vec<Inline>),
+}
+
+impl Inline {
+  /// Converts the span into a node, drawing `decoration` lines under its text.
+  ///
+  /// Text decorations aren't propagated from inline containers to their text, so the lines of
+  /// every enclosing link and strikethrough are set on the text nodes themselves.
+  fn into_node(self, decoration: &[&str]) -> Node {
+    match self {
+      Inline::Text(text) if decoration.is_empty() => Node::text(text),
+      Inline::Text(text) => Node::text(text).with_preset(
+        parse_declarations(&format!("text-decoration-line: {}", decoration.join(" "))).into(),
+      ),
+      Inline::Strong(children) => {
+        styled(inline_container(children, decoration), "strong", STRONG_CSS)
+      }
+      Inline::Emphasis(children) => {
+        styled(inline_container(children, decoration), "em", EMPHASIS_CSS)
+      }
+      Inline::Strikethrough(children) => Node::container(inline_nodes(
+        children,
+        &[decoration, &["line-through"]].concat(),
+      ))
+      .with_tag_name("del"),
+      Inline::Code(code) => styled(Node::container([Node::text(code)]), "code", CODE_CSS),
+      Inline::Link(children) => styled(
+        Node::container(inline_nodes(
+          children,
+          &[decoration, &["underline"]].concat(),
+        )),
+        "a",
+        LINK_CSS,
+      ),
+    }
+  }
+}
+
+fn inline_nodes(children: Vec<Inline>, decoration: &[&str]) -> Vec<Node> {
+  children
+    .into_iter()
+    .map(|child| child.into_node(decoration))
+    .collect()
+}
+
+fn inline_container(children: Vec<Inline>, decoration: &[&str]) -> Node {
+  Node::container(inline_nodes(children, decoration))
+}
+
+fn parse_inline_nodes(text: &str) -> Vec<Node> {
+  inline_nodes(parse_inlines(text, 0), &[])
+}
+
+fn styled(node: Node, tag_name: &str, css: &str) -> Node {
+  node
+    .with_tag_name(tag_name)
+    .with_preset(parse_declarations(css).into())
+}
+
+/// Parses declarations of the built-in presets, which are always valid.
+fn parse_declarations(css: &str) -> StyleDeclarationBlock {
+  StyleDeclarationBlock::from_str(css).unwrap_or_default()
+}
+
+/// Parses the blocks of `source`, nested `depth` block quotes deep.
+fn parse_blocks(source: &str, depth: usize) -> Vec<Block> {
+  let mut blocks = Vec::new();
+  let mut paragraph = Vec::new();
+  let mut lines = source.lines().peekable();
+
+  while let Some(line) = lines.next() {
+    let trimmed = line.trim();
+
+    if trimmed.is_empty() {
+      flush_paragraph(&mut paragraph, &mut blocks);
+      continue;
+    }
+
+    if let Some(fence) = code_fence(trimmed) {
+      flush_paragraph(&mut paragraph, &mut blocks);
+
+      let mut code = Vec::new();
+      for line in lines.by_ref() {
+        if line.trim().starts_with(fence) {
+          break;
+        }
+        code.push(line);
+      }
+
+      blocks.push(Block::Code(code.join("\n")));
+      continue;
+    }
+
+    if let Some((level, text)) = heading(trimmed) {
+      flush_paragraph(&mut paragraph, &mut blocks);
+      blocks.push(Block::Heading(level, text.to_owned()));
+      continue;
+    }
+
+    if is_rule(trimmed) {
+      flush_paragraph(&mut paragraph, &mut blocks);
+      blocks.push(Block::Rule);
+      continue;
+    }
+
+    if trimmed.starts_with('>') && depth < MAX_NESTING_DEPTH {
+      flush_paragraph(&mut paragraph, &mut blocks);
+
+      let mut quoted = vec![strip_quote(trimmed)];
+      while let Some(line) = lines.next_if(|line| line.trim_start().starts_with('>')) {
+        quoted.push(strip_quote(line.trim()));
+      }
+
+      blocks.push(Block::Quote(parse_blocks(&quoted.join("\n"), depth + 1)));
+      continue;
+    }
+
+    if let Some((marker, text)) = list_marker(trimmed) {
+      flush_paragraph(&mut paragraph, &mut blocks);
+
+      let mut items = vec![text.to_owned()];
+      while let Some(&line) = lines.peek() {
+        let line = line.trim();
+
+        if line.is_empty() {
+          lines.next();
+
+          // A blank line only continues the list if the next line is another item.
+          if lines
+            .peek()
+            .and_then(|line| list_marker(line.trim()))
+            .is_some_and(|(next, _)| next.same_list(marker))
+          {
+            continue;
+          }
+
+          break;
+        }
+
+        match list_marker(line) {
+          Some((next, text)) if next.same_list(marker) => items.push(text.to_owned()),
+          Some(_) => break,
+          None if code_fence(line).is_some() || heading(line).is_some() || is_rule(line) => break,
+          None => {
+            if let Some(item) = items.last_mut() {
+              item.push(' ');
+              item.push_str(line);
+            }
+          }
+        }
+
+        lines.next();
+      }
+
+      let start = match marker {
+        ListMarker::Bullet(_) => None,
+        ListMarker::Ordered(start) => Some(start),
+      };
+
+      blocks.push(Block::List { start, items });
+      continue;
+    }
+
+    paragraph.push(trimmed);
+  }
+
+  flush_paragraph(&mut paragraph, &mut blocks);
+
+  blocks
+}
+
+fn code_fence(line: &str) -> Option<&'static str> {
+  ["```", "~~~"]
+    .into_iter()
+    .find(|fence| line.starts_with(fence))
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+  let level = line.bytes().take_while(|&byte| byte == b'#').count();
+
+  if !(1..=6).contains(&level) {
+    return None;
+  }
+
+  let rest = &line[level..];
+  if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+    return None;
+  }
+
+  // Closing hashes are optional and not part of the content.
+  let text = rest.trim();
+  let without_closing = text.trim_end_matches('#');
+  if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+    return Some((level, without_closing.trim_end()));
+  }
+
+  Some((level, text))
+}
+
+fn flush_paragraph(paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>) {
+  if !paragraph.is_empty() {
+    blocks.push(Block::Paragraph(paragraph.join(" ")));
+    paragraph.clear();
+  }
+}
+
+fn is_rule(line: &str) -> bool {
+  let Some(first) = line.chars().next().filter(|c| matches!(c, '-' | '*' | '_')) else {
+    return false;
+  };
+
+  line.chars().filter(|&c| c == first).count() >= 3
+    && line.chars().all(|c| c == first || c == ' ' || c == '\t')
+}
+
+fn strip_quote(line: &str) -> &str {
+  let line = line.strip_prefix('>').unwrap_or(line);
+  line.strip_prefix(' ').unwrap_or(line)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ListMarker {
+  Bullet(char),
+  Ordered(u64),
+}
+
+impl ListMarker {
+  fn same_list(self, other: ListMarker) -> bool {
+    match (self, other) {
+      (ListMarker::Bullet(a), ListMarker::Bullet(b)) => a == b,
+      (ListMarker::Ordered(_), ListMarker::Ordered(_)) => true,
+      _ => false,
+    }
+  }
+}
+
+fn list_marker(line: &str) -> Option<(ListMarker, &str)> {
+  if let Some(bullet) = line.chars().next().filter(|c| matches!(c, '-' | '*' | '+'))
+    && let Some(text) = line[1..].strip_prefix([' ', '\t'])
+  {
+    return Some((ListMarker::Bullet(bullet), text.trim_start()));
+  }
+
+  let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+  if !(1..=9).contains(&digits) {
+    return None;
+  }
+
+  let text = line[digits..]
+    .strip_prefix(['.', ')'])?
+    .strip_prefix([' ', '\t'])?;
+
+  Some((
+    ListMarker::Ordered(line[..digits].parse().ok()?),
+    text.trim_start(),
+  ))
+}
+
+/// Parses the spans of `text`, nested `depth` spans deep.
+fn parse_inlines(text: &str, depth: usize) -> Vec<Inline> {
+  let mut inlines = Vec::new();
+  let mut buffer = String::new();
+  let mut rest = text;
+
+  let flush = |buffer: &mut String, inlines: &mut Vec<Inline>| {
+    if !buffer.is_empty() {
+      inlines.push(Inline::Text(std::mem::take(buffer)));
+    }
+  };
+
+  while let Some(c) = rest.chars().next() {
+    if c == '\\'
+      && let Some(escaped) = rest[1..].chars().next().filter(char::is_ascii_punctuation)
+    {
+      buffer.push(escaped);
+      rest = &rest[1 + escaped.len_utf8()..];
+      continue;
+    }
+
+    if c == '`' {
+      let ticks = run_length(rest, '`');
+
+      if let Some(end) = find_code_end(&rest[ticks..], ticks) {
+        flush(&mut buffer, &mut inlines);
+
+        let code = &rest[ticks..ticks + end];
+        inlines.push(Inline::Code(trim_code_span(code).to_owned()));
+        rest = &rest[ticks + end + ticks..];
+        continue;
+      }
+
+      buffer.push_str(&rest[..ticks]);
+      rest = &rest[ticks..];
+      continue;
+    }
+
+    if matches!(c, '*' | '_' | '~') && depth < MAX_NESTING_DEPTH {
+      let run = run_length(rest, c);
+      let delimiter = match (c, run) {
+        ('~', 2) => Some(2),
+        ('*' | '_', 1 | 2) => Some(run),
+        _ => None,
+      };
+
+      // `_` doesn't open emphasis inside words, such as in `snake_case`.
+      let intraword = c == '_'
+        && buffer
+          .chars()
+          .next_back()
+          .is_some_and(char::is_alphanumeric);
+
+      if let Some(length) = delimiter.filter(|_| !intraword)
+        && let Some(end) = find_closing(&rest[length..], c, length)
+      {
+        flush(&mut buffer, &mut inlines);
+
+        let children = parse_inlines(&rest[length..length + end], depth + 1);
+        inlines.push(match (c, length) {
+          ('~', _) => Inline::Strikethrough(children),
+          (_, 2) => Inline::Strong(children),
+          _ => Inline::Emphasis(children),
+        });
+        rest = &rest[length + end + length..];
+        continue;
+      }
+
+      buffer.push_str(&rest[..run]);
+      rest = &rest[run..];
+      continue;
+    }
+
+    if (c == '[' || (c == '!' && rest[1..].starts_with('['))) && depth < MAX_NESTING_DEPTH {
+      let label_start = rest.find('[').unwrap_or_default() + 1;
+
+      if let Some((label_end, link_end)) = find_link(&rest[label_start..]) {
+        flush(&mut buffer, &mut inlines);
+
+        let label = parse_inlines(&rest[label_start..label_start + label_end], depth + 1);
+        // Images can't be sized from markdown, so their alt text is shown instead.
+        if c == '!' {
+          inlines.extend(label);
+        } else {
+          inlines.push(Inline::Link(label));
+        }
+
+        rest = &rest[label_start + link_end..];
+        continue;
+      }
+    }
+
+    buffer.push(c);
+    rest = &rest[c.len_utf8()..];
+  }
+
+  flush(&mut buffer, &mut inlines);
+
+  inlines
+}
+
+fn run_length(text: &str, delimiter: char) -> usize {
+  text.chars().take_while(|&c| c == delimiter).count() * delimiter.len_utf8()
+}
+
+fn find_code_end(text: &str, ticks: usize) -> Option<usize> {
+  let mut offset = 0;
+
+  while let Some(start) = text[offset..].find('`') {
+    let start = offset + start;
+    let run = run_length(&text[start..], '`');
+
+    if run == ticks {
+      return Some(start);
+    }
+
+    offset = start + run;
+  }
+
+  None
+}
+
+fn trim_code_span(code: &str) -> &str {
+  code
+    .strip_prefix(' ')
+    .and_then(|code| code.strip_suffix(' '))
+    .filter(|code| !code.trim().is_empty())
+    .unwrap_or(code)
+}
+
+/// Finds a closing run of exactly `length` delimiters, skipping escapes and code spans.
+fn find_closing(text: &str, delimiter: char, length: usize) -> Option<usize> {
+  if text.starts_with(char::is_whitespace) {
+    return None;
+  }
+
+  let mut offset = 0;
+
+  while let Some(c) = text[offset..].chars().next() {
+    match c {
+      '\\' => offset += 1 + text[offset + 1..].chars().next().map_or(0, char::len_utf8),
+      '`' => {
+        let ticks = run_length(&text[offset..], '`');
+        offset +=
+          ticks + find_code_end(&text[offset + ticks..], ticks).map_or(0, |end| end + ticks);
+      }
+      c if c == delimiter => {
+        let run = run_length(&text[offset..], delimiter);
+        let preceded_by_space = text[..offset]
+          .chars()
+          .next_back()
+          .is_none_or(char::is_whitespace);
+
+        if run == length && !preceded_by_space {
+          return Some(offset);
+        }
+
+        offset += run;
+      }
+      c => offset += c.len_utf8(),
+    }
+  }
+
+  None
+}
+
+/// Finds the end of a link label and of the `(destination)` after it.
+fn find_link(text: &str) -> Option<(usize, usize)> {
+  let mut depth = 0;
+  let mut label_end = None;
+
+  for (index, c) in text.char_indices() {
+    match c {
+      '[' => depth += 1,
+      ']' if depth == 0 => {
+        label_end = Some(index);
+        break;
+      }
+      ']' => depth -= 1,
+      _ => {}
+    }
+  }
+
+  let label_end = label_end?;
+  let destination = text[label_end + 1..].strip_prefix('(')?;
+  let destination_end = destination.find(')')?;
+
+  Some((label_end, label_end + 2 + destination_end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn tag_names(node: &Node) -> Vec<String> {
+    node
+      .children_ref()
+      .into_iter()
+      .flatten()
+      .filter_map(|child| child.metadata.tag_name.as_deref().map(str::to_owned))
+      .collect()
+  }
+
+  #[test]
+  fn generated_styles_parse() {
+    for css in HEADING_CSS.into_iter().chain([
+      CONTAINER_CSS,
+      PARAGRAPH_CSS,
+      CODE_BLOCK_CSS,
+      QUOTE_CSS,
+      LIST_CSS,
+      LIST_ITEM_CSS,
+      LIST_MARKER_CSS,
+      LIST_CONTENT_CSS,
+      RULE_CSS,
+      STRONG_CSS,
+      EMPHASIS_CSS,
+      CODE_CSS,
+      LINK_CSS,
+    ]) {
+      let declarations = StyleDeclarationBlock::from_str(css);
+      assert!(declarations.is_ok(), "{css}");
+    }
+  }
+
+  #[test]
+  fn parses_blocks() {
+    let blocks = parse_blocks(
+      "# Release 1.2 #\n\nFirst line\nsecond line\n\n- one\n- two\n  continued\n\n3. three\n4. four\n\n> quoted\n> text\n\n---\n\n```rust\nlet a = 1;\n\nlet b = 2;\n```",
+      0,
+    );
+
+    assert_eq!(
+      blocks,
+      vec![
+        Block::Heading(1, "Release 1.2".to_owned()),
+        Block::Paragraph("First line second line".to_owned()),
+        Block::List {
+          start: None,
+          items: vec!["one".to_owned(), "two continued".to_owned()],
+        },
+        Block::List {
+          start: Some(3),
+          items: vec!["three".to_owned(), "four".to_owned()],
+        },
+        Block::Quote(vec![Block::Paragraph("quoted text".to_owned())]),
+        Block::Rule,
+        Block::Code("let a = 1;\n\nlet b = 2;".to_owned()),
+      ]
+    );
+  }
+
+  #[test]
+  fn parses_inlines() {
+    let text = |text: &str| Inline::Text(text.to_owned());
+
+    assert_eq!(
+      parse_inlines(
+        "a **bold *and em* too** `x * y` ~~gone~~ [link](https://a.b) \\*raw\\* snake_case_name",
+        0,
+      ),
+      vec![
+        text("a "),
+        Inline::Strong(vec![
+          text("bold "),
+          Inline::Emphasis(vec![text("and em")]),
+          text(" too"),
+        ]),
+        text(" "),
+        Inline::Code("x * y".to_owned()),
+        text(" "),
+        Inline::Strikethrough(vec![text("gone")]),
+        text(" "),
+        Inline::Link(vec![text("link")]),
+        text(" *raw* snake_case_name"),
+      ]
+    );
+    assert_eq!(parse_inlines("2 * 3 * 4", 0), vec![text("2 * 3 * 4")]);
+    assert_eq!(parse_inlines("![alt](a.png)", 0), vec![text("alt")]);
+  }
+
+  #[test]
+  fn keeps_markers_nested_too_deep_as_text() {
+    fn quote_depth(blocks: &[Block]) -> usize {
+      match blocks {
+        [Block::Quote(blocks)] => 1 + quote_depth(blocks),
+        _ => 0,
+      }
+    }
+
+    fn link_depth(inlines: &[Inline]) -> usize {
+      match inlines {
+        [Inline::Link(inlines)] => 1 + link_depth(inlines),
+        _ => 0,
+      }
+    }
+
+    let quotes = format!("{} hi", ">".repeat(50_000));
+    let blocks = parse_blocks(&quotes, 0);
+    assert_eq!(quote_depth(&blocks), MAX_NESTING_DEPTH);
+
+    let links = format!("{}a{}", "[".repeat(10_000), "](u)".repeat(10_000));
+    assert_eq!(link_depth(&parse_inlines(&links, 0)), MAX_NESTING_DEPTH);
+
+    let node = Node::markdown(MarkdownData::new(format!("{quotes}\n\n{links}")));
+    assert_eq!(tag_names(&node), vec!["blockquote", "p"]);
+  }
+
+  #[test]
+  fn expands_into_tagged_nodes() {
+    let node = Node::markdown(MarkdownData::new(
+      "## Title\n\nBody with **bold**\n\n1. item",
+    ));
+
+    assert_eq!(tag_names(&node), vec!["h2", "p", "ol"]);
+
+    let Some(paragraph) = node.children_ref().and_then(|children| children.get(1)) else {
+      unreachable!()
+    };
+    assert_eq!(tag_names(paragraph), vec!["strong"]);
+  }
+
+  #[test]
+  fn deserializes_as_container() {
+    let Ok(node) = serde_json::from_value::<Node>(serde_json::json!({
+      "type": "markdown",
+      "markdown": "# Hello\n\nWorld",
+    })) else {
+      unreachable!()
+    };
+
+    assert!(matches!(node.kind, NodeKind::Container { .. }));
+    assert_eq!(tag_names(&node), vec!["h1", "p"]);
+  }
+}
@@ -1,12 +1,24 @@
+mod avatar_group;
+mod canonical;
 mod container;
+mod custom;
+mod deserialize_error;
+mod format;
 mod image;
+#[cfg(feature = "map")]
+mod map;
+mod markdown;
+mod plugin;
+#[cfg(feature = "svg")]
+mod svg;
 mod text;
+mod validate;
 
 use ::image::RgbaImage;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, de::DeserializeSeed};
 use std::collections::BTreeMap;
 use std::sync::Arc;
-use taffy::{AvailableSpace, Layout, Point, Size};
+use taffy::{AvailableSpace, Layout, Point, Rect, Size};
 use zeno::Fill;
 
 use crate::{
@@ -14,18 +26,22 @@ use crate::{
   layout::{
     Viewport,
     inline::InlineContentKind,
-    style::{Affine, BackgroundClip, BlendMode, Sides, Style, tw::TailwindValues},
+    style::{
+      Affine, BackgroundClip, BlendMode, BorderAlignment, ColorInput, FromCss, Sides, Style,
+      snap_border_width, tw::TailwindValues,
+    },
   },
   rendering::{
-    BackgroundTile, BorderProperties, Canvas, RenderContext, SizedShadow,
-    collect_background_layers, rasterize_layers,
+    BackgroundTile, BorderProperties, BoxInsets, Canvas, RenderContext, SizedShadow,
+    collect_background_layers, collect_clipped_background_layers, layers_overflow,
+    rasterize_layers, viewport_area,
   },
   resources::task::FetchTaskCollection,
 };
 
 use self::{
   container::{
-    container_children_ref, deserialize_children, drop_container_children, take_container_children,
+    container_children_ref, drop_container_children, take_container_children,
     take_container_style_layers,
   },
   image::{
@@ -35,7 +51,43 @@ use self::{
   text::{draw_text_node_content, measure_text_node, take_text_style_layers, text_inline_content},
 };
 
+pub use self::avatar_group::AvatarGroupData;
+pub use self::custom::{CustomCanvas, CustomData, CustomDraw};
+use self::custom::{custom_inline_content, draw_custom_node_content, measure_custom_node};
+pub use self::deserialize_error::NodeDeserializeError;
+pub use self::format::{DateFormat, DateStyle, NumberFormat, TextFormat};
+#[cfg(feature = "map")]
+pub use self::map::{MapData, MapMarker};
+#[cfg(feature = "map")]
+use self::map::{
+  draw_map_node_content, map_collect_fetch_tasks, map_inline_content, measure_map_node,
+};
+pub use self::markdown::MarkdownData;
+use self::plugin::NodeSeed;
+pub use self::plugin::{NodePlugin, NodePluginRegistry};
+#[cfg(feature = "svg")]
+pub use self::svg::SvgData;
+#[cfg(feature = "svg")]
+use self::svg::{draw_svg_node_content, measure_svg_node, svg_inline_content};
+
 pub(crate) use self::image::resolve_image;
+pub use self::validate::{ValidationIssue, ValidationIssueKind, ValidationLimits};
+
+/// Deserializes an optional CSS color string for node data fields.
+pub(crate) fn deserialize_color_input<'de, D, const DEFAULT_CURRENT_COLOR: bool>(
+  deserializer: D,
+) -> std::result::Result<Option<ColorInput<DEFAULT_CURRENT_COLOR>>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let Some(color) = Option::<String>::deserialize(deserializer)? else {
+    return Ok(None);
+  };
+
+  ColorInput::from_str(&color)
+    .map(Some)
+    .map_err(|_| serde::de::Error::custom(format!("invalid color '{color}'")))
+}
 
 /// Shared metadata stored by every renderable node.
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -61,7 +113,24 @@ pub(crate) struct NodeMetadata {
 #[serde(rename_all = "camelCase")]
 /// Variant-specific text node data.
 pub(crate) struct TextData {
+  #[serde(default)]
   pub(crate) text: String,
+  /// A raw number that replaces `text` once formatted with `format`.
+  pub(crate) value: Option<f64>,
+  /// How `value` is formatted, a plain number by default.
+  pub(crate) format: Option<TextFormat>,
+}
+
+impl TextData {
+  /// Replaces the text with the formatted value, if the node has one.
+  pub(crate) fn format_value(&mut self, locale: Option<&str>) {
+    let format = self.format.take();
+    if let Some(value) = self.value.take() {
+      self.text = format
+        .unwrap_or(TextFormat::Number(NumberFormat::default()))
+        .format(value, locale);
+    }
+  }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -74,31 +143,46 @@ pub struct ImageData {
   pub(crate) height: Option<f32>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone)]
 /// A renderable node with shared metadata and variant-specific content.
+///
+/// Nodes with a `type` that isn't built in are drawn by the [`NodePlugin`] registered for it, and
+/// are deserialized with [`NodePluginRegistry::deserialize_node`].
 #[non_exhaustive]
 pub struct Node {
-  #[serde(flatten)]
   pub(crate) metadata: NodeMetadata,
-  #[serde(flatten)]
   pub(crate) kind: NodeKind,
 }
 
+impl<'de> Deserialize<'de> for Node {
+  fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    NodeSeed { plugins: None }.deserialize(deserializer)
+  }
+}
+
 /// Represents the nodes enum.
-#[derive(Debug, Clone, Deserialize)]
-#[serde(tag = "type", rename_all = "camelCase")]
+#[derive(Debug, Clone)]
 pub(crate) enum NodeKind {
   /// A node that contains other nodes.
   Container {
     /// The container child nodes.
-    #[serde(default, deserialize_with = "deserialize_children")]
     children: Vec<Node>,
   },
   /// A node that displays an image.
   Image(ImageData),
   /// A node that displays text.
   Text(TextData),
+  /// A node whose content is drawn by a [`CustomDraw`] implementation, including plugin nodes.
+  Custom(CustomData),
+  /// A node that displays a static map from tiles.
+  #[cfg(feature = "map")]
+  Map(MapData),
+  /// A node that displays inline SVG markup.
+  #[cfg(feature = "svg")]
+  Svg(SvgData),
 }
 
 impl Default for Node {
@@ -136,7 +220,22 @@ impl Node {
   pub fn text(text: impl Into<String>) -> Self {
     Self {
       metadata: NodeMetadata::default(),
-      kind: NodeKind::Text(TextData { text: text.into() }),
+      kind: NodeKind::Text(TextData {
+        text: text.into(),
+        ..Default::default()
+      }),
+    }
+  }
+
+  /// Creates a text node showing `value` formatted with `format` for the render's locale.
+  pub fn formatted(value: f64, format: TextFormat) -> Self {
+    Self {
+      metadata: NodeMetadata::default(),
+      kind: NodeKind::Text(TextData {
+        value: Some(value),
+        format: Some(format),
+        ..Default::default()
+      }),
     }
   }
 
@@ -148,6 +247,42 @@ impl Node {
     take_container_children(&mut self.kind)
   }
 
+  /// Creates a custom node drawn by the provided data's drawer.
+  pub fn custom(data: CustomData) -> Self {
+    Self {
+      metadata: NodeMetadata::default(),
+      kind: NodeKind::Custom(data),
+    }
+  }
+
+  /// Creates a container of overlapping circular avatars from the group data.
+  pub fn avatar_group(data: AvatarGroupData) -> Self {
+    data.into_container(NodeMetadata::default())
+  }
+
+  /// Creates a container of headings, paragraphs and lists from the markdown data.
+  pub fn markdown(data: MarkdownData) -> Self {
+    data.into_container(NodeMetadata::default())
+  }
+
+  /// Creates a map node.
+  #[cfg(feature = "map")]
+  pub fn map(data: MapData) -> Self {
+    Self {
+      metadata: NodeMetadata::default(),
+      kind: NodeKind::Map(data),
+    }
+  }
+
+  /// Creates an svg node from SVG markup.
+  #[cfg(feature = "svg")]
+  pub fn svg(data: impl Into<SvgData>) -> Self {
+    Self {
+      metadata: NodeMetadata::default(),
+      kind: NodeKind::Svg(data.into()),
+    }
+  }
+
   /// Sets the tag name and returns the updated node.
   pub fn with_tag_name(mut self, tag_name: impl Into<Box<str>>) -> Self {
     self.metadata.tag_name = Some(tag_name.into());
@@ -199,6 +334,16 @@ impl Node {
       return take_image_style_layers(self, image.width, image.height);
     }
 
+    #[cfg(feature = "map")]
+    if let NodeKind::Map(map) = &self.kind {
+      return take_image_style_layers(self, Some(map.width), Some(map.height));
+    }
+
+    #[cfg(feature = "svg")]
+    if let NodeKind::Svg(svg) = &self.kind {
+      return take_image_style_layers(self, svg.width, svg.height);
+    }
+
     take_text_style_layers(self)
   }
 
@@ -207,6 +352,11 @@ impl Node {
       NodeKind::Container { .. } => None,
       NodeKind::Image(_) => image_inline_content(&self.kind),
       NodeKind::Text(text) => text_inline_content(text),
+      NodeKind::Custom(_) => custom_inline_content(),
+      #[cfg(feature = "map")]
+      NodeKind::Map(_) => map_inline_content(),
+      #[cfg(feature = "svg")]
+      NodeKind::Svg(_) => svg_inline_content(),
     }
   }
 
@@ -223,6 +373,13 @@ impl Node {
         measure_image_node(image, context, available_space, known_dimensions, style)
       }
       NodeKind::Text(text) => measure_text_node(text, context, available_space, known_dimensions),
+      NodeKind::Custom(custom) => measure_custom_node(custom, context, known_dimensions),
+      #[cfg(feature = "map")]
+      NodeKind::Map(map) => measure_map_node(map, context, known_dimensions),
+      #[cfg(feature = "svg")]
+      NodeKind::Svg(svg) => {
+        measure_svg_node(svg, context, available_space, known_dimensions, style)
+      }
     }
   }
 
@@ -239,7 +396,11 @@ impl Node {
         }
       }
       NodeKind::Image(image) => image_collect_fetch_tasks(image, collection),
-      NodeKind::Text(_) => {}
+      #[cfg(feature = "map")]
+      NodeKind::Map(map) => map_collect_fetch_tasks(map, collection),
+      NodeKind::Text(_) | NodeKind::Custom(_) => {}
+      #[cfg(feature = "svg")]
+      NodeKind::Svg(_) => {}
     }
   }
 
@@ -267,7 +428,14 @@ impl Node {
   }
 
   pub(crate) fn is_replaced_element(&self) -> bool {
-    matches!(self.kind, NodeKind::Image(_))
+    match self.kind {
+      NodeKind::Image(_) | NodeKind::Custom(_) => true,
+      #[cfg(feature = "map")]
+      NodeKind::Map(_) => true,
+      #[cfg(feature = "svg")]
+      NodeKind::Svg(_) => true,
+      NodeKind::Container { .. } | NodeKind::Text(_) => false,
+    }
   }
 
   pub(crate) fn draw_outset_box_shadow(
@@ -360,92 +528,121 @@ impl Node {
     canvas: &mut Canvas,
     layout: Layout,
   ) -> Result<()> {
-    let mut border_radius = BorderProperties::from_context(context, layout.size, layout.border);
-
-    match context.style.background_clip {
-      BackgroundClip::BorderBox => {
-        let tiles = collect_background_layers(context, layout.size, &mut canvas.buffer_pool)?;
-
-        for tile in tiles {
-          for y in &tile.ys {
-            for x in &tile.xs {
-              canvas.overlay_image(
-                &tile.tile,
-                border_radius,
-                context.transform * Affine::translation(*x as f32, *y as f32),
-                context.style.image_rendering,
-                tile.blend_mode,
-              );
+    let runs = collect_background_layers(
+      context,
+      layout.size,
+      (&layout).into(),
+      viewport_area(context.transform, canvas.size()),
+      &mut canvas.buffer_pool,
+    )?;
+
+    for (clip, layers) in runs {
+      let mut border_radius = BorderProperties::from_context(context, layout.size, layout.border);
+
+      match clip {
+        // Tiles reaching outside the box, like those of fixed layers, are clipped to it first.
+        BackgroundClip::BorderBox if layers_overflow(&layers, layout.size.map(|x| x as u32)) => {
+          if let Some(tile) = rasterize_layers(
+            layers,
+            layout.size.map(|x| x as u32),
+            context,
+            border_radius,
+            Affine::IDENTITY,
+            &mut canvas.mask_memory,
+            &mut canvas.buffer_pool,
+          )? {
+            canvas.overlay_image(
+              &tile,
+              BorderProperties::default(),
+              context.transform,
+              context.style.image_rendering,
+              BlendMode::Normal,
+            );
+
+            if let BackgroundTile::Image(image) = tile {
+              canvas.buffer_pool.release_image(image);
             }
           }
         }
-      }
-      BackgroundClip::PaddingBox => {
-        border_radius.inset_by_border_width();
-
-        let layers = collect_background_layers(context, layout.size, &mut canvas.buffer_pool)?;
-
-        if let Some(tile) = rasterize_layers(
-          layers,
-          Size {
-            width: (layout.size.width - layout.border.left - layout.border.right) as u32,
-            height: (layout.size.height - layout.border.top - layout.border.bottom) as u32,
-          },
-          context,
-          border_radius,
-          Affine::translation(-layout.border.left, -layout.border.top),
-          &mut canvas.mask_memory,
-          &mut canvas.buffer_pool,
-        )? {
-          canvas.overlay_image(
-            &tile,
-            BorderProperties::default(),
-            context.transform * Affine::translation(layout.border.left, layout.border.top),
-            context.style.image_rendering,
-            BlendMode::Normal,
-          );
-
-          if let BackgroundTile::Image(image) = tile {
-            canvas.buffer_pool.release_image(image);
+        BackgroundClip::BorderBox => {
+          for tile in layers {
+            for y in &tile.ys {
+              for x in &tile.xs {
+                canvas.overlay_image(
+                  &tile.tile,
+                  border_radius,
+                  context.transform * Affine::translation(*x as f32, *y as f32),
+                  context.style.image_rendering,
+                  tile.blend_mode,
+                );
+              }
+            }
           }
         }
-      }
-      BackgroundClip::ContentBox => {
-        border_radius.inset_by_border_width();
-        border_radius.expand_by(layout.padding.map(|size| -size));
-
-        let layers = collect_background_layers(context, layout.size, &mut canvas.buffer_pool)?;
-
-        if let Some(tile) = rasterize_layers(
-          layers,
-          layout.content_box_size().map(|x| x as u32),
-          context,
-          border_radius,
-          Affine::translation(
-            -layout.padding.left - layout.border.left,
-            -layout.padding.top - layout.border.top,
-          ),
-          &mut canvas.mask_memory,
-          &mut canvas.buffer_pool,
-        )? {
-          canvas.overlay_image(
-            &tile,
-            BorderProperties::default(),
-            context.transform
-              * Affine::translation(
-                layout.padding.left + layout.border.left,
-                layout.padding.top + layout.border.top,
-              ),
-            context.style.image_rendering,
-            BlendMode::Normal,
-          );
-
-          if let BackgroundTile::Image(image) = tile {
-            canvas.buffer_pool.release_image(image);
+        BackgroundClip::PaddingBox => {
+          border_radius.inset_by_border_width();
+
+          if let Some(tile) = rasterize_layers(
+            layers,
+            Size {
+              width: (layout.size.width - layout.border.left - layout.border.right) as u32,
+              height: (layout.size.height - layout.border.top - layout.border.bottom) as u32,
+            },
+            context,
+            border_radius,
+            Affine::translation(-layout.border.left, -layout.border.top),
+            &mut canvas.mask_memory,
+            &mut canvas.buffer_pool,
+          )? {
+            canvas.overlay_image(
+              &tile,
+              BorderProperties::default(),
+              context.transform * Affine::translation(layout.border.left, layout.border.top),
+              context.style.image_rendering,
+              BlendMode::Normal,
+            );
+
+            if let BackgroundTile::Image(image) = tile {
+              canvas.buffer_pool.release_image(image);
+            }
+          }
+        }
+        BackgroundClip::ContentBox => {
+          border_radius.inset_by_border_width();
+          border_radius.expand_by(layout.padding.map(|size| -size));
+
+          if let Some(tile) = rasterize_layers(
+            layers,
+            layout.content_box_size().map(|x| x as u32),
+            context,
+            border_radius,
+            Affine::translation(
+              -layout.padding.left - layout.border.left,
+              -layout.padding.top - layout.border.top,
+            ),
+            &mut canvas.mask_memory,
+            &mut canvas.buffer_pool,
+          )? {
+            canvas.overlay_image(
+              &tile,
+              BorderProperties::default(),
+              context.transform
+                * Affine::translation(
+                  layout.padding.left + layout.border.left,
+                  layout.padding.top + layout.border.top,
+                ),
+              context.style.image_rendering,
+              BlendMode::Normal,
+            );
+
+            if let BackgroundTile::Image(image) = tile {
+              canvas.buffer_pool.release_image(image);
+            }
           }
         }
+        // Text and border-area layers are painted with the text and the border.
+        _ => {}
       }
-      _ => {}
     }
 
     Ok(())
@@ -461,6 +658,11 @@ impl Node {
       NodeKind::Container { .. } => Ok(()),
       NodeKind::Image(image) => draw_image_node_content(image, context, canvas, layout),
       NodeKind::Text(text) => draw_text_node_content(text, context, canvas, layout),
+      NodeKind::Custom(custom) => draw_custom_node_content(custom, context, canvas, layout),
+      #[cfg(feature = "map")]
+      NodeKind::Map(map) => draw_map_node_content(map, context, canvas, layout),
+      #[cfg(feature = "svg")]
+      NodeKind::Svg(svg) => draw_svg_node_content(svg, context, canvas, layout),
     }
   }
 
@@ -470,10 +672,51 @@ impl Node {
     canvas: &mut Canvas,
     layout: Layout,
   ) -> Result<()> {
-    let clip_image = if context.style.background_clip == BackgroundClip::BorderArea {
+    let mut border = BorderProperties::from_context(context, layout.size, layout.border);
+    let mut size = layout.size;
+    let mut transform = context.transform;
+
+    // With `border-alignment`, only part of the border is laid out inside the box.
+    // The rest is painted outward, the same way outlines grow around the box.
+    if context.style.border_alignment != BorderAlignment::Inside {
+      // Like the laid out part, percentages refer to the width of the containing block.
+      let containing_width = context.sizing.query_container_width();
+      let widths = context
+        .style
+        .border_widths()
+        .map(|width| snap_border_width(width.to_px(&context.sizing, containing_width)));
+      let outward = Rect {
+        top: widths.top - layout.border.top,
+        right: widths.right - layout.border.right,
+        bottom: widths.bottom - layout.border.bottom,
+        left: widths.left - layout.border.left,
+      };
+
+      border.width = widths;
+      border.expand_by(outward);
+      size.width += outward.left + outward.right;
+      size.height += outward.top + outward.bottom;
+      transform = Affine::translation(-outward.left, -outward.top) * transform;
+    }
+
+    let clip_image = if context
+      .style
+      .background_clip
+      .contains(&BackgroundClip::BorderArea)
+    {
       rasterize_layers(
-        collect_background_layers(context, layout.size, &mut canvas.buffer_pool)?,
-        layout.size.map(|x| x as u32),
+        collect_clipped_background_layers(
+          context,
+          BackgroundClip::BorderArea,
+          size,
+          BoxInsets {
+            border: border.width,
+            padding: layout.padding,
+          },
+          viewport_area(transform, canvas.size()),
+          &mut canvas.buffer_pool,
+        )?,
+        size.map(|x| x as u32),
         context,
         BorderProperties::default(),
         Affine::IDENTITY,
@@ -484,12 +727,7 @@ impl Node {
       None
     };
 
-    BorderProperties::from_context(context, layout.size, layout.border).draw(
-      canvas,
-      layout.size,
-      context.transform,
-      clip_image.as_ref(),
-    );
+    border.draw(canvas, size, transform, clip_image.as_ref());
 
     if let Some(BackgroundTile::Image(image)) = clip_image {
       canvas.buffer_pool.release_image(image);
@@ -503,11 +741,12 @@ impl Node {
     canvas: &mut Canvas,
     layout: Layout,
   ) -> Result<()> {
-    let width = context
-      .style
-      .outline_width
-      .to_px(&context.sizing, layout.size.width)
-      .max(0.0);
+    let width = snap_border_width(
+      context
+        .style
+        .outline_width
+        .to_px(&context.sizing, layout.size.width),
+    );
 
     let offset = context
       .style
@@ -520,6 +759,7 @@ impl Node {
       style: context.style.outline_style,
       image_rendering: context.style.image_rendering,
       radius: BorderProperties::resolve_radius_part(context, layout.size),
+      smoothing: context.style.corner_smoothing.0,
     };
 
     border.expand_by(Sides([offset + width; 4]).into());
@@ -548,7 +788,10 @@ pub(crate) struct NodeStyleLayers {
 mod tests {
   use std::str::FromStr;
 
-  use crate::layout::style::{BackgroundImage, Style, StyleDeclaration, tw::TailwindValues};
+  use crate::layout::style::{
+    BackgroundImage, Display, Length, Style, StyleDeclaration, StyleDeclarationBlock,
+    tw::TailwindValues,
+  };
 
   use super::*;
 
@@ -572,6 +815,20 @@ mod tests {
     assert_eq!(tasks, vec![background_url.to_string()]);
   }
 
+  #[test]
+  fn collect_fetch_tasks_skips_data_uris() {
+    let data_uri = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=";
+    let node = Node::container([Node::image(data_uri)]).with_style(Style::default().with(
+      StyleDeclaration::background_image(Some([BackgroundImage::Url(data_uri.into())].into())),
+    ));
+
+    let mut collection = FetchTaskCollection::default();
+    node.collect_fetch_tasks(&mut collection);
+    node.collect_style_fetch_tasks(&mut collection);
+
+    assert!(collection.into_inner().is_empty());
+  }
+
   #[test]
   fn collect_style_fetch_tasks_collects_preset_and_tailwind_image_urls() {
     let preset_url = "https://placehold.co/64x64/f97316/white";
@@ -601,4 +858,68 @@ mod tests {
       vec![preset_url.to_string(), tailwind_url.to_string()]
     );
   }
+
+  fn render_border(alignment: &str, width: &str) -> RgbaImage {
+    let Ok(declarations) = StyleDeclarationBlock::from_str(&format!(
+      "margin: 4px; width: 8px; height: 8px; background-color: blue; border: 2px solid red; border-width: {width}; border-alignment: {alignment}"
+    )) else {
+      unreachable!()
+    };
+
+    let result = crate::rendering::render(
+      crate::rendering::RenderOptions::builder()
+        .viewport(Viewport::new((16, 16)))
+        .node(
+          Node::container([Node::container([]).with_style(declarations.into())]).with_style(
+            Style::default()
+              .with(StyleDeclaration::display(Display::Flex))
+              .with(StyleDeclaration::width(Length::Px(16.0))),
+          ),
+        )
+        .global(&crate::GlobalContext::default())
+        .build(),
+    );
+
+    assert!(result.is_ok());
+    let Ok(image) = result else { unreachable!() };
+    image
+  }
+
+  #[test]
+  fn border_alignment_moves_border_outward() {
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+    let inside = render_border("inside", "2px");
+    assert_eq!(inside.get_pixel(4, 8).0, RED);
+    assert_eq!(inside.get_pixel(6, 8).0, BLUE);
+    assert_eq!(inside.get_pixel(3, 8).0, [0, 0, 0, 0]);
+
+    let center = render_border("center", "2px");
+    assert_eq!(center.get_pixel(3, 8).0, RED);
+    assert_eq!(center.get_pixel(4, 8).0, RED);
+    assert_eq!(center.get_pixel(5, 8).0, BLUE);
+
+    let outside = render_border("outside", "2px");
+    assert_eq!(outside.get_pixel(2, 8).0, RED);
+    assert_eq!(outside.get_pixel(3, 8).0, RED);
+    assert_eq!(outside.get_pixel(4, 8).0, BLUE);
+  }
+
+  #[test]
+  fn border_alignment_resolves_percentages_against_the_containing_block() {
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+    // 12.5% of the 16px wide containing block is 2px.
+    let center = render_border("center", "12.5%");
+    assert_eq!(center.get_pixel(3, 8).0, RED);
+    assert_eq!(center.get_pixel(4, 8).0, RED);
+    assert_eq!(center.get_pixel(5, 8).0, BLUE);
+
+    let outside = render_border("outside", "12.5%");
+    assert_eq!(outside.get_pixel(2, 8).0, RED);
+    assert_eq!(outside.get_pixel(3, 8).0, RED);
+    assert_eq!(outside.get_pixel(4, 8).0, BLUE);
+  }
 }
@@ -0,0 +1,502 @@
+use std::{
+  collections::HashMap,
+  fmt::{self, Debug},
+  sync::{Arc, OnceLock},
+};
+
+use serde::{
+  Deserialize, Deserializer,
+  de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+};
+use serde_json::{Map, Value};
+
+use crate::{
+  Error, Result,
+  layout::node::{
+    AvatarGroupData, CustomData, CustomDraw, ImageData, MarkdownData, Node, NodeKind, NodeMetadata,
+    TextData,
+  },
+};
+
+#[cfg(feature = "map")]
+use crate::layout::node::MapData;
+#[cfg(feature = "svg")]
+use crate::layout::node::SvgData;
+
+/// Node `type` strings handled by the crate itself, which plugins can't override.
+const BUILTIN_NODE_TYPES: &[&str] = &[
+  "container",
+  "image",
+  "text",
+  "custom",
+  "avatarGroup",
+  "markdown",
+  #[cfg(feature = "map")]
+  "map",
+  #[cfg(feature = "svg")]
+  "svg",
+];
+
+/// Creates drawers for nodes of a custom `type`.
+///
+/// Register implementations in a [`NodePluginRegistry`] on the [`GlobalContext`](crate::GlobalContext)
+/// so domain-specific nodes can live in separate crates.
+pub trait NodePlugin: Send + Sync {
+  /// Builds the drawer for a node from its fields, excluding `type`, `children` and the shared
+  /// node metadata such as `style` and `tw`.
+  fn create(&self, props: &Value) -> Result<Arc<dyn CustomDraw>>;
+}
+
+impl<F> NodePlugin for F
+where
+  F: Fn(&Value) -> Result<Arc<dyn CustomDraw>> + Send + Sync,
+{
+  fn create(&self, props: &Value) -> Result<Arc<dyn CustomDraw>> {
+    self(props)
+  }
+}
+
+/// Node plugins keyed by the node `type` they handle.
+#[derive(Default, Clone)]
+pub struct NodePluginRegistry {
+  plugins: HashMap<Box<str>, Arc<dyn NodePlugin>>,
+}
+
+impl Debug for NodePluginRegistry {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_set().entries(self.plugins.keys()).finish()
+  }
+}
+
+impl NodePluginRegistry {
+  /// Registers a plugin for a node type, returning the plugin it replaced.
+  ///
+  /// Built-in types such as `container`, `image`, `text` and `custom` always take precedence.
+  pub fn register(
+    &mut self,
+    type_name: impl Into<Box<str>>,
+    plugin: impl NodePlugin + 'static,
+  ) -> Option<Arc<dyn NodePlugin>> {
+    self.plugins.insert(type_name.into(), Arc::new(plugin))
+  }
+
+  /// Removes the plugin for a node type, returning it if present.
+  pub fn remove(&mut self, type_name: &str) -> Option<Arc<dyn NodePlugin>> {
+    self.plugins.remove(type_name)
+  }
+
+  /// Returns the plugin for a node type.
+  pub fn get(&self, type_name: &str) -> Option<&Arc<dyn NodePlugin>> {
+    self.plugins.get(type_name)
+  }
+
+  /// Returns true if a plugin is registered for the node type.
+  pub fn contains(&self, type_name: &str) -> bool {
+    self.plugins.contains_key(type_name)
+  }
+
+  /// Deserializes a node tree, accepting the node types registered here as well as the built-in
+  /// ones.
+  ///
+  /// Deserializing a [`Node`] directly only accepts built-in types, so a misspelled `type` is
+  /// reported while parsing rather than when rendering.
+  pub fn deserialize_node<'de, D>(&self, deserializer: D) -> std::result::Result<Node, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    NodeSeed {
+      plugins: Some(self),
+    }
+    .deserialize(deserializer)
+  }
+}
+
+/// The fields of a node with a plugin `type`, and the drawer created from them.
+pub(crate) struct PluginNode {
+  props: Value,
+  drawer: OnceLock<Arc<dyn CustomDraw>>,
+}
+
+impl PluginNode {
+  pub(crate) fn new(props: Value) -> Self {
+    Self {
+      props,
+      drawer: OnceLock::new(),
+    }
+  }
+
+  pub(crate) fn props(&self) -> &Value {
+    &self.props
+  }
+
+  /// Creates the drawer from the registered plugin once and reuses it for later passes.
+  pub(crate) fn resolve_drawer(
+    &self,
+    type_name: &str,
+    plugins: &NodePluginRegistry,
+  ) -> Result<Arc<dyn CustomDraw>> {
+    if let Some(drawer) = self.drawer.get() {
+      return Ok(drawer.clone());
+    }
+
+    let plugin = plugins
+      .get(type_name)
+      .ok_or_else(|| Error::UnknownNodeType(type_name.into()))?;
+    let drawer = plugin.create(&self.props)?;
+
+    Ok(self.drawer.get_or_init(|| drawer).clone())
+  }
+}
+
+/// The fields shared by every node, which are never passed to plugins.
+const METADATA_FIELDS: &[&str] = &[
+  "tagName",
+  "className",
+  "id",
+  "attributes",
+  "preset",
+  "style",
+  "tw",
+];
+
+/// Deserializes a node, and its children, accepting the plugin types registered in `plugins`.
+///
+/// `children` are deserialized straight into nodes, and only the remaining fields of each node
+/// are buffered, so the cost stays linear in the size of the tree.
+#[derive(Clone, Copy)]
+pub(crate) struct NodeSeed<'p> {
+  pub(crate) plugins: Option<&'p NodePluginRegistry>,
+}
+
+impl<'de> DeserializeSeed<'de> for NodeSeed<'_> {
+  type Value = Node;
+
+  fn deserialize<D>(self, deserializer: D) -> std::result::Result<Node, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    deserializer.deserialize_map(self)
+  }
+}
+
+impl<'de> Visitor<'de> for NodeSeed<'_> {
+  type Value = Node;
+
+  fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("a node object")
+  }
+
+  fn visit_map<A>(self, mut map: A) -> std::result::Result<Node, A::Error>
+  where
+    A: MapAccess<'de>,
+  {
+    let mut type_name: Option<Box<str>> = None;
+    let mut children = Vec::new();
+    let mut fields = Map::new();
+
+    while let Some(key) = map.next_key::<String>()? {
+      match key.as_str() {
+        "type" => type_name = Some(map.next_value()?),
+        // Only containers have children, other nodes ignore them.
+        "children"
+          if type_name
+            .as_deref()
+            .is_none_or(|type_name| type_name == "container") =>
+        {
+          children = map.next_value_seed(ChildrenSeed(self))?;
+        }
+        "children" => {
+          map.next_value::<IgnoredAny>()?;
+        }
+        _ => {
+          fields.insert(key, map.next_value()?);
+        }
+      }
+    }
+
+    let type_name = type_name.ok_or_else(|| de::Error::missing_field("type"))?;
+    self
+      .build(&type_name, children, fields)
+      .map_err(de::Error::custom)
+  }
+}
+
+impl NodeSeed<'_> {
+  /// Builds the node of `type_name` from its buffered fields.
+  fn build(
+    self,
+    type_name: &str,
+    children: Vec<Node>,
+    mut fields: Map<String, Value>,
+  ) -> std::result::Result<Node, serde_json::Error> {
+    let is_plugin = !is_builtin_node_type(type_name)
+      && self
+        .plugins
+        .is_some_and(|plugins| plugins.contains(type_name));
+
+    if !is_builtin_node_type(type_name) && !is_plugin {
+      return Err(de::Error::unknown_variant(type_name, BUILTIN_NODE_TYPES));
+    }
+
+    let metadata = NodeMetadata::deserialize(&Value::Object(
+      METADATA_FIELDS
+        .iter()
+        .filter_map(|&field| Some((field.to_owned(), fields.remove(field)?)))
+        .collect(),
+    ))?;
+    let fields = Value::Object(fields);
+
+    let kind = match type_name {
+      "container" => NodeKind::Container { children },
+      "image" => NodeKind::Image(ImageData::deserialize(&fields)?),
+      "text" => NodeKind::Text(TextData::deserialize(&fields)?),
+      "custom" => NodeKind::Custom(CustomData::deserialize(&fields)?),
+      "avatarGroup" => return Ok(AvatarGroupData::deserialize(&fields)?.into_container(metadata)),
+      "markdown" => return Ok(MarkdownData::deserialize(&fields)?.into_container(metadata)),
+      #[cfg(feature = "map")]
+      "map" => NodeKind::Map(MapData::deserialize(&fields)?),
+      #[cfg(feature = "svg")]
+      "svg" => NodeKind::Svg(SvgData::deserialize(&fields)?),
+      _ => NodeKind::Custom(CustomData::plugin(type_name.into(), fields)),
+    };
+
+    Ok(Node { metadata, kind })
+  }
+}
+
+/// Deserializes an optional list of child nodes with the same plugins as their parent.
+struct ChildrenSeed<'p>(NodeSeed<'p>);
+
+impl<'de> DeserializeSeed<'de> for ChildrenSeed<'_> {
+  type Value = Vec<Node>;
+
+  fn deserialize<D>(self, deserializer: D) -> std::result::Result<Vec<Node>, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    deserializer.deserialize_option(self)
+  }
+}
+
+impl<'de> Visitor<'de> for ChildrenSeed<'_> {
+  type Value = Vec<Node>;
+
+  fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("a list of nodes")
+  }
+
+  fn visit_none<E: de::Error>(self) -> std::result::Result<Vec<Node>, E> {
+    Ok(Vec::new())
+  }
+
+  fn visit_unit<E: de::Error>(self) -> std::result::Result<Vec<Node>, E> {
+    Ok(Vec::new())
+  }
+
+  fn visit_some<D>(self, deserializer: D) -> std::result::Result<Vec<Node>, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    deserializer.deserialize_seq(self)
+  }
+
+  fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Vec<Node>, A::Error>
+  where
+    A: SeqAccess<'de>,
+  {
+    let mut children = Vec::with_capacity(seq.size_hint().unwrap_or_default().min(1024));
+
+    while let Some(child) = seq.next_element_seed(self.0)? {
+      children.push(child);
+    }
+
+    Ok(children)
+  }
+}
+
+fn is_builtin_node_type(type_name: &str) -> bool {
+  BUILTIN_NODE_TYPES.contains(&type_name)
+}
+
+impl CustomData {
+  /// Returns the node `type` if this is a plugin node without a registered plugin.
+  pub(crate) fn unregistered_plugin_type(&self, plugins: &NodePluginRegistry) -> Option<&str> {
+    self.plugin.as_ref()?;
+
+    self
+      .name
+      .as_deref()
+      .filter(|type_name| !plugins.contains(type_name))
+  }
+}
+
+impl Node {
+  /// Fails on the first node whose plugin `type` isn't registered, before any layout work is done.
+  pub(crate) fn check_plugin_types(&self, plugins: &NodePluginRegistry) -> Result<()> {
+    let mut stack = vec![self];
+
+    while let Some(node) = stack.pop() {
+      if let NodeKind::Custom(custom) = &node.kind
+        && let Some(type_name) = custom.unregistered_plugin_type(plugins)
+      {
+        return Err(Error::UnknownNodeType(type_name.into()));
+      }
+
+      stack.extend(node.children_ref().into_iter().flatten());
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+  use taffy::Size;
+
+  use crate::{
+    GlobalContext,
+    layout::{Viewport, node::CustomCanvas, style::Color},
+    rendering::{RenderOptions, render},
+  };
+
+  use super::*;
+
+  /// Fills its content box with the `color` from the node's props.
+  struct Swatch(Color);
+
+  impl CustomDraw for Swatch {
+    fn draw(&self, canvas: &mut CustomCanvas<'_>) -> Result<()> {
+      let Size { width, height } = canvas.size();
+      canvas.fill_path(
+        &[
+          zeno::Command::MoveTo((0.0, 0.0).into()),
+          zeno::Command::LineTo((width, 0.0).into()),
+          zeno::Command::LineTo((width, height).into()),
+          zeno::Command::LineTo((0.0, height).into()),
+          zeno::Command::Close,
+        ],
+        self.0,
+      );
+      Ok(())
+    }
+
+    fn intrinsic_size(&self) -> Option<Size<f32>> {
+      Some(Size {
+        width: 2.0,
+        height: 2.0,
+      })
+    }
+  }
+
+  fn swatch_plugin(props: &Value) -> Result<Arc<dyn CustomDraw>> {
+    let [r, g, b, a] = <[u8; 4]>::deserialize(&props["color"])
+      .map_err(|error| Error::CustomDrawError(error.to_string()))?;
+
+    Ok(Arc::new(Swatch(Color([r, g, b, a]))))
+  }
+
+  fn swatch_plugins() -> NodePluginRegistry {
+    let mut plugins = NodePluginRegistry::default();
+    plugins.register("swatch", swatch_plugin);
+    plugins
+  }
+
+  fn node(plugins: &NodePluginRegistry, value: &Value) -> Node {
+    let Ok(node) = plugins.deserialize_node(value) else {
+      unreachable!()
+    };
+    node
+  }
+
+  fn render_with(global: &GlobalContext, node: Node) -> Result<image::RgbaImage> {
+    render(
+      RenderOptions::builder()
+        .viewport(Viewport::new((4, 4)))
+        .node(node)
+        .global(global)
+        .build(),
+    )
+  }
+
+  #[test]
+  fn separates_plugin_props_from_metadata() {
+    let node = node(
+      &swatch_plugins(),
+      &json!({
+        "type": "swatch",
+        "tw": "w-4",
+        "color": [1, 2, 3, 4],
+        "children": [{ "type": "container" }],
+      }),
+    );
+
+    let NodeKind::Custom(custom) = &node.kind else {
+      unreachable!()
+    };
+
+    assert_eq!(custom.name.as_deref(), Some("swatch"));
+    assert_eq!(
+      custom.plugin.as_ref().map(|plugin| plugin.props()),
+      Some(&json!({ "color": [1, 2, 3, 4] }))
+    );
+  }
+
+  #[test]
+  fn builtin_types_are_not_plugins() {
+    let node = node(
+      &swatch_plugins(),
+      &json!({ "type": "custom", "name": "swatch" }),
+    );
+
+    let NodeKind::Custom(custom) = &node.kind else {
+      unreachable!()
+    };
+
+    assert!(custom.plugin.is_none());
+  }
+
+  #[test]
+  fn draws_registered_plugin_nodes() {
+    let mut global = GlobalContext::default();
+    global.node_plugins_mut().register("swatch", swatch_plugin);
+
+    let node = node(
+      global.node_plugins(),
+      &json!({
+        "type": "container",
+        "children": [{ "type": "swatch", "color": [0, 255, 0, 255] }],
+      }),
+    );
+    let result = render_with(&global, node);
+
+    assert!(result.is_ok());
+    let Ok(image) = result else { unreachable!() };
+
+    assert_eq!(image.get_pixel(1, 1).0, [0, 255, 0, 255]);
+    assert_eq!(image.get_pixel(3, 3).0, [0, 0, 0, 0]);
+  }
+
+  #[test]
+  fn rejects_unregistered_types_when_deserializing() {
+    let value = json!({ "type": "container", "children": [{ "type": "swatch" }] });
+
+    assert!(serde_json::from_value::<Node>(value.clone()).is_err());
+    assert!(swatch_plugins().deserialize_node(&value).is_ok());
+
+    let typo = json!({ "type": "container", "children": [{ "type": "contianer" }] });
+    assert!(swatch_plugins().deserialize_node(&typo).is_err());
+  }
+
+  #[test]
+  fn rejects_types_unregistered_at_render() {
+    let node = node(
+      &swatch_plugins(),
+      &json!({ "type": "container", "children": [{ "type": "swatch" }] }),
+    );
+
+    let result = render_with(&GlobalContext::default(), node);
+
+    assert!(matches!(result, Err(Error::UnknownNodeType(type_name)) if &*type_name == "swatch"));
+  }
+}
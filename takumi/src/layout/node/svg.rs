@@ -0,0 +1,119 @@
+use std::sync::{Arc, OnceLock};
+
+use serde::Deserialize;
+use taffy::{AvailableSpace, Layout, Size};
+
+use crate::{
+  Result,
+  layout::{inline::InlineContentKind, node::image::measure_image_source},
+  rendering::{Canvas, RenderContext, draw_image},
+  resources::image::{ImageResult, ImageSource, parse_svg_str},
+};
+
+/// Variant-specific svg node data.
+///
+/// The markup is parsed once and rasterized at the layout-resolved size of the node, so the
+/// graphic stays crisp at any device pixel ratio.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SvgData {
+  pub(crate) svg: Arc<str>,
+  pub(crate) width: Option<f32>,
+  pub(crate) height: Option<f32>,
+  /// The parsed tree, shared between clones of the node.
+  #[serde(skip)]
+  parsed: Arc<OnceLock<Option<Arc<ImageSource>>>>,
+}
+
+impl SvgData {
+  /// Creates svg node data from SVG markup.
+  pub fn new(svg: impl Into<Arc<str>>) -> Self {
+    Self {
+      svg: svg.into(),
+      width: None,
+      height: None,
+      parsed: Arc::default(),
+    }
+  }
+
+  /// Creates svg node data from UTF-8 encoded SVG markup, replacing invalid sequences.
+  pub fn from_bytes(bytes: &[u8]) -> Self {
+    Self::new(String::from_utf8_lossy(bytes).as_ref())
+  }
+
+  /// Sets the preferred size in CSS pixels, overriding the size declared by the SVG.
+  pub fn with_size(mut self, width: Option<f32>, height: Option<f32>) -> Self {
+    self.width = width;
+    self.height = height;
+    self
+  }
+
+  /// Parses the markup, reporting why it can't be drawn.
+  pub(crate) fn parse(&self) -> ImageResult {
+    parse_svg_str(&self.svg)
+  }
+
+  fn image_source(&self) -> Option<&Arc<ImageSource>> {
+    self.parsed.get_or_init(|| self.parse().ok()).as_ref()
+  }
+}
+
+impl From<&str> for SvgData {
+  fn from(svg: &str) -> Self {
+    Self::new(svg)
+  }
+}
+
+impl From<String> for SvgData {
+  fn from(svg: String) -> Self {
+    Self::new(svg)
+  }
+}
+
+impl From<Arc<str>> for SvgData {
+  fn from(svg: Arc<str>) -> Self {
+    Self::new(svg)
+  }
+}
+
+pub(crate) fn svg_inline_content() -> Option<InlineContentKind<'static>> {
+  Some(InlineContentKind::Box)
+}
+
+pub(crate) fn measure_svg_node(
+  svg: &SvgData,
+  context: &RenderContext,
+  available_space: Size<AvailableSpace>,
+  known_dimensions: Size<Option<f32>>,
+  style: &taffy::Style,
+) -> Size<f32> {
+  let Some(image_source) = svg.image_source() else {
+    return Size::zero();
+  };
+
+  measure_image_source(
+    image_source,
+    Size {
+      width: svg.width,
+      height: svg.height,
+    },
+    context,
+    available_space,
+    known_dimensions,
+    style,
+  )
+}
+
+pub(crate) fn draw_svg_node_content(
+  svg: &SvgData,
+  context: &RenderContext,
+  canvas: &mut Canvas,
+  layout: Layout,
+) -> Result<()> {
+  let Some(image_source) = svg.image_source() else {
+    return Ok(());
+  };
+
+  draw_image(image_source, context, canvas, layout)
+}
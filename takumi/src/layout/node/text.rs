@@ -11,7 +11,10 @@ use crate::{
     },
     node::{Node, NodeStyleLayers, TextData},
   },
-  rendering::{Canvas, MaxHeight, RenderContext, inline_drawing::draw_inline_layout},
+  rendering::{
+    Canvas, MaxHeight, RenderContext,
+    inline_drawing::{InlineOpacityLayers, draw_inline_layout},
+  },
 };
 
 pub(crate) fn take_text_style_layers(node: &mut Node) -> NodeStyleLayers {
@@ -47,9 +50,10 @@ pub(crate) fn draw_text_node_content(
   let inline_text: InlineItem<'_, '_> = InlineItem::Text {
     text: text.text.as_str().into(),
     context,
+    opacity_group: None,
   };
 
-  let (inline_layout, _, spans) = create_inline_layout(
+  let (inline_layout, text, spans) = create_inline_layout(
     once(inline_text),
     Size {
       width: AvailableSpace::Definite(size.width),
@@ -62,7 +66,16 @@ pub(crate) fn draw_text_node_content(
     InlineLayoutStage::Draw,
   );
 
-  draw_inline_layout(context, canvas, layout, inline_layout, &font_style, &spans)?;
+  draw_inline_layout(
+    context,
+    canvas,
+    layout,
+    inline_layout,
+    &text,
+    &font_style,
+    &spans,
+    &mut InlineOpacityLayers::default(),
+  )?;
 
   Ok(())
 }
@@ -76,6 +89,7 @@ pub(crate) fn measure_text_node(
   let inline_content: InlineItem<'_, '_> = InlineItem::Text {
     text: text.text.as_str().into(),
     context,
+    opacity_group: None,
   };
 
   let (max_width, max_height) =
@@ -92,5 +106,5 @@ pub(crate) fn measure_text_node(
     InlineLayoutStage::Measure,
   );
 
-  measure_inline_layout(&mut layout, max_width)
+  measure_inline_layout(&mut layout, max_width, &font_style)
 }
@@ -0,0 +1,315 @@
+use std::fmt::{Display, Formatter};
+
+use data_url::DataUrl;
+use thiserror::Error;
+
+use crate::layout::node::{Node, NodeKind, NodePluginRegistry};
+
+/// Structural limits enforced by [`Node::validate`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ValidationLimits {
+  /// Maximum nesting depth, where the root node has depth 0.
+  pub max_depth: Option<usize>,
+  /// Maximum total number of nodes in the tree.
+  pub max_nodes: Option<usize>,
+}
+
+impl ValidationLimits {
+  /// Sets the maximum nesting depth.
+  pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+    self.max_depth = Some(max_depth);
+    self
+  }
+
+  /// Sets the maximum total number of nodes.
+  pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+    self.max_nodes = Some(max_nodes);
+    self
+  }
+}
+
+/// The kind of problem found while validating a node tree.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum ValidationIssueKind {
+  /// A `tw` token that is not a supported Tailwind utility.
+  #[error("unsupported tailwind class '{0}'")]
+  UnsupportedTailwindClass(Box<str>),
+  /// An image node without a source.
+  #[error("image source is empty")]
+  EmptyImageSource,
+  /// A node whose `type` has no registered [`NodePlugin`](crate::layout::node::NodePlugin).
+  #[error("unknown node type '{0}'")]
+  UnknownNodeType(Box<str>),
+  /// An image node with a malformed `data:` URI.
+  #[error("image source is not a valid data URI")]
+  InvalidDataUri,
+  /// An svg node whose markup can't be parsed.
+  #[cfg(feature = "svg")]
+  #[error("invalid svg markup: {0}")]
+  InvalidSvg(Box<str>),
+  /// The tree is nested deeper than allowed.
+  #[error("node depth {depth} exceeds the limit of {limit}")]
+  MaxDepthExceeded {
+    /// The depth of the offending node.
+    depth: usize,
+    /// The configured limit.
+    limit: usize,
+  },
+  /// The tree contains more nodes than allowed.
+  #[error("node count {count} exceeds the limit of {limit}")]
+  MaxNodesExceeded {
+    /// The total number of nodes in the tree.
+    count: usize,
+    /// The configured limit.
+    limit: usize,
+  },
+}
+
+/// A problem found at a specific location in a node tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ValidationIssue {
+  /// Location of the node, e.g. `children[2].children[0]`. Empty for the root.
+  pub path: String,
+  /// What is wrong with the node.
+  pub kind: ValidationIssueKind,
+}
+
+impl Display for ValidationIssue {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    if self.path.is_empty() {
+      write!(f, "{}", self.kind)
+    } else {
+      write!(f, "{}: {}", self.path, self.kind)
+    }
+  }
+}
+
+impl Node {
+  /// Checks the node tree for problems that would otherwise be silently ignored while rendering.
+  ///
+  /// Every issue is collected instead of stopping at the first one. Nodes with a plugin `type` are
+  /// reported as unknown, use [`Node::validate_with_plugins`] to accept registered types.
+  pub fn validate(&self, limits: ValidationLimits) -> Vec<ValidationIssue> {
+    self.validate_with_plugins(limits, &NodePluginRegistry::default())
+  }
+
+  /// Like [`Node::validate`], but accepts node types registered in `plugins`.
+  pub fn validate_with_plugins(
+    &self,
+    limits: ValidationLimits,
+    plugins: &NodePluginRegistry,
+  ) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut count = 0;
+    let mut stack = vec![(self, String::new(), 0)];
+
+    while let Some((node, path, depth)) = stack.pop() {
+      count += 1;
+      node.validate_self(&path, plugins, &mut issues);
+
+      if let Some(limit) = limits.max_depth
+        && depth > limit
+      {
+        issues.push(ValidationIssue {
+          path,
+          kind: ValidationIssueKind::MaxDepthExceeded { depth, limit },
+        });
+        continue;
+      }
+
+      let Some(children) = node.children_ref() else {
+        continue;
+      };
+
+      for (index, child) in children.iter().enumerate().rev() {
+        let child_path = if path.is_empty() {
+          format!("children[{index}]")
+        } else {
+          format!("{path}.children[{index}]")
+        };
+        stack.push((child, child_path, depth + 1));
+      }
+    }
+
+    if let Some(limit) = limits.max_nodes
+      && count > limit
+    {
+      issues.push(ValidationIssue {
+        path: String::new(),
+        kind: ValidationIssueKind::MaxNodesExceeded { count, limit },
+      });
+    }
+
+    issues
+  }
+
+  fn validate_self(
+    &self,
+    path: &str,
+    plugins: &NodePluginRegistry,
+    issues: &mut Vec<ValidationIssue>,
+  ) {
+    let mut push = |kind| {
+      issues.push(ValidationIssue {
+        path: path.to_owned(),
+        kind,
+      })
+    };
+
+    if let Some(tw) = self.metadata.tw.as_ref() {
+      for token in tw.unsupported() {
+        push(ValidationIssueKind::UnsupportedTailwindClass(token.clone()));
+      }
+    }
+
+    if let Some(kind) = self.kind.validate(plugins) {
+      push(kind);
+    }
+  }
+}
+
+impl NodeKind {
+  /// Checks the content of this kind of node, the problem found if any.
+  pub(crate) fn validate(&self, plugins: &NodePluginRegistry) -> Option<ValidationIssueKind> {
+    match self {
+      NodeKind::Custom(custom) => custom
+        .unregistered_plugin_type(plugins)
+        .map(|type_name| ValidationIssueKind::UnknownNodeType(type_name.into())),
+      NodeKind::Image(image) if image.src.trim().is_empty() => {
+        Some(ValidationIssueKind::EmptyImageSource)
+      }
+      NodeKind::Image(image)
+        if image.src.starts_with("data:") && DataUrl::process(&image.src).is_err() =>
+      {
+        Some(ValidationIssueKind::InvalidDataUri)
+      }
+      #[cfg(feature = "svg")]
+      NodeKind::Svg(svg) => svg
+        .parse()
+        .err()
+        .map(|error| ValidationIssueKind::InvalidSvg(error.to_string().into())),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use crate::layout::style::tw::TailwindValues;
+
+  use super::*;
+
+  fn tw(value: &str) -> TailwindValues {
+    let Ok(tw) = TailwindValues::from_str(value) else {
+      unreachable!()
+    };
+    tw
+  }
+
+  #[test]
+  fn valid_tree_has_no_issues() {
+    let node = Node::container([Node::text("hello").with_tw(tw("flex mt-4"))]);
+
+    assert!(node.validate(ValidationLimits::default()).is_empty());
+  }
+
+  #[test]
+  fn collects_all_issues_with_paths() {
+    let node = Node::container([
+      Node::text("a"),
+      Node::container([Node::image("").with_tw(tw("mt-4 nope"))]),
+      Node::image("data:"),
+    ]);
+
+    let issues = node
+      .validate(ValidationLimits::default())
+      .iter()
+      .map(ToString::to_string)
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      issues,
+      vec![
+        "children[1].children[0]: unsupported tailwind class 'nope'",
+        "children[1].children[0]: image source is empty",
+        "children[2]: image source is not a valid data URI",
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_limit_violations() {
+    let node = Node::container([Node::container([Node::text("deep")]), Node::text("b")]);
+
+    let issues = node.validate(
+      ValidationLimits::default()
+        .with_max_depth(1)
+        .with_max_nodes(3),
+    );
+
+    assert_eq!(
+      issues,
+      vec![
+        ValidationIssue {
+          path: "children[0].children[0]".to_owned(),
+          kind: ValidationIssueKind::MaxDepthExceeded { depth: 2, limit: 1 },
+        },
+        ValidationIssue {
+          path: String::new(),
+          kind: ValidationIssueKind::MaxNodesExceeded { count: 4, limit: 3 },
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_unregistered_plugin_types() {
+    let unused = |_: &serde_json::Value| Err(crate::Error::CustomDrawError("unused".to_owned()));
+
+    let mut plugins = NodePluginRegistry::default();
+    plugins.register("chart", unused);
+    plugins.register("globe", unused);
+
+    let Ok(node) = plugins.deserialize_node(&serde_json::json!({
+      "type": "container",
+      "children": [{ "type": "chart" }, { "type": "globe" }],
+    })) else {
+      unreachable!()
+    };
+
+    plugins.remove("globe");
+
+    assert_eq!(
+      node.validate_with_plugins(ValidationLimits::default(), &plugins),
+      vec![ValidationIssue {
+        path: "children[1]".to_owned(),
+        kind: ValidationIssueKind::UnknownNodeType("globe".into()),
+      }]
+    );
+  }
+
+  #[cfg(feature = "svg")]
+  #[test]
+  fn reports_invalid_svg_markup() {
+    let Ok(node) = serde_json::from_value::<Node>(serde_json::json!({
+      "type": "container",
+      "children": [
+        { "type": "svg", "svg": "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"4\" height=\"4\"/>" },
+        { "type": "svg", "svg": "<svg" },
+      ],
+    })) else {
+      unreachable!()
+    };
+
+    let issues = node.validate(ValidationLimits::default());
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].path, "children[1]");
+    assert!(matches!(issues[0].kind, ValidationIssueKind::InvalidSvg(_)));
+  }
+}
@@ -527,11 +527,13 @@ impl_passthrough_animatable!(
   JustifyContent,
   FlexWrap,
   Position,
+  PositionAnchor,
   BorderStyle,
+  BorderAlignment,
   Border,
   ObjectFit,
   Overflow,
-  BackgroundClip,
+  FadeEdges,
   GridAutoFlow,
   GridLine,
   GridTemplateAreas,
@@ -553,6 +555,13 @@ impl_passthrough_animatable!(
   ImageScalingAlgorithm,
   OverflowWrap,
   WordBreak,
+  LineBreak,
+  FontKerning,
+  FontVariantLigatures,
+  FontVariantCaps,
+  TextAutospace,
+  TabSize,
+  TextEmboss,
   BasicShape,
   FillRule,
   WhiteSpace,
@@ -653,6 +662,7 @@ mod tests {
       viewport: Viewport::new((200, 100)),
       container_size: Size::NONE,
       font_size: 16.0,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     }
   }
@@ -986,6 +996,7 @@ mod tests {
       offset_y: Length::zero(),
       blur_radius: Length::zero(),
       color: ColorInput::CurrentColor,
+      inset: false,
     };
     target.interpolate(
       &TextShadow {
@@ -993,12 +1004,14 @@ mod tests {
         offset_y: Length::Px(10.0),
         blur_radius: Length::Px(20.0),
         color: ColorInput::Value(Color([0, 0, 0, 255])),
+        inset: false,
       },
       &TextShadow {
         offset_x: Length::Px(20.0),
         offset_y: Length::Px(30.0),
         blur_radius: Length::Px(40.0),
         color: ColorInput::Value(Color([200, 100, 50, 255])),
+        inset: false,
       },
       0.5,
       &sizing(),
@@ -1012,6 +1025,7 @@ mod tests {
         offset_y: Length::Px(20.0),
         blur_radius: Length::Px(30.0),
         color: ColorInput::Value(Color([100, 50, 25, 255])),
+        inset: false,
       }
     );
   }
@@ -1,4 +1,7 @@
-use std::borrow::Cow;
+use std::{
+  borrow::Cow,
+  hash::{Hash, Hasher},
+};
 
 use cssparser::{BasicParseErrorKind, Parser, Token, match_ignore_ascii_case};
 use typed_builder::TypedBuilder;
@@ -15,6 +18,12 @@ pub struct AnimationTime {
   pub milliseconds: f32,
 }
 
+impl Hash for AnimationTime {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.milliseconds.to_bits().hash(state);
+  }
+}
+
 impl AnimationTime {
   /// Creates a time value from milliseconds.
   pub const fn from_milliseconds(milliseconds: f32) -> Self {
@@ -44,7 +53,7 @@ impl<'i> FromCss<'i> for AnimationTime {
 }
 
 /// Parsed values for `animation-name`.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Hash)]
 pub struct AnimationNames(pub Box<[String]>);
 
 impl MakeComputed for AnimationNames {}
@@ -82,7 +91,7 @@ impl<'i> FromCss<'i> for AnimationNames {
 }
 
 /// Parsed values for `animation-duration` and `animation-delay`.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Hash)]
 pub struct AnimationDurations(pub(crate) Box<[AnimationTime]>);
 
 impl MakeComputed for AnimationDurations {}
@@ -122,10 +131,21 @@ pub enum AnimationTimingFunction {
   CubicBezier(f32, f32, f32, f32),
 }
 
+impl Hash for AnimationTimingFunction {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    std::mem::discriminant(self).hash(state);
+    match *self {
+      Self::Steps(steps, position) => (steps, position).hash(state),
+      Self::CubicBezier(x1, y1, x2, y2) => [x1, y1, x2, y2].map(f32::to_bits).hash(state),
+      _ => {}
+    }
+  }
+}
+
 impl MakeComputed for AnimationTimingFunction {}
 
 /// Supported step positions for CSS stepped easing functions.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum StepPosition {
   /// Jumps at the start of each step interval.
@@ -176,7 +196,7 @@ impl<'i> FromCss<'i> for AnimationTimingFunction {
 }
 
 /// Parsed values for `animation-timing-function`.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub struct AnimationTimingFunctions(pub Box<[AnimationTimingFunction]>);
 
@@ -202,6 +222,15 @@ pub enum AnimationIterationCount {
   Infinite,
 }
 
+impl Hash for AnimationIterationCount {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    std::mem::discriminant(self).hash(state);
+    if let Self::Number(count) = self {
+      count.to_bits().hash(state);
+    }
+  }
+}
+
 impl Default for AnimationIterationCount {
   fn default() -> Self {
     Self::Number(1.0)
@@ -234,7 +263,7 @@ impl<'i> FromCss<'i> for AnimationIterationCount {
 }
 
 /// Parsed values for `animation-iteration-count`.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub struct AnimationIterationCounts(pub Box<[AnimationIterationCount]>);
 
@@ -251,7 +280,7 @@ impl<'i> FromCss<'i> for AnimationIterationCounts {
 }
 
 /// Supported values for `animation-direction`.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum AnimationDirection {
   #[default]
@@ -274,7 +303,7 @@ declare_enum_from_css_impl!(
 );
 
 /// Parsed values for `animation-direction`.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub struct AnimationDirections(pub Box<[AnimationDirection]>);
 
@@ -291,7 +320,7 @@ impl<'i> FromCss<'i> for AnimationDirections {
 }
 
 /// Supported values for `animation-fill-mode`.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum AnimationFillMode {
   #[default]
@@ -314,7 +343,7 @@ declare_enum_from_css_impl!(
 );
 
 /// Parsed values for `animation-fill-mode`.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub struct AnimationFillModes(pub Box<[AnimationFillMode]>);
 
@@ -331,7 +360,7 @@ impl<'i> FromCss<'i> for AnimationFillModes {
 }
 
 /// Supported values for `animation-play-state`.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum AnimationPlayState {
   #[default]
@@ -348,7 +377,7 @@ declare_enum_from_css_impl!(
 );
 
 /// Parsed values for `animation-play-state`.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub struct AnimationPlayStates(pub Box<[AnimationPlayState]>);
 
@@ -365,7 +394,7 @@ impl<'i> FromCss<'i> for AnimationPlayStates {
 }
 
 /// Parsed value for one `animation` shorthand item.
-#[derive(Debug, Clone, PartialEq, Default, TypedBuilder)]
+#[derive(Debug, Clone, PartialEq, Default, TypedBuilder, Hash)]
 #[non_exhaustive]
 #[builder(field_defaults(default))]
 pub struct Animation {
@@ -1,3 +1,5 @@
+use std::hash::{Hash, Hasher};
+
 use cssparser::Parser;
 
 use crate::layout::style::{
@@ -17,6 +19,15 @@ pub enum AspectRatio {
   Ratio(f32),
 }
 
+impl Hash for AspectRatio {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    std::mem::discriminant(self).hash(state);
+    if let Self::Ratio(ratio) = self {
+      ratio.to_bits().hash(state);
+    }
+  }
+}
+
 impl MakeComputed for AspectRatio {}
 
 impl Animatable for AspectRatio {
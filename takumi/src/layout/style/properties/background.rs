@@ -21,6 +21,10 @@ pub struct Background {
   pub repeat: BackgroundRepeat,
   /// Background clip.
   pub clip: BackgroundClip,
+  /// Background origin.
+  pub origin: BackgroundOrigin,
+  /// Background attachment.
+  pub attachment: BackgroundAttachment,
   /// Background blend mode.
   pub blend_mode: BlendMode,
 }
@@ -41,6 +45,8 @@ impl<'i> FromCss<'i> for Background {
     let mut size = None;
     let mut repeat = None;
     let mut clip = None;
+    let mut origin = None;
+    let mut attachment = None;
     let mut blend_mode = None;
 
     while !input.is_exhausted() && !next_is_comma(input) {
@@ -84,6 +90,23 @@ impl<'i> FromCss<'i> for Background {
         continue;
       }
 
+      // Try to parse background-attachment
+      if attachment.is_none()
+        && let Ok(value) = input.try_parse(BackgroundAttachment::from_css)
+      {
+        attachment = Some(value);
+        continue;
+      }
+
+      // The first box sets background-origin, and background-clip too unless a second one follows
+      if origin.is_none()
+        && clip.is_none()
+        && let Ok(value) = input.try_parse(BackgroundOrigin::from_css)
+      {
+        origin = Some(value);
+        continue;
+      }
+
       // Try to parse background-clip
       if clip.is_none()
         && let Ok(value) = input.try_parse(BackgroundClip::from_css)
@@ -113,7 +136,9 @@ impl<'i> FromCss<'i> for Background {
       position: position.unwrap_or_default(),
       size: size.unwrap_or_default(),
       repeat: repeat.unwrap_or_default(),
-      clip: clip.unwrap_or_default(),
+      clip: clip.or(origin.map(Into::into)).unwrap_or_default(),
+      origin: origin.unwrap_or_default(),
+      attachment: attachment.unwrap_or_default(),
       blend_mode: blend_mode.unwrap_or_default(),
     })
   }
@@ -124,6 +149,9 @@ impl<'i> FromCss<'i> for Background {
     CssToken::Syntax(CssSyntaxKind::Position),
     CssToken::Syntax(CssSyntaxKind::Repeat),
     CssToken::Syntax(CssSyntaxKind::Clip),
+    CssToken::Keyword("scroll"),
+    CssToken::Keyword("fixed"),
+    CssToken::Keyword("local"),
     CssToken::Descriptor(CssDescriptorKind::BlendMode),
   ];
 }
@@ -165,11 +193,42 @@ mod tests {
       Ok(Background {
         color: Some(ColorInput::Value(Color([255, 0, 0, 255]))),
         clip: BackgroundClip::BorderBox,
+        origin: BackgroundOrigin::BorderBox,
         ..Default::default()
       })
     );
   }
 
+  #[test]
+  fn test_parse_background_origin_and_clip() {
+    assert_eq!(
+      Background::from_str("content-box padding-box fixed"),
+      Ok(Background {
+        origin: BackgroundOrigin::ContentBox,
+        clip: BackgroundClip::PaddingBox,
+        attachment: BackgroundAttachment::Fixed,
+        ..Default::default()
+      })
+    );
+  }
+
+  #[test]
+  fn test_parse_backgrounds_layers_with_size() {
+    let result = Backgrounds::from_str(
+      "url(a.png) center / cover no-repeat local, linear-gradient(red, blue)",
+    );
+    assert!(result.is_ok());
+    let Ok(layers) = result else { unreachable!() };
+
+    assert_eq!(layers.len(), 2);
+    assert_eq!(layers[0].image, BackgroundImage::Url("a.png".into()));
+    assert_eq!(layers[0].size, BackgroundSize::Cover);
+    assert_eq!(layers[0].repeat, BackgroundRepeat::no_repeat());
+    assert_eq!(layers[0].attachment, BackgroundAttachment::Local);
+    assert!(matches!(layers[1].image, BackgroundImage::Linear(_)));
+    assert_eq!(layers[1].attachment, BackgroundAttachment::Scroll);
+  }
+
   #[test]
   fn test_parse_background_with_position_and_size() {
     assert_eq!(
@@ -0,0 +1,59 @@
+use cssparser::Parser;
+
+use crate::layout::style::{
+  Animatable, CssToken, FromCss, ListInterpolationStrategy, ParseResult,
+  declare_enum_from_css_impl, tw::TailwindPropertyParser,
+};
+
+/// Defines whether a background scrolls with its element.
+///
+/// `fixed` layers are positioned against the viewport, except on transformed elements, where they
+/// paint like `scroll` as in browsers. Rendered images never scroll, so `local` paints like `scroll`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
+#[non_exhaustive]
+pub enum BackgroundAttachment {
+  /// The background is fixed relative to the element
+  #[default]
+  Scroll,
+  /// The background is fixed relative to the viewport
+  Fixed,
+  /// The background is fixed relative to the element's contents
+  Local,
+}
+
+declare_enum_from_css_impl!(
+  BackgroundAttachment,
+  "scroll" => BackgroundAttachment::Scroll,
+  "fixed" => BackgroundAttachment::Fixed,
+  "local" => BackgroundAttachment::Local
+);
+
+impl TailwindPropertyParser for BackgroundAttachment {
+  fn parse_tw(token: &str) -> Option<Self> {
+    Self::from_str(token).ok()
+  }
+}
+
+impl Animatable for BackgroundAttachment {
+  fn list_interpolation_strategy() -> ListInterpolationStrategy {
+    ListInterpolationStrategy::RepeatToLcm
+  }
+}
+
+/// A list of background-attachment values (one per layer).
+pub type BackgroundAttachments = Box<[BackgroundAttachment]>;
+
+impl<'i> FromCss<'i> for BackgroundAttachments {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let mut values = Vec::new();
+    values.push(BackgroundAttachment::from_css(input)?);
+
+    while input.expect_comma().is_ok() {
+      values.push(BackgroundAttachment::from_css(input)?);
+    }
+
+    Ok(values.into_boxed_slice())
+  }
+
+  const VALID_TOKENS: &'static [CssToken] = BackgroundAttachment::VALID_TOKENS;
+}
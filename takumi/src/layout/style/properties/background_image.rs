@@ -9,7 +9,7 @@ use crate::layout::style::{
 use crate::rendering::Sizing;
 
 /// Background image variants supported by Takumi.
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum BackgroundImage {
   /// No background image.
@@ -0,0 +1,71 @@
+use cssparser::Parser;
+
+use crate::layout::style::{
+  Animatable, BackgroundClip, CssToken, FromCss, ListInterpolationStrategy, ParseResult,
+  declare_enum_from_css_impl, tw::TailwindPropertyParser,
+};
+
+/// Defines the box that background images are positioned and sized against.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
+#[non_exhaustive]
+pub enum BackgroundOrigin {
+  /// The background is positioned relative to the border box
+  BorderBox,
+  /// The background is positioned relative to the padding box
+  #[default]
+  PaddingBox,
+  /// The background is positioned relative to the content box
+  ContentBox,
+}
+
+declare_enum_from_css_impl!(
+  BackgroundOrigin,
+  "border-box" => BackgroundOrigin::BorderBox,
+  "padding-box" => BackgroundOrigin::PaddingBox,
+  "content-box" => BackgroundOrigin::ContentBox
+);
+
+impl From<BackgroundOrigin> for BackgroundClip {
+  fn from(origin: BackgroundOrigin) -> Self {
+    match origin {
+      BackgroundOrigin::BorderBox => BackgroundClip::BorderBox,
+      BackgroundOrigin::PaddingBox => BackgroundClip::PaddingBox,
+      BackgroundOrigin::ContentBox => BackgroundClip::ContentBox,
+    }
+  }
+}
+
+impl TailwindPropertyParser for BackgroundOrigin {
+  fn parse_tw(token: &str) -> Option<Self> {
+    cssparser::match_ignore_ascii_case! {token,
+      "border" => Some(BackgroundOrigin::BorderBox),
+      "padding" => Some(BackgroundOrigin::PaddingBox),
+      "content" => Some(BackgroundOrigin::ContentBox),
+      _ => None,
+    }
+  }
+}
+
+impl Animatable for BackgroundOrigin {
+  fn list_interpolation_strategy() -> ListInterpolationStrategy {
+    ListInterpolationStrategy::RepeatToLcm
+  }
+}
+
+/// A list of background-origin values (one per layer).
+pub type BackgroundOrigins = Box<[BackgroundOrigin]>;
+
+impl<'i> FromCss<'i> for BackgroundOrigins {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let mut values = Vec::new();
+    values.push(BackgroundOrigin::from_css(input)?);
+
+    while input.expect_comma().is_ok() {
+      values.push(BackgroundOrigin::from_css(input)?);
+    }
+
+    Ok(values.into_boxed_slice())
+  }
+
+  const VALID_TOKENS: &'static [CssToken] = BackgroundOrigin::VALID_TOKENS;
+}
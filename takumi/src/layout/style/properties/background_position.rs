@@ -10,7 +10,7 @@ use crate::{
 };
 
 /// Horizontal keywords for `background-position`.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum PositionKeywordX {
   /// Align to the left edge.
@@ -22,7 +22,7 @@ pub enum PositionKeywordX {
 }
 
 /// Vertical keywords for `background-position`.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum PositionKeywordY {
   /// Align to the top edge.
@@ -34,7 +34,7 @@ pub enum PositionKeywordY {
 }
 
 /// A single `background-position` component for an axis.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum PositionComponent {
   /// A horizontal keyword.
@@ -99,7 +99,7 @@ impl From<PositionComponent> for Length {
 }
 
 /// Parsed position value for one layer-like CSS property.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 pub struct BackgroundPosition<const DEFAULT_TOP_LEFT: bool = true>(
   pub SpacePair<PositionComponent>,
 );
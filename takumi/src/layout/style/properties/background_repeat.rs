@@ -6,7 +6,7 @@ use crate::layout::style::{
 };
 
 /// Per-axis repeat style.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum BackgroundRepeatStyle {
   /// Tile as many times as needed with no extra spacing
@@ -29,7 +29,7 @@ declare_enum_from_css_impl!(
 );
 
 /// Combined repeat for X and Y axes.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 pub struct BackgroundRepeat(pub BackgroundRepeatStyle, pub BackgroundRepeatStyle);
 
 impl MakeComputed for BackgroundRepeat {}
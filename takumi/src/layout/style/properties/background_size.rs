@@ -24,7 +24,7 @@ pub(crate) struct ResolvedBackgroundSize {
 }
 
 /// Parsed `background-size` for one layer.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum BackgroundSize {
   /// Scale the image to cover the container (may crop).
@@ -1,9 +1,11 @@
+use std::hash::{Hash, Hasher};
+
 use cssparser::Parser;
 
 use crate::{
   layout::style::{
-    BorderStyle, ColorInput, CssSyntaxKind, CssToken, FromCss, MakeComputed, ParseResult,
-    properties::Length,
+    Animatable, BorderStyle, Color, ColorInput, CssSyntaxKind, CssToken, FromCss, MakeComputed,
+    ParseResult, PercentageNumber, lerp, properties::Length,
   },
   rendering::Sizing,
 };
@@ -72,6 +74,42 @@ impl MakeComputed for Border {
   }
 }
 
+/// Parsed `corner-smoothing` value, from `0` for circular corners to `1` (or `100%`) for fully
+/// smoothed, squircle-like corners.
+///
+/// This is a non-standard property matching the corner smoothing parameter of Figma.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CornerSmoothing(pub f32);
+
+impl Hash for CornerSmoothing {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.0.to_bits().hash(state);
+  }
+}
+
+impl<'i> FromCss<'i> for CornerSmoothing {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    PercentageNumber::from_css(input).map(|value| Self(value.0.min(1.0)))
+  }
+
+  const VALID_TOKENS: &'static [CssToken] = PercentageNumber::VALID_TOKENS;
+}
+
+impl MakeComputed for CornerSmoothing {}
+
+impl Animatable for CornerSmoothing {
+  fn interpolate(
+    &mut self,
+    from: &Self,
+    to: &Self,
+    progress: f32,
+    _sizing: &Sizing,
+    _current_color: Color,
+  ) {
+    *self = Self(lerp(from.0, to.0, progress));
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use crate::layout::style::Color;
@@ -83,9 +121,16 @@ mod tests {
     assert_eq!(BorderStyle::from_str("solid"), Ok(BorderStyle::Solid));
   }
 
+  #[test]
+  fn test_parse_border_style_patterns() {
+    assert_eq!(BorderStyle::from_str("dashed"), Ok(BorderStyle::Dashed));
+    assert_eq!(BorderStyle::from_str("dotted"), Ok(BorderStyle::Dotted));
+    assert_eq!(BorderStyle::from_str("double"), Ok(BorderStyle::Double));
+  }
+
   #[test]
   fn test_parse_border_style_invalid() {
-    assert!(BorderStyle::from_str("dashed").is_err());
+    assert!(BorderStyle::from_str("groove").is_err());
   }
 
   #[test]
@@ -148,6 +193,18 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_parse_border_width_dashed_color() {
+    assert_eq!(
+      Border::from_str("2px dashed red"),
+      Ok(Border {
+        width: Length::Px(2.0),
+        style: BorderStyle::Dashed,
+        color: ColorInput::Value(Color([255, 0, 0, 255])),
+      })
+    );
+  }
+
   #[test]
   fn test_parse_border_style_width_color() {
     assert_eq!(
@@ -210,7 +267,7 @@ mod tests {
 
   #[test]
   fn test_parse_border_invalid_style() {
-    assert!(Border::from_str("2px dashed red").is_err());
+    assert!(Border::from_str("2px groove red").is_err());
   }
 
   #[test]
@@ -239,4 +296,11 @@ mod tests {
   fn test_border_value_from_invalid_css() {
     assert!(Border::from_str("invalid border").is_err());
   }
+
+  #[test]
+  fn test_parse_corner_smoothing() {
+    assert_eq!(CornerSmoothing::from_str("0.6"), Ok(CornerSmoothing(0.6)));
+    assert_eq!(CornerSmoothing::from_str("60%"), Ok(CornerSmoothing(0.6)));
+    assert_eq!(CornerSmoothing::from_str("2"), Ok(CornerSmoothing(1.0)));
+  }
 }
@@ -13,7 +13,7 @@ use crate::{
 
 /// Represents a box shadow with all its properties.
 /// Construct with [`BoxShadow::builder`].
-#[derive(Debug, Clone, PartialEq, Copy, Default, TypedBuilder)]
+#[derive(Debug, Clone, PartialEq, Copy, Default, TypedBuilder, Hash)]
 #[non_exhaustive]
 #[builder(field_defaults(default))]
 pub struct BoxShadow {
@@ -13,7 +13,7 @@ use crate::{
 /// Represents the fill rule used for determining the interior of shapes.
 ///
 /// Corresponds to the SVG fill-rule attribute and is used in polygon(), path(), and shape() functions.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum FillRule {
   /// The default rule - counts the number of times a ray from the point crosses the shape's edges
@@ -35,7 +35,7 @@ impl From<FillRule> for Fill {
 }
 
 /// Represents radius values for circle() and ellipse() functions.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum ShapeRadius {
   /// Uses the length from the center to the closest side of the reference box
@@ -56,7 +56,7 @@ impl MakeComputed for ShapeRadius {
 }
 
 /// Represents a position for circle() and ellipse() functions.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct ShapePosition(pub SpacePair<Length>);
 
@@ -76,7 +76,7 @@ impl Default for ShapePosition {
 ///
 /// The inset() function creates an inset rectangle, with its size defined by the offset distance
 /// of each of the four sides of its container and, optionally, rounded corners.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct InsetShape {
   /// Sides of the inset.
@@ -93,7 +93,7 @@ impl MakeComputed for InsetShape {
 }
 
 /// Represents an ellipse() shape.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct EllipseShape {
   /// The horizontal radius
@@ -116,7 +116,7 @@ impl MakeComputed for EllipseShape {
 pub type PolygonCoordinate = SpacePair<Length>;
 
 /// Represents a polygon() shape.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct PolygonShape {
   /// The fill rule to use
@@ -132,7 +132,7 @@ impl MakeComputed for PolygonShape {
 }
 
 /// Represents a path() shape using an SVG path string.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct PathShape {
   /// The fill rule to use
@@ -142,7 +142,7 @@ pub struct PathShape {
 }
 
 /// Represents a basic shape function for clip-path.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum BasicShape {
   /// inset() function
@@ -220,6 +220,7 @@ impl BasicShape {
               )
             })
             .unwrap_or_default(),
+          smoothing: 0.0,
           image_rendering: ImageScalingAlgorithm::Auto,
           style: BorderStyle::Solid,
         };
@@ -1,4 +1,7 @@
-use std::fmt::Display;
+use std::{
+  fmt::Display,
+  hash::{Hash, Hasher},
+};
 
 use color::{AlphaColor, ColorSpaceTag, DynamicColor, HueDirection, Srgb, parse_color};
 use cssparser::{
@@ -34,6 +37,13 @@ pub struct ColorInterpolationMethod {
   pub hue_direction: HueDirection,
 }
 
+impl Hash for ColorInterpolationMethod {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.color_space.hash(state);
+    std::mem::discriminant(&self.hue_direction).hash(state);
+  }
+}
+
 impl Default for ColorInterpolationMethod {
   fn default() -> Self {
     Self {
@@ -112,7 +122,7 @@ impl<'i> FromCss<'i> for ColorInterpolationMethod {
 }
 
 /// Represents a color with 8-bit RGBA components.
-#[derive(Debug, Default, Clone, PartialEq, Copy)]
+#[derive(Debug, Default, Clone, PartialEq, Copy, Hash)]
 pub struct Color(pub [u8; 4]);
 
 impl From<[u8; 4]> for Color {
@@ -133,13 +143,24 @@ impl From<[f32; 4]> for Color {
 }
 
 /// Represents a color input value.
-#[derive(Debug, Clone, PartialEq, Copy)]
+#[derive(Debug, Clone, PartialEq, Copy, Hash)]
 #[non_exhaustive]
 pub enum ColorInput<const DEFAULT_CURRENT_COLOR: bool = true> {
   /// Inherit from the `color` value.
   CurrentColor,
   /// A color value.
   Value(Color),
+  /// `contrast-auto`, picks whichever of `dark` and `light` contrasts more with the background
+  /// colors behind the element. Defaults to black and white, and can be customized with
+  /// `contrast-auto(<dark>, <light>)`.
+  ///
+  /// Only `color` looks at the background, other properties resolve it like `currentColor`.
+  ContrastAuto {
+    /// The color used on light backgrounds.
+    dark: Color,
+    /// The color used on dark backgrounds.
+    light: Color,
+  },
 }
 
 impl<const DEFAULT_CURRENT_COLOR: bool> MakeComputed for ColorInput<DEFAULT_CURRENT_COLOR> {}
@@ -164,6 +185,14 @@ impl<const DEFAULT_CURRENT_COLOR: bool> Animatable for ColorInput<DEFAULT_CURREN
         ))
       }
       (ColorInput::CurrentColor, ColorInput::CurrentColor) => ColorInput::CurrentColor,
+      // The picked color depends on the backdrop, so keep the keyword and flip at the midpoint.
+      (ColorInput::ContrastAuto { .. }, ColorInput::ContrastAuto { .. }) => {
+        if progress < 0.5 {
+          *from
+        } else {
+          *to
+        }
+      }
       _ => ColorInput::Value(interpolate_with_color_space(
         from.resolve(current_color),
         to.resolve(current_color),
@@ -186,11 +215,36 @@ impl<const DEFAULT_CURRENT_COLOR: bool> Default for ColorInput<DEFAULT_CURRENT_C
 }
 
 impl<const DEFAULT_CURRENT_COLOR: bool> ColorInput<DEFAULT_CURRENT_COLOR> {
+  /// `contrast-auto` choosing between black and white.
+  pub const fn contrast_auto() -> Self {
+    ColorInput::ContrastAuto {
+      dark: Color::black(),
+      light: Color::white(),
+    }
+  }
+
   /// Resolves the color input to a color.
   pub fn resolve(self, current_color: Color) -> Color {
     match self {
       ColorInput::Value(color) => color,
-      ColorInput::CurrentColor => current_color,
+      ColorInput::CurrentColor | ColorInput::ContrastAuto { .. } => current_color,
+    }
+  }
+
+  /// Resolves the color input, picking the `contrast-auto` color against `backdrop`.
+  ///
+  /// A translucent backdrop is assumed to sit on white.
+  pub(crate) fn resolve_with_backdrop(self, current_color: Color, backdrop: Color) -> Color {
+    let ColorInput::ContrastAuto { dark, light } = self else {
+      return self.resolve(current_color);
+    };
+
+    let backdrop = backdrop.composite_over(Color::white());
+
+    if dark.contrast_ratio(backdrop) >= light.contrast_ratio(backdrop) {
+      dark
+    } else {
+      light
     }
   }
 }
@@ -203,6 +257,10 @@ impl<const DEFAULT_CURRENT_COLOR: bool> TailwindPropertyParser
       return Some(ColorInput::CurrentColor);
     }
 
+    if token.eq_ignore_ascii_case("contrast-auto") {
+      return Some(ColorInput::contrast_auto());
+    }
+
     Color::parse_tw(token).map(ColorInput::Value)
   }
 }
@@ -459,6 +517,46 @@ impl Color {
     Color([255, 255, 255, 255])
   }
 
+  /// The WCAG relative luminance of the color, ignoring alpha.
+  pub fn relative_luminance(self) -> f32 {
+    let channel = |value: u8| {
+      let value = value as f32 / 255.0;
+
+      if value <= 0.04045 {
+        value / 12.92
+      } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+      }
+    };
+
+    0.2126 * channel(self.0[0]) + 0.7152 * channel(self.0[1]) + 0.0722 * channel(self.0[2])
+  }
+
+  /// The WCAG contrast ratio between two colors, from 1 to 21.
+  pub fn contrast_ratio(self, other: Color) -> f32 {
+    let lhs = self.relative_luminance();
+    let rhs = other.relative_luminance();
+
+    (lhs.max(rhs) + 0.05) / (lhs.min(rhs) + 0.05)
+  }
+
+  /// Composites this color over `backdrop` with source-over blending.
+  pub fn composite_over(self, backdrop: Color) -> Color {
+    let alpha = self.0[3] as f32 / 255.0;
+    let backdrop_alpha = backdrop.0[3] as f32 / 255.0 * (1.0 - alpha);
+    let out_alpha = alpha + backdrop_alpha;
+
+    if out_alpha <= f32::EPSILON {
+      return Color::transparent();
+    }
+
+    let channel = |index: usize| {
+      (self.0[index] as f32 * alpha + backdrop.0[index] as f32 * backdrop_alpha) / out_alpha
+    };
+
+    Color::from([channel(0), channel(1), channel(2), out_alpha * 255.0])
+  }
+
   /// Apply opacity to alpha channel
   pub fn with_opacity(mut self, opacity: u8) -> Self {
     self.0[3] = fast_div_255(self.0[3] as u32 * opacity as u32);
@@ -600,11 +698,32 @@ impl<'i, const DEFAULT_CURRENT_COLOR: bool> FromCss<'i> for ColorInput<DEFAULT_C
       return Ok(ColorInput::CurrentColor);
     }
 
+    if input
+      .try_parse(|input| input.expect_ident_matching("contrast-auto"))
+      .is_ok()
+    {
+      return Ok(ColorInput::contrast_auto());
+    }
+
+    if input
+      .try_parse(|input| input.expect_function_matching("contrast-auto"))
+      .is_ok()
+    {
+      return input.parse_nested_block(|input| {
+        let dark = Color::from_css(input)?;
+        input.expect_comma()?;
+        let light = Color::from_css(input)?;
+
+        Ok(ColorInput::ContrastAuto { dark, light })
+      });
+    }
+
     Ok(ColorInput::Value(Color::from_css(input)?))
   }
 
   const VALID_TOKENS: &'static [CssToken] = &[
     CssToken::Keyword("currentColor"),
+    CssToken::Keyword("contrast-auto"),
     CssToken::Syntax(CssSyntaxKind::Color),
   ];
 }
@@ -669,6 +788,50 @@ impl<'i> FromCss<'i> for Color {
 mod tests {
   use super::*;
 
+  #[test]
+  fn parses_contrast_auto() {
+    assert_eq!(
+      ColorInput::from_str("contrast-auto"),
+      Ok(ColorInput::<true>::contrast_auto())
+    );
+    assert_eq!(
+      ColorInput::from_str("contrast-auto(#111, rgb(250, 250, 240))"),
+      Ok(ColorInput::<true>::ContrastAuto {
+        dark: Color([17, 17, 17, 255]),
+        light: Color([250, 250, 240, 255]),
+      })
+    );
+    assert!(ColorInput::<true>::from_str("contrast-auto(#111)").is_err());
+  }
+
+  #[test]
+  fn contrast_auto_picks_higher_contrast_color() {
+    let palette = ColorInput::<true>::ContrastAuto {
+      dark: Color::from_rgb(0x1e3a8a),
+      light: Color::from_rgb(0xeff6ff),
+    };
+
+    assert_eq!(
+      palette.resolve_with_backdrop(Color::black(), Color::from_rgb(0xfacc15)),
+      Color::from_rgb(0x1e3a8a)
+    );
+    assert_eq!(
+      palette.resolve_with_backdrop(Color::black(), Color::from_rgb(0x7e22ce)),
+      Color::from_rgb(0xeff6ff)
+    );
+    assert_eq!(
+      ColorInput::<true>::Value(Color::white())
+        .resolve_with_backdrop(Color::black(), Color::white()),
+      Color::white()
+    );
+  }
+
+  #[test]
+  fn contrast_ratio_matches_wcag() {
+    assert!((Color::black().contrast_ratio(Color::white()) - 21.0).abs() < 0.01);
+    assert!((Color::white().contrast_ratio(Color::white()) - 1.0).abs() < 0.01);
+  }
+
   #[test]
   fn test_color_from_f32_array_clamps_before_rounding() {
     assert_eq!(
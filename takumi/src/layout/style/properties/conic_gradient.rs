@@ -18,7 +18,7 @@ use crate::{
 };
 
 /// Represents a CSS conic-gradient.
-#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+#[derive(Debug, Clone, PartialEq, TypedBuilder, Hash)]
 #[non_exhaustive]
 pub struct ConicGradient {
   /// Whether the gradient repeats beyond the last stop.
@@ -0,0 +1,141 @@
+use cssparser::{Parser, Token, match_ignore_ascii_case};
+
+use crate::{
+  layout::style::{
+    CssSyntaxKind, CssToken, FromCss, Length, LengthDefaultsToZero, MakeComputed, ParseResult,
+    Sides,
+  },
+  rendering::Sizing,
+};
+
+/// Fades content out toward the edges of a clipping container, where it is cut off.
+///
+/// Only edges on an axis with `overflow` other than `visible`, and that content extends past,
+/// are faded. The fade is drawn over the content, the container's own background and border
+/// stay opaque. Percentages refer to the size of the padding box along the faded axis.
+///
+/// Syntax: `none | [ top | right | bottom | left | x | y | all ]+ <length-percentage>`.
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+pub struct FadeEdges {
+  /// Whether the top, right, bottom and left edges fade.
+  pub edges: Sides<bool>,
+  /// The distance the content fades out over.
+  pub length: LengthDefaultsToZero,
+}
+
+impl Default for FadeEdges {
+  fn default() -> Self {
+    Self {
+      edges: Sides([false; 4]),
+      length: Length::zero(),
+    }
+  }
+}
+
+impl FadeEdges {
+  /// Returns `true` if no edge fades.
+  pub(crate) fn is_none(self) -> bool {
+    !self.edges.0.contains(&true)
+  }
+}
+
+impl<'i> FromCss<'i> for FadeEdges {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    if input
+      .try_parse(|input| input.expect_ident_matching("none"))
+      .is_ok()
+    {
+      return Ok(FadeEdges::default());
+    }
+
+    let mut edges = [false; 4];
+
+    loop {
+      let location = input.current_source_location();
+      let Ok(ident) = input.try_parse(Parser::expect_ident_cloned) else {
+        break;
+      };
+
+      let [top, right, bottom, left] = &mut edges;
+
+      match_ignore_ascii_case! { &ident,
+        "top" => *top = true,
+        "right" => *right = true,
+        "bottom" => *bottom = true,
+        "left" => *left = true,
+        "x" => (*left, *right) = (true, true),
+        "y" => (*top, *bottom) = (true, true),
+        "all" => edges = [true; 4],
+        _ => return Err(location.new_unexpected_token_error(Token::Ident(ident))),
+      }
+    }
+
+    if !edges.contains(&true) {
+      let location = input.current_source_location();
+      let token = input.next()?.clone();
+
+      return Err(location.new_unexpected_token_error(token));
+    }
+
+    Ok(FadeEdges {
+      edges: Sides(edges),
+      length: Length::from_css(input)?,
+    })
+  }
+
+  const VALID_TOKENS: &'static [CssToken] = &[
+    CssToken::Keyword("none"),
+    CssToken::Keyword("top"),
+    CssToken::Keyword("right"),
+    CssToken::Keyword("bottom"),
+    CssToken::Keyword("left"),
+    CssToken::Keyword("x"),
+    CssToken::Keyword("y"),
+    CssToken::Keyword("all"),
+    CssToken::Syntax(CssSyntaxKind::Length),
+  ];
+}
+
+impl MakeComputed for FadeEdges {
+  fn make_computed(&mut self, sizing: &Sizing) {
+    self.length.make_computed(sizing);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_edges_and_length() {
+    assert_eq!(FadeEdges::from_str("none"), Ok(FadeEdges::default()));
+    assert_eq!(
+      FadeEdges::from_str("right 32px"),
+      Ok(FadeEdges {
+        edges: Sides([false, true, false, false]),
+        length: Length::Px(32.0),
+      })
+    );
+    assert_eq!(
+      FadeEdges::from_str("x top 10%"),
+      Ok(FadeEdges {
+        edges: Sides([true, true, false, true]),
+        length: Length::Percentage(10.0),
+      })
+    );
+    assert_eq!(
+      FadeEdges::from_str("all 1em"),
+      Ok(FadeEdges {
+        edges: Sides([true; 4]),
+        length: Length::Em(1.0),
+      })
+    );
+  }
+
+  #[test]
+  fn rejects_missing_edges_or_length() {
+    assert!(FadeEdges::from_str("24px").is_err());
+    assert!(FadeEdges::from_str("left").is_err());
+    assert!(FadeEdges::from_str("start 24px").is_err());
+  }
+}
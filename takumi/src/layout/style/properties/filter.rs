@@ -1,4 +1,4 @@
-use cssparser::{Parser, Token, match_ignore_ascii_case};
+use cssparser::{BasicParseErrorKind, Parser, Token, match_ignore_ascii_case};
 use image::{Rgba, RgbaImage, imageops::colorops::huerotate_in_place};
 use smallvec::SmallVec;
 use taffy::{Point, Size};
@@ -6,8 +6,8 @@ use taffy::{Point, Size};
 use crate::{
   Result,
   layout::style::{
-    Affine, Angle, Animatable, BlendMode, Color, CssDescriptorKind, CssToken, FromCss, Length,
-    ListInterpolationStrategy, MakeComputed, ParseResult, PercentageNumber, TextShadow,
+    Affine, Angle, Animatable, BlendMode, Color, ColorInput, CssDescriptorKind, CssToken, FromCss,
+    Length, ListInterpolationStrategy, MakeComputed, ParseResult, PercentageNumber, TextShadow,
     tw::TailwindPropertyParser,
   },
   rendering::{
@@ -57,7 +57,7 @@ pub(crate) fn build_opacity_table(value: f32) -> TransferTable {
 }
 
 /// Represents a single CSS filter operation
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum Filter {
   /// Brightness multiplier (1 = unchanged). Accepts number or percentage
@@ -80,6 +80,10 @@ pub enum Filter {
   Blur(Length),
   /// Drop shadow effect with offset, blur, and color (reuses TextShadow parsing)
   DropShadow(TextShadow),
+  /// Maps luminance onto a ramp from black to the color, like a grayscale image multiplied by it
+  Tint(ColorInput),
+  /// Maps luminance onto a ramp from the shadow color to the highlight color
+  Duotone(ColorInput, ColorInput),
 }
 
 /// A list of filter operations
@@ -116,7 +120,10 @@ impl Animatable for Filter {
         offset_y: Length::zero(),
         blur_radius: Length::zero(),
         color: Color::transparent().into(),
+        inset: false,
       }),
+      // Color ramps have no identity mapping to pad with.
+      Filter::Tint(_) | Filter::Duotone(..) => return None,
     })
   }
 
@@ -179,6 +186,24 @@ impl Animatable for Filter {
         value.interpolate(&from, &to, progress, sizing, current_color);
         Filter::DropShadow(value)
       }
+      (Filter::Tint(from), Filter::Tint(to)) => {
+        let mut value = from;
+        value.interpolate(&from, &to, progress, sizing, current_color);
+        Filter::Tint(value)
+      }
+      (Filter::Duotone(from_shadow, from_highlight), Filter::Duotone(to_shadow, to_highlight)) => {
+        let mut shadow = from_shadow;
+        shadow.interpolate(&from_shadow, &to_shadow, progress, sizing, current_color);
+        let mut highlight = from_highlight;
+        highlight.interpolate(
+          &from_highlight,
+          &to_highlight,
+          progress,
+          sizing,
+          current_color,
+        );
+        Filter::Duotone(shadow, highlight)
+      }
       _ => {
         if progress >= 0.5 {
           *to
@@ -199,9 +224,11 @@ impl TailwindPropertyParser for Filters {
 impl Filter {
   pub(crate) fn categorize(&self) -> FilterCategory<'_> {
     match self {
-      Filter::Blur(_) | Filter::DropShadow(_) | Filter::HueRotate(_) => {
-        FilterCategory::Complex(self)
-      }
+      Filter::Blur(_)
+      | Filter::DropShadow(_)
+      | Filter::HueRotate(_)
+      | Filter::Tint(_)
+      | Filter::Duotone(..) => FilterCategory::Complex(self),
       _ => FilterCategory::Pixel(self),
     }
   }
@@ -223,7 +250,7 @@ impl Filter {
 pub(crate) enum FilterCategory<'f> {
   /// Pixel filters that can potentially be batched
   Pixel(&'f Filter),
-  /// Complex filters that need special handling (blur, drop-shadow, hue-rotate)
+  /// Complex filters that need special handling (blur, drop-shadow, hue-rotate, color ramps)
   Complex(&'f Filter),
 }
 
@@ -284,7 +311,33 @@ fn apply_single_pixel_filter(pixel: &mut [u8], filter: &Filter) {
       pixel[3] = ((pixel[3]) as f32 * value).clamp(0.0, 255.0) as u8;
     }
     // Complex filters are not handled here
-    Filter::Blur(_) | Filter::DropShadow(_) | Filter::HueRotate(_) => {}
+    Filter::Blur(_)
+    | Filter::DropShadow(_)
+    | Filter::HueRotate(_)
+    | Filter::Tint(_)
+    | Filter::Duotone(..) => {}
+  }
+}
+
+/// Maps the luminance of every pixel onto a ramp between two colors.
+///
+/// The ramp colors' alpha multiplies the pixel alpha.
+fn apply_color_ramp(image: &mut RgbaImage, shadow: Color, highlight: Color) {
+  for pixel in bytemuck::cast_slice_mut::<u8, [u8; 4]>(image.as_mut()) {
+    if pixel[3] == 0 {
+      continue;
+    }
+
+    let t = get_luma(pixel) / 255.0;
+    for channel in 0..4 {
+      let value = shadow.0[channel] as f32 * (1.0 - t) + highlight.0[channel] as f32 * t;
+
+      pixel[channel] = if channel == 3 {
+        fast_div_255(pixel[3] as u32 * value.round() as u32)
+      } else {
+        value.round().clamp(0.0, 255.0) as u8
+      };
+    }
   }
 }
 
@@ -384,6 +437,16 @@ pub(crate) fn apply_filters<'f, F: Iterator<Item = &'f Filter>>(
             let shadow = SizedShadow::from_text_shadow(drop_shadow, sizing, current_color, size);
             apply_drop_shadow_filter(image, &shadow, buffer_pool)?;
           }
+          Filter::Tint(color) => {
+            apply_color_ramp(image, Color::black(), color.resolve(current_color));
+          }
+          Filter::Duotone(shadow, highlight) => {
+            apply_color_ramp(
+              image,
+              shadow.resolve(current_color),
+              highlight.resolve(current_color),
+            );
+          }
           _ => unreachable!(),
         }
       }
@@ -425,17 +488,16 @@ pub(crate) fn apply_backdrop_filter(
     return Ok(());
   }
 
-  // Generate the mask for the element's shape (with border-radius)
-  let mut paths = Vec::new();
-  border.append_mask_commands(&mut paths, layout_size, Point::ZERO);
-
-  // Render the mask — this borrows mask_memory only for the duration of the
-  // composite loop below. apply_filters borrows buffer_pool separately, so
-  // there is no conflict and no clone is needed.
-  let (mask_data, placement) =
-    canvas
-      .mask_memory
-      .render(&paths, Some(transform), None, &mut canvas.buffer_pool);
+  // Render the mask for the element's shape (with border-radius) — this borrows
+  // mask_memory only for the duration of the composite loop below. apply_filters
+  // borrows buffer_pool separately, so there is no conflict and no clone is needed.
+  let (mask_data, placement) = border.render_mask(
+    layout_size,
+    Point::ZERO,
+    Some(transform),
+    &mut canvas.mask_memory,
+    &mut canvas.buffer_pool,
+  );
 
   if placement.width == 0 || placement.height == 0 {
     return Ok(());
@@ -687,8 +749,21 @@ impl<'i> FromCss<'i> for Filter {
         Ok(Filter::Blur(radius))
       }),
       "drop-shadow" => parser.parse_nested_block(|input| {
-        // drop-shadow uses the same syntax as text-shadow
-        Ok(Filter::DropShadow(TextShadow::from_css(input)?))
+        // drop-shadow uses the same syntax as text-shadow, without `inset`
+        let shadow = TextShadow::from_css(input)?;
+        if shadow.inset {
+          return Err(input.new_error(BasicParseErrorKind::QualifiedRuleInvalid));
+        }
+
+        Ok(Filter::DropShadow(shadow))
+      }),
+      "tint" => parser.parse_nested_block(|input| {
+        Ok(Filter::Tint(ColorInput::from_css(input)?))
+      }),
+      "duotone" => parser.parse_nested_block(|input| {
+        let shadow = ColorInput::from_css(input)?;
+        input.expect_comma()?;
+        Ok(Filter::Duotone(shadow, ColorInput::from_css(input)?))
       }),
       _ => Err(Self::unexpected_token_error(location, token)),
     }
@@ -705,6 +780,8 @@ impl<'i> FromCss<'i> for Filter {
     CssToken::Descriptor(CssDescriptorKind::SepiaFn),
     CssToken::Descriptor(CssDescriptorKind::BlurFn),
     CssToken::Descriptor(CssDescriptorKind::DropShadowFn),
+    CssToken::Descriptor(CssDescriptorKind::TintFn),
+    CssToken::Descriptor(CssDescriptorKind::DuotoneFn),
   ];
 }
 
@@ -740,10 +817,16 @@ mod tests {
         offset_y: Px(4.0),
         blur_radius: Px(6.0),
         color: ColorInput::Value(Color([255, 0, 0, 255])),
+        inset: false,
       }))
     );
   }
 
+  #[test]
+  fn test_parse_drop_shadow_rejects_inset() {
+    assert!(Filter::from_str("drop-shadow(inset 2px 4px red)").is_err());
+  }
+
   #[test]
   fn test_parse_drop_shadow_color_first() {
     assert_eq!(
@@ -753,6 +836,7 @@ mod tests {
         offset_y: Px(4.0),
         blur_radius: Length::zero(),
         color: ColorInput::Value(Color([255, 0, 0, 255])),
+        inset: false,
       }))
     );
   }
@@ -766,10 +850,62 @@ mod tests {
         offset_y: Px(4.0),
         blur_radius: Length::zero(),
         color: ColorInput::CurrentColor,
+        inset: false,
       }))
     );
   }
 
+  #[test]
+  fn test_parse_color_ramp_filters() {
+    assert_eq!(
+      Filter::from_str("tint(red)"),
+      Ok(Filter::Tint(ColorInput::Value(Color([255, 0, 0, 255]))))
+    );
+    assert_eq!(
+      Filter::from_str("duotone(#000080, currentColor)"),
+      Ok(Filter::Duotone(
+        ColorInput::Value(Color([0, 0, 128, 255])),
+        ColorInput::CurrentColor
+      ))
+    );
+    assert!(Filter::from_str("duotone(red)").is_err());
+  }
+
+  #[test]
+  fn test_apply_duotone_maps_luminance() -> Result<()> {
+    let mut image = RgbaImage::from_fn(3, 1, |x, _| match x {
+      0 => Rgba([0, 0, 0, 255]),
+      1 => Rgba([255, 255, 255, 255]),
+      _ => Rgba([128, 128, 128, 128]),
+    });
+
+    let filters = [Filter::Duotone(
+      ColorInput::Value(Color([0, 0, 100, 255])),
+      ColorInput::CurrentColor,
+    )];
+
+    let sizing = Sizing {
+      viewport: Viewport::new((100, 100)),
+      container_size: Size::NONE,
+      font_size: 16.0,
+      zoom: 1.0,
+      calc_arena: Rc::new(CalcArena::default()),
+    };
+    apply_filters(
+      &mut image,
+      &sizing,
+      Color([250, 200, 0, 255]),
+      &mut BufferPool::default(),
+      filters.iter(),
+    )?;
+
+    assert_eq!(image.get_pixel(0, 0).0, [0, 0, 100, 255]);
+    assert_eq!(image.get_pixel(1, 0).0, [250, 200, 0, 255]);
+    assert_eq!(image.get_pixel(2, 0).0, [125, 100, 50, 128]);
+
+    Ok(())
+  }
+
   #[test]
   fn test_apply_filters_lut_batching() -> Result<()> {
     let mut image = RgbaImage::new(1, 1);
@@ -786,6 +922,7 @@ mod tests {
       viewport,
       container_size: Size::NONE,
       font_size: 16.0,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     };
     let mut buffer_pool = BufferPool::default();
@@ -1,3 +1,5 @@
+use std::hash::{Hash, Hasher};
+
 use cssparser::{Parser, match_ignore_ascii_case};
 
 use crate::{
@@ -20,6 +22,13 @@ pub struct Flex {
   pub basis: Length,
 }
 
+impl Hash for Flex {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    [self.grow, self.shrink].map(f32::to_bits).hash(state);
+    self.basis.hash(state);
+  }
+}
+
 impl TailwindPropertyParser for Flex {
   fn parse_tw(token: &str) -> Option<Self> {
     match_ignore_ascii_case! {token,
@@ -1,3 +1,5 @@
+use std::hash::{Hash, Hasher};
+
 use cssparser::Parser;
 
 use crate::layout::style::{
@@ -10,6 +12,12 @@ use crate::rendering::Sizing;
 /// Represents a flex grow value.
 pub struct FlexGrow(pub f32);
 
+impl Hash for FlexGrow {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.0.to_bits().hash(state);
+  }
+}
+
 impl MakeComputed for FlexGrow {}
 
 impl Animatable for FlexGrow {
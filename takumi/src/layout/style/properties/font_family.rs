@@ -9,11 +9,11 @@ use crate::layout::style::{
 
 /// Represents a font family for text rendering.
 /// Multi value fallback is supported.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct FontFamily(Box<[FontFamilyToken]>);
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 enum FontFamilyToken {
   Owned(String),
   Generic(GenericFamily),
@@ -104,6 +104,44 @@ impl<'a> From<&'a FontFamily> for FontStack<'a> {
   }
 }
 
+impl FontFamily {
+  /// Follows each named family with its fallback chain.
+  ///
+  /// Families already in the list are skipped, so cyclic or overlapping chains stay finite.
+  pub(crate) fn with_fallbacks<'f>(&self, fallbacks: impl Fn(&str) -> &'f [Box<str>]) -> Self {
+    fn push_named<'f>(
+      tokens: &mut Vec<FontFamilyToken>,
+      name: &str,
+      fallbacks: &impl Fn(&str) -> &'f [Box<str>],
+    ) {
+      let seen = tokens.iter().any(
+        |token| matches!(token, FontFamilyToken::Owned(existing) if existing.eq_ignore_ascii_case(name)),
+      );
+
+      if seen {
+        return;
+      }
+
+      tokens.push(FontFamilyToken::Owned(name.to_owned()));
+
+      for fallback in fallbacks(name) {
+        push_named(tokens, fallback, fallbacks);
+      }
+    }
+
+    let mut tokens = Vec::with_capacity(self.0.len());
+
+    for token in &self.0 {
+      match token {
+        FontFamilyToken::Owned(name) => push_named(&mut tokens, name, &fallbacks),
+        FontFamilyToken::Generic(generic) => tokens.push(FontFamilyToken::Generic(*generic)),
+      }
+    }
+
+    Self(tokens.into_boxed_slice())
+  }
+}
+
 impl From<GenericFamily> for FontFamily {
   fn from(generic: GenericFamily) -> Self {
     Self(Box::new([FontFamilyToken::Generic(generic)]))
@@ -165,4 +203,32 @@ mod tests {
     );
     assert_eq!(FontFamily::parse_tw("display"), None);
   }
+
+  #[test]
+  fn expands_fallback_chains_once() {
+    let chains = [
+      (
+        "inter",
+        vec!["Noto Sans JP".into(), "Noto Color Emoji".into()],
+      ),
+      ("noto sans jp", vec!["Inter".into()]),
+    ];
+    let fallbacks = |family: &str| {
+      chains
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(family))
+        .map_or(&[][..], |(_, chain): &(_, Vec<Box<str>>)| chain.as_slice())
+    };
+
+    assert_eq!(
+      FontFamily::from_str("Inter, \"Noto Color Emoji\", serif")
+        .map(|family| family.with_fallbacks(fallbacks)),
+      Ok(FontFamily(Box::new([
+        FontFamilyToken::Owned(String::from("Inter")),
+        FontFamilyToken::Owned(String::from("Noto Sans JP")),
+        FontFamilyToken::Owned(String::from("Noto Color Emoji")),
+        FontFamilyToken::Generic(GenericFamily::Serif),
+      ])))
+    );
+  }
 }
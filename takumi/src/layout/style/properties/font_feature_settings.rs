@@ -1,3 +1,5 @@
+use std::hash::{Hash, Hasher};
+
 use cssparser::{Parser, Token};
 use parley::FontFeature;
 use swash::tag_from_str_lossy;
@@ -12,6 +14,14 @@ pub type FontFeatureSettings = Box<[FontFeature]>;
 
 impl MakeComputed for FontFeatureSettings {}
 
+/// Hashes the settings, which `Hash` can't be implemented for.
+pub(crate) fn hash_font_feature_settings<H: Hasher>(settings: &FontFeatureSettings, state: &mut H) {
+  state.write_usize(settings.len());
+  for feature in settings.iter() {
+    (feature.tag, feature.value).hash(state);
+  }
+}
+
 impl<'i> FromCss<'i> for FontFeatureSettings {
   fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
     if input
@@ -0,0 +1,23 @@
+use crate::layout::style::declare_enum_from_css_impl;
+
+/// Controls whether the kerning information stored in a font is used.
+///
+/// Corresponds to CSS font-kerning property.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum FontKerning {
+  /// Lets the shaper decide, which applies kerning.
+  #[default]
+  Auto,
+  /// Applies kerning.
+  Normal,
+  /// Disables kerning.
+  None,
+}
+
+declare_enum_from_css_impl!(
+  FontKerning,
+  "auto" => FontKerning::Auto,
+  "normal" => FontKerning::Normal,
+  "none" => FontKerning::None,
+);
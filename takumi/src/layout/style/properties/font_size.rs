@@ -8,7 +8,7 @@ use crate::{
 };
 
 /// Absolute `font-size` keywords.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
 #[non_exhaustive]
 pub enum FontSizeKeyword {
   /// Maps to the `xx-small` keyword.
@@ -80,7 +80,7 @@ impl<'i> FromCss<'i> for FontSizeKeyword {
 }
 
 /// A `font-size` value, either a keyword or an explicit length.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum FontSize {
   /// A CSS absolute-size keyword such as `medium`.
@@ -195,6 +195,7 @@ mod tests {
       viewport: Viewport::new((1200, 630)),
       container_size: Size::NONE,
       font_size: 16.0,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     };
 
@@ -1,3 +1,5 @@
+use std::hash::{Hash, Hasher};
+
 use cssparser::{Parser, Token, match_ignore_ascii_case};
 use parley::FontWidth;
 
@@ -12,6 +14,12 @@ use crate::rendering::Sizing;
 #[non_exhaustive]
 pub struct FontStretch(FontWidth);
 
+impl Hash for FontStretch {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.0.ratio().to_bits().hash(state);
+  }
+}
+
 impl MakeComputed for FontStretch {}
 
 impl Animatable for FontStretch {
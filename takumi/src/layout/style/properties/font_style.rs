@@ -1,3 +1,5 @@
+use std::hash::{Hash, Hasher};
+
 use cssparser::{Parser, Token, match_ignore_ascii_case};
 use parley::style::FontStyle as ParleyFontStyle;
 
@@ -8,6 +10,15 @@ use crate::layout::style::{Angle, CssToken, FromCss, MakeComputed, ParseResult};
 #[non_exhaustive]
 pub struct FontStyle(ParleyFontStyle);
 
+impl Hash for FontStyle {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    std::mem::discriminant(&self.0).hash(state);
+    if let ParleyFontStyle::Oblique(angle) = self.0 {
+      angle.map(f32::to_bits).hash(state);
+    }
+  }
+}
+
 impl MakeComputed for FontStyle {}
 
 impl<'i> FromCss<'i> for FontStyle {
@@ -57,7 +57,7 @@ impl<'i> FromCss<'i> for FontSynthesis {
 }
 
 /// Control mode for synthetic.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum FontSynthesic {
   /// Synthetic is allowed.
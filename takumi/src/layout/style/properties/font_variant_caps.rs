@@ -0,0 +1,67 @@
+use parley::FontFeature;
+use swash::tag_from_bytes;
+
+use crate::layout::style::declare_enum_from_css_impl;
+
+/// Selects small or petite capital glyphs for letters.
+///
+/// Corresponds to CSS font-variant-caps property. When the first available font lacks the
+/// feature, capitals are synthesized by drawing uppercased letters at a reduced size.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum FontVariantCaps {
+  /// Uses the regular glyphs.
+  #[default]
+  Normal,
+  /// Draws lowercase letters as small capitals (`smcp` feature).
+  SmallCaps,
+  /// Draws both lowercase and uppercase letters as small capitals (`smcp` and `c2sc` features).
+  AllSmallCaps,
+  /// Draws lowercase letters as petite capitals (`pcap` feature).
+  PetiteCaps,
+  /// Draws both lowercase and uppercase letters as petite capitals (`pcap` and `c2pc` features).
+  AllPetiteCaps,
+}
+
+declare_enum_from_css_impl!(
+  FontVariantCaps,
+  "normal" => FontVariantCaps::Normal,
+  "small-caps" => FontVariantCaps::SmallCaps,
+  "all-small-caps" => FontVariantCaps::AllSmallCaps,
+  "petite-caps" => FontVariantCaps::PetiteCaps,
+  "all-petite-caps" => FontVariantCaps::AllPetiteCaps,
+);
+
+impl FontVariantCaps {
+  /// Font size ratio of synthesized capitals, matching browsers.
+  pub(crate) const SYNTHETIC_SCALE: f32 = 0.7;
+
+  /// Returns the feature the font must provide to avoid synthesis.
+  pub(crate) fn required_feature(self) -> Option<u32> {
+    match self {
+      Self::Normal => None,
+      Self::SmallCaps | Self::AllSmallCaps => Some(tag_from_bytes(b"smcp")),
+      Self::PetiteCaps | Self::AllPetiteCaps => Some(tag_from_bytes(b"pcap")),
+    }
+  }
+
+  /// Returns true if uppercase letters are turned into capitals as well.
+  pub(crate) fn includes_uppercase(self) -> bool {
+    matches!(self, Self::AllSmallCaps | Self::AllPetiteCaps)
+  }
+
+  /// Returns the OpenType features that select the capitals.
+  pub(crate) fn features(self) -> impl Iterator<Item = FontFeature> {
+    let uppercase = match self {
+      Self::AllSmallCaps => Some(tag_from_bytes(b"c2sc")),
+      Self::AllPetiteCaps => Some(tag_from_bytes(b"c2pc")),
+      _ => None,
+    };
+
+    self
+      .required_feature()
+      .into_iter()
+      .chain(uppercase)
+      .map(|tag| FontFeature { tag, value: 1 })
+  }
+}
@@ -0,0 +1,173 @@
+use cssparser::{Parser, Token, match_ignore_ascii_case};
+use parley::FontFeature;
+use swash::tag_from_bytes;
+
+use crate::layout::style::{CssToken, FromCss, MakeComputed, ParseResult};
+
+/// Controls which ligatures and contextual forms are used in text.
+///
+/// Corresponds to CSS font-variant-ligatures property. Each group is `None` when left to the font's defaults.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct FontVariantLigatures {
+  /// Common ligatures such as `fi` (`liga` and `clig` features).
+  pub common: Option<bool>,
+  /// Discretionary ligatures (`dlig` feature).
+  pub discretionary: Option<bool>,
+  /// Historical ligatures (`hlig` feature).
+  pub historical: Option<bool>,
+  /// Contextual alternates (`calt` feature).
+  pub contextual: Option<bool>,
+}
+
+impl FontVariantLigatures {
+  /// Disables every ligature and contextual form, like `font-variant-ligatures: none`.
+  pub const fn none() -> Self {
+    Self {
+      common: Some(false),
+      discretionary: Some(false),
+      historical: Some(false),
+      contextual: Some(false),
+    }
+  }
+
+  /// Returns the OpenType features that apply the explicitly set groups.
+  pub(crate) fn features(self) -> impl Iterator<Item = FontFeature> {
+    [
+      (self.common, tag_from_bytes(b"liga")),
+      (self.common, tag_from_bytes(b"clig")),
+      (self.discretionary, tag_from_bytes(b"dlig")),
+      (self.historical, tag_from_bytes(b"hlig")),
+      (self.contextual, tag_from_bytes(b"calt")),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, tag)| {
+      enabled.map(|enabled| FontFeature {
+        tag,
+        value: enabled.into(),
+      })
+    })
+  }
+}
+
+impl MakeComputed for FontVariantLigatures {}
+
+impl<'i> FromCss<'i> for FontVariantLigatures {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    if input
+      .try_parse(|input| input.expect_ident_matching("normal"))
+      .is_ok()
+    {
+      return Ok(Self::default());
+    }
+
+    if input
+      .try_parse(|input| input.expect_ident_matching("none"))
+      .is_ok()
+    {
+      return Ok(Self::none());
+    }
+
+    let mut ligatures = Self::default();
+
+    loop {
+      let location = input.current_source_location();
+      let Ok(ident) = input.try_parse(Parser::expect_ident_cloned) else {
+        break;
+      };
+
+      let (group, enabled) = match_ignore_ascii_case! {&ident,
+        "common-ligatures" => (&mut ligatures.common, true),
+        "no-common-ligatures" => (&mut ligatures.common, false),
+        "discretionary-ligatures" => (&mut ligatures.discretionary, true),
+        "no-discretionary-ligatures" => (&mut ligatures.discretionary, false),
+        "historical-ligatures" => (&mut ligatures.historical, true),
+        "no-historical-ligatures" => (&mut ligatures.historical, false),
+        "contextual" => (&mut ligatures.contextual, true),
+        "no-contextual" => (&mut ligatures.contextual, false),
+        _ => return Err(Self::unexpected_token_error(location, &Token::Ident(ident))),
+      };
+
+      // Each group may only be given once.
+      if group.replace(enabled).is_some() {
+        return Err(Self::unexpected_token_error(location, &Token::Ident(ident)));
+      }
+    }
+
+    if ligatures == Self::default() {
+      return Err(input.new_error_for_next_token());
+    }
+
+    Ok(ligatures)
+  }
+
+  const VALID_TOKENS: &'static [CssToken] = &[
+    CssToken::Keyword("normal"),
+    CssToken::Keyword("none"),
+    CssToken::Keyword("common-ligatures"),
+    CssToken::Keyword("no-common-ligatures"),
+    CssToken::Keyword("discretionary-ligatures"),
+    CssToken::Keyword("no-discretionary-ligatures"),
+    CssToken::Keyword("historical-ligatures"),
+    CssToken::Keyword("no-historical-ligatures"),
+    CssToken::Keyword("contextual"),
+    CssToken::Keyword("no-contextual"),
+  ];
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_font_variant_ligatures_keywords() {
+    assert_eq!(
+      FontVariantLigatures::from_str("normal"),
+      Ok(FontVariantLigatures::default())
+    );
+    assert_eq!(
+      FontVariantLigatures::from_str("none"),
+      Ok(FontVariantLigatures::none())
+    );
+  }
+
+  #[test]
+  fn test_parse_font_variant_ligatures_groups() {
+    assert_eq!(
+      FontVariantLigatures::from_str("no-common-ligatures discretionary-ligatures"),
+      Ok(FontVariantLigatures {
+        common: Some(false),
+        discretionary: Some(true),
+        historical: None,
+        contextual: None,
+      })
+    );
+  }
+
+  #[test]
+  fn test_parse_font_variant_ligatures_rejects_repeated_groups() {
+    assert!(FontVariantLigatures::from_str("contextual no-contextual").is_err());
+    assert!(FontVariantLigatures::from_str("ligatures").is_err());
+  }
+
+  #[test]
+  fn test_font_variant_ligatures_features() {
+    let features = FontVariantLigatures {
+      common: Some(false),
+      contextual: Some(true),
+      ..Default::default()
+    }
+    .features()
+    .map(|feature| (feature.tag, feature.value))
+    .collect::<Vec<_>>();
+
+    assert_eq!(
+      features,
+      [
+        (tag_from_bytes(b"liga"), 0),
+        (tag_from_bytes(b"clig"), 0),
+        (tag_from_bytes(b"calt"), 1),
+      ]
+    );
+  }
+}
@@ -1,3 +1,5 @@
+use std::hash::{Hash, Hasher};
+
 use crate::layout::style::{CssSyntaxKind, CssToken, FromCss, MakeComputed, ParseResult};
 use cssparser::{Parser, Token};
 use parley::FontVariation;
@@ -11,6 +13,17 @@ pub type FontVariationSettings = Box<[FontVariation]>;
 
 impl MakeComputed for FontVariationSettings {}
 
+/// Hashes the settings, which `Hash` can't be implemented for.
+pub(crate) fn hash_font_variation_settings<H: Hasher>(
+  settings: &FontVariationSettings,
+  state: &mut H,
+) {
+  state.write_usize(settings.len());
+  for variation in settings.iter() {
+    (variation.tag, variation.value.to_bits()).hash(state);
+  }
+}
+
 impl<'i> FromCss<'i> for FontVariationSettings {
   fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
     if input
@@ -1,3 +1,5 @@
+use std::hash::{Hash, Hasher};
+
 use cssparser::{Parser, Token, match_ignore_ascii_case};
 use parley::style::FontWeight as ParleyFontWeight;
 
@@ -12,6 +14,12 @@ use crate::rendering::Sizing;
 #[non_exhaustive]
 pub struct FontWeight(ParleyFontWeight);
 
+impl Hash for FontWeight {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.0.value().to_bits().hash(state);
+  }
+}
+
 impl MakeComputed for FontWeight {}
 
 impl Animatable for FontWeight {
@@ -0,0 +1,124 @@
+use cssparser::Parser;
+
+use crate::layout::style::{CssToken, FromCss, GridLine, GridPlacement, MakeComputed, ParseResult};
+
+/// Represents the `grid-area` shorthand, which places an item on both grid axes at once.
+///
+/// A single area name such as `grid-area: header` places the item into the matching
+/// `grid-template-areas` region.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct GridArea {
+  /// The row placement (`grid-row-start` / `grid-row-end`)
+  pub row: GridLine,
+  /// The column placement (`grid-column-start` / `grid-column-end`)
+  pub column: GridLine,
+}
+
+impl MakeComputed for GridArea {}
+
+impl<'i> FromCss<'i> for GridArea {
+  /// Parses up to four `/` separated placements in the order
+  /// `row-start / column-start / row-end / column-end`.
+  ///
+  /// Omitted placements copy `column-start` and `row-start` when those are area names, and are `auto` otherwise.
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let mut placements = vec![GridPlacement::from_css(input)?];
+
+    while placements.len() < 4 && input.try_parse(|input| input.expect_delim('/')).is_ok() {
+      placements.push(GridPlacement::from_css(input)?);
+    }
+
+    let mut placements = placements.into_iter();
+    let row_start = placements.next().unwrap_or_default();
+    let column_start = placements
+      .next()
+      .unwrap_or_else(|| named_or_auto(&row_start));
+    let row_end = placements
+      .next()
+      .unwrap_or_else(|| named_or_auto(&row_start));
+    let column_end = placements
+      .next()
+      .unwrap_or_else(|| named_or_auto(&column_start));
+
+    Ok(GridArea {
+      row: GridLine {
+        start: row_start,
+        end: row_end,
+      },
+      column: GridLine {
+        start: column_start,
+        end: column_end,
+      },
+    })
+  }
+
+  const VALID_TOKENS: &'static [CssToken] = GridLine::VALID_TOKENS;
+}
+
+fn named_or_auto(placement: &GridPlacement) -> GridPlacement {
+  match placement {
+    GridPlacement::Named(_) => placement.clone(),
+    _ => GridPlacement::auto(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn named(name: &str) -> GridPlacement {
+    GridPlacement::Named(name.to_string())
+  }
+
+  #[test]
+  fn test_parse_grid_area_name() {
+    assert_eq!(
+      GridArea::from_str("header"),
+      Ok(GridArea {
+        row: GridLine {
+          start: named("header"),
+          end: named("header"),
+        },
+        column: GridLine {
+          start: named("header"),
+          end: named("header"),
+        },
+      })
+    );
+  }
+
+  #[test]
+  fn test_parse_grid_area_lines() {
+    assert_eq!(
+      GridArea::from_str("1 / 2 / 3 / span 2"),
+      Ok(GridArea {
+        row: GridLine {
+          start: GridPlacement::Line(1),
+          end: GridPlacement::Line(3),
+        },
+        column: GridLine {
+          start: GridPlacement::Line(2),
+          end: GridPlacement::span(2),
+        },
+      })
+    );
+  }
+
+  #[test]
+  fn test_parse_grid_area_omitted_lines() {
+    assert_eq!(
+      GridArea::from_str("2 / main"),
+      Ok(GridArea {
+        row: GridLine {
+          start: GridPlacement::Line(2),
+          end: GridPlacement::auto(),
+        },
+        column: GridLine {
+          start: named("main"),
+          end: named("main"),
+        },
+      })
+    );
+  }
+}
@@ -3,7 +3,7 @@ use cssparser::Parser;
 use crate::layout::style::{CssToken, FromCss, MakeComputed, ParseResult};
 
 /// Represents the direction of the grid auto flow.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum GridDirection {
   /// The grid auto flow is in the row direction.
@@ -14,7 +14,7 @@ pub enum GridDirection {
 }
 
 /// Represents the flow of the grid auto placement.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub struct GridAutoFlow {
   /// The direction of the grid auto flow.
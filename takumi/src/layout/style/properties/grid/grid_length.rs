@@ -1,3 +1,5 @@
+use std::hash::{Hash, Hasher};
+
 use cssparser::{Parser, Token};
 use taffy::CompactLength;
 
@@ -24,6 +26,16 @@ pub enum GridLength {
   Unit(Length),
 }
 
+impl Hash for GridLength {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    std::mem::discriminant(self).hash(state);
+    match self {
+      Self::Fr(fraction) => fraction.to_bits().hash(state),
+      Self::Unit(length) => length.hash(state),
+    }
+  }
+}
+
 impl GridLength {
   /// Converts the grid track size to a compact length representation.
   pub(crate) fn to_compact_length(self, sizing: &Sizing) -> CompactLength {
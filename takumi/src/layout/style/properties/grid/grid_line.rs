@@ -1,15 +1,15 @@
 use cssparser::Parser;
 
 use crate::layout::style::{
-  CssSyntaxKind, CssToken, FromCss, GridPlacementKeyword, GridPlacementSpan, MakeComputed,
-  ParseResult, tw::TailwindPropertyParser,
+  CssSyntaxKind, CssToken, FromCss, GridPlacementSpan, MakeComputed, ParseResult,
+  tw::TailwindPropertyParser,
 };
 use crate::rendering::Sizing;
 
 use super::GridPlacement;
 
 /// Represents a grid line placement with serde support
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct GridLine {
   /// The start line placement
@@ -70,20 +70,7 @@ impl From<GridLine> for taffy::Line<taffy::GridPlacement> {
 
 impl From<&GridLine> for taffy::Line<taffy::GridPlacement> {
   fn from(line: &GridLine) -> Self {
-    Self {
-      start: match &line.start {
-        GridPlacement::Keyword(GridPlacementKeyword::Auto) => taffy::GridPlacement::Auto,
-        GridPlacement::Line(index) => taffy::GridPlacement::Line((*index).into()),
-        GridPlacement::Span(GridPlacementSpan::Span(span)) => taffy::GridPlacement::Span(*span),
-        GridPlacement::Named(_) => taffy::GridPlacement::Auto,
-      },
-      end: match &line.end {
-        GridPlacement::Keyword(GridPlacementKeyword::Auto) => taffy::GridPlacement::Auto,
-        GridPlacement::Line(index) => taffy::GridPlacement::Line((*index).into()),
-        GridPlacement::Span(GridPlacementSpan::Span(span)) => taffy::GridPlacement::Span(*span),
-        GridPlacement::Named(_) => taffy::GridPlacement::Auto,
-      },
-    }
+    line.clone().into()
   }
 }
 
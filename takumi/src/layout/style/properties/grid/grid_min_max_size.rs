@@ -6,7 +6,7 @@ use crate::{
 };
 
 /// Represents a grid minmax()
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct GridMinMaxSize {
   /// The minimum size of the grid item
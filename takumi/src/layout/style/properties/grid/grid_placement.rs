@@ -5,7 +5,7 @@ use crate::layout::style::{
 };
 
 /// Represents a grid placement with serde support
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum GridPlacement {
   /// Keyword placement
@@ -45,7 +45,7 @@ impl TailwindPropertyParser for GridPlacement {
 }
 
 /// Represents a grid placement keyword
-#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum GridPlacementKeyword {
   /// Auto placement
@@ -54,7 +54,7 @@ pub enum GridPlacementKeyword {
 }
 
 /// Represents a grid placement span
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum GridPlacementSpan {
   /// Span count
@@ -86,7 +86,7 @@ impl From<GridPlacement> for taffy::GridPlacement {
       GridPlacement::Keyword(GridPlacementKeyword::Auto) => taffy::GridPlacement::Auto,
       GridPlacement::Line(line) => taffy::GridPlacement::Line(line.into()),
       GridPlacement::Span(GridPlacementSpan::Span(span)) => taffy::GridPlacement::Span(span),
-      GridPlacement::Named(_) => taffy::GridPlacement::Auto,
+      GridPlacement::Named(name) => taffy::GridPlacement::NamedLine(name, 0),
     }
   }
 }
@@ -6,7 +6,7 @@ use crate::layout::style::{
 use crate::rendering::Sizing;
 
 /// Represents a grid repeat track
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct GridRepeatTrack {
   /// The size of the grid track
@@ -3,7 +3,7 @@ use cssparser::{Parser, Token};
 use crate::layout::style::{CssSyntaxKind, CssToken, FromCss, ParseResult};
 
 /// Represents grid track repetition keywords
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum GridRepetitionKeyword {
   /// Automatically fills the available space with as many tracks as possible
@@ -13,7 +13,7 @@ pub enum GridRepetitionKeyword {
 }
 
 /// Represents a grid track repetition pattern
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum GridRepetitionCount {
   /// Keywords for auto-fill and auto-fit
@@ -8,7 +8,7 @@ use crate::layout::style::{CssSyntaxKind, CssToken, FromCss, MakeComputed, Parse
 ///
 /// Supports either a 2D matrix of area names (use "." for empty) or a CSS string value
 /// like: "a a ." "b b c"
-#[derive(Default, Debug, Clone, PartialEq)]
+#[derive(Default, Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct GridTemplateAreas(pub Vec<Vec<String>>);
 
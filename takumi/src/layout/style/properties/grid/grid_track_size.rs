@@ -26,7 +26,7 @@ impl<'i> FromCss<'i> for GridTrackSizes {
 }
 
 /// Represents a grid track size
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum GridTrackSize {
   /// A minmax() track size
@@ -1,6 +1,12 @@
-use std::{cell::RefCell, ops::Neg};
+use std::{
+  cell::RefCell,
+  collections::HashMap,
+  hash::{Hash, Hasher},
+  ops::Neg,
+  sync::{Arc, LazyLock, PoisonError, RwLock},
+};
 
-use cssparser::{Parser, Token, match_ignore_ascii_case};
+use cssparser::{CowRcStr, Parser, Token, match_ignore_ascii_case};
 use taffy::{CompactLength, Dimension, LengthPercentage, LengthPercentageAuto};
 
 use crate::{
@@ -23,36 +29,65 @@ const SAFE_INT_MAX_PX: f32 = i32::MAX as f32;
 
 #[derive(Default)]
 pub(crate) struct CalcArena {
-  linear_values: RefCell<Vec<CalcLinear>>,
+  values: RefCell<Vec<ResolvedCalc>>,
+  /// Comparisons looked up during this render, so the shared table is read once per comparison.
+  comparisons: RefCell<HashMap<CalcComparisonId, Arc<CalcComparison>>>,
 }
 
 impl CalcArena {
-  fn register_linear(&self, linear: CalcLinear) -> *const () {
-    let mut linear_values = self.linear_values.borrow_mut();
+  /// Returns the arguments of an interned `min()` or `max()` comparison.
+  fn comparison(&self, id: CalcComparisonId) -> Arc<CalcComparison> {
+    Arc::clone(
+      self
+        .comparisons
+        .borrow_mut()
+        .entry(id)
+        .or_insert_with(|| id.get()),
+    )
+  }
+
+  fn register(&self, value: ResolvedCalc) -> *const () {
+    let mut values = self.values.borrow_mut();
+
+    values.push(value);
+    encode_calc_id(values.len())
+  }
+
+  /// Registers a copy of a registered value scaled by `factor`.
+  fn register_scaled(&self, val: *const (), factor: f32) -> *const () {
+    let scaled = decode_calc_id(val).and_then(|id| {
+      self
+        .values
+        .borrow()
+        .get(id - 1)
+        .map(|value| value.scaled(factor))
+    });
 
-    linear_values.push(linear);
-    encode_linear_id(linear_values.len())
+    self.register(scaled.unwrap_or(ResolvedCalc::Linear(CalcLinear {
+      px: 0.0,
+      percent: 0.0,
+    })))
   }
 
   pub(crate) fn resolve_calc_value(&self, val: *const (), basis: f32) -> f32 {
-    let Some(id) = decode_linear_id(val) else {
+    let Some(id) = decode_calc_id(val) else {
       return 0.0;
     };
 
-    let linear_values = self.linear_values.borrow();
-    linear_values
+    let values = self.values.borrow();
+    values
       .get(id - 1)
-      .map(|linear| linear.resolve(basis))
+      .map(|value| value.resolve(basis))
       .unwrap_or(0.0)
   }
 }
 
-fn encode_linear_id(id: usize) -> *const () {
+fn encode_calc_id(id: usize) -> *const () {
   // The low 3 bits are reserved because aligned pointers keep them as zero.
   ((id << 3) as *const ()).cast()
 }
 
-fn decode_linear_id(ptr: *const ()) -> Option<usize> {
+fn decode_calc_id(ptr: *const ()) -> Option<usize> {
   let raw = ptr as usize;
   // `raw != 0` filters out the null pointer case.
   (raw != 0).then_some(raw >> 3)
@@ -76,8 +111,145 @@ impl CalcLinear {
   }
 }
 
+/// A `calc(...)` expression resolved against [`Sizing`], leaving only the percentage basis.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ResolvedCalc {
+  /// A linear expression.
+  Linear(CalcLinear),
+  /// The smallest or largest of the arguments.
+  Comparison(CalcComparisonKind, Box<[ResolvedCalc]>),
+}
+
+impl ResolvedCalc {
+  fn resolve(&self, basis: f32) -> f32 {
+    match self {
+      Self::Linear(linear) => linear.resolve(basis),
+      Self::Comparison(kind, args) => args
+        .iter()
+        .map(|arg| arg.resolve(basis))
+        .reduce(|lhs, rhs| kind.pick(lhs, rhs))
+        .unwrap_or(0.0),
+    }
+  }
+
+  /// Multiplies the expression by a non-negative `factor`.
+  fn scaled(&self, factor: f32) -> Self {
+    match self {
+      Self::Linear(linear) => Self::Linear(CalcLinear {
+        px: linear.px * factor,
+        percent: linear.percent * factor,
+      }),
+      Self::Comparison(kind, args) => {
+        Self::Comparison(*kind, args.iter().map(|arg| arg.scaled(factor)).collect())
+      }
+    }
+  }
+
+  fn has_percentage(&self) -> bool {
+    match self {
+      Self::Linear(linear) => !is_near_zero(linear.percent),
+      Self::Comparison(_, args) => args.iter().any(Self::has_percentage),
+    }
+  }
+}
+
+/// Which argument a `min()` or `max()` comparison picks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum CalcComparisonKind {
+  Min,
+  Max,
+}
+
+impl CalcComparisonKind {
+  fn pick(self, lhs: f32, rhs: f32) -> f32 {
+    // NaN wins either way, as in CSS.
+    if lhs.is_nan() || rhs.is_nan() {
+      return f32::NAN;
+    }
+
+    match self {
+      Self::Min => lhs.min(rhs),
+      Self::Max => lhs.max(rhs),
+    }
+  }
+
+  fn flip(self) -> Self {
+    match self {
+      Self::Min => Self::Max,
+      Self::Max => Self::Min,
+    }
+  }
+}
+
+/// The arguments of every `min()` and `max()` comparison parsed so far.
+///
+/// Comparisons are interned so [`CalcFormula`] stays `Copy`, and identical ones share an id, which
+/// keeps the table bounded by the distinct expressions in use. Renders go through their
+/// [`CalcArena`], which reads each comparison from here once.
+static CALC_COMPARISONS: LazyLock<RwLock<CalcComparisons>> = LazyLock::new(Default::default);
+
+#[derive(Default)]
+struct CalcComparisons {
+  values: Vec<Arc<CalcComparison>>,
+  ids: HashMap<Vec<u32>, CalcComparisonId>,
+}
+
+/// The arguments of a `min()` or `max()` comparison.
+#[derive(Debug)]
+struct CalcComparison {
+  kind: CalcComparisonKind,
+  args: Box<[CalcFormula]>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct CalcComparisonId(u32);
+
+impl CalcComparisonId {
+  fn intern(kind: CalcComparisonKind, args: Box<[CalcFormula]>) -> Self {
+    let mut key = vec![kind as u32];
+    for arg in &args {
+      key.extend(arg.key());
+    }
+
+    if let Some(id) = CALC_COMPARISONS
+      .read()
+      .unwrap_or_else(PoisonError::into_inner)
+      .ids
+      .get(&key)
+    {
+      return *id;
+    }
+
+    let mut comparisons = CALC_COMPARISONS
+      .write()
+      .unwrap_or_else(PoisonError::into_inner);
+    let next_id = CalcComparisonId(comparisons.values.len() as u32);
+    let id = *comparisons.ids.entry(key).or_insert(next_id);
+
+    if id == next_id {
+      comparisons
+        .values
+        .push(Arc::new(CalcComparison { kind, args }));
+    }
+
+    id
+  }
+
+  fn get(self) -> Arc<CalcComparison> {
+    Arc::clone(
+      &CALC_COMPARISONS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .values[self.0 as usize],
+    )
+  }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 /// Internal symbolic form of a `calc(...)` expression before sizing is known.
+///
+/// `min()`, `max()` and `clamp()` are kept as an interned comparison of formulas, with the linear
+/// terms folded into its arguments.
 #[non_exhaustive]
 pub struct CalcFormula {
   px: f32,
@@ -98,6 +270,13 @@ pub struct CalcFormula {
   q: f32,
   pt: f32,
   pc: f32,
+  comparison: Option<CalcComparisonId>,
+}
+
+impl Hash for CalcFormula {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.key().hash(state);
+  }
 }
 
 impl CalcFormula {
@@ -232,6 +411,10 @@ impl CalcFormula {
   }
 
   fn neg(self) -> Self {
+    if self.comparison.is_some() {
+      return self.scale(-1.0);
+    }
+
     Self {
       px: -self.px,
       percent: -self.percent,
@@ -251,10 +434,25 @@ impl CalcFormula {
       q: -self.q,
       pt: -self.pt,
       pc: -self.pc,
+      comparison: None,
     }
   }
 
   fn add(self, rhs: Self) -> Self {
+    // A formula with a comparison has no linear terms of its own, so `rhs` is added to every
+    // argument instead.
+    if let Some(comparison) = self.comparison {
+      let comparison = comparison.get();
+      return Self::compare(
+        comparison.kind,
+        comparison.args.iter().map(|arg| arg.add(rhs)).collect(),
+      );
+    }
+
+    if rhs.comparison.is_some() {
+      return rhs.add(self);
+    }
+
     Self {
       px: self.px + rhs.px,
       percent: self.percent + rhs.percent,
@@ -274,33 +472,33 @@ impl CalcFormula {
       q: self.q + rhs.q,
       pt: self.pt + rhs.pt,
       pc: self.pc + rhs.pc,
+      comparison: None,
     }
   }
 
   fn sub(self, rhs: Self) -> Self {
-    Self {
-      px: self.px - rhs.px,
-      percent: self.percent - rhs.percent,
-      rem: self.rem - rhs.rem,
-      em: self.em - rhs.em,
-      vh: self.vh - rhs.vh,
-      vw: self.vw - rhs.vw,
-      cqh: self.cqh - rhs.cqh,
-      cqw: self.cqw - rhs.cqw,
-      cqmin: self.cqmin - rhs.cqmin,
-      cqmax: self.cqmax - rhs.cqmax,
-      vmin: self.vmin - rhs.vmin,
-      vmax: self.vmax - rhs.vmax,
-      cm: self.cm - rhs.cm,
-      mm: self.mm - rhs.mm,
-      inch: self.inch - rhs.inch,
-      q: self.q - rhs.q,
-      pt: self.pt - rhs.pt,
-      pc: self.pc - rhs.pc,
-    }
+    self.add(rhs.neg())
   }
 
   fn scale(self, factor: f32) -> Self {
+    if let Some(comparison) = self.comparison {
+      let comparison = comparison.get();
+      // Scaling by a negative factor turns the smallest argument into the largest.
+      let kind = if factor < 0.0 {
+        comparison.kind.flip()
+      } else {
+        comparison.kind
+      };
+      return Self::compare(
+        kind,
+        comparison
+          .args
+          .iter()
+          .map(|arg| arg.scale(factor))
+          .collect(),
+      );
+    }
+
     Self {
       px: Self::scale_component(self.px, factor),
       percent: Self::scale_component(self.percent, factor),
@@ -320,10 +518,94 @@ impl CalcFormula {
       q: Self::scale_component(self.q, factor),
       pt: Self::scale_component(self.pt, factor),
       pc: Self::scale_component(self.pc, factor),
+      comparison: None,
+    }
+  }
+
+  /// Builds the `min()` or `max()` of `args`, comparing the arguments that only differ in pixels
+  /// right away.
+  fn compare(kind: CalcComparisonKind, args: Vec<Self>) -> Self {
+    let mut compared: Vec<Self> = Vec::with_capacity(args.len());
+
+    for arg in args {
+      if let Some(comparison) = arg.comparison {
+        let nested = comparison.get();
+
+        if nested.kind == kind {
+          compared.extend(nested.args.iter().copied());
+        } else {
+          compared.push(arg);
+        }
+        continue;
+      }
+
+      if let Some(existing) = compared
+        .iter_mut()
+        .find(|existing| existing.comparison.is_none() && existing.has_same_units(arg))
+      {
+        existing.px = kind.pick(existing.px, arg.px);
+        continue;
+      }
+
+      compared.push(arg);
+    }
+
+    if let [only] = compared.as_slice() {
+      return *only;
+    }
+
+    Self {
+      comparison: Some(CalcComparisonId::intern(kind, compared.into())),
+      ..Default::default()
     }
   }
 
-  pub(crate) fn resolve(self, sizing: &Sizing) -> CalcLinear {
+  fn has_same_units(self, other: Self) -> bool {
+    Self { px: 0.0, ..self } == Self { px: 0.0, ..other }
+  }
+
+  /// The bits of every term, equal for formulas that are the same term by term.
+  fn key(self) -> [u32; 19] {
+    [
+      self.px.to_bits(),
+      self.percent.to_bits(),
+      self.rem.to_bits(),
+      self.em.to_bits(),
+      self.vh.to_bits(),
+      self.vw.to_bits(),
+      self.cqh.to_bits(),
+      self.cqw.to_bits(),
+      self.cqmin.to_bits(),
+      self.cqmax.to_bits(),
+      self.vmin.to_bits(),
+      self.vmax.to_bits(),
+      self.cm.to_bits(),
+      self.mm.to_bits(),
+      self.inch.to_bits(),
+      self.q.to_bits(),
+      self.pt.to_bits(),
+      self.pc.to_bits(),
+      self.comparison.map_or(0, |id| id.0 + 1),
+    ]
+  }
+
+  pub(crate) fn resolve(self, sizing: &Sizing) -> ResolvedCalc {
+    if let Some(id) = self.comparison {
+      let comparison = sizing.calc_arena.comparison(id);
+      return ResolvedCalc::Comparison(
+        comparison.kind,
+        comparison
+          .args
+          .iter()
+          .map(|arg| arg.resolve(sizing))
+          .collect(),
+      );
+    }
+
+    ResolvedCalc::Linear(self.resolve_linear(sizing))
+  }
+
+  fn resolve_linear(self, sizing: &Sizing) -> CalcLinear {
     let viewport_width = sizing.viewport.size.width.unwrap_or_default() as f32;
     let viewport_height = sizing.viewport.size.height.unwrap_or_default() as f32;
     let viewport_min = viewport_width.min(viewport_height);
@@ -332,10 +614,11 @@ impl CalcFormula {
     let container_height = sizing.query_container_height();
     let container_min = container_width.min(container_height);
     let container_max = container_width.max(container_height);
+    let pixel_ratio = sizing.pixel_ratio();
 
     CalcLinear {
-      px: self.px * sizing.viewport.device_pixel_ratio
-        + self.rem * sizing.viewport.font_size * sizing.viewport.device_pixel_ratio
+      px: self.px * pixel_ratio
+        + self.rem * sizing.viewport.font_size * pixel_ratio
         + self.em * sizing.font_size
         + self.vh * viewport_height / 100.0
         + self.vw * viewport_width / 100.0
@@ -345,12 +628,12 @@ impl CalcFormula {
         + self.cqmax * container_max / 100.0
         + self.vmin * viewport_min / 100.0
         + self.vmax * viewport_max / 100.0
-        + self.cm * ONE_CM_IN_PX * sizing.viewport.device_pixel_ratio
-        + self.mm * ONE_MM_IN_PX * sizing.viewport.device_pixel_ratio
-        + self.inch * ONE_IN_PX * sizing.viewport.device_pixel_ratio
-        + self.q * ONE_Q_IN_PX * sizing.viewport.device_pixel_ratio
-        + self.pt * ONE_PT_IN_PX * sizing.viewport.device_pixel_ratio
-        + self.pc * ONE_PC_IN_PX * sizing.viewport.device_pixel_ratio,
+        + self.cm * ONE_CM_IN_PX * pixel_ratio
+        + self.mm * ONE_MM_IN_PX * pixel_ratio
+        + self.inch * ONE_IN_PX * pixel_ratio
+        + self.q * ONE_Q_IN_PX * pixel_ratio
+        + self.pt * ONE_PT_IN_PX * pixel_ratio
+        + self.pc * ONE_PC_IN_PX * pixel_ratio,
       percent: self.percent,
     }
   }
@@ -407,8 +690,8 @@ pub(crate) fn parse_calc_number_expression<'i>(input: &mut Parser<'i, '_>) -> Pa
   let token = input.next()?.clone();
 
   match &token {
-    Token::Function(function) if function.eq_ignore_ascii_case("calc") => {
-      match input.parse_nested_block(parse_calc_sum)? {
+    Token::Function(function) if is_calc_function(function) => {
+      match parse_calc_function(function, input)? {
         CalcValue::Number(value) => Ok(value),
         _ => Err(location.new_unexpected_token_error(token.clone())),
       }
@@ -417,6 +700,69 @@ pub(crate) fn parse_calc_number_expression<'i>(input: &mut Parser<'i, '_>) -> Pa
   }
 }
 
+fn is_calc_function(name: &str) -> bool {
+  match_ignore_ascii_case! {name,
+    "calc" | "min" | "max" | "clamp" => true,
+    _ => false,
+  }
+}
+
+/// Parses the arguments of a `calc()`, `min()`, `max()` or `clamp()` function.
+fn parse_calc_function<'i>(
+  name: &CowRcStr<'i>,
+  input: &mut Parser<'i, '_>,
+) -> ParseResult<'i, CalcValue> {
+  let location = input.current_source_location();
+
+  if name.eq_ignore_ascii_case("calc") {
+    return input.parse_nested_block(parse_calc_sum);
+  }
+
+  let args = input.parse_nested_block(|input| input.parse_comma_separated(parse_calc_sum))?;
+
+  let value = match_ignore_ascii_case! {name.as_ref(),
+    "min" => compare_calc_values(CalcComparisonKind::Min, args),
+    "max" => compare_calc_values(CalcComparisonKind::Max, args),
+    // `clamp(MIN, VAL, MAX)` is `max(MIN, min(VAL, MAX))`.
+    "clamp" => match args[..] {
+      [min, value, max] => compare_calc_values(CalcComparisonKind::Min, vec![value, max])
+        .and_then(|upper| compare_calc_values(CalcComparisonKind::Max, vec![min, upper])),
+      _ => None,
+    },
+    _ => None,
+  };
+
+  value.ok_or_else(|| location.new_unexpected_token_error(Token::Function(name.clone())))
+}
+
+/// Picks the smallest or largest of `args`, which must all be numbers or all be lengths.
+fn compare_calc_values(kind: CalcComparisonKind, args: Vec<CalcValue>) -> Option<CalcValue> {
+  let numbers = args
+    .iter()
+    .map(|arg| match arg {
+      CalcValue::Number(value) => Some(*value),
+      CalcValue::Formula(_) => None,
+    })
+    .collect::<Option<Vec<_>>>();
+
+  if let Some(numbers) = numbers {
+    return numbers
+      .into_iter()
+      .reduce(|lhs, rhs| kind.pick(lhs, rhs))
+      .map(CalcValue::Number);
+  }
+
+  let formulas = args
+    .into_iter()
+    .map(|arg| match arg {
+      CalcValue::Formula(formula) => Some(formula),
+      CalcValue::Number(_) => None,
+    })
+    .collect::<Option<Vec<_>>>()?;
+
+  Some(CalcValue::Formula(CalcFormula::compare(kind, formulas)))
+}
+
 fn parse_calc_product<'i>(input: &mut Parser<'i, '_>) -> ParseResult<'i, CalcValue> {
   let mut value = parse_calc_factor(input)?;
 
@@ -519,8 +865,9 @@ fn parse_calc_factor<'i>(input: &mut Parser<'i, '_>) -> ParseResult<'i, CalcValu
         _ => Err(<Length as FromCss<'i>>::unexpected_token_error(location, token)),
       }
     }
-    Token::Function(name) if name.eq_ignore_ascii_case("calc") => {
-      input.parse_nested_block(parse_calc_sum)
+    Token::Function(name) if is_calc_function(name) => {
+      let name = name.clone();
+      parse_calc_function(&name, input)
     }
     Token::Ident(ident) => match_ignore_ascii_case! {ident.as_ref(),
       "e" => Ok(CalcValue::Number(std::f32::consts::E)),
@@ -540,6 +887,21 @@ fn is_near_zero(value: f32) -> bool {
   value.abs() <= CALC_ZERO_EPSILON
 }
 
+/// Snaps a border or outline width in device pixels so hairlines stay crisp at fractional
+/// device pixel ratios.
+///
+/// Widths between zero and one device pixel become one pixel, wider ones are rounded down to whole
+/// pixels, as described for border widths in CSS Values 4.
+pub(crate) fn snap_border_width(width: f32) -> f32 {
+  if width <= 0.0 {
+    0.0
+  } else if width < 1.0 {
+    1.0
+  } else {
+    width.floor()
+  }
+}
+
 fn clamp_px_for_integer_cast(value: f32) -> f32 {
   if value.is_nan() {
     return 0.0;
@@ -601,10 +963,38 @@ pub enum Length<const DEFAULT_AUTO: bool = true> {
   Pc(f32),
   /// Specific pixel value
   Px(f32),
-  /// calc(...) expression
+  /// calc(...), min(...), max(...) or clamp(...) expression
   Calc(CalcFormula),
 }
 
+impl<const DEFAULT_AUTO: bool> Hash for Length<DEFAULT_AUTO> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    std::mem::discriminant(self).hash(state);
+    match *self {
+      Self::Auto => {}
+      Self::Calc(formula) => formula.hash(state),
+      Self::Percentage(value)
+      | Self::Rem(value)
+      | Self::Em(value)
+      | Self::Vh(value)
+      | Self::Vw(value)
+      | Self::CqH(value)
+      | Self::CqW(value)
+      | Self::CqMin(value)
+      | Self::CqMax(value)
+      | Self::VMin(value)
+      | Self::VMax(value)
+      | Self::Cm(value)
+      | Self::Mm(value)
+      | Self::In(value)
+      | Self::Q(value)
+      | Self::Pt(value)
+      | Self::Pc(value)
+      | Self::Px(value) => value.to_bits().hash(state),
+    }
+  }
+}
+
 impl<const DEFAULT_AUTO: bool> Default for Length<DEFAULT_AUTO> {
   fn default() -> Self {
     if DEFAULT_AUTO {
@@ -722,8 +1112,9 @@ impl<'i, const DEFAULT_AUTO: bool> FromCss<'i> for Length<DEFAULT_AUTO> {
         "auto" => Ok(Self::Auto),
         _ => Err(Self::unexpected_token_error(location, token)),
       },
-      Token::Function(function) if function.eq_ignore_ascii_case("calc") => {
-        match input.parse_nested_block(parse_calc_sum)? {
+      Token::Function(function) if is_calc_function(function) => {
+        let function = function.clone();
+        match parse_calc_function(&function, input)? {
           CalcValue::Number(value) => Ok(Self::Px(value)),
           CalcValue::Formula(formula) => Ok(Self::Calc(formula)),
         }
@@ -820,9 +1211,9 @@ impl<const DEFAULT_AUTO: bool> Length<DEFAULT_AUTO> {
     match self {
       Length::Auto => CompactLength::auto(),
       Length::Percentage(value) => CompactLength::percent(value / 100.0),
-      Length::Rem(value) => CompactLength::length(
-        value * sizing.viewport.font_size * sizing.viewport.device_pixel_ratio,
-      ),
+      Length::Rem(value) => {
+        CompactLength::length(value * sizing.viewport.font_size * sizing.pixel_ratio())
+      }
       Length::Em(value) => CompactLength::length(value * sizing.font_size),
       Length::Vh(value) => CompactLength::length(
         sizing.viewport.size.height.unwrap_or_default() as f32 * value / 100.0,
@@ -857,17 +1248,21 @@ impl<const DEFAULT_AUTO: bool> Length<DEFAULT_AUTO> {
         CompactLength::length(viewport_width.max(viewport_height) * value / 100.0)
       }
       Length::Calc(formula) => {
-        let linear = formula.resolve(sizing);
-
-        if is_near_zero(linear.percent) {
-          return CompactLength::length(linear.px);
-        }
-
-        if is_near_zero(linear.px) {
-          return CompactLength::percent(linear.percent);
+        let resolved = formula.resolve(sizing);
+
+        if let ResolvedCalc::Linear(linear) = resolved {
+          if is_near_zero(linear.percent) {
+            return CompactLength::length(linear.px);
+          }
+
+          if is_near_zero(linear.px) {
+            return CompactLength::percent(linear.percent);
+          }
+        } else if !resolved.has_percentage() {
+          return CompactLength::length(resolved.resolve(0.0));
         }
 
-        CompactLength::calc(sizing.calc_arena.register_linear(linear))
+        CompactLength::calc(sizing.calc_arena.register(resolved))
       }
       _ => CompactLength::length(self.to_px(
         sizing,
@@ -886,6 +1281,38 @@ impl<const DEFAULT_AUTO: bool> Length<DEFAULT_AUTO> {
     unsafe { LengthPercentage::from_raw(compact_length) }
   }
 
+  /// Resolves a border width, snapping absolute widths to whole device pixels.
+  pub(crate) fn resolve_to_border_width(self, sizing: &Sizing) -> LengthPercentage {
+    let length_percentage = self.resolve_to_length_percentage(sizing);
+    let compact_length = length_percentage.into_raw();
+
+    if compact_length.tag() == CompactLength::LENGTH_TAG {
+      return LengthPercentage::length(snap_border_width(compact_length.value()));
+    }
+
+    length_percentage
+  }
+
+  /// Resolves the part of a border width that lies inside the box, `fraction` of the snapped
+  /// width. Percentages keep resolving against the containing block width.
+  pub(crate) fn resolve_to_inside_border_width(
+    self,
+    sizing: &Sizing,
+    fraction: f32,
+  ) -> LengthPercentage {
+    let compact_length = self.resolve_to_border_width(sizing).into_raw();
+
+    match compact_length.tag() {
+      CompactLength::LENGTH_TAG => LengthPercentage::length(compact_length.value() * fraction),
+      CompactLength::PERCENT_TAG => LengthPercentage::percent(compact_length.value() * fraction),
+      _ => LengthPercentage::calc(
+        sizing
+          .calc_arena
+          .register_scaled(compact_length.calc_value(), fraction),
+      ),
+    }
+  }
+
   pub(crate) fn to_px(self, sizing: &Sizing, percentage_full_px: f32) -> f32 {
     let value = self.to_px_pre_dpr(sizing, percentage_full_px);
 
@@ -906,7 +1333,7 @@ impl<const DEFAULT_AUTO: bool> Length<DEFAULT_AUTO> {
     ) {
       value
     } else {
-      value * sizing.viewport.device_pixel_ratio
+      value * sizing.pixel_ratio()
     };
 
     clamp_px_for_integer_cast(value)
@@ -924,7 +1351,7 @@ impl<const DEFAULT_AUTO: bool> Length<DEFAULT_AUTO> {
 impl<const DEFAULT_AUTO: bool> MakeComputed for Length<DEFAULT_AUTO> {
   fn make_computed(&mut self, sizing: &Sizing) {
     if let Self::Em(em) = *self {
-      let dpr = sizing.viewport.device_pixel_ratio;
+      let dpr = sizing.pixel_ratio();
       let font_size = if dpr > 0.0 {
         sizing.font_size / dpr
       } else {
@@ -936,10 +1363,17 @@ impl<const DEFAULT_AUTO: bool> MakeComputed for Length<DEFAULT_AUTO> {
     }
 
     if let Self::Calc(formula) = *self {
-      let linear = formula.resolve(sizing);
+      let resolved = formula.resolve(sizing);
+
+      let ResolvedCalc::Linear(linear) = resolved else {
+        if !resolved.has_percentage() {
+          *self = Self::Px(resolved.resolve(0.0) / sizing.pixel_ratio());
+        }
+        return;
+      };
 
       if is_near_zero(linear.percent) {
-        *self = Self::Px(linear.px / sizing.viewport.device_pixel_ratio);
+        *self = Self::Px(linear.px / sizing.pixel_ratio());
         return;
       }
 
@@ -965,9 +1399,11 @@ mod tests {
         size: (200, 100).into(),
         font_size: 16.0,
         device_pixel_ratio: 2.0,
+        color_scheme: Default::default(),
       },
       container_size: Size::NONE,
       font_size: 10.0,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     }
   }
@@ -1201,4 +1637,147 @@ mod tests {
     assert_eq!(resolved, SAFE_INT_MAX_PX);
     assert!(resolved.is_finite());
   }
+
+  #[test]
+  fn parse_clamp_resolves_against_basis() {
+    let parsed = Length::<true>::from_str("clamp(200px, 50%, 480px)");
+    assert!(parsed.is_ok(), "expected successful parse, got {parsed:?}");
+    let Ok(length) = parsed else {
+      return;
+    };
+    let sizing = sizing();
+
+    assert_near(length.to_px(&sizing, 200.0), 400.0);
+    assert_near(length.to_px(&sizing, 1000.0), 500.0);
+    assert_near(length.to_px(&sizing, 4000.0), 960.0);
+  }
+
+  #[test]
+  fn parse_min_and_max_nest_inside_calc() {
+    let parsed = Length::<true>::from_str("calc(min(100%, 300px) - max(5px, 1rem) * 2)");
+    assert!(parsed.is_ok(), "expected successful parse, got {parsed:?}");
+    let Ok(length) = parsed else {
+      return;
+    };
+    let sizing = sizing();
+
+    assert_near(length.to_px(&sizing, 200.0), 136.0);
+    assert_near(length.to_px(&sizing, 1000.0), 536.0);
+  }
+
+  #[test]
+  fn negating_min_picks_the_largest_negated_argument() {
+    let parsed = Length::<true>::from_str("calc(-1 * min(50%, 100px))");
+    assert!(parsed.is_ok(), "expected successful parse, got {parsed:?}");
+    let Ok(length) = parsed else {
+      return;
+    };
+    let sizing = sizing();
+
+    assert_near(length.to_px(&sizing, 100.0), -50.0);
+    assert_near(length.to_px(&sizing, 1000.0), -200.0);
+  }
+
+  #[test]
+  fn parse_min_of_same_units_folds_to_linear_formula() {
+    assert_eq!(
+      Length::<true>::from_str("min(10px, 20px, max(1px, 4px))"),
+      Ok(Length::Calc(CalcFormula {
+        px: 4.0,
+        ..Default::default()
+      }))
+    );
+    assert_eq!(
+      Length::<true>::from_str("calc(max(1, 2) * 3px)"),
+      Ok(Length::Calc(CalcFormula {
+        px: 6.0,
+        ..Default::default()
+      }))
+    );
+  }
+
+  #[test]
+  fn parse_math_functions_reject_invalid_arguments() {
+    assert!(Length::<true>::from_str("clamp(1px, 2px)").is_err());
+    assert!(Length::<true>::from_str("min(1, 2px)").is_err());
+    assert!(Length::<true>::from_str("max()").is_err());
+  }
+
+  #[test]
+  fn identical_comparisons_are_interned_once() {
+    assert_eq!(
+      Length::<true>::from_str("min(50%, 10rem)"),
+      Length::<true>::from_str("min(50%, 10rem)")
+    );
+    assert_ne!(
+      Length::<true>::from_str("min(50%, 10rem)"),
+      Length::<true>::from_str("max(50%, 10rem)")
+    );
+  }
+
+  #[test]
+  fn renders_look_up_each_comparison_once() {
+    let Ok(value) = Length::<true>::from_str("min(50%, max(10rem, 20px))") else {
+      unreachable!()
+    };
+    let sizing = sizing();
+
+    for _ in 0..3 {
+      assert_near(value.to_px(&sizing, 200.0), 100.0);
+    }
+
+    // The outer `min()` and the nested `max()`.
+    assert_eq!(sizing.calc_arena.comparisons.borrow().len(), 2);
+  }
+
+  #[test]
+  fn make_computed_collapses_comparison_without_percent_to_px() {
+    let parsed = Length::<true>::from_str("min(1rem, 20px)");
+    assert!(parsed.is_ok(), "expected successful parse, got {parsed:?}");
+    let Ok(mut value) = parsed else {
+      return;
+    };
+    value.make_computed(&sizing());
+    assert_eq!(value, Length::Px(16.0));
+  }
+
+  #[test]
+  fn compact_length_comparison_pointer_resolves_through_callback() {
+    let parsed = Length::<true>::from_str("max(25%, 30px)");
+    assert!(parsed.is_ok(), "expected successful parse, got {parsed:?}");
+    let Ok(value) = parsed else {
+      return;
+    };
+    let sizing = sizing();
+    let compact = value.to_compact_length(&sizing);
+    assert!(compact.is_calc());
+
+    let resolve = |basis| {
+      sizing
+        .calc_arena
+        .resolve_calc_value(compact.calc_value(), basis)
+    };
+    assert_near(resolve(100.0), 60.0);
+    assert_near(resolve(400.0), 100.0);
+  }
+
+  #[test]
+  fn zoom_scales_absolute_lengths_with_device_pixel_ratio() {
+    let sizing = Sizing {
+      zoom: 1.5,
+      ..sizing()
+    };
+
+    assert_near(Length::<true>::Px(10.0).to_px(&sizing, 0.0), 30.0);
+    assert_near(Length::<true>::Pt(3.0).to_px(&sizing, 0.0), 12.0);
+    assert_near(Length::<true>::Percentage(50.0).to_px(&sizing, 80.0), 40.0);
+  }
+
+  #[test]
+  fn border_widths_snap_to_device_pixels() {
+    assert_eq!(snap_border_width(0.0), 0.0);
+    assert_eq!(snap_border_width(0.25), 1.0);
+    assert_eq!(snap_border_width(1.5), 1.0);
+    assert_eq!(snap_border_width(6.75), 6.0);
+  }
 }
@@ -0,0 +1,27 @@
+use crate::layout::style::declare_enum_from_css_impl;
+
+/// Controls how strictly line breaking rules are applied to CJK text.
+///
+/// Corresponds to CSS line-break property.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum LineBreak {
+  /// Uses the default line breaking rules, which forbid breaks before small kana.
+  #[default]
+  Auto,
+  /// Like `normal`, and also allows breaks before iteration marks, centered punctuation,
+  /// fullwidth postfix characters and between inseparable characters.
+  Loose,
+  /// Allows breaks before small kana, the prolonged sound mark and CJK hyphens.
+  Normal,
+  /// Forbids breaks before small kana, the prolonged sound mark and CJK hyphens.
+  Strict,
+}
+
+declare_enum_from_css_impl!(
+  LineBreak,
+  "auto" => LineBreak::Auto,
+  "loose" => LineBreak::Loose,
+  "normal" => LineBreak::Normal,
+  "strict" => LineBreak::Strict,
+);
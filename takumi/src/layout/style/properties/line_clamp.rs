@@ -4,7 +4,7 @@ use crate::layout::style::{
   CssSyntaxKind, CssToken, FromCss, MakeComputed, ParseResult, tw::TailwindPropertyParser,
 };
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 /// Represents a line clamp value.
 #[non_exhaustive]
 pub struct LineClamp {
@@ -1,12 +1,14 @@
+use std::hash::{Hash, Hasher};
+
 use cssparser::{Parser, match_ignore_ascii_case};
 
 use crate::{
   layout::style::{
-    CssSyntaxKind, CssToken, FromCss, Length, MakeComputed, ParseResult,
+    CssSyntaxKind, CssToken, FromCss, Length, MakeComputed, ParseResult, SizedFontStyle,
     parse_calc_number_expression,
     tw::{TW_VAR_SPACING, TailwindPropertyParser},
   },
-  rendering::Sizing,
+  rendering::{RenderContext, Sizing},
 };
 
 /// Represents a line height value, number value is parsed as em.
@@ -22,6 +24,17 @@ pub enum LineHeight {
   Length(Length),
 }
 
+impl Hash for LineHeight {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    std::mem::discriminant(self).hash(state);
+    match self {
+      Self::Normal => {}
+      Self::Unitless(value) => value.to_bits().hash(state),
+      Self::Length(length) => length.hash(state),
+    }
+  }
+}
+
 impl From<Length> for LineHeight {
   fn from(value: Length) -> Self {
     Self::Length(value)
@@ -84,6 +97,40 @@ impl LineHeight {
   }
 }
 
+/// Rounds the line height of `style` up to a multiple of `step`, for `line-height-step`.
+///
+/// `normal` is resolved against the metrics of the primary font first.
+pub(crate) fn snap_line_height(
+  style: &SizedFontStyle,
+  step: f32,
+  context: &RenderContext,
+) -> parley::LineHeight {
+  let height = match style.line_height {
+    parley::LineHeight::Absolute(height) => height,
+    parley::LineHeight::FontSizeRelative(value) => value * style.sizing.font_size,
+    parley::LineHeight::MetricsRelative(_) => {
+      let (mut layout, _) = context
+        .global
+        .font_context()
+        .tree_builder(style.into(), |builder| {
+          builder.push_text("x");
+        });
+      layout.break_all_lines(None);
+
+      let Some(line) = layout.lines().next() else {
+        return style.line_height;
+      };
+
+      line.metrics().line_height
+    }
+  };
+
+  // Tolerate float error so a height already on the grid isn't pushed up a step.
+  let steps = (height / step - 1e-3).ceil().max(1.0);
+
+  parley::LineHeight::Absolute(steps * step)
+}
+
 impl MakeComputed for LineHeight {
   fn make_computed(&mut self, sizing: &Sizing) {
     if let Self::Length(length) = self {
@@ -94,8 +141,15 @@ impl MakeComputed for LineHeight {
 
 #[cfg(test)]
 mod tests {
-  use super::LineHeight;
-  use crate::layout::style::FromCss;
+  use super::{LineHeight, snap_line_height};
+  use crate::{
+    GlobalContext,
+    layout::{
+      Viewport,
+      style::{ComputedStyle, FromCss, Length},
+    },
+    rendering::RenderContext,
+  };
 
   #[test]
   fn parses_unitless_calc_expression() {
@@ -104,4 +158,31 @@ mod tests {
       Ok(LineHeight::Unitless(1.75 / 1.125))
     );
   }
+
+  #[test]
+  fn snaps_line_height_up_to_step() {
+    let global = GlobalContext::default();
+    let context = RenderContext::new_test(&global, Viewport::new((100, 100)));
+    let snap = |line_height: LineHeight| {
+      let style = ComputedStyle {
+        line_height,
+        ..Default::default()
+      };
+
+      snap_line_height(&style.to_sized_font_style(&context), 4.0, &context)
+    };
+
+    assert_eq!(
+      snap(LineHeight::Length(Length::Px(18.0))),
+      parley::LineHeight::Absolute(20.0)
+    );
+    assert_eq!(
+      snap(LineHeight::Length(Length::Px(20.0))),
+      parley::LineHeight::Absolute(20.0)
+    );
+    assert_eq!(
+      snap(LineHeight::Unitless(1.2)),
+      parley::LineHeight::Absolute(20.0)
+    );
+  }
 }
@@ -1,6 +1,9 @@
 use cssparser::{Parser, Token, match_ignore_ascii_case};
 use image::{GenericImageView, Rgba};
-use std::ops::{Deref, Neg};
+use std::{
+  hash::{Hash, Hasher},
+  ops::{Deref, Neg},
+};
 
 use typed_builder::TypedBuilder;
 
@@ -16,7 +19,7 @@ use crate::layout::style::{
 use crate::rendering::{RenderContext, Sizing};
 
 /// Represents a linear gradient.
-#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+#[derive(Debug, Clone, PartialEq, TypedBuilder, Hash)]
 #[non_exhaustive]
 pub struct LinearGradient {
   /// Whether the gradient repeats beyond the last stop.
@@ -235,7 +238,7 @@ impl GradientOverlayTile for LinearGradientTile {
 
 /// Represents a gradient stop position.
 /// If a percentage or number (0.0-1.0) is provided, it is treated as a percentage.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct StopPosition(pub Length);
 
@@ -246,7 +249,7 @@ impl MakeComputed for StopPosition {
 }
 
 /// Represents a gradient stop.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum GradientStop {
   /// A color gradient stop.
@@ -377,6 +380,12 @@ impl<'i> FromCss<'i> for GradientStop {
 #[non_exhaustive]
 pub struct Angle(f32);
 
+impl Hash for Angle {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.0.to_bits().hash(state);
+  }
+}
+
 impl MakeComputed for Angle {}
 
 impl Animatable for Angle {
@@ -647,6 +656,7 @@ mod tests {
       viewport: Viewport::new((200, 100)),
       container_size: Size::NONE,
       font_size: 16.0,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     }
   }
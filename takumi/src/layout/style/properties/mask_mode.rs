@@ -0,0 +1,49 @@
+use cssparser::Parser;
+
+use crate::layout::style::{
+  Animatable, CssToken, FromCss, ListInterpolationStrategy, ParseResult, declare_enum_from_css_impl,
+};
+
+/// Defines which values of a mask layer are used as the mask.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
+#[non_exhaustive]
+pub enum MaskMode {
+  /// The alpha channel of the mask layer is used
+  Alpha,
+  /// The luminance of the mask layer, multiplied by its alpha, is used
+  Luminance,
+  /// Same as `alpha`, since mask layers are always images or gradients
+  #[default]
+  MatchSource,
+}
+
+declare_enum_from_css_impl!(
+  MaskMode,
+  "alpha" => MaskMode::Alpha,
+  "luminance" => MaskMode::Luminance,
+  "match-source" => MaskMode::MatchSource
+);
+
+impl Animatable for MaskMode {
+  fn list_interpolation_strategy() -> ListInterpolationStrategy {
+    ListInterpolationStrategy::RepeatToLcm
+  }
+}
+
+/// A list of mask-mode values (one per layer).
+pub type MaskModes = Box<[MaskMode]>;
+
+impl<'i> FromCss<'i> for MaskModes {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let mut values = Vec::new();
+    values.push(MaskMode::from_css(input)?);
+
+    while input.expect_comma().is_ok() {
+      values.push(MaskMode::from_css(input)?);
+    }
+
+    Ok(values.into_boxed_slice())
+  }
+
+  const VALID_TOKENS: &'static [CssToken] = MaskMode::VALID_TOKENS;
+}
@@ -6,7 +6,9 @@
 mod animation;
 mod aspect_ratio;
 mod background;
+mod background_attachment;
 mod background_image;
+mod background_origin;
 mod background_position;
 mod background_repeat;
 mod background_size;
@@ -16,30 +18,41 @@ mod box_shadow;
 mod clip_path;
 mod color;
 mod conic_gradient;
+mod fade_edges;
 mod filter;
 mod flex;
 mod flex_grow;
 mod font_family;
 mod font_feature_settings;
+mod font_kerning;
 mod font_size;
 mod font_stretch;
 mod font_style;
 mod font_synthesis;
+mod font_variant_caps;
+mod font_variant_ligatures;
 mod font_variation_settings;
 mod font_weight;
 mod gradient_utils;
 mod grid;
 mod length;
+mod line_break;
 mod line_clamp;
 mod line_height;
 mod linear_gradient;
+mod mask_mode;
 mod overflow;
 mod overflow_wrap;
 mod percentage_number;
+mod position_anchor;
 mod radial_gradient;
 mod sides;
 mod space_pair;
+mod spacing;
+mod tab_size;
+mod text_autospace;
 mod text_decoration;
+mod text_emboss;
 mod text_overflow;
 mod text_shadow;
 mod text_stroke;
@@ -52,7 +65,9 @@ mod word_break;
 pub use animation::*;
 pub use aspect_ratio::*;
 pub use background::*;
+pub use background_attachment::*;
 pub use background_image::*;
+pub use background_origin::*;
 pub use background_position::*;
 pub use background_repeat::*;
 pub use background_size::*;
@@ -62,15 +77,19 @@ pub use box_shadow::*;
 pub use clip_path::*;
 pub use color::*;
 pub use conic_gradient::*;
+pub use fade_edges::*;
 pub use filter::*;
 pub use flex::*;
 pub use flex_grow::*;
 pub use font_family::*;
 pub use font_feature_settings::*;
+pub use font_kerning::*;
 pub use font_size::*;
 pub use font_stretch::*;
 pub use font_style::*;
 pub use font_synthesis::*;
+pub use font_variant_caps::*;
+pub use font_variant_ligatures::*;
 pub use font_variation_settings::*;
 pub use font_weight::*;
 pub(crate) use gradient_utils::{
@@ -78,16 +97,23 @@ pub(crate) use gradient_utils::{
 };
 pub use grid::*;
 pub use length::*;
+pub use line_break::*;
 pub use line_clamp::*;
 pub use line_height::*;
 pub use linear_gradient::*;
+pub use mask_mode::*;
 pub use overflow::*;
 pub use overflow_wrap::*;
 pub use percentage_number::*;
+pub use position_anchor::*;
 pub use radial_gradient::*;
 pub use sides::*;
 pub use space_pair::*;
+pub use spacing::*;
+pub use tab_size::*;
+pub use text_autospace::*;
 pub use text_decoration::*;
+pub use text_emboss::*;
 pub use text_overflow::*;
 pub use text_shadow::*;
 pub use text_stroke::*;
@@ -215,6 +241,8 @@ pub enum CssDescriptorKind {
   CubicBezierFn,
   /// `<drop-shadow()>`
   DropShadowFn,
+  /// `<duotone()>`
+  DuotoneFn,
   /// `<ellipse()>`
   EllipseFn,
   /// `<grayscale()>`
@@ -257,6 +285,8 @@ pub enum CssDescriptorKind {
   TextWrapMode,
   /// `<text-wrap-style>`
   TextWrapStyle,
+  /// `<tint()>`
+  TintFn,
   /// `<url()>`
   UrlFn,
   /// `<white-space-collapse>`
@@ -277,6 +307,7 @@ impl CssDescriptorKind {
       Self::ContrastFn => "contrast()",
       Self::CubicBezierFn => "cubic-bezier()",
       Self::DropShadowFn => "drop-shadow()",
+      Self::DuotoneFn => "duotone()",
       Self::EllipseFn => "ellipse()",
       Self::GrayscaleFn => "grayscale()",
       Self::HueRotateFn => "hue-rotate()",
@@ -298,6 +329,7 @@ impl CssDescriptorKind {
       Self::StepsFn => "steps()",
       Self::TextWrapMode => "text-wrap-mode",
       Self::TextWrapStyle => "text-wrap-style",
+      Self::TintFn => "tint()",
       Self::UrlFn => "url()",
       Self::WhiteSpaceCollapse => "white-space-collapse",
     }
@@ -812,7 +844,7 @@ pub(crate) use declare_enum_from_css_impl;
 /// Defines how an image should be resized to fit its container.
 ///
 /// Similar to CSS object-fit property.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum ObjectFit {
   /// The replaced content is sized to fill the element's content box exactly, without maintaining aspect ratio
@@ -844,7 +876,7 @@ impl TailwindPropertyParser for ObjectFit {
 }
 
 /// Defines how the background is clipped.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum BackgroundClip {
   /// The background extends to the outside edge of the border
@@ -881,10 +913,34 @@ impl TailwindPropertyParser for BackgroundClip {
   }
 }
 
+impl Animatable for BackgroundClip {
+  fn list_interpolation_strategy() -> ListInterpolationStrategy {
+    ListInterpolationStrategy::RepeatToLcm
+  }
+}
+
+/// A list of background-clip values (one per layer).
+pub type BackgroundClips = Box<[BackgroundClip]>;
+
+impl<'i> FromCss<'i> for BackgroundClips {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let mut values = Vec::new();
+    values.push(BackgroundClip::from_css(input)?);
+
+    while input.expect_comma().is_ok() {
+      values.push(BackgroundClip::from_css(input)?);
+    }
+
+    Ok(values.into_boxed_slice())
+  }
+
+  const VALID_TOKENS: &'static [CssToken] = BackgroundClip::VALID_TOKENS;
+}
+
 /// Represents the CSS `border-radius` property, supporting elliptical corners.
 ///
 /// Each corner has independent horizontal and vertical radii, allowing for both circular and elliptical shapes.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 pub struct BorderRadius(pub Sides<SpacePair<LengthDefaultsToZero>>);
 
 impl From<f32> for BorderRadius {
@@ -973,7 +1029,7 @@ impl<'i> FromCss<'i> for BorderRadius {
 /// Defines how the width and height of an element are calculated.
 ///
 /// This enum determines whether the width and height properties include padding and border, or just the content area.
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum BoxSizing {
   /// The width and height properties include padding and border, but not the content area
@@ -994,7 +1050,7 @@ impl_from_taffy_enum!(BoxSizing, taffy::BoxSizing, ContentBox, BorderBox);
 /// Text alignment options for text rendering.
 ///
 /// Corresponds to CSS text-align property values.
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum TextAlign {
   /// Aligns inline content to the left edge of the line box
@@ -1033,7 +1089,7 @@ impl_from_taffy_enum!(
 );
 
 /// Defines whether an element creates a new stacking context.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum Isolation {
   /// The element creates a new stacking context.
@@ -1053,7 +1109,7 @@ declare_enum_from_css_impl!(
 ///
 /// This controls whether an element is rendered, but unlike `display: none`,
 /// it still takes up space in the layout.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum Visibility {
   /// The element is visible.
@@ -1070,7 +1126,7 @@ declare_enum_from_css_impl!(
 );
 
 /// Defines how the corners of text strokes are rendered.
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum LineJoin {
   /// The corners are sharp and pointed.
@@ -1108,7 +1164,7 @@ impl TailwindPropertyParser for LineJoin {
 /// Defines the positioning method for an element.
 ///
 /// This enum determines how an element is positioned within its containing element.
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum Position {
   /// The element is positioned according to the normal flow of the document.
@@ -1131,7 +1187,7 @@ impl_from_taffy_enum!(Position, taffy::Position, Relative, Absolute);
 /// Defines the direction of flex items within a flex container.
 ///
 /// This enum determines how flex items are laid out along the main axis.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum FlexDirection {
   /// Items are laid out in the same direction as the text direction (left-to-right for English)
@@ -1166,7 +1222,7 @@ impl_from_taffy_enum!(
 ///
 /// This enum determines how space is distributed between and around flex items
 /// along the main axis of the flex container.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum JustifyContent {
   /// The items are distributed using the normal flow of the flex container.
@@ -1242,7 +1298,7 @@ impl From<JustifyContent> for Option<taffy::JustifyContent> {
 }
 
 /// This enum determines the layout algorithm used for the children of a node.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum Display {
   /// The element is not displayed
@@ -1330,7 +1386,7 @@ impl From<Display> for taffy::Display {
 ///
 /// This enum determines how items are aligned within the flex container
 /// along the cross axis (perpendicular to the main axis).
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum AlignItems {
   /// The items are distributed using the normal flow of the flex container.
@@ -1388,7 +1444,7 @@ impl From<AlignItems> for Option<taffy::AlignItems> {
 /// Defines how flex items should wrap.
 ///
 /// This enum determines how flex items should wrap within the flex container.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum FlexWrap {
   /// Flex items will all be displayed in a single line, shrinking as needed
@@ -1410,7 +1466,7 @@ declare_enum_from_css_impl!(
 impl_from_taffy_enum!(FlexWrap, taffy::FlexWrap, NoWrap, Wrap, WrapReverse);
 
 /// Controls text case transformation when rendering.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum TextTransform {
   /// Do not transform text
@@ -1433,7 +1489,7 @@ declare_enum_from_css_impl!(
 );
 
 /// Controls whether text decoration should skip descenders.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum TextDecorationSkipInk {
   /// Skip descenders and glyph interiors when painting decorations.
@@ -1450,7 +1506,7 @@ declare_enum_from_css_impl!(
 );
 
 /// Controls how whitespace should be collapsed.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum WhiteSpaceCollapse {
   /// Preserve whitespace as is—spaces and tabs are not collapsed.
@@ -1473,7 +1529,7 @@ declare_enum_from_css_impl!(
 );
 
 /// Defines how images should be scaled when rendered.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum ImageScalingAlgorithm {
   /// The image is scaled using Catmull-Rom interpolation.
@@ -1507,7 +1563,7 @@ impl From<ImageScalingAlgorithm> for resvg::usvg::ImageRendering {
 }
 
 /// Represents border style options.
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum BorderStyle {
   /// No border will be rendered.
@@ -1515,14 +1571,57 @@ pub enum BorderStyle {
   None,
   /// Solid border style.
   Solid,
+  /// A series of square-ended dashes.
+  Dashed,
+  /// A series of round dots.
+  Dotted,
+  /// Two parallel solid lines with a gap between them.
+  Double,
 }
 
 declare_enum_from_css_impl!(
   BorderStyle,
   "none" => BorderStyle::None,
   "solid" => BorderStyle::Solid,
+  "dashed" => BorderStyle::Dashed,
+  "dotted" => BorderStyle::Dotted,
+  "double" => BorderStyle::Double,
 );
 
+/// Where a border is painted relative to the edge of the border box.
+///
+/// This is a non-standard extension matching the stroke alignment of design tools. Only the part
+/// of the border inside the box takes up layout space, the rest is painted outward like an outline.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum BorderAlignment {
+  /// The border is painted inside the border box, as in CSS.
+  #[default]
+  Inside,
+  /// The border straddles the edge of the box, half inside and half outside.
+  Center,
+  /// The border is painted entirely outside the box and takes up no layout space.
+  Outside,
+}
+
+declare_enum_from_css_impl!(
+  BorderAlignment,
+  "inside" => BorderAlignment::Inside,
+  "center" => BorderAlignment::Center,
+  "outside" => BorderAlignment::Outside,
+);
+
+impl BorderAlignment {
+  /// Returns the fraction of the border width that lies inside the box.
+  pub(crate) fn inside_fraction(self) -> f32 {
+    match self {
+      BorderAlignment::Inside => 1.0,
+      BorderAlignment::Center => 0.5,
+      BorderAlignment::Outside => 0.0,
+    }
+  }
+}
+
 impl TailwindPropertyParser for BorderStyle {
   fn parse_tw(token: &str) -> Option<Self> {
     Self::from_str(token).ok()
@@ -4,7 +4,7 @@ use taffy::Overflow as TaffyOverflow;
 use crate::layout::style::{declare_enum_from_css_impl, tw::TailwindPropertyParser};
 
 /// How children overflowing their container should affect layout
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum Overflow {
   /// The automatic minimum size of this node as a flexbox/grid item should be based on the size of its content.
@@ -1,3 +1,5 @@
+use std::hash::{Hash, Hasher};
+
 use crate::layout::style::{FromCss, declare_enum_from_css_impl, tw::TailwindPropertyParser};
 
 /// Controls how text should be overflowed.
@@ -5,6 +7,12 @@ use crate::layout::style::{FromCss, declare_enum_from_css_impl, tw::TailwindProp
 #[non_exhaustive]
 pub struct OverflowWrap(parley::OverflowWrap);
 
+impl Hash for OverflowWrap {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    std::mem::discriminant(&self.0).hash(state);
+  }
+}
+
 impl TailwindPropertyParser for OverflowWrap {
   fn parse_tw(token: &str) -> Option<Self> {
     Self::from_str(token).ok()
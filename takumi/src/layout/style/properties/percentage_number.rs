@@ -1,4 +1,7 @@
-use std::ops::{Deref, Neg};
+use std::{
+  hash::{Hash, Hasher},
+  ops::{Deref, Neg},
+};
 
 use cssparser::{Parser, Token};
 
@@ -15,6 +18,12 @@ use super::{CssSyntaxKind, CssToken};
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct PercentageNumber(pub f32);
 
+impl Hash for PercentageNumber {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.0.to_bits().hash(state);
+  }
+}
+
 impl MakeComputed for PercentageNumber {}
 
 impl Animatable for PercentageNumber {
@@ -0,0 +1,45 @@
+use cssparser::{Parser, Token};
+
+use crate::layout::style::{CssSyntaxKind, CssToken, FromCss, MakeComputed, ParseResult};
+
+/// The `id` of the node an absolutely positioned node is anchored to.
+///
+/// Accepts either a bare identifier (`position-anchor: avatar`) or an id selector
+/// (`position-anchor: #avatar`).
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct PositionAnchor(pub Box<str>);
+
+impl MakeComputed for PositionAnchor {}
+
+impl<'i> FromCss<'i> for PositionAnchor {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+
+    match token {
+      Token::Ident(id) | Token::IDHash(id) => Ok(Self(id.as_ref().into())),
+      _ => Err(Self::unexpected_token_error(location, token)),
+    }
+  }
+
+  const VALID_TOKENS: &'static [CssToken] = &[CssToken::Syntax(CssSyntaxKind::Ident)];
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_identifier_and_id_selector() {
+    assert_eq!(
+      PositionAnchor::from_str("avatar"),
+      Ok(PositionAnchor("avatar".into()))
+    );
+    assert_eq!(
+      PositionAnchor::from_str("#avatar"),
+      Ok(PositionAnchor("avatar".into()))
+    );
+    assert_eq!(Option::<PositionAnchor>::from_str("none"), Ok(None));
+    assert!(PositionAnchor::from_str("12px").is_err());
+  }
+}
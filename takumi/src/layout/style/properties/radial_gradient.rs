@@ -16,7 +16,7 @@ use crate::{
 };
 
 /// Represents a radial gradient.
-#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+#[derive(Debug, Clone, PartialEq, TypedBuilder, Hash)]
 #[non_exhaustive]
 pub struct RadialGradient {
   /// Whether the gradient repeats beyond the last stop.
@@ -47,7 +47,7 @@ impl MakeComputed for RadialGradient {
 }
 
 /// Supported shapes for radial gradients
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum RadialShape {
   /// A circle shape where radii are equal
@@ -64,7 +64,7 @@ declare_enum_from_css_impl!(
 );
 
 /// Supported size keywords for radial gradients
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum RadialSize {
   /// The gradient end stops at the nearest side from the center
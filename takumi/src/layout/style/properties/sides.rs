@@ -8,7 +8,7 @@ use crate::{
 };
 
 /// Represents the values for the four sides of a box (top, right, bottom, left).
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 pub struct Sides<T: Copy>(pub [T; 4]);
 
 pub(crate) enum Axis {
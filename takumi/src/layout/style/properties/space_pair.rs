@@ -11,7 +11,7 @@ use crate::{
 };
 
 /// A pair of values for horizontal and vertical axes.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 pub struct SpacePair<T: Copy> {
   /// The horizontal value.
   pub x: T,
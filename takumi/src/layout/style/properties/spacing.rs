@@ -0,0 +1,112 @@
+use cssparser::Parser;
+
+use crate::{
+  layout::style::{
+    Animatable, Color, CssSyntaxKind, CssToken, FromCss, Length, MakeComputed, ParseResult,
+  },
+  rendering::Sizing,
+};
+
+/// Extra space between letters or words.
+///
+/// Corresponds to CSS letter-spacing and word-spacing properties. Percentages and `em` are
+/// relative to the font size.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
+#[non_exhaustive]
+pub enum Spacing {
+  /// No extra space.
+  #[default]
+  Normal,
+  /// A specific length, which may be negative.
+  Length(Length),
+}
+
+impl From<Length> for Spacing {
+  fn from(value: Length) -> Self {
+    Self::Length(value)
+  }
+}
+
+impl Spacing {
+  /// Resolves the spacing to pixels.
+  pub(crate) fn to_px(self, sizing: &Sizing, font_size: f32) -> f32 {
+    match self {
+      Self::Normal => 0.0,
+      Self::Length(length) => length.to_px(sizing, font_size),
+    }
+  }
+
+  fn as_length(self) -> Length {
+    match self {
+      Self::Normal => Length::Px(0.0),
+      Self::Length(length) => length,
+    }
+  }
+}
+
+impl<'i> FromCss<'i> for Spacing {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    if input
+      .try_parse(|input| input.expect_ident_matching("normal"))
+      .is_ok()
+    {
+      return Ok(Self::Normal);
+    }
+
+    Length::from_css(input).map(Into::into)
+  }
+
+  const VALID_TOKENS: &'static [CssToken] = &[
+    CssToken::Keyword("normal"),
+    CssToken::Syntax(CssSyntaxKind::Length),
+  ];
+}
+
+impl MakeComputed for Spacing {
+  fn make_computed(&mut self, sizing: &Sizing) {
+    if let Self::Length(length) = self {
+      length.make_computed(sizing);
+    }
+  }
+}
+
+impl Animatable for Spacing {
+  fn interpolate(
+    &mut self,
+    from: &Self,
+    to: &Self,
+    progress: f32,
+    sizing: &Sizing,
+    current_color: Color,
+  ) {
+    // `normal` animates as a zero length.
+    let mut value = from.as_length();
+    value.interpolate(
+      &from.as_length(),
+      &to.as_length(),
+      progress,
+      sizing,
+      current_color,
+    );
+    *self = Self::Length(value);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Spacing;
+  use crate::layout::style::{FromCss, Length};
+
+  #[test]
+  fn parses_normal_and_lengths() {
+    assert_eq!(Spacing::from_str("normal"), Ok(Spacing::Normal));
+    assert_eq!(
+      Spacing::from_str("-0.05em"),
+      Ok(Spacing::Length(Length::Em(-0.05)))
+    );
+    assert_eq!(
+      Spacing::from_str("10%"),
+      Ok(Spacing::Length(Length::Percentage(10.0)))
+    );
+  }
+}
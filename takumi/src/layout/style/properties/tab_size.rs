@@ -0,0 +1,85 @@
+use std::hash::{Hash, Hasher};
+
+use cssparser::{BasicParseErrorKind, Parser};
+
+use crate::{
+  layout::style::{CssSyntaxKind, CssToken, FromCss, Length, MakeComputed, ParseResult},
+  rendering::Sizing,
+};
+
+/// Controls the width of tab characters in preserved white space.
+///
+/// Corresponds to CSS tab-size property. A number is measured in space characters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum TabSize {
+  /// A tab stop every given number of spaces.
+  Spaces(f32),
+  /// A tab stop every given length.
+  Length(Length),
+}
+
+impl Hash for TabSize {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    std::mem::discriminant(self).hash(state);
+    match self {
+      Self::Spaces(spaces) => spaces.to_bits().hash(state),
+      Self::Length(length) => length.hash(state),
+    }
+  }
+}
+
+impl Default for TabSize {
+  fn default() -> Self {
+    Self::Spaces(8.0)
+  }
+}
+
+impl From<Length> for TabSize {
+  fn from(value: Length) -> Self {
+    Self::Length(value)
+  }
+}
+
+impl<'i> FromCss<'i> for TabSize {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let Ok(number) = input.try_parse(Parser::expect_number) else {
+      return Length::from_css(input).map(Into::into);
+    };
+
+    if number < 0.0 {
+      return Err(input.new_error(BasicParseErrorKind::QualifiedRuleInvalid));
+    }
+
+    Ok(Self::Spaces(number))
+  }
+
+  const VALID_TOKENS: &'static [CssToken] = &[
+    CssToken::Syntax(CssSyntaxKind::Number),
+    CssToken::Syntax(CssSyntaxKind::Length),
+  ];
+}
+
+impl MakeComputed for TabSize {
+  fn make_computed(&mut self, sizing: &Sizing) {
+    if let Self::Length(length) = self {
+      length.make_computed(sizing);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::TabSize;
+  use crate::layout::style::{FromCss, Length};
+
+  #[test]
+  fn parses_spaces_and_lengths() {
+    assert_eq!(TabSize::from_str("4"), Ok(TabSize::Spaces(4.0)));
+    assert_eq!(
+      TabSize::from_str("24px"),
+      Ok(TabSize::Length(Length::Px(24.0)))
+    );
+    assert!(TabSize::from_str("-2").is_err());
+  }
+}
@@ -0,0 +1,41 @@
+use crate::layout::style::declare_enum_from_css_impl;
+
+/// Controls the automatic spacing inserted between ideographic and non-ideographic text.
+///
+/// Corresponds to CSS text-autospace property. Unlike CSS, spacing is opt-in and defaults to `no-autospace`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum TextAutospace {
+  /// Inserts no extra spacing.
+  #[default]
+  NoAutospace,
+  /// Inserts spacing between ideographs and both letters and numerals.
+  Normal,
+  /// Inserts spacing between ideographs and letters of non-ideographic scripts.
+  IdeographAlpha,
+  /// Inserts spacing between ideographs and numerals of non-ideographic scripts.
+  IdeographNumeric,
+}
+
+impl TextAutospace {
+  /// Whether letters of non-ideographic scripts are spaced from ideographs.
+  pub(crate) fn spaces_alpha(self) -> bool {
+    matches!(self, TextAutospace::Normal | TextAutospace::IdeographAlpha)
+  }
+
+  /// Whether numerals of non-ideographic scripts are spaced from ideographs.
+  pub(crate) fn spaces_numeric(self) -> bool {
+    matches!(
+      self,
+      TextAutospace::Normal | TextAutospace::IdeographNumeric
+    )
+  }
+}
+
+declare_enum_from_css_impl!(
+  TextAutospace,
+  "no-autospace" => TextAutospace::NoAutospace,
+  "normal" => TextAutospace::Normal,
+  "ideograph-alpha" => TextAutospace::IdeographAlpha,
+  "ideograph-numeric" => TextAutospace::IdeographNumeric,
+);
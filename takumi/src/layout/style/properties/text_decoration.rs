@@ -12,7 +12,7 @@ use crate::{
 
 bitflags! {
   /// Represents a collection of text decoration lines.
-  #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+  #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
   #[non_exhaustive]
   pub struct TextDecorationLines: u8 {
     /// Underline text decoration.
@@ -69,7 +69,7 @@ impl<'i> FromCss<'i> for TextDecorationLines {
 impl MakeComputed for TextDecorationLines {}
 
 /// Represents text decoration thickness options.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum TextDecorationThickness {
   /// Use the font's default thickness, fallback to `auto` if not available.
@@ -152,18 +152,30 @@ impl TailwindPropertyParser for TextDecorationThickness {
   }
 }
 
-/// Represents text decoration style options (currently only solid is supported).
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+/// Represents text decoration style options.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum TextDecorationStyle {
-  /// Solid text decoration style.
+  /// A single solid line.
   #[default]
   Solid,
+  /// Two parallel solid lines.
+  Double,
+  /// A series of round dots.
+  Dotted,
+  /// A series of short dashes.
+  Dashed,
+  /// A wavy line.
+  Wavy,
 }
 
 declare_enum_from_css_impl!(
   TextDecorationStyle,
-  "solid" => Self::Solid
+  "solid" => Self::Solid,
+  "double" => Self::Double,
+  "dotted" => Self::Dotted,
+  "dashed" => Self::Dashed,
+  "wavy" => Self::Wavy
 );
 
 /// Parsed `text-decoration` value.
@@ -173,7 +185,7 @@ declare_enum_from_css_impl!(
 pub struct TextDecoration {
   /// Text decoration line style.
   pub line: TextDecorationLines,
-  /// Text decoration style (currently only solid is supported).
+  /// Text decoration style.
   pub style: TextDecorationStyle,
   /// Optional text decoration color.
   pub color: ColorInput,
@@ -0,0 +1,136 @@
+use cssparser::{Parser, Token, match_ignore_ascii_case};
+
+use crate::{
+  layout::style::{
+    Color, CssSyntaxKind, CssToken, FromCss, Length, LengthDefaultsToZero, MakeComputed,
+    ParseResult, TextShadow,
+  },
+  rendering::Sizing,
+};
+
+/// Depth used when `text-emboss` doesn't specify one.
+const DEFAULT_EMBOSS_DEPTH: LengthDefaultsToZero = Length::Em(0.04);
+
+/// Makes text look raised from or pressed into the surface behind it.
+///
+/// The effect is drawn as a light and a dark text shadow, offset vertically by the depth in
+/// opposite directions, with colors derived from the text color. They are drawn behind any
+/// `text-shadow`.
+///
+/// Syntax: `none | raised <length>? | pressed <length>?`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum TextEmboss {
+  /// No effect.
+  #[default]
+  None,
+  /// A light edge above the glyphs and a dark edge below, as if lit from above.
+  Raised(LengthDefaultsToZero),
+  /// A dark edge above the glyphs and a light edge below, like letterpress.
+  Pressed(LengthDefaultsToZero),
+}
+
+impl TextEmboss {
+  /// Returns the light and dark shadows of the effect for text drawn in `color`.
+  pub(crate) fn shadows(self, color: Color) -> Option<[TextShadow; 2]> {
+    let (depth, light_above) = match self {
+      TextEmboss::None => return None,
+      TextEmboss::Raised(depth) => (depth, true),
+      TextEmboss::Pressed(depth) => (depth, false),
+    };
+
+    let shadow = |above: bool, color: Color| {
+      TextShadow::builder()
+        .offset_y(if above { -depth } else { depth })
+        .color(color.into())
+        .build()
+    };
+
+    Some([
+      shadow(light_above, mix(color, Color::white(), 0.75)),
+      shadow(!light_above, mix(color, Color::black(), 0.6)),
+    ])
+  }
+}
+
+/// Mixes `amount` of `target` into the color channels, keeping the alpha of `color`.
+fn mix(color: Color, target: Color, amount: f32) -> Color {
+  let channel = |index: usize| {
+    color.0[index] as f32 + (target.0[index] as f32 - color.0[index] as f32) * amount
+  };
+
+  Color::from([channel(0), channel(1), channel(2), color.0[3] as f32])
+}
+
+impl<'i> FromCss<'i> for TextEmboss {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    if input
+      .try_parse(|input| input.expect_ident_matching("none"))
+      .is_ok()
+    {
+      return Ok(TextEmboss::None);
+    }
+
+    let location = input.current_source_location();
+    let ident = input.expect_ident_cloned()?;
+    let depth = input
+      .try_parse(Length::from_css)
+      .unwrap_or(DEFAULT_EMBOSS_DEPTH);
+
+    match_ignore_ascii_case! { &ident,
+      "raised" => Ok(TextEmboss::Raised(depth)),
+      "pressed" => Ok(TextEmboss::Pressed(depth)),
+      _ => Err(location.new_unexpected_token_error(Token::Ident(ident))),
+    }
+  }
+
+  const VALID_TOKENS: &'static [CssToken] = &[
+    CssToken::Keyword("none"),
+    CssToken::Keyword("raised"),
+    CssToken::Keyword("pressed"),
+    CssToken::Syntax(CssSyntaxKind::Length),
+  ];
+}
+
+impl MakeComputed for TextEmboss {
+  fn make_computed(&mut self, sizing: &Sizing) {
+    if let TextEmboss::Raised(depth) | TextEmboss::Pressed(depth) = self {
+      depth.make_computed(sizing);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_keywords_and_depths() {
+    assert_eq!(TextEmboss::from_str("none"), Ok(TextEmboss::None));
+    assert_eq!(
+      TextEmboss::from_str("raised"),
+      Ok(TextEmboss::Raised(DEFAULT_EMBOSS_DEPTH))
+    );
+    assert_eq!(
+      TextEmboss::from_str("pressed 2px"),
+      Ok(TextEmboss::Pressed(Length::Px(2.0)))
+    );
+    assert!(TextEmboss::from_str("sunken").is_err());
+  }
+
+  #[test]
+  fn derives_light_and_dark_shadows_from_text_color() {
+    let Some([light, dark]) =
+      TextEmboss::Raised(Length::Px(2.0)).shadows(Color([100, 100, 100, 200]))
+    else {
+      unreachable!()
+    };
+
+    assert_eq!(light.offset_y, Length::Px(-2.0));
+    assert_eq!(dark.offset_y, Length::Px(2.0));
+    assert_eq!(light.color, Color([216, 216, 216, 200]).into());
+    assert_eq!(dark.color, Color([40, 40, 40, 200]).into());
+
+    assert!(TextEmboss::None.shadows(Color::black()).is_none());
+  }
+}
@@ -5,7 +5,7 @@ use crate::layout::style::{CssSyntaxKind, CssToken, FromCss, MakeComputed, Parse
 /// Defines how text should be overflowed.
 ///
 /// This enum determines how text should be handled when it exceeds the container width.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum TextOverflow {
   /// Text is simply clipped at the overflow edge with no visual indication
@@ -12,7 +12,7 @@ use crate::{
 };
 
 /// Represents a text shadow with all its properties.
-#[derive(Debug, Clone, PartialEq, Copy, Default, TypedBuilder)]
+#[derive(Debug, Clone, PartialEq, Copy, Default, TypedBuilder, Hash)]
 #[non_exhaustive]
 #[builder(field_defaults(default))]
 pub struct TextShadow {
@@ -24,6 +24,8 @@ pub struct TextShadow {
   pub blur_radius: LengthDefaultsToZero,
   /// Color of the shadow.
   pub color: ColorInput,
+  /// Whether the shadow is cast inside the glyphs (letterpress) instead of behind them.
+  pub inset: bool,
 }
 
 /// Represents a collection of text shadows; has custom `FromCss` implementation for comma-separated values.
@@ -48,16 +50,28 @@ impl<'i> FromCss<'i> for TextShadow {
   /// - Two length values for horizontal and vertical offsets (required)
   /// - An optional length value for blur radius
   /// - An optional color value
+  /// - An optional `inset` keyword
   ///
   /// Examples:
   /// - `text-shadow: 2px 4px;`
   /// - `text-shadow: 2px 4px 6px;`
   /// - `text-shadow: 2px 4px red;`
+  /// - `text-shadow: inset 0 1px 1px white;`
   fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, TextShadow> {
     let mut color = None;
     let mut lengths = None;
+    let mut inset = false;
 
     while !input.is_exhausted() && !next_is_comma(input) {
+      if !inset
+        && input
+          .try_parse(|input| input.expect_ident_matching("inset"))
+          .is_ok()
+      {
+        inset = true;
+        continue;
+      }
+
       if lengths.is_none() {
         let value = input.try_parse::<_, _, ParseError<Cow<'i, str>>>(|input| {
           let horizontal = Length::from_css(input)?;
@@ -91,10 +105,12 @@ impl<'i> FromCss<'i> for TextShadow {
       offset_x: lengths.0,
       offset_y: lengths.1,
       blur_radius: lengths.2,
+      inset,
     })
   }
 
   const VALID_TOKENS: &'static [CssToken] = &[
+    CssToken::Keyword("inset"),
     CssToken::Syntax(CssSyntaxKind::Length),
     CssToken::Syntax(CssSyntaxKind::Color),
   ];
@@ -119,8 +135,9 @@ impl Animatable for TextShadow {
     ListInterpolationStrategy::PadToLongestWithNeutral
   }
 
-  fn neutral_value_like(_other: &Self) -> Option<Self> {
+  fn neutral_value_like(other: &Self) -> Option<Self> {
     Some(Self {
+      inset: other.inset,
       offset_x: Length::zero(),
       offset_y: Length::zero(),
       blur_radius: Length::zero(),
@@ -136,6 +153,12 @@ impl Animatable for TextShadow {
     sizing: &Sizing,
     current_color: Color,
   ) {
+    if from.inset != to.inset {
+      *self = if progress >= 0.5 { *to } else { *from };
+      return;
+    }
+
+    self.inset = from.inset;
     self.offset_x.interpolate(
       &from.offset_x,
       &to.offset_x,
@@ -179,6 +202,7 @@ mod tests {
           offset_y: Px(5.0),
           blur_radius: Px(0.0),
           color: Color([85, 138, 187, 255]).into(),
+          inset: false,
         }]
         .into()
       )
@@ -196,12 +220,14 @@ mod tests {
             offset_y: Px(5.0),
             blur_radius: Px(0.0),
             color: Color([85, 138, 187, 255]).into(),
+            inset: false,
           },
           TextShadow {
             offset_x: Px(10.0),
             offset_y: Px(10.0),
             blur_radius: Px(0.0),
             color: Color([85, 138, 187, 255]).into(),
+            inset: false,
           }
         ]
         .into()
@@ -220,16 +246,52 @@ mod tests {
             offset_y: Px(5.0),
             blur_radius: Px(0.0),
             color: Color([0, 0, 0, 128]).into(),
+            inset: false,
           },
           TextShadow {
             offset_x: Px(10.0),
             offset_y: Px(10.0),
             blur_radius: Px(0.0),
             color: Color([255, 0, 0, 64]).into(),
+            inset: false,
+          }
+        ]
+        .into()
+      )
+    );
+  }
+
+  #[test]
+  fn test_parse_text_shadow_inset() {
+    assert_eq!(
+      TextShadows::from_str("inset 0 1px 1px white, 2px 2px 0 black"),
+      Ok(
+        [
+          TextShadow {
+            offset_x: Px(0.0),
+            offset_y: Px(1.0),
+            blur_radius: Px(1.0),
+            color: Color::white().into(),
+            inset: true,
+          },
+          TextShadow {
+            offset_x: Px(2.0),
+            offset_y: Px(2.0),
+            blur_radius: Px(0.0),
+            color: Color::black().into(),
+            inset: false,
           }
         ]
         .into()
       )
     );
   }
+
+  #[test]
+  fn test_parse_text_shadow_inset_after_color() {
+    assert_eq!(
+      TextShadow::from_str("1px 1px red inset").map(|shadow| shadow.inset),
+      Ok(true)
+    );
+  }
 }
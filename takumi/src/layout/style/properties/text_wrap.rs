@@ -8,7 +8,7 @@ use crate::layout::style::{
 
 /// Controls how text should be wrapped.
 /// Construct with [`TextWrap::builder`].
-#[derive(Debug, Clone, Copy, PartialEq, Default, TypedBuilder)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, TypedBuilder, Hash)]
 #[non_exhaustive]
 #[builder(field_defaults(default))]
 pub struct TextWrap {
@@ -76,7 +76,7 @@ impl<'i> FromCss<'i> for TextWrap {
 }
 
 /// Controls whether text should be wrapped.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum TextWrapMode {
   /// Text is wrapped across lines at appropriate characters to minimize overflow.
@@ -102,7 +102,7 @@ declare_enum_from_css_impl!(
 );
 
 /// Controls the style of text wrapping.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum TextWrapStyle {
   /// Text is wrapped in the default way.
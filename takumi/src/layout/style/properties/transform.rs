@@ -1,4 +1,7 @@
-use std::ops::{Mul, MulAssign};
+use std::{
+  hash::{Hash, Hasher},
+  ops::{Mul, MulAssign},
+};
 
 use cssparser::{Parser, Token, match_ignore_ascii_case};
 use taffy::{Point, Size};
@@ -29,6 +32,19 @@ pub enum Transform {
   Matrix(Affine),
 }
 
+impl Hash for Transform {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    std::mem::discriminant(self).hash(state);
+    match self {
+      Self::Translate(x, y) => (x, y).hash(state),
+      Self::Scale(x, y) => [x, y].map(|scale| scale.to_bits()).hash(state),
+      Self::Rotate(angle) => angle.hash(state),
+      Self::Skew(x, y) => (x, y).hash(state),
+      Self::Matrix(matrix) => matrix.hash(state),
+    }
+  }
+}
+
 impl MakeComputed for Transform {
   fn make_computed(&mut self, sizing: &Sizing) {
     if let Transform::Translate(x, y) = self {
@@ -123,6 +139,12 @@ pub struct Affine {
   pub y: f32,
 }
 
+impl Hash for Affine {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.to_cols_array().map(f32::to_bits).hash(state);
+  }
+}
+
 impl Mul<Affine> for Affine {
   type Output = Affine;
 
@@ -194,6 +216,11 @@ impl Affine {
       && (self.d - 1.0).abs() < 1e-8
   }
 
+  /// Returns true if the transform keeps edges parallel to the axes, i.e. has no rotation or skew
+  pub(crate) fn is_axis_aligned(self) -> bool {
+    self.b.abs() < 1e-6 && self.c.abs() < 1e-6
+  }
+
   /// Creates a new rotation transform
   pub fn rotation(angle: Angle) -> Self {
     let (sin, cos) = angle.to_radians().sin_cos();
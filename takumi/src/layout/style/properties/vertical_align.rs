@@ -7,7 +7,7 @@ use crate::{
 };
 
 /// Keyword values for the CSS `vertical-align` property.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub enum VerticalAlignKeyword {
   /// Aligns the baseline of the box with the baseline of the parent box.
@@ -42,7 +42,7 @@ declare_enum_from_css_impl!(
 );
 
 /// Defines the vertical alignment of an inline-level box.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum VerticalAlign {
   /// A keyword-based alignment mode.
@@ -118,8 +118,9 @@ impl VerticalAlign {
             };
           }
 
-          if let Length::Calc(formula) = length {
-            let linear = formula.resolve(sizing);
+          if let Length::Calc(formula) = length
+            && let ResolvedCalc::Linear(linear) = formula.resolve(sizing)
+          {
             let (px, percent) = linear.components();
 
             return ResolvedVerticalAlign::BaselineShift {
@@ -221,9 +222,11 @@ mod tests {
         size: (200, 100).into(),
         font_size: 16.0,
         device_pixel_ratio: 2.0,
+        color_scheme: Default::default(),
       },
       container_size: Size::NONE,
       font_size: 10.0,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     }
   }
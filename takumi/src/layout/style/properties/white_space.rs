@@ -6,7 +6,7 @@ use crate::layout::style::{
 };
 
 /// Controls how whitespace should be handled.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
 #[non_exhaustive]
 pub struct WhiteSpace {
   /// Controls whether text should be wrapped.
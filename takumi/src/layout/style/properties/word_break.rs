@@ -5,7 +5,7 @@ use crate::layout::style::declare_enum_from_css_impl;
 /// Controls how text should be broken at word boundaries.
 ///
 /// Corresponds to CSS word-break property.
-#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum WordBreak {
   /// Normal line breaking behavior—lines may break according to language rules.
@@ -17,6 +17,9 @@ pub enum WordBreak {
   KeepAll,
   /// Allow breaking within long words if necessary to prevent overflow.
   BreakWord,
+  /// Non-standard: also allow breaks after the punctuation that separates the parts of URLs,
+  /// paths and handles, like `/`, `.` and `-`, so long links wrap between their parts.
+  BreakUrl,
 }
 
 declare_enum_from_css_impl!(
@@ -25,12 +28,13 @@ declare_enum_from_css_impl!(
   "break-all" => WordBreak::BreakAll,
   "keep-all" => WordBreak::KeepAll,
   "break-word" => WordBreak::BreakWord,
+  "break-url" => WordBreak::BreakUrl,
 );
 
 impl From<WordBreak> for WordBreakStrength {
   fn from(value: WordBreak) -> Self {
     match value {
-      WordBreak::Normal | WordBreak::BreakWord => WordBreakStrength::Normal,
+      WordBreak::Normal | WordBreak::BreakWord | WordBreak::BreakUrl => WordBreakStrength::Normal,
       WordBreak::BreakAll => WordBreakStrength::BreakAll,
       WordBreak::KeepAll => WordBreakStrength::KeepAll,
     }
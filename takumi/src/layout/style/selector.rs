@@ -17,7 +17,7 @@ use crate::{
   error::StyleSheetParseError,
   keyframes::parse_keyframe_prelude,
   layout::{
-    Viewport,
+    ColorScheme, Viewport,
     style::{
       CalcArena, FromCss, KeyframeRule, KeyframesRule, LengthDefaultsToZero, StyleDeclarationBlock,
     },
@@ -519,6 +519,7 @@ enum MediaFeature {
   Width(MediaFeatureComparison, LengthDefaultsToZero),
   Height(MediaFeatureComparison, LengthDefaultsToZero),
   Orientation(MediaOrientation),
+  PrefersColorScheme(ColorScheme),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -556,6 +557,7 @@ impl MediaFeature {
         .width
         .zip(viewport.size.height)
         .is_some_and(|(width, height)| width > height),
+      Self::PrefersColorScheme(scheme) => viewport.color_scheme == *scheme,
     }
   }
 }
@@ -590,7 +592,8 @@ impl MediaQueryList {
     let sizing = Sizing {
       viewport,
       container_size: Size::NONE,
-      font_size: viewport.font_size,
+      font_size: viewport.font_size * viewport.device_pixel_ratio,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     };
 
@@ -705,6 +708,17 @@ fn parse_media_feature<'i, 't>(
     };
   }
 
+  if feature_name.eq_ignore_ascii_case("prefers-color-scheme") {
+    let scheme = input.expect_ident_cloned()?;
+    return if scheme.eq_ignore_ascii_case("light") {
+      Ok(MediaFeature::PrefersColorScheme(ColorScheme::Light))
+    } else if scheme.eq_ignore_ascii_case("dark") {
+      Ok(MediaFeature::PrefersColorScheme(ColorScheme::Dark))
+    } else {
+      Err(input.new_error(BasicParseErrorKind::UnexpectedToken(Token::Ident(scheme))))
+    };
+  }
+
   let comparison = if feature_name.eq_ignore_ascii_case("min-width")
     || feature_name.eq_ignore_ascii_case("min-height")
   {
@@ -1755,6 +1769,23 @@ mod tests {
     assert!(!media.matches(Viewport::new((800, 800))));
   }
 
+  #[test]
+  fn test_parse_prefers_color_scheme_media_rule() {
+    let sheet = parse_stylesheet(
+      r#"
+        @media (prefers-color-scheme: dark) {
+          .card { color: white; }
+        }
+      "#,
+    );
+
+    let Some(media) = sheet.rules[0].media_queries.first() else {
+      unreachable!("expected media queries on parsed rule");
+    };
+    assert!(media.matches(Viewport::new((400, 800)).with_color_scheme(ColorScheme::Dark)));
+    assert!(!media.matches(Viewport::new((400, 800))));
+  }
+
   #[test]
   fn test_parse_media_rule_applies_to_keyframes_and_property_rules() {
     let sheet = parse_stylesheet(
@@ -1,26 +1,50 @@
-use std::{borrow::Cow, collections::HashMap, fmt::Write, marker::PhantomData, str::FromStr};
+use std::{
+  borrow::Cow,
+  collections::HashMap,
+  fmt::Write,
+  hash::{Hash, Hasher},
+  marker::PhantomData,
+  mem::take,
+  str::FromStr,
+  sync::Arc,
+};
 
 use cssparser::{
-  ParseError, ParseErrorKind, Parser, ParserInput, SourceLocation, Token, match_ignore_ascii_case,
+  CowRcStr, ParseError, ParseErrorKind, Parser, ParserInput, SourceLocation, Token,
+  match_ignore_ascii_case,
 };
-use parley::{FontSettings, TextStyle};
+use parley::{FontFeature, FontSettings, TextStyle};
 use paste::paste;
-use serde::de::IgnoredAny;
+use serde::de::{DeserializeSeed, IgnoredAny};
 use smallvec::SmallVec;
+use swash::tag_from_bytes;
 use taffy::{Point, Rect, Size, prelude::FromLength};
+use xxhash_rust::xxh3::Xxh3;
 
 use crate::layout::style::selector::{PropertyRule, StyleDeclarationParser};
 use crate::{
   error::StyleDeclarationBlockParseError,
   layout::{
+    ColorScheme,
     inline::InlineBrush,
     style::{RawCssInput, RawCssValueSeed, properties::*},
   },
   rendering::{RenderContext, SizedShadow, Sizing},
-  resources::task::FetchTaskCollection,
+  resources::{palette::ImagePalettes, task::FetchTaskCollection},
 };
 use cssparser::RuleBodyParser;
 
+/// Hashes a longhand value with its `Hash` impl, or with the function given for types that
+/// don't have one.
+macro_rules! hash_longhand_value {
+  ($value:ident, $state:ident) => {
+    $value.hash($state)
+  };
+  ($value:ident, $state:ident, $hash:path) => {
+    $hash($value, $state)
+  };
+}
+
 macro_rules! define_inherited_default {
   ($parent:expr, $inherit:tt) => {
     $parent.to_owned()
@@ -41,7 +65,7 @@ enum ParsedDeclarations {
   Many(Vec<StyleDeclaration>),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct DeferredDeclaration {
   property: PropertyId,
   raw_value: String,
@@ -155,6 +179,39 @@ impl RawStyleValueParseError<'_> {
   }
 }
 
+/// Why a single style property failed to parse.
+pub(crate) struct StylePropertyError {
+  pub(crate) message: String,
+  pub(crate) token: Option<String>,
+}
+
+/// Parses a single camelCase style property on its own, returning why it's invalid.
+///
+/// Properties that are ignored, custom or substituted later are never reported.
+pub(crate) fn style_property_error(
+  name: &str,
+  value: &serde_json::Value,
+) -> Option<StylePropertyError> {
+  let property = PropertyId::from_camel_case(name);
+  if matches!(property, PropertyId::Ignored | PropertyId::Custom) {
+    return None;
+  }
+
+  let raw_value = RawCssValueSeed.deserialize(value).ok()?;
+  if let RawCssInput::Str(value) = &raw_value
+    && contains_substitution_function(value)
+  {
+    return None;
+  }
+
+  let error = property.try_parse_raw_declarations(raw_value).err()?;
+
+  Some(StylePropertyError {
+    token: error.failure().and_then(|failure| failure.detail),
+    message: error.message(name, property),
+  })
+}
+
 fn expected_message<T>() -> super::CssExpectedMessage<'static>
 where
   T: for<'i> FromCss<'i>,
@@ -378,21 +435,26 @@ fn parse_custom_property_declaration<'i>(
   ))
 }
 
-fn contains_var_function(raw_value: &str) -> bool {
+fn contains_substitution_function(raw_value: &str) -> bool {
   fn contains_in_parser(input: &mut Parser<'_, '_>) -> bool {
     while let Ok(token) = input.next_including_whitespace_and_comments() {
       match token {
-        Token::Function(name) if name.eq_ignore_ascii_case("var") => return true,
+        Token::Function(name)
+          if name.eq_ignore_ascii_case("var") || name.eq_ignore_ascii_case("palette") =>
+        {
+          return true;
+        }
         Token::Function(_)
         | Token::ParenthesisBlock
         | Token::SquareBracketBlock
         | Token::CurlyBracketBlock => {
-          if input
+          let nested_contains_var = input
             .parse_nested_block(|input| {
               Ok::<_, cssparser::ParseError<'_, Cow<'_, str>>>(contains_in_parser(input))
             })
-            .unwrap_or(true)
-          {
+            .unwrap_or(true);
+
+          if nested_contains_var {
             return true;
           }
         }
@@ -463,6 +525,7 @@ macro_rules! define_style {
     longhands {
       $(
         $longhand:ident: $longhand_ty:ty
+          $(where hash = $longhand_hash:path)?
           $(where inherit = $longhand_inherit:expr)?,
       )*
     }
@@ -478,7 +541,7 @@ macro_rules! define_style {
   ) => {
     paste! {
       #[repr(u8)]
-      #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+      #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
       pub(crate) enum LonghandId {
         $([<$longhand:camel>],)*
       }
@@ -498,7 +561,7 @@ macro_rules! define_style {
       }
 
       #[repr(u8)]
-      #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+      #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
       pub(crate) enum ShorthandId {
         $([<$shorthand:camel>],)*
       }
@@ -590,7 +653,7 @@ macro_rules! define_style {
         $([<parse_raw_ $shorthand _declarations>],)*
       ];
 
-      #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+      #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
       pub(crate) enum PropertyId {
         Ignored,
         Custom,
@@ -665,6 +728,15 @@ macro_rules! define_style {
         where
           E: serde::de::Error,
         {
+          self
+            .try_parse_raw_declarations(raw_value)
+            .map_err(|error| error.into_serde_error(name, self))
+        }
+
+        fn try_parse_raw_declarations<'de>(
+          self,
+          raw_value: RawCssInput<'de>,
+        ) -> Result<ParsedDeclarations, RawStyleValueParseError<'de>> {
           debug_assert!(
             !matches!(self, Self::Custom),
             "custom properties should be handled before parse_raw_declarations",
@@ -676,7 +748,7 @@ macro_rules! define_style {
             RawCssInput::Unexpected(_) => None,
           };
 
-          if raw_string.is_some_and(contains_var_function) {
+          if raw_string.is_some_and(contains_substitution_function) {
             return Ok(ParsedDeclarations::Single(StyleDeclaration::Deferred(
               DeferredDeclaration {
                 property: self,
@@ -688,14 +760,8 @@ macro_rules! define_style {
           match self {
             Self::Ignored => Ok(ParsedDeclarations::None),
             Self::Custom => unreachable!(),
-            Self::Shorthand(property) => {
-              RAW_SHORTHAND_PARSE_FNS[property.index()](raw_value)
-                .map_err(|error| error.into_serde_error(name, self))
-            }
-            Self::Longhand(property) => {
-              RAW_LONGHAND_PARSE_FNS[property.index()](raw_value)
-                .map_err(|error| error.into_serde_error(name, self))
-            }
+            Self::Shorthand(property) => RAW_SHORTHAND_PARSE_FNS[property.index()](raw_value),
+            Self::Longhand(property) => RAW_LONGHAND_PARSE_FNS[property.index()](raw_value),
           }
         }
 
@@ -726,7 +792,7 @@ macro_rules! define_style {
           Err(error) if !matches!(property, PropertyId::Ignored | PropertyId::Custom) => {
             while input.next_including_whitespace_and_comments().is_ok() {}
             let raw_value = input.slice_from(start).trim();
-            if contains_var_function(raw_value) {
+            if contains_substitution_function(raw_value) {
               Ok(StyleDeclarationBlock::from_parsed_declarations(
                 ParsedDeclarations::Single(StyleDeclaration::Deferred(DeferredDeclaration {
                   property,
@@ -746,6 +812,8 @@ macro_rules! define_style {
       #[derive(Debug, Default, Clone, PartialEq)]
       pub struct Style {
         pub(crate) declarations: StyleDeclarationBlock,
+        /// Declarations applied on top for one color scheme only.
+        pub(crate) color_schemes: Option<Box<ColorSchemeStyles>>,
       }
 
       impl<'de> serde::Deserialize<'de> for Style {
@@ -769,6 +837,12 @@ macro_rules! define_style {
               let mut style = Style::default();
 
               while let Some(key) = map.next_key::<Cow<'de, str>>()? {
+                if let Some(scheme) = ColorSchemeStyles::scheme_of_key(&key) {
+                  let declarations = map.next_value::<StyleDeclarationBlock>()?;
+                  *style.color_schemes.get_or_insert_default().get_mut(scheme) = declarations;
+                  continue;
+                }
+
                 let property = PropertyId::from_camel_case(&key);
                 if matches!(property, PropertyId::Ignored) {
                   map.next_value::<IgnoredAny>()?;
@@ -831,6 +905,23 @@ macro_rules! define_style {
           }
         )*
 
+        /// Returns a new style with declarations that only apply when rendering with `scheme`,
+        /// replacing any given for that scheme before.
+        pub fn with_color_scheme(mut self, scheme: ColorScheme, style: Style) -> Self {
+          *self.color_schemes.get_or_insert_default().get_mut(scheme) = style.declarations;
+          self
+        }
+
+        /// Appends the declarations of `scheme` after the base declarations and drops the other
+        /// schemes.
+        pub(crate) fn resolve_color_scheme(mut self, scheme: ColorScheme) -> Self {
+          if let Some(mut color_schemes) = self.color_schemes.take() {
+            self.append_block(take(color_schemes.get_mut(scheme)));
+          }
+
+          self
+        }
+
         /// Returns a new style with one `!important` declaration appended in source order.
         pub fn with_important(self, declaration: StyleDeclaration) -> Self {
           self.with_declarations([declaration], true)
@@ -847,6 +938,11 @@ macro_rules! define_style {
         /// Collects fetch tasks referenced by this style's declarations.
         pub fn collect_fetch_tasks(&self, collection: &mut FetchTaskCollection) {
           self.declarations.collect_fetch_tasks(collection);
+
+          if let Some(color_schemes) = &self.color_schemes {
+            color_schemes.light.collect_fetch_tasks(collection);
+            color_schemes.dark.collect_fetch_tasks(collection);
+          }
         }
 
         pub(crate) fn inherit(self, parent: &ComputedStyle) -> ComputedStyle {
@@ -887,7 +983,10 @@ macro_rules! define_style {
 
       impl From<StyleDeclarationBlock> for Style {
         fn from(declarations: StyleDeclarationBlock) -> Self {
-          Self { declarations }
+          Self {
+            declarations,
+            color_schemes: None,
+          }
         }
       }
 
@@ -898,10 +997,12 @@ macro_rules! define_style {
       }
 
       /// The computed style snapshot used during layout and rendering.
-      #[derive(Clone, Debug, Default)]
+      #[derive(Clone, Debug, Default, PartialEq)]
       pub struct ComputedStyle {
         pub(crate) custom_properties: HashMap<String, String>,
         pub(crate) registered_custom_properties: HashMap<String, PropertyRule>,
+        /// Images that `palette()` references resolve against.
+        pub(crate) image_palettes: Option<Arc<ImagePalettes>>,
         $(pub(crate) $longhand: $longhand_ty,)*
       }
 
@@ -922,11 +1023,28 @@ macro_rules! define_style {
         CssWideKeyword(LonghandId, CssWideKeyword),
       }
 
+      impl Hash for StyleDeclaration {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+          std::mem::discriminant(self).hash(state);
+          match self {
+            $(
+              Self::[<$longhand:camel>](value) => {
+                hash_longhand_value!(value, state $(, $longhand_hash)?)
+              }
+            )*
+            Self::CustomProperty(name, value) => (name, value).hash(state),
+            Self::Deferred(deferred) => deferred.hash(state),
+            Self::CssWideKeyword(longhand, keyword) => (longhand, keyword).hash(state),
+          }
+        }
+      }
+
       impl ComputedStyle {
         pub(crate) fn from_parent(parent: &Self) -> Self {
           Self {
             custom_properties: parent.custom_properties.clone(),
                         registered_custom_properties: parent.registered_custom_properties.clone(),
+            image_palettes: parent.image_palettes.clone(),
             $($longhand: define_inherited_default!(parent.$longhand $(, $longhand_inherit)?),)*
           }
         }
@@ -1032,6 +1150,14 @@ macro_rules! define_style {
           }
         )*
 
+        /// Returns a declaration whose value is resolved after `var()` substitution.
+        pub(crate) fn deferred(property: LonghandId, raw_value: String) -> Self {
+          Self::Deferred(DeferredDeclaration {
+            property: PropertyId::Longhand(property),
+            raw_value,
+          })
+        }
+
         pub(crate) fn longhand_id(&self) -> LonghandId {
           match self {
             $(Self::[<$longhand:camel>](..) => LonghandId::[<$longhand:camel>],)*
@@ -1132,6 +1258,7 @@ define_style! {
   longhands {
     box_sizing: BoxSizing,
     opacity: PercentageNumber,
+    zoom: PercentageNumber,
     animation_name: AnimationNames,
     animation_duration: AnimationDurations,
     animation_delay: AnimationDurations,
@@ -1170,6 +1297,7 @@ define_style! {
     flex_wrap: FlexWrap,
     flex_basis: Option<Length>,
     position: Position,
+    position_anchor: Option<PositionAnchor>,
     rotate: Option<Angle>,
     scale: SpacePair<PercentageNumber>,
     translate: SpacePair<Length>,
@@ -1179,6 +1307,7 @@ define_style! {
     mask_size: BackgroundSizes,
     mask_position: BackgroundPositions,
     mask_repeat: BackgroundRepeats,
+    mask_mode: MaskModes,
     column_gap: LengthDefaultsToZero,
     row_gap: LengthDefaultsToZero,
     flex_grow: Option<FlexGrow>,
@@ -1187,11 +1316,13 @@ define_style! {
     border_top_right_radius: SpacePair<LengthDefaultsToZero>,
     border_bottom_right_radius: SpacePair<LengthDefaultsToZero>,
     border_bottom_left_radius: SpacePair<LengthDefaultsToZero>,
+    corner_smoothing: CornerSmoothing,
     border_top_width: Length,
     border_right_width: Length,
     border_bottom_width: Length,
     border_left_width: Length,
     border_style: BorderStyle,
+    border_alignment: BorderAlignment,
     border_color: ColorInput,
     outline_width: Length,
     outline_style: BorderStyle,
@@ -1200,6 +1331,7 @@ define_style! {
     object_fit: ObjectFit,
     overflow_x: Overflow,
     overflow_y: Overflow,
+    fade_edges: FadeEdges,
     object_position: ObjectPosition where inherit = true,
     background_image: Option<BackgroundImages>,
     background_position: BackgroundPositions,
@@ -1207,7 +1339,9 @@ define_style! {
     background_repeat: BackgroundRepeats,
     background_blend_mode: BlendModes,
     background_color: ColorInput<false>,
-    background_clip: BackgroundClip,
+    background_clip: BackgroundClips,
+    background_origin: BackgroundOrigins,
+    background_attachment: BackgroundAttachments,
     box_shadow: Option<BoxShadows>,
     grid_auto_columns: Option<GridTrackSizes>,
     grid_auto_rows: Option<GridTrackSizes>,
@@ -1227,28 +1361,41 @@ define_style! {
     font_size: FontSize where inherit = true,
     font_family: FontFamily where inherit = true,
     line_height: LineHeight where inherit = true,
+    line_height_step: LengthDefaultsToZero where inherit = true,
     font_weight: FontWeight where inherit = true,
-    font_variation_settings: FontVariationSettings where inherit = true,
-    font_feature_settings: FontFeatureSettings where inherit = true,
+    font_variation_settings: FontVariationSettings
+      where hash = hash_font_variation_settings
+      where inherit = true,
+    font_feature_settings: FontFeatureSettings
+      where hash = hash_font_feature_settings
+      where inherit = true,
+    font_kerning: FontKerning where inherit = true,
+    font_variant_ligatures: FontVariantLigatures where inherit = true,
+    font_variant_caps: FontVariantCaps where inherit = true,
     font_synthesis_weight: FontSynthesic where inherit = true,
     font_synthesis_style: FontSynthesic where inherit = true,
     line_clamp: Option<LineClamp> where inherit = true,
     text_align: TextAlign where inherit = true,
     webkit_text_stroke_width: Option<LengthDefaultsToZero> where inherit = true,
     webkit_text_stroke_color: Option<ColorInput> where inherit = true,
+    text_stroke_image: Option<BackgroundImages> where inherit = true,
     webkit_text_fill_color: Option<ColorInput> where inherit = true,
     stroke_linejoin: LineJoin where inherit = true,
     text_shadow: Option<TextShadows> where inherit = true,
+    text_emboss: TextEmboss where inherit = true,
     text_decoration_line: Option<TextDecorationLines>,
     text_decoration_style: TextDecorationStyle,
     text_decoration_color: ColorInput,
     text_decoration_thickness: TextDecorationThickness,
     text_decoration_skip_ink: TextDecorationSkipInk where inherit = true,
-    letter_spacing: Length where inherit = true,
-    word_spacing: Length where inherit = true,
+    letter_spacing: Spacing where inherit = true,
+    word_spacing: Spacing where inherit = true,
     image_rendering: ImageScalingAlgorithm where inherit = true,
     overflow_wrap: OverflowWrap where inherit = true,
     word_break: WordBreak where inherit = true,
+    line_break: LineBreak where inherit = true,
+    text_autospace: TextAutospace where inherit = true,
+    tab_size: TabSize where inherit = true,
     clip_path: Option<BasicShape>,
     clip_rule: FillRule where inherit = true,
     white_space_collapse: WhiteSpaceCollapse where inherit = true,
@@ -1258,6 +1405,7 @@ define_style! {
     mix_blend_mode: BlendMode,
     visibility: Visibility,
     vertical_align: VerticalAlign,
+    block_step_size: Option<LengthDefaultsToZero>,
   }
   shorthands {
     animation: Animations => [AnimationName, AnimationDuration, AnimationDelay, AnimationTimingFunction, AnimationIterationCount, AnimationDirection, AnimationFillMode, AnimationPlayState] |value, target| {
@@ -1314,6 +1462,13 @@ define_style! {
     flex: Option<Flex> => [FlexGrow, FlexShrink, FlexBasis] |value, target| {
       expand_flex_shorthand(value, target);
     },
+    grid_area: GridArea => [GridRow, GridColumn] |value, target| {
+      push_expanded_declarations!(
+        target;
+        StyleDeclaration::grid_row(Some(value.row)),
+        StyleDeclaration::grid_column(Some(value.column)),
+      );
+    },
     border_radius: Box<BorderRadius> => [BorderTopLeftRadius, BorderTopRightRadius, BorderBottomRightRadius, BorderBottomLeftRadius] |value, target| {
       push_four_side_declarations!(
         target,
@@ -1359,7 +1514,7 @@ define_style! {
     overflow: SpacePair<Overflow> => [OverflowX, OverflowY] |value, target| {
       push_axis_declarations!(target, value, overflow_x, overflow_y);
     },
-    background: Backgrounds => [BackgroundImage, BackgroundPosition, BackgroundSize, BackgroundRepeat, BackgroundBlendMode, BackgroundColor, BackgroundClip] |value, target| {
+    background: Backgrounds => [BackgroundImage, BackgroundPosition, BackgroundSize, BackgroundRepeat, BackgroundBlendMode, BackgroundColor, BackgroundClip, BackgroundOrigin, BackgroundAttachment] |value, target| {
       expand_background_shorthand(value, target);
     },
     font_synthesis: FontSynthesis where inherit = true => [FontSynthesisWeight, FontSynthesisStyle] |value, target| {
@@ -1447,7 +1602,9 @@ fn expand_background_shorthand(value: Backgrounds, target: &mut Vec<StyleDeclara
     StyleDeclaration::background_repeat(value.iter().map(|background| background.repeat).collect()),
     StyleDeclaration::background_blend_mode(value.iter().map(|background| background.blend_mode).collect()),
     StyleDeclaration::background_color(value.iter().filter_map(|background| background.color).next_back().unwrap_or_default()),
-    StyleDeclaration::background_clip(value.last().map(|background| background.clip).unwrap_or_default()),
+    StyleDeclaration::background_clip(value.iter().map(|background| background.clip).collect()),
+    StyleDeclaration::background_origin(value.iter().map(|background| background.origin).collect()),
+    StyleDeclaration::background_attachment(value.iter().map(|background| background.attachment).collect()),
     StyleDeclaration::background_image(Some(value.into_iter().map(|background| background.image).collect())),
   );
 }
@@ -1617,6 +1774,118 @@ fn resolve_var_references(
   resolve_var_tokens(&mut parser, custom_properties, stack)
 }
 
+/// Parses the arguments of `palette(<url> | <string>, <integer>?)`.
+fn parse_palette_arguments<'i>(
+  input: &mut Parser<'i, '_>,
+) -> Result<(CowRcStr<'i>, usize), cssparser::ParseError<'i, ()>> {
+  let src = input.expect_url_or_string()?;
+  let index = if input.try_parse(Parser::expect_comma).is_ok() {
+    let index = input.expect_integer()?;
+    usize::try_from(index).map_err(|_| input.new_custom_error(()))?
+  } else {
+    0
+  };
+
+  input.expect_exhausted()?;
+
+  Ok((src, index))
+}
+
+fn resolve_palette_tokens(input: &mut Parser<'_, '_>, palettes: &ImagePalettes) -> Option<String> {
+  let mut output = String::new();
+
+  while !input.is_exhausted() {
+    let start = input.position();
+    let token = input.next_including_whitespace_and_comments().ok()?.clone();
+
+    let (open, close) = match &token {
+      Token::Function(name) if name.eq_ignore_ascii_case("palette") => {
+        let color = input
+          .parse_nested_block(|input| {
+            let (src, index) = parse_palette_arguments(input)?;
+
+            palettes
+              .color(&src, index)
+              .ok_or_else(|| input.new_custom_error(()))
+          })
+          .ok()?;
+
+        // Writing into a `String` can't fail.
+        let _ = write!(output, "{color}");
+        continue;
+      }
+      Token::Function(name) => (format!("{name}("), ')'),
+      Token::ParenthesisBlock => ("(".to_owned(), ')'),
+      Token::SquareBracketBlock => ("[".to_owned(), ']'),
+      Token::CurlyBracketBlock => ("{".to_owned(), '}'),
+      _ => {
+        output.push_str(input.slice_from(start));
+        continue;
+      }
+    };
+
+    let nested = input
+      .parse_nested_block(|input| {
+        resolve_palette_tokens(input, palettes)
+          .ok_or_else(|| input.new_error_for_next_token::<()>())
+      })
+      .ok()?;
+
+    output.push_str(&open);
+    output.push_str(&nested);
+    output.push(close);
+  }
+
+  Some(output)
+}
+
+/// Replaces every `palette()` reference with the color it points at.
+fn resolve_palette_references(raw_value: &str, palettes: &ImagePalettes) -> Option<String> {
+  let mut parser_input = ParserInput::new(raw_value);
+  let mut parser = Parser::new(&mut parser_input);
+  resolve_palette_tokens(&mut parser, palettes)
+}
+
+/// Hashes the words of a raw value, so runs of whitespace between them hash the same.
+fn hash_collapsed_whitespace<H: Hasher>(value: &str, state: &mut H) {
+  for word in value.split_whitespace() {
+    word.hash(state);
+  }
+}
+
+/// Collects the image sources of every `palette()` reference.
+fn collect_palette_sources(raw_value: &str, collection: &mut FetchTaskCollection) {
+  fn collect_in_parser(input: &mut Parser<'_, '_>, collection: &mut FetchTaskCollection) {
+    while let Ok(token) = input.next_including_whitespace_and_comments() {
+      let is_palette = match token {
+        Token::Function(name) => name.eq_ignore_ascii_case("palette"),
+        Token::ParenthesisBlock | Token::SquareBracketBlock | Token::CurlyBracketBlock => false,
+        _ => continue,
+      };
+
+      let _ = input.parse_nested_block(|input| {
+        if is_palette {
+          if let Ok((src, _)) = parse_palette_arguments(input) {
+            collection.insert(src.as_ref().into());
+          }
+        } else {
+          collect_in_parser(input, collection);
+        }
+
+        Ok::<_, cssparser::ParseError<'_, ()>>(())
+      });
+    }
+  }
+
+  if !raw_value.contains("palette(") {
+    return;
+  }
+
+  let mut parser_input = ParserInput::new(raw_value);
+  let mut parser = Parser::new(&mut parser_input);
+  collect_in_parser(&mut parser, collection);
+}
+
 fn apply_resolved_declarations(
   style: &mut ComputedStyle,
   parent: Option<&ComputedStyle>,
@@ -1670,7 +1939,7 @@ fn apply_deferred_declaration(
   parent: Option<&ComputedStyle>,
   deferred: &DeferredDeclaration,
 ) {
-  let Some(resolved_value) = resolve_var_references(
+  let Some(mut resolved_value) = resolve_var_references(
     &deferred.raw_value,
     &style.custom_properties,
     &mut Vec::new(),
@@ -1678,6 +1947,18 @@ fn apply_deferred_declaration(
     return;
   };
 
+  if resolved_value.contains("palette(") {
+    let Some(value) = style
+      .image_palettes
+      .as_deref()
+      .and_then(|palettes| resolve_palette_references(&resolved_value, palettes))
+    else {
+      return;
+    };
+
+    resolved_value = value;
+  }
+
   let property = deferred.property;
   let css_name = property.css_name();
   let declarations = property
@@ -1695,7 +1976,7 @@ fn apply_deferred_declaration(
 }
 
 /// CSS-wide keywords that can target any longhand declaration.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub(crate) enum CssWideKeyword {
   /// Reset the targeted longhand to its initial value.
   Initial,
@@ -1872,6 +2153,30 @@ where
   }
 }
 
+/// The `light` and `dark` blocks of an inline style, see [`ColorScheme`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ColorSchemeStyles {
+  pub(crate) light: StyleDeclarationBlock,
+  pub(crate) dark: StyleDeclarationBlock,
+}
+
+impl ColorSchemeStyles {
+  fn scheme_of_key(key: &str) -> Option<ColorScheme> {
+    match key {
+      "light" => Some(ColorScheme::Light),
+      "dark" => Some(ColorScheme::Dark),
+      _ => None,
+    }
+  }
+
+  fn get_mut(&mut self, scheme: ColorScheme) -> &mut StyleDeclarationBlock {
+    match scheme {
+      ColorScheme::Light => &mut self.light,
+      ColorScheme::Dark => &mut self.dark,
+    }
+  }
+}
+
 /// Ordered specified declarations plus the set of important properties.
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct StyleDeclarationBlock {
@@ -1933,7 +2238,8 @@ impl StyleDeclarationBlock {
     for declaration in self.iter() {
       match declaration {
         StyleDeclaration::BackgroundImage(Some(images))
-        | StyleDeclaration::MaskImage(Some(images)) => {
+        | StyleDeclaration::MaskImage(Some(images))
+        | StyleDeclaration::TextStrokeImage(Some(images)) => {
           collection.insert_many(images.iter().filter_map(|image| {
             if let BackgroundImage::Url(url) = image {
               Some(url.clone())
@@ -1945,6 +2251,95 @@ impl StyleDeclarationBlock {
         _ => {}
       }
     }
+
+    self.collect_palette_sources(collection);
+  }
+
+  /// Collects the image sources referenced by `palette()` in unresolved values.
+  pub(crate) fn collect_palette_sources(&self, collection: &mut FetchTaskCollection) {
+    for declaration in self.iter() {
+      if let StyleDeclaration::CustomProperty(_, raw_value)
+      | StyleDeclaration::Deferred(DeferredDeclaration { raw_value, .. }) = declaration
+      {
+        collect_palette_sources(raw_value, collection);
+      }
+    }
+  }
+
+  /// Hashes the declarations that take effect in a canonical form.
+  ///
+  /// Declarations overridden later in the block are skipped and whitespace in raw values is
+  /// collapsed. The rest hash the same in any order, unless one of them partly overrides another,
+  /// in which case their source order still matters.
+  pub(crate) fn hash_canonical<H: Hasher>(&self, state: &mut H) {
+    let mut covered = PropertyMask::default();
+    let mut custom_properties = Vec::new();
+    let mut ordered = false;
+    let mut digests = SmallVec::<[u64; 8]>::new();
+    for declaration in self.declarations.iter().rev() {
+      let (effective, important) = match declaration {
+        StyleDeclaration::CustomProperty(name, _) => {
+          let effective = !custom_properties.contains(&name);
+          custom_properties.push(name);
+          (
+            effective,
+            self
+              .importance
+              .custom_properties
+              .iter()
+              .any(|important| important.as_ref() == name),
+          )
+        }
+        _ => {
+          let longhands = declaration.affected_longhands();
+          let (mut effective, mut overridden) = (false, false);
+          for longhand in longhands.iter() {
+            if covered.insert(longhand) {
+              effective = true;
+            } else {
+              overridden = true;
+            }
+          }
+
+          ordered |= effective && overridden;
+          (
+            effective,
+            longhands
+              .iter()
+              .any(|longhand| self.importance.longhands.contains(&longhand)),
+          )
+        }
+      };
+
+      if !effective {
+        continue;
+      }
+
+      let mut hasher = Xxh3::new();
+      match declaration {
+        StyleDeclaration::CustomProperty(name, value) => {
+          std::mem::discriminant(declaration).hash(&mut hasher);
+          name.hash(&mut hasher);
+          hash_collapsed_whitespace(value, &mut hasher);
+        }
+        StyleDeclaration::Deferred(deferred) => {
+          std::mem::discriminant(declaration).hash(&mut hasher);
+          deferred.property.hash(&mut hasher);
+          hash_collapsed_whitespace(&deferred.raw_value, &mut hasher);
+        }
+        _ => declaration.hash(&mut hasher),
+      }
+      important.hash(&mut hasher);
+      digests.push(hasher.finish());
+    }
+
+    if ordered {
+      digests.reverse();
+    } else {
+      digests.sort_unstable();
+    }
+
+    digests.hash(state);
   }
 
   /// Consumes the declaration block and returns an iterator over the declarations.
@@ -1987,6 +2382,8 @@ impl FromStr for StyleDeclarationBlock {
 #[derive(Clone)]
 pub(crate) struct SizedFontStyle<'s> {
   pub parent: &'s ComputedStyle,
+  /// `font-family` with the registered fallback chains expanded.
+  pub font_family: FontFamily,
   pub line_height: parley::LineHeight,
   pub stroke_width: f32,
   pub outline_width: f32,
@@ -2013,8 +2410,8 @@ impl<'s> From<&'s SizedFontStyle<'s>> for TextStyle<'s, InlineBrush> {
       font_variations: FontSettings::List(Cow::Borrowed(
         style.parent.font_variation_settings.as_ref(),
       )),
-      font_features: FontSettings::List(Cow::Borrowed(style.parent.font_feature_settings.as_ref())),
-      font_stack: (&style.parent.font_family).into(),
+      font_features: FontSettings::List(style.parent.resolved_font_features()),
+      font_stack: (&style.font_family).into(),
       letter_spacing: style.letter_spacing,
       word_spacing: style.word_spacing,
       word_break: style.parent.word_break.into(),
@@ -2031,7 +2428,9 @@ impl<'s> From<&'s SizedFontStyle<'s>> for TextStyle<'s, InlineBrush> {
         decoration_color: style.text_decoration_color,
         decoration_thickness: style.text_decoration_thickness,
         decoration_line: style.parent.text_decoration_line.unwrap_or_default(),
+        decoration_style: style.parent.text_decoration_style,
         decoration_skip_ink: style.parent.text_decoration_skip_ink,
+        stroke_width: style.stroke_width,
         stroke_color: style.text_stroke_color,
         font_synthesis: FontSynthesis {
           weight: style.parent.font_synthesis_weight,
@@ -2060,7 +2459,7 @@ impl ComputedStyle {
   pub(crate) fn make_computed(&mut self, sizing: &Sizing) {
     // `font-size` computed value is already resolved in `sizing.font_size`.
     // Keep it as css-px in style to avoid re-resolving descendant inheritance.
-    let dpr = sizing.viewport.device_pixel_ratio;
+    let dpr = sizing.pixel_ratio();
     self.font_size = if dpr > 0.0 {
       FontSize::Length(Length::Px(sizing.font_size / dpr))
     } else {
@@ -2190,24 +2589,65 @@ impl ComputedStyle {
     )
   }
 
+  /// Combines `font-kerning`, `font-variant-ligatures` and `font-variant-caps` with
+  /// `font-feature-settings`, which is applied last so it takes precedence.
+  fn resolved_font_features(&self) -> Cow<'_, [FontFeature]> {
+    let kerning = match self.font_kerning {
+      FontKerning::Auto => None,
+      FontKerning::Normal => Some(1),
+      FontKerning::None => Some(0),
+    };
+
+    if kerning.is_none()
+      && self.font_variant_ligatures == FontVariantLigatures::default()
+      && self.font_variant_caps == FontVariantCaps::Normal
+    {
+      return Cow::Borrowed(self.font_feature_settings.as_ref());
+    }
+
+    Cow::Owned(
+      kerning
+        .map(|value| FontFeature {
+          tag: tag_from_bytes(b"kern"),
+          value,
+        })
+        .into_iter()
+        .chain(self.font_variant_ligatures.features())
+        .chain(self.font_variant_caps.features())
+        .chain(self.font_feature_settings.iter().copied())
+        .collect(),
+    )
+  }
+
   #[inline]
   fn resolved_text_shadows(&self, context: &RenderContext) -> SmallVec<[SizedShadow; 4]> {
+    // Shadows listed first are drawn on top, so the emboss shadows go last.
+    let emboss = self.text_emboss.shadows(context.current_color);
+
     self
       .text_shadow
-      .as_ref()
-      .map_or_else(SmallVec::new, |shadows| {
-        shadows
-          .iter()
-          .map(|shadow| {
-            SizedShadow::from_text_shadow(
-              *shadow,
-              &context.sizing,
-              context.current_color,
-              Size::from_length(context.sizing.font_size),
-            )
-          })
-          .collect()
+      .iter()
+      .flatten()
+      .chain(emboss.iter().flatten())
+      .map(|shadow| {
+        SizedShadow::from_text_shadow(
+          *shadow,
+          &context.sizing,
+          context.current_color,
+          Size::from_length(context.sizing.font_size),
+        )
       })
+      .collect()
+  }
+
+  /// Returns the specified border widths, regardless of `border-style`.
+  pub(crate) fn border_widths(&self) -> Rect<Length> {
+    Rect {
+      top: self.border_top_width,
+      right: self.border_right_width,
+      bottom: self.border_bottom_width,
+      left: self.border_left_width,
+    }
   }
 
   #[inline]
@@ -2225,15 +2665,18 @@ impl ComputedStyle {
   pub(crate) fn to_sized_font_style(&'_ self, context: &RenderContext) -> SizedFontStyle<'_> {
     let line_height = self.line_height.into_parley(&context.sizing);
 
-    SizedFontStyle {
+    let mut style = SizedFontStyle {
       sizing: context.sizing.to_owned(),
       parent: self,
+      font_family: self
+        .font_family
+        .with_fallbacks(|family| context.global.font_context().fallbacks(family)),
       line_height,
       stroke_width: self
         .webkit_text_stroke_width
         .unwrap_or_default()
         .to_px(&context.sizing, context.sizing.font_size),
-      outline_width: self.outline_width.to_px(&context.sizing, 0.0).max(0.0),
+      outline_width: snap_border_width(self.outline_width.to_px(&context.sizing, 0.0)),
       outline_offset: self.outline_offset.to_px(&context.sizing, 0.0),
       letter_spacing: self
         .letter_spacing
@@ -2254,7 +2697,16 @@ impl ComputedStyle {
         .resolve(context.current_color),
       text_decoration_color: self.text_decoration_color.resolve(context.current_color),
       text_decoration_thickness: self.resolved_text_decoration_thickness(&context.sizing),
+    };
+
+    let line_height_step = self
+      .line_height_step
+      .to_px(&context.sizing, context.sizing.font_size);
+    if line_height_step > 0.0 {
+      style.line_height = snap_line_height(&style, line_height_step, context);
     }
+
+    style
   }
 
   pub(crate) fn to_taffy_style(&self, sizing: &Sizing) -> taffy::Style {
@@ -2274,13 +2726,12 @@ impl ComputedStyle {
       border: if self.border_style == BorderStyle::None {
         Rect::zero()
       } else {
-        Rect {
-          top: self.border_top_width,
-          right: self.border_right_width,
-          bottom: self.border_bottom_width,
-          left: self.border_left_width,
-        }
-        .map(|border| border.resolve_to_length_percentage(sizing))
+        self
+          .border_widths()
+          .map(|border| match self.border_alignment {
+            BorderAlignment::Inside => border.resolve_to_border_width(sizing),
+            alignment => border.resolve_to_inside_border_width(sizing, alignment.inside_fraction()),
+          })
       },
       padding: Rect {
         top: self.padding_top,
@@ -2390,7 +2841,7 @@ mod tests {
   };
   use crate::{
     layout::{
-      Viewport,
+      ColorScheme, Viewport,
       style::{ComputedStyle, Style, StyleDeclaration, properties::*},
     },
     rendering::Sizing,
@@ -2456,6 +2907,31 @@ mod tests {
     assert_eq!(resolved.color, ColorInput::Value(Color([255, 0, 0, 255])));
   }
 
+  #[test]
+  fn test_color_scheme_blocks_apply_for_their_scheme() {
+    let Ok(style) = serde_json::from_value::<Style>(serde_json::json!({
+      "width": 10,
+      "color": "black",
+      "dark": { "color": "white" },
+      "light": { "width": 20 },
+    })) else {
+      unreachable!()
+    };
+
+    let dark = style
+      .clone()
+      .resolve_color_scheme(ColorScheme::Dark)
+      .inherit(&ComputedStyle::default());
+    assert_eq!(dark.width, Length::Px(10.0));
+    assert_eq!(dark.color, ColorInput::Value(Color([255, 255, 255, 255])));
+
+    let light = style
+      .resolve_color_scheme(ColorScheme::Light)
+      .inherit(&ComputedStyle::default());
+    assert_eq!(light.width, Length::Px(20.0));
+    assert_eq!(light.color, ColorInput::Value(Color([0, 0, 0, 255])));
+  }
+
   #[test]
   fn property_id_accepts_kebab_and_camel_case() {
     let padding_left_kebab = PropertyId::from_kebab_case("padding-left");
@@ -2626,6 +3102,31 @@ mod tests {
     );
   }
 
+  #[test]
+  fn style_declaration_block_collect_style_fetch_tasks_collects_palette_sources() {
+    let declarations = StyleDeclarationBlock::from_str(
+      "--accent: palette(url(cover.png), 2); background-color: palette(\"avatar.png\"); color: var(--accent)",
+    );
+    assert!(declarations.is_ok());
+    let Ok(declarations) = declarations else {
+      unreachable!()
+    };
+    let mut collection = FetchTaskCollection::default();
+
+    declarations.collect_fetch_tasks(&mut collection);
+
+    let tasks = collection
+      .into_inner()
+      .iter()
+      .map(ToString::to_string)
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      tasks,
+      vec!["cover.png".to_string(), "avatar.png".to_string()]
+    );
+  }
+
   #[test]
   fn style_declaration_block_into_iter_yields_owned_values() {
     let declarations = parse_declarations("padding", "1px 2px");
@@ -2675,7 +3176,7 @@ mod tests {
       StyleDeclaration::background_repeat([BackgroundRepeat::default()].into()),
       StyleDeclaration::background_blend_mode([BlendMode::default()].into()),
       StyleDeclaration::background_color(ColorInput::default()),
-      StyleDeclaration::background_clip(BackgroundClip::default()),
+      StyleDeclaration::background_clip([BackgroundClip::default()].into()),
     ]);
 
     preset_style.merge_from(inline_style);
@@ -2705,6 +3206,7 @@ mod tests {
       viewport: Viewport::new((1200, 630)),
       container_size: Size::NONE,
       font_size: 16.0,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     };
     let border_box = Size {
@@ -2753,6 +3255,7 @@ mod tests {
       viewport: Viewport::new((1200, 630)),
       container_size: Size::NONE,
       font_size: 16.0,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     };
 
@@ -2765,7 +3268,7 @@ mod tests {
   fn test_inherited_em_text_lengths_are_computed_once() {
     let mut parent = style_with([
       StyleDeclaration::font_size(Length::Em(2.0).into()),
-      StyleDeclaration::letter_spacing(Length::Em(1.0)),
+      StyleDeclaration::letter_spacing(Length::Em(1.0).into()),
       StyleDeclaration::line_height(LineHeight::Length(Length::Em(1.5))),
     ])
     .inherit(&ComputedStyle::default());
@@ -2773,6 +3276,7 @@ mod tests {
       viewport: Viewport::new((1200, 630)),
       container_size: Size::NONE,
       font_size: 32.0,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     });
 
@@ -2781,6 +3285,7 @@ mod tests {
       viewport: Viewport::new((1200, 630)),
       container_size: Size::NONE,
       font_size: 32.0,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     };
     let inherited_font_size = inherited_child
@@ -2794,6 +3299,7 @@ mod tests {
       viewport: Viewport::new((1200, 630)),
       container_size: Size::NONE,
       font_size: 10.0,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     };
 
@@ -2978,6 +3484,7 @@ mod tests {
       viewport: Viewport::new((1200, 630)),
       container_size: Size::NONE,
       font_size: 16.0,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     };
     let radius = style.border_top_left_radius.x.to_px(
@@ -2992,4 +3499,19 @@ mod tests {
       style.border_top_left_radius.x
     );
   }
+
+  #[test]
+  fn test_border_radius_shorthand_parses_elliptical_corners() {
+    let style = inherited_style_from_pairs(
+      [("border-radius", "10px 20px / 5px 15px")],
+      &ComputedStyle::default(),
+    );
+
+    let corner = |x: f32, y: f32| SpacePair::from_pair(Length::Px(x), Length::Px(y));
+
+    assert_eq!(style.border_top_left_radius, corner(10.0, 5.0));
+    assert_eq!(style.border_top_right_radius, corner(20.0, 15.0));
+    assert_eq!(style.border_bottom_right_radius, corner(10.0, 5.0));
+    assert_eq!(style.border_bottom_left_radius, corner(20.0, 15.0));
+  }
 }
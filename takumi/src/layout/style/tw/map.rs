@@ -24,6 +24,7 @@ pub enum PropertyParser {
   BorderWidth(fn(TwBorderWidth) -> TailwindProperty),
   BorderStyle(fn(BorderStyle) -> TailwindProperty),
   Rounded(fn(TwRounded) -> TailwindProperty),
+  BorderRadius(fn(TwBorderRadius) -> TailwindProperty),
   GridTemplate(fn(TwGridTemplate) -> TailwindProperty),
   GridAuto(fn(GridTrackSize) -> TailwindProperty),
   GridLine(fn(GridLine) -> TailwindProperty),
@@ -36,6 +37,7 @@ pub enum PropertyParser {
   TextWrap(fn(TextWrap) -> TailwindProperty),
   ColorCurrent(fn(ColorInput) -> TailwindProperty),
   ColorTransparent(fn(ColorInput<false>) -> TailwindProperty),
+  AccentColor(fn(TwAccentColor) -> TailwindProperty),
   Percentage(fn(PercentageNumber) -> TailwindProperty),
   FontFamily(fn(FontFamily) -> TailwindProperty),
   LineClamp(fn(LineClamp) -> TailwindProperty),
@@ -46,6 +48,7 @@ pub enum PropertyParser {
   Flex(fn(Flex) -> TailwindProperty),
   Angle(fn(Angle) -> TailwindProperty),
   BackgroundClip(fn(BackgroundClip) -> TailwindProperty),
+  BackgroundOrigin(fn(BackgroundOrigin) -> TailwindProperty),
   Blur(fn(TwBlur) -> TailwindProperty),
   Filter(fn(Filters) -> TailwindProperty),
   BoxShadow(fn(BoxShadow) -> TailwindProperty),
@@ -62,6 +65,7 @@ impl PropertyParser {
   pub fn parse(&self, suffix: &str) -> Option<TailwindProperty> {
     match self {
       Self::BackgroundClip(f) => BackgroundClip::parse_tw_with_arbitrary(suffix).map(f),
+      Self::BackgroundOrigin(f) => BackgroundOrigin::parse_tw_with_arbitrary(suffix).map(f),
       Self::ObjectFit(f) => ObjectFit::parse_tw_with_arbitrary(suffix).map(f),
       Self::ObjectPosition(f) => ObjectPosition::parse_tw_with_arbitrary(suffix).map(f),
       Self::BgPosition(f) => BackgroundPosition::parse_tw_with_arbitrary(suffix).map(f),
@@ -77,6 +81,7 @@ impl PropertyParser {
       Self::BorderWidth(f) => TwBorderWidth::parse_tw_with_arbitrary(suffix).map(f),
       Self::BorderStyle(f) => BorderStyle::parse_tw_with_arbitrary(suffix).map(f),
       Self::Rounded(f) => TwRounded::parse_tw_with_arbitrary(suffix).map(f),
+      Self::BorderRadius(f) => TwBorderRadius::parse_tw_with_arbitrary(suffix).map(f),
       Self::GridTemplate(f) => TwGridTemplate::parse_tw_with_arbitrary(suffix).map(f),
       Self::GridAuto(f) => GridTrackSize::parse_tw_with_arbitrary(suffix).map(f),
       Self::GridPlacement(f) => GridPlacement::parse_tw_with_arbitrary(suffix).map(f),
@@ -89,6 +94,7 @@ impl PropertyParser {
       Self::TextWrap(f) => TextWrap::parse_tw_with_arbitrary(suffix).map(f),
       Self::ColorCurrent(f) => ColorInput::parse_tw_with_arbitrary(suffix).map(f),
       Self::ColorTransparent(f) => ColorInput::parse_tw_with_arbitrary(suffix).map(f),
+      Self::AccentColor(f) => TwAccentColor::parse(suffix).map(f),
       Self::Percentage(f) => PercentageNumber::parse_tw_with_arbitrary(suffix).map(f),
       Self::FontFamily(f) => FontFamily::parse_tw_with_arbitrary(suffix).map(f),
       Self::LineClamp(f) => LineClamp::parse_tw_with_arbitrary(suffix).map(f),
@@ -124,8 +130,10 @@ pub static PREFIX_PARSERS: phf::Map<&str, &[PropertyParser]> = phf_map! {
     PropertyParser::BgImage(TailwindProperty::BackgroundImage),
     PropertyParser::BgPosition(TailwindProperty::BackgroundPosition),
     PropertyParser::BgSize(TailwindProperty::BackgroundSize),
+    PropertyParser::AccentColor(TailwindProperty::BackgroundAccentColor),
   ],
   "bg-clip" => &[PropertyParser::BackgroundClip(TailwindProperty::BackgroundClip)],
+  "bg-origin" => &[PropertyParser::BackgroundOrigin(TailwindProperty::BackgroundOrigin)],
   "bg-linear" => &[PropertyParser::Angle(TailwindProperty::BgLinearAngle)],
   "bg-conic" => &[PropertyParser::Angle(TailwindProperty::BgConicAngle)],
   "from" => &[PropertyParser::ColorCurrent(TailwindProperty::GradientFrom)],
@@ -161,6 +169,7 @@ pub static PREFIX_PARSERS: phf::Map<&str, &[PropertyParser]> = phf_map! {
     PropertyParser::ColorCurrent(TailwindProperty::BorderColor),
     PropertyParser::BorderStyle(TailwindProperty::BorderStyle),
     PropertyParser::BorderWidth(TailwindProperty::BorderWidth),
+    PropertyParser::AccentColor(TailwindProperty::BorderAccentColor),
   ],
   "border-t" => &[PropertyParser::BorderWidth(TailwindProperty::BorderTopWidth)],
   "border-r" => &[PropertyParser::BorderWidth(TailwindProperty::BorderRightWidth)],
@@ -172,6 +181,7 @@ pub static PREFIX_PARSERS: phf::Map<&str, &[PropertyParser]> = phf_map! {
     PropertyParser::ColorCurrent(TailwindProperty::OutlineColor),
     PropertyParser::BorderStyle(TailwindProperty::OutlineStyle),
     PropertyParser::BorderWidth(TailwindProperty::OutlineWidth),
+    PropertyParser::AccentColor(TailwindProperty::OutlineAccentColor),
   ],
   "shadow" => &[
     PropertyParser::ColorCurrent(TailwindProperty::ShadowColor),
@@ -187,10 +197,12 @@ pub static PREFIX_PARSERS: phf::Map<&str, &[PropertyParser]> = phf_map! {
     PropertyParser::ColorCurrent(TailwindProperty::Color),
     PropertyParser::TextAlign(TailwindProperty::TextAlign),
     PropertyParser::TextWrap(TailwindProperty::TextWrap),
+    PropertyParser::AccentColor(TailwindProperty::TextAccentColor),
   ],
   "decoration" => &[
     PropertyParser::ColorCurrent(TailwindProperty::TextDecorationColor),
     PropertyParser::DecorationThickness(TailwindProperty::TextDecorationThickness),
+    PropertyParser::AccentColor(TailwindProperty::DecorationAccentColor),
   ],
   "leading" => &[PropertyParser::LineHeight(TailwindProperty::LineHeight)],
   "opacity" => &[PropertyParser::Percentage(TailwindProperty::Opacity)],
@@ -227,7 +239,7 @@ pub static PREFIX_PARSERS: phf::Map<&str, &[PropertyParser]> = phf_map! {
   "right" => &[PropertyParser::LengthAuto(TailwindProperty::Right)],
   "bottom" => &[PropertyParser::LengthAuto(TailwindProperty::Bottom)],
   "left" => &[PropertyParser::LengthAuto(TailwindProperty::Left)],
-  "rounded" => &[PropertyParser::Rounded(TailwindProperty::Rounded)],
+  "rounded" => &[PropertyParser::BorderRadius(TailwindProperty::Rounded)],
   "rounded-t" => &[PropertyParser::Rounded(TailwindProperty::RoundedTop)],
   "rounded-r" => &[PropertyParser::Rounded(TailwindProperty::RoundedRight)],
   "rounded-b" => &[PropertyParser::Rounded(TailwindProperty::RoundedBottom)],
@@ -288,11 +300,15 @@ pub static FIXED_PROPERTIES: phf::Map<&str, TailwindProperty> = phf_map! {
   "inline-block" => TailwindProperty::Display(Display::InlineBlock),
   "inline-flex" => TailwindProperty::Display(Display::InlineFlex),
   "bg-radial" => TailwindProperty::BgRadial,
+  "bg-conic" => TailwindProperty::BgConicAngle(Angle::zero()),
   "inline-grid" => TailwindProperty::Display(Display::InlineGrid),
   "block" => TailwindProperty::Display(Display::Block),
   "flex" => TailwindProperty::Display(Display::Flex),
   "grid" => TailwindProperty::Display(Display::Grid),
   "hidden" => TailwindProperty::Display(Display::None),
+  "bg-fixed" => TailwindProperty::BackgroundAttachment(BackgroundAttachment::Fixed),
+  "bg-local" => TailwindProperty::BackgroundAttachment(BackgroundAttachment::Local),
+  "bg-scroll" => TailwindProperty::BackgroundAttachment(BackgroundAttachment::Scroll),
   "bg-repeat" => TailwindProperty::BackgroundRepeat(BackgroundRepeat::repeat()),
   "bg-no-repeat" => TailwindProperty::BackgroundRepeat(BackgroundRepeat::no_repeat()),
   "bg-space" => TailwindProperty::BackgroundRepeat(BackgroundRepeat::space()),
@@ -330,6 +346,11 @@ pub static FIXED_PROPERTIES: phf::Map<&str, TailwindProperty> = phf_map! {
   "overline" => TailwindProperty::TextDecorationLine(TextDecorationLines::OVERLINE),
   "line-through" => TailwindProperty::TextDecorationLine(TextDecorationLines::LINE_THROUGH),
   "no-underline" => TailwindProperty::TextDecorationLine(TextDecorationLines::empty()),
+  "decoration-solid" => TailwindProperty::TextDecorationStyle(TextDecorationStyle::Solid),
+  "decoration-double" => TailwindProperty::TextDecorationStyle(TextDecorationStyle::Double),
+  "decoration-dotted" => TailwindProperty::TextDecorationStyle(TextDecorationStyle::Dotted),
+  "decoration-dashed" => TailwindProperty::TextDecorationStyle(TextDecorationStyle::Dashed),
+  "decoration-wavy" => TailwindProperty::TextDecorationStyle(TextDecorationStyle::Wavy),
   "italic" => TailwindProperty::FontStyle(FontStyle::italic()),
   "not-italic" => TailwindProperty::FontStyle(FontStyle::normal()),
   "w-screen" => TailwindProperty::Width(Length::Vw(100.0)),
@@ -344,6 +365,7 @@ pub static FIXED_PROPERTIES: phf::Map<&str, TailwindProperty> = phf_map! {
   "break-normal" => TailwindProperty::WordBreak(WordBreak::Normal),
   "break-all" => TailwindProperty::WordBreak(WordBreak::BreakAll),
   "break-keep" => TailwindProperty::WordBreak(WordBreak::KeepAll),
+  "break-url" => TailwindProperty::WordBreak(WordBreak::BreakUrl),
   "grid-flow-row" => TailwindProperty::GridAutoFlow(GridAutoFlow::row()),
   "grid-flow-col" => TailwindProperty::GridAutoFlow(GridAutoFlow::column()),
   "grid-flow-row-dense" | "grid-flow-dense" => TailwindProperty::GridAutoFlow(GridAutoFlow::row().dense()),
@@ -421,48 +443,56 @@ pub static FIXED_PROPERTIES: phf::Map<&str, TailwindProperty> = phf_map! {
     offset_y: Length::Px(1.0),
     blur_radius: Length::Px(1.0),
     color: ColorInput::Value(Color([0, 0, 0, 13])),
+    inset: false,
   }),
   "drop-shadow-sm" => TailwindProperty::DropShadow(TextShadow {
     offset_x: Length::Px(0.0),
     offset_y: Length::Px(1.0),
     blur_radius: Length::Px(2.0),
     color: ColorInput::Value(Color([0, 0, 0, 38])),
+    inset: false,
   }),
   "drop-shadow" => TailwindProperty::DropShadow(TextShadow {
     offset_x: Length::Px(0.0),
     offset_y: Length::Px(1.0),
     blur_radius: Length::Px(2.0),
     color: ColorInput::Value(Color([0, 0, 0, 26])),
+    inset: false,
   }),
   "drop-shadow-md" => TailwindProperty::DropShadow(TextShadow {
     offset_x: Length::Px(0.0),
     offset_y: Length::Px(3.0),
     blur_radius: Length::Px(3.0),
     color: ColorInput::Value(Color([0, 0, 0, 31])),
+    inset: false,
   }),
   "drop-shadow-lg" => TailwindProperty::DropShadow(TextShadow {
     offset_x: Length::Px(0.0),
     offset_y: Length::Px(4.0),
     blur_radius: Length::Px(4.0),
     color: ColorInput::Value(Color([0, 0, 0, 38])),
+    inset: false,
   }),
   "drop-shadow-xl" => TailwindProperty::DropShadow(TextShadow {
     offset_x: Length::Px(0.0),
     offset_y: Length::Px(9.0),
     blur_radius: Length::Px(7.0),
     color: ColorInput::Value(Color([0, 0, 0, 26])),
+    inset: false,
   }),
   "drop-shadow-2xl" => TailwindProperty::DropShadow(TextShadow {
     offset_x: Length::Px(0.0),
     offset_y: Length::Px(25.0),
     blur_radius: Length::Px(25.0),
     color: ColorInput::Value(Color([0, 0, 0, 38])),
+    inset: false,
   }),
   "drop-shadow-none" => TailwindProperty::DropShadow(TextShadow {
     offset_x: Length::Px(0.0),
     offset_y: Length::Px(0.0),
     blur_radius: Length::Px(0.0),
     color: ColorInput::Value(Color([0, 0, 0, 0])),
+    inset: false,
   }),
   // Inset shadows (--inset-shadow-*)
   "inset-shadow-2xs" => TailwindProperty::Shadow(BoxShadow {
@@ -495,30 +525,35 @@ pub static FIXED_PROPERTIES: phf::Map<&str, TailwindProperty> = phf_map! {
     offset_y: Length::Px(1.0),
     blur_radius: Length::Px(0.0),
     color: ColorInput::Value(Color([0, 0, 0, 38])),
+    inset: false,
   }),
   "text-shadow-xs" => TailwindProperty::TextShadow(TextShadow {
     offset_x: Length::Px(0.0),
     offset_y: Length::Px(1.0),
     blur_radius: Length::Px(1.0),
     color: ColorInput::Value(Color([0, 0, 0, 51])),
+    inset: false,
   }),
   "text-shadow-sm" => TailwindProperty::TextShadow(TextShadow {
     offset_x: Length::Px(0.0),
     offset_y: Length::Px(1.0),
     blur_radius: Length::Px(2.0),
     color: ColorInput::Value(Color([0, 0, 0, 19])),
+    inset: false,
   }),
   "text-shadow-md" => TailwindProperty::TextShadow(TextShadow {
     offset_x: Length::Px(0.0),
     offset_y: Length::Px(1.0),
     blur_radius: Length::Px(4.0),
     color: ColorInput::Value(Color([0, 0, 0, 26])),
+    inset: false,
   }),
   "text-shadow-lg" => TailwindProperty::TextShadow(TextShadow {
     offset_x: Length::Px(0.0),
     offset_y: Length::Px(1.0),
     blur_radius: Length::Px(8.0),
     color: ColorInput::Value(Color([0, 0, 0, 26])),
+    inset: false,
   }),
   "isolate" => TailwindProperty::Isolation(Isolation::Isolate),
   "isolation-auto" => TailwindProperty::Isolation(Isolation::Auto),
@@ -3,7 +3,7 @@ pub(crate) mod parser;
 
 use std::{borrow::Cow, cmp::Ordering, ops::Neg, str::FromStr};
 
-use cssparser::match_ignore_ascii_case;
+use cssparser::{Parser, ParserInput, match_ignore_ascii_case};
 use serde::{Deserializer, de::Error as DeError};
 
 use crate::{
@@ -24,20 +24,26 @@ use crate::{
 pub const TW_VAR_SPACING: f32 = 0.25;
 
 /// Represents a collection of tailwind properties.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct TailwindValues {
   inner: Vec<TailwindValue>,
+  unsupported: Vec<Box<str>>,
 }
 
 impl FromStr for TailwindValues {
   type Err = String;
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
-    let mut collected = s
-      .split_whitespace()
-      .filter_map(TailwindValue::parse)
-      .collect::<Vec<_>>();
+    let mut collected = Vec::new();
+    let mut unsupported = Vec::new();
+
+    for token in s.split_whitespace() {
+      match TailwindValue::parse(token) {
+        Some(value) => collected.push(value),
+        None => unsupported.push(token.into()),
+      }
+    }
 
     // sort in reverse order by is important, then has breakpoint, then rest is last.
     collected.sort_unstable_by(|a, b| {
@@ -58,7 +64,10 @@ impl FromStr for TailwindValues {
       }
     });
 
-    Ok(TailwindValues { inner: collected })
+    Ok(TailwindValues {
+      inner: collected,
+      unsupported,
+    })
   }
 }
 
@@ -68,6 +77,13 @@ impl TailwindValues {
     self.inner.iter()
   }
 
+  /// Returns the class tokens that could not be parsed as Tailwind utilities.
+  ///
+  /// These tokens are ignored during rendering.
+  pub fn unsupported(&self) -> &[Box<str>] {
+    &self.unsupported
+  }
+
   /// Collects fetch tasks referenced by active Tailwind utilities for the given viewport.
   pub fn collect_fetch_tasks(&self, viewport: Viewport, collection: &mut FetchTaskCollection) {
     for value in &self.inner {
@@ -148,6 +164,13 @@ impl TailwindDeclarationBuilder {
     }
   }
 
+  fn push_accent_color(&mut self, property: LonghandId, accent: TwAccentColor, important: bool) {
+    self.push(
+      StyleDeclaration::deferred(property, accent.to_css()),
+      important,
+    );
+  }
+
   fn push_filter(&mut self, filter: Filter, important: bool) {
     self.filter.get_or_insert_with(Vec::new).push(filter);
     self.filter_important = important;
@@ -386,7 +409,7 @@ impl<'de> Deserialize<'de> for TailwindValues {
 }
 
 /// Represents a tailwind value.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct TailwindValue {
   /// The tailwind property.
@@ -450,7 +473,7 @@ impl TailwindValue {
 }
 
 /// Represents a breakpoint.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct Breakpoint(pub(crate) Length);
 
@@ -485,11 +508,15 @@ impl Breakpoint {
 }
 
 /// Represents a tailwind property.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum TailwindProperty {
   /// `background-clip` property.
   BackgroundClip(BackgroundClip),
+  /// `background-origin` property.
+  BackgroundOrigin(BackgroundOrigin),
+  /// `background-attachment` property.
+  BackgroundAttachment(BackgroundAttachment),
   /// `box-sizing` property.
   BoxSizing(BoxSizing),
   /// `flex-grow` property.
@@ -552,6 +579,8 @@ pub enum TailwindProperty {
   TextAlign(TextAlign),
   /// `text-decoration` property.
   TextDecorationLine(TextDecorationLines),
+  /// `text-decoration-style` property.
+  TextDecorationStyle(TextDecorationStyle),
   /// `text-decoration-color` property.
   TextDecorationColor(ColorInput),
   /// `text-decoration-thickness` property.
@@ -661,7 +690,7 @@ pub enum TailwindProperty {
   /// `outline-offset` property.
   OutlineOffset(TwBorderWidth),
   /// `border-radius` property.
-  Rounded(TwRounded),
+  Rounded(TwBorderRadius),
   /// `border-top-left-radius` property.
   RoundedTopLeft(TwRounded),
   /// `border-top-right-radius` property.
@@ -808,6 +837,16 @@ pub enum TailwindProperty {
   GradientTo(ColorInput),
   /// `via` property.
   GradientVia(ColorInput),
+  /// `background-color` property from the `accent` theme color.
+  BackgroundAccentColor(TwAccentColor),
+  /// `color` property from the `accent` theme color.
+  TextAccentColor(TwAccentColor),
+  /// `border-color` property from the `accent` theme color.
+  BorderAccentColor(TwAccentColor),
+  /// `outline-color` property from the `accent` theme color.
+  OutlineAccentColor(TwAccentColor),
+  /// `text-decoration-color` property from the `accent` theme color.
+  DecorationAccentColor(TwAccentColor),
 }
 
 fn extract_arbitrary_value(suffix: &str) -> Option<Cow<'_, str>> {
@@ -823,6 +862,17 @@ fn extract_arbitrary_value(suffix: &str) -> Option<Cow<'_, str>> {
   }
 }
 
+/// Parses an arbitrary value, rejecting values with leftover tokens.
+///
+/// Returns `None` if the token is not an arbitrary value.
+pub(crate) fn parse_arbitrary_entirely<T: for<'i> FromCss<'i>>(token: &str) -> Option<Option<T>> {
+  let value = extract_arbitrary_value(token)?;
+  let mut input = ParserInput::new(&value);
+  let mut parser = Parser::new(&mut input);
+
+  Some(parser.parse_entirely(T::from_css).ok())
+}
+
 /// A trait for parsing tailwind properties.
 pub trait TailwindPropertyParser: Sized + for<'i> FromCss<'i> {
   /// Parse a tailwind property from a token.
@@ -964,7 +1014,25 @@ impl TailwindProperty {
         builder.gradient_state.important = important;
       }
       TailwindProperty::BackgroundClip(background_clip) => {
-        push_decl!(builder, important, background_clip(background_clip));
+        push_decl!(
+          builder,
+          important,
+          background_clip([background_clip].into())
+        );
+      }
+      TailwindProperty::BackgroundOrigin(background_origin) => {
+        push_decl!(
+          builder,
+          important,
+          background_origin([background_origin].into())
+        );
+      }
+      TailwindProperty::BackgroundAttachment(background_attachment) => {
+        push_decl!(
+          builder,
+          important,
+          background_attachment([background_attachment].into())
+        );
       }
       TailwindProperty::Gap(gap) => {
         push_decl!(builder, important, row_gap(gap));
@@ -1034,6 +1102,9 @@ impl TailwindProperty {
         important,
         text_decoration_line(Some(text_decoration))
       ),
+      TailwindProperty::TextDecorationStyle(style) => {
+        push_decl!(builder, important, text_decoration_style(style))
+      }
       TailwindProperty::TextDecorationColor(color_input) => {
         push_decl!(builder, important, text_decoration_color(color_input))
       }
@@ -1120,6 +1191,21 @@ impl TailwindProperty {
       TailwindProperty::BorderColor(color_input) => {
         push_decl!(builder, important, border_color(color_input))
       }
+      TailwindProperty::BackgroundAccentColor(accent) => {
+        builder.push_accent_color(LonghandId::BackgroundColor, accent, important)
+      }
+      TailwindProperty::TextAccentColor(accent) => {
+        builder.push_accent_color(LonghandId::Color, accent, important)
+      }
+      TailwindProperty::BorderAccentColor(accent) => {
+        builder.push_accent_color(LonghandId::BorderColor, accent, important)
+      }
+      TailwindProperty::OutlineAccentColor(accent) => {
+        builder.push_accent_color(LonghandId::OutlineColor, accent, important)
+      }
+      TailwindProperty::DecorationAccentColor(accent) => {
+        builder.push_accent_color(LonghandId::TextDecorationColor, accent, important)
+      }
       TailwindProperty::BorderTopWidth(tw_border_width) => {
         push_decl!(builder, important, border_top_width(tw_border_width.0))
       }
@@ -1157,97 +1243,43 @@ impl TailwindProperty {
         push_decl!(builder, important, outline_offset(outline_offset.0))
       }
       TailwindProperty::Rounded(rounded) => {
-        push_decl!(
-          builder,
-          important,
-          border_top_left_radius(SpacePair::from_single(rounded.0))
-        );
-        push_decl!(
-          builder,
-          important,
-          border_top_right_radius(SpacePair::from_single(rounded.0))
-        );
-        push_decl!(
-          builder,
-          important,
-          border_bottom_right_radius(SpacePair::from_single(rounded.0))
-        );
-        push_decl!(
-          builder,
-          important,
-          border_bottom_left_radius(SpacePair::from_single(rounded.0))
-        );
+        let Sides([top_left, top_right, bottom_right, bottom_left]) = rounded.0.0;
+
+        push_decl!(builder, important, border_top_left_radius(top_left));
+        push_decl!(builder, important, border_top_right_radius(top_right));
+        push_decl!(builder, important, border_bottom_right_radius(bottom_right));
+        push_decl!(builder, important, border_bottom_left_radius(bottom_left));
       }
       TailwindProperty::VerticalAlign(vertical_align) => {
         push_decl!(builder, important, vertical_align(vertical_align))
       }
-      TailwindProperty::RoundedTopLeft(rounded) => push_decl!(
-        builder,
-        important,
-        border_top_left_radius(SpacePair::from_single(rounded.0))
-      ),
-      TailwindProperty::RoundedTopRight(rounded) => push_decl!(
-        builder,
-        important,
-        border_top_right_radius(SpacePair::from_single(rounded.0))
-      ),
-      TailwindProperty::RoundedBottomRight(rounded) => push_decl!(
-        builder,
-        important,
-        border_bottom_right_radius(SpacePair::from_single(rounded.0))
-      ),
-      TailwindProperty::RoundedBottomLeft(rounded) => push_decl!(
-        builder,
-        important,
-        border_bottom_left_radius(SpacePair::from_single(rounded.0))
-      ),
+      TailwindProperty::RoundedTopLeft(rounded) => {
+        push_decl!(builder, important, border_top_left_radius(rounded.0))
+      }
+      TailwindProperty::RoundedTopRight(rounded) => {
+        push_decl!(builder, important, border_top_right_radius(rounded.0))
+      }
+      TailwindProperty::RoundedBottomRight(rounded) => {
+        push_decl!(builder, important, border_bottom_right_radius(rounded.0))
+      }
+      TailwindProperty::RoundedBottomLeft(rounded) => {
+        push_decl!(builder, important, border_bottom_left_radius(rounded.0))
+      }
       TailwindProperty::RoundedTop(rounded) => {
-        push_decl!(
-          builder,
-          important,
-          border_top_left_radius(SpacePair::from_single(rounded.0))
-        );
-        push_decl!(
-          builder,
-          important,
-          border_top_right_radius(SpacePair::from_single(rounded.0))
-        );
+        push_decl!(builder, important, border_top_left_radius(rounded.0));
+        push_decl!(builder, important, border_top_right_radius(rounded.0));
       }
       TailwindProperty::RoundedRight(rounded) => {
-        push_decl!(
-          builder,
-          important,
-          border_top_right_radius(SpacePair::from_single(rounded.0))
-        );
-        push_decl!(
-          builder,
-          important,
-          border_bottom_right_radius(SpacePair::from_single(rounded.0))
-        );
+        push_decl!(builder, important, border_top_right_radius(rounded.0));
+        push_decl!(builder, important, border_bottom_right_radius(rounded.0));
       }
       TailwindProperty::RoundedBottom(rounded) => {
-        push_decl!(
-          builder,
-          important,
-          border_bottom_left_radius(SpacePair::from_single(rounded.0))
-        );
-        push_decl!(
-          builder,
-          important,
-          border_bottom_right_radius(SpacePair::from_single(rounded.0))
-        );
+        push_decl!(builder, important, border_bottom_left_radius(rounded.0));
+        push_decl!(builder, important, border_bottom_right_radius(rounded.0));
       }
       TailwindProperty::RoundedLeft(rounded) => {
-        push_decl!(
-          builder,
-          important,
-          border_top_left_radius(SpacePair::from_single(rounded.0))
-        );
-        push_decl!(
-          builder,
-          important,
-          border_bottom_left_radius(SpacePair::from_single(rounded.0))
-        );
+        push_decl!(builder, important, border_top_left_radius(rounded.0));
+        push_decl!(builder, important, border_bottom_left_radius(rounded.0));
       }
       TailwindProperty::TextOverflow(text_overflow) => {
         push_decl!(builder, important, text_overflow(text_overflow))
@@ -1428,7 +1460,11 @@ impl TailwindProperty {
         grid_template_rows(Some(tw_grid_template.0))
       ),
       TailwindProperty::LetterSpacing(tw_letter_spacing) => {
-        push_decl!(builder, important, letter_spacing(tw_letter_spacing.0))
+        push_decl!(
+          builder,
+          important,
+          letter_spacing(tw_letter_spacing.0.into())
+        )
       }
       TailwindProperty::GridAutoFlow(grid_auto_flow) => {
         push_decl!(builder, important, grid_auto_flow(grid_auto_flow))
@@ -1750,14 +1786,71 @@ mod tests {
   fn test_parse_border_radius() {
     assert_eq!(
       TailwindProperty::parse("rounded-xs"),
-      Some(TailwindProperty::Rounded(TwRounded(Length::Rem(0.125))))
+      Some(TailwindProperty::Rounded(TwBorderRadius(Box::new(
+        BorderRadius(Sides([SpacePair::from_single(Length::Rem(0.125)); 4]))
+      ))))
     );
     assert_eq!(
       TailwindProperty::parse("rounded-full"),
-      Some(TailwindProperty::Rounded(TwRounded(Length::Px(9999.0))))
+      Some(TailwindProperty::Rounded(TwBorderRadius(Box::new(
+        BorderRadius(Sides([SpacePair::from_single(Length::Px(9999.0)); 4]))
+      ))))
     );
   }
 
+  #[test]
+  fn test_parse_border_radius_arbitrary_per_corner() {
+    assert_eq!(
+      TailwindProperty::parse("rounded-[12px_24px_0_4px]"),
+      Some(TailwindProperty::Rounded(TwBorderRadius(Box::new(
+        BorderRadius(Sides([
+          SpacePair::from_single(Length::Px(12.0)),
+          SpacePair::from_single(Length::Px(24.0)),
+          SpacePair::from_single(Length::Px(0.0)),
+          SpacePair::from_single(Length::Px(4.0)),
+        ]))
+      ))))
+    );
+    assert_eq!(
+      TailwindProperty::parse("rounded-[10px_20px/5px]"),
+      Some(TailwindProperty::Rounded(TwBorderRadius(Box::new(
+        BorderRadius(Sides([
+          SpacePair::from_pair(Length::Px(10.0), Length::Px(5.0)),
+          SpacePair::from_pair(Length::Px(20.0), Length::Px(5.0)),
+          SpacePair::from_pair(Length::Px(10.0), Length::Px(5.0)),
+          SpacePair::from_pair(Length::Px(20.0), Length::Px(5.0)),
+        ]))
+      ))))
+    );
+    assert_eq!(
+      TailwindProperty::parse("rounded-tl-[12px]"),
+      Some(TailwindProperty::RoundedTopLeft(TwRounded(
+        SpacePair::from_single(Length::Px(12.0))
+      )))
+    );
+    assert_eq!(
+      TailwindProperty::parse("rounded-br-[12px_50%]"),
+      Some(TailwindProperty::RoundedBottomRight(TwRounded(
+        SpacePair::from_pair(Length::Px(12.0), Length::Percentage(50.0))
+      )))
+    );
+    assert_eq!(
+      TailwindProperty::parse("rounded-t-[1rem]"),
+      Some(TailwindProperty::RoundedTop(TwRounded(
+        SpacePair::from_single(Length::Rem(1.0))
+      )))
+    );
+  }
+
+  #[test]
+  fn test_parse_border_radius_arbitrary_rejects_extra_values() {
+    assert_eq!(
+      TailwindProperty::parse("rounded-[1px_2px_3px_4px_5px]"),
+      None
+    );
+    assert_eq!(TailwindProperty::parse("rounded-tl-[1px_2px_3px]"), None);
+  }
+
   #[test]
   fn test_parse_font_size_with_arbitrary_line_height() {
     assert_eq!(
@@ -1791,6 +1884,10 @@ mod tests {
       TailwindProperty::parse("border-solid"),
       Some(TailwindProperty::BorderStyle(BorderStyle::Solid))
     );
+    assert_eq!(
+      TailwindProperty::parse("border-dashed"),
+      Some(TailwindProperty::BorderStyle(BorderStyle::Dashed))
+    );
     assert_eq!(
       TailwindProperty::parse("border-none"),
       Some(TailwindProperty::BorderStyle(BorderStyle::None))
@@ -1819,6 +1916,10 @@ mod tests {
       TailwindProperty::parse("outline-solid"),
       Some(TailwindProperty::OutlineStyle(BorderStyle::Solid))
     );
+    assert_eq!(
+      TailwindProperty::parse("outline-dotted"),
+      Some(TailwindProperty::OutlineStyle(BorderStyle::Dotted))
+    );
     assert_eq!(
       TailwindProperty::parse("outline-offset-4"),
       Some(TailwindProperty::OutlineOffset(TwBorderWidth(Length::Px(
@@ -2013,11 +2114,25 @@ mod tests {
             breakpoint: Some(Breakpoint(Length::Rem(48.0))),
             important: true,
           },
-        ]
+        ],
+        unsupported: Vec::new(),
       })
     )
   }
 
+  #[test]
+  fn test_values_collects_unsupported_tokens() {
+    let Ok(values) = TailwindValues::from_str("mt-4 not-a-utility flex bogus-[") else {
+      unreachable!()
+    };
+
+    assert_eq!(values.iter().count(), 2);
+    assert_eq!(
+      values.unsupported(),
+      &["not-a-utility".into(), "bogus-[".into()]
+    );
+  }
+
   #[test]
   fn test_filters_append() {
     use crate::layout::style::properties::Filter;
@@ -2072,6 +2187,22 @@ mod tests {
       Some(TailwindProperty::BackgroundBlendMode(BlendMode::Screen))
     );
   }
+
+  #[test]
+  fn test_parse_background_origin_and_attachment() {
+    assert_eq!(
+      TailwindProperty::parse("bg-origin-content"),
+      Some(TailwindProperty::BackgroundOrigin(
+        BackgroundOrigin::ContentBox
+      ))
+    );
+    assert_eq!(
+      TailwindProperty::parse("bg-fixed"),
+      Some(TailwindProperty::BackgroundAttachment(
+        BackgroundAttachment::Fixed
+      ))
+    );
+  }
   #[test]
   fn test_parse_vertical_align() {
     assert_eq!(
@@ -2170,6 +2301,22 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_parse_decoration_style() {
+    assert_eq!(
+      TailwindProperty::parse("decoration-wavy"),
+      Some(TailwindProperty::TextDecorationStyle(
+        TextDecorationStyle::Wavy
+      ))
+    );
+    assert_eq!(
+      TailwindProperty::parse("decoration-dashed"),
+      Some(TailwindProperty::TextDecorationStyle(
+        TextDecorationStyle::Dashed
+      ))
+    );
+  }
+
   #[test]
   fn test_linear_gradient_apply() {
     let viewport = Viewport::new((100, 100));
@@ -2210,6 +2357,90 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_conic_gradient_apply() {
+    let viewport = Viewport::new((100, 100));
+    let Ok(values) = TailwindValues::from_str("bg-conic from-red-500 to-blue-500") else {
+      unreachable!()
+    };
+
+    let style =
+      Style::from(values.into_declaration_block(viewport)).inherit(&ComputedStyle::default());
+
+    assert_eq!(
+      style.background_image,
+      Some(
+        [BackgroundImage::Conic(ConicGradient {
+          repeating: false,
+          from_angle: Angle::zero(),
+          center: BackgroundPosition::default(),
+          interpolation: ColorInterpolationMethod::default(),
+          stops: [
+            GradientStop::ColorHint {
+              color: ColorInput::Value(Color([239, 68, 68, 255])),
+              hint: Some(StopPosition(Length::Percentage(0.0))),
+            },
+            GradientStop::ColorHint {
+              color: ColorInput::Value(Color([59, 130, 246, 255])),
+              hint: Some(StopPosition(Length::Percentage(100.0))),
+            },
+          ]
+          .into(),
+        })]
+        .into()
+      )
+    );
+    assert_eq!(
+      TailwindProperty::parse("bg-conic-45"),
+      Some(TailwindProperty::BgConicAngle(Angle::new(45.0)))
+    );
+  }
+  #[test]
+  fn test_parse_accent_color() {
+    assert_eq!(
+      TailwindProperty::parse("bg-accent"),
+      Some(TailwindProperty::BackgroundAccentColor(TwAccentColor {
+        shade: None,
+        opacity: None,
+      }))
+    );
+    assert_eq!(
+      TailwindProperty::parse("text-accent-700/40"),
+      Some(TailwindProperty::TextAccentColor(TwAccentColor {
+        shade: Some(700),
+        opacity: Some(40),
+      }))
+    );
+    assert_eq!(TailwindProperty::parse("bg-accent-550"), None);
+    assert_eq!(TailwindProperty::parse("bg-accented-500"), None);
+  }
+
+  #[test]
+  fn test_accent_color_apply() {
+    let viewport = Viewport::new((100, 100));
+    let Ok(values) = TailwindValues::from_str("bg-accent text-accent-50 border-accent-950/50")
+    else {
+      unreachable!()
+    };
+
+    let mut parent = ComputedStyle::default();
+    parent
+      .custom_properties
+      .insert("--color-accent".to_owned(), "#3b82f6".to_owned());
+
+    let style = Style::from(values.into_declaration_block(viewport)).inherit(&parent);
+
+    assert_eq!(
+      style.background_color,
+      ColorInput::Value(Color([59, 130, 246, 255]))
+    );
+    assert_eq!(style.color, ColorInput::Value(Color([239, 246, 255, 255])));
+    assert_eq!(
+      style.border_color,
+      ColorInput::Value(Color([13, 39, 82, 128]))
+    );
+  }
+
   #[test]
   fn test_shadow_color_overrides_shadow_preset_in_any_order() {
     let viewport = Viewport::new((100, 100));
@@ -2260,6 +2491,7 @@ mod tests {
             offset_y: Length::Px(1.0),
             blur_radius: Length::Px(2.0),
             color: ColorInput::Value(Color([239, 68, 68, 255])),
+            inset: false,
           }]
           .into()
         )
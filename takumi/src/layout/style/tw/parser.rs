@@ -1,15 +1,15 @@
-use std::ops::Neg;
+use std::{borrow::Cow, ops::Neg};
 
 use cssparser::{Parser, match_ignore_ascii_case};
 
 use crate::layout::style::{
   CssToken,
   Length::{self, *},
-  tw::TailwindPropertyParser,
+  tw::{TailwindPropertyParser, parse_arbitrary_entirely},
   *,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct TwFontSize {
   pub(crate) font_size: FontSize,
@@ -88,7 +88,7 @@ impl TwFontSize {
   }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct TwGridTemplate(pub GridTemplateComponents);
 
@@ -122,7 +122,7 @@ impl TailwindPropertyParser for TwGridTemplate {
   }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct TwLetterSpacing(pub Length);
 
@@ -156,7 +156,7 @@ impl TailwindPropertyParser for TwLetterSpacing {
   }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct TwBorderWidth(pub Length);
 
@@ -176,13 +176,13 @@ impl TailwindPropertyParser for TwBorderWidth {
   }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
-pub struct TwRounded(pub(crate) LengthDefaultsToZero);
+pub struct TwRounded(pub(crate) SpacePair<LengthDefaultsToZero>);
 
 impl<'i> FromCss<'i> for TwRounded {
   fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
-    Ok(TwRounded(Length::from_css(input)?))
+    Ok(TwRounded(SpacePair::from_css(input)?))
   }
 
   const VALID_TOKENS: &'static [CssToken] = Length::<false>::VALID_TOKENS;
@@ -190,23 +190,59 @@ impl<'i> FromCss<'i> for TwRounded {
 
 impl TailwindPropertyParser for TwRounded {
   fn parse_tw(token: &str) -> Option<Self> {
-    match_ignore_ascii_case! {token,
-      "full" => Some(TwRounded(Length::Px(9999.0))),
-      "none" => Some(TwRounded(Length::Px(0.0))),
-      "xs" => Some(TwRounded(Length::Rem(0.125))),
-      "sm" => Some(TwRounded(Length::Rem(0.25))),
-      "md" => Some(TwRounded(Length::Rem(0.375))),
-      "lg" => Some(TwRounded(Length::Rem(0.5))),
-      "xl" => Some(TwRounded(Length::Rem(0.75))),
-      "2xl" => Some(TwRounded(Length::Rem(1.0))),
-      "3xl" => Some(TwRounded(Length::Rem(1.5))),
-      "4xl" => Some(TwRounded(Length::Rem(2.0))),
-      _ => None,
-    }
+    let radius = match_ignore_ascii_case! {token,
+      "full" => Length::Px(9999.0),
+      "none" => Length::Px(0.0),
+      "xs" => Length::Rem(0.125),
+      "sm" => Length::Rem(0.25),
+      "md" => Length::Rem(0.375),
+      "lg" => Length::Rem(0.5),
+      "xl" => Length::Rem(0.75),
+      "2xl" => Length::Rem(1.0),
+      "3xl" => Length::Rem(1.5),
+      "4xl" => Length::Rem(2.0),
+      _ => return None,
+    };
+
+    Some(TwRounded(SpacePair::from_single(radius)))
+  }
+
+  // Extra radii would otherwise be dropped, rounding the corner differently than written.
+  fn parse_tw_with_arbitrary(token: &str) -> Option<Self> {
+    parse_arbitrary_entirely(token).unwrap_or_else(|| Self::parse_tw(token))
   }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// The `rounded` utility, which accepts a full `border-radius` shorthand as an arbitrary value.
+#[derive(Debug, Clone, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct TwBorderRadius(pub(crate) Box<BorderRadius>);
+
+impl<'i> FromCss<'i> for TwBorderRadius {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    Ok(TwBorderRadius(Box::new(BorderRadius::from_css(input)?)))
+  }
+
+  fn expect_message() -> Cow<'static, str> {
+    BorderRadius::expect_message()
+  }
+
+  const VALID_TOKENS: &'static [CssToken] = BorderRadius::VALID_TOKENS;
+}
+
+impl TailwindPropertyParser for TwBorderRadius {
+  fn parse_tw(token: &str) -> Option<Self> {
+    let TwRounded(radius) = TwRounded::parse_tw(token)?;
+
+    Some(TwBorderRadius(Box::new(BorderRadius(Sides([radius; 4])))))
+  }
+
+  fn parse_tw_with_arbitrary(token: &str) -> Option<Self> {
+    parse_arbitrary_entirely(token).unwrap_or_else(|| Self::parse_tw(token))
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
 #[non_exhaustive]
 pub struct TwBlur(pub(crate) Length);
 
@@ -233,3 +269,79 @@ impl TailwindPropertyParser for TwBlur {
     }
   }
 }
+
+/// Mix weights of `--color-accent` against white (lighter shades) or black (darker shades),
+/// approximating the curve of the built-in Tailwind palettes around a `500` base.
+const ACCENT_SHADES: [(u16, u8, &str); 11] = [
+  (50, 8, "white"),
+  (100, 18, "white"),
+  (200, 31, "white"),
+  (300, 51, "white"),
+  (400, 78, "white"),
+  (500, 100, ""),
+  (600, 88, "black"),
+  (700, 78, "black"),
+  (800, 68, "black"),
+  (900, 61, "black"),
+  (950, 45, "black"),
+];
+
+/// A shade of the `accent` theme color, derived at render time from the `--color-accent`
+/// custom property, e.g. `bg-accent-600/50`.
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct TwAccentColor {
+  pub(crate) shade: Option<u16>,
+  pub(crate) opacity: Option<u8>,
+}
+
+impl TwAccentColor {
+  /// Parses `accent`, `accent-<shade>` and their `/<opacity>` forms.
+  pub fn parse(token: &str) -> Option<Self> {
+    if let Some((color, opacity)) = token.split_once('/') {
+      let opacity = opacity
+        .parse::<u8>()
+        .ok()
+        .filter(|opacity| *opacity <= 100)?;
+
+      return Some(Self {
+        opacity: Some(opacity),
+        ..Self::parse(color)?
+      });
+    }
+
+    let shade = match token.split_once('-') {
+      Some((name, shade)) if name.eq_ignore_ascii_case("accent") => {
+        let shade = shade.parse::<u16>().ok()?;
+
+        Some(ACCENT_SHADES.iter().find(|(value, ..)| *value == shade)?.0)
+      }
+      None if token.eq_ignore_ascii_case("accent") => None,
+      _ => return None,
+    };
+
+    Some(Self {
+      shade,
+      opacity: None,
+    })
+  }
+
+  /// Returns the CSS value this shade resolves to, referencing `var(--color-accent)`.
+  pub(crate) fn to_css(self) -> String {
+    let base = "var(--color-accent)";
+    let color = match self
+      .shade
+      .and_then(|shade| ACCENT_SHADES.iter().find(|(value, ..)| *value == shade))
+    {
+      Some((_, weight, mix_with)) if !mix_with.is_empty() => {
+        format!("color-mix(in oklab, {base} {weight}%, {mix_with})")
+      }
+      _ => base.to_owned(),
+    };
+
+    match self.opacity {
+      Some(opacity) => format!("color-mix(in oklab, {color} {opacity}%, transparent)"),
+      None => color,
+    }
+  }
+}
@@ -1,9 +1,9 @@
-use std::{iter::Copied, mem::take, slice::Iter, vec::IntoIter};
+use std::{iter::Copied, mem::take, slice::Iter, sync::Arc, time::Duration, vec::IntoIter};
 
 use taffy::{
   AvailableSpace, Cache, CacheTree, Display as TaffyDisplay, Layout, LayoutBlockContainer,
   LayoutFlexboxContainer, LayoutGridContainer, LayoutInput, LayoutOutput, LayoutPartialTree,
-  NodeId, RequestedAxis, RoundTree, RunMode, Size, SizingMode, Style, TaffyError,
+  NodeId, Point, RequestedAxis, RoundTree, RunMode, Size, SizingMode, Style, TaffyError,
   TraversePartialTree, TraverseTree, compute_block_layout, compute_cached_layout,
   compute_flexbox_layout, compute_grid_layout, compute_hidden_layout, compute_leaf_layout,
   compute_root_layout, round_layout,
@@ -15,23 +15,28 @@ use crate::{
     Viewport,
     inline::{
       InlineContentKind, InlineLayoutStage, ProcessedInlineSpan, collect_inline_items,
-      create_inline_constraint, create_inline_layout, measure_inline_layout,
+      collect_inline_items_with_opacity_groups, create_inline_constraint, create_inline_layout,
+      measure_inline_layout,
     },
-    node::{Node, NodeStyleLayers},
+    node::{Node, NodeKind, NodeStyleLayers, resolve_image},
     style::{
-      Affine, BlendMode, Color, ComputedStyle, Display, Filters, Isolation, PercentageNumber,
-      Style as NodeStyle, StyleSheet, apply_stylesheet_animations,
+      Affine, BlendMode, Color, ComputedStyle, Display, Filters, Isolation, Length,
+      PercentageNumber, Position, SizedFontStyle, Style as NodeStyle, StyleSheet,
+      apply_stylesheet_animations,
       matching::{MatchedDeclarations, match_stylesheets},
     },
   },
   rendering::{
-    Canvas, MaxHeight, RenderContext, Sizing,
-    inline_drawing::{draw_inline_box, draw_inline_layout},
+    Canvas, Instant, LayoutTiming, MaxHeight, RenderContext, Sizing,
+    inline_drawing::{InlineOpacityLayers, draw_inline_box, draw_inline_layout},
   },
+  resources::{palette::ImagePalettes, task::FetchTaskCollection},
 };
 
 pub(crate) struct LayoutResults {
   nodes: Vec<LayoutResultNode>,
+  /// Layout time of every node, empty unless the layout was profiled.
+  timings: Vec<LayoutTiming>,
 }
 
 struct LayoutResultNode {
@@ -61,11 +66,26 @@ impl LayoutResults {
       .map(|node| node.children.as_ref())
       .ok_or(TaffyError::InvalidInputNode(node_id))
   }
+
+  /// Returns the time spent laying out a node, zero unless the layout was profiled.
+  pub(crate) fn timing(&self, node_id: NodeId) -> LayoutTiming {
+    let idx: usize = node_id.into();
+    self.timings.get(idx).copied().unwrap_or_default()
+  }
+
+  /// Adds the timings of an earlier layout of the same tree to this one.
+  pub(crate) fn add_timings(&mut self, earlier: &LayoutResults) {
+    for (timing, earlier) in self.timings.iter_mut().zip(&earlier.timings) {
+      timing.add(*earlier);
+    }
+  }
 }
 
 pub(crate) struct LayoutTree<'r, 'g> {
   nodes: Vec<LayoutNodeState>,
   render_nodes: Vec<&'r RenderNode<'g>>,
+  /// Layout time of every node, recorded when profiling.
+  timings: Option<Vec<LayoutTiming>>,
 }
 
 struct LayoutNodeState {
@@ -95,7 +115,7 @@ fn build_style_layers(
   let mut style = NodeStyle::default();
 
   if let Some(preset) = node_layers.preset {
-    style.merge_from(preset);
+    style.merge_from(preset.resolve_color_scheme(viewport.color_scheme));
   }
 
   for declaration in matched_declarations.normal.iter() {
@@ -107,7 +127,7 @@ fn build_style_layers(
   }
 
   if let Some(inline) = node_layers.inline {
-    style.merge_from(inline);
+    style.merge_from(inline.resolve_color_scheme(viewport.color_scheme));
   }
 
   for declaration in matched_declarations.important.iter() {
@@ -117,6 +137,43 @@ fn build_style_layers(
   style
 }
 
+/// Resolves the images referenced by `palette()` in the node tree and stylesheet.
+fn image_palettes(node: &Node, context: &RenderContext) -> Option<ImagePalettes> {
+  let mut sources = FetchTaskCollection::default();
+  let mut stack = vec![node];
+
+  while let Some(node) = stack.pop() {
+    for style in [&node.metadata.preset, &node.metadata.style]
+      .into_iter()
+      .flatten()
+    {
+      style.declarations.collect_palette_sources(&mut sources);
+    }
+
+    stack.extend(node.children_ref().into_iter().flatten());
+  }
+
+  for rule in &context.stylesheet.rules {
+    rule
+      .normal_declarations
+      .collect_palette_sources(&mut sources);
+    rule
+      .important_declarations
+      .collect_palette_sources(&mut sources);
+  }
+
+  let sources = sources.into_inner();
+  if sources.is_empty() {
+    return None;
+  }
+
+  // Unresolvable images make their `palette()` references invalid, like an unknown `var()`.
+  Some(ImagePalettes::new(sources.into_iter().filter_map(|src| {
+    let image = resolve_image(&src, context).ok()?;
+    Some((src, image))
+  })))
+}
+
 fn registered_custom_property_parent_style(
   parent_style: &ComputedStyle,
   stylesheets: &[StyleSheet],
@@ -179,7 +236,7 @@ fn build_inherited_style(
     let mut style = NodeStyle::default();
 
     if let Some(preset) = node_layers.preset {
-      style.merge_from(preset);
+      style.merge_from(preset.resolve_color_scheme(viewport.color_scheme));
     }
 
     for declaration in matched_declarations.normal.declarations {
@@ -191,7 +248,7 @@ fn build_inherited_style(
     }
 
     if let Some(inline) = node_layers.inline {
-      style.merge_from(inline);
+      style.merge_from(inline.resolve_color_scheme(viewport.color_scheme));
     }
 
     for declaration in matched_declarations.important.declarations {
@@ -304,9 +361,15 @@ impl<'r, 'g> LayoutTree<'r, 'g> {
     Self {
       nodes,
       render_nodes,
+      timings: None,
     }
   }
 
+  /// Records the time spent laying out every node from now on.
+  pub(crate) fn enable_profiling(&mut self) {
+    self.timings = Some(vec![LayoutTiming::default(); self.nodes.len()]);
+  }
+
   pub(crate) fn root_node_id(&self) -> NodeId {
     NodeId::from(0usize)
   }
@@ -315,6 +378,128 @@ impl<'r, 'g> LayoutTree<'r, 'g> {
     let root_node_id = self.root_node_id();
     compute_root_layout(self, root_node_id, available_space);
     round_layout(self, root_node_id);
+    self.apply_anchor_positions();
+  }
+
+  /// Rounds an auto height up to a multiple of `block-step-size`, leaving the extra space below
+  /// the content.
+  fn snap_to_block_step(
+    &self,
+    node: NodeId,
+    inputs: LayoutInput,
+    mut output: LayoutOutput,
+  ) -> LayoutOutput {
+    if inputs.known_dimensions.height.is_some() || output.size.height <= 0.0 {
+      return output;
+    }
+
+    let idx: usize = node.into();
+    let Some(render_node) = self.render_nodes.get(idx) else {
+      return output;
+    };
+    let Some(step) = render_node
+      .context
+      .style
+      .block_step_size
+      .map(|step| step.to_px(&render_node.context.sizing, 0.0))
+      .filter(|step| *step > 0.0)
+    else {
+      return output;
+    };
+
+    // Tolerate float error so a height already on the grid isn't pushed up a step.
+    output.size.height = (output.size.height / step - 1e-3).ceil() * step;
+    output
+  }
+
+  /// Moves absolutely positioned nodes with a `position-anchor` next to their anchor.
+  ///
+  /// The anchor is the closest node before the positioned node in tree order whose `id` matches.
+  /// Its border box stands in for the containing block when resolving insets, while the size
+  /// computed against the real containing block is kept.
+  fn apply_anchor_positions(&mut self) {
+    if self
+      .render_nodes
+      .iter()
+      .all(|render_node| render_node.context.style.position_anchor.is_none())
+    {
+      return;
+    }
+
+    let mut parents = vec![None; self.nodes.len()];
+    for (index, node) in self.nodes.iter().enumerate() {
+      for &child_id in &node.children {
+        parents[usize::from(child_id)] = Some(index);
+      }
+    }
+
+    // Node ids are assigned in preorder, so parents and anchors are always resolved first.
+    let mut origins = vec![Point::ZERO; self.nodes.len()];
+    for index in 0..self.nodes.len() {
+      let parent_origin = parents[index].map_or(Point::ZERO, |parent| origins[parent]);
+
+      if let Some(location) = self.anchored_location(index, parent_origin, &origins) {
+        self.nodes[index].final_layout.location = location;
+      }
+
+      let location = self.nodes[index].final_layout.location;
+      origins[index] = Point {
+        x: parent_origin.x + location.x,
+        y: parent_origin.y + location.y,
+      };
+    }
+  }
+
+  fn anchored_location(
+    &self,
+    index: usize,
+    parent_origin: Point<f32>,
+    origins: &[Point<f32>],
+  ) -> Option<Point<f32>> {
+    let render_node = self.render_nodes[index];
+    let style = &render_node.context.style;
+
+    if style.position != Position::Absolute {
+      return None;
+    }
+
+    let anchor_id = style.position_anchor.as_ref()?;
+    let anchor_index = (0..index).rev().find(|&candidate| {
+      self.render_nodes[candidate]
+        .node
+        .as_ref()
+        .and_then(|node| node.metadata.id.as_deref())
+        == Some(anchor_id.0.as_ref())
+    })?;
+
+    let anchor_origin = origins[anchor_index];
+    let anchor_size = self.nodes[anchor_index].final_layout.size;
+    let layout = &self.nodes[index].final_layout;
+    let sizing = &render_node.context.sizing;
+    let resolve = |inset: Length, percentage_full_px: f32| {
+      (inset != Length::Auto).then(|| inset.to_px(sizing, percentage_full_px))
+    };
+
+    let x = if let Some(left) = resolve(style.left, anchor_size.width) {
+      anchor_origin.x + left + layout.margin.left
+    } else if let Some(right) = resolve(style.right, anchor_size.width) {
+      anchor_origin.x + anchor_size.width - right - layout.margin.right - layout.size.width
+    } else {
+      parent_origin.x + layout.location.x
+    };
+
+    let y = if let Some(top) = resolve(style.top, anchor_size.height) {
+      anchor_origin.y + top + layout.margin.top
+    } else if let Some(bottom) = resolve(style.bottom, anchor_size.height) {
+      anchor_origin.y + anchor_size.height - bottom - layout.margin.bottom - layout.size.height
+    } else {
+      parent_origin.y + layout.location.y
+    };
+
+    Some(Point {
+      x: x - parent_origin.x,
+      y: y - parent_origin.y,
+    })
   }
 
   pub(crate) fn into_results(self) -> LayoutResults {
@@ -327,6 +512,7 @@ impl<'r, 'g> LayoutTree<'r, 'g> {
           children: node.children,
         })
         .collect(),
+      timings: self.timings.unwrap_or_default(),
     }
   }
 
@@ -496,6 +682,26 @@ impl LayoutPartialTree for LayoutTree<'_, '_> {
   }
 
   fn compute_child_layout(&mut self, node: NodeId, inputs: LayoutInput) -> LayoutOutput {
+    let Some(started) = self.timings.is_some().then(Instant::now) else {
+      return self.layout_node(node, inputs);
+    };
+
+    let output = self.layout_node(node, inputs);
+
+    if let Some(timing) = self
+      .timings
+      .as_mut()
+      .and_then(|timings| timings.get_mut(usize::from(node)))
+    {
+      timing.layout += started.elapsed();
+    }
+
+    output
+  }
+}
+
+impl LayoutTree<'_, '_> {
+  fn layout_node(&mut self, node: NodeId, inputs: LayoutInput) -> LayoutOutput {
     self.update_node_style_for_available_space(
       node,
       inputs.available_space,
@@ -513,8 +719,10 @@ impl LayoutPartialTree for LayoutTree<'_, '_> {
 
       let display_mode = node_data.style.display;
       let has_children = !node_data.children.is_empty();
+      let is_profiling = tree.timings.is_some();
+      let mut measure_time = Duration::ZERO;
 
-      match (display_mode, has_children) {
+      let output = match (display_mode, has_children) {
         (TaffyDisplay::None, _) => compute_hidden_layout(tree, node),
         (TaffyDisplay::Block, true) => compute_block_layout(tree, node, inputs),
         (TaffyDisplay::Flex, true) => compute_flexbox_layout(tree, node, inputs),
@@ -546,15 +754,32 @@ impl LayoutPartialTree for LayoutTree<'_, '_> {
               return Size { width, height };
             }
 
-            render_node.measure(
+            let started = is_profiling.then(Instant::now);
+            let size = render_node.measure(
               available_space,
               known_dimensions,
               &node_data.style,
               node_data.is_inline_children,
-            )
+            );
+
+            if let Some(started) = started {
+              measure_time = started.elapsed();
+            }
+
+            size
           },
         ),
+      };
+
+      if let Some(timing) = tree
+        .timings
+        .as_mut()
+        .and_then(|timings| timings.get_mut(usize::from(node)))
+      {
+        timing.measure += measure_time;
       }
+
+      tree.snap_to_block_step(node, inputs, output)
     })
   }
 }
@@ -689,6 +914,8 @@ impl<'g> RenderNode<'g> {
     context.style.mask_size = Default::default();
     context.style.mask_position = Default::default();
     context.style.mask_repeat = Default::default();
+    context.style.mask_mode = Default::default();
+    context.style.fade_edges = Default::default();
     context.style.transform = None;
     context.style.rotate = None;
     context.style.scale = Default::default();
@@ -764,6 +991,26 @@ impl<'g> RenderNode<'g> {
     Ok(())
   }
 
+  /// Returns the width of the widest line of the inline content, as it is drawn in `layout`.
+  pub(crate) fn inline_content_width(&self, layout: Layout) -> f32 {
+    let font_style = self.context.style.to_sized_font_style(&self.context);
+
+    let (mut inline_layout, _, _) = create_inline_layout(
+      collect_inline_items(self).into_iter(),
+      Size {
+        width: AvailableSpace::Definite(layout.content_box_width()),
+        height: AvailableSpace::Definite(layout.content_box_height()),
+      },
+      layout.content_box_width(),
+      inline_max_height(&font_style, layout),
+      &font_style,
+      self.context.global,
+      InlineLayoutStage::Measure,
+    );
+
+    measure_inline_layout(&mut inline_layout, layout.content_box_width(), &font_style).width
+  }
+
   pub fn draw_inline(&mut self, canvas: &mut Canvas, layout: Layout) -> Result<()> {
     if self.context.style.opacity.0 == 0.0 {
       return Ok(());
@@ -771,22 +1018,15 @@ impl<'g> RenderNode<'g> {
 
     let font_style = self.context.style.to_sized_font_style(&self.context);
 
-    let max_height = match font_style.parent.line_clamp.as_ref() {
-      Some(clamp) => Some(MaxHeight::HeightAndLines(
-        layout.content_box_height(),
-        clamp.count,
-      )),
-      None => Some(MaxHeight::Absolute(layout.content_box_height())),
-    };
-
-    let (inline_layout, _, spans) = create_inline_layout(
-      collect_inline_items(self).into_iter(),
+    let (items, opacity_groups) = collect_inline_items_with_opacity_groups(self);
+    let (inline_layout, text, spans) = create_inline_layout(
+      items.into_iter(),
       Size {
         width: AvailableSpace::Definite(layout.content_box_width()),
         height: AvailableSpace::Definite(layout.content_box_height()),
       },
       layout.content_box_width(),
-      max_height,
+      inline_max_height(&font_style, layout),
       &font_style,
       self.context.global,
       InlineLayoutStage::Draw,
@@ -798,13 +1038,16 @@ impl<'g> RenderNode<'g> {
       _ => None,
     });
 
+    let mut opacity_layers = InlineOpacityLayers::new(&opacity_groups);
     let positioned_inline_boxes = draw_inline_layout(
       &self.context,
       canvas,
       inline_layout_box,
       inline_layout,
+      &text,
       &font_style,
       &spans,
+      &mut opacity_layers,
     )?;
 
     let inline_transform = Affine::translation(
@@ -813,9 +1056,12 @@ impl<'g> RenderNode<'g> {
     ) * self.context.transform;
 
     for (item, positioned) in boxes.zip(positioned_inline_boxes.iter()) {
-      draw_inline_box(positioned, item, canvas, inline_transform)?;
+      opacity_layers.paint(canvas, item.opacity_group, |canvas| {
+        draw_inline_box(positioned, item, canvas, inline_transform)
+      })?;
     }
-    Ok(())
+
+    opacity_layers.composite(canvas, &self.context)
   }
 
   pub fn is_inline_level(&self) -> bool {
@@ -845,6 +1091,17 @@ impl<'g> RenderNode<'g> {
       &parent_context.stylesheet,
       parent_context.sizing.viewport,
     );
+    let palette_context;
+    let parent_context = match image_palettes(&node, parent_context) {
+      Some(palettes) => {
+        let mut context = parent_context.clone();
+        context.style.image_palettes = Some(Arc::new(palettes));
+        palette_context = context;
+        &palette_context
+      }
+      None => parent_context,
+    };
+
     let mut tree = Self::from_node_iterative(parent_context, node, &matched_styles);
 
     if tree.is_inline_level() {
@@ -884,18 +1141,24 @@ impl<'g> RenderNode<'g> {
       parent_context: &RenderContext<'g>,
       style: ComputedStyle,
       sizing: Sizing,
-      current_color: Color,
+      (current_color, backdrop_color): (Color, Color),
     ) -> RenderContext<'g> {
       RenderContext {
         global: parent_context.global,
         transform: parent_context.transform,
         style: Box::new(style),
         current_color,
+        backdrop_color,
         time: parent_context.time,
+        now_ms: parent_context.now_ms,
+        seed: parent_context.seed,
+        locale: parent_context.locale.clone(),
+        missing_font_policy: parent_context.missing_font_policy,
         draw_debug_border: parent_context.draw_debug_border,
         fetched_resources: parent_context.fetched_resources.clone(),
         sizing,
         stylesheet: parent_context.stylesheet.clone(),
+        custom_drawers: parent_context.custom_drawers.clone(),
       }
     }
 
@@ -904,7 +1167,7 @@ impl<'g> RenderNode<'g> {
       node: &mut Node,
       node_index: usize,
       matched_declarations: &[MatchedDeclarations],
-    ) -> (ComputedStyle, Sizing, Color) {
+    ) -> (ComputedStyle, Sizing, (Color, Color)) {
       let default_matched = MatchedDeclarations::default();
       let matched = matched_declarations
         .get(node_index)
@@ -918,19 +1181,20 @@ impl<'g> RenderNode<'g> {
       );
       let mut style = style_layers.inherit(&inherited_parent);
 
+      let zoomed_sizing = apply_zoom(&parent_context.sizing, &style);
       let font_size = style
         .font_size
-        .to_px(&parent_context.sizing, parent_context.sizing.font_size);
+        .to_px(&zoomed_sizing, zoomed_sizing.font_size);
       let child_sizing = Sizing {
         font_size,
-        ..parent_context.sizing.clone()
+        ..zoomed_sizing
       };
-      let child_current_color = style.color.resolve(parent_context.current_color);
+      let child_colors = resolve_colors(parent_context, &style);
       let child_context = build_render_context(
         parent_context,
         style.clone(),
         child_sizing.clone(),
-        child_current_color,
+        child_colors,
       );
       style = apply_stylesheet_animations(style, &child_context);
 
@@ -938,14 +1202,45 @@ impl<'g> RenderNode<'g> {
         declaration.apply_to_computed(&mut style);
       }
 
-      let font_size = style.font_size.to_px(&child_sizing, child_sizing.font_size);
+      let zoomed_sizing = apply_zoom(&parent_context.sizing, &style);
+      let font_size = style
+        .font_size
+        .to_px(&zoomed_sizing, zoomed_sizing.font_size);
       let sizing = Sizing {
         font_size,
-        ..parent_context.sizing.clone()
+        ..zoomed_sizing
       };
-      let current_color = style.color.resolve(parent_context.current_color);
+      let colors = resolve_colors(parent_context, &style);
       style.make_computed(&sizing);
-      (style, sizing, current_color)
+      (style, sizing, colors)
+    }
+
+    /// Applies the node's `zoom` to the parent sizing, including the inherited font size.
+    ///
+    /// Zoom scales lengths the same way the device pixel ratio does, but only for the subtree,
+    /// so viewport units, percentages and media queries are unaffected.
+    fn apply_zoom(parent_sizing: &Sizing, style: &ComputedStyle) -> Sizing {
+      // Zero and negative zoom are treated as no zoom.
+      let zoom = if *style.zoom > 0.0 { *style.zoom } else { 1.0 };
+
+      Sizing {
+        zoom: parent_sizing.zoom * zoom,
+        font_size: parent_sizing.font_size * zoom,
+        ..parent_sizing.clone()
+      }
+    }
+
+    /// Resolves `currentColor` and the backdrop behind the node's content.
+    fn resolve_colors(parent_context: &RenderContext, style: &ComputedStyle) -> (Color, Color) {
+      let backdrop_color = style
+        .background_color
+        .resolve(parent_context.current_color)
+        .composite_over(parent_context.backdrop_color);
+      let current_color = style
+        .color
+        .resolve_with_backdrop(parent_context.current_color, backdrop_color);
+
+      (current_color, backdrop_color)
     }
 
     fn build_pending_node<'g>(
@@ -955,10 +1250,13 @@ impl<'g> RenderNode<'g> {
       preorder_cursor: &mut usize,
     ) -> PendingRenderNode<'g> {
       let node_index = next_preorder_index(preorder_cursor);
-      let (style, sizing, current_color) =
+      if let NodeKind::Text(text) = &mut node.kind {
+        text.format_value(parent_context.locale.as_deref());
+      }
+      let (style, sizing, colors) =
         resolve_computed_style(parent_context, &mut node, node_index, matched_declarations);
       let (children_is_some, children) = take_children_vec(&mut node);
-      let context = build_render_context(parent_context, style, sizing, current_color);
+      let context = build_render_context(parent_context, style, sizing, colors);
 
       PendingRenderNode {
         context,
@@ -1195,7 +1493,7 @@ impl<'g> RenderNode<'g> {
         InlineLayoutStage::Measure,
       );
 
-      return measure_inline_layout(&mut layout, max_width);
+      return measure_inline_layout(&mut layout, max_width, &font_style);
     }
 
     assert_ne!(
@@ -1212,6 +1510,14 @@ impl<'g> RenderNode<'g> {
   }
 }
 
+/// Lines past the content box, or the line clamp, are not drawn.
+fn inline_max_height(font_style: &SizedFontStyle, layout: Layout) -> Option<MaxHeight> {
+  Some(match font_style.parent.line_clamp.as_ref() {
+    Some(clamp) => MaxHeight::HeightAndLines(layout.content_box_height(), clamp.count),
+    None => MaxHeight::Absolute(layout.content_box_height()),
+  })
+}
+
 fn flush_inline_group<'g>(
   inline_group: &mut Vec<RenderNode<'g>>,
   final_children: &mut Vec<RenderNode<'g>>,
@@ -1232,17 +1538,25 @@ mod tests {
   use cssparser::{Parser, ParserInput};
   use smallvec::smallvec;
 
+  use std::{str::FromStr, sync::Arc};
+
+  use image::{Rgba, RgbaImage};
+
+  use super::RenderNode;
   use super::build_inherited_style;
   use super::registered_custom_property_parent_style;
+  use crate::GlobalContext;
   use crate::layout::style::{
     LonghandId, PropertyRule, StyleDeclaration, StyleDeclarationBlock, StyleSheet,
     matching::MatchedDeclarations,
   };
   use crate::layout::{
     Viewport,
-    node::NodeStyleLayers,
-    style::{ComputedStyle, Length, Style},
+    node::{Node, NodeStyleLayers},
+    style::{Color, ColorInput, ComputedStyle, Length, Style},
   };
+  use crate::rendering::RenderContext;
+  use crate::resources::image::ImageSource;
 
   fn parse_stylesheet(css: &str) -> StyleSheet {
     let result = StyleSheet::parse(css);
@@ -1277,6 +1591,67 @@ mod tests {
     assert_eq!(resolved.width, Length::Px(30.0));
   }
 
+  fn contrast_auto_color_on(background: Color) -> Color {
+    let global = GlobalContext::default();
+    let context = RenderContext::new_test(&global, Viewport::new((100, 100)));
+    let node = Node::container([Node::container([])
+      .with_style(Style::default().with(StyleDeclaration::color(ColorInput::contrast_auto())))])
+    .with_style(Style::default().with(StyleDeclaration::background_color(background.into())));
+
+    let tree = RenderNode::from_node(&context, node);
+    let Some(child) = tree.children.as_deref().and_then(<[RenderNode]>::first) else {
+      unreachable!()
+    };
+
+    child.context.current_color
+  }
+
+  #[test]
+  fn palette_references_resolve_against_image_colors() {
+    let global = GlobalContext::default();
+    global.persistent_image_store().insert(
+      "cover".to_owned(),
+      Arc::new(ImageSource::Bitmap(RgbaImage::from_fn(10, 10, |x, _| {
+        if x < 7 {
+          Rgba([180, 20, 60, 255])
+        } else {
+          Rgba([240, 230, 200, 255])
+        }
+      }))),
+    );
+    let context = RenderContext::new_test(&global, Viewport::new((100, 100)));
+
+    let style = StyleDeclarationBlock::from_str(
+      "--accent: palette(url(cover), 1); background-color: palette(\"cover\"); color: var(--accent); border-color: palette(\"missing\")",
+    );
+    assert!(style.is_ok());
+    let Ok(style) = style else { unreachable!() };
+
+    let tree = RenderNode::from_node(&context, Node::container([]).with_style(style.into()));
+
+    assert_eq!(
+      tree.context.style.background_color,
+      ColorInput::Value(Color([180, 20, 60, 255]))
+    );
+    assert_eq!(tree.context.current_color, Color([240, 230, 200, 255]));
+    // Unknown images make the declaration invalid, like an unresolved `var()`.
+    assert_eq!(tree.context.style.border_color, ColorInput::CurrentColor);
+  }
+
+  #[test]
+  fn contrast_auto_picks_color_from_ancestor_background() {
+    assert_eq!(
+      contrast_auto_color_on(Color([20, 20, 80, 255])),
+      Color::white()
+    );
+    assert_eq!(
+      contrast_auto_color_on(Color([250, 220, 80, 255])),
+      Color::black()
+    );
+    // Mostly transparent backgrounds are judged against white.
+    assert_eq!(contrast_auto_color_on(Color([0, 0, 0, 20])), Color::black());
+  }
+
   #[test]
   fn registered_custom_property_can_disable_inheritance() {
     let mut parent = ComputedStyle::default();
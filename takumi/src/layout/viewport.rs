@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use taffy::{AvailableSpace, Size};
 
 /// The default font size in pixels.
@@ -16,6 +17,20 @@ pub struct Viewport {
   pub font_size: f32,
   /// The device pixel ratio.
   pub device_pixel_ratio: f32,
+  /// The color scheme that selects `dark` and `light` style blocks and matches
+  /// `prefers-color-scheme` media queries.
+  pub color_scheme: ColorScheme,
+}
+
+/// The preferred color scheme of a render.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorScheme {
+  /// A light theme.
+  #[default]
+  Light,
+  /// A dark theme.
+  Dark,
 }
 
 impl From<Viewport> for Size<AvailableSpace> {
@@ -48,6 +63,7 @@ impl Viewport {
       size: size.into(),
       font_size: DEFAULT_FONT_SIZE,
       device_pixel_ratio: DEFAULT_DEVICE_PIXEL_RATIO,
+      color_scheme: ColorScheme::Light,
     }
   }
 
@@ -62,6 +78,12 @@ impl Viewport {
     self.device_pixel_ratio = device_pixel_ratio;
     self
   }
+
+  /// Sets the color scheme.
+  pub const fn with_color_scheme(mut self, color_scheme: ColorScheme) -> Self {
+    self.color_scheme = color_scheme;
+    self
+  }
 }
 
 /// Represents Viewport size
@@ -72,6 +72,9 @@
 //! - `woff`: Enable WOFF font support.
 //! - `svg`: Enable SVG support.
 //! - `rayon`: Enable rayon support.
+//! - `avif`: Enable AVIF output with a pure Rust encoder, which also builds for WASM.
+//! - `reqwest`: Enable `ReqwestLoader` for fetching remote images with `RenderOptions::fetch_resources`.
+//! - `html`: Enable `import::html_to_node` for converting HTML or JSX markup into nodes.
 //!
 //! # Credits
 //!
@@ -92,21 +95,34 @@ pub mod rendering;
 
 /// Error handling types and utilities.
 pub mod error;
+/// Converters from markup written for other renderers into nodes.
+#[cfg(feature = "html")]
+pub mod import;
 /// Shared deserializers for structured and shorthand keyframe inputs.
 pub mod keyframes;
 /// External resource management (fonts, images)
 pub mod resources;
 
-use std::collections::HashSet;
+use std::{collections::HashMap, ops::Deref, sync::Arc};
 
-pub use error::{Result, StyleSheetParseError, TakumiError as Error};
+pub use error::{ErrorCode, Result, StyleSheetParseError, TakumiError as Error};
 
 pub use image;
 pub use parley;
 pub use taffy;
 use xxhash_rust::xxh3::Xxh3DefaultBuilder;
+pub use zeno;
 
-use crate::resources::{font::FontContext, image::PersistentImageStore};
+use crate::{
+  layout::node::{NodePlugin, NodePluginRegistry},
+  rendering::{CacheStats, GlyphCache, TextRenderingOptions},
+  resources::{
+    emoji::{EmojiSource, EmojiStore},
+    font::{FontContext, FontError, FontResource, MissingFontPolicy},
+    image::{ImageSource, PersistentImageStore},
+    image_cache::ImageDecodeCache,
+  },
+};
 
 /// The main context for image rendering.
 ///
@@ -118,9 +134,26 @@ pub struct GlobalContext {
   font_context: FontContext,
   /// The image store for persisting contents
   persistent_image_store: PersistentImageStore,
+  /// The plugins drawing nodes of custom types
+  node_plugins: NodePluginRegistry,
+  /// The pictures drawn in place of emoji glyphs
+  emoji_store: EmojiStore,
+  /// Scaled glyphs reused across renders, shared with scoped contexts
+  glyph_cache: Arc<GlyphCache>,
+  /// Decoded fetched images reused across renders, shared with scoped contexts
+  image_cache: Arc<ImageDecodeCache>,
+  /// What happens to text no font covers, unless a render overrides it
+  missing_font_policy: MissingFontPolicy,
+  /// How glyphs are rasterized
+  text_rendering: TextRenderingOptions,
 }
 
 impl GlobalContext {
+  /// Starts configuring a context with fonts, fallbacks, images, plugins and emoji in one place.
+  pub fn builder<'a>() -> GlobalContextBuilder<'a> {
+    GlobalContextBuilder::default()
+  }
+
   /// Returns a reference to the font context.
   #[inline]
   pub fn font_context(&self) -> &FontContext {
@@ -144,7 +177,248 @@ impl GlobalContext {
   pub fn persistent_image_store_mut(&mut self) -> &mut PersistentImageStore {
     &mut self.persistent_image_store
   }
+
+  /// Returns a reference to the node plugin registry.
+  #[inline]
+  pub fn node_plugins(&self) -> &NodePluginRegistry {
+    &self.node_plugins
+  }
+
+  /// Returns a mutable reference to the node plugin registry.
+  #[inline]
+  pub fn node_plugins_mut(&mut self) -> &mut NodePluginRegistry {
+    &mut self.node_plugins
+  }
+
+  /// Returns a reference to the emoji store.
+  #[inline]
+  pub fn emoji_store(&self) -> &EmojiStore {
+    &self.emoji_store
+  }
+
+  /// Returns a mutable reference to the emoji store.
+  #[inline]
+  pub fn emoji_store_mut(&mut self) -> &mut EmojiStore {
+    &mut self.emoji_store
+  }
+
+  /// Returns a reference to the glyph cache.
+  #[inline]
+  pub fn glyph_cache(&self) -> &GlyphCache {
+    &self.glyph_cache
+  }
+
+  /// Returns a reference to the image decode cache.
+  #[inline]
+  pub fn image_cache(&self) -> &ImageDecodeCache {
+    &self.image_cache
+  }
+
+  /// Returns what happens to text no loaded font or fallback covers.
+  #[inline]
+  pub fn missing_font_policy(&self) -> MissingFontPolicy {
+    self.missing_font_policy
+  }
+
+  /// Sets what happens to text no loaded font or fallback covers.
+  /// [`RenderOptions`](crate::rendering::RenderOptions) can override it per render.
+  #[inline]
+  pub fn set_missing_font_policy(&mut self, policy: MissingFontPolicy) {
+    self.missing_font_policy = policy;
+  }
+
+  /// Returns how glyphs are rasterized.
+  #[inline]
+  pub fn text_rendering(&self) -> TextRenderingOptions {
+    self.text_rendering
+  }
+
+  /// Sets how glyphs are rasterized, see [`TextRenderingOptions`].
+  #[inline]
+  pub fn set_text_rendering(&mut self, options: TextRenderingOptions) {
+    self.text_rendering = options;
+  }
+
+  /// Unloads every font registered under `family`, returning whether any was loaded.
+  ///
+  /// Glyphs cached for those fonts are dropped along with them, so a long-running process can
+  /// swap fonts without holding on to the old ones. Fallback chains naming `family` are kept.
+  pub fn unload_font_family(&mut self, family: &str) -> bool {
+    let Some(font_ids) = self.font_context.unload(family) else {
+      return false;
+    };
+
+    self.glyph_cache.remove_fonts(&font_ids);
+    true
+  }
+
+  /// Replaces the fonts of `family` with `font`, registered under the same family name.
+  ///
+  /// The current fonts are kept if `font` fails to load.
+  pub fn replace_font_family<'a>(
+    &mut self,
+    family: &'a str,
+    font: FontResource<'a>,
+  ) -> std::result::Result<(), FontError> {
+    let font = font.family_name(family).into_resolved()?;
+
+    self.unload_font_family(family);
+    self.font_context.load_and_store(font)
+  }
+
+  /// Creates a context that sees `fonts` on top of the fonts loaded here, for renders that need
+  /// fonts no other render should see, such as ones uploaded by a tenant.
+  ///
+  /// Images, plugins, emoji and the glyph cache are shared with this context, which is left
+  /// untouched. Dropping the returned context releases the fonts and their cached glyphs.
+  pub fn with_scoped_fonts<'a>(
+    &self,
+    fonts: impl IntoIterator<Item = FontResource<'a>>,
+  ) -> std::result::Result<ScopedGlobalContext, FontError> {
+    let mut context = GlobalContext {
+      font_context: self.font_context.clone(),
+      persistent_image_store: self.persistent_image_store.clone(),
+      node_plugins: self.node_plugins.clone(),
+      emoji_store: self.emoji_store.share(),
+      glyph_cache: Arc::clone(&self.glyph_cache),
+      image_cache: Arc::clone(&self.image_cache),
+      missing_font_policy: self.missing_font_policy,
+      text_rendering: self.text_rendering,
+    };
+
+    let mut font_ids = Vec::new();
+    for font in fonts {
+      font_ids.extend(context.font_context.load(font)?);
+    }
+
+    Ok(ScopedGlobalContext { context, font_ids })
+  }
+
+  /// Returns the statistics of the caches kept across renders.
+  pub fn cache_stats(&self) -> CacheStats {
+    CacheStats {
+      glyphs: self.glyph_cache.stats(),
+      images: self.image_cache.stats(),
+    }
+  }
+}
+
+/// A [`GlobalContext`] with fonts only renders through it can see, created with
+/// [`GlobalContext::with_scoped_fonts`].
+pub struct ScopedGlobalContext {
+  context: GlobalContext,
+  /// Font data loaded for this context only, whose glyphs are dropped from the shared cache.
+  font_ids: Vec<u64>,
+}
+
+impl Deref for ScopedGlobalContext {
+  type Target = GlobalContext;
+
+  fn deref(&self) -> &Self::Target {
+    &self.context
+  }
+}
+
+impl Drop for ScopedGlobalContext {
+  fn drop(&mut self) {
+    self.context.glyph_cache.remove_fonts(&self.font_ids);
+  }
+}
+
+/// Builder for [`GlobalContext`], created with [`GlobalContext::builder`].
+///
+/// Fonts are loaded when [`GlobalContextBuilder::build`] is called, everything else is applied as
+/// it's set.
+#[derive(Default)]
+#[must_use]
+pub struct GlobalContextBuilder<'a> {
+  context: GlobalContext,
+  fonts: Vec<FontResource<'a>>,
+}
+
+impl<'a> GlobalContextBuilder<'a> {
+  /// Adds a font to load.
+  pub fn font(mut self, font: FontResource<'a>) -> Self {
+    self.fonts.push(font);
+    self
+  }
+
+  /// Adds fonts to load.
+  pub fn fonts(mut self, fonts: impl IntoIterator<Item = FontResource<'a>>) -> Self {
+    self.fonts.extend(fonts);
+    self
+  }
+
+  /// Sets the fallback chain for a family, see [`FontContext::set_fallbacks`].
+  pub fn fallbacks<I, S>(mut self, family: &str, fallbacks: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<Box<str>>,
+  {
+    self.context.font_context.set_fallbacks(family, fallbacks);
+    self
+  }
+
+  /// Stores a persistent image under `src`.
+  pub fn persistent_image(self, src: impl Into<String>, image: Arc<ImageSource>) -> Self {
+    self
+      .context
+      .persistent_image_store
+      .insert(src.into(), image);
+    self
+  }
+
+  /// Registers a plugin for a node type, see [`NodePluginRegistry::register`].
+  pub fn node_plugin(
+    mut self,
+    type_name: impl Into<Box<str>>,
+    plugin: impl NodePlugin + 'static,
+  ) -> Self {
+    self.context.node_plugins.register(type_name, plugin);
+    self
+  }
+
+  /// Sets the source of emoji pictures.
+  pub fn emoji_source(mut self, source: impl EmojiSource + 'static) -> Self {
+    self.context.emoji_store.set_source(source);
+    self
+  }
+
+  /// Sets the byte budget of the glyph cache, see [`GlyphCache`].
+  pub fn glyph_cache_budget(self, budget: usize) -> Self {
+    self.context.glyph_cache.set_budget(budget);
+    self
+  }
+
+  /// Sets the byte budget of the image decode cache, see [`ImageDecodeCache`].
+  pub fn image_cache_budget(self, budget: usize) -> Self {
+    self.context.image_cache.set_budget(budget);
+    self
+  }
+
+  /// Sets what happens to text no font covers, see [`MissingFontPolicy`].
+  pub fn missing_font_policy(mut self, policy: MissingFontPolicy) -> Self {
+    self.context.missing_font_policy = policy;
+    self
+  }
+
+  /// Sets how glyphs are rasterized, see [`TextRenderingOptions`].
+  pub fn text_rendering(mut self, options: TextRenderingOptions) -> Self {
+    self.context.text_rendering = options;
+    self
+  }
+
+  /// Loads the fonts and returns the context, stopping at the first font that fails to load.
+  pub fn build(self) -> std::result::Result<GlobalContext, FontError> {
+    let Self { mut context, fonts } = self;
+
+    for font in fonts {
+      context.font_context.load_and_store(font)?;
+    }
+
+    Ok(context)
+  }
 }
 
 /// Type alias for HashSet using XXH3 hasher
-pub(crate) type Xxh3HashSet<T> = HashSet<T, Xxh3DefaultBuilder>;
+pub(crate) type Xxh3HashMap<K, V> = HashMap<K, V, Xxh3DefaultBuilder>;
@@ -2,14 +2,14 @@ use std::iter::successors;
 
 use image::{GenericImageView, Rgba, RgbaImage};
 use smallvec::{SmallVec, smallvec};
-use taffy::Size;
+use taffy::{Layout, Rect, Size};
 
 use crate::{
   Result,
   layout::{node::resolve_image, style::*},
   rendering::{
-    BorderProperties, BufferPool, MaskMemory, RenderContext, Sizing, overlay_gradient_tile,
-    overlay_image,
+    BorderProperties, BufferPool, MaskMemory, RenderContext, Sizing, fast_div_255,
+    overlay_gradient_tile, overlay_image,
   },
 };
 
@@ -22,6 +22,58 @@ pub(crate) struct TileLayer {
 
 pub(crate) type TileLayers = Vec<TileLayer>;
 
+/// The box a layer is positioned against, relative to the border box it paints.
+#[derive(Clone, Copy)]
+pub(crate) struct PositioningArea {
+  pub x: i32,
+  pub y: i32,
+  pub size: Size<u32>,
+}
+
+/// Border and padding widths that separate the `background-origin` boxes.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct BoxInsets {
+  pub border: Rect<f32>,
+  pub padding: Rect<f32>,
+}
+
+impl From<&Layout> for BoxInsets {
+  fn from(layout: &Layout) -> Self {
+    Self {
+      border: layout.border,
+      padding: layout.padding,
+    }
+  }
+}
+
+impl BoxInsets {
+  pub(crate) fn positioning_area(
+    self,
+    origin: BackgroundOrigin,
+    border_box: Size<u32>,
+  ) -> PositioningArea {
+    let inset = match origin {
+      BackgroundOrigin::BorderBox => Rect::zero(),
+      BackgroundOrigin::PaddingBox => self.border,
+      BackgroundOrigin::ContentBox => Rect {
+        left: self.border.left + self.padding.left,
+        right: self.border.right + self.padding.right,
+        top: self.border.top + self.padding.top,
+        bottom: self.border.bottom + self.padding.bottom,
+      },
+    };
+
+    PositioningArea {
+      x: inset.left as i32,
+      y: inset.top as i32,
+      size: Size {
+        width: (border_box.width as f32 - inset.left - inset.right).max(0.0) as u32,
+        height: (border_box.height as f32 - inset.top - inset.bottom).max(0.0) as u32,
+      },
+    }
+  }
+}
+
 fn should_rasterize_repeated_tile(
   tile: &BackgroundTile,
   xs: &SmallVec<[i32; 1]>,
@@ -192,14 +244,27 @@ impl BackgroundTile {
   }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn resolve_axis_tiles(
   repeat: BackgroundRepeatStyle,
   pos: BackgroundPosition,
   tile_size: u32,
+  start: i32,
   area_size: u32,
+  paint_size: u32,
   sizing: &Sizing,
   is_x: bool,
 ) -> (SmallVec<[i32; 1]>, u32) {
+  let shift = |(positions, tile_size): (SmallVec<[i32; 1]>, u32)| {
+    (
+      positions
+        .into_iter()
+        .map(|position| position + start)
+        .collect(),
+      tile_size,
+    )
+  };
+
   match repeat {
     BackgroundRepeatStyle::Repeat => {
       let origin = if is_x {
@@ -208,7 +273,7 @@ fn resolve_axis_tiles(
         resolve_position_component_y(pos, tile_size, area_size, sizing)
       };
       (
-        collect_repeat_tile_positions(area_size, tile_size, origin),
+        collect_repeat_tile_positions(paint_size, tile_size, start + origin),
         tile_size,
       )
     }
@@ -218,13 +283,13 @@ fn resolve_axis_tiles(
       } else {
         resolve_position_component_y(pos, tile_size, area_size, sizing)
       };
-      (smallvec![origin], tile_size)
+      (smallvec![start + origin], tile_size)
     }
-    BackgroundRepeatStyle::Space => (
+    BackgroundRepeatStyle::Space => shift((
       collect_spaced_tile_positions(area_size, tile_size),
       tile_size,
-    ),
-    BackgroundRepeatStyle::Round => collect_stretched_tile_positions(area_size, tile_size),
+    )),
+    BackgroundRepeatStyle::Round => shift(collect_stretched_tile_positions(area_size, tile_size)),
   }
 }
 
@@ -344,10 +409,16 @@ pub(crate) fn resolve_layer_tiles(
   size: BackgroundSize,
   repeat: BackgroundRepeat,
   blend_mode: BlendMode,
-  area: Size<u32>,
+  area: PositioningArea,
+  paint_size: Size<u32>,
   context: &RenderContext,
   buffer_pool: &mut BufferPool,
 ) -> Result<Option<TileLayer>> {
+  let PositioningArea {
+    x: start_x,
+    y: start_y,
+    size: area,
+  } = area;
   let resolved_size = size.resolve(
     area,
     &context.sizing,
@@ -364,7 +435,9 @@ pub(crate) fn resolve_layer_tiles(
         repeat.1,
         pos,
         resolved_size.height,
+        start_y,
         area.height,
+        paint_size.height,
         &context.sizing,
         false,
       );
@@ -378,8 +451,16 @@ pub(crate) fn resolve_layer_tiles(
       } else {
         resolved_size.width
       };
-      let (xs, tile_w) =
-        resolve_axis_tiles(repeat.0, pos, tile_w, area.width, &context.sizing, true);
+      let (xs, tile_w) = resolve_axis_tiles(
+        repeat.0,
+        pos,
+        tile_w,
+        start_x,
+        area.width,
+        paint_size.width,
+        &context.sizing,
+        true,
+      );
       (xs, ys, tile_w, tile_h)
     }
     Some(AutoBackgroundAxis::Height) => {
@@ -387,7 +468,9 @@ pub(crate) fn resolve_layer_tiles(
         repeat.0,
         pos,
         resolved_size.width,
+        start_x,
         area.width,
+        paint_size.width,
         &context.sizing,
         true,
       );
@@ -401,8 +484,16 @@ pub(crate) fn resolve_layer_tiles(
       } else {
         resolved_size.height
       };
-      let (ys, tile_h) =
-        resolve_axis_tiles(repeat.1, pos, tile_h, area.height, &context.sizing, false);
+      let (ys, tile_h) = resolve_axis_tiles(
+        repeat.1,
+        pos,
+        tile_h,
+        start_y,
+        area.height,
+        paint_size.height,
+        &context.sizing,
+        false,
+      );
       (xs, ys, tile_w, tile_h)
     }
     None => {
@@ -410,7 +501,9 @@ pub(crate) fn resolve_layer_tiles(
         repeat.0,
         pos,
         resolved_size.width,
+        start_x,
         area.width,
+        paint_size.width,
         &context.sizing,
         true,
       );
@@ -418,7 +511,9 @@ pub(crate) fn resolve_layer_tiles(
         repeat.1,
         pos,
         resolved_size.height,
+        start_y,
         area.height,
+        paint_size.height,
         &context.sizing,
         false,
       );
@@ -524,14 +619,17 @@ pub(crate) fn resolve_tile_layers(
   sizes: &[BackgroundSize],
   repeats: &[BackgroundRepeat],
   blend_modes: &[BlendMode],
+  origins: &[BackgroundOrigin],
   context: &RenderContext,
   border_box: Size<u32>,
+  insets: BoxInsets,
   buffer_pool: &mut BufferPool,
 ) -> Result<TileLayers> {
   let last_position = positions.last().copied().unwrap_or_default();
   let last_size = sizes.last().copied().unwrap_or_default();
   let last_repeat = repeats.last().copied().unwrap_or_default();
   let last_blend_mode = blend_modes.last().copied().unwrap_or_default();
+  let last_origin = origins.last().copied().unwrap_or_default();
 
   let mut results = Vec::new();
   for (i, image) in images.iter().enumerate().rev() {
@@ -539,6 +637,7 @@ pub(crate) fn resolve_tile_layers(
     let size = sizes.get(i).copied().unwrap_or(last_size);
     let repeat = repeats.get(i).copied().unwrap_or(last_repeat);
     let blend_mode = blend_modes.get(i).copied().unwrap_or(last_blend_mode);
+    let origin = origins.get(i).copied().unwrap_or(last_origin);
 
     results.push(resolve_layer_tiles(
       image,
@@ -546,6 +645,7 @@ pub(crate) fn resolve_tile_layers(
       size,
       repeat,
       blend_mode,
+      insets.positioning_area(origin, border_box),
       border_box,
       context,
       buffer_pool,
@@ -565,15 +665,83 @@ pub(crate) fn create_mask(
   let mask_position = context.style.mask_position.as_ref();
   let mask_size = context.style.mask_size.as_ref();
   let mask_repeat = context.style.mask_repeat.as_ref();
+  let modes = (0..mask_image.len()).map(|i| layer_value(&context.style.mask_mode, i));
+
+  // Alpha masks can be stacked as images, but luminance has to be taken from each layer first.
+  if !modes.clone().any(|mode| mode == MaskMode::Luminance) {
+    return rasterize_mask(
+      mask_image,
+      mask_position,
+      mask_size,
+      mask_repeat,
+      MaskMode::Alpha,
+      context,
+      border_box,
+      mask_memory,
+      buffer_pool,
+    );
+  }
+
+  let mut mask: Option<Vec<u8>> = None;
+
+  for (i, mode) in modes.enumerate().rev() {
+    let Some(values) = rasterize_mask(
+      &mask_image[i..=i],
+      &[layer_value(mask_position, i)],
+      &[layer_value(mask_size, i)],
+      &[layer_value(mask_repeat, i)],
+      mode,
+      context,
+      border_box,
+      mask_memory,
+      buffer_pool,
+    )?
+    else {
+      continue;
+    };
+
+    match &mut mask {
+      Some(mask) => {
+        for (below, &above) in mask.iter_mut().zip(&values) {
+          *below = above + fast_div_255(*below as u32 * (255 - above as u32));
+        }
+        buffer_pool.release(values);
+      }
+      None => mask = Some(values),
+    }
+  }
+
+  Ok(mask)
+}
+
+/// Returns the value for layer `i`, repeating the last value for layers beyond the list.
+fn layer_value<T: Copy + Default>(values: &[T], i: usize) -> T {
+  values.get(i).or(values.last()).copied().unwrap_or_default()
+}
 
+/// Rasterizes mask layers and reads one mask value per pixel according to `mode`.
+#[allow(clippy::too_many_arguments)]
+fn rasterize_mask(
+  images: &[BackgroundImage],
+  positions: &[BackgroundPosition],
+  sizes: &[BackgroundSize],
+  repeats: &[BackgroundRepeat],
+  mode: MaskMode,
+  context: &RenderContext,
+  border_box: Size<f32>,
+  mask_memory: &mut MaskMemory,
+  buffer_pool: &mut BufferPool,
+) -> Result<Option<Vec<u8>>> {
   let layers = resolve_tile_layers(
-    mask_image,
-    mask_position,
-    mask_size,
-    mask_repeat,
+    images,
+    positions,
+    sizes,
+    repeats,
     &[], // no blending mode for mask
+    &[BackgroundOrigin::BorderBox],
     context,
     border_box.map(|x| x as u32),
+    BoxInsets::default(),
     buffer_pool,
   )?;
 
@@ -581,6 +749,15 @@ pub(crate) fn create_mask(
     return Ok(None);
   }
 
+  let mask_value = |[red, green, blue, alpha]: [u8; 4]| match mode {
+    MaskMode::Luminance => {
+      // Rec. 709 luma coefficients scaled to 256.
+      let luminance = (red as u32 * 54 + green as u32 * 183 + blue as u32 * 19) >> 8;
+      fast_div_255(luminance * alpha as u32)
+    }
+    MaskMode::Alpha | MaskMode::MatchSource => alpha,
+  };
+
   Ok(
     rasterize_layers(
       layers,
@@ -593,28 +770,28 @@ pub(crate) fn create_mask(
     )?
     .map(|tile| {
       let (w, h) = tile.dimensions();
-      let mut alpha = buffer_pool.acquire_dirty((w * h) as usize);
+      let mut values = buffer_pool.acquire_dirty((w * h) as usize);
 
       if let Some(raw) = tile.as_raw() {
-        let count = alpha.len().min(raw.len() / 4);
+        let count = values.len().min(raw.len() / 4);
         for i in 0..count {
-          alpha[i] = raw[i * 4 + 3];
+          values[i] = mask_value([raw[i * 4], raw[i * 4 + 1], raw[i * 4 + 2], raw[i * 4 + 3]]);
         }
-        for alpha_val in alpha.iter_mut().skip(count) {
-          *alpha_val = 0;
+        for value in values.iter_mut().skip(count) {
+          *value = 0;
         }
       } else {
         let mut i = 0;
         for y in 0..h {
           for x in 0..w {
-            if i < alpha.len() {
-              alpha[i] = tile.get_pixel(x, y).0[3];
+            if i < values.len() {
+              values[i] = mask_value(tile.get_pixel(x, y).0);
               i += 1;
             }
           }
         }
-        for alpha_val in alpha.iter_mut().skip(i) {
-          *alpha_val = 0;
+        for value in values.iter_mut().skip(i) {
+          *value = 0;
         }
       }
 
@@ -622,40 +799,71 @@ pub(crate) fn create_mask(
         buffer_pool.release_image(image);
       }
 
-      alpha
+      values
     }),
   )
 }
 
+/// Returns `true` if a tile of any layer reaches outside a box of `size`.
+pub(crate) fn layers_overflow(layers: &[TileLayer], size: Size<u32>) -> bool {
+  layers.iter().any(|layer| {
+    let (width, height) = layer.tile.dimensions();
+
+    layer
+      .xs
+      .iter()
+      .any(|&x| x < 0 || x + width as i32 > size.width as i32)
+      || layer
+        .ys
+        .iter()
+        .any(|&y| y < 0 || y + height as i32 > size.height as i32)
+  })
+}
+
+/// Returns the viewport relative to a border box painted with `transform`, which
+/// `background-attachment: fixed` layers are positioned against.
+///
+/// Boxes painted with a transform other than a translation get no viewport, so their fixed layers
+/// are positioned like `scroll`, the way browsers treat fixed backgrounds of transformed elements.
+pub(crate) fn viewport_area(transform: Affine, viewport: Size<u32>) -> Option<PositioningArea> {
+  transform.only_translation().then(|| PositioningArea {
+    x: -transform.x.round() as i32,
+    y: -transform.y.round() as i32,
+    size: viewport,
+  })
+}
+
+/// Collects the background color and image layers bottom first, grouped into runs of
+/// consecutive layers that share a `background-clip`.
+///
+/// The color is clipped by the clip of the bottom image layer.
 pub(crate) fn collect_background_layers(
   context: &RenderContext,
   border_box: Size<f32>,
+  insets: BoxInsets,
+  viewport: Option<PositioningArea>,
   buffer_pool: &mut BufferPool,
-) -> Result<TileLayers> {
-  let mut layers = resolve_tile_layers(
-    context.style.background_image.as_deref().unwrap_or(&[]),
-    &context.style.background_position,
-    &context.style.background_size,
-    &context.style.background_repeat,
-    &context.style.background_blend_mode,
-    context,
-    border_box.map(|x| x as u32),
-    buffer_pool,
-  )?;
+) -> Result<Vec<(BackgroundClip, TileLayers)>> {
+  let style = &context.style;
+  let images = style.background_image.as_deref().unwrap_or(&[]);
+  let border_box = border_box.map(|x| x as u32);
+
+  let mut runs: Vec<(BackgroundClip, TileLayers)> = Vec::new();
+  let mut push = |clip: BackgroundClip, layer: TileLayer| match runs.last_mut() {
+    Some((last_clip, layers)) if *last_clip == clip => layers.push(layer),
+    _ => runs.push((clip, vec![layer])),
+  };
 
-  let background_color = context
-    .style
-    .background_color
-    .resolve(context.current_color);
+  let background_color = style.background_color.resolve(context.current_color);
 
   if background_color.0[3] > 0 {
-    layers.insert(
-      0,
+    push(
+      layer_value(&style.background_clip, images.len().saturating_sub(1)),
       TileLayer {
         tile: BackgroundTile::Color(ColorTile {
           color: background_color.into(),
-          width: border_box.width as u32,
-          height: border_box.height as u32,
+          width: border_box.width,
+          height: border_box.height,
         }),
         xs: [0].into(),
         ys: [0].into(),
@@ -664,7 +872,46 @@ pub(crate) fn collect_background_layers(
     );
   }
 
-  Ok(layers)
+  for (i, image) in images.iter().enumerate().rev() {
+    let area = match (layer_value(&style.background_attachment, i), viewport) {
+      (BackgroundAttachment::Fixed, Some(viewport)) => viewport,
+      _ => insets.positioning_area(layer_value(&style.background_origin, i), border_box),
+    };
+
+    if let Some(layer) = resolve_layer_tiles(
+      image,
+      layer_value(&style.background_position, i),
+      layer_value(&style.background_size, i),
+      layer_value(&style.background_repeat, i),
+      layer_value(&style.background_blend_mode, i),
+      area,
+      border_box,
+      context,
+      buffer_pool,
+    )? {
+      push(layer_value(&style.background_clip, i), layer);
+    }
+  }
+
+  Ok(runs)
+}
+
+/// Collects the background layers painted with `clip`, bottom first.
+pub(crate) fn collect_clipped_background_layers(
+  context: &RenderContext,
+  clip: BackgroundClip,
+  border_box: Size<f32>,
+  insets: BoxInsets,
+  viewport: Option<PositioningArea>,
+  buffer_pool: &mut BufferPool,
+) -> Result<TileLayers> {
+  Ok(
+    collect_background_layers(context, border_box, insets, viewport, buffer_pool)?
+      .into_iter()
+      .filter(|(run_clip, _)| *run_clip == clip)
+      .flat_map(|(_, layers)| layers)
+      .collect(),
+  )
 }
 
 #[cfg(test)]
@@ -675,14 +922,17 @@ mod tests {
 
   use super::{resolve_position_component_x, resolve_position_component_y};
   use crate::{
+    GlobalContext,
     layout::{
       Viewport,
+      node::Node,
       style::{
-        BackgroundPosition, CalcArena, Length, PositionComponent, PositionKeywordX,
-        PositionKeywordY, SpacePair,
+        BackgroundAttachment, BackgroundClip, BackgroundImages, BackgroundPosition, CalcArena,
+        Display, FromCss, Length, PositionComponent, PositionKeywordX, PositionKeywordY, Sides,
+        SpacePair, Style, StyleDeclaration,
       },
     },
-    rendering::Sizing,
+    rendering::{RenderOptions, Sizing, render},
   };
 
   fn test_sizing() -> Sizing {
@@ -691,6 +941,7 @@ mod tests {
       viewport,
       container_size: Size::NONE,
       font_size: viewport.font_size,
+      zoom: 1.0,
       calc_arena: Rc::new(CalcArena::default()),
     }
   }
@@ -730,4 +981,86 @@ mod tests {
       -30
     );
   }
+
+  fn background_images(css: &str) -> Option<BackgroundImages> {
+    BackgroundImages::from_str(css).ok()
+  }
+
+  #[test]
+  fn background_clip_applies_per_layer() {
+    let global = GlobalContext::default();
+    let node = Node::container([]).with_style(
+      Style::default()
+        .with(StyleDeclaration::width(Length::Px(30.0)))
+        .with(StyleDeclaration::height(Length::Px(30.0)))
+        .with_padding(Sides::from(Length::Px(5.0)))
+        .with(StyleDeclaration::background_image(background_images(
+          "linear-gradient(red, red), linear-gradient(blue, blue)",
+        )))
+        .with(StyleDeclaration::background_clip(
+          [BackgroundClip::ContentBox, BackgroundClip::BorderBox].into(),
+        )),
+    );
+
+    let image = render(
+      RenderOptions::builder()
+        .global(&global)
+        .viewport(Viewport::new((30, 30)))
+        .node(node)
+        .build(),
+    );
+
+    assert_eq!(
+      image.as_ref().ok().map(|image| image.get_pixel(1, 1).0),
+      Some([0, 0, 255, 255])
+    );
+    assert_eq!(
+      image.as_ref().ok().map(|image| image.get_pixel(15, 15).0),
+      Some([255, 0, 0, 255])
+    );
+  }
+
+  #[test]
+  fn fixed_background_is_positioned_against_the_viewport() {
+    let global = GlobalContext::default();
+    let render_with = |attachment: BackgroundAttachment| {
+      let child = Node::container([]).with_style(
+        Style::default()
+          .with(StyleDeclaration::width(Length::Px(20.0)))
+          .with(StyleDeclaration::height(Length::Px(20.0)))
+          .with(StyleDeclaration::background_image(background_images(
+            "linear-gradient(to right, red 50%, blue 50%)",
+          )))
+          .with(StyleDeclaration::background_attachment([attachment].into())),
+      );
+      let node = Node::container([child]).with_style(
+        Style::default()
+          .with(StyleDeclaration::display(Display::Flex))
+          .with(StyleDeclaration::width(Length::Px(60.0)))
+          .with(StyleDeclaration::height(Length::Px(60.0)))
+          .with_padding(Sides::from(Length::Px(20.0))),
+      );
+
+      render(
+        RenderOptions::builder()
+          .global(&global)
+          .viewport(Viewport::new((40, 40)))
+          .node(node)
+          .build(),
+      )
+      .ok()
+      .map(|image| (image.get_pixel(22, 30).0, image.get_pixel(10, 30).0))
+    };
+
+    // The left edge of the box is in the right half of the viewport, and the layer stays
+    // clipped to the box.
+    assert_eq!(
+      render_with(BackgroundAttachment::Fixed),
+      Some(([0, 0, 255, 255], [0, 0, 0, 0]))
+    );
+    assert_eq!(
+      render_with(BackgroundAttachment::Scroll),
+      Some(([255, 0, 0, 255], [0, 0, 0, 0]))
+    );
+  }
 }
@@ -3,14 +3,19 @@
 //! This module provides performance-optimized canvas operations including
 //! fast image blending and pixel manipulation operations.
 
-use std::{borrow::Cow, mem::replace};
+use std::{
+  borrow::Cow,
+  collections::{HashMap, HashSet},
+  mem::replace,
+  time::Duration,
+};
 
 use image::{
   GenericImageView, ImageError, Rgba, RgbaImage,
   error::{ParameterError, ParameterErrorKind},
 };
 use smallvec::SmallVec;
-use taffy::{Layout, Point, Size};
+use taffy::{Layout, Point, Rect, Size};
 use zeno::{Command, Mask, Placement, Scratch};
 
 use crate::{Result, layout::style::BlendMode};
@@ -19,7 +24,10 @@ use crate::{
     Affine, Color, ComputedStyle, GradientOverlayTile, ImageScalingAlgorithm, Overflow,
     compute_overlay_bounds, overlay_gradient_tile_fast_normal_unconstrained,
   },
-  rendering::{BorderProperties, RenderContext, blend_pixel, create_mask, fast_div_255},
+  rendering::{
+    BorderProperties, GlyphOutline, LayerCache, RenderContext, blend_pixel, create_mask,
+    fast_div_255,
+  },
 };
 
 #[derive(Clone)]
@@ -123,7 +131,11 @@ pub(crate) enum CanvasConstrain {
     from: Point<u32>,
     to: Point<u32>,
     inverse_transform: Affine,
-    border_radius_mask: Option<(Vec<u8>, u32)>,
+  },
+  /// An overflow clip rendered in canvas space, for rounded or rotated boxes.
+  OverflowMask {
+    mask: Vec<u8>,
+    placement: Placement,
   },
   ClipPath {
     mask: Vec<u8>,
@@ -135,6 +147,93 @@ pub(crate) enum CanvasConstrain {
     to: Point<u32>,
     inverse_transform: Affine,
   },
+  /// An overflow clip whose content fades out toward the edges it overflows, for `fade-edges`.
+  FadeEdges {
+    clip: Box<CanvasConstrain>,
+    fade: EdgeFade,
+    inverse_transform: Affine,
+  },
+}
+
+/// Linear alpha ramps inside the padding box of a node, in its local coordinates.
+#[derive(Clone, Copy)]
+pub(crate) struct EdgeFade {
+  from: Point<f32>,
+  to: Point<f32>,
+  /// The fade length of each edge, `0.0` for edges that don't fade.
+  lengths: Rect<f32>,
+}
+
+impl EdgeFade {
+  /// Resolves the fade of `style`, on the edges in `overflowing` that content is clipped at.
+  fn from_style(
+    context: &RenderContext,
+    style: &ComputedStyle,
+    layout: Layout,
+    overflowing: Rect<bool>,
+  ) -> Option<Self> {
+    let fade_edges = style.fade_edges;
+
+    if fade_edges.is_none() {
+      return None;
+    }
+
+    let overflow = style.resolve_overflows();
+    let clip_x = overflow.x != Overflow::Visible;
+    let clip_y = overflow.y != Overflow::Visible;
+
+    let width = layout.size.width - layout.border.left - layout.border.right;
+    let height = layout.size.height - layout.border.top - layout.border.bottom;
+
+    let [top, right, bottom, left] = fade_edges.edges.0;
+    let length = |fades: bool, clips: bool, overflows: bool, full: f32| {
+      if fades && clips && overflows {
+        fade_edges.length.to_px(&context.sizing, full).max(0.0)
+      } else {
+        0.0
+      }
+    };
+
+    let lengths = Rect {
+      top: length(top, clip_y, overflowing.top, height),
+      right: length(right, clip_x, overflowing.right, width),
+      bottom: length(bottom, clip_y, overflowing.bottom, height),
+      left: length(left, clip_x, overflowing.left, width),
+    };
+
+    if lengths.top + lengths.right + lengths.bottom + lengths.left <= 0.0 {
+      return None;
+    }
+
+    Some(Self {
+      from: Point {
+        x: layout.border.left,
+        y: layout.border.top,
+      },
+      to: Point {
+        x: layout.border.left + width,
+        y: layout.border.top + height,
+      },
+      lengths,
+    })
+  }
+
+  fn get_alpha(&self, point: Point<f32>) -> u8 {
+    let ramp = |distance: f32, length: f32| {
+      if length > 0.0 {
+        (distance / length).clamp(0.0, 1.0)
+      } else {
+        1.0
+      }
+    };
+
+    let alpha = ramp(point.y - self.from.y, self.lengths.top)
+      * ramp(self.to.x - point.x, self.lengths.right)
+      * ramp(self.to.y - point.y, self.lengths.bottom)
+      * ramp(point.x - self.from.x, self.lengths.left);
+
+    (alpha * 255.0).round() as u8
+  }
 }
 
 impl CanvasConstrain {
@@ -143,6 +242,7 @@ impl CanvasConstrain {
     style: &ComputedStyle,
     layout: Layout,
     transform: Affine,
+    overflowing: Rect<bool>,
     mask_memory: &mut MaskMemory,
     buffer_pool: &mut BufferPool,
   ) -> Result<CanvasConstrainResult> {
@@ -189,56 +289,71 @@ impl CanvasConstrain {
       return Ok(CanvasConstrainResult::None);
     }
 
+    let fade = EdgeFade::from_style(context, style, layout, overflowing);
+    let with_fade = |clip: CanvasConstrain| match fade {
+      Some(fade) => CanvasConstrain::FadeEdges {
+        clip: Box::new(clip),
+        fade,
+        inverse_transform,
+      },
+      None => clip,
+    };
+
     if (clip_x && layout.content_box_width() < f32::EPSILON)
       || (clip_y && layout.content_box_height() < f32::EPSILON)
     {
       return Ok(CanvasConstrainResult::SkipRendering);
     }
 
-    // When border-radius is non-zero, create a mask-based overflow constraint
-    // so that children (including abs-pos) are clipped to the padding-box
-    // rounded corners (inset from the border edge by border widths).
+    // Rounded corners, and the edges of rotated or skewed boxes, clip along an anti-aliased mask
+    // rendered with the node's transform, so transformed descendants are clipped to the box as it
+    // appears on the canvas.
     let border_props = BorderProperties::from_context(context, layout.size, layout.border);
-    if !border_props.is_zero() {
-      // Compute padding-box: border-box inset by border widths on each side.
-      let padding_box = Size {
-        width: (layout.size.width - layout.border.left - layout.border.right).max(0.0),
-        height: (layout.size.height - layout.border.top - layout.border.bottom).max(0.0),
-      };
-
-      // Shrink corner radii inward by border widths to get padding-box radii.
+    let clip_mask = if !border_props.is_zero() {
+      // Children (including abs-pos) are clipped to the padding-box rounded corners, inset from
+      // the border edge by border widths.
       let mut inner_props = border_props;
       inner_props.inset_by_border_width();
 
-      let mut paths = Vec::with_capacity(10);
-      // Offset origin so the mask starts at the padding edge (inside the border).
-      let padding_origin = Point {
-        x: layout.border.left,
-        y: layout.border.top,
-      };
-      inner_props.append_mask_commands(&mut paths, padding_box, padding_origin);
+      Some((
+        inner_props,
+        Size {
+          width: (layout.size.width - layout.border.left - layout.border.right).max(0.0),
+          height: (layout.size.height - layout.border.top - layout.border.bottom).max(0.0),
+        },
+        Point {
+          x: layout.border.left,
+          y: layout.border.top,
+        },
+      ))
+    } else if clip_x && clip_y && !transform.is_axis_aligned() {
+      Some((
+        BorderProperties::zero(),
+        Size {
+          width: layout.content_box_width(),
+          height: layout.content_box_height(),
+        },
+        Point {
+          x: layout.padding.left + layout.border.left,
+          y: layout.padding.top + layout.border.top,
+        },
+      ))
+    } else {
+      None
+    };
 
-      let (mask_data, placement) = mask_memory.render(&paths, None, None, buffer_pool);
+    if let Some((props, size, origin)) = clip_mask {
+      let (mask, placement) =
+        props.render_mask(size, origin, Some(transform), mask_memory, buffer_pool);
 
       if placement.width == 0 || placement.height == 0 {
-        buffer_pool.release(mask_data);
+        buffer_pool.release(mask);
         return Ok(CanvasConstrainResult::SkipRendering);
       }
 
-      let from = Point {
-        x: placement.left.max(0) as u32,
-        y: placement.top.max(0) as u32,
-      };
-
-      return Ok(CanvasConstrainResult::Some(CanvasConstrain::Overflow {
-        from,
-        to: Point {
-          x: from.x + placement.width,
-          y: from.y + placement.height,
-        },
-        inverse_transform,
-        border_radius_mask: Some((mask_data, placement.width)),
-      }));
+      return Ok(CanvasConstrainResult::Some(with_fade(
+        CanvasConstrain::OverflowMask { mask, placement },
+      )));
     }
 
     let from = Point {
@@ -266,12 +381,13 @@ impl CanvasConstrain {
       },
     };
 
-    Ok(CanvasConstrainResult::Some(CanvasConstrain::Overflow {
-      from,
-      to,
-      inverse_transform,
-      border_radius_mask: None,
-    }))
+    Ok(CanvasConstrainResult::Some(with_fade(
+      CanvasConstrain::Overflow {
+        from,
+        to,
+        inverse_transform,
+      },
+    )))
   }
 
   pub(crate) fn get_alpha(&self, x: u32, y: u32) -> u8 {
@@ -280,7 +396,6 @@ impl CanvasConstrain {
         from,
         to,
         inverse_transform,
-        ref border_radius_mask,
       } => {
         let original_point = inverse_transform.transform_point(Point {
           x: x as f32,
@@ -302,13 +417,6 @@ impl CanvasConstrain {
           return 0;
         }
 
-        // Apply border-radius mask if present
-        if let Some((mask, mask_w)) = border_radius_mask {
-          let mx = original_point.x - from.x;
-          let my = original_point.y - from.y;
-          return mask[mask_index_from_coord(mx, my, *mask_w)];
-        }
-
         u8::MAX
       }
       CanvasConstrain::MaskImage {
@@ -339,7 +447,11 @@ impl CanvasConstrain {
 
         mask[mask_index_from_coord(original_point.x, original_point.y, to.x - from.x)]
       }
-      CanvasConstrain::ClipPath {
+      CanvasConstrain::OverflowMask {
+        ref mask,
+        placement,
+      }
+      | CanvasConstrain::ClipPath {
         ref mask,
         placement,
       } => {
@@ -356,6 +468,35 @@ impl CanvasConstrain {
 
         mask[mask_index_from_coord(mask_x as u32, mask_y as u32, placement.width)]
       }
+      CanvasConstrain::FadeEdges {
+        ref clip,
+        fade,
+        inverse_transform,
+      } => {
+        let alpha = clip.get_alpha(x, y);
+
+        if alpha == 0 {
+          return 0;
+        }
+
+        let fade_alpha = fade.get_alpha(inverse_transform.transform_point(Point {
+          x: x as f32 + 0.5,
+          y: y as f32 + 0.5,
+        }));
+
+        fast_div_255(alpha as u32 * fade_alpha as u32)
+      }
+    }
+  }
+
+  /// Returns the mask buffers of the constrain, to be released back to the pool.
+  fn into_masks(self) -> SmallVec<[Vec<u8>; 1]> {
+    match self {
+      CanvasConstrain::OverflowMask { mask, .. }
+      | CanvasConstrain::ClipPath { mask, .. }
+      | CanvasConstrain::MaskImage { mask, .. } => SmallVec::from_elem(mask, 1),
+      CanvasConstrain::Overflow { .. } => SmallVec::new(),
+      CanvasConstrain::FadeEdges { clip, .. } => clip.into_masks(),
     }
   }
 }
@@ -553,6 +694,14 @@ pub(crate) struct Canvas {
   // we can just include the memory here instead of making the function argument bloated.
   pub(crate) mask_memory: MaskMemory,
   pub(crate) buffer_pool: BufferPool,
+  /// Records drawn glyph outlines when set, used by [`outline_text`](crate::rendering::outline_text).
+  pub(crate) glyph_outlines: Option<Vec<GlyphOutline>>,
+  /// Layers painted on the previous animation frame, set when rendering a sequence.
+  pub(crate) layer_cache: Option<LayerCache>,
+  /// Paths of the nodes left unpainted because they are outside the region that changed.
+  pub(crate) skipped_paints: Option<HashSet<Vec<usize>>>,
+  /// Time spent painting each node by path, recorded when profiling.
+  pub(crate) draw_timings: Option<HashMap<Vec<usize>, Duration>>,
 }
 
 impl Canvas {
@@ -563,6 +712,10 @@ impl Canvas {
       constrains: SmallVec::new(),
       mask_memory: MaskMemory::default(),
       buffer_pool: BufferPool::default(),
+      glyph_outlines: None,
+      layer_cache: None,
+      skipped_paints: None,
+      draw_timings: None,
     }
   }
 
@@ -580,20 +733,8 @@ impl Canvas {
 
   pub(crate) fn pop_constrain(&mut self) {
     if let Some(constrain) = self.constrains.pop() {
-      match constrain {
-        CanvasConstrain::Overflow {
-          border_radius_mask: Some((mask, _)),
-          ..
-        } => {
-          self.buffer_pool.release(mask);
-        }
-        CanvasConstrain::ClipPath { mask, .. } => {
-          self.buffer_pool.release(mask);
-        }
-        CanvasConstrain::MaskImage { mask, .. } => {
-          self.buffer_pool.release(mask);
-        }
-        _ => {}
+      for mask in constrain.into_masks() {
+        self.buffer_pool.release(mask);
       }
     }
   }
@@ -845,11 +986,13 @@ pub(crate) fn overlay_image<I: GenericImageView<Pixel = Rgba<u8>>>(
     });
   }
 
-  let mut paths = Vec::new();
-
-  border.append_mask_commands(&mut paths, size.map(|size| size as f32), Point::ZERO);
-
-  let (mask, placement) = mask_memory.render(&paths, Some(transform), None, buffer_pool);
+  let (mask, placement) = border.render_mask(
+    size.map(|size| size as f32),
+    Point::ZERO,
+    Some(transform),
+    mask_memory,
+    buffer_pool,
+  );
 
   let inverse = transform.invert();
   let is_identity = transform.is_identity() && placement.left >= 0 && placement.top >= 0;
@@ -1,14 +1,14 @@
-use std::f32::consts::SQRT_2;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, SQRT_2};
 
 use image::{GenericImageView, Rgba};
 use taffy::{Point, Rect, Size};
-use zeno::{Command, Fill, PathBuilder};
+use zeno::{Cap, Command, Fill, PathBuilder, Placement, Stroke};
 
 use crate::{
   layout::style::{Affine, BlendMode, BorderStyle, Color, ImageScalingAlgorithm, Sides, SpacePair},
   rendering::{
-    Canvas, RenderContext, apply_mask_alpha_to_pixel, blend_pixel, mask_index_from_coord,
-    overlay_area, sample_transformed_pixel,
+    BufferPool, Canvas, MaskMemory, RenderContext, RoundedRect, apply_mask_alpha_to_pixel,
+    blend_pixel, fast_div_255, mask_index_from_coord, overlay_area, sample_transformed_pixel,
   },
 };
 
@@ -21,6 +21,8 @@ pub(crate) struct BorderProperties {
   pub color: Color,
   /// Corner radii: top, right, bottom, left (in pixels)
   pub radius: Sides<SpacePair<f32>>,
+  /// Corner smoothing from 0 (circular arcs) to 1 (squircle-like corners).
+  pub smoothing: f32,
   /// The style of the border
   pub style: BorderStyle,
   /// The image rendering algorithm to use when sampling the image.
@@ -38,6 +40,7 @@ impl BorderProperties {
       width: Rect::ZERO,
       color: Color([0, 0, 0, 255]),
       radius: Sides([SpacePair::from_single(0.0); 4]),
+      smoothing: 0.0,
       style: BorderStyle::None,
       image_rendering: ImageScalingAlgorithm::Auto,
     }
@@ -78,6 +81,7 @@ impl BorderProperties {
       width: border_width,
       color: context.style.border_color.resolve(context.current_color),
       radius: Self::resolve_radius_part(context, border_box),
+      smoothing: context.style.corner_smoothing.0,
       style: context.style.border_style,
       image_rendering: context.style.image_rendering,
     }
@@ -124,20 +128,10 @@ impl BorderProperties {
     self.expand_by(self.width.map(|size| -size))
   }
 
-  /// Append rounded-rect path commands for this border's corner radii.
-  pub fn append_mask_commands(
-    &self,
-    path: &mut Vec<Command>,
-    border_box: Size<f32>,
-    offset: Point<f32>,
-  ) {
-    path.reserve_exact(BorderProperties::PATH_COMMANDS_AMOUNT);
-
-    // The magic number for the cubic bezier curve
-    const KAPPA: f32 = 4.0 / 3.0 * (SQRT_2 - 1.0);
-
-    // Calculate scale factor inline (CSS Overlapping Curves)
-    let scale = 1.0f32
+  /// Returns the factor corner radii are scaled by so adjacent curves don't overlap
+  /// (CSS Overlapping Curves).
+  fn radius_scale(&self, border_box: Size<f32>) -> f32 {
+    1.0f32
       .min(
         if self.radius.0[0].x + self.radius.0[1].x > border_box.width {
           border_box.width / (self.radius.0[0].x + self.radius.0[1].x)
@@ -165,7 +159,83 @@ impl BorderProperties {
         } else {
           1.0
         },
-      );
+      )
+  }
+
+  /// Returns the corner radii scaled to fit the border box, with degenerate corners zeroed.
+  fn scaled_radius(&self, border_box: Size<f32>) -> [SpacePair<f32>; 4] {
+    let scale = self.radius_scale(border_box);
+
+    self.radius.0.map(|corner| {
+      if corner.x > 0.0 && corner.y > 0.0 {
+        SpacePair::from_pair(corner.x * scale, corner.y * scale)
+      } else {
+        SpacePair::from_single(0.0)
+      }
+    })
+  }
+
+  /// Returns the rounded rectangle for this border's corner radii at `offset`.
+  pub(crate) fn rounded_rect(&self, border_box: Size<f32>, offset: Point<f32>) -> RoundedRect {
+    RoundedRect {
+      origin: offset,
+      size: border_box,
+      radius: self.scaled_radius(border_box),
+    }
+  }
+
+  /// Returns true if the corners are smoothed rather than circular, which only paths can draw.
+  fn has_smoothed_corners(&self) -> bool {
+    self.smoothing > 0.0 && !self.is_zero()
+  }
+
+  /// Renders the mask of this border's rounded rectangle.
+  ///
+  /// Translated shapes with circular corners get analytic coverage, anything else is rasterized
+  /// as paths.
+  pub(crate) fn render_mask(
+    &self,
+    border_box: Size<f32>,
+    offset: Point<f32>,
+    transform: Option<Affine>,
+    mask_memory: &mut MaskMemory,
+    buffer_pool: &mut BufferPool,
+  ) -> (Vec<u8>, Placement) {
+    let translation = match transform {
+      Some(transform) if transform.only_translation() && !self.has_smoothed_corners() => {
+        transform.decompose_translation()
+      }
+      None if !self.has_smoothed_corners() => Point::ZERO,
+      transform => {
+        let mut paths = Vec::with_capacity(BorderProperties::PATH_COMMANDS_AMOUNT);
+        self.append_mask_commands(&mut paths, border_box, offset);
+
+        return mask_memory.render(&paths, transform, None, buffer_pool);
+      }
+    };
+
+    self
+      .rounded_rect(border_box, offset + translation)
+      .render_mask(None, buffer_pool)
+  }
+
+  /// Append rounded-rect path commands for this border's corner radii.
+  pub fn append_mask_commands(
+    &self,
+    path: &mut Vec<Command>,
+    border_box: Size<f32>,
+    offset: Point<f32>,
+  ) {
+    if self.has_smoothed_corners() {
+      return self.append_smoothed_mask_commands(path, border_box, offset);
+    }
+
+    path.reserve_exact(BorderProperties::PATH_COMMANDS_AMOUNT);
+
+    // The magic number for the cubic bezier curve
+    const KAPPA: f32 = 4.0 / 3.0 * (SQRT_2 - 1.0);
+
+    let scale = self.radius_scale(border_box);
 
     // --- Top Edge ---
     // Start after Top-Left corner
@@ -256,8 +326,255 @@ impl BorderProperties {
     path.close();
   }
 
+  /// Append path commands for corners smoothed like Figma's squircles.
+  fn append_smoothed_mask_commands(
+    &self,
+    path: &mut Vec<Command>,
+    border_box: Size<f32>,
+    offset: Point<f32>,
+  ) {
+    let Size { width, height } = border_box;
+    let [top_left, top_right, bottom_right, bottom_left] = self.scaled_radius(border_box);
+
+    // Each corner may extend along an edge up to its share of the edge, split by radius.
+    let share = |length: f32, radius: f32, other: f32| {
+      if radius + other > 0.0 {
+        length * radius / (radius + other)
+      } else {
+        0.0
+      }
+    };
+
+    let corners = [
+      SmoothedCorner::new(
+        Point { x: width, y: 0.0 },
+        (Point { x: 1.0, y: 0.0 }, Point { x: 0.0, y: 1.0 }),
+        SpacePair::from_pair(top_right.x, top_right.y),
+        SpacePair::from_pair(
+          share(width, top_right.x, top_left.x),
+          share(height, top_right.y, bottom_right.y),
+        ),
+        self.smoothing,
+      ),
+      SmoothedCorner::new(
+        Point {
+          x: width,
+          y: height,
+        },
+        (Point { x: 0.0, y: 1.0 }, Point { x: -1.0, y: 0.0 }),
+        SpacePair::from_pair(bottom_right.y, bottom_right.x),
+        SpacePair::from_pair(
+          share(height, bottom_right.y, top_right.y),
+          share(width, bottom_right.x, bottom_left.x),
+        ),
+        self.smoothing,
+      ),
+      SmoothedCorner::new(
+        Point { x: 0.0, y: height },
+        (Point { x: -1.0, y: 0.0 }, Point { x: 0.0, y: -1.0 }),
+        SpacePair::from_pair(bottom_left.x, bottom_left.y),
+        SpacePair::from_pair(
+          share(width, bottom_left.x, bottom_right.x),
+          share(height, bottom_left.y, top_left.y),
+        ),
+        self.smoothing,
+      ),
+      SmoothedCorner::new(
+        Point::ZERO,
+        (Point { x: 0.0, y: -1.0 }, Point { x: 1.0, y: 0.0 }),
+        SpacePair::from_pair(top_left.y, top_left.x),
+        SpacePair::from_pair(
+          share(height, top_left.y, bottom_left.y),
+          share(width, top_left.x, top_right.x),
+        ),
+        self.smoothing,
+      ),
+    ];
+
+    path.reserve_exact(corners.len() * 4 + 2);
+    path.move_to(corners[3].point(offset, 0.0, corners[3].extent));
+
+    for corner in &corners {
+      corner.append_commands(path, offset);
+    }
+
+    path.close();
+  }
+
+  /// Returns the edge `fraction` of the way from the outer to the inner edge of the border,
+  /// along with the size and offset of the box it encloses.
+  fn inset_edge(&self, border_box: Size<f32>, fraction: f32) -> (Self, Size<f32>, Point<f32>) {
+    let amount = self.width.map(|width| width * fraction);
+
+    let mut edge = *self;
+    edge.expand_by(amount.map(|size| -size));
+
+    (
+      edge,
+      border_box
+        - Size {
+          width: amount.left + amount.right,
+          height: amount.top + amount.bottom,
+        },
+      Point {
+        x: amount.left,
+        y: amount.top,
+      },
+    )
+  }
+
+  /// Renders the mask of the whole border area, between the outer and inner edges.
+  fn render_ring_mask(
+    &self,
+    border_box: Size<f32>,
+    transform: Affine,
+    mask_memory: &mut MaskMemory,
+    buffer_pool: &mut BufferPool,
+  ) -> (Vec<u8>, Placement) {
+    let (inner, inner_box, inner_offset) = self.inset_edge(border_box, 1.0);
+
+    if transform.only_translation() && !self.has_smoothed_corners() {
+      let translation = transform.decompose_translation();
+
+      return self.rounded_rect(border_box, translation).render_mask(
+        Some(&inner.rounded_rect(inner_box, inner_offset + translation)),
+        buffer_pool,
+      );
+    }
+
+    let mut paths = Vec::with_capacity(BorderProperties::PATH_COMMANDS_AMOUNT * 2);
+    self.append_mask_commands(&mut paths, border_box, Point::ZERO);
+    inner.append_mask_commands(&mut paths, inner_box, inner_offset);
+
+    mask_memory.render(
+      &paths,
+      Some(transform),
+      Some(Fill::EvenOdd.into()),
+      buffer_pool,
+    )
+  }
+
+  /// Renders the mask of a `double` border: two lines each a third of the border width,
+  /// separated by a gap of the same width.
+  fn render_double_mask(
+    &self,
+    border_box: Size<f32>,
+    transform: Affine,
+    mask_memory: &mut MaskMemory,
+    buffer_pool: &mut BufferPool,
+  ) -> (Vec<u8>, Placement) {
+    let mut paths = Vec::with_capacity(BorderProperties::PATH_COMMANDS_AMOUNT * 4);
+    self.append_mask_commands(&mut paths, border_box, Point::ZERO);
+
+    for fraction in [1.0 / 3.0, 2.0 / 3.0, 1.0] {
+      let (edge, edge_box, edge_offset) = self.inset_edge(border_box, fraction);
+      edge.append_mask_commands(&mut paths, edge_box, edge_offset);
+    }
+
+    mask_memory.render(
+      &paths,
+      Some(transform),
+      Some(Fill::EvenOdd.into()),
+      buffer_pool,
+    )
+  }
+
+  /// Renders the dash or dot pattern of a `dashed` or `dotted` border along the middle of the
+  /// border, with the spacing stretched so the pattern repeats evenly.
+  ///
+  /// Returns `None` for styles without a pattern.
+  fn render_pattern_mask(
+    &self,
+    border_box: Size<f32>,
+    transform: Affine,
+    mask_memory: &mut MaskMemory,
+    buffer_pool: &mut BufferPool,
+  ) -> Option<(Vec<u8>, Placement)> {
+    let dashed = match self.style {
+      BorderStyle::Dashed => true,
+      BorderStyle::Dotted => false,
+      _ => return None,
+    };
+
+    let thickest = self
+      .width
+      .top
+      .max(self.width.right)
+      .max(self.width.bottom)
+      .max(self.width.left);
+
+    let (center, center_box, center_offset) = self.inset_edge(border_box, 0.5);
+    let mut paths = Vec::with_capacity(BorderProperties::PATH_COMMANDS_AMOUNT);
+    center.append_mask_commands(&mut paths, center_box, center_offset);
+
+    // Dashes are twice as long as the border is wide, with gaps of the same length.
+    // Dots are as wide as the border, with gaps of the same width.
+    let nominal_period = thickest * if dashed { 4.0 } else { 2.0 };
+    let fit = |length: f32| {
+      let count = (length / nominal_period).round().max(1.0) as usize;
+
+      (count, length / count as f32)
+    };
+
+    let (dashes, offset) = if self.is_zero() {
+      // Fit the pattern to each side, so every corner lands on the middle of a dash or a dot.
+      let sides = [
+        center_box.width,
+        center_box.height,
+        center_box.width,
+        center_box.height,
+      ]
+      .map(fit);
+
+      let mut dashes = Vec::new();
+      let mut previous_period = sides[3].1;
+
+      for (count, period) in sides {
+        if dashed {
+          // The dash around the corner is made of half a dash from each side.
+          dashes.extend([previous_period / 4.0 + period / 4.0, period / 2.0]);
+          dashes.extend([period / 2.0; 2].repeat(count - 1));
+        } else {
+          dashes.extend([0.0, period].repeat(count));
+        }
+
+        previous_period = period;
+      }
+
+      let offset = if dashed { sides[3].1 / 4.0 } else { 0.0 };
+
+      (dashes, offset)
+    } else {
+      let length = zeno::length(&paths, None);
+      if length <= 0.0 {
+        return None;
+      }
+
+      let (_, period) = fit(length);
+
+      if dashed {
+        (vec![period / 2.0; 2], period / 4.0)
+      } else {
+        (vec![0.0, period], 0.0)
+      }
+    };
+
+    let mut stroke = Stroke::new(thickest);
+
+    if dashed {
+      // The stroke is wider than the border, which then clips it to its own shape.
+      stroke.width(thickest * 2.0);
+    } else {
+      stroke.cap(Cap::Round);
+    }
+
+    stroke.dash(&dashes, offset);
+
+    Some(mask_memory.render(&paths, Some(transform), Some(stroke.into()), buffer_pool))
+  }
+
   pub(crate) fn draw<I: GenericImageView<Pixel = Rgba<u8>>>(
-    mut self,
+    self,
     canvas: &mut Canvas,
     border_box: Size<f32>,
     transform: Affine,
@@ -279,30 +596,31 @@ impl BorderProperties {
       return;
     }
 
-    let mut paths = Vec::with_capacity(BorderProperties::PATH_COMMANDS_AMOUNT * 2);
-
-    self.append_mask_commands(&mut paths, border_box, Point::ZERO);
-
-    self.inset_by_border_width();
-    self.append_mask_commands(
-      &mut paths,
-      border_box
-        - Size {
-          width: self.width.left + self.width.right,
-          height: self.width.top + self.width.bottom,
-        },
-      Point {
-        x: self.width.left,
-        y: self.width.top,
-      },
-    );
+    let (mut mask, placement) = if self.style == BorderStyle::Double {
+      self.render_double_mask(
+        border_box,
+        transform,
+        &mut canvas.mask_memory,
+        &mut canvas.buffer_pool,
+      )
+    } else {
+      self.render_ring_mask(
+        border_box,
+        transform,
+        &mut canvas.mask_memory,
+        &mut canvas.buffer_pool,
+      )
+    };
 
-    let (mask, placement) = canvas.mask_memory.render(
-      &paths,
-      Some(transform),
-      Some(Fill::EvenOdd.into()),
+    if let Some((pattern, pattern_placement)) = self.render_pattern_mask(
+      border_box,
+      transform,
+      &mut canvas.mask_memory,
       &mut canvas.buffer_pool,
-    );
+    ) {
+      intersect_masks(&mut mask, placement, &pattern, pattern_placement);
+      canvas.buffer_pool.release(pattern);
+    }
 
     let Some(inverse) = transform.invert() else {
       return;
@@ -351,3 +669,223 @@ impl BorderProperties {
     );
   }
 }
+
+/// Multiplies `mask` by `other`, treating pixels outside of `other` as transparent.
+fn intersect_masks(
+  mask: &mut [u8],
+  placement: Placement,
+  other: &[u8],
+  other_placement: Placement,
+) {
+  for y in 0..placement.height {
+    let other_y = y as i32 + placement.top - other_placement.top;
+
+    for x in 0..placement.width {
+      let other_x = x as i32 + placement.left - other_placement.left;
+      let index = mask_index_from_coord(x, y, placement.width);
+
+      let alpha = if (0..other_placement.width as i32).contains(&other_x)
+        && (0..other_placement.height as i32).contains(&other_y)
+      {
+        other[mask_index_from_coord(other_x as u32, other_y as u32, other_placement.width)]
+      } else {
+        0
+      };
+
+      mask[index] = fast_div_255(mask[index] as u32 * alpha as u32);
+    }
+  }
+}
+
+/// A corner with a smoothed curve, laid out in a frame where `s` runs along the incoming edge
+/// towards the corner point and `t` runs along the outgoing edge, both in units of the radius.
+///
+/// The curve follows the construction of Figma's corner smoothing: a circular arc shortened by the
+/// smoothing, eased into both edges by cubic curves.
+struct SmoothedCorner {
+  corner: Point<f32>,
+  incoming: Point<f32>,
+  outgoing: Point<f32>,
+  /// Radius along the incoming and outgoing edge.
+  radius: SpacePair<f32>,
+  /// How far the curve extends along each edge, in units of the radius.
+  extent: f32,
+  /// Lengths of the easing curve handles, in units of the radius.
+  a: f32,
+  b: f32,
+  c: f32,
+  d: f32,
+  /// Length of the arc handles, in units of the radius.
+  arc_handle: f32,
+}
+
+impl SmoothedCorner {
+  fn new(
+    corner: Point<f32>,
+    (incoming, outgoing): (Point<f32>, Point<f32>),
+    radius: SpacePair<f32>,
+    budget: SpacePair<f32>,
+    smoothing: f32,
+  ) -> Self {
+    let is_rounded = radius.x > 0.0 && radius.y > 0.0;
+
+    // Only smooth as far as the edges have room for, so curves of adjacent corners don't overlap.
+    let smoothing = if is_rounded {
+      smoothing
+        .min(budget.x / radius.x - 1.0)
+        .min(budget.y / radius.y - 1.0)
+        .clamp(0.0, 1.0)
+    } else {
+      0.0
+    };
+
+    let arc_angle = FRAC_PI_2 * (1.0 - smoothing);
+    let alpha = FRAC_PI_4 * smoothing;
+    let arc_length = SQRT_2 * (arc_angle / 2.0).sin();
+    let c = (alpha / 2.0).tan() * alpha.cos();
+    let d = c * alpha.tan();
+    let extent = if is_rounded { 1.0 + smoothing } else { 0.0 };
+    let b = ((extent - arc_length - c - d) / 3.0).max(0.0);
+
+    Self {
+      corner,
+      incoming,
+      outgoing,
+      radius,
+      extent,
+      a: 2.0 * b,
+      b,
+      c,
+      d,
+      arc_handle: 4.0 / 3.0 * (arc_angle / 4.0).tan(),
+    }
+  }
+
+  /// Maps a point in the corner frame to the border box.
+  fn point(&self, offset: Point<f32>, s: f32, t: f32) -> (f32, f32) {
+    let s = s * self.radius.x;
+    let t = t * self.radius.y;
+
+    (
+      offset.x + self.corner.x + self.incoming.x * s + self.outgoing.x * t,
+      offset.y + self.corner.y + self.incoming.y * s + self.outgoing.y * t,
+    )
+  }
+
+  fn append_commands(&self, path: &mut Vec<Command>, offset: Point<f32>) {
+    if self.extent == 0.0 {
+      path.line_to(self.point(offset, 0.0, 0.0));
+      return;
+    }
+
+    let Self {
+      extent: p,
+      a,
+      b,
+      c,
+      d,
+      arc_handle,
+      ..
+    } = *self;
+
+    let arc_start = (-p + a + b + c, d);
+    let arc_end = (-d, p - a - b - c);
+    // Tangents of the arc, which is centered at (-1, 1) in the corner frame.
+    let start_tangent = (-(arc_start.1 - 1.0), arc_start.0 + 1.0);
+    let end_tangent = (-(arc_end.1 - 1.0), arc_end.0 + 1.0);
+
+    path.line_to(self.point(offset, -p, 0.0));
+    path.curve_to(
+      self.point(offset, -p + a, 0.0),
+      self.point(offset, -p + a + b, 0.0),
+      self.point(offset, arc_start.0, arc_start.1),
+    );
+    path.curve_to(
+      self.point(
+        offset,
+        arc_start.0 + arc_handle * start_tangent.0,
+        arc_start.1 + arc_handle * start_tangent.1,
+      ),
+      self.point(
+        offset,
+        arc_end.0 - arc_handle * end_tangent.0,
+        arc_end.1 - arc_handle * end_tangent.1,
+      ),
+      self.point(offset, arc_end.0, arc_end.1),
+    );
+    path.curve_to(
+      self.point(offset, 0.0, p - a - b),
+      self.point(offset, 0.0, p - a),
+      self.point(offset, 0.0, p),
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn render_square(smoothing: f32) -> Vec<u8> {
+    let border = BorderProperties {
+      radius: Sides([SpacePair::from_single(20.0); 4]),
+      smoothing,
+      ..BorderProperties::zero()
+    };
+
+    let (mask, placement) = border.render_mask(
+      Size {
+        width: 100.0,
+        height: 100.0,
+      },
+      Point::ZERO,
+      None,
+      &mut MaskMemory::default(),
+      &mut BufferPool::default(),
+    );
+
+    assert_eq!((placement.width, placement.height), (100, 100));
+    mask
+  }
+
+  #[test]
+  fn smoothed_corners_ease_into_edges() {
+    let circular = render_square(0.0);
+    let smoothed = render_square(1.0);
+
+    // Past the end of the circular arc, the smoothed curve still bends away from the edge.
+    assert_eq!(circular[24], 255);
+    assert!(smoothed[24] < 255);
+    // The centers of the edges and the box are unaffected.
+    assert_eq!(smoothed[50], 255);
+    assert_eq!(smoothed[50 * 100 + 50], 255);
+    // The smoothed shape stays symmetric.
+    assert_eq!(smoothed[24], smoothed[24 * 100]);
+    assert_eq!(smoothed[24], smoothed[99 - 24]);
+  }
+
+  #[test]
+  fn smoothing_without_room_falls_back_to_circular_corners() {
+    let border = BorderProperties {
+      radius: Sides([SpacePair::from_single(50.0); 4]),
+      smoothing: 1.0,
+      ..BorderProperties::zero()
+    };
+    let size = Size {
+      width: 100.0,
+      height: 100.0,
+    };
+
+    let mut smoothed = Vec::new();
+    border.append_mask_commands(&mut smoothed, size, Point::ZERO);
+
+    // Every corner ends exactly where its neighbor begins, at the middle of each edge.
+    assert!(smoothed.iter().all(|command| match command {
+      Command::MoveTo(point) | Command::LineTo(point) => {
+        [point.x, point.y]
+          .iter()
+          .all(|value| (0.0..=100.0).contains(value))
+      }
+      _ => true,
+    }));
+  }
+}
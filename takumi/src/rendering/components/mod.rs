@@ -1,7 +1,9 @@
 mod blur;
 mod border;
+mod rounded_rect;
 mod shadow;
 
 pub(crate) use blur::*;
 pub(crate) use border::*;
+pub(crate) use rounded_rect::*;
 pub(crate) use shadow::*;
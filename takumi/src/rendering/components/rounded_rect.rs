@@ -0,0 +1,227 @@
+use taffy::{Point, Size};
+use zeno::Placement;
+
+use crate::{layout::style::SpacePair, rendering::BufferPool};
+
+/// An axis-aligned rounded rectangle in canvas space.
+///
+/// Its mask is computed from the exact pixel coverage of straight edges and the distance to each
+/// corner ellipse, which keeps small radii smooth where flattened curves show stair-stepping.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RoundedRect {
+  pub origin: Point<f32>,
+  pub size: Size<f32>,
+  /// Corner radii: top-left, top-right, bottom-right, bottom-left, already scaled to fit the size.
+  pub radius: [SpacePair<f32>; 4],
+}
+
+impl RoundedRect {
+  /// Returns how much of the pixel whose top-left corner is at (`x`, `y`) the shape covers, in 0..=1.
+  pub(crate) fn coverage(&self, x: f32, y: f32) -> f32 {
+    let Size { width, height } = self.size;
+
+    if width <= 0.0 || height <= 0.0 {
+      return 0.0;
+    }
+
+    let x = x - self.origin.x;
+    let y = y - self.origin.y;
+
+    let coverage_x = ((x + 1.0).min(width) - x.max(0.0)).clamp(0.0, 1.0);
+    let coverage_y = ((y + 1.0).min(height) - y.max(0.0)).clamp(0.0, 1.0);
+    let coverage = coverage_x * coverage_y;
+
+    if coverage == 0.0 {
+      return 0.0;
+    }
+
+    let center = Point {
+      x: x + 0.5,
+      y: y + 0.5,
+    };
+
+    let [top_left, top_right, bottom_right, bottom_left] = self.radius;
+
+    let corner = if center.x < top_left.x && center.y < top_left.y {
+      Some((
+        top_left,
+        Point {
+          x: top_left.x,
+          y: top_left.y,
+        },
+      ))
+    } else if center.x > width - top_right.x && center.y < top_right.y {
+      Some((
+        top_right,
+        Point {
+          x: width - top_right.x,
+          y: top_right.y,
+        },
+      ))
+    } else if center.x > width - bottom_right.x && center.y > height - bottom_right.y {
+      Some((
+        bottom_right,
+        Point {
+          x: width - bottom_right.x,
+          y: height - bottom_right.y,
+        },
+      ))
+    } else if center.x < bottom_left.x && center.y > height - bottom_left.y {
+      Some((
+        bottom_left,
+        Point {
+          x: bottom_left.x,
+          y: height - bottom_left.y,
+        },
+      ))
+    } else {
+      None
+    };
+
+    let Some((radius, ellipse_center)) = corner else {
+      return coverage;
+    };
+
+    let distance = ellipse_distance(
+      center.x - ellipse_center.x,
+      center.y - ellipse_center.y,
+      radius,
+    );
+
+    coverage.min((0.5 - distance).clamp(0.0, 1.0))
+  }
+
+  /// Returns the top-left pixel and the pixel size of the area the shape may cover.
+  fn pixel_bounds(&self) -> (Point<i32>, Size<u32>) {
+    let left = self.origin.x.floor();
+    let top = self.origin.y.floor();
+    let right = (self.origin.x + self.size.width.max(0.0)).ceil();
+    let bottom = (self.origin.y + self.size.height.max(0.0)).ceil();
+
+    (
+      Point {
+        x: left as i32,
+        y: top as i32,
+      },
+      Size {
+        width: (right - left) as u32,
+        height: (bottom - top) as u32,
+      },
+    )
+  }
+
+  /// Renders the coverage of this shape, minus the `hole` if given, into a mask buffer.
+  ///
+  /// The placement matches what rasterizing the equivalent path would produce.
+  pub(crate) fn render_mask(
+    &self,
+    hole: Option<&RoundedRect>,
+    buffer_pool: &mut BufferPool,
+  ) -> (Vec<u8>, Placement) {
+    let (origin, size) = self.pixel_bounds();
+    let mut mask = buffer_pool.acquire_dirty(size.width as usize * size.height as usize);
+
+    for (index, value) in mask.iter_mut().enumerate() {
+      let x = (origin.x + (index % size.width as usize) as i32) as f32;
+      let y = (origin.y + (index / size.width as usize) as i32) as f32;
+
+      let mut coverage = self.coverage(x, y);
+
+      if let Some(hole) = hole
+        && coverage > 0.0
+      {
+        coverage = (coverage - hole.coverage(x, y)).max(0.0);
+      }
+
+      *value = (coverage * 255.0).round() as u8;
+    }
+
+    (
+      mask,
+      Placement {
+        left: origin.x,
+        top: origin.y,
+        width: size.width,
+        height: size.height,
+      },
+    )
+  }
+}
+
+/// Approximates the signed distance from a point to an ellipse centered at the origin.
+///
+/// Exact for circles, and close enough near the edge of an ellipse for antialiasing.
+fn ellipse_distance(x: f32, y: f32, radius: SpacePair<f32>) -> f32 {
+  let k0 = ((x / radius.x).powi(2) + (y / radius.y).powi(2)).sqrt();
+  let k1 = ((x / (radius.x * radius.x)).powi(2) + (y / (radius.y * radius.y)).powi(2)).sqrt();
+
+  if k1 == 0.0 {
+    return -radius.x.min(radius.y);
+  }
+
+  k0 * (k0 - 1.0) / k1
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rect(size: f32, radius: f32) -> RoundedRect {
+    RoundedRect {
+      origin: Point::ZERO,
+      size: Size {
+        width: size,
+        height: size,
+      },
+      radius: [SpacePair::from_single(radius); 4],
+    }
+  }
+
+  #[test]
+  fn covers_fractional_edges() {
+    let shape = RoundedRect {
+      origin: Point { x: 0.25, y: 0.0 },
+      ..rect(2.0, 0.0)
+    };
+
+    assert_eq!(shape.coverage(0.0, 0.0), 0.75);
+    assert_eq!(shape.coverage(1.0, 0.0), 1.0);
+    assert_eq!(shape.coverage(2.0, 0.0), 0.25);
+    assert_eq!(shape.coverage(3.0, 0.0), 0.0);
+  }
+
+  #[test]
+  fn corner_coverage_is_smooth() {
+    let shape = rect(20.0, 6.0);
+
+    // Along the diagonal of the corner, coverage should rise monotonically through partial values.
+    let diagonal = (0..6)
+      .map(|i| shape.coverage(i as f32, i as f32))
+      .collect::<Vec<_>>();
+
+    assert!(diagonal.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(diagonal[0], 0.0);
+    assert_eq!(diagonal[5], 1.0);
+    assert!(diagonal.iter().any(|&value| value > 0.0 && value < 1.0));
+
+    // The corner is symmetric across all four sides.
+    assert_eq!(shape.coverage(1.0, 2.0), shape.coverage(2.0, 1.0));
+    assert_eq!(shape.coverage(1.0, 2.0), shape.coverage(18.0, 17.0));
+  }
+
+  #[test]
+  fn renders_ring_masks() {
+    let outer = rect(4.0, 0.0);
+    let inner = RoundedRect {
+      origin: Point { x: 1.0, y: 1.0 },
+      ..rect(2.0, 0.0)
+    };
+
+    let mut buffer_pool = BufferPool::default();
+    let (mask, placement) = outer.render_mask(Some(&inner), &mut buffer_pool);
+
+    assert_eq!((placement.width, placement.height), (4, 4));
+    assert_eq!(&mask[0..4], &[255; 4]);
+    assert_eq!(&mask[4..8], &[255, 0, 0, 255]);
+  }
+}
@@ -24,6 +24,8 @@ pub(crate) struct SizedShadow {
   pub spread_radius: f32,
   /// Color of the shadow.
   pub color: Color,
+  /// Whether the shadow is drawn inside the shape instead of behind it.
+  pub inset: bool,
 }
 
 impl SizedShadow {
@@ -40,6 +42,7 @@ impl SizedShadow {
       blur_radius: shadow.blur_radius.to_px(sizing, size.width),
       spread_radius: shadow.spread_radius.to_px(sizing, size.width),
       color: shadow.color.resolve(current_color),
+      inset: shadow.inset,
     }
   }
 
@@ -57,6 +60,7 @@ impl SizedShadow {
       // Text shadows do not support spread radius; set to 0.
       spread_radius: 0.0,
       color: shadow.color.resolve(current_color),
+      inset: shadow.inset,
     }
   }
 
@@ -179,6 +183,101 @@ impl SizedShadow {
     Ok(())
   }
 
+  /// Draws the shadow inside the filled `paths`, as cast by the area around the shape.
+  pub fn draw_inset_paths(
+    &self,
+    canvas: &mut Canvas,
+    paths: &[Command],
+    transform: Affine,
+  ) -> Result<()> {
+    let (mask, placement) =
+      canvas
+        .mask_memory
+        .render(paths, Some(transform), None, &mut canvas.buffer_pool);
+
+    if mask.is_empty() {
+      canvas.buffer_pool.release(mask);
+      return Ok(());
+    }
+
+    // The padding keeps the blurred area around the shape, which bleeds inwards, in the image.
+    let padding = (self.blur_radius.max(0.0) * BlurType::Shadow.extent_multiplier()
+      + self.offset_x.abs().max(self.offset_y.abs()))
+    .ceil() as u32;
+
+    let mut image = canvas.buffer_pool.acquire_image(
+      placement.width + padding * 2,
+      placement.height + padding * 2,
+    )?;
+
+    for pixel in bytemuck::cast_slice_mut::<u8, [u8; 4]>(image.as_mut()) {
+      *pixel = self.color.0;
+    }
+
+    let img_w = image.width() as i32;
+    let img_h = image.height() as i32;
+    let data = image.as_mut();
+
+    // Cut the offset shape out so only the surrounding area casts the shadow.
+    for my in 0..placement.height as i32 {
+      for mx in 0..placement.width as i32 {
+        let ix = mx + padding as i32 + self.offset_x.round() as i32;
+        let iy = my + padding as i32 + self.offset_y.round() as i32;
+
+        if ix >= 0 && iy >= 0 && ix < img_w && iy < img_h {
+          let mask_alpha = mask[(my as u32 * placement.width + mx as u32) as usize] as u32;
+          let alpha = &mut data[(iy as usize * img_w as usize + ix as usize) * 4 + 3];
+          *alpha = ((*alpha as u32 * (255 - mask_alpha)) / 255) as u8;
+        }
+      }
+    }
+
+    apply_blur(
+      BlurFormat::Rgba(&mut image),
+      self.blur_radius,
+      BlurType::Shadow,
+      &mut canvas.buffer_pool,
+    )?;
+
+    // Clip the shadow to the shape itself.
+    for (index, pixel) in bytemuck::cast_slice_mut::<u8, [u8; 4]>(image.as_mut())
+      .iter_mut()
+      .enumerate()
+    {
+      let mx = (index as i32 % img_w) - padding as i32;
+      let my = (index as i32 / img_w) - padding as i32;
+
+      let mask_alpha =
+        if mx >= 0 && my >= 0 && mx < placement.width as i32 && my < placement.height as i32 {
+          mask[(my as u32 * placement.width + mx as u32) as usize] as u32
+        } else {
+          0
+        };
+
+      pixel[3] = ((pixel[3] as u32 * mask_alpha) / 255) as u8;
+    }
+
+    canvas.buffer_pool.release(mask);
+
+    overlay_image(
+      &mut canvas.image,
+      &image,
+      BorderProperties::zero(),
+      Affine::translation(
+        (placement.left - padding as i32) as f32,
+        (placement.top - padding as i32) as f32,
+      ),
+      ImageScalingAlgorithm::Auto,
+      BlendMode::Normal,
+      &canvas.constrains,
+      &mut canvas.mask_memory,
+      &mut canvas.buffer_pool,
+    );
+
+    canvas.buffer_pool.release_image(image);
+    Ok(())
+  }
+
   pub fn draw_inset(
     &self,
     transform: Affine,
@@ -229,11 +328,8 @@ pub(crate) fn draw_inset_shadow(
     y: shadow.offset_y,
   };
 
-  let mut paths = Vec::new();
-
   border.expand_by(Sides([-shadow.spread_radius; 4]).into());
-  border.append_mask_commands(
-    &mut paths,
+  let (mask, placement) = border.render_mask(
     border_box
       - Size {
         width: shadow.spread_radius * 2.0,
@@ -244,10 +340,11 @@ pub(crate) fn draw_inset_shadow(
         x: shadow.spread_radius,
         y: shadow.spread_radius,
       },
+    None,
+    mask_memory,
+    buffer_pool,
   );
 
-  let (mask, placement) = mask_memory.render(&paths, None, Some(Fill::NonZero.into()), buffer_pool);
-
   if !mask.is_empty() {
     let img_w = shadow_image.width() as i32;
     let img_h = shadow_image.height() as i32;
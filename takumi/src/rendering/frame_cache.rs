@@ -0,0 +1,507 @@
+use std::{
+  collections::{HashMap, HashSet},
+  sync::Arc,
+};
+
+use image::RgbaImage;
+use parley::PositionedLayoutItem;
+use taffy::{AvailableSpace, Layout, Size};
+
+use crate::{
+  layout::{
+    inline::{InlineLayoutStage, ProcessedInlineSpan, collect_inline_items, create_inline_layout},
+    node::NodeKind,
+    style::{Affine, ComputedStyle, TextEmboss},
+    tree::RenderNode,
+  },
+  rendering::{LayoutRegion, inline_drawing::get_parent_x_height},
+};
+
+/// What a node paints with, compared between consecutive animation frames.
+#[derive(Clone, PartialEq)]
+pub(crate) struct NodePaint {
+  style: ComputedStyle,
+  content: NodeContent,
+  layout: Layout,
+  transform: Affine,
+  font_size: f32,
+  device_pixel_ratio: f32,
+  /// Styles and contents of the descendants painted as part of the node's inline layout.
+  inline_descendants: Vec<(ComputedStyle, NodeContent)>,
+}
+
+impl NodePaint {
+  fn new(node: &RenderNode<'_>, layout: Layout, transform: Affine) -> Self {
+    let mut inline_descendants = Vec::new();
+
+    if node.should_create_inline_layout() {
+      let mut pending = node.children.iter().flatten().collect::<Vec<_>>();
+      while let Some(descendant) = pending.pop() {
+        inline_descendants.push((
+          (*descendant.context.style).clone(),
+          NodeContent::new(descendant),
+        ));
+        pending.extend(descendant.children.iter().flatten());
+      }
+    }
+
+    Self {
+      style: (*node.context.style).clone(),
+      content: NodeContent::new(node),
+      layout,
+      transform,
+      font_size: node.context.sizing.font_size,
+      device_pixel_ratio: node.context.sizing.viewport.device_pixel_ratio,
+      inline_descendants,
+    }
+  }
+
+  /// Returns true if the node's own painting can reach pixels far outside its border box.
+  fn paints_outside_box(&self) -> bool {
+    self.content == NodeContent::Opaque
+      || self
+        .inline_descendants
+        .iter()
+        .any(|(style, content)| *content == NodeContent::Opaque || style_paints_outside_box(style))
+      || style_paints_outside_box(&self.style)
+  }
+
+  /// Returns true if painting depends on pixels around the node, so neighbours can't be skipped.
+  fn samples_surroundings(&self) -> bool {
+    !self.style.filter.is_empty() || !self.style.backdrop_filter.is_empty()
+  }
+}
+
+fn style_paints_outside_box(style: &ComputedStyle) -> bool {
+  style.box_shadow.is_some()
+    || style.text_shadow.is_some()
+    || style.text_emboss != TextEmboss::None
+    || !style.filter.is_empty()
+    || !style.backdrop_filter.is_empty()
+}
+
+/// The content a node paints besides its style.
+#[derive(Clone)]
+enum NodeContent {
+  Empty,
+  Text(String),
+  Image(Arc<str>),
+  #[cfg(feature = "svg")]
+  Svg(Arc<str>),
+  /// Content that may paint differently on every frame, such as custom drawers.
+  Opaque,
+}
+
+impl PartialEq for NodeContent {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Self::Empty, Self::Empty) => true,
+      (Self::Text(a), Self::Text(b)) => a == b,
+      (Self::Image(a), Self::Image(b)) => a == b,
+      #[cfg(feature = "svg")]
+      (Self::Svg(a), Self::Svg(b)) => a == b,
+      _ => false,
+    }
+  }
+}
+
+impl NodeContent {
+  fn new(node: &RenderNode<'_>) -> Self {
+    if let Some(text) = &node.anonymous_text_content {
+      return Self::Text(text.clone());
+    }
+
+    let Some(node) = &node.node else {
+      return Self::Empty;
+    };
+
+    match &node.kind {
+      NodeKind::Container { .. } => Self::Empty,
+      NodeKind::Text(text) => Self::Text(text.text.clone()),
+      NodeKind::Image(image) => Self::Image(image.src.clone()),
+      #[cfg(feature = "svg")]
+      NodeKind::Svg(svg) => Self::Svg(svg.svg.clone()),
+      _ => Self::Opaque,
+    }
+  }
+}
+
+/// The paint of a node along with the output region it may touch, `None` when unbounded.
+pub(crate) struct PaintRecord {
+  paint: NodePaint,
+  bounds: Option<LayoutRegion>,
+}
+
+/// How to produce a frame from the previous one.
+#[derive(Debug)]
+pub(crate) enum FramePlan {
+  /// Nothing changed, the previous frame is reused as is.
+  Reuse,
+  /// Only `rect`, in whole pixels, changed, so nodes outside of it (`skipped`, by node path)
+  /// don't need painting.
+  Repaint {
+    rect: LayoutRegion,
+    skipped: HashSet<Vec<usize>>,
+  },
+  /// The whole frame is painted.
+  Full,
+}
+
+/// The previous animation frame and what each of its nodes painted with, keyed by node path.
+///
+/// Comparing the next frame against it finds the region that changed, so subtrees that paint
+/// the same, such as everything but a counter or a progress bar, aren't rasterized again.
+#[derive(Default)]
+pub(crate) struct FrameCache {
+  records: HashMap<Vec<usize>, PaintRecord>,
+  image: Option<RgbaImage>,
+}
+
+impl FrameCache {
+  /// Records the paint of the visible node at `path` for the next frame.
+  ///
+  /// Bounds are carried over from the previous frame when the node paints the same, saving the
+  /// text layout needed to find them.
+  pub(crate) fn record(
+    &self,
+    path: &[usize],
+    node: &RenderNode<'_>,
+    layout: Layout,
+    transform: Affine,
+  ) -> PaintRecord {
+    let paint = NodePaint::new(node, layout, transform);
+
+    let bounds = match self.records.get(path) {
+      Some(previous) if previous.paint == paint => previous.bounds,
+      _ => paint_bounds(&paint, node, layout, transform),
+    };
+
+    PaintRecord { paint, bounds }
+  }
+
+  /// Compares the records of the next frame against the previous frame.
+  pub(crate) fn plan(
+    &self,
+    records: &HashMap<Vec<usize>, PaintRecord>,
+    size: Size<u32>,
+  ) -> FramePlan {
+    let Some(image) = &self.image else {
+      return FramePlan::Full;
+    };
+
+    if image.dimensions() != (size.width, size.height) {
+      return FramePlan::Full;
+    }
+
+    let changed = records
+      .iter()
+      .filter(|(path, record)| {
+        self
+          .records
+          .get(*path)
+          .is_none_or(|previous| previous.paint != record.paint)
+      })
+      .map(|(path, _)| path.as_slice())
+      .chain(
+        self
+          .records
+          .keys()
+          .filter(|path| !records.contains_key(*path))
+          .map(Vec::as_slice),
+      )
+      .collect::<HashSet<_>>();
+
+    if changed.is_empty() {
+      return FramePlan::Reuse;
+    }
+
+    // Compositing changes such as opacity affect everything painted by the subtree.
+    let is_affected =
+      |path: &[usize]| (0..=path.len()).any(|depth| changed.contains(&path[..depth]));
+
+    let mut dirty = LayoutRegion::default();
+    for (path, record) in self.records.iter().chain(records) {
+      if !is_affected(path) {
+        continue;
+      }
+
+      let Some(bounds) = record.bounds else {
+        return FramePlan::Full;
+      };
+
+      dirty = dirty.union(&bounds);
+    }
+
+    if records
+      .values()
+      .any(|record| record.paint.samples_surroundings())
+    {
+      return FramePlan::Full;
+    }
+
+    let Some(rect) = dirty_rect(dirty, size) else {
+      return FramePlan::Reuse;
+    };
+
+    if rect.width == size.width as f32 && rect.height == size.height as f32 {
+      return FramePlan::Full;
+    }
+
+    let skipped = records
+      .iter()
+      .filter(|(_, record)| {
+        record
+          .bounds
+          .is_some_and(|bounds| bounds.is_empty() || !bounds.intersects(&rect))
+      })
+      .map(|(path, _)| path.clone())
+      .collect();
+
+    FramePlan::Repaint { rect, skipped }
+  }
+
+  /// Completes the frame in `image` according to `plan` and remembers it for the next one.
+  ///
+  /// For [`FramePlan::Repaint`], `image` only has to be correct inside the dirty rect.
+  pub(crate) fn finish(
+    &mut self,
+    records: HashMap<Vec<usize>, PaintRecord>,
+    plan: &FramePlan,
+    image: &mut RgbaImage,
+  ) {
+    match (plan, self.image.as_mut()) {
+      (FramePlan::Reuse, Some(previous)) => image.copy_from_slice(previous),
+      (FramePlan::Repaint { rect, .. }, Some(previous)) => {
+        copy_rect(image, previous, *rect);
+        image.copy_from_slice(previous);
+      }
+      (_, Some(previous)) if previous.dimensions() == image.dimensions() => {
+        previous.copy_from_slice(image);
+      }
+      _ => self.image = Some(image.clone()),
+    }
+
+    self.records = records;
+  }
+}
+
+/// Returns the output region the node may paint, or `None` if it may paint anywhere.
+fn paint_bounds(
+  paint: &NodePaint,
+  node: &RenderNode<'_>,
+  layout: Layout,
+  transform: Affine,
+) -> Option<LayoutRegion> {
+  if paint.paints_outside_box() {
+    return None;
+  }
+
+  let style = &node.context.style;
+  let outline = style
+    .outline_width
+    .to_px(&node.context.sizing, layout.size.width)
+    + style
+      .outline_offset
+      .to_px(&node.context.sizing, layout.size.width)
+      .max(0.0);
+
+  let mut local = LayoutRegion {
+    x: -outline,
+    y: -outline,
+    width: layout.size.width + outline * 2.0,
+    height: layout.size.height + outline * 2.0,
+  };
+
+  let paints_text = node.should_create_inline_layout()
+    || node
+      .node
+      .as_ref()
+      .is_some_and(|node| matches!(node.kind, NodeKind::Text(_)));
+
+  if paints_text {
+    local = local.union(&text_bounds(node, layout));
+  }
+
+  Some(local.transform(transform))
+}
+
+/// Returns the area covered by the node's text, padded for glyphs that overhang their runs.
+fn text_bounds(node: &RenderNode<'_>, layout: Layout) -> LayoutRegion {
+  let font_style = node.context.style.to_sized_font_style(&node.context);
+  let parent_x_height = get_parent_x_height(&node.context, &font_style);
+  let size = layout.content_box_size();
+
+  let (inline_layout, _, spans) = create_inline_layout(
+    collect_inline_items(node).into_iter(),
+    Size {
+      width: AvailableSpace::Definite(size.width),
+      height: AvailableSpace::Definite(size.height),
+    },
+    size.width,
+    None,
+    &font_style,
+    node.context.global,
+    InlineLayoutStage::Draw,
+  );
+
+  let mut bounds = LayoutRegion::default();
+  // Half a line covers italic overhang, marks above the ascent, strokes and decorations.
+  let mut overhang = font_style.sizing.font_size / 2.0;
+
+  for line in inline_layout.lines() {
+    for item in line.items() {
+      match item {
+        PositionedLayoutItem::GlyphRun(glyph_run) => {
+          let metrics = glyph_run.run().metrics();
+          let height = metrics.ascent + metrics.descent;
+          overhang = overhang.max(height / 2.0);
+
+          bounds = bounds.union(&LayoutRegion {
+            x: glyph_run.offset(),
+            y: glyph_run.baseline() - metrics.ascent,
+            width: glyph_run.advance(),
+            height,
+          });
+        }
+        PositionedLayoutItem::InlineBox(mut positioned_box) => {
+          if let Some(ProcessedInlineSpan::Box(item)) = spans.get(positioned_box.id as usize) {
+            item.vertical_align.apply(
+              &mut positioned_box.y,
+              line.metrics(),
+              positioned_box.height,
+              parent_x_height,
+            );
+          }
+
+          bounds = bounds.union(&LayoutRegion {
+            x: positioned_box.x,
+            y: positioned_box.y,
+            width: positioned_box.width,
+            height: positioned_box.height,
+          });
+        }
+      }
+    }
+  }
+
+  if bounds.is_empty() {
+    return bounds;
+  }
+
+  LayoutRegion {
+    x: layout.content_box_x() + bounds.x - overhang,
+    y: layout.content_box_y() + bounds.y - overhang,
+    width: bounds.width + overhang * 2.0,
+    height: bounds.height + overhang * 2.0,
+  }
+}
+
+/// Rounds `region` out to whole pixels with a pixel of margin for anti-aliasing, within `size`.
+fn dirty_rect(region: LayoutRegion, size: Size<u32>) -> Option<LayoutRegion> {
+  if region.is_empty() {
+    return None;
+  }
+
+  let left = (region.x.floor() - 1.0).clamp(0.0, size.width as f32);
+  let top = (region.y.floor() - 1.0).clamp(0.0, size.height as f32);
+  let right = ((region.x + region.width).ceil() + 1.0).clamp(0.0, size.width as f32);
+  let bottom = ((region.y + region.height).ceil() + 1.0).clamp(0.0, size.height as f32);
+
+  (right > left && bottom > top).then_some(LayoutRegion {
+    x: left,
+    y: top,
+    width: right - left,
+    height: bottom - top,
+  })
+}
+
+/// Copies the pixels inside `rect`, in whole pixels, from `source` into `target` of the same
+/// size.
+fn copy_rect(source: &RgbaImage, target: &mut RgbaImage, rect: LayoutRegion) {
+  let stride = source.width() as usize * 4;
+  let start_x = rect.x as usize * 4;
+  let row_bytes = rect.width as usize * 4;
+
+  for y in rect.y as usize..(rect.y + rect.height) as usize {
+    let start = y * stride + start_x;
+    let (source, target) = (source.as_raw(), &mut **target);
+    target[start..start + row_bytes].copy_from_slice(&source[start..start + row_bytes]);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use image::{Rgba, RgbaImage};
+  use taffy::Size;
+
+  use super::{copy_rect, dirty_rect};
+  use crate::rendering::LayoutRegion;
+
+  #[test]
+  fn dirty_rect_rounds_out_and_clamps_to_the_output() {
+    let size = Size {
+      width: 100,
+      height: 50,
+    };
+
+    assert_eq!(
+      dirty_rect(
+        LayoutRegion {
+          x: 10.5,
+          y: 4.2,
+          width: 20.0,
+          height: 10.0,
+        },
+        size,
+      ),
+      Some(LayoutRegion {
+        x: 9.0,
+        y: 3.0,
+        width: 23.0,
+        height: 13.0,
+      })
+    );
+
+    assert_eq!(
+      dirty_rect(
+        LayoutRegion {
+          x: -20.0,
+          y: 40.0,
+          width: 200.0,
+          height: 30.0,
+        },
+        size,
+      ),
+      Some(LayoutRegion {
+        x: 0.0,
+        y: 39.0,
+        width: 100.0,
+        height: 11.0,
+      })
+    );
+
+    assert_eq!(dirty_rect(LayoutRegion::default(), size), None);
+  }
+
+  #[test]
+  fn copy_rect_only_touches_pixels_inside_the_rect() {
+    let source = RgbaImage::from_pixel(4, 3, Rgba([255, 0, 0, 255]));
+    let mut target = RgbaImage::new(4, 3);
+
+    copy_rect(
+      &source,
+      &mut target,
+      LayoutRegion {
+        x: 1.0,
+        y: 1.0,
+        width: 2.0,
+        height: 1.0,
+      },
+    );
+
+    assert_eq!(target.get_pixel(1, 1), &Rgba([255, 0, 0, 255]));
+    assert_eq!(target.get_pixel(2, 1), &Rgba([255, 0, 0, 255]));
+    assert_eq!(target.get_pixel(0, 1), &Rgba([0, 0, 0, 0]));
+    assert_eq!(target.get_pixel(1, 0), &Rgba([0, 0, 0, 0]));
+  }
+}
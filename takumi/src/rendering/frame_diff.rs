@@ -0,0 +1,258 @@
+use std::ops::Range;
+
+use image::{RgbaImage, imageops::crop_imm};
+
+use crate::{
+  Result,
+  rendering::{LayoutRegion, RenderOptions, render},
+};
+
+/// Side length of the square blocks frames are compared in.
+const BLOCK_SIZE: u32 = 16;
+
+/// A render compared against a previous frame, see [`render_changes`].
+#[derive(Debug, Clone)]
+pub struct RenderChanges {
+  /// The full render.
+  pub image: RgbaImage,
+  /// Areas whose pixels differ from the previous frame in whole pixels, empty when nothing
+  /// changed.
+  pub regions: Vec<LayoutRegion>,
+}
+
+impl RenderChanges {
+  /// Returns the smallest region covering every change.
+  pub fn bounds(&self) -> Option<LayoutRegion> {
+    self.regions.iter().copied().reduce(|a, b| a.union(&b))
+  }
+
+  /// Crops the changed [`bounds`](Self::bounds) out of the render, to be drawn over the
+  /// previous frame at the region's offset.
+  pub fn patch(&self) -> Option<(LayoutRegion, RgbaImage)> {
+    let bounds = self.bounds()?;
+    let patch = crop_imm(
+      &self.image,
+      bounds.x as u32,
+      bounds.y as u32,
+      bounds.width as u32,
+      bounds.height as u32,
+    )
+    .to_image();
+
+    Some((bounds, patch))
+  }
+}
+
+/// Renders a node and finds the regions that differ from `previous`, a frame returned by an
+/// earlier render.
+///
+/// A previous frame of a different size counts as changed everywhere.
+pub fn render_changes<'g>(
+  options: RenderOptions<'g>,
+  previous: &RgbaImage,
+) -> Result<RenderChanges> {
+  let image = render(options)?;
+  let regions = diff_frames(previous, &image);
+
+  Ok(RenderChanges { image, regions })
+}
+
+/// Compares two frames and returns the regions of `next` whose pixels differ from `previous`.
+///
+/// Frames are compared in blocks of 16 pixels. Horizontally adjacent changed blocks are joined,
+/// runs spanning the same columns on consecutive rows are merged, and each region is then
+/// shrunk to the pixels that actually changed.
+pub fn diff_frames(previous: &RgbaImage, next: &RgbaImage) -> Vec<LayoutRegion> {
+  let (width, height) = next.dimensions();
+
+  if width == 0 || height == 0 {
+    return Vec::new();
+  }
+
+  if previous.dimensions() != (width, height) {
+    return vec![pixel_region(0..width, 0..height)];
+  }
+
+  let columns = width.div_ceil(BLOCK_SIZE);
+  let rows = height.div_ceil(BLOCK_SIZE);
+
+  // Changed block runs per block row, as `[start, end)` column spans.
+  let mut open: Vec<(u32, u32, u32, u32)> = Vec::new();
+  let mut regions = Vec::new();
+
+  for row in 0..rows {
+    let mut spans = Vec::new();
+    let mut span_start = None;
+
+    for column in 0..=columns {
+      let changed = column < columns && block_changed(previous, next, column, row);
+
+      match (changed, span_start) {
+        (true, None) => span_start = Some(column),
+        (false, Some(start)) => {
+          spans.push((start, column));
+          span_start = None;
+        }
+        _ => {}
+      }
+    }
+
+    // Extend regions whose span continues on this row, close the rest.
+    let mut next_open = Vec::with_capacity(spans.len());
+    for (start, end) in spans {
+      if let Some(index) = open
+        .iter()
+        .position(|&(open_start, open_end, _, _)| (open_start, open_end) == (start, end))
+      {
+        let (_, _, first_row, _) = open.swap_remove(index);
+        next_open.push((start, end, first_row, row));
+      } else {
+        next_open.push((start, end, row, row));
+      }
+    }
+
+    regions.append(&mut open);
+    open = next_open;
+  }
+
+  regions.append(&mut open);
+
+  regions
+    .into_iter()
+    .filter_map(|(start, end, first_row, last_row)| {
+      tighten(
+        previous,
+        next,
+        start * BLOCK_SIZE..(end * BLOCK_SIZE).min(width),
+        first_row * BLOCK_SIZE..((last_row + 1) * BLOCK_SIZE).min(height),
+      )
+    })
+    .collect()
+}
+
+fn block_changed(previous: &RgbaImage, next: &RgbaImage, column: u32, row: u32) -> bool {
+  let (width, height) = next.dimensions();
+  let x = column * BLOCK_SIZE;
+  let y = row * BLOCK_SIZE;
+
+  (y..(y + BLOCK_SIZE).min(height)).any(|y| {
+    let start = (y * width + x) as usize * 4;
+    let end = (y * width + (x + BLOCK_SIZE).min(width)) as usize * 4;
+
+    previous.as_raw()[start..end] != next.as_raw()[start..end]
+  })
+}
+
+/// Shrinks the area spanned by `columns` and `rows` to the pixels inside it that differ between
+/// the frames.
+fn tighten(
+  previous: &RgbaImage,
+  next: &RgbaImage,
+  columns: Range<u32>,
+  rows: Range<u32>,
+) -> Option<LayoutRegion> {
+  let (mut min_x, mut min_y, mut max_x, mut max_y) = (u32::MAX, u32::MAX, 0, 0);
+
+  for y in rows {
+    for x in columns.clone() {
+      if previous.get_pixel(x, y) != next.get_pixel(x, y) {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+      }
+    }
+  }
+
+  (min_x <= max_x).then(|| pixel_region(min_x..max_x + 1, min_y..max_y + 1))
+}
+
+/// The region covering the pixels in `columns` and `rows`.
+fn pixel_region(columns: Range<u32>, rows: Range<u32>) -> LayoutRegion {
+  LayoutRegion {
+    x: columns.start as f32,
+    y: rows.start as f32,
+    width: columns.len() as f32,
+    height: rows.len() as f32,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use image::Rgba;
+
+  use super::*;
+
+  fn frame(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 255]))
+  }
+
+  #[test]
+  fn identical_frames_have_no_changes() {
+    assert_eq!(diff_frames(&frame(40, 30), &frame(40, 30)), Vec::new());
+  }
+
+  #[test]
+  fn resized_frames_change_everywhere() {
+    assert_eq!(
+      diff_frames(&frame(40, 30), &frame(30, 40)),
+      vec![LayoutRegion {
+        x: 0.0,
+        y: 0.0,
+        width: 30.0,
+        height: 40.0,
+      }]
+    );
+  }
+
+  #[test]
+  fn separate_changes_report_separate_tight_regions() {
+    let previous = frame(64, 64);
+    let mut next = previous.clone();
+
+    for (x, y) in [(3, 4), (4, 5), (20, 4), (50, 60)] {
+      next.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+    }
+
+    let mut regions = diff_frames(&previous, &next);
+    regions.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+
+    assert_eq!(
+      regions,
+      vec![
+        LayoutRegion {
+          x: 3.0,
+          y: 4.0,
+          width: 18.0,
+          height: 2.0,
+        },
+        LayoutRegion {
+          x: 50.0,
+          y: 60.0,
+          width: 1.0,
+          height: 1.0,
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn changes_spanning_block_rows_merge_into_one_region() {
+    let previous = frame(48, 48);
+    let mut next = previous.clone();
+
+    for y in 10..40 {
+      next.put_pixel(20, y, Rgba([0, 0, 0, 255]));
+    }
+
+    assert_eq!(
+      diff_frames(&previous, &next),
+      vec![LayoutRegion {
+        x: 20.0,
+        y: 10.0,
+        width: 1.0,
+        height: 30.0,
+      }]
+    );
+  }
+}
@@ -0,0 +1,362 @@
+use std::{
+  collections::{BTreeMap, HashMap},
+  mem::{size_of, size_of_val},
+  sync::Mutex,
+};
+
+use crate::resources::{font::ResolvedGlyph, image_cache::ImageCacheStats};
+use smallvec::SmallVec;
+
+/// Default byte budget of a [`GlyphCache`].
+pub const DEFAULT_GLYPH_CACHE_BUDGET: usize = 16 * 1024 * 1024;
+
+/// Counters describing the state of a [`GlyphCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GlyphCacheStats {
+  /// Number of cached glyphs.
+  pub entries: usize,
+  /// Estimated memory held by the cached glyphs, in bytes.
+  pub bytes: usize,
+  /// Maximum number of bytes the cache may hold.
+  pub budget: usize,
+  /// Glyph lookups served from the cache.
+  pub hits: u64,
+  /// Glyph lookups that had to scale the glyph.
+  pub misses: u64,
+  /// Glyphs dropped to stay within the budget.
+  pub evictions: u64,
+}
+
+/// Cache statistics of a [`GlobalContext`](crate::GlobalContext).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+  /// Statistics of the glyph cache.
+  pub glyphs: GlyphCacheStats,
+  /// Statistics of the image decode cache.
+  pub images: ImageCacheStats,
+}
+
+/// Identifies how the glyphs of a run are scaled, shared by every glyph of the run.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct GlyphRunKey {
+  /// Id of the font data blob.
+  pub font_id: u64,
+  pub font_index: u32,
+  /// Bits of the font size in pixels.
+  pub size: u32,
+  pub normalized_coords: SmallVec<[i16; 4]>,
+  /// Bits of the synthetic emboldening strength.
+  pub embolden: Option<u32>,
+  /// Bits of the synthetic skew angle in degrees.
+  pub skew: Option<u32>,
+  /// Whether the outlines are hinted.
+  pub hinting: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GlyphCacheKey {
+  run: GlyphRunKey,
+  glyph_id: u32,
+}
+
+struct GlyphCacheEntry {
+  glyph: ResolvedGlyph,
+  bytes: usize,
+  last_used: u64,
+}
+
+struct GlyphCacheState {
+  entries: HashMap<GlyphCacheKey, GlyphCacheEntry>,
+  /// Keys ordered from least to most recently used.
+  recency: BTreeMap<u64, GlyphCacheKey>,
+  tick: u64,
+  stats: GlyphCacheStats,
+}
+
+impl GlyphCacheState {
+  fn touch(&mut self, key: &GlyphCacheKey) -> Option<ResolvedGlyph> {
+    self.tick += 1;
+    let tick = self.tick;
+
+    let entry = self.entries.get_mut(key)?;
+    let previous = std::mem::replace(&mut entry.last_used, tick);
+    let glyph = entry.glyph.clone();
+
+    if let Some(key) = self.recency.remove(&previous) {
+      self.recency.insert(tick, key);
+    }
+
+    Some(glyph)
+  }
+
+  fn insert(&mut self, key: GlyphCacheKey, glyph: ResolvedGlyph) {
+    let bytes = estimate_glyph_bytes(&glyph);
+
+    if bytes > self.stats.budget {
+      return;
+    }
+
+    self.tick += 1;
+    self.recency.insert(self.tick, key.clone());
+
+    if let Some(previous) = self.entries.insert(
+      key,
+      GlyphCacheEntry {
+        glyph,
+        bytes,
+        last_used: self.tick,
+      },
+    ) {
+      self.recency.remove(&previous.last_used);
+      self.stats.bytes -= previous.bytes;
+    }
+
+    self.stats.bytes += bytes;
+    self.evict_to(self.stats.budget);
+  }
+
+  fn evict_to(&mut self, budget: usize) {
+    while self.stats.bytes > budget {
+      let Some((_, key)) = self.recency.pop_first() else {
+        break;
+      };
+
+      if let Some(entry) = self.entries.remove(&key) {
+        self.stats.bytes -= entry.bytes;
+        self.stats.evictions += 1;
+      }
+    }
+
+    self.stats.entries = self.entries.len();
+  }
+}
+
+/// Scaled glyph outlines and bitmaps reused across renders, bounded by a byte budget.
+///
+/// Glyphs are cached before rasterization, so the same entry serves every subpixel position and
+/// transform the glyph is drawn at. The least recently used glyphs are dropped first once the
+/// budget is exceeded, and a budget of `0` disables the cache.
+pub struct GlyphCache {
+  state: Mutex<GlyphCacheState>,
+}
+
+impl Default for GlyphCache {
+  fn default() -> Self {
+    Self::new(DEFAULT_GLYPH_CACHE_BUDGET)
+  }
+}
+
+impl GlyphCache {
+  /// Creates a cache holding at most `budget` bytes of glyphs.
+  pub fn new(budget: usize) -> Self {
+    Self {
+      state: Mutex::new(GlyphCacheState {
+        entries: HashMap::new(),
+        recency: BTreeMap::new(),
+        tick: 0,
+        stats: GlyphCacheStats {
+          budget,
+          ..Default::default()
+        },
+      }),
+    }
+  }
+
+  /// Returns the current counters.
+  pub fn stats(&self) -> GlyphCacheStats {
+    self
+      .state
+      .lock()
+      .map(|state| state.stats)
+      .unwrap_or_default()
+  }
+
+  /// Changes the byte budget, dropping the least recently used glyphs if it shrank.
+  pub fn set_budget(&self, budget: usize) {
+    if let Ok(mut state) = self.state.lock() {
+      state.stats.budget = budget;
+      state.evict_to(budget);
+    }
+  }
+
+  /// Drops every cached glyph, keeping the budget and the hit and miss counters.
+  pub fn clear(&self) {
+    if let Ok(mut state) = self.state.lock() {
+      state.entries.clear();
+      state.recency.clear();
+      state.stats.bytes = 0;
+      state.stats.entries = 0;
+    }
+  }
+
+  /// Drops the glyphs scaled from the given font data blobs.
+  pub(crate) fn remove_fonts(&self, font_ids: &[u64]) {
+    let Ok(mut state) = self.state.lock() else {
+      return;
+    };
+    let GlyphCacheState {
+      entries,
+      recency,
+      stats,
+      ..
+    } = &mut *state;
+
+    entries.retain(|key, entry| {
+      if !font_ids.contains(&key.run.font_id) {
+        return true;
+      }
+
+      recency.remove(&entry.last_used);
+      stats.bytes -= entry.bytes;
+      false
+    });
+    stats.entries = entries.len();
+  }
+
+  /// Resolves the glyphs of a run, scaling only the ones not cached yet.
+  pub(crate) fn resolve(
+    &self,
+    run: &GlyphRunKey,
+    glyph_ids: impl IntoIterator<Item = u32>,
+    mut scale: impl FnMut(u32) -> Option<ResolvedGlyph>,
+  ) -> HashMap<u32, ResolvedGlyph> {
+    let mut result = HashMap::new();
+    let mut missing = Vec::new();
+
+    {
+      let Ok(mut state) = self.state.lock() else {
+        return glyph_ids
+          .into_iter()
+          .filter_map(|glyph_id| Some((glyph_id, scale(glyph_id)?)))
+          .collect();
+      };
+
+      for glyph_id in glyph_ids {
+        let key = GlyphCacheKey {
+          run: run.clone(),
+          glyph_id,
+        };
+
+        match state.touch(&key) {
+          Some(glyph) => {
+            state.stats.hits += 1;
+            result.insert(glyph_id, glyph);
+          }
+          None => {
+            state.stats.misses += 1;
+            missing.push(key);
+          }
+        }
+      }
+    }
+
+    if missing.is_empty() {
+      return result;
+    }
+
+    let scaled = missing
+      .into_iter()
+      .filter_map(|key| Some((scale(key.glyph_id)?, key)))
+      .collect::<Vec<_>>();
+
+    let Ok(mut state) = self.state.lock() else {
+      result.extend(scaled.into_iter().map(|(glyph, key)| (key.glyph_id, glyph)));
+      return result;
+    };
+
+    for (glyph, key) in scaled {
+      let glyph_id = key.glyph_id;
+
+      state.insert(key, glyph.clone());
+      result.insert(glyph_id, glyph);
+    }
+
+    result
+  }
+}
+
+/// Estimates the memory held by a cached glyph, including its key and bookkeeping.
+fn estimate_glyph_bytes(glyph: &ResolvedGlyph) -> usize {
+  let data = match glyph {
+    ResolvedGlyph::Image(image) => image.data.len(),
+    ResolvedGlyph::Outline(outline) => size_of_val(outline.points()) + size_of_val(outline.verbs()),
+  };
+
+  data + size_of::<GlyphCacheEntry>() + size_of::<GlyphCacheKey>() * 2
+}
+
+#[cfg(test)]
+mod tests {
+  use swash::scale::outline::Outline;
+
+  use super::*;
+
+  fn run_key() -> GlyphRunKey {
+    GlyphRunKey {
+      font_id: 1,
+      font_index: 0,
+      size: 16f32.to_bits(),
+      normalized_coords: SmallVec::new(),
+      embolden: None,
+      skew: None,
+      hinting: false,
+    }
+  }
+
+  fn outline() -> Option<ResolvedGlyph> {
+    Some(ResolvedGlyph::Outline(Outline::new()))
+  }
+
+  #[test]
+  fn serves_repeated_glyphs_from_cache() {
+    let cache = GlyphCache::default();
+    let mut scaled = 0;
+
+    cache.resolve(&run_key(), [1, 2], |_| {
+      scaled += 1;
+      outline()
+    });
+    let glyphs = cache.resolve(&run_key(), [1, 2, 3], |_| {
+      scaled += 1;
+      outline()
+    });
+
+    assert_eq!(glyphs.len(), 3);
+    assert_eq!(scaled, 3);
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (2, 3, 3));
+  }
+
+  #[test]
+  fn evicts_least_recently_used_glyphs() {
+    let entry_bytes = estimate_glyph_bytes(&ResolvedGlyph::Outline(Outline::new()));
+    let cache = GlyphCache::new(entry_bytes * 2);
+
+    cache.resolve(&run_key(), [1], |_| outline());
+    cache.resolve(&run_key(), [2], |_| outline());
+    cache.resolve(&run_key(), [1], |_| outline());
+    cache.resolve(&run_key(), [3], |_| outline());
+
+    let stats = cache.stats();
+    assert_eq!((stats.entries, stats.evictions), (2, 1));
+
+    let mut rescaled = Vec::new();
+    cache.resolve(&run_key(), [1, 2, 3], |glyph_id| {
+      rescaled.push(glyph_id);
+      outline()
+    });
+    assert_eq!(rescaled, [2]);
+  }
+
+  #[test]
+  fn zero_budget_disables_caching() {
+    let cache = GlyphCache::new(0);
+
+    cache.resolve(&run_key(), [1], |_| outline());
+    cache.resolve(&run_key(), [1], |_| outline());
+
+    let stats = cache.stats();
+    assert_eq!((stats.entries, stats.hits, stats.misses), (0, 0, 2));
+  }
+}
@@ -0,0 +1,116 @@
+use taffy::Point;
+
+use crate::{layout::style::Affine, rendering::MeasuredNode};
+
+impl MeasuredNode {
+  /// Returns the path of child indices to the topmost node containing the point, in output pixels.
+  ///
+  /// Transforms are taken into account, and later siblings are considered above earlier ones.
+  /// An empty path means the point only hits this node, `None` means it hits nothing.
+  pub fn hit_test(&self, x: f32, y: f32) -> Option<Vec<usize>> {
+    let point = Point { x, y };
+    let mut hit = None;
+    let mut stack = vec![(self, Vec::new())];
+
+    // Nodes paint in pre-order, so the last node containing the point is the topmost one.
+    // Descendants are not clipped to their parent since they can overflow it.
+    while let Some((node, path)) = stack.pop() {
+      for (index, child) in node.children.iter().enumerate().rev() {
+        let mut child_path = path.clone();
+        child_path.push(index);
+        stack.push((child, child_path));
+      }
+
+      if node.contains(point) {
+        hit = Some(path);
+      }
+    }
+
+    hit
+  }
+
+  fn contains(&self, point: Point<f32>) -> bool {
+    let [a, b, c, d, x, y] = self.transform;
+    let Some(inverse) = (Affine { a, b, c, d, x, y }).invert() else {
+      return false;
+    };
+
+    let local = inverse.transform_point(point);
+
+    local.x >= 0.0 && local.y >= 0.0 && local.x < self.width && local.y < self.height
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn node(
+    transform: [f32; 6],
+    width: f32,
+    height: f32,
+    children: Vec<MeasuredNode>,
+  ) -> MeasuredNode {
+    MeasuredNode {
+      width,
+      height,
+      transform,
+      children,
+      runs: Vec::new(),
+      lines: Vec::new(),
+    }
+  }
+
+  fn at(x: f32, y: f32) -> [f32; 6] {
+    [1.0, 0.0, 0.0, 1.0, x, y]
+  }
+
+  #[test]
+  fn returns_deepest_topmost_path() {
+    let layout = node(
+      at(0.0, 0.0),
+      100.0,
+      100.0,
+      vec![
+        node(
+          at(0.0, 0.0),
+          50.0,
+          50.0,
+          vec![node(at(10.0, 10.0), 10.0, 10.0, vec![])],
+        ),
+        node(at(40.0, 40.0), 50.0, 50.0, vec![]),
+      ],
+    );
+
+    assert_eq!(layout.hit_test(15.0, 15.0), Some(vec![0, 0]));
+    assert_eq!(layout.hit_test(45.0, 45.0), Some(vec![1]));
+    assert_eq!(layout.hit_test(95.0, 5.0), Some(vec![]));
+    assert_eq!(layout.hit_test(150.0, 5.0), None);
+  }
+
+  #[test]
+  fn respects_transforms() {
+    // 20x10 box rotated by 90deg around the origin, then moved to (50, 0).
+    let layout = node(
+      at(0.0, 0.0),
+      100.0,
+      100.0,
+      vec![node([0.0, 1.0, -1.0, 0.0, 50.0, 0.0], 20.0, 10.0, vec![])],
+    );
+
+    assert_eq!(layout.hit_test(45.0, 15.0), Some(vec![0]));
+    assert_eq!(layout.hit_test(55.0, 5.0), Some(vec![]));
+  }
+
+  #[test]
+  fn finds_overflowing_descendants() {
+    let layout = node(
+      at(0.0, 0.0),
+      10.0,
+      10.0,
+      vec![node(at(20.0, 20.0), 10.0, 10.0, vec![])],
+    );
+
+    assert_eq!(layout.hit_test(25.0, 25.0), Some(vec![0]));
+  }
+}
@@ -0,0 +1,179 @@
+use image::{RgbaImage, imageops::replace};
+use typed_builder::TypedBuilder;
+
+use crate::{
+  Result,
+  error::TakumiError,
+  layout::style::{Color, ImageScalingAlgorithm, ObjectFit},
+  resources::image::ImageSource,
+};
+
+/// The largest output width or height, and the largest size an image is resized to before it
+/// is cropped into the box.
+const MAX_TRANSFORM_SIZE: u32 = 16384;
+
+/// Options for [`transform_image`].
+#[derive(Debug, Clone, Copy, TypedBuilder)]
+#[builder(field_defaults(default))]
+#[non_exhaustive]
+pub struct TransformImageOptions {
+  /// The output width in pixels. Derived from the aspect ratio when only `height` is set.
+  pub width: Option<u32>,
+  /// The output height in pixels. Derived from the aspect ratio when only `width` is set.
+  pub height: Option<u32>,
+  /// How the image fits the output box when both `width` and `height` are set, like CSS
+  /// `object-fit`. The image is centered in the box.
+  pub fit: ObjectFit,
+  /// The resampling algorithm, Lanczos3 by default.
+  #[builder(default = ImageScalingAlgorithm::Smooth)]
+  pub algorithm: ImageScalingAlgorithm,
+}
+
+impl Default for TransformImageOptions {
+  fn default() -> Self {
+    Self::builder().build()
+  }
+}
+
+/// Resizes an image to the box described by `options`.
+///
+/// SVGs are rasterized at the target size rather than scaled afterwards. Areas of the box the
+/// image doesn't cover, as with `contain`, are left transparent. Fails with
+/// [`TakumiError::TransformTooLarge`] if either size exceeds 16384 pixels.
+pub fn transform_image(source: &ImageSource, options: TransformImageOptions) -> Result<RgbaImage> {
+  let (intrinsic_width, intrinsic_height) = source.intrinsic_size();
+  let aspect_ratio = intrinsic_width / intrinsic_height;
+
+  let (box_width, box_height) = match (options.width, options.height) {
+    (Some(width), Some(height)) => (width, height),
+    (Some(width), None) => (width, to_pixels(width as f32 / aspect_ratio)),
+    (None, Some(height)) => (to_pixels(height as f32 * aspect_ratio), height),
+    (None, None) => (to_pixels(intrinsic_width), to_pixels(intrinsic_height)),
+  };
+  let (box_width, box_height) = (box_width.max(1), box_height.max(1));
+
+  let scale_x = box_width as f32 / intrinsic_width;
+  let scale_y = box_height as f32 / intrinsic_height;
+
+  let (width, height) = match options.fit {
+    ObjectFit::Fill => (box_width, box_height),
+    fit => {
+      let scale = match fit {
+        ObjectFit::Contain => scale_x.min(scale_y),
+        ObjectFit::Cover => scale_x.max(scale_y),
+        ObjectFit::ScaleDown => scale_x.min(scale_y).min(1.0),
+        _ => 1.0,
+      };
+
+      (
+        to_pixels(intrinsic_width * scale),
+        to_pixels(intrinsic_height * scale),
+      )
+    }
+  };
+
+  check_transform_size(box_width, box_height)?;
+  check_transform_size(width, height)?;
+
+  let image = source.render_to_rgba_image(width, height, options.algorithm, Color::black())?;
+
+  if (width, height) == (box_width, box_height) {
+    return Ok(image.into_owned());
+  }
+
+  let mut output = RgbaImage::new(box_width, box_height);
+
+  replace(
+    &mut output,
+    image.as_ref(),
+    (box_width as i64 - width as i64) / 2,
+    (box_height as i64 - height as i64) / 2,
+  );
+
+  Ok(output)
+}
+
+fn check_transform_size(width: u32, height: u32) -> Result<()> {
+  if width > MAX_TRANSFORM_SIZE || height > MAX_TRANSFORM_SIZE {
+    return Err(TakumiError::TransformTooLarge {
+      width,
+      height,
+      max: MAX_TRANSFORM_SIZE,
+    });
+  }
+
+  Ok(())
+}
+
+fn to_pixels(value: f32) -> u32 {
+  (value.round() as u32).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+  use image::Rgba;
+
+  use super::*;
+
+  fn source(width: u32, height: u32) -> ImageSource {
+    ImageSource::Bitmap(RgbaImage::from_pixel(width, height, Rgba([255, 0, 0, 255])))
+  }
+
+  #[test]
+  fn derives_missing_dimension_from_aspect_ratio() -> Result<()> {
+    let options = TransformImageOptions::builder().width(Some(50)).build();
+    let image = transform_image(&source(200, 100), options)?;
+
+    assert_eq!(image.dimensions(), (50, 25));
+    Ok(())
+  }
+
+  #[test]
+  fn contain_letterboxes_with_transparency() -> Result<()> {
+    let options = TransformImageOptions::builder()
+      .width(Some(40))
+      .height(Some(40))
+      .fit(ObjectFit::Contain)
+      .build();
+    let image = transform_image(&source(200, 100), options)?;
+
+    assert_eq!(image.dimensions(), (40, 40));
+    assert_eq!(image.get_pixel(20, 2).0[3], 0);
+    assert_eq!(image.get_pixel(20, 20).0, [255, 0, 0, 255]);
+    Ok(())
+  }
+
+  #[test]
+  fn cover_fills_the_box() -> Result<()> {
+    let options = TransformImageOptions::builder()
+      .width(Some(40))
+      .height(Some(40))
+      .fit(ObjectFit::Cover)
+      .build();
+    let image = transform_image(&source(200, 100), options)?;
+
+    assert_eq!(image.dimensions(), (40, 40));
+    assert!(image.pixels().all(|pixel| pixel.0[3] == 255));
+    Ok(())
+  }
+
+  #[test]
+  fn rejects_oversized_outputs() {
+    let too_wide = TransformImageOptions::builder().width(Some(20_000)).build();
+    assert!(matches!(
+      transform_image(&source(200, 100), too_wide),
+      Err(TakumiError::TransformTooLarge { .. })
+    ));
+
+    // Covering a wide box scales a tall image far past the box before it is cropped.
+    let tall_cover = TransformImageOptions::builder()
+      .width(Some(10_000))
+      .height(Some(10))
+      .fit(ObjectFit::Cover)
+      .build();
+    assert!(matches!(
+      transform_image(&source(1, 100), tall_cover),
+      Err(TakumiError::TransformTooLarge { .. })
+    ));
+  }
+}
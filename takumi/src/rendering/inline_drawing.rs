@@ -1,28 +1,34 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, iter::once, mem::replace, sync::Arc};
 
-use image::{GenericImageView, Rgba};
+use image::{GenericImageView, Rgba, RgbaImage};
 use parley::{GlyphRun, PositionedInlineBox, PositionedLayoutItem};
 use swash::FontRef;
 use taffy::{Layout, Point};
 use zeno::{Command, PathBuilder, Stroke};
 
 use crate::{
-  Result,
+  Error, Result,
   layout::{
-    inline::{InlineBoxItem, InlineBrush, InlineLayout, ProcessedInlineSpan},
+    inline::{InlineBoxItem, InlineBrush, InlineLayout, InlineOpacityGroup, ProcessedInlineSpan},
     style::{
-      Affine, BackgroundClip, BlendMode, BorderStyle, Color, ImageScalingAlgorithm, SizedFontStyle,
-      SizedTextDecorationThickness, TextDecorationLines, TextDecorationSkipInk,
+      Affine, BackgroundClip, BackgroundOrigin, BackgroundSize, BlendMode, BorderStyle, Color,
+      Filter, ImageScalingAlgorithm, Length, SizedFontStyle, SizedTextDecorationThickness,
+      TextDecorationLines, TextDecorationSkipInk, apply_filters,
     },
     tree::LayoutTree,
   },
   rendering::{
-    BackgroundTile, BorderProperties, Canvas, ColorTile, RenderContext, collect_background_layers,
-    collect_outline_paths, draw_decoration, draw_glyph, draw_glyph_clip_image,
-    draw_glyph_text_shadow, mask_index_from_coord, overlay_area, rasterize_layers,
-    render::render_node,
+    BackgroundTile, BorderProperties, BoxInsets, Canvas, DecorationPaint, GlyphOutline,
+    RenderContext, StrokePaint, collect_clipped_background_layers, collect_outline_paths,
+    commands_to_svg_path, decoration_extent, draw_decoration, draw_decoration_line, draw_glyph,
+    draw_glyph_clip_image, draw_glyph_text_shadow, draw_mask, mask_index_from_coord, overlay_area,
+    overlay_image, rasterize_layers, render::render_node, resolve_tile_layers, viewport_area,
+  },
+  resources::{
+    emoji::EmojiStore,
+    font::{FontError, MissingFontPolicy, ResolvedGlyph},
+    image::ImageSource,
   },
-  resources::font::{FontError, ResolvedGlyph},
 };
 use taffy::{AvailableSpace, geometry::Size};
 
@@ -30,6 +36,8 @@ const UNDERLINE_SKIP_INK_ALPHA_THRESHOLD: u8 = 16;
 const SKIP_PADDING_RATIO: f32 = 0.6;
 const SKIP_PADDING_MIN: f32 = 1.0;
 const SKIP_PADDING_MAX: f32 = 3.0;
+/// The glyph fonts map characters they don't have to.
+const NOTDEF_GLYPH_ID: u32 = 0;
 
 #[derive(Clone, Copy)]
 struct GlyphLocalBounds {
@@ -45,6 +53,22 @@ struct GlyphSkipInkData {
   alpha: Box<[u8]>,
 }
 
+/// An emoji cluster drawn from the [`EmojiStore`] instead of its font glyphs.
+struct PositionedEmoji {
+  image: Arc<ImageSource>,
+  x: f32,
+  y: f32,
+  size: f32,
+}
+
+/// A character no font covers, drawn as a hollow box under [`MissingFontPolicy::Replace`].
+struct MissingGlyphBox {
+  x: f32,
+  y: f32,
+  width: f32,
+  height: f32,
+}
+
 #[derive(Clone, Copy)]
 struct InlineOutlineRect {
   span_id: u64,
@@ -105,41 +129,6 @@ fn build_glyph_bounds_cache(
   bounds
 }
 
-fn draw_decoration_segment(
-  canvas: &mut Canvas,
-  color: Color,
-  start_x: f32,
-  end_x: f32,
-  y: f32,
-  height: f32,
-  transform: Affine,
-) {
-  if end_x <= start_x {
-    return;
-  }
-
-  let x = start_x.floor();
-  let width = (end_x.ceil() - x) as u32;
-
-  let tile = ColorTile {
-    color: color.into(),
-    width,
-    height: height as u32,
-  };
-
-  if tile.width == 0 || tile.height == 0 {
-    return;
-  }
-
-  canvas.overlay_image(
-    &tile,
-    BorderProperties::default(),
-    transform * Affine::translation(x, y),
-    ImageScalingAlgorithm::Auto,
-    BlendMode::Normal,
-  );
-}
-
 fn compute_skip_padding(size: f32) -> f32 {
   (size * SKIP_PADDING_RATIO).clamp(SKIP_PADDING_MIN, SKIP_PADDING_MAX)
 }
@@ -149,7 +138,7 @@ fn draw_underline_with_skip_ink(
   canvas: &mut Canvas,
   glyph_run: &GlyphRun<'_, InlineBrush>,
   glyph_bounds_cache: &HashMap<u32, GlyphSkipInkData>,
-  color: Color,
+  paint: DecorationPaint<'_>,
   offset: f32,
   size: f32,
   layout: Layout,
@@ -157,8 +146,9 @@ fn draw_underline_with_skip_ink(
 ) {
   let run_start_x = layout.border.left + layout.padding.left + glyph_run.offset();
   let run_end_x = run_start_x + glyph_run.advance();
-  let line_top = layout.border.top + layout.padding.top + offset;
-  let line_bottom = line_top + size;
+  let decoration_style = glyph_run.style().brush.decoration_style;
+  let decoration_top = layout.border.top + layout.padding.top + offset;
+  let (line_top, line_bottom) = decoration_extent(decoration_style, decoration_top, size);
   let skip_padding = compute_skip_padding(size);
 
   let mut skip_ranges = Vec::new();
@@ -237,7 +227,7 @@ fn draw_underline_with_skip_ink(
   }
 
   if skip_ranges.is_empty() {
-    draw_decoration(canvas, glyph_run, color, offset, size, layout, transform);
+    draw_decoration(canvas, glyph_run, paint, offset, size, layout, transform);
     return;
   }
 
@@ -260,16 +250,30 @@ fn draw_underline_with_skip_ink(
   let mut current_x = run_start_x;
   for (skip_start, skip_end) in merged_ranges {
     if skip_start > current_x {
-      draw_decoration_segment(
-        canvas, color, current_x, skip_start, line_top, size, transform,
+      draw_decoration_line(
+        canvas,
+        paint,
+        decoration_style,
+        current_x,
+        skip_start,
+        decoration_top,
+        size,
+        transform,
       );
     }
     current_x = current_x.max(skip_end);
   }
 
   if run_end_x > current_x {
-    draw_decoration_segment(
-      canvas, color, current_x, run_end_x, line_top, size, transform,
+    draw_decoration_line(
+      canvas,
+      paint,
+      decoration_style,
+      current_x,
+      run_end_x,
+      decoration_top,
+      size,
+      transform,
     );
   }
 }
@@ -280,8 +284,13 @@ fn draw_glyph_run_under_overline(
   canvas: &mut Canvas,
   layout: Layout,
   context: &RenderContext,
+  clip_image: Option<&BackgroundTile>,
 ) -> Result<()> {
   let brush = &glyph_run.style().brush;
+  let paint = DecorationPaint {
+    color: brush.decoration_color,
+    background: clip_image,
+  };
 
   let run = glyph_run.run();
   let metrics = run.metrics();
@@ -305,7 +314,7 @@ fn draw_glyph_run_under_overline(
         canvas,
         glyph_run,
         &glyph_bounds_cache,
-        brush.decoration_color,
+        paint,
         offset,
         size,
         layout,
@@ -315,7 +324,7 @@ fn draw_glyph_run_under_overline(
       draw_decoration(
         canvas,
         glyph_run,
-        brush.decoration_color,
+        paint,
         offset,
         size,
         layout,
@@ -331,7 +340,7 @@ fn draw_glyph_run_under_overline(
     draw_decoration(
       canvas,
       glyph_run,
-      glyph_run.style().brush.decoration_color,
+      paint,
       glyph_run.baseline() - metrics.ascent - metrics.underline_offset,
       match brush.decoration_thickness {
         SizedTextDecorationThickness::Value(v) => v,
@@ -350,6 +359,7 @@ fn draw_glyph_run_line_through(
   canvas: &mut Canvas,
   layout: Layout,
   context: &RenderContext,
+  clip_image: Option<&BackgroundTile>,
 ) -> Result<()> {
   let brush = &glyph_run.style().brush;
   let decoration_line = brush.decoration_line;
@@ -368,7 +378,10 @@ fn draw_glyph_run_line_through(
   draw_decoration(
     canvas,
     glyph_run,
-    glyph_run.style().brush.decoration_color,
+    DecorationPaint {
+      color: brush.decoration_color,
+      background: clip_image,
+    },
     offset,
     size,
     layout,
@@ -612,6 +625,7 @@ fn draw_merged_outline_rects(
   }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw_glyph_run_content<I: GenericImageView<Pixel = Rgba<u8>>>(
   style: &SizedFontStyle,
   glyph_run: &GlyphRun<'_, InlineBrush>,
@@ -620,12 +634,16 @@ fn draw_glyph_run_content<I: GenericImageView<Pixel = Rgba<u8>>>(
   layout: Layout,
   context: &RenderContext,
   clip_image: Option<&I>,
+  stroke_image: Option<&BackgroundTile>,
 ) -> Result<()> {
   let run = glyph_run.run();
+  let stroke_paint = stroke_image.map_or(StrokePaint::Color, StrokePaint::Image);
 
   let font = FontRef::from_index(run.font().data.as_ref(), run.font().index as usize)
     .ok_or(FontError::InvalidFontIndex)?;
   let palette = font.color_palettes().next();
+  let text_rendering = context.global.text_rendering;
+  let coverage = text_rendering.coverage_table();
 
   if let Some(clip_image) = clip_image {
     for glyph in glyph_run.positioned_glyphs() {
@@ -633,18 +651,24 @@ fn draw_glyph_run_content<I: GenericImageView<Pixel = Rgba<u8>>>(
         continue;
       };
 
-      let inline_offset = Point {
-        x: layout.border.left + layout.padding.left + glyph.x,
-        y: layout.border.top + layout.padding.top + glyph.y,
-      };
+      let inline_offset = text_rendering.quantize_glyph_offset(
+        context.transform,
+        Point {
+          x: layout.border.left + layout.padding.left + glyph.x,
+          y: layout.border.top + layout.padding.top + glyph.y,
+        },
+      );
 
       draw_glyph_clip_image(
         content,
         canvas,
         style,
+        &glyph_run.style().brush,
         context.transform,
         inline_offset,
         clip_image,
+        stroke_paint,
+        coverage.as_ref(),
       )?;
     }
   }
@@ -654,19 +678,36 @@ fn draw_glyph_run_content<I: GenericImageView<Pixel = Rgba<u8>>>(
       continue;
     };
 
-    let inline_offset = Point {
-      x: layout.border.left + layout.padding.left + glyph.x,
-      y: layout.border.top + layout.padding.top + glyph.y,
-    };
+    let inline_offset = text_rendering.quantize_glyph_offset(
+      context.transform,
+      Point {
+        x: layout.border.left + layout.padding.left + glyph.x,
+        y: layout.border.top + layout.padding.top + glyph.y,
+      },
+    );
+
+    if let Some(glyph_outlines) = canvas.glyph_outlines.as_mut()
+      && let ResolvedGlyph::Outline(outline) = content
+    {
+      glyph_outlines.push(GlyphOutline {
+        glyph_id: glyph.id,
+        transform: (context.transform * Affine::translation(inline_offset.x, inline_offset.y))
+          .to_cols_array(),
+        path: commands_to_svg_path(&collect_outline_paths(outline)),
+        color: glyph_run.style().brush.color.0,
+      });
+    }
 
     draw_glyph(
       content,
       canvas,
       style,
+      &glyph_run.style().brush,
       context.transform,
       inline_offset,
-      glyph_run.style().brush.color,
       palette,
+      stroke_paint,
+      coverage.as_ref(),
     )?;
   }
 
@@ -686,10 +727,13 @@ fn draw_glyph_run_text_shadow(
       continue;
     };
 
-    let inline_offset = Point {
-      x: layout.border.left + layout.padding.left + glyph.x,
-      y: layout.border.top + layout.padding.top + glyph.y,
-    };
+    let inline_offset = context.global.text_rendering.quantize_glyph_offset(
+      context.transform,
+      Point {
+        x: layout.border.left + layout.padding.left + glyph.x,
+        y: layout.border.top + layout.padding.top + glyph.y,
+      },
+    );
 
     draw_glyph_text_shadow(content, canvas, style, context.transform, inline_offset)?;
   }
@@ -697,6 +741,186 @@ fn draw_glyph_run_text_shadow(
   Ok(())
 }
 
+/// Looks up pictures for the emoji clusters of a glyph run and removes their glyphs, so neither
+/// the glyphs nor their shadows and skip-ink bounds are painted.
+fn take_glyph_run_emojis(
+  glyph_run: &GlyphRun<'_, InlineBrush>,
+  resolved_glyphs: &mut HashMap<u32, ResolvedGlyph>,
+  text: &str,
+  emoji_store: &EmojiStore,
+) -> Vec<PositionedEmoji> {
+  let run = glyph_run.run();
+  let metrics = run.metrics();
+  let size = run.font_size();
+  // Centers the em square between the ascent and descent of the run.
+  let y = glyph_run.baseline() - (metrics.ascent - metrics.descent + size) / 2.0;
+
+  let mut emojis = Vec::new();
+  let mut x = glyph_run.offset();
+
+  for cluster in run.visual_clusters() {
+    let advance = cluster.advance();
+
+    if cluster.is_emoji()
+      && let Some(image) = text
+        .get(cluster.text_range())
+        .and_then(|emoji| emoji_store.get(emoji))
+    {
+      for glyph in cluster.glyphs() {
+        resolved_glyphs.remove(&glyph.id);
+      }
+
+      emojis.push(PositionedEmoji {
+        image,
+        x: x + (advance - size) / 2.0,
+        y,
+        size,
+      });
+    }
+
+    x += advance;
+  }
+
+  emojis
+}
+
+fn has_visible_chars(text: &str) -> bool {
+  text
+    .chars()
+    .any(|char| !char.is_whitespace() && !char.is_control())
+}
+
+/// Finds the characters of a glyph run that fell back to `.notdef` and applies the missing font
+/// policy to them, returning the boxes to draw in their place.
+///
+/// Emoji clusters count as covered when an emoji source is set, since they're drawn as pictures.
+fn take_glyph_run_missing_glyphs(
+  glyph_run: &GlyphRun<'_, InlineBrush>,
+  resolved_glyphs: &mut HashMap<u32, ResolvedGlyph>,
+  text: &str,
+  policy: MissingFontPolicy,
+  emoji_store: &EmojiStore,
+) -> Result<Vec<MissingGlyphBox>> {
+  let run = glyph_run.run();
+  let size = run.font_size();
+  let inset = size * 0.05;
+
+  let mut boxes = Vec::new();
+  let mut x = glyph_run.offset();
+
+  for cluster in run.visual_clusters() {
+    let advance = cluster.advance();
+
+    let missing = text.get(cluster.text_range()).filter(|cluster_text| {
+      has_visible_chars(cluster_text)
+        && !(cluster.is_emoji() && emoji_store.has_source())
+        && cluster.glyphs().any(|glyph| glyph.id == NOTDEF_GLYPH_ID)
+    });
+
+    if let Some(missing) = missing {
+      match policy {
+        MissingFontPolicy::Error => {
+          return Err(Error::MissingGlyphs {
+            text: missing.to_owned(),
+          });
+        }
+        MissingFontPolicy::Replace => boxes.push(MissingGlyphBox {
+          x: x + inset,
+          y: glyph_run.baseline() - size * 0.75,
+          width: (advance - inset * 2.0).max(0.0),
+          height: size * 0.75,
+        }),
+        _ => {}
+      }
+    }
+
+    x += advance;
+  }
+
+  if matches!(policy, MissingFontPolicy::Skip | MissingFontPolicy::Replace) {
+    resolved_glyphs.remove(&NOTDEF_GLYPH_ID);
+  }
+
+  Ok(boxes)
+}
+
+fn draw_missing_glyph_boxes(
+  boxes: &[MissingGlyphBox],
+  glyph_run: &GlyphRun<'_, InlineBrush>,
+  canvas: &mut Canvas,
+  layout: Layout,
+  context: &RenderContext,
+) {
+  if boxes.is_empty() {
+    return;
+  }
+
+  let stroke_width = (glyph_run.run().font_size() / 16.0).max(1.0);
+  let mut path = Vec::<Command>::with_capacity(boxes.len() * 5);
+
+  for missing_box in boxes {
+    path.add_rect(
+      [
+        layout.border.left + layout.padding.left + missing_box.x + stroke_width / 2.0,
+        layout.border.top + layout.padding.top + missing_box.y + stroke_width / 2.0,
+      ],
+      (missing_box.width - stroke_width).max(0.0),
+      (missing_box.height - stroke_width).max(0.0),
+    );
+  }
+
+  let (mask, placement) = canvas.mask_memory.render(
+    &path,
+    Some(context.transform),
+    Some(Stroke::new(stroke_width).into()),
+    &mut canvas.buffer_pool,
+  );
+  draw_mask(
+    &mut canvas.image,
+    &mask,
+    placement,
+    glyph_run.style().brush.color,
+    BlendMode::Normal,
+    &canvas.constrains,
+  );
+  canvas.buffer_pool.release(mask);
+}
+
+fn draw_glyph_run_emojis(
+  emojis: &[PositionedEmoji],
+  canvas: &mut Canvas,
+  layout: Layout,
+  context: &RenderContext,
+) -> Result<()> {
+  for emoji in emojis {
+    let size = emoji.size.round() as u32;
+    if size == 0 {
+      continue;
+    }
+
+    let image = emoji.image.render_to_rgba_image(
+      size,
+      size,
+      context.style.image_rendering,
+      context.current_color,
+    )?;
+
+    canvas.overlay_image(
+      image.as_ref(),
+      BorderProperties::default(),
+      context.transform
+        * Affine::translation(
+          layout.border.left + layout.padding.left + emoji.x,
+          layout.border.top + layout.padding.top + emoji.y,
+        ),
+      context.style.image_rendering,
+      BlendMode::Normal,
+    );
+  }
+
+  Ok(())
+}
+
 fn glyph_runs(
   inline_layout: &InlineLayout,
 ) -> impl Iterator<Item = GlyphRun<'_, InlineBrush>> + '_ {
@@ -729,12 +953,13 @@ fn resolve_inline_layout_glyphs(
       let font = FontRef::from_index(run.font().data.as_ref(), run.font().index as usize)
         .ok_or(FontError::InvalidFontIndex)?;
 
-      Ok(
-        context
-          .global
-          .font_context
-          .resolve_glyphs(&glyph_run, font, glyph_ids),
-      )
+      Ok(context.global.font_context.resolve_glyphs(
+        &glyph_run,
+        font,
+        glyph_ids,
+        &context.global.glyph_cache,
+        context.global.text_rendering.hinting,
+      ))
     })
     .collect()
 }
@@ -763,6 +988,121 @@ pub(crate) fn get_parent_x_height(
   Some(metrics.x_height * scale)
 }
 
+/// Returns the cap height of the font `run` is shaped with, scaled to its font size.
+pub(crate) fn get_run_cap_height<B: parley::Brush>(run: &parley::Run<'_, B>) -> Option<f32> {
+  let font = run.font();
+  let font_ref = FontRef::from_index(font.data.as_ref(), font.index as usize)?;
+
+  let metrics = font_ref.metrics(run.normalized_coords());
+  let units_per_em = metrics.units_per_em as f32;
+  if units_per_em == 0.0 {
+    return None;
+  }
+  Some(metrics.cap_height * run.font_size() / units_per_em)
+}
+
+/// Offscreen layers of the inline [`InlineOpacityGroup`]s, so a span with `opacity` fades
+/// its glyphs, decorations and boxes together instead of each one on its own.
+#[derive(Default)]
+pub(crate) struct InlineOpacityLayers<'a> {
+  groups: &'a [InlineOpacityGroup],
+  layers: Vec<Option<RgbaImage>>,
+}
+
+impl<'a> InlineOpacityLayers<'a> {
+  pub(crate) fn new(groups: &'a [InlineOpacityGroup]) -> Self {
+    Self {
+      groups,
+      layers: groups.iter().map(|_| None).collect(),
+    }
+  }
+
+  /// Runs `paint` against the layer of `group`, or the canvas itself outside any group.
+  pub(crate) fn paint<R>(
+    &mut self,
+    canvas: &mut Canvas,
+    group: Option<usize>,
+    paint: impl FnOnce(&mut Canvas) -> Result<R>,
+  ) -> Result<R> {
+    let Some(slot) = group.and_then(|group| self.layers.get_mut(group)) else {
+      return paint(canvas);
+    };
+
+    let layer = match slot.take() {
+      Some(layer) => layer,
+      None => {
+        let size = canvas.size();
+        canvas.buffer_pool.acquire_image(size.width, size.height)?
+      }
+    };
+
+    let source_image = replace(&mut canvas.image, layer);
+    let result = paint(canvas);
+    *slot = Some(replace(&mut canvas.image, source_image));
+
+    result
+  }
+
+  /// Composites the layers innermost first, each onto its enclosing group or the canvas.
+  pub(crate) fn composite(mut self, canvas: &mut Canvas, context: &RenderContext) -> Result<()> {
+    // Groups are collected in tree order, so every parent precedes its children.
+    for index in (0..self.layers.len()).rev() {
+      let Some(mut layer) = self.layers[index].take() else {
+        continue;
+      };
+      let group = self.groups[index];
+
+      apply_filters(
+        &mut layer,
+        &context.sizing,
+        context.current_color,
+        &mut canvas.buffer_pool,
+        once(&Filter::Opacity(group.opacity)),
+      )?;
+
+      let target = match group.parent.and_then(|parent| self.layers.get_mut(parent)) {
+        Some(slot) => match slot {
+          Some(parent_layer) => parent_layer,
+          None => slot.insert(
+            canvas
+              .buffer_pool
+              .acquire_image(layer.width(), layer.height())?,
+          ),
+        },
+        None => &mut canvas.image,
+      };
+
+      overlay_image(
+        target,
+        &layer,
+        BorderProperties::zero(),
+        Affine::IDENTITY,
+        ImageScalingAlgorithm::Auto,
+        BlendMode::Normal,
+        &[],
+        &mut canvas.mask_memory,
+        &mut canvas.buffer_pool,
+      );
+
+      canvas.buffer_pool.release_image(layer);
+    }
+
+    Ok(())
+  }
+}
+
+fn glyph_run_opacity_group(
+  glyph_run: &GlyphRun<'_, InlineBrush>,
+  spans: &[ProcessedInlineSpan<'_, '_>],
+) -> Option<usize> {
+  let span_id = glyph_run.style().brush.source_span_id?;
+
+  match spans.get(span_id as usize)? {
+    ProcessedInlineSpan::Text { opacity_group, .. } => *opacity_group,
+    ProcessedInlineSpan::Box(_) => None,
+  }
+}
+
 pub(crate) fn draw_inline_box(
   inline_box: &PositionedInlineBox,
   item: &InlineBoxItem<'_, '_>,
@@ -827,17 +1167,104 @@ pub(crate) fn draw_inline_box(
   Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn draw_inline_layout(
   context: &RenderContext,
   canvas: &mut Canvas,
   layout: Layout,
   inline_layout: InlineLayout,
+  text: &str,
   font_style: &SizedFontStyle,
   spans: &[ProcessedInlineSpan<'_, '_>],
+  opacity_layers: &mut InlineOpacityLayers<'_>,
 ) -> Result<Vec<PositionedInlineBox>> {
-  let resolved_glyph_runs = resolve_inline_layout_glyphs(context, &inline_layout)?;
-  let clip_image = if context.style.background_clip == BackgroundClip::Text {
-    let layers = collect_background_layers(context, layout.size, &mut canvas.buffer_pool)?;
+  let mut resolved_glyph_runs = resolve_inline_layout_glyphs(context, &inline_layout)?;
+  let emoji_store = &context.global.emoji_store;
+
+  // Without any font loaded, text doesn't shape into glyph runs at all. Neither does text
+  // clipped away by the height of its box, which is shaped again without the limit to tell.
+  if context.missing_font_policy == MissingFontPolicy::Error
+    && resolved_glyph_runs.is_empty()
+    && has_visible_chars(text)
+    && !context
+      .global
+      .font_context
+      .shapes_any_glyph(font_style.into(), text)
+  {
+    return Err(Error::MissingGlyphs {
+      text: text.to_owned(),
+    });
+  }
+
+  let glyph_run_missing_glyphs: Vec<Vec<MissingGlyphBox>> =
+    if context.missing_font_policy == MissingFontPolicy::Notdef {
+      Vec::new()
+    } else {
+      glyph_runs(&inline_layout)
+        .zip(resolved_glyph_runs.iter_mut())
+        .map(|(glyph_run, resolved_glyphs)| {
+          take_glyph_run_missing_glyphs(
+            &glyph_run,
+            resolved_glyphs,
+            text,
+            context.missing_font_policy,
+            emoji_store,
+          )
+        })
+        .collect::<Result<_>>()?
+    };
+  let glyph_run_emojis: Vec<Vec<PositionedEmoji>> = if emoji_store.has_source() {
+    glyph_runs(&inline_layout)
+      .zip(resolved_glyph_runs.iter_mut())
+      .map(|(glyph_run, resolved_glyphs)| {
+        take_glyph_run_emojis(&glyph_run, resolved_glyphs, text, emoji_store)
+      })
+      .collect()
+  } else {
+    Vec::new()
+  };
+  let clip_image = if context
+    .style
+    .background_clip
+    .contains(&BackgroundClip::Text)
+  {
+    let layers = collect_clipped_background_layers(
+      context,
+      BackgroundClip::Text,
+      layout.size,
+      (&layout).into(),
+      viewport_area(context.transform, canvas.size()),
+      &mut canvas.buffer_pool,
+    )?;
+
+    rasterize_layers(
+      layers,
+      layout.size.map(|x| x as u32),
+      context,
+      BorderProperties::default(),
+      Affine::IDENTITY,
+      &mut canvas.mask_memory,
+      &mut canvas.buffer_pool,
+    )?
+  } else {
+    None
+  };
+  let stroke_image = if let Some(images) = context.style.text_stroke_image.as_deref() {
+    let layers = resolve_tile_layers(
+      images,
+      &[],
+      &[BackgroundSize::Explicit {
+        width: Length::Percentage(100.0),
+        height: Length::Percentage(100.0),
+      }],
+      &[],
+      &[],
+      &[BackgroundOrigin::BorderBox],
+      context,
+      layout.size.map(|x| x as u32),
+      BoxInsets::default(),
+      &mut canvas.buffer_pool,
+    )?;
 
     rasterize_layers(
       layers,
@@ -858,23 +1285,44 @@ pub(crate) fn draw_inline_layout(
   // Reference: https://www.w3.org/TR/css-text-decor-3/#painting-order
   for (glyph_run, resolved_glyphs) in glyph_runs_with_resolved(&inline_layout, &resolved_glyph_runs)
   {
-    draw_glyph_run_text_shadow(
-      font_style,
-      &glyph_run,
-      resolved_glyphs,
+    opacity_layers.paint(
       canvas,
-      layout,
-      context,
+      glyph_run_opacity_group(&glyph_run, spans),
+      |canvas| {
+        draw_glyph_run_text_shadow(
+          font_style,
+          &glyph_run,
+          resolved_glyphs,
+          canvas,
+          layout,
+          context,
+        )
+      },
     )?;
   }
 
   for (glyph_run, resolved_glyphs) in glyph_runs_with_resolved(&inline_layout, &resolved_glyph_runs)
   {
-    draw_glyph_run_under_overline(&glyph_run, resolved_glyphs, canvas, layout, context)?;
+    opacity_layers.paint(
+      canvas,
+      glyph_run_opacity_group(&glyph_run, spans),
+      |canvas| {
+        draw_glyph_run_under_overline(
+          &glyph_run,
+          resolved_glyphs,
+          canvas,
+          layout,
+          context,
+          clip_image.as_ref(),
+        )
+      },
+    )?;
   }
 
   let parent_x_height = get_parent_x_height(context, font_style);
   let mut glyph_runs_with_resolved = glyph_runs_with_resolved(&inline_layout, &resolved_glyph_runs);
+  let mut glyph_run_emojis = glyph_run_emojis.iter();
+  let mut glyph_run_missing_glyphs = glyph_run_missing_glyphs.iter();
   for (line_index, line) in inline_layout.lines().enumerate() {
     let line_metrics = line.metrics();
 
@@ -884,14 +1332,30 @@ pub(crate) fn draw_inline_layout(
           let Some((_, resolved_glyphs)) = glyph_runs_with_resolved.next() else {
             continue;
           };
-          draw_glyph_run_content(
-            font_style,
-            &glyph_run,
-            resolved_glyphs,
+          let emojis = glyph_run_emojis.next();
+          let missing_glyphs = glyph_run_missing_glyphs.next();
+          opacity_layers.paint(
             canvas,
-            layout,
-            context,
-            clip_image.as_ref(),
+            glyph_run_opacity_group(&glyph_run, spans),
+            |canvas| {
+              draw_glyph_run_content(
+                font_style,
+                &glyph_run,
+                resolved_glyphs,
+                canvas,
+                layout,
+                context,
+                clip_image.as_ref(),
+                stroke_image.as_ref(),
+              )?;
+              if let Some(emojis) = emojis {
+                draw_glyph_run_emojis(emojis, canvas, layout, context)?;
+              }
+              if let Some(boxes) = missing_glyphs {
+                draw_missing_glyph_boxes(boxes, &glyph_run, canvas, layout, context);
+              }
+              Ok(())
+            },
           )?;
           if let Some(outline_rect) = collect_glyph_run_outline_rect(
             &glyph_run,
@@ -923,11 +1387,19 @@ pub(crate) fn draw_inline_layout(
   draw_merged_outline_rects(inline_outline_rects, canvas, spans, context.transform);
 
   for glyph_run in glyph_runs(&inline_layout) {
-    draw_glyph_run_line_through(&glyph_run, canvas, layout, context)?;
+    opacity_layers.paint(
+      canvas,
+      glyph_run_opacity_group(&glyph_run, spans),
+      |canvas| {
+        draw_glyph_run_line_through(&glyph_run, canvas, layout, context, clip_image.as_ref())
+      },
+    )?;
   }
 
-  if let Some(BackgroundTile::Image(image)) = clip_image {
-    canvas.buffer_pool.release_image(image);
+  for tile in [clip_image, stroke_image].into_iter().flatten() {
+    if let BackgroundTile::Image(image) = tile {
+      canvas.buffer_pool.release_image(image);
+    }
   }
 
   Ok(positioned_inline_boxes)
@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+
+use image::RgbaImage;
+use taffy::{Layout, NodeId, Size};
+
+use crate::{
+  Result,
+  layout::{
+    node::NodeKind,
+    style::{Affine, ComputedStyle},
+    tree::{LayoutResults, RenderNode},
+  },
+};
+
+/// Tolerance for treating a translation delta between frames as whole device pixels.
+const PIXEL_SNAP_EPSILON: f32 = 1e-3;
+
+/// Everything that determines how an animated layer's subtree paints, apart from the
+/// compositing properties (`opacity`, `filter` and transforms) applied to the painted layer.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LayerFingerprint {
+  style: ComputedStyle,
+  container_size: Size<Option<f32>>,
+  layouts: Vec<Layout>,
+}
+
+impl LayerFingerprint {
+  /// Fingerprints an isolated node whose own animations may be skipped by reusing its layer.
+  ///
+  /// Returns `None` when the node is not animated, or when its subtree may paint differently
+  /// on the next frame regardless of the fingerprint, such as animated descendants or custom
+  /// drawers that run arbitrary code.
+  pub(crate) fn new(
+    node: &RenderNode<'_>,
+    layout_results: &LayoutResults,
+    node_id: NodeId,
+    container_size: Size<Option<f32>>,
+  ) -> Result<Option<Self>> {
+    if node.context.style.animation_name.0.is_empty() || is_custom(node) {
+      return Ok(None);
+    }
+
+    let mut pending = node.children.iter().flatten().collect::<Vec<_>>();
+    while let Some(descendant) = pending.pop() {
+      if !descendant.context.style.animation_name.0.is_empty() || is_custom(descendant) {
+        return Ok(None);
+      }
+
+      pending.extend(descendant.children.iter().flatten());
+    }
+
+    let mut layouts = Vec::new();
+    let mut pending_ids = vec![node_id];
+    while let Some(id) = pending_ids.pop() {
+      layouts.push(*layout_results.layout(id)?);
+      pending_ids.extend(layout_results.children(id)?.iter().copied());
+    }
+
+    let defaults = ComputedStyle::default();
+    let mut style = (*node.context.style).clone();
+    style.opacity = defaults.opacity;
+    style.filter = defaults.filter;
+    style.translate = defaults.translate;
+    style.rotate = defaults.rotate;
+    style.scale = defaults.scale;
+    style.transform = defaults.transform;
+    style.transform_origin = defaults.transform_origin;
+
+    Ok(Some(Self {
+      style,
+      container_size,
+      layouts,
+    }))
+  }
+}
+
+fn is_custom(node: &RenderNode<'_>) -> bool {
+  node
+    .node
+    .as_ref()
+    .is_some_and(|node| matches!(node.kind, NodeKind::Custom(_)))
+}
+
+struct CachedLayer {
+  fingerprint: LayerFingerprint,
+  transform: Affine,
+  /// Whether any painted pixel touches the canvas edge, meaning content may have been cut off.
+  touches_edge: bool,
+  image: RgbaImage,
+}
+
+/// Painted isolated layers from the previous animation frame, keyed by node path.
+///
+/// Layers are captured before the node's filters and opacity are applied, so a layer can be
+/// reused when only those change, or when the layer moves by whole device pixels.
+#[derive(Default)]
+pub(crate) struct LayerCache {
+  layers: HashMap<Vec<usize>, CachedLayer>,
+}
+
+impl LayerCache {
+  pub(crate) fn clear(&mut self) {
+    self.layers.clear();
+  }
+
+  /// Copies the cached layer for `path` into the blank `target` at `transform`.
+  ///
+  /// Returns `false` without touching `target` when the layer has to be repainted.
+  pub(crate) fn reuse(
+    &self,
+    path: &[usize],
+    fingerprint: &LayerFingerprint,
+    transform: Affine,
+    target: &mut RgbaImage,
+  ) -> bool {
+    let Some(layer) = self.layers.get(path) else {
+      return false;
+    };
+
+    if layer.fingerprint != *fingerprint || layer.image.dimensions() != target.dimensions() {
+      return false;
+    }
+
+    if layer.transform == transform {
+      target.copy_from_slice(&layer.image);
+      return true;
+    }
+
+    let Some((dx, dy)) = pixel_translation(layer.transform, transform) else {
+      return false;
+    };
+
+    // Content cut off at the canvas edge would be missing once shifted into view.
+    if layer.touches_edge {
+      return false;
+    }
+
+    shift_image(&layer.image, target, dx, dy);
+    true
+  }
+
+  /// Stores the painted layer for `path`, replacing the previous frame's layer.
+  pub(crate) fn store(
+    &mut self,
+    path: Vec<usize>,
+    fingerprint: LayerFingerprint,
+    transform: Affine,
+    image: &RgbaImage,
+  ) {
+    let touches_edge = image_touches_edge(image);
+
+    match self.layers.get_mut(&path) {
+      Some(layer) if layer.image.dimensions() == image.dimensions() => {
+        layer.image.copy_from_slice(image);
+        layer.fingerprint = fingerprint;
+        layer.transform = transform;
+        layer.touches_edge = touches_edge;
+      }
+      _ => {
+        self.layers.insert(
+          path,
+          CachedLayer {
+            fingerprint,
+            transform,
+            touches_edge,
+            image: image.clone(),
+          },
+        );
+      }
+    }
+  }
+}
+
+/// Returns the whole-pixel offset from `from` to `to` if they differ only by such a translation.
+fn pixel_translation(from: Affine, to: Affine) -> Option<(i64, i64)> {
+  if from.a != to.a || from.b != to.b || from.c != to.c || from.d != to.d {
+    return None;
+  }
+
+  let dx = to.x - from.x;
+  let dy = to.y - from.y;
+
+  if (dx - dx.round()).abs() > PIXEL_SNAP_EPSILON || (dy - dy.round()).abs() > PIXEL_SNAP_EPSILON {
+    return None;
+  }
+
+  Some((dx.round() as i64, dy.round() as i64))
+}
+
+fn image_touches_edge(image: &RgbaImage) -> bool {
+  let (width, height) = image.dimensions();
+  if width == 0 || height == 0 {
+    return false;
+  }
+
+  let painted = |x: u32, y: u32| image.get_pixel(x, y).0[3] != 0;
+
+  (0..width).any(|x| painted(x, 0) || painted(x, height - 1))
+    || (0..height).any(|y| painted(0, y) || painted(width - 1, y))
+}
+
+/// Copies `source` into the blank `target` offset by `(dx, dy)` pixels.
+fn shift_image(source: &RgbaImage, target: &mut RgbaImage, dx: i64, dy: i64) {
+  let (width, height) = source.dimensions();
+  let (width, height) = (i64::from(width), i64::from(height));
+
+  let copy_width = width - dx.abs();
+  if copy_width <= 0 || dy.abs() >= height {
+    return;
+  }
+
+  let row_bytes = copy_width as usize * 4;
+  let source_x = (-dx).max(0) as usize * 4;
+  let target_x = dx.max(0) as usize * 4;
+  let stride = width as usize * 4;
+
+  for target_y in dy.max(0)..(height + dy).min(height) {
+    let source_y = (target_y - dy) as usize;
+    let target_y = target_y as usize;
+
+    let source_start = source_y * stride + source_x;
+    let target_start = target_y * stride + target_x;
+
+    let (source, target) = (source.as_raw(), &mut **target);
+    target[target_start..target_start + row_bytes]
+      .copy_from_slice(&source[source_start..source_start + row_bytes]);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use image::{Rgba, RgbaImage};
+
+  use super::{pixel_translation, shift_image};
+  use crate::layout::style::Affine;
+
+  #[test]
+  fn pixel_translation_accepts_whole_pixel_offsets_only() {
+    let from = Affine::translation(10.0, 4.0);
+
+    assert_eq!(
+      pixel_translation(from, Affine::translation(13.0, 2.0)),
+      Some((3, -2))
+    );
+    assert_eq!(
+      pixel_translation(from, Affine::translation(10.5, 4.0)),
+      None
+    );
+    assert_eq!(
+      pixel_translation(from, Affine::scale(2.0, 2.0) * from),
+      None
+    );
+  }
+
+  #[test]
+  fn shift_image_moves_pixels_and_drops_overflow() {
+    let source = RgbaImage::from_fn(3, 2, |x, y| Rgba([x as u8, y as u8, 0, 255]));
+    let mut target = RgbaImage::new(3, 2);
+
+    shift_image(&source, &mut target, 1, 1);
+
+    assert_eq!(target.get_pixel(0, 0), &Rgba([0, 0, 0, 0]));
+    assert_eq!(target.get_pixel(1, 1), &Rgba([0, 0, 0, 255]));
+    assert_eq!(target.get_pixel(2, 1), &Rgba([1, 0, 0, 255]));
+  }
+}
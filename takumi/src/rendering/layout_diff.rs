@@ -0,0 +1,306 @@
+use serde::{Deserialize, Serialize};
+use taffy::Point;
+
+use crate::{layout::style::Affine, rendering::MeasuredNode};
+
+/// An axis-aligned rectangle in output pixel coordinates.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutRegion {
+  /// The left edge of the region.
+  pub x: f32,
+  /// The top edge of the region.
+  pub y: f32,
+  /// The width of the region.
+  pub width: f32,
+  /// The height of the region.
+  pub height: f32,
+}
+
+impl LayoutRegion {
+  /// Returns true if the region covers no area.
+  pub fn is_empty(&self) -> bool {
+    self.width <= 0.0 || self.height <= 0.0
+  }
+
+  /// Returns the smallest region containing both regions.
+  pub fn union(&self, other: &LayoutRegion) -> LayoutRegion {
+    if self.is_empty() {
+      return *other;
+    }
+
+    if other.is_empty() {
+      return *self;
+    }
+
+    let x = self.x.min(other.x);
+    let y = self.y.min(other.y);
+
+    LayoutRegion {
+      x,
+      y,
+      width: (self.x + self.width).max(other.x + other.width) - x,
+      height: (self.y + self.height).max(other.y + other.height) - y,
+    }
+  }
+
+  /// Returns true if the regions overlap or touch.
+  pub fn intersects(&self, other: &LayoutRegion) -> bool {
+    self.x <= other.x + other.width
+      && other.x <= self.x + self.width
+      && self.y <= other.y + other.height
+      && other.y <= self.y + self.height
+  }
+}
+
+impl LayoutRegion {
+  /// Returns the axis-aligned bounding box of the region after `transform`.
+  pub(crate) fn transform(&self, transform: Affine) -> LayoutRegion {
+    let corners = [
+      Point {
+        x: self.x,
+        y: self.y,
+      },
+      Point {
+        x: self.x + self.width,
+        y: self.y,
+      },
+      Point {
+        x: self.x,
+        y: self.y + self.height,
+      },
+      Point {
+        x: self.x + self.width,
+        y: self.y + self.height,
+      },
+    ]
+    .map(|corner| transform.transform_point(corner));
+
+    let (min_x, max_x, min_y, max_y) = corners.iter().fold(
+      (f32::MAX, f32::MIN, f32::MAX, f32::MIN),
+      |(min_x, max_x, min_y, max_y), point| {
+        (
+          min_x.min(point.x),
+          max_x.max(point.x),
+          min_y.min(point.y),
+          max_y.max(point.y),
+        )
+      },
+    );
+
+    LayoutRegion {
+      x: min_x,
+      y: min_y,
+      width: max_x - min_x,
+      height: max_y - min_y,
+    }
+  }
+}
+
+impl MeasuredNode {
+  /// Returns the axis-aligned bounding box of this node's border box after its transform.
+  pub fn bounds(&self) -> LayoutRegion {
+    let [a, b, c, d, x, y] = self.transform;
+
+    LayoutRegion {
+      x: 0.0,
+      y: 0.0,
+      width: self.width,
+      height: self.height,
+    }
+    .transform(Affine { a, b, c, d, x, y })
+  }
+
+  fn subtree_bounds(&self) -> LayoutRegion {
+    let mut bounds = self.bounds();
+    let mut stack = self.children.iter().collect::<Vec<_>>();
+
+    while let Some(node) = stack.pop() {
+      bounds = bounds.union(&node.bounds());
+      stack.extend(node.children.iter());
+    }
+
+    bounds
+  }
+
+  fn same_box(&self, other: &MeasuredNode) -> bool {
+    self.width == other.width
+      && self.height == other.height
+      && self.transform == other.transform
+      && self.runs == other.runs
+  }
+}
+
+/// Compares two measured layouts and returns the regions of the output that differ.
+///
+/// A node whose size, transform or text runs changed marks its whole subtree in both layouts
+/// as changed, as do nodes that were added or removed. Overlapping regions are merged.
+/// Paint-only changes such as colors don't affect layout and are not reported.
+pub fn diff_layouts(before: &MeasuredNode, after: &MeasuredNode) -> Vec<LayoutRegion> {
+  let mut regions = Vec::new();
+  let mut stack = vec![(before, after)];
+
+  while let Some((before, after)) = stack.pop() {
+    if !before.same_box(after) {
+      regions.push(before.subtree_bounds());
+      regions.push(after.subtree_bounds());
+      continue;
+    }
+
+    let common = before.children.len().min(after.children.len());
+
+    regions.extend(
+      before.children[common..]
+        .iter()
+        .chain(&after.children[common..])
+        .map(MeasuredNode::subtree_bounds),
+    );
+
+    stack.extend(before.children.iter().zip(&after.children));
+  }
+
+  merge_regions(regions)
+}
+
+fn merge_regions(mut regions: Vec<LayoutRegion>) -> Vec<LayoutRegion> {
+  regions.retain(|region| !region.is_empty());
+
+  let mut merged: Vec<LayoutRegion> = Vec::with_capacity(regions.len());
+
+  while let Some(mut region) = regions.pop() {
+    // Absorbing a region can make it overlap ones that were already merged, so re-check them.
+    while let Some(index) = merged
+      .iter()
+      .position(|existing| existing.intersects(&region))
+    {
+      region = region.union(&merged.swap_remove(index));
+    }
+
+    merged.push(region);
+  }
+
+  merged.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+  merged
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::rendering::MeasuredTextRun;
+
+  use super::*;
+
+  fn node(x: f32, y: f32, width: f32, height: f32, children: Vec<MeasuredNode>) -> MeasuredNode {
+    MeasuredNode {
+      width,
+      height,
+      transform: [1.0, 0.0, 0.0, 1.0, x, y],
+      children,
+      runs: Vec::new(),
+      lines: Vec::new(),
+    }
+  }
+
+  #[test]
+  fn identical_layouts_have_no_changes() {
+    let layout = node(
+      0.0,
+      0.0,
+      100.0,
+      100.0,
+      vec![node(10.0, 10.0, 20.0, 20.0, vec![])],
+    );
+
+    assert!(diff_layouts(&layout, &layout).is_empty());
+  }
+
+  #[test]
+  fn moved_child_reports_old_and_new_bounds() {
+    let before = node(
+      0.0,
+      0.0,
+      100.0,
+      100.0,
+      vec![node(0.0, 0.0, 10.0, 10.0, vec![])],
+    );
+    let after = node(
+      0.0,
+      0.0,
+      100.0,
+      100.0,
+      vec![node(50.0, 50.0, 10.0, 10.0, vec![])],
+    );
+
+    assert_eq!(
+      diff_layouts(&before, &after),
+      vec![
+        LayoutRegion {
+          x: 0.0,
+          y: 0.0,
+          width: 10.0,
+          height: 10.0,
+        },
+        LayoutRegion {
+          x: 50.0,
+          y: 50.0,
+          width: 10.0,
+          height: 10.0,
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn text_changes_and_added_children_are_merged() {
+    let mut text = node(0.0, 0.0, 40.0, 20.0, vec![]);
+    text.runs.push(MeasuredTextRun {
+      text: "before".to_owned(),
+      x: 0.0,
+      y: 0.0,
+      width: 40.0,
+      height: 20.0,
+    });
+
+    let before = node(0.0, 0.0, 100.0, 100.0, vec![text.clone()]);
+
+    text.runs[0].text = "after".to_owned();
+    let after = node(
+      0.0,
+      0.0,
+      100.0,
+      100.0,
+      vec![text, node(30.0, 10.0, 20.0, 20.0, vec![])],
+    );
+
+    assert_eq!(
+      diff_layouts(&before, &after),
+      vec![LayoutRegion {
+        x: 0.0,
+        y: 0.0,
+        width: 50.0,
+        height: 30.0,
+      }]
+    );
+  }
+
+  #[test]
+  fn bounds_account_for_rotation() {
+    let rotated = MeasuredNode {
+      width: 10.0,
+      height: 10.0,
+      transform: [0.0, 1.0, -1.0, 0.0, 10.0, 0.0],
+      children: Vec::new(),
+      runs: Vec::new(),
+      lines: Vec::new(),
+    };
+
+    assert_eq!(
+      rotated.bounds(),
+      LayoutRegion {
+        x: 0.0,
+        y: 0.0,
+        width: 10.0,
+        height: 10.0,
+      }
+    );
+  }
+}
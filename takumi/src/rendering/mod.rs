@@ -7,13 +7,35 @@ mod components;
 /// Debug drawing utilities
 mod debug_drawing;
 mod dithering;
+/// Reuse of unchanged regions between animation frames
+mod frame_cache;
+/// Changed regions between consecutive renders
+mod frame_diff;
+/// Scaled glyphs reused across renders
+mod glyph_cache;
+/// Point queries on measured layouts
+mod hit_test;
 /// Image drawing functions
 mod image_drawing;
+/// Standalone image resizing
+mod image_transform;
 pub(crate) mod inline_drawing;
+/// Reuse of painted layers between animation frames
+mod layer_cache;
+/// Layout comparison for partial updates
+mod layout_diff;
+/// Per-node timings of profiled renders
+mod profile;
 /// Main image renderer and viewport management
 mod render;
+/// Render hashing for cache keys
+mod render_hash;
 /// Text drawing functions
 mod text_drawing;
+/// Vector outlines of drawn text
+mod text_outline;
+/// Glyph rasterization options
+mod text_rendering;
 mod webp;
 mod write;
 
@@ -27,18 +49,31 @@ pub(crate) use canvas::*;
 pub(crate) use components::*;
 pub(crate) use debug_drawing::*;
 pub use dithering::*;
+pub(crate) use frame_cache::*;
+pub use frame_diff::*;
+pub use glyph_cache::*;
 pub(crate) use image_drawing::*;
+pub use image_transform::*;
+pub(crate) use layer_cache::*;
+pub use layout_diff::*;
+pub(crate) use profile::{Instant, LayoutTiming, Profiler, record_draw_time};
+pub use profile::{NodeProfile, RenderProfile};
 pub use render::*;
+pub use render_hash::*;
 pub(crate) use text_drawing::*;
+pub use text_outline::GlyphOutline;
+pub(crate) use text_outline::commands_to_svg_path;
+pub use text_rendering::*;
 pub use write::*;
 
 use crate::{
   GlobalContext,
   layout::{
     Viewport,
+    node::CustomDraw,
     style::{Affine, CalcArena, Color, ComputedStyle, StyleSheet},
   },
-  resources::image::ImageSource,
+  resources::{font::MissingFontPolicy, image::ImageSource},
 };
 
 /// The sizing context used for length value resolving.
@@ -50,11 +85,18 @@ pub(crate) struct Sizing {
   pub(crate) container_size: Size<Option<f32>>,
   /// The font size in pixels.
   pub(crate) font_size: f32,
+  /// The effective `zoom`, the product of the zoom of the node and all of its ancestors.
+  pub(crate) zoom: f32,
   /// The calc arena shared by the current layout tree.
   pub(crate) calc_arena: Rc<CalcArena>,
 }
 
 impl Sizing {
+  /// Device pixels per CSS pixel, combining the device pixel ratio with the effective zoom.
+  pub(crate) fn pixel_ratio(&self) -> f32 {
+    self.viewport.device_pixel_ratio * self.zoom
+  }
+
   pub(crate) fn query_container_width(&self) -> f32 {
     self
       .container_size
@@ -82,16 +124,28 @@ pub(crate) struct RenderContext<'g> {
   pub(crate) sizing: Sizing,
   /// What the `currentColor` value is resolved to.
   pub(crate) current_color: Color,
+  /// The background colors of the node and its ancestors composited together, used by `contrast-auto`.
+  pub(crate) backdrop_color: Color,
   /// The style after inheritance.
   pub(crate) style: Box<ComputedStyle>,
   /// The active time for animation sampling.
   pub(crate) time: u64,
+  /// The injected current time as a Unix timestamp in milliseconds.
+  pub(crate) now_ms: Option<u64>,
+  /// The seed for random variation.
+  pub(crate) seed: u64,
+  /// The BCP 47 locale text node values are formatted for.
+  pub(crate) locale: Option<Rc<str>>,
+  /// What happens to text no font covers.
+  pub(crate) missing_font_policy: MissingFontPolicy,
   /// Whether to draw debug borders.
   pub(crate) draw_debug_border: bool,
   /// The resources fetched externally.
   pub(crate) fetched_resources: HashMap<Arc<str>, Arc<ImageSource>>,
   /// The stylesheets to apply before layout/rendering.
   pub(crate) stylesheet: Rc<StyleSheet>,
+  /// Drawers for custom nodes, looked up by name.
+  pub(crate) custom_drawers: Rc<HashMap<Arc<str>, Arc<dyn CustomDraw>>>,
 }
 
 impl<'g> RenderContext<'g> {
@@ -107,16 +161,23 @@ impl<'g> RenderContext<'g> {
       sizing: Sizing {
         viewport,
         container_size: Size::NONE,
-        font_size: viewport.font_size,
+        font_size: viewport.font_size * viewport.device_pixel_ratio,
+        zoom: 1.0,
         calc_arena: Rc::new(CalcArena::default()),
       },
       transform: Affine::IDENTITY,
       current_color: Color::black(),
+      backdrop_color: Color::transparent(),
       style: Box::default(),
       time,
+      now_ms: None,
+      seed: 0,
+      locale: None,
+      missing_font_policy: global.missing_font_policy(),
       draw_debug_border: false,
       fetched_resources,
       stylesheet,
+      custom_drawers: Rc::default(),
     }
   }
 
@@ -0,0 +1,188 @@
+use std::{collections::HashMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use taffy::NodeId;
+
+use crate::{
+  Result,
+  layout::tree::{LayoutResults, RenderNode},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::Instant;
+
+/// Stand-in for [`std::time::Instant`], which panics on wasm32 without a JS clock.
+///
+/// Every timing measured with it is zero.
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Copy)]
+pub(crate) struct Instant;
+
+#[cfg(target_arch = "wasm32")]
+impl Instant {
+  pub(crate) fn now() -> Self {
+    Self
+  }
+
+  pub(crate) fn elapsed(&self) -> Duration {
+    Duration::ZERO
+  }
+}
+
+/// Time spent laying out a single node.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct LayoutTiming {
+  /// Time spent measuring the node's content, such as shaping its text.
+  pub(crate) measure: Duration,
+  /// Time spent laying out the node and its descendants, including measuring.
+  pub(crate) layout: Duration,
+}
+
+impl LayoutTiming {
+  pub(crate) fn add(&mut self, other: LayoutTiming) {
+    self.measure += other.measure;
+    self.layout += other.layout;
+  }
+}
+
+/// Timings of a render made with [`RenderOptionsBuilder::profile`](crate::rendering::RenderOptionsBuilder),
+/// returned by [`render_with_profile`](crate::rendering::render_with_profile).
+///
+/// All times are wall-clock milliseconds. They are always zero on wasm32, which has no clock
+/// without JS bindings.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderProfile {
+  /// Time spent resolving styles and building the render tree.
+  pub prepare_ms: f64,
+  /// Time spent laying out the tree.
+  pub layout_ms: f64,
+  /// Time spent painting the tree.
+  pub draw_ms: f64,
+  /// Time spent on the whole render, including encoding-independent post-processing.
+  pub total_ms: f64,
+  /// Timings of the root node and its descendants.
+  pub root: NodeProfile,
+}
+
+/// Timings of a single node in a [`RenderProfile`].
+///
+/// Nodes laid out as inline content belong to their inline root, which accounts for their time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeProfile {
+  /// The node's tag name, if any.
+  pub tag_name: Option<String>,
+  /// The node's `id`, if any.
+  pub id: Option<String>,
+  /// Child indices leading from the root to this node.
+  pub path: Vec<usize>,
+  /// Time spent measuring the node's own content, such as shaping its text.
+  pub measure_ms: f64,
+  /// Time spent laying out the node, including its descendants.
+  pub layout_ms: f64,
+  /// Time spent painting the node itself, excluding its descendants.
+  pub draw_ms: f64,
+  /// Timings of the node's children.
+  pub children: Vec<NodeProfile>,
+}
+
+/// Stage timings of a render being profiled.
+pub(crate) struct Profiler {
+  pub(crate) started: Instant,
+  pub(crate) prepare: Duration,
+  pub(crate) layout: Duration,
+}
+
+impl Profiler {
+  /// Builds the profile once the tree has been painted in `draw`.
+  pub(crate) fn finish(
+    self,
+    root: &RenderNode<'_>,
+    layout_results: &LayoutResults,
+    draw: Duration,
+    draw_timings: &HashMap<Vec<usize>, Duration>,
+  ) -> Result<RenderProfile> {
+    let root = collect_node_profiles(root, layout_results, draw_timings)?;
+
+    Ok(RenderProfile {
+      prepare_ms: to_ms(self.prepare),
+      layout_ms: to_ms(self.layout),
+      draw_ms: to_ms(draw),
+      total_ms: to_ms(self.started.elapsed()),
+      root,
+    })
+  }
+}
+
+/// Adds the time since the visit to `path` started to the draw time of that node.
+pub(crate) fn record_draw_time(
+  draw_timings: &mut HashMap<Vec<usize>, Duration>,
+  visit: Option<(Vec<usize>, Instant)>,
+) {
+  if let Some((path, started)) = visit {
+    *draw_timings.entry(path).or_default() += started.elapsed();
+  }
+}
+
+fn to_ms(duration: Duration) -> f64 {
+  duration.as_secs_f64() * 1000.0
+}
+
+fn collect_node_profiles(
+  root: &RenderNode<'_>,
+  layout_results: &LayoutResults,
+  draw_timings: &HashMap<Vec<usize>, Duration>,
+) -> Result<NodeProfile> {
+  // Nodes in preorder with the index of their parent, assembled into a tree afterwards.
+  let mut profiles: Vec<(Option<usize>, NodeProfile)> = Vec::new();
+  let mut pending = vec![(root, layout_results.root_node_id(), Vec::new(), None)];
+
+  while let Some((current, node_id, path, parent)) = pending.pop() {
+    let index = profiles.len();
+    let metadata = current.node.as_ref().map(|node| &node.metadata);
+    let timing = layout_results.timing(node_id);
+
+    profiles.push((
+      parent,
+      NodeProfile {
+        tag_name: metadata.and_then(|metadata| metadata.tag_name.as_deref().map(Into::into)),
+        id: metadata.and_then(|metadata| metadata.id.as_deref().map(Into::into)),
+        measure_ms: to_ms(timing.measure),
+        layout_ms: to_ms(timing.layout),
+        draw_ms: draw_timings.get(&path).copied().map_or(0.0, to_ms),
+        path: path.clone(),
+        children: Vec::new(),
+      },
+    ));
+
+    if current.should_create_inline_layout() {
+      continue;
+    }
+
+    let Some(children) = current.children.as_deref() else {
+      continue;
+    };
+
+    let child_ids: &[NodeId] = layout_results.children(node_id)?;
+    for (child_index, (child, &child_id)) in children.iter().zip(child_ids).enumerate().rev() {
+      let mut child_path = path.clone();
+      child_path.push(child_index);
+      pending.push((child, child_id, child_path, Some(index)));
+    }
+  }
+
+  // Children come after their parent, so folding from the back completes every subtree
+  // before it is attached. They are attached last to first, hence the reverse.
+  while let Some((parent, mut profile)) = profiles.pop() {
+    profile.children.reverse();
+
+    let Some(parent) = parent else {
+      return Ok(profile);
+    };
+
+    profiles[parent].1.children.push(profile);
+  }
+
+  unreachable!()
+}
@@ -1,9 +1,16 @@
-use std::{collections::HashMap, mem::replace, ops::Range, sync::Arc};
+use std::{
+  collections::HashMap,
+  mem::{replace, take},
+  ops::Range,
+  ptr,
+  sync::Arc,
+};
 
-use image::RgbaImage;
+use image::{RgbaImage, imageops::crop_imm};
 use parley::{GlyphRun, PositionedLayoutItem};
-use serde::Serialize;
-use taffy::{AvailableSpace, Layout, NodeId, TaffyError, geometry::Size};
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use taffy::{AvailableSpace, Layout, NodeId, Rect, TaffyError, geometry::Size};
 use typed_builder::TypedBuilder;
 
 use crate::{
@@ -14,19 +21,27 @@ use crate::{
       InlineBrush, InlineLayoutStage, ProcessedInlineSpan, collect_inline_items,
       create_inline_constraint, create_inline_layout,
     },
-    node::Node,
+    node::{CustomDraw, Node},
     style::{
-      Affine, ComputedStyle, Filter, ImageScalingAlgorithm, SpacePair, StyleSheet,
+      Affine, ComputedStyle, Filter, ImageScalingAlgorithm, SpacePair, Style, StyleSheet,
       apply_backdrop_filter, apply_filters,
     },
     tree::{LayoutResults, LayoutTree, RenderNode},
   },
   rendering::{
     AnimationFrame, BorderProperties, Canvas, CanvasConstrain, CanvasConstrainResult,
-    DitheringAlgorithm, RenderContext, Sizing, apply_dithering, draw_debug_border,
-    inline_drawing::get_parent_x_height, overlay_image,
+    DitheringAlgorithm, FrameCache, FramePlan, GlyphOutline, Instant, LayerCache, LayerFingerprint,
+    PaintRecord, Profiler, RenderContext, RenderProfile, Sizing, apply_dithering,
+    draw_debug_border,
+    inline_drawing::{get_parent_x_height, get_run_cap_height},
+    overlay_image, record_draw_time,
+  },
+  resources::{
+    font::MissingFontPolicy,
+    image::ImageSource,
+    loader::{ResourceLoader, load_resources},
+    task::FetchTaskCollection,
   },
-  resources::image::ImageSource,
 };
 
 #[derive(Clone, TypedBuilder)]
@@ -53,6 +68,36 @@ pub struct RenderOptions<'g> {
   /// Output dithering algorithm. Only used by encoding frontends.
   #[builder(default)]
   pub(crate) dithering: DitheringAlgorithm,
+  /// Drawers for custom nodes that reference them by name.
+  #[builder(default)]
+  pub(crate) custom_drawers: HashMap<Arc<str>, Arc<dyn CustomDraw>>,
+  /// The largest width in pixels the output can grow to when the viewport has no width.
+  /// Content wider than it is laid out again at this width, so text wraps instead.
+  #[builder(default)]
+  pub(crate) max_width: Option<u32>,
+  /// The largest height in pixels the output can grow to when the viewport has no height.
+  /// Content below it is cut off.
+  #[builder(default)]
+  pub(crate) max_height: Option<u32>,
+  /// The Unix timestamp in milliseconds that templates see as the current time.
+  /// Features reading the time fall back to the system clock when it's not set.
+  #[builder(default)]
+  pub(crate) now_ms: Option<u64>,
+  /// The seed for anything that varies randomly, so the same seed always renders the same image.
+  #[builder(default)]
+  pub(crate) seed: u64,
+  /// The BCP 47 locale that text node values are formatted for, see [`TextFormat`].
+  ///
+  /// [`TextFormat`]: crate::layout::node::TextFormat
+  #[builder(default)]
+  pub(crate) locale: Option<String>,
+  /// What happens to text no font covers, overriding [`GlobalContext::missing_font_policy`].
+  #[builder(default)]
+  pub(crate) missing_font_policy: Option<MissingFontPolicy>,
+  /// Whether to record the time spent measuring, laying out and drawing every node.
+  /// The timings are returned by [`render_with_profile`].
+  #[builder(default)]
+  pub(crate) profile: bool,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -65,7 +110,7 @@ pub struct SequentialScene<'g> {
 }
 
 /// Information about a text run in an inline layout.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MeasuredTextRun {
   /// The text content of this run.
@@ -80,8 +125,30 @@ pub struct MeasuredTextRun {
   pub height: f32,
 }
 
+/// Information about a line in an inline layout.
+///
+/// The cap height bounds let callers center text optically: the visual middle of a line of
+/// capitals sits at `baseline - capHeight / 2`, which differs from the middle of the line box for
+/// most fonts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MeasuredTextLine {
+  /// The x position of the line content.
+  pub x: f32,
+  /// The y position of the top of the line box.
+  pub y: f32,
+  /// The width of the line content, excluding trailing whitespace.
+  pub width: f32,
+  /// The height of the line box.
+  pub height: f32,
+  /// The y position of the baseline.
+  pub baseline: f32,
+  /// The largest cap height among the text runs on the line, `0` when it has none.
+  pub cap_height: f32,
+}
+
 /// The result of a layout measurement.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MeasuredNode {
   /// The width of the node.
@@ -94,6 +161,9 @@ pub struct MeasuredNode {
   pub children: Vec<MeasuredNode>,
   /// Text runs for inline layouts.
   pub runs: Vec<MeasuredTextRun>,
+  /// Lines for inline layouts.
+  #[serde(default)]
+  pub lines: Vec<MeasuredTextLine>,
 }
 
 fn measured_run_text<'a>(
@@ -154,7 +224,23 @@ struct MeasureExit {
 struct RenderExit {
   path: Vec<usize>,
   has_constrain: bool,
-  original_canvas_image: Option<RgbaImage>,
+  isolated_layer: Option<IsolatedLayer>,
+  cached_layer: Option<PendingLayer>,
+}
+
+/// A node painted on its own image before being composited onto the canvas below.
+struct IsolatedLayer {
+  /// The canvas image the layer is composited onto.
+  backdrop: RgbaImage,
+  /// Clips of the ancestors, applied when compositing so they also clip the node's filters.
+  constrains: SmallVec<[CanvasConstrain; 1]>,
+}
+
+/// An animated layer being painted, stored in the [`LayerCache`] once its subtree is done.
+struct PendingLayer {
+  path: Vec<usize>,
+  fingerprint: LayerFingerprint,
+  transform: Affine,
 }
 
 /// Measures the layout of a node.
@@ -168,6 +254,14 @@ pub fn measure_layout<'g>(options: RenderOptions<'g>) -> Result<MeasuredNode> {
     stylesheet,
     time_ms,
     dithering: _,
+    custom_drawers,
+    max_width,
+    max_height: _,
+    now_ms,
+    seed,
+    locale,
+    missing_font_policy,
+    profile: _,
   } = options;
   let mut render_context = RenderContext::new(
     global,
@@ -177,10 +271,16 @@ pub fn measure_layout<'g>(options: RenderOptions<'g>) -> Result<MeasuredNode> {
     time_ms,
   );
   render_context.draw_debug_border = draw_debug_border;
+  render_context.custom_drawers = custom_drawers.into();
+  render_context.now_ms = now_ms;
+  render_context.seed = seed;
+  render_context.locale = locale.map(Into::into);
+  if let Some(policy) = missing_font_policy {
+    render_context.missing_font_policy = policy;
+  }
+  node.check_plugin_types(global.node_plugins())?;
   let mut root = RenderNode::from_node(&render_context, node);
-  let mut tree = LayoutTree::from_render_node(&root);
-  tree.compute_layout(render_context.sizing.viewport.into());
-  let layout_results = tree.into_results();
+  let layout_results = compute_root_layout(&root, viewport, max_width, false)?;
 
   collect_measure_result(
     &mut root,
@@ -234,6 +334,7 @@ fn collect_measure_result<'g>(
 
         let mut children = Vec::new();
         let mut runs = Vec::new();
+        let mut lines = Vec::new();
 
         if current.should_create_inline_layout() {
           let font_style = current.context.style.to_sized_font_style(&current.context);
@@ -262,6 +363,9 @@ fn collect_measure_result<'g>(
           let inline_offset = taffy::Point::ZERO;
 
           for line in inline_layout.lines() {
+            let line_metrics = line.metrics();
+            let mut cap_height = 0.0f32;
+
             for item in line.items() {
               match item {
                 PositionedLayoutItem::GlyphRun(glyph_run) => {
@@ -272,6 +376,10 @@ fn collect_measure_result<'g>(
                   let run = glyph_run.run();
                   let metrics = run.metrics();
 
+                  if let Some(run_cap_height) = get_run_cap_height(run) {
+                    cap_height = cap_height.max(run_cap_height);
+                  }
+
                   runs.push(MeasuredTextRun {
                     text: text.to_string(),
                     x: glyph_run.offset() + inline_offset.x,
@@ -302,15 +410,25 @@ fn collect_measure_result<'g>(
                     transform: inline_transform.to_cols_array(),
                     children: Vec::new(),
                     runs: Vec::new(),
+                    lines: Vec::new(),
                   });
                 }
               }
             }
+
+            lines.push(MeasuredTextLine {
+              x: line_metrics.offset + inline_offset.x,
+              y: line_metrics.min_coord + inline_offset.y,
+              width: line_metrics.advance - line_metrics.trailing_whitespace,
+              height: line_metrics.max_coord - line_metrics.min_coord,
+              baseline: line_metrics.baseline + inline_offset.y,
+              cap_height,
+            });
           }
 
           measured_by_node_id.insert(
             usize::from(node_id),
-            create_measured_node(layout, local_transform, children, runs),
+            create_measured_node(layout, local_transform, children, runs, lines),
           );
           continue;
         }
@@ -318,7 +436,7 @@ fn collect_measure_result<'g>(
         let Some(render_children) = current.children.as_deref() else {
           measured_by_node_id.insert(
             usize::from(node_id),
-            create_measured_node(layout, local_transform, children, runs),
+            create_measured_node(layout, local_transform, children, runs, lines),
           );
           continue;
         };
@@ -327,7 +445,7 @@ fn collect_measure_result<'g>(
         if child_ids.is_empty() {
           measured_by_node_id.insert(
             usize::from(node_id),
-            create_measured_node(layout, local_transform, children, runs),
+            create_measured_node(layout, local_transform, children, runs, lines),
           );
           continue;
         }
@@ -381,6 +499,7 @@ fn collect_measure_result<'g>(
             transform: local_transform.to_cols_array(),
             children,
             runs,
+            lines: Vec::new(),
           },
         );
       }
@@ -397,6 +516,7 @@ fn create_measured_node(
   local_transform: Affine,
   children: Vec<MeasuredNode>,
   runs: Vec<MeasuredTextRun>,
+  lines: Vec<MeasuredTextLine>,
 ) -> MeasuredNode {
   MeasuredNode {
     width: layout.size.width,
@@ -404,11 +524,241 @@ fn create_measured_node(
     transform: local_transform.to_cols_array(),
     children,
     runs,
+    lines,
   }
 }
 
 /// Renders a node to an image.
 pub fn render<'g>(options: RenderOptions<'g>) -> Result<RgbaImage> {
+  render_with_profile(options).map(|(image, _)| image)
+}
+
+/// Renders a node to an image, along with where the time went when
+/// [`profile`](RenderOptionsBuilder::profile) is enabled.
+///
+/// The profile is `None` unless profiling is enabled. Recording it slows the render down
+/// slightly, so it's meant for finding slow nodes rather than for production renders.
+pub fn render_with_profile<'g>(
+  options: RenderOptions<'g>,
+) -> Result<(RgbaImage, Option<RenderProfile>)> {
+  let dithering = options.dithering;
+  let PreparedRender {
+    mut root,
+    layout_results,
+    root_node_id,
+    root_size,
+    container_size,
+    profiler,
+  } = prepare_render(options)?;
+
+  let mut canvas = Canvas::new(root_size);
+  let draw_started = profiler.as_ref().map(|_| {
+    canvas.draw_timings = Some(HashMap::new());
+    Instant::now()
+  });
+
+  render_node(
+    &mut root,
+    &layout_results,
+    root_node_id,
+    &mut canvas,
+    Affine::IDENTITY,
+    container_size,
+  )?;
+
+  let draw = draw_started.map(|started| started.elapsed());
+  let draw_timings = canvas.draw_timings.take().unwrap_or_default();
+  let mut image = canvas.into_inner();
+  apply_dithering(&mut image, dithering);
+
+  let profile = profiler
+    .map(|profiler| {
+      profiler.finish(
+        &root,
+        &layout_results,
+        draw.unwrap_or_default(),
+        &draw_timings,
+      )
+    })
+    .transpose()?;
+
+  Ok((image, profile))
+}
+
+/// Renders a node and returns the vector outlines of every glyph drawn, in paint order.
+///
+/// Bitmap glyphs such as color emoji have no outline and are skipped.
+pub fn outline_text<'g>(options: RenderOptions<'g>) -> Result<Vec<GlyphOutline>> {
+  let mut canvas = render_canvas(options, true, None, None)?;
+
+  Ok(canvas.glyph_outlines.take().unwrap_or_default())
+}
+
+/// A render split into separately painted layers, see [`render_layers`].
+#[derive(Debug, Clone)]
+pub struct LayeredRender {
+  /// Width of the full render.
+  pub width: u32,
+  /// Height of the full render.
+  pub height: u32,
+  /// The layers in paint order, from bottom to top.
+  pub layers: Vec<RenderedLayer>,
+}
+
+/// A single layer of a [`LayeredRender`].
+#[derive(Debug, Clone)]
+pub struct RenderedLayer {
+  /// The node's `id`, falling back to its tag name and then to `layer-<index>`.
+  pub name: String,
+  /// Child indices leading from the root to the node the layer was painted from.
+  pub path: Vec<usize>,
+  /// Horizontal offset of `image` within the full render.
+  pub x: u32,
+  /// Vertical offset of `image` within the full render.
+  pub y: u32,
+  /// The layer's pixels, cropped to the area it paints.
+  pub image: RgbaImage,
+}
+
+/// Renders a node into separate layers for post-editing in layered image editors.
+///
+/// The root's own painting forms the first layer, followed by one layer per child of the root
+/// and per descendant with a `data-layer` attribute, in document order. Each node is painted
+/// into the layer of its nearest such ancestor. Clips, opacity and filters of ancestors still
+/// apply, so stacking the layers at their offsets reproduces [`render`] unless blend modes
+/// or backdrop filters mix them. Layers that paint nothing are left out.
+pub fn render_layers<'g>(options: RenderOptions<'g>) -> Result<LayeredRender> {
+  let dithering = options.dithering;
+  let PreparedRender {
+    mut root,
+    layout_results,
+    root_node_id,
+    root_size,
+    container_size,
+    profiler: _,
+  } = prepare_render(options)?;
+
+  // Each painted path with the index of the layer it belongs to.
+  let mut owners = Vec::new();
+  let mut layer_paths = Vec::new();
+  let mut pending = vec![(Vec::new(), None)];
+
+  while let Some((path, parent_layer)) = pending.pop() {
+    let Some(current) = get_node_mut_by_path(&mut root, &path) else {
+      unreachable!()
+    };
+
+    let starts_layer = path.len() <= 1
+      || current
+        .node
+        .as_ref()
+        .and_then(|node| node.metadata.attributes.as_ref())
+        .is_some_and(|attributes| attributes.contains_key("data-layer"));
+    let layer = match parent_layer {
+      Some(layer) if !starts_layer => layer,
+      _ => {
+        layer_paths.push(path.clone());
+        layer_paths.len() - 1
+      }
+    };
+
+    owners.push((path.clone(), layer));
+
+    if current.should_create_inline_layout() {
+      continue;
+    }
+
+    let Some(children) = current.children.as_deref() else {
+      continue;
+    };
+
+    for index in (0..children.len()).rev() {
+      let mut child_path = path.clone();
+      child_path.push(index);
+      pending.push((child_path, Some(layer)));
+    }
+  }
+
+  let mut layers = Vec::with_capacity(layer_paths.len());
+
+  for (index, path) in layer_paths.into_iter().enumerate() {
+    let mut canvas = Canvas::new(root_size);
+    canvas.skipped_paints = Some(
+      owners
+        .iter()
+        .filter(|(_, layer)| *layer != index)
+        .map(|(path, _)| path.clone())
+        .collect(),
+    );
+
+    render_node(
+      &mut root,
+      &layout_results,
+      root_node_id,
+      &mut canvas,
+      Affine::IDENTITY,
+      container_size,
+    )?;
+
+    let mut image = canvas.into_inner();
+    let Some((x, y, width, height)) = painted_bounds(&image) else {
+      continue;
+    };
+
+    apply_dithering(&mut image, dithering);
+
+    let Some(current) = get_node_mut_by_path(&mut root, &path) else {
+      unreachable!()
+    };
+    let metadata = current.node.as_ref().map(|node| &node.metadata);
+    let name = metadata
+      .and_then(|metadata| metadata.id.as_deref().or(metadata.tag_name.as_deref()))
+      .map_or_else(|| format!("layer-{index}"), ToString::to_string);
+
+    layers.push(RenderedLayer {
+      name,
+      path,
+      x,
+      y,
+      image: crop_imm(&image, x, y, width, height).to_image(),
+    });
+  }
+
+  Ok(LayeredRender {
+    width: root_size.width,
+    height: root_size.height,
+    layers,
+  })
+}
+
+/// Returns `(x, y, width, height)` of the smallest rectangle holding every visible pixel.
+fn painted_bounds(image: &RgbaImage) -> Option<(u32, u32, u32, u32)> {
+  let (mut min_x, mut min_y, mut max_x, mut max_y) = (u32::MAX, u32::MAX, 0, 0);
+
+  for (x, y, pixel) in image.enumerate_pixels() {
+    if pixel.0[3] != 0 {
+      min_x = min_x.min(x);
+      min_y = min_y.min(y);
+      max_x = max_x.max(x);
+      max_y = max_y.max(y);
+    }
+  }
+
+  (min_x <= max_x).then(|| (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1))
+}
+
+/// A node tree laid out and ready to be painted.
+struct PreparedRender<'g> {
+  root: RenderNode<'g>,
+  layout_results: LayoutResults,
+  root_node_id: NodeId,
+  root_size: Size<u32>,
+  container_size: Size<Option<f32>>,
+  /// Stage timings so far, set when profiling.
+  profiler: Option<Profiler>,
+}
+
+fn prepare_render<'g>(options: RenderOptions<'g>) -> Result<PreparedRender<'g>> {
   let RenderOptions {
     viewport,
     global,
@@ -417,9 +767,18 @@ pub fn render<'g>(options: RenderOptions<'g>) -> Result<RgbaImage> {
     fetched_resources,
     stylesheet,
     time_ms,
-    dithering,
+    dithering: _,
+    custom_drawers,
+    max_width,
+    max_height,
+    now_ms,
+    seed,
+    locale,
+    missing_font_policy,
+    profile,
   } = options;
 
+  let started = profile.then(Instant::now);
   let mut render_context = RenderContext::new(
     global,
     viewport,
@@ -428,18 +787,35 @@ pub fn render<'g>(options: RenderOptions<'g>) -> Result<RgbaImage> {
     time_ms,
   );
   render_context.draw_debug_border = draw_debug_border;
-
-  let mut root = RenderNode::from_node(&render_context, node);
-  let mut tree = LayoutTree::from_render_node(&root);
-  tree.compute_layout(render_context.sizing.viewport.into());
-  let layout_results = tree.into_results();
+  render_context.custom_drawers = custom_drawers.into();
+  render_context.now_ms = now_ms;
+  render_context.seed = seed;
+  render_context.locale = locale.map(Into::into);
+  if let Some(policy) = missing_font_policy {
+    render_context.missing_font_policy = policy;
+  }
+  node.check_plugin_types(global.node_plugins())?;
+
+  let root = RenderNode::from_node(&render_context, node);
+  let prepare = started.map(|started| started.elapsed());
+  let layout_started = profile.then(Instant::now);
+  let layout_results = compute_root_layout(&root, viewport, max_width, profile)?;
+  let profiler =
+    started
+      .zip(prepare)
+      .zip(layout_started)
+      .map(|((started, prepare), layout_started)| Profiler {
+        started,
+        prepare,
+        layout: layout_started.elapsed(),
+      });
   let root_node_id = layout_results.root_node_id();
   let root_size = layout_results
     .layout(root_node_id)?
     .size
     .map(|size| size.round() as u32);
 
-  let root_size = root_size.zip_map(viewport.into(), |size, viewport| {
+  let mut root_size = root_size.zip_map(viewport.into(), |size, viewport| {
     if let AvailableSpace::Definite(defined) = viewport {
       defined as u32
     } else {
@@ -447,28 +823,295 @@ pub fn render<'g>(options: RenderOptions<'g>) -> Result<RgbaImage> {
     }
   });
 
+  if viewport.size.width.is_none()
+    && let Some(max_width) = max_width
+  {
+    root_size.width = root_size.width.min(max_width);
+  }
+
+  if viewport.size.height.is_none()
+    && let Some(max_height) = max_height
+  {
+    root_size.height = root_size.height.min(max_height);
+  }
+
   if root_size.width == 0 || root_size.height == 0 {
     return Err(Error::InvalidViewport);
   }
 
+  Ok(PreparedRender {
+    root,
+    layout_results,
+    root_node_id,
+    root_size,
+    container_size: Size {
+      width: viewport.size.width.map(|value| value as f32),
+      height: viewport.size.height.map(|value| value as f32),
+    },
+    profiler,
+  })
+}
+
+/// Lays out the tree at the viewport, then again at `max_width` if an auto width grew past it.
+fn compute_root_layout(
+  root: &RenderNode<'_>,
+  viewport: Viewport,
+  max_width: Option<u32>,
+  profile: bool,
+) -> Result<LayoutResults> {
+  let mut tree = LayoutTree::from_render_node(root);
+  if profile {
+    tree.enable_profiling();
+  }
+  tree.compute_layout(viewport.into());
+  let layout_results = tree.into_results();
+
+  let Some(max_width) = max_width.filter(|_| viewport.size.width.is_none()) else {
+    return Ok(layout_results);
+  };
+
+  if layout_results
+    .layout(layout_results.root_node_id())?
+    .size
+    .width
+    <= max_width as f32
+  {
+    return Ok(layout_results);
+  }
+
+  let mut available_space: Size<AvailableSpace> = viewport.into();
+  available_space.width = AvailableSpace::Definite(max_width as f32);
+
+  let mut tree = LayoutTree::from_render_node(root);
+  if profile {
+    tree.enable_profiling();
+  }
+  tree.compute_layout(available_space);
+
+  let mut wrapped_results = tree.into_results();
+  wrapped_results.add_timings(&layout_results);
+
+  Ok(wrapped_results)
+}
+
+fn render_canvas<'g>(
+  options: RenderOptions<'g>,
+  record_glyph_outlines: bool,
+  layer_cache: Option<LayerCache>,
+  frame_cache: Option<&mut FrameCache>,
+) -> Result<Canvas> {
+  let PreparedRender {
+    mut root,
+    layout_results,
+    root_node_id,
+    root_size,
+    container_size,
+    profiler: _,
+  } = prepare_render(options)?;
+
   let mut canvas = Canvas::new(root_size);
+  if record_glyph_outlines {
+    canvas.glyph_outlines = Some(Vec::new());
+  }
+  canvas.layer_cache = layer_cache;
+
+  let Some(frame_cache) = frame_cache else {
+    render_node(
+      &mut root,
+      &layout_results,
+      root_node_id,
+      &mut canvas,
+      Affine::IDENTITY,
+      container_size,
+    )?;
+
+    return Ok(canvas);
+  };
 
-  render_node(
+  let records = collect_paint_records(
     &mut root,
     &layout_results,
     root_node_id,
-    &mut canvas,
-    Affine::IDENTITY,
-    Size {
-      width: viewport.size.width.map(|value| value as f32),
-      height: viewport.size.height.map(|value| value as f32),
-    },
+    container_size,
+    frame_cache,
   )?;
+  let mut plan = frame_cache.plan(&records, root_size);
+
+  match &mut plan {
+    FramePlan::Reuse => {}
+    FramePlan::Repaint { skipped, .. } => {
+      // Layers miss the skipped nodes, so they can't be reused or cached on this frame.
+      let layer_cache = canvas.layer_cache.take();
+      canvas.skipped_paints = Some(take(skipped));
+
+      render_node(
+        &mut root,
+        &layout_results,
+        root_node_id,
+        &mut canvas,
+        Affine::IDENTITY,
+        container_size,
+      )?;
 
-  let mut image = canvas.into_inner();
-  apply_dithering(&mut image, dithering);
+      canvas.skipped_paints = None;
+      canvas.layer_cache = layer_cache;
+    }
+    FramePlan::Full => {
+      render_node(
+        &mut root,
+        &layout_results,
+        root_node_id,
+        &mut canvas,
+        Affine::IDENTITY,
+        container_size,
+      )?;
+    }
+  }
+
+  frame_cache.finish(records, &plan, &mut canvas.image);
 
-  Ok(image)
+  Ok(canvas)
+}
+
+/// Records what every visible node paints with, keyed by node path, for the [`FrameCache`].
+///
+/// Visits nodes the same way as [`render_node`], without painting them.
+fn collect_paint_records<'g>(
+  root: &mut RenderNode<'g>,
+  layout_results: &LayoutResults,
+  node_id: NodeId,
+  container_size: Size<Option<f32>>,
+  frame_cache: &FrameCache,
+) -> Result<HashMap<Vec<usize>, PaintRecord>> {
+  let mut records = HashMap::new();
+  let mut pending = vec![TraversalEnter {
+    path: Vec::new(),
+    node_id,
+    transform: Affine::IDENTITY,
+    container_size,
+  }];
+
+  while let Some(TraversalEnter {
+    path,
+    node_id,
+    mut transform,
+    container_size,
+  }) = pending.pop()
+  {
+    let Some(current) = get_node_mut_by_path(root, &path) else {
+      unreachable!()
+    };
+    let layout = *layout_results.layout(node_id)?;
+
+    if current.context.style.is_invisible() {
+      continue;
+    }
+
+    current.context.sizing.container_size = container_size;
+    transform *= Affine::translation(layout.location.x, layout.location.y);
+    apply_transform(
+      &mut transform,
+      &current.context.style,
+      layout.size,
+      &current.context.sizing,
+    );
+
+    if !transform.is_invertible() {
+      continue;
+    }
+
+    current.context.transform = transform;
+    records.insert(
+      path.clone(),
+      frame_cache.record(&path, current, layout, transform),
+    );
+
+    if current.should_create_inline_layout() {
+      continue;
+    }
+
+    let Some(children) = current.children.as_deref() else {
+      continue;
+    };
+
+    let child_container_size = Size {
+      width: Some(layout.content_box_width()),
+      height: Some(layout.content_box_height()),
+    };
+
+    for (index, child_id) in collect_child_node_ids(layout_results, node_id, children.len())?
+      .into_iter()
+      .enumerate()
+    {
+      let mut child_path = path.clone();
+      child_path.push(index);
+      pending.push(TraversalEnter {
+        path: child_path,
+        node_id: child_id,
+        transform,
+        container_size: child_container_size,
+      });
+    }
+  }
+
+  Ok(records)
+}
+
+/// Shapes every text with every style ahead of time, so the first render after a cold start
+/// finds the font and shaper caches of `global` already warm.
+///
+/// Texts are shaped with the default style when `styles` is empty.
+pub fn warmup(global: &GlobalContext, texts: &[impl AsRef<str>], styles: &[Style]) -> Result<()> {
+  let default_style = [Style::default()];
+  let styles = if styles.is_empty() {
+    &default_style[..]
+  } else {
+    styles
+  };
+
+  for text in texts {
+    for style in styles {
+      let options = RenderOptions::builder()
+        .global(global)
+        .viewport(Viewport::default())
+        .node(Node::text(text.as_ref()).with_style(style.clone()))
+        .build();
+
+      measure_layout(options)?;
+    }
+  }
+
+  Ok(())
+}
+
+impl RenderOptions<'_> {
+  /// Loads every remote resource referenced by the node tree with `loader`.
+  ///
+  /// Resources already present in `fetched_resources` or the persistent image store are skipped.
+  pub async fn fetch_resources<L: ResourceLoader>(&mut self, loader: &L) -> Result<()> {
+    let mut collection = FetchTaskCollection::default();
+    self.node.collect_fetch_tasks(&mut collection);
+    self.node.collect_style_fetch_tasks(&mut collection);
+
+    let tasks = collection.into_inner().into_iter().filter(|url| {
+      !self.fetched_resources.contains_key(url)
+        && self.global.persistent_image_store.get(url).is_none()
+    });
+
+    let loaded = load_resources(loader, tasks.collect::<Vec<_>>()).await?;
+    self.fetched_resources.extend(loaded);
+
+    Ok(())
+  }
+}
+
+/// Fetches the node's remote resources with `loader`, then renders it.
+pub async fn render_with_loader<'g, L: ResourceLoader>(
+  mut options: RenderOptions<'g>,
+  loader: &L,
+) -> Result<RgbaImage> {
+  options.fetch_resources(loader).await?;
+  render(options)
 }
 
 /// Renders a node at a specific time on the global animation timeline.
@@ -507,6 +1150,11 @@ pub fn render_sequence_animation<'g>(
     .saturating_mul(u64::from(fps))
     .div_ceil(1000);
   let mut frames = Vec::with_capacity(frame_count as usize);
+  // Animated layers are only comparable between frames of the same scene.
+  let mut layer_cache = LayerCache::default();
+  let mut cached_scene = None;
+  // Frames are compared node by node, so unchanged regions carry over between scenes too.
+  let mut frame_cache = FrameCache::default();
 
   for frame_index in 0..frame_count {
     let start_ms = frame_index * 1000 / u64::from(fps);
@@ -516,7 +1164,29 @@ pub fn render_sequence_animation<'g>(
       continue;
     }
 
-    let image = render_sequence_at_time(scenes, start_ms)?;
+    let Some((scene, local_time_ms)) = resolve_scene_at_time(scenes, start_ms) else {
+      return Err(Error::InvalidViewport);
+    };
+
+    if !cached_scene.is_some_and(|cached| ptr::eq(cached, scene)) {
+      layer_cache.clear();
+      cached_scene = Some(scene);
+    }
+
+    let mut options = scene.options.clone();
+    options.time_ms = local_time_ms;
+    let dithering = options.dithering;
+
+    let mut canvas = render_canvas(
+      options,
+      false,
+      Some(take(&mut layer_cache)),
+      Some(&mut frame_cache),
+    )?;
+    layer_cache = canvas.layer_cache.take().unwrap_or_default();
+
+    let mut image = canvas.into_inner();
+    apply_dithering(&mut image, dithering);
     frames.push(AnimationFrame::new(image, frame_duration_ms as u32));
   }
 
@@ -619,6 +1289,57 @@ fn collect_child_node_ids(
   )
 }
 
+/// Returns the edges of the padding box that the content of `node` extends past.
+fn content_overflow(
+  node: &RenderNode,
+  layout_results: &LayoutResults,
+  node_id: NodeId,
+  layout: Layout,
+) -> Result<Rect<bool>> {
+  let padding_box = Rect {
+    top: layout.border.top,
+    right: layout.size.width - layout.border.right,
+    bottom: layout.size.height - layout.border.bottom,
+    left: layout.border.left,
+  };
+
+  let content = if node.should_create_inline_layout() {
+    // Lines past the content box are not drawn, so text can only overflow horizontally.
+    let left = padding_box.left + layout.padding.left;
+
+    Rect {
+      top: padding_box.top,
+      right: left + node.inline_content_width(layout),
+      bottom: padding_box.bottom,
+      left,
+    }
+  } else {
+    let child_count = node.children.as_deref().map_or(0, <[_]>::len);
+    let mut content = padding_box;
+
+    for child_id in collect_child_node_ids(layout_results, node_id, child_count)? {
+      let child = layout_results.layout(child_id)?;
+
+      content.top = content.top.min(child.location.y);
+      content.right = content.right.max(child.location.x + child.size.width);
+      content.bottom = content.bottom.max(child.location.y + child.size.height);
+      content.left = content.left.min(child.location.x);
+    }
+
+    content
+  };
+
+  // Ignore rounding errors in the layout.
+  const TOLERANCE: f32 = 0.5;
+
+  Ok(Rect {
+    top: content.top < padding_box.top - TOLERANCE,
+    right: content.right > padding_box.right + TOLERANCE,
+    bottom: content.bottom > padding_box.bottom + TOLERANCE,
+    left: content.left < padding_box.left - TOLERANCE,
+  })
+}
+
 pub(crate) fn render_node<'g>(
   node: &mut RenderNode<'g>,
   layout_results: &LayoutResults,
@@ -631,8 +1352,19 @@ pub(crate) fn render_node<'g>(
     node: &mut RenderNode<'g>,
     canvas: &mut Canvas,
     has_constrain: bool,
-    original_canvas_image: Option<RgbaImage>,
+    isolated_layer: Option<IsolatedLayer>,
+    cached_layer: Option<PendingLayer>,
   ) -> Result<()> {
+    if let Some(PendingLayer {
+      path,
+      fingerprint,
+      transform,
+    }) = cached_layer
+      && let Some(layer_cache) = canvas.layer_cache.as_mut()
+    {
+      layer_cache.store(path, fingerprint, transform, &canvas.image);
+    }
+
     let opacity_filter =
       (node.context.style.opacity.0 < 1.0).then_some(Filter::Opacity(node.context.style.opacity));
 
@@ -651,38 +1383,56 @@ pub(crate) fn render_node<'g>(
       )?;
     }
 
-    if let Some(mut source_canvas_image) = original_canvas_image {
+    if has_constrain {
+      canvas.pop_constrain();
+    }
+
+    if let Some(IsolatedLayer {
+      mut backdrop,
+      constrains,
+    }) = isolated_layer
+    {
+      canvas.constrains = constrains;
+
       overlay_image(
-        &mut source_canvas_image,
+        &mut backdrop,
         &canvas.image,
         BorderProperties::zero(),
         Affine::IDENTITY,
         ImageScalingAlgorithm::Auto,
         node.context.style.mix_blend_mode,
-        &[],
+        &canvas.constrains,
         &mut canvas.mask_memory,
         &mut canvas.buffer_pool,
       );
 
-      let isolated_image = replace(&mut canvas.image, source_canvas_image);
+      let isolated_image = replace(&mut canvas.image, backdrop);
       canvas.buffer_pool.release_image(isolated_image);
     }
 
-    if has_constrain {
-      canvas.pop_constrain();
-    }
-
     Ok(())
   }
 
-  let mut visits = vec![TraversalVisit::Enter(TraversalEnter {
+  let mut visits: Vec<TraversalVisit<RenderExit>> = vec![TraversalVisit::Enter(TraversalEnter {
     path: Vec::new(),
     node_id,
     transform,
     container_size,
   })];
 
+  // The visit being timed, recorded when the next one starts since visits end in many places.
+  let mut timed_visit = None;
+
   while let Some(visit) = visits.pop() {
+    if let Some(draw_timings) = canvas.draw_timings.as_mut() {
+      record_draw_time(draw_timings, timed_visit.take());
+      let path = match &visit {
+        TraversalVisit::Enter(enter) => &enter.path,
+        TraversalVisit::Exit(exit) => &exit.path,
+      };
+      timed_visit = Some((path.clone(), Instant::now()));
+    }
+
     match visit {
       TraversalVisit::Enter(TraversalEnter {
         path,
@@ -714,11 +1464,18 @@ pub(crate) fn render_node<'g>(
 
         current.context.transform = transform;
 
+        let overflowing = if current.context.style.fade_edges.is_none() {
+          Rect::default()
+        } else {
+          content_overflow(current, layout_results, node_id, layout)?
+        };
+
         let constrain = CanvasConstrain::from_node(
           &current.context,
           &current.context.style,
           layout,
           transform,
+          overflowing,
           &mut canvas.mask_memory,
           &mut canvas.buffer_pool,
         )?;
@@ -729,7 +1486,13 @@ pub(crate) fn render_node<'g>(
 
         let has_constrain = constrain.is_some();
 
-        if !current.context.style.backdrop_filter.is_empty() {
+        // Nodes outside the region that changed since the previous frame keep their pixels.
+        let should_paint = !canvas
+          .skipped_paints
+          .as_ref()
+          .is_some_and(|skipped| skipped.contains(&path));
+
+        if should_paint && !current.context.style.backdrop_filter.is_empty() {
           let border = BorderProperties::from_context(&current.context, layout.size, layout.border);
           apply_backdrop_filter(canvas, border, layout.size, transform, &current.context)?;
         }
@@ -739,56 +1502,98 @@ pub(crate) fn render_node<'g>(
             .context
             .style
             .has_non_identity_transform(layout.size, &current.context.sizing);
-        let original_canvas_image = if should_isolate {
-          Some(canvas.replace_new_image()?)
+        let isolated_layer = if should_isolate {
+          Some(IsolatedLayer {
+            backdrop: canvas.replace_new_image()?,
+            constrains: take(&mut canvas.constrains),
+          })
         } else {
           None
         };
 
+        // Ancestor clips are applied when compositing rather than baked into the layer.
+        let fingerprint = if should_isolate && canvas.layer_cache.is_some() {
+          LayerFingerprint::new(current, layout_results, node_id, container_size)?
+        } else {
+          None
+        };
+
+        if let Some(fingerprint) = &fingerprint
+          && let Some(layer_cache) = canvas.layer_cache.as_ref()
+          && layer_cache.reuse(&path, fingerprint, transform, &mut canvas.image)
+        {
+          // Pushed only so the constrain's buffers are released along with the layer.
+          if let CanvasConstrainResult::Some(constrain) = constrain {
+            canvas.push_constrain(constrain);
+          }
+
+          finish_node_render(current, canvas, has_constrain, isolated_layer, None)?;
+          continue;
+        }
+
+        let cached_layer = fingerprint.map(|fingerprint| PendingLayer {
+          path: path.clone(),
+          fingerprint,
+          transform,
+        });
+
         match constrain {
           CanvasConstrainResult::None => {
-            current.draw_shell(canvas, layout)?;
+            if should_paint {
+              current.draw_shell(canvas, layout)?;
+            }
           }
           CanvasConstrainResult::Some(constrain) => match constrain {
             CanvasConstrain::ClipPath { .. } | CanvasConstrain::MaskImage { .. } => {
               canvas.push_constrain(constrain);
-              current.draw_shell(canvas, layout)?;
+              if should_paint {
+                current.draw_shell(canvas, layout)?;
+              }
             }
-            CanvasConstrain::Overflow { .. } => {
-              current.draw_shell(canvas, layout)?;
+            CanvasConstrain::Overflow { .. }
+            | CanvasConstrain::OverflowMask { .. }
+            | CanvasConstrain::FadeEdges { .. } => {
+              if should_paint {
+                current.draw_shell(canvas, layout)?;
+              }
               canvas.push_constrain(constrain);
             }
           },
           CanvasConstrainResult::SkipRendering => unreachable!(),
         }
 
-        current.draw_content(canvas, layout)?;
+        if should_paint {
+          current.draw_content(canvas, layout)?;
 
-        if current.context.draw_debug_border {
-          draw_debug_border(canvas, layout, transform);
+          if current.context.draw_debug_border {
+            draw_debug_border(canvas, layout, transform);
+          }
         }
 
         if current.should_create_inline_layout() {
-          current.draw_inline(canvas, layout)?;
-          finish_node_render(current, canvas, has_constrain, original_canvas_image)?;
+          if should_paint {
+            current.draw_inline(canvas, layout)?;
+          }
+          finish_node_render(current, canvas, has_constrain, isolated_layer, cached_layer)?;
           continue;
         }
 
         let Some(children) = current.children.as_deref() else {
-          finish_node_render(current, canvas, has_constrain, original_canvas_image)?;
+          finish_node_render(current, canvas, has_constrain, isolated_layer, cached_layer)?;
           continue;
         };
 
         let child_ids = collect_child_node_ids(layout_results, node_id, children.len())?;
         if child_ids.is_empty() {
-          finish_node_render(current, canvas, has_constrain, original_canvas_image)?;
+          finish_node_render(current, canvas, has_constrain, isolated_layer, cached_layer)?;
           continue;
         }
 
         visits.push(TraversalVisit::Exit(RenderExit {
           path: path.clone(),
           has_constrain,
-          original_canvas_image,
+          isolated_layer,
+          cached_layer,
         }));
 
         let child_container_size = Size {
@@ -810,23 +1615,31 @@ pub(crate) fn render_node<'g>(
       TraversalVisit::Exit(RenderExit {
         path,
         has_constrain,
-        original_canvas_image,
+        isolated_layer,
+        cached_layer,
       }) => {
         let Some(current) = get_node_mut_by_path(node, &path) else {
           unreachable!()
         };
-        finish_node_render(current, canvas, has_constrain, original_canvas_image)?;
+        finish_node_render(current, canvas, has_constrain, isolated_layer, cached_layer)?;
       }
     };
   }
 
+  if let Some(draw_timings) = canvas.draw_timings.as_mut() {
+    record_draw_time(draw_timings, timed_visit);
+  }
+
   Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+  use image::{RgbaImage, imageops::overlay};
+
   use super::{
-    RenderOptions, SequentialScene, render_sequence_animation, resolve_scene_at_time,
+    RenderOptions, SequentialScene, render, render_layers, render_sequence_animation,
+    render_sequence_at_time, render_with_profile, resolve_scene_at_time,
     slice_text_at_char_boundaries,
   };
   use crate::{
@@ -835,9 +1648,11 @@ mod tests {
       Viewport,
       node::Node,
       style::{
-        AnimationDurations, AnimationFillMode, AnimationFillModes, AnimationNames, AnimationTime,
-        AnimationTimingFunction, AnimationTimingFunctions, KeyframeRule, KeyframesRule, Length::Px,
-        Style, StyleDeclaration,
+        AlignItems, Angle, AnimationDurations, AnimationFillMode, AnimationFillModes,
+        AnimationNames, AnimationTime, AnimationTimingFunction, AnimationTimingFunctions,
+        BorderRadius, Color, ColorInput, Display, Filter, FlexDirection, FlexWrap, KeyframeRule,
+        KeyframesRule, Length::Px, Overflow, PercentageNumber, Sides, SpacePair, Style,
+        StyleDeclaration,
       },
     },
     rendering::measure_layout,
@@ -938,10 +1753,174 @@ mod tests {
     assert_eq!(slice_text_at_char_boundaries(text, 0..text.len()), text);
   }
 
+  #[test]
+  fn rounded_overflow_clips_transformed_and_filtered_children() {
+    let global = GlobalContext::default();
+    let render_with = |child_style: Style| {
+      let child = Node::container([]).with_style(
+        child_style
+          .with(StyleDeclaration::width(Px(20.0)))
+          .with(StyleDeclaration::height(Px(20.0)))
+          .with(StyleDeclaration::background_color(ColorInput::Value(
+            Color([255, 0, 0, 255]),
+          ))),
+      );
+      let node = Node::container([child]).with_style(
+        Style::default()
+          .with(StyleDeclaration::display(Display::Flex))
+          .with(StyleDeclaration::width(Px(20.0)))
+          .with(StyleDeclaration::height(Px(20.0)))
+          .with_overflow(SpacePair::from_single(Overflow::Hidden))
+          .with_border_radius(Box::new(BorderRadius(Sides(
+            [SpacePair::from_single(Px(10.0)); 4],
+          )))),
+      );
+
+      render(
+        RenderOptions::builder()
+          .global(&global)
+          .viewport(Viewport::new((20, 20)))
+          .node(node)
+          .build(),
+      )
+      .ok()
+      .map(|image| (image.get_pixel(0, 0).0[3], image.get_pixel(10, 10).0[3]))
+    };
+
+    let rotated =
+      render_with(Style::default().with(StyleDeclaration::rotate(Some(Angle::new(45.0)))));
+    assert_eq!(rotated.map(|(corner, _)| corner), Some(0));
+    assert_eq!(rotated.map(|(_, center)| center), Some(255));
+
+    // The blur spreads past the rounded corner unless the clip applies after the filter.
+    let blurred =
+      render_with(Style::default().with(StyleDeclaration::filter(vec![Filter::Blur(Px(4.0))])));
+    assert_eq!(blurred.map(|(corner, _)| corner), Some(0));
+    assert!(blurred.is_some_and(|(_, center)| center > 200));
+  }
+
+  #[test]
+  fn auto_height_follows_content_up_to_max_height() {
+    let global = GlobalContext::default();
+    let render_with = |viewport: Viewport, max_height: Option<u32>| {
+      let node = Node::container([]).with_style(
+        Style::default()
+          .with(StyleDeclaration::width(Px(20.0)))
+          .with(StyleDeclaration::height(Px(80.0))),
+      );
+
+      render(
+        RenderOptions::builder()
+          .global(&global)
+          .viewport(viewport)
+          .node(node)
+          .max_height(max_height)
+          .build(),
+      )
+      .map(|image| image.dimensions())
+      .ok()
+    };
+
+    assert_eq!(render_with(Viewport::new((20, None)), None), Some((20, 80)));
+    assert_eq!(
+      render_with(Viewport::new((20, None)), Some(50)),
+      Some((20, 50))
+    );
+    assert_eq!(
+      render_with(Viewport::new((20, None)), Some(100)),
+      Some((20, 80))
+    );
+    assert_eq!(
+      render_with(Viewport::new((20, 90)), Some(50)),
+      Some((20, 90))
+    );
+  }
+
+  #[test]
+  fn auto_width_wraps_at_max_width() {
+    let global = GlobalContext::default();
+    let render_with = |viewport: Viewport, max_width: Option<u32>| {
+      let item = || {
+        Node::container([]).with_style(
+          Style::default()
+            .with(StyleDeclaration::width(Px(40.0)))
+            .with(StyleDeclaration::height(Px(20.0))),
+        )
+      };
+      let node = Node::container([item(), item(), item()]).with_style(
+        Style::default()
+          .with(StyleDeclaration::display(Display::Flex))
+          .with(StyleDeclaration::flex_wrap(FlexWrap::Wrap)),
+      );
+
+      render(
+        RenderOptions::builder()
+          .global(&global)
+          .viewport(viewport)
+          .node(node)
+          .max_width(max_width)
+          .build(),
+      )
+      .map(|image| image.dimensions())
+      .ok()
+    };
+
+    assert_eq!(
+      render_with(Viewport::new((None, None)), None),
+      Some((120, 20))
+    );
+    assert_eq!(
+      render_with(Viewport::new((None, None)), Some(100)),
+      Some((100, 40))
+    );
+    assert_eq!(
+      render_with(Viewport::new((None, None)), Some(200)),
+      Some((120, 20))
+    );
+    assert_eq!(
+      render_with(Viewport::new((Some(150), None)), Some(100)),
+      Some((150, 20))
+    );
+  }
+
+  #[test]
+  fn auto_height_rounds_up_to_block_step_size() {
+    let global = GlobalContext::default();
+    let render_with = |style: Style| {
+      let node = Node::container([Node::container([]).with_style(
+        Style::default()
+          .with(StyleDeclaration::display(Display::Block))
+          .with(StyleDeclaration::width(Px(40.0)))
+          .with(StyleDeclaration::height(Px(30.0))),
+      )])
+      .with_style(
+        style
+          .with(StyleDeclaration::display(Display::Block))
+          .with(StyleDeclaration::block_step_size(Some(Px(24.0)))),
+      );
+
+      render(
+        RenderOptions::builder()
+          .global(&global)
+          .viewport(Viewport::new((40, None)))
+          .node(node)
+          .build(),
+      )
+      .map(|image| image.dimensions())
+      .ok()
+    };
+
+    assert_eq!(render_with(Style::default()), Some((40, 48)));
+    assert_eq!(
+      render_with(Style::default().with(StyleDeclaration::height(Px(30.0)))),
+      Some((40, 30))
+    );
+  }
+
   #[test]
   fn measure_layout_supports_structured_keyframes() {
     let global = GlobalContext::default();
-    let node = Node::container([]).with_tag_name("div").with_style(
+    let node = Node::container([]).with_style(
       Style::default()
         .with(StyleDeclaration::width(Px(100.0)))
         .with(StyleDeclaration::animation_name(AnimationNames(
@@ -998,4 +1977,312 @@ mod tests {
 
     assert_eq!(layout.width, 150.0);
   }
+
+  #[test]
+  fn render_sequence_animation_reused_layers_match_independent_renders() {
+    let global = GlobalContext::default();
+    let keyframe = |opacity: f32, offset: f32, translate: f32| {
+      KeyframeRule::builder()
+        .offsets([offset])
+        .declarations(
+          Style::default()
+            .with(StyleDeclaration::opacity(PercentageNumber(opacity)))
+            .with(StyleDeclaration::translate(SpacePair::from_pair(
+              Px(translate),
+              Px(0.0),
+            )))
+            .into(),
+        )
+        .build()
+    };
+
+    let box_node = Node::container([Node::container([]).with_tag_name("div").with_style(
+      Style::default()
+        .with(StyleDeclaration::width(Px(4.0)))
+        .with(StyleDeclaration::height(Px(4.0)))
+        .with(StyleDeclaration::background_color(ColorInput::Value(
+          Color([0, 0, 255, 255]),
+        ))),
+    )])
+    .with_style(
+      Style::default()
+        .with(StyleDeclaration::display(Display::Flex))
+        .with(StyleDeclaration::width(Px(10.0)))
+        .with(StyleDeclaration::height(Px(10.0)))
+        .with(StyleDeclaration::background_color(ColorInput::Value(
+          Color([255, 0, 0, 255]),
+        )))
+        .with(StyleDeclaration::animation_name(AnimationNames(
+          vec!["slide".to_string()].into(),
+        )))
+        .with(StyleDeclaration::animation_duration(AnimationDurations(
+          vec![AnimationTime::from_milliseconds(1000.0)].into(),
+        )))
+        .with(StyleDeclaration::animation_timing_function(
+          AnimationTimingFunctions(vec![AnimationTimingFunction::Linear].into()),
+        )),
+    );
+
+    let options = RenderOptions::builder()
+      .global(&global)
+      .viewport(Viewport::new((40, 20)))
+      .node(
+        Node::container([box_node]).with_style(
+          Style::default()
+            .with(StyleDeclaration::display(Display::Flex))
+            .with(StyleDeclaration::padding_top(Px(5.0)))
+            .with(StyleDeclaration::padding_left(Px(5.0))),
+        ),
+      )
+      .stylesheet(
+        vec![KeyframesRule {
+          name: "slide".to_string(),
+          keyframes: vec![keyframe(0.2, 0.0, 0.0), keyframe(1.0, 1.0, 20.0)],
+          media_queries: Vec::new(),
+        }]
+        .into(),
+      )
+      .build();
+
+    let scenes = vec![
+      SequentialScene::builder()
+        .duration_ms(1000)
+        .options(options)
+        .build(),
+    ];
+
+    let frames_result = render_sequence_animation(&scenes, 10);
+    assert!(frames_result.is_ok());
+    let Ok(frames) = frames_result else {
+      unreachable!()
+    };
+    assert_eq!(frames.len(), 10);
+
+    for (index, frame) in frames.iter().enumerate() {
+      let expected = render_sequence_at_time(&scenes, index as u64 * 100);
+      assert!(expected.is_ok());
+      let Ok(expected) = expected else {
+        unreachable!()
+      };
+
+      assert_eq!(frame.image, expected, "frame {index} differs");
+    }
+  }
+  #[test]
+  fn render_sequence_animation_repainted_regions_match_independent_renders() {
+    let global = GlobalContext::default();
+    let keyframe = |offset: f32, width: f32| {
+      KeyframeRule::builder()
+        .offsets([offset])
+        .declarations(
+          Style::default()
+            .with(StyleDeclaration::width(Px(width)))
+            .into(),
+        )
+        .build()
+    };
+
+    let scene = |count: &str| {
+      let progress = Node::container([]).with_style(
+        Style::default()
+          .with(StyleDeclaration::height(Px(6.0)))
+          .with(StyleDeclaration::background_color(ColorInput::Value(
+            Color([0, 128, 0, 255]),
+          )))
+          .with(StyleDeclaration::animation_name(AnimationNames(
+            vec!["grow".to_string()].into(),
+          )))
+          .with(StyleDeclaration::animation_duration(AnimationDurations(
+            vec![AnimationTime::from_milliseconds(200.0)].into(),
+          )))
+          .with(StyleDeclaration::animation_fill_mode(AnimationFillModes(
+            vec![AnimationFillMode::Forwards].into(),
+          )))
+          .with(StyleDeclaration::animation_timing_function(
+            AnimationTimingFunctions(vec![AnimationTimingFunction::Linear].into()),
+          )),
+      );
+
+      let options = RenderOptions::builder()
+        .global(&global)
+        .viewport(Viewport::new((60, 40)))
+        .node(
+          Node::container([
+            Node::text(count.to_string()).with_style(
+              Style::default()
+                .with(StyleDeclaration::font_size(Px(14.0).into()))
+                .with(StyleDeclaration::color(ColorInput::Value(Color([
+                  0, 0, 0, 255,
+                ])))),
+            ),
+            Node::container([]).with_style(
+              Style::default()
+                .with(StyleDeclaration::width(Px(20.0)))
+                .with(StyleDeclaration::height(Px(10.0)))
+                .with(StyleDeclaration::background_color(ColorInput::Value(
+                  Color([255, 0, 0, 255]),
+                ))),
+            ),
+            progress,
+          ])
+          .with_style(
+            Style::default()
+              .with(StyleDeclaration::display(Display::Flex))
+              .with(StyleDeclaration::flex_direction(FlexDirection::Column))
+              .with(StyleDeclaration::align_items(AlignItems::FlexStart))
+              .with(StyleDeclaration::background_color(ColorInput::Value(
+                Color([255, 255, 255, 255]),
+              ))),
+          ),
+        )
+        .stylesheet(
+          vec![KeyframesRule {
+            name: "grow".to_string(),
+            keyframes: vec![keyframe(0.0, 0.0), keyframe(1.0, 60.0)],
+            media_queries: Vec::new(),
+          }]
+          .into(),
+        )
+        .build();
+
+      SequentialScene::builder()
+        .duration_ms(400)
+        .options(options)
+        .build()
+    };
+
+    // The bar grows for half of each scene and then holds, while the counter changes per scene.
+    let scenes = vec![scene("1"), scene("2"), scene("10")];
+
+    let frames_result = render_sequence_animation(&scenes, 10);
+    assert!(frames_result.is_ok());
+    let Ok(frames) = frames_result else {
+      unreachable!()
+    };
+    assert_eq!(frames.len(), 12);
+
+    for (index, frame) in frames.iter().enumerate() {
+      let expected = render_sequence_at_time(&scenes, index as u64 * 100);
+      assert!(expected.is_ok());
+      let Ok(expected) = expected else {
+        unreachable!()
+      };
+
+      assert_eq!(frame.image, expected, "frame {index} differs");
+    }
+  }
+
+  #[test]
+  fn render_layers_stack_back_into_the_full_render() {
+    let global = GlobalContext::default();
+    let block = |width: f32, color: [u8; 4]| {
+      Node::container([]).with_style(
+        Style::default()
+          .with(StyleDeclaration::width(Px(width)))
+          .with(StyleDeclaration::height(Px(8.0)))
+          .with(StyleDeclaration::background_color(ColorInput::Value(
+            Color(color),
+          ))),
+      )
+    };
+
+    let node = Node::container([
+      block(10.0, [255, 0, 0, 255]).with_id("logo"),
+      Node::container([
+        block(6.0, [0, 0, 255, 255]),
+        block(12.0, [0, 128, 0, 255]).with_attributes([("data-layer".into(), "".into())].into()),
+      ])
+      .with_style(
+        Style::default()
+          .with(StyleDeclaration::display(Display::Flex))
+          .with(StyleDeclaration::flex_direction(FlexDirection::Column)),
+      ),
+    ])
+    .with_id("page")
+    .with_style(
+      Style::default()
+        .with(StyleDeclaration::display(Display::Flex))
+        .with(StyleDeclaration::background_color(ColorInput::Value(
+          Color([255, 255, 255, 255]),
+        ))),
+    );
+    let options = || {
+      RenderOptions::builder()
+        .global(&global)
+        .viewport(Viewport::new((40, 20)))
+        .node(node.clone())
+        .build()
+    };
+
+    let layered = render_layers(options());
+    assert!(layered.is_ok());
+    let Ok(layered) = layered else { unreachable!() };
+    let summary: Vec<_> = layered
+      .layers
+      .iter()
+      .map(|layer| {
+        (
+          layer.name.as_str(),
+          layer.path.clone(),
+          (layer.x, layer.y),
+          layer.image.dimensions(),
+        )
+      })
+      .collect();
+
+    assert_eq!((layered.width, layered.height), (40, 20));
+    assert_eq!(
+      summary,
+      [
+        ("page", vec![], (0, 0), (22, 16)),
+        ("logo", vec![0], (0, 0), (10, 8)),
+        ("layer-2", vec![1], (10, 0), (6, 8)),
+        ("layer-3", vec![1, 1], (10, 8), (12, 8)),
+      ]
+    );
+
+    let mut stacked = RgbaImage::new(40, 20);
+    for layer in &layered.layers {
+      overlay(&mut stacked, &layer.image, layer.x.into(), layer.y.into());
+    }
+
+    let expected = render(options());
+    assert!(expected.is_ok());
+    let Ok(expected) = expected else {
+      unreachable!()
+    };
+    assert_eq!(stacked, expected);
+  }
+
+  #[test]
+  fn render_with_profile_mirrors_the_node_tree() {
+    let global = GlobalContext::default();
+    let node = Node::container([
+      Node::text("Hello").with_tag_name("p"),
+      Node::container([]).with_id("empty"),
+    ])
+    .with_style(Style::default().with(StyleDeclaration::display(Display::Flex)));
+    let options = RenderOptions::builder()
+      .global(&global)
+      .viewport(Viewport::new((40, 20)))
+      .node(node);
+
+    let Ok((_, None)) = render_with_profile(options.clone().build()) else {
+      unreachable!()
+    };
+    let Ok((image, Some(profile))) = render_with_profile(options.profile(true).build()) else {
+      unreachable!()
+    };
+
+    assert_eq!(image.dimensions(), (40, 20));
+    assert!(profile.total_ms >= profile.layout_ms + profile.draw_ms);
+
+    let children = &profile.root.children;
+    assert_eq!(children.len(), 2);
+    assert_eq!(children[0].tag_name.as_deref(), Some("p"));
+    assert_eq!(children[0].path, [0]);
+    assert_eq!(children[1].id.as_deref(), Some("empty"));
+    assert_eq!(children[1].path, [1]);
+    assert!(profile.root.layout_ms >= children[0].layout_ms);
+  }
 }
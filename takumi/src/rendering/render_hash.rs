@@ -0,0 +1,211 @@
+use std::{
+  collections::BTreeSet,
+  fmt::{self, Write},
+  hash::Hasher,
+};
+
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::{
+  Result,
+  layout::{
+    node::{NodeKind, resolve_image},
+    style::BackgroundImage,
+    tree::RenderNode,
+  },
+  rendering::{RenderContext, RenderOptions},
+  resources::image::ImageSource,
+};
+
+/// Streams formatted values into the hasher without building intermediate strings.
+struct HashWriter(Xxh3);
+
+impl Write for HashWriter {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    self.0.write(s.as_bytes());
+    Ok(())
+  }
+}
+
+impl HashWriter {
+  fn value(&mut self, value: &impl fmt::Debug) {
+    // Writing into the hasher itself can't fail.
+    let _ = write!(self, "{value:?}\u{0}");
+  }
+}
+
+/// Hashes everything that decides the pixels of a render without laying out or rasterizing it.
+///
+/// The hash covers the computed style and content of every node, the viewport, the animation time,
+/// the injected clock and seed, the missing font policy, the text rendering options, the pixels of
+/// every image the node tree references, the loaded fonts and their fallback chains. Equal hashes
+/// mean [`render`](crate::rendering::render) produces the same image, which makes it usable as an
+/// `ETag` or cache key. Hashes are stable across runs of the same version but not across versions.
+///
+/// Custom drawers are identified by name only, so a drawer that paints differently under the
+/// same name doesn't change the hash.
+pub fn render_hash<'g>(options: RenderOptions<'g>) -> Result<u64> {
+  let RenderOptions {
+    viewport,
+    global,
+    node,
+    draw_debug_border,
+    fetched_resources,
+    stylesheet,
+    time_ms,
+    dithering,
+    custom_drawers,
+    max_width,
+    max_height,
+    now_ms,
+    seed,
+    locale,
+    missing_font_policy,
+    profile: _,
+  } = options;
+
+  node.check_plugin_types(global.node_plugins())?;
+
+  let mut hasher = HashWriter(Xxh3::new());
+  hasher.value(&(viewport, max_width, max_height));
+  hasher.value(&(draw_debug_border, time_ms, dithering, now_ms, seed));
+  hasher.value(&custom_drawers.keys().collect::<BTreeSet<_>>());
+  hasher.value(&global.font_context().fingerprint());
+  hasher.value(&global.emoji_store().has_source());
+  hasher.value(&missing_font_policy.unwrap_or(global.missing_font_policy()));
+  hasher.value(&global.text_rendering());
+
+  let mut context = RenderContext::new(
+    global,
+    viewport,
+    fetched_resources,
+    stylesheet.into(),
+    time_ms,
+  );
+  context.locale = locale.map(Into::into);
+  let root = RenderNode::from_node(&context, node);
+
+  let mut stack = vec![&root];
+  while let Some(render_node) = stack.pop() {
+    hash_render_node(&mut hasher, render_node);
+
+    if let Some(children) = &render_node.children {
+      stack.extend(children.iter().rev());
+    }
+  }
+
+  Ok(hasher.0.finish())
+}
+
+fn hash_render_node(hasher: &mut HashWriter, render_node: &RenderNode) {
+  let context = &render_node.context;
+
+  hasher.value(&context.style);
+  hasher.value(&(context.current_color, context.transform));
+  hasher.value(&render_node.layout_style_override);
+  hasher.value(&(
+    &render_node.anonymous_text_content,
+    render_node.force_inline_layout,
+    render_node.children.as_ref().map(|children| children.len()),
+  ));
+
+  // Containers only contribute their style, their children are hashed on their own.
+  let image_src = match render_node.node.as_ref().map(|node| &node.kind) {
+    Some(NodeKind::Container { .. }) | None => None,
+    Some(NodeKind::Image(image)) => {
+      hasher.value(image);
+      Some(&*image.src)
+    }
+    Some(kind) => {
+      hasher.value(kind);
+      None
+    }
+  };
+
+  let urls = context
+    .style
+    .background_image
+    .iter()
+    .chain(context.style.mask_image.iter())
+    .chain(context.style.text_stroke_image.iter())
+    .flat_map(|images| images.iter())
+    .filter_map(|image| match image {
+      BackgroundImage::Url(url) => Some(&**url),
+      _ => None,
+    });
+
+  for src in image_src.into_iter().chain(urls) {
+    hash_image(hasher, src, context);
+  }
+}
+
+fn hash_image(hasher: &mut HashWriter, src: &str, context: &RenderContext) {
+  match resolve_image(src, context).as_deref() {
+    Ok(ImageSource::Bitmap(image)) => {
+      hasher.value(&image.dimensions());
+      hasher.0.write(image.as_raw());
+    }
+    Ok(ImageSource::ScaledBitmap {
+      bitmap,
+      intrinsic_size,
+    }) => {
+      hasher.value(intrinsic_size);
+      hasher.value(&bitmap.dimensions());
+      hasher.0.write(bitmap.as_raw());
+    }
+    #[cfg(feature = "svg")]
+    Ok(ImageSource::Svg { source, .. }) => hasher.0.write(source.as_bytes()),
+    Err(_) => hasher.value(&"missing"),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    GlobalContext,
+    layout::{
+      Viewport,
+      node::Node,
+      style::{Color, ColorInput, Style, StyleDeclaration},
+    },
+    rendering::{RenderOptions, render_hash},
+  };
+
+  fn hash_with(global: &GlobalContext, color: Color, text: &str) -> u64 {
+    let node = Node::container([Node::text(text)]).with_style(
+      Style::default().with(StyleDeclaration::background_color(ColorInput::Value(color))),
+    );
+
+    let hash = render_hash(
+      RenderOptions::builder()
+        .global(global)
+        .viewport(Viewport::new((40, 20)))
+        .node(node)
+        .build(),
+    );
+    assert!(hash.is_ok());
+    let Ok(hash) = hash else { unreachable!() };
+
+    hash
+  }
+
+  #[test]
+  fn equal_inputs_hash_equally() {
+    let first = GlobalContext::default();
+    let second = GlobalContext::default();
+
+    assert_eq!(
+      hash_with(&first, Color([255, 0, 0, 255]), "a"),
+      hash_with(&second, Color([255, 0, 0, 255]), "a")
+    );
+  }
+
+  #[test]
+  fn style_and_content_changes_change_the_hash() {
+    let global = GlobalContext::default();
+    let base = hash_with(&global, Color([255, 0, 0, 255]), "a");
+
+    assert_ne!(base, hash_with(&global, Color([0, 0, 255, 255]), "a"));
+    assert_ne!(base, hash_with(&global, Color([255, 0, 0, 255]), "b"));
+  }
+}
@@ -1,22 +1,23 @@
-use std::{borrow::Cow, convert::Into};
+use std::{borrow::Cow, convert::Into, ops::Range};
 
 use image::{GenericImageView, Pixel, Rgba, RgbaImage};
 use parley::{GlyphRun, layout::BreakReason};
 use swash::{ColorPalette, scale::outline::Outline};
 use taffy::{Layout, Point, Size};
-use zeno::{Command, PathData, Stroke};
+use unicode_segmentation::UnicodeSegmentation;
+use zeno::{Command, PathBuilder, PathData, Placement, Stroke};
 
 use crate::{
   Result,
   layout::{
     inline::{InlineBrush, InlineLayout, break_lines},
     style::{
-      Affine, BlendMode, Color, ImageScalingAlgorithm, SizedFontStyle, TextTransform,
-      WhiteSpaceCollapse,
+      Affine, BlendMode, Color, FontVariantCaps, ImageScalingAlgorithm, LineBreak, SizedFontStyle,
+      TextAutospace, TextDecorationStyle, TextTransform, WhiteSpaceCollapse, WordBreak,
     },
   },
   rendering::{
-    BorderProperties, BufferPool, Canvas, CanvasConstrain, ColorTile, MaskMemory,
+    BackgroundTile, BorderProperties, BufferPool, Canvas, CanvasConstrain, ColorTile, MaskMemory,
     apply_mask_alpha_to_pixel, blend_pixel, draw_mask, mask_index_from_coord, overlay_area,
     sample_transformed_pixel,
   },
@@ -58,47 +59,322 @@ fn invert_y_coordinate(command: Command) -> Command {
 pub(crate) fn draw_decoration(
   canvas: &mut Canvas,
   glyph_run: &GlyphRun<'_, InlineBrush>,
-  color: Color,
+  paint: DecorationPaint<'_>,
   offset: f32,
   size: f32,
   layout: Layout,
   transform: Affine,
 ) {
   let start_x = layout.border.left + layout.padding.left + glyph_run.offset();
-  let end_x = start_x + glyph_run.advance();
-  if end_x <= start_x {
+
+  draw_decoration_line(
+    canvas,
+    paint,
+    glyph_run.style().brush.decoration_style,
+    start_x,
+    start_x + glyph_run.advance(),
+    layout.border.top + layout.padding.top + offset,
+    size,
+    transform,
+  );
+}
+
+/// Returns the vertical extent of a decoration line whose top edge is at `y`.
+pub(crate) fn decoration_extent(style: TextDecorationStyle, y: f32, size: f32) -> (f32, f32) {
+  match style {
+    TextDecorationStyle::Double => (y, y + size * 3.0),
+    TextDecorationStyle::Wavy => {
+      let amplitude = wave_amplitude(size);
+      (y - amplitude, y + size + amplitude)
+    }
+    _ => (y, y + size),
+  }
+}
+
+fn wave_amplitude(size: f32) -> f32 {
+  size.max(1.0)
+}
+
+/// Paint for a decoration line.
+#[derive(Clone, Copy)]
+pub(crate) struct DecorationPaint<'a> {
+  pub color: Color,
+  /// Background shown through the line for `background-clip: text`, in layout coordinates.
+  pub background: Option<&'a BackgroundTile>,
+}
+
+/// Draws a decoration line covering `start_x..end_x` with its top edge at `y`.
+///
+/// Dots, dashes and waves are anchored to the start of the inline layout rather than the line,
+/// so a line split around glyphs or across runs stays in phase.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn draw_decoration_line(
+  canvas: &mut Canvas,
+  paint: DecorationPaint<'_>,
+  style: TextDecorationStyle,
+  start_x: f32,
+  end_x: f32,
+  y: f32,
+  size: f32,
+  transform: Affine,
+) {
+  if end_x <= start_x || size <= 0.0 {
     return;
   }
 
-  let snapped_start_x = start_x.floor();
-  let width = (end_x.ceil() - snapped_start_x) as u32;
+  if let Some(background) = paint.background {
+    draw_decoration_background(
+      canvas, background, style, start_x, end_x, y, size, transform,
+    );
+  }
+
+  let color = paint.color;
+
+  match style {
+    TextDecorationStyle::Double => {
+      draw_decoration_rect(canvas, color, start_x, end_x, y, size, transform);
+      draw_decoration_rect(
+        canvas,
+        color,
+        start_x,
+        end_x,
+        y + size * 2.0,
+        size,
+        transform,
+      );
+    }
+    TextDecorationStyle::Dashed | TextDecorationStyle::Dotted | TextDecorationStyle::Wavy => {
+      let (paths, stroke) = decoration_paths(style, start_x, end_x, y, size);
+
+      if paths.is_empty() {
+        return;
+      }
+
+      let (mask, placement) = canvas.mask_memory.render(
+        &paths,
+        Some(transform),
+        stroke.map(Into::into),
+        &mut canvas.buffer_pool,
+      );
+      draw_mask(
+        &mut canvas.image,
+        &mask,
+        placement,
+        color,
+        BlendMode::Normal,
+        &canvas.constrains,
+      );
+      canvas.buffer_pool.release(mask);
+    }
+    _ => draw_decoration_rect(canvas, color, start_x, end_x, y, size, transform),
+  }
+}
+
+/// Fills the shape of a decoration line with the background it was clipped from.
+#[allow(clippy::too_many_arguments)]
+fn draw_decoration_background(
+  canvas: &mut Canvas,
+  background: &BackgroundTile,
+  style: TextDecorationStyle,
+  start_x: f32,
+  end_x: f32,
+  y: f32,
+  size: f32,
+  transform: Affine,
+) {
+  let Some(inverse) = transform.invert() else {
+    return;
+  };
+
+  let (paths, stroke) = decoration_paths(style, start_x, end_x, y, size);
+
+  if paths.is_empty() {
+    return;
+  }
+
+  let (mask, placement) = canvas.mask_memory.render(
+    &paths,
+    Some(transform),
+    stroke.map(Into::into),
+    &mut canvas.buffer_pool,
+  );
+
+  overlay_area(
+    &mut canvas.image,
+    Point {
+      x: placement.left as f32,
+      y: placement.top as f32,
+    },
+    Size {
+      width: placement.width,
+      height: placement.height,
+    },
+    BlendMode::Normal,
+    &canvas.constrains,
+    |x, y| {
+      let alpha = mask[mask_index_from_coord(x, y, placement.width)];
+
+      if alpha == 0 {
+        return Color::transparent().into();
+      }
+
+      let sampled_pixel = sample_transformed_pixel(
+        background,
+        inverse,
+        ImageScalingAlgorithm::Auto,
+        (x as i32 + placement.left) as f32,
+        (y as i32 + placement.top) as f32,
+        Point::ZERO,
+      );
+
+      let Some(mut pixel) = sampled_pixel else {
+        return Color::transparent().into();
+      };
+
+      apply_mask_alpha_to_pixel(&mut pixel, alpha);
+
+      pixel
+    },
+  );
+
+  canvas.buffer_pool.release(mask);
+}
+
+/// Builds the path of a decoration line, with the stroke to render it with if it isn't filled.
+fn decoration_paths(
+  style: TextDecorationStyle,
+  start_x: f32,
+  end_x: f32,
+  y: f32,
+  size: f32,
+) -> (Vec<Command>, Option<Stroke<'static>>) {
+  let unit = size.max(1.0);
+  let mut paths = Vec::new();
+
+  match style {
+    TextDecorationStyle::Double => {
+      paths.add_rect((start_x, y), end_x - start_x, size);
+      paths.add_rect((start_x, y + size * 2.0), end_x - start_x, size);
+    }
+    TextDecorationStyle::Dashed => {
+      let (dash, period) = (unit * 3.0, unit * 5.0);
+      let mut x = (start_x / period).floor() * period;
+
+      while x < end_x {
+        let (left, right) = (x.max(start_x), (x + dash).min(end_x));
+        if right > left {
+          paths.add_rect((left, y), right - left, size);
+        }
+        x += period;
+      }
+    }
+    TextDecorationStyle::Dotted => {
+      let period = unit * 2.0;
+      let radius = size / 2.0;
+      let mut x = (start_x / period).floor() * period + radius;
+
+      while x - radius < end_x {
+        if x - radius >= start_x - f32::EPSILON && x + radius <= end_x + f32::EPSILON {
+          append_circle(&mut paths, x, y + radius, radius);
+        }
+        x += period;
+      }
+    }
+    TextDecorationStyle::Wavy => {
+      let wavelength = unit * 6.0;
+      let amplitude = wave_amplitude(size);
+      let center_y = y + size / 2.0;
+      let wave_y = |x: f32| center_y + amplitude * (x / wavelength * std::f32::consts::TAU).sin();
+
+      paths.move_to((start_x, wave_y(start_x)));
+      let mut x = start_x;
+      while x < end_x {
+        x = (x + 1.0).min(end_x);
+        paths.line_to((x, wave_y(x)));
+      }
 
+      return (paths, Some(Stroke::new(size)));
+    }
+    _ => {
+      paths.add_rect((start_x, y), end_x - start_x, size);
+    }
+  }
+
+  (paths, None)
+}
+
+fn append_circle(paths: &mut Vec<Command>, cx: f32, cy: f32, radius: f32) {
+  // Control point distance for approximating a quarter circle with a cubic curve.
+  const KAPPA: f32 = 0.552_284_8;
+  let k = radius * KAPPA;
+
+  paths.move_to((cx + radius, cy));
+  paths.curve_to(
+    (cx + radius, cy + k),
+    (cx + k, cy + radius),
+    (cx, cy + radius),
+  );
+  paths.curve_to(
+    (cx - k, cy + radius),
+    (cx - radius, cy + k),
+    (cx - radius, cy),
+  );
+  paths.curve_to(
+    (cx - radius, cy - k),
+    (cx - k, cy - radius),
+    (cx, cy - radius),
+  );
+  paths.curve_to(
+    (cx + k, cy - radius),
+    (cx + radius, cy - k),
+    (cx + radius, cy),
+  );
+  paths.close();
+}
+
+fn draw_decoration_rect(
+  canvas: &mut Canvas,
+  color: Color,
+  start_x: f32,
+  end_x: f32,
+  y: f32,
+  height: f32,
+  transform: Affine,
+) {
+  let x = start_x.floor();
+  let width = (end_x.ceil() - x) as u32;
+
+  // Lines thinner than a device pixel, such as at a low device pixel ratio, still draw one.
   let tile = ColorTile {
     color: color.into(),
     width,
-    height: size as u32,
+    height: (height as u32).max(1),
   };
 
+  if tile.width == 0 || tile.height == 0 {
+    return;
+  }
+
   canvas.overlay_image(
     &tile,
     BorderProperties::default(),
-    transform
-      * Affine::translation(
-        snapped_start_x,
-        layout.border.top + layout.padding.top + offset,
-      ),
+    transform * Affine::translation(x, y),
     ImageScalingAlgorithm::Auto,
     BlendMode::Normal,
   );
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn draw_glyph_clip_image<I: GenericImageView<Pixel = Rgba<u8>>>(
   glyph: &ResolvedGlyph,
   canvas: &mut Canvas,
   style: &SizedFontStyle,
+  brush: &InlineBrush,
   mut transform: Affine,
   inline_offset: Point<f32>,
   clip_image: &I,
+  stroke_paint: StrokePaint<'_>,
+  coverage: Option<&[u8; 256]>,
 ) -> Result<()> {
   transform *= Affine::translation(inline_offset.x, inline_offset.y);
 
@@ -166,11 +442,13 @@ pub(crate) fn draw_glyph_clip_image<I: GenericImageView<Pixel = Rgba<u8>>>(
 
       let paths = collect_outline_paths(outline);
 
-      let (mask, placement) =
+      let (mut mask, placement) =
         canvas
           .mask_memory
           .render(&paths, Some(transform), None, &mut canvas.buffer_pool);
 
+      apply_glyph_coverage(&mut mask, coverage);
+
       overlay_area(
         &mut canvas.image,
         Point {
@@ -211,7 +489,18 @@ pub(crate) fn draw_glyph_clip_image<I: GenericImageView<Pixel = Rgba<u8>>>(
 
       canvas.buffer_pool.release(mask);
 
-      draw_text_stroke_clip_image(canvas, style, transform, &paths, clip_image, inline_offset);
+      draw_text_inset_shadow(canvas, style, transform, &paths)?;
+
+      draw_text_stroke(
+        canvas,
+        style,
+        brush,
+        transform,
+        &paths,
+        inline_offset,
+        stroke_paint,
+        Some(clip_image),
+      );
     }
   }
 
@@ -223,10 +512,12 @@ pub(crate) fn draw_glyph(
   glyph: &ResolvedGlyph,
   canvas: &mut Canvas,
   style: &SizedFontStyle,
+  brush: &InlineBrush,
   mut transform: Affine,
   inline_offset: Point<f32>,
-  color: Color,
   palette: Option<ColorPalette>,
+  stroke_paint: StrokePaint<'_>,
+  coverage: Option<&[u8; 256]>,
 ) -> Result<()> {
   transform *= Affine::translation(inline_offset.x, inline_offset.y);
 
@@ -258,19 +549,21 @@ pub(crate) fn draw_glyph(
           palette,
           transform,
           &canvas.constrains,
-          color.0[3],
+          brush.color.0[3],
         );
       } else {
-        let (mask, placement) =
+        let (mut mask, placement) =
           canvas
             .mask_memory
             .render(&paths, Some(transform), None, &mut canvas.buffer_pool);
 
+        apply_glyph_coverage(&mut mask, coverage);
+
         draw_mask(
           &mut canvas.image,
           &mask,
           placement,
-          color,
+          brush.color,
           BlendMode::Normal,
           &canvas.constrains,
         );
@@ -278,30 +571,60 @@ pub(crate) fn draw_glyph(
         canvas.buffer_pool.release(mask);
       }
 
-      draw_text_stroke(canvas, style, transform, &paths);
+      draw_text_inset_shadow(canvas, style, transform, &paths)?;
+      draw_text_stroke(
+        canvas,
+        style,
+        brush,
+        transform,
+        &paths,
+        inline_offset,
+        stroke_paint,
+        None::<&BackgroundTile>,
+      );
     }
   }
 
   Ok(())
 }
 
-fn draw_text_stroke_clip_image<I: GenericImageView<Pixel = Rgba<u8>>>(
+/// Corrects the coverage of a glyph mask with the table from
+/// [`TextRenderingOptions::coverage_table`](crate::rendering::TextRenderingOptions).
+fn apply_glyph_coverage(mask: &mut [u8], coverage: Option<&[u8; 256]>) {
+  let Some(coverage) = coverage else {
+    return;
+  };
+
+  for alpha in mask {
+    *alpha = coverage[*alpha as usize];
+  }
+}
+
+/// What the stroke of `-webkit-text-stroke` is painted with.
+#[derive(Clone, Copy)]
+pub(crate) enum StrokePaint<'a> {
+  /// The stroke color of the glyph's brush.
+  Color,
+  /// The `text-stroke-image`, rasterized over the border box of the inline layout.
+  Image(&'a BackgroundTile),
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_text_stroke<I: GenericImageView<Pixel = Rgba<u8>>>(
   canvas: &mut Canvas,
   style: &SizedFontStyle,
+  brush: &InlineBrush,
   transform: Affine,
   paths: &[Command],
-  clip_image: &I,
   inline_offset: Point<f32>,
+  paint: StrokePaint<'_>,
+  clip_image: Option<&I>,
 ) {
-  if style.stroke_width <= 0.0 {
+  if brush.stroke_width <= 0.0 {
     return;
   }
 
-  let Some(inverse) = transform.invert() else {
-    return;
-  };
-
-  let mut stroke = Stroke::new(style.stroke_width);
+  let mut stroke = Stroke::new(brush.stroke_width);
   stroke.join = style.parent.stroke_linejoin.into();
 
   let (stroke_mask, stroke_placement) = canvas.mask_memory.render(
@@ -311,6 +634,54 @@ fn draw_text_stroke_clip_image<I: GenericImageView<Pixel = Rgba<u8>>>(
     &mut canvas.buffer_pool,
   );
 
+  match (paint, clip_image) {
+    (StrokePaint::Image(image), _) => draw_sampled_stroke(
+      canvas,
+      style,
+      transform,
+      (&stroke_mask, stroke_placement),
+      image,
+      inline_offset,
+      None,
+    ),
+    // With `background-clip: text`, the stroke color is blended over the clipped background.
+    (StrokePaint::Color, Some(clip_image)) => draw_sampled_stroke(
+      canvas,
+      style,
+      transform,
+      (&stroke_mask, stroke_placement),
+      clip_image,
+      inline_offset,
+      Some(brush.stroke_color),
+    ),
+    (StrokePaint::Color, None) => draw_mask(
+      &mut canvas.image,
+      &stroke_mask,
+      stroke_placement,
+      brush.stroke_color,
+      BlendMode::Normal,
+      &canvas.constrains,
+    ),
+  }
+
+  canvas.buffer_pool.release(stroke_mask);
+}
+
+/// Paints a stroke mask with `image` sampled in inline layout coordinates, optionally with
+/// `tint` blended over it.
+fn draw_sampled_stroke<I: GenericImageView<Pixel = Rgba<u8>>>(
+  canvas: &mut Canvas,
+  style: &SizedFontStyle,
+  transform: Affine,
+  (stroke_mask, stroke_placement): (&[u8], Placement),
+  image: &I,
+  inline_offset: Point<f32>,
+  tint: Option<Color>,
+) {
+  let Some(inverse) = transform.invert() else {
+    return;
+  };
+
   overlay_area(
     &mut canvas.image,
     Point {
@@ -334,7 +705,7 @@ fn draw_text_stroke_clip_image<I: GenericImageView<Pixel = Rgba<u8>>>(
       let inline_y = (y as i32 + stroke_placement.top) as f32;
 
       let sampled_pixel = sample_transformed_pixel(
-        clip_image,
+        image,
         inverse,
         style.parent.image_rendering,
         inline_x,
@@ -346,64 +717,48 @@ fn draw_text_stroke_clip_image<I: GenericImageView<Pixel = Rgba<u8>>>(
         return Color::transparent().into();
       };
 
-      blend_pixel(
-        &mut pixel,
-        style.text_stroke_color.into(),
-        BlendMode::Normal,
-      );
+      if let Some(tint) = tint {
+        blend_pixel(&mut pixel, tint.into(), BlendMode::Normal);
+      }
       apply_mask_alpha_to_pixel(&mut pixel, alpha);
 
       pixel
     },
   );
-
-  canvas.buffer_pool.release(stroke_mask);
 }
 
-fn draw_text_stroke(
+fn draw_text_shadow(
   canvas: &mut Canvas,
   style: &SizedFontStyle,
   transform: Affine,
   paths: &[Command],
-) {
-  if style.stroke_width <= 0.0 {
-    return;
+) -> Result<()> {
+  if style.text_shadow.is_empty() {
+    return Ok(());
   }
 
-  let mut stroke = Stroke::new(style.stroke_width);
-  stroke.join = style.parent.stroke_linejoin.into();
-
-  let (stroke_mask, stroke_placement) = canvas.mask_memory.render(
-    paths,
-    Some(transform),
-    Some(stroke.into()),
-    &mut canvas.buffer_pool,
-  );
-
-  draw_mask(
-    &mut canvas.image,
-    &stroke_mask,
-    stroke_placement,
-    style.text_stroke_color,
-    BlendMode::Normal,
-    &canvas.constrains,
-  );
+  // The first shadow is painted on top, so paint the list back to front.
+  for shadow in style
+    .text_shadow
+    .iter()
+    .rev()
+    .filter(|shadow| !shadow.inset)
+  {
+    shadow.draw_outset(canvas, paths, transform, Default::default(), None)?;
+  }
 
-  canvas.buffer_pool.release(stroke_mask);
+  Ok(())
 }
 
-fn draw_text_shadow(
+/// Draws `inset` text shadows over the glyph fill, clipped to the glyph.
+fn draw_text_inset_shadow(
   canvas: &mut Canvas,
   style: &SizedFontStyle,
   transform: Affine,
   paths: &[Command],
 ) -> Result<()> {
-  if style.text_shadow.is_empty() {
-    return Ok(());
-  }
-
-  for shadow in style.text_shadow.iter() {
-    shadow.draw_outset(canvas, paths, transform, Default::default(), None)?;
+  for shadow in style.text_shadow.iter().rev().filter(|shadow| shadow.inset) {
+    shadow.draw_inset_paths(canvas, paths, transform)?;
   }
 
   Ok(())
@@ -515,33 +870,386 @@ pub(crate) fn apply_text_transform<'a>(input: &'a str, transform: TextTransform)
   }
 }
 
-/// Applies whitespace collapse rules to the input text according to `WhiteSpaceCollapse`.
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+
+/// Characters that may start a line under `line-break: normal`: small kana (class CJ), the
+/// prolonged sound mark and CJK hyphens.
+fn allows_normal_break_before(ch: char) -> bool {
+  matches!(
+    ch,
+    '\u{3041}' | '\u{3043}' | '\u{3045}' | '\u{3047}' | '\u{3049}' | '\u{3063}'
+      | '\u{3083}' | '\u{3085}' | '\u{3087}' | '\u{308E}' | '\u{3095}' | '\u{3096}'
+      | '\u{30A1}' | '\u{30A3}' | '\u{30A5}' | '\u{30A7}' | '\u{30A9}' | '\u{30C3}'
+      | '\u{30E3}' | '\u{30E5}' | '\u{30E7}' | '\u{30EE}' | '\u{30F5}' | '\u{30F6}'
+      | '\u{30FC}' | '\u{31F0}'..='\u{31FF}' | '\u{FF67}'..='\u{FF70}'
+      | '\u{301C}' | '\u{30A0}'
+  )
+}
+
+/// Characters that may additionally start a line under `line-break: loose`: hyphens, iteration
+/// marks, inseparable characters, centered punctuation and fullwidth postfixes.
+fn allows_loose_break_before(ch: char) -> bool {
+  matches!(
+    ch,
+    '\u{2010}'
+      | '\u{2013}'
+      | '\u{3005}'
+      | '\u{303B}'
+      | '\u{309D}'
+      | '\u{309E}'
+      | '\u{30FD}'
+      | '\u{30FE}'
+      | '\u{2025}'
+      | '\u{2026}'
+      | '\u{30FB}'
+      | '\u{FF1A}'
+      | '\u{FF1B}'
+      | '\u{FF65}'
+      | '\u{203C}'
+      | '\u{2047}'
+      ..='\u{2049}' | '\u{FF01}' | '\u{FF1F}' | '\u{2030}' | '\u{2103}' | '\u{FF05}' | '\u{FFE0}'
+  )
+}
+
+/// Whether `ch` is Chinese or Japanese text, the only context `line-break` relaxes rules in.
+fn is_cjk(ch: char) -> bool {
+  matches!(
+    ch,
+    '\u{3000}'..='\u{30FF}'
+      | '\u{31F0}'..='\u{31FF}'
+      | '\u{3400}'..='\u{4DBF}'
+      | '\u{4E00}'..='\u{9FFF}'
+      | '\u{F900}'..='\u{FAFF}'
+      | '\u{FF00}'..='\u{FFEF}'
+      | '\u{20000}'..='\u{3FFFF}'
+  )
+}
+
+/// Inserts zero width spaces where `line-break` allows breaks that the default rules forbid.
+///
+/// The default line breaker already follows the strict rules, so `auto` and `strict` keep the text as is.
+pub(crate) fn apply_line_break<'a>(input: &'a str, line_break: LineBreak) -> Cow<'a, str> {
+  let loose = match line_break {
+    LineBreak::Auto | LineBreak::Strict => return Cow::Borrowed(input),
+    LineBreak::Normal => false,
+    LineBreak::Loose => true,
+  };
+
+  let mut output: Option<String> = None;
+  let mut previous = None;
+
+  for (index, ch) in input.char_indices() {
+    if previous.is_some_and(is_cjk)
+      && (allows_normal_break_before(ch) || (loose && allows_loose_break_before(ch)))
+    {
+      output
+        .get_or_insert_with(|| input[..index].to_string())
+        .push(ZERO_WIDTH_SPACE);
+    }
+
+    if let Some(output) = output.as_mut() {
+      output.push(ch);
+    }
+
+    previous = Some(ch);
+  }
+
+  output.map_or(Cow::Borrowed(input), Cow::Owned)
+}
+
+/// Whether `ch` separates the parts of a URL, path or handle, which `word-break: break-url` breaks after.
+fn is_url_separator(ch: char) -> bool {
+  matches!(
+    ch,
+    '/' | '\\' | '.' | '-' | '_' | '?' | '&' | '=' | '#' | '@' | ':' | '~' | '+' | '|'
+  )
+}
+
+/// Inserts zero width spaces after URL separators under `word-break: break-url`.
+///
+/// Breaks are only added inside a token and after the last of consecutive separators, so `https://`
+/// stays together, and never in a decimal point or before a leading `@` of a handle.
+pub(crate) fn apply_url_breaks<'a>(input: &'a str, word_break: WordBreak) -> Cow<'a, str> {
+  if word_break != WordBreak::BreakUrl {
+    return Cow::Borrowed(input);
+  }
+
+  let mut output: Option<String> = None;
+  let mut previous: Option<char> = None;
+  let mut chars = input.char_indices().peekable();
+
+  while let Some((index, ch)) = chars.next() {
+    if let Some(output) = output.as_mut() {
+      output.push(ch);
+    }
+
+    let following = chars.peek().map(|(_, ch)| *ch);
+    let is_decimal_point = ch == '.'
+      && previous.is_some_and(|ch| ch.is_ascii_digit())
+      && following.is_some_and(|ch| ch.is_ascii_digit());
+
+    if is_url_separator(ch)
+      && !is_decimal_point
+      && previous.is_some_and(|ch| !ch.is_whitespace())
+      && following.is_some_and(|ch| !ch.is_whitespace() && !is_url_separator(ch))
+    {
+      output
+        .get_or_insert_with(|| input[..index + ch.len_utf8()].to_string())
+        .push(ZERO_WIDTH_SPACE);
+    }
+
+    previous = Some(ch);
+  }
+
+  output.map_or(Cow::Borrowed(input), Cow::Owned)
+}
+
+/// Whether `ch` is an ideograph or kana that `text-autospace` separates from other scripts.
+fn is_autospace_ideograph(ch: char) -> bool {
+  matches!(
+    ch,
+    '\u{3041}'..='\u{30FF}'
+      | '\u{31F0}'..='\u{31FF}'
+      | '\u{3400}'..='\u{4DBF}'
+      | '\u{4E00}'..='\u{9FFF}'
+      | '\u{F900}'..='\u{FAFF}'
+      | '\u{20000}'..='\u{3FFFF}'
+  )
+}
+
+/// Whether `ch` is a letter or numeral of a non-ideographic script that `autospace` spaces from ideographs.
+fn is_autospace_other(ch: char, autospace: TextAutospace) -> bool {
+  // Fullwidth forms and Hangul already sit on the ideographic grid.
+  if is_cjk(ch)
+    || matches!(ch, '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}')
+  {
+    return false;
+  }
+
+  (autospace.spaces_numeric() && ch.is_numeric())
+    || (autospace.spaces_alpha() && ch.is_alphabetic())
+}
+
+fn is_autospace_boundary(current: char, next: char, autospace: TextAutospace) -> bool {
+  (is_autospace_ideograph(current) && is_autospace_other(next, autospace))
+    || (is_autospace_other(current, autospace) && is_autospace_ideograph(next))
+}
+
+/// Inserts a zero width space wherever `text-autospace` separates ideographs from other scripts,
+/// where `next` is the character that follows `input` in the inline formatting context.
+///
+/// The spacers are widened when the text is shaped, so the runs on either side keep their kerning.
+pub(crate) fn apply_text_autospace<'a>(
+  input: &'a str,
+  next: Option<char>,
+  autospace: TextAutospace,
+) -> Cow<'a, str> {
+  if autospace == TextAutospace::NoAutospace {
+    return Cow::Borrowed(input);
+  }
+
+  let mut output: Option<String> = None;
+  let mut chars = input.char_indices().peekable();
+
+  while let Some((index, ch)) = chars.next() {
+    let following = chars.peek().map(|(_, ch)| *ch).or(next);
+
+    if let Some(output) = output.as_mut() {
+      output.push(ch);
+    }
+
+    if following.is_some_and(|following| is_autospace_boundary(ch, following, autospace)) {
+      output
+        .get_or_insert_with(|| input[..index + ch.len_utf8()].to_string())
+        .push(ZERO_WIDTH_SPACE);
+    }
+  }
+
+  output.map_or(Cow::Borrowed(input), Cow::Owned)
+}
+
+/// Returns the byte ranges of the spacers inserted by [`apply_text_autospace`].
+pub(crate) fn text_autospace_spacers(
+  input: &str,
+  next: Option<char>,
+  autospace: TextAutospace,
+) -> Vec<Range<usize>> {
+  if autospace == TextAutospace::NoAutospace {
+    return Vec::new();
+  }
+
+  let mut ranges = Vec::new();
+  let mut previous = None;
+  let mut chars = input.char_indices().peekable();
+
+  while let Some((index, ch)) = chars.next() {
+    let following = chars.peek().map(|(_, ch)| *ch).or(next);
+
+    if ch == ZERO_WIDTH_SPACE
+      && let (Some(previous), Some(following)) = (previous, following)
+      && is_autospace_boundary(previous, following, autospace)
+    {
+      ranges.push(index..index + ch.len_utf8());
+    }
+
+    previous = Some(ch);
+  }
+
+  ranges
+}
+
+/// Uppercases the letters drawn as synthesized capitals by `font-variant-caps`, returning the
+/// byte ranges to draw at a reduced size.
+///
+/// Letters whose uppercase form has a different UTF-8 length (such as `ß`) are left untouched
+/// so byte offsets into the span text stay valid.
+pub(crate) fn apply_synthetic_small_caps(
+  input: &str,
+  caps: FontVariantCaps,
+) -> (Cow<'_, str>, Vec<Range<usize>>) {
+  if caps == FontVariantCaps::Normal {
+    return (Cow::Borrowed(input), Vec::new());
+  }
+
+  let mut output = String::with_capacity(input.len());
+  let mut ranges: Vec<Range<usize>> = Vec::new();
+
+  for (index, ch) in input.char_indices() {
+    let mut upper = ch.to_uppercase();
+    let synthesized = match (upper.next(), upper.next()) {
+      (Some(upper), None) if upper.len_utf8() == ch.len_utf8() => {
+        if ch.is_lowercase() && upper != ch {
+          Some(upper)
+        } else if caps.includes_uppercase() && ch.is_uppercase() {
+          Some(ch)
+        } else {
+          None
+        }
+      }
+      _ => None,
+    };
+
+    let Some(upper) = synthesized else {
+      output.push(ch);
+      continue;
+    };
+
+    output.push(upper);
+    let end = index + ch.len_utf8();
+
+    match ranges.last_mut() {
+      Some(range) if range.end == index => range.end = end,
+      _ => ranges.push(index..end),
+    }
+  }
+
+  (Cow::Owned(output), ranges)
+}
+
+/// Expands tab characters into spaces up to the next tab stop, every `tab_spaces` columns.
+///
+/// `column` carries the column after the last line break across consecutive spans.
+pub(crate) fn apply_tab_size<'a>(
+  input: &'a str,
+  tab_spaces: usize,
+  column: &mut usize,
+) -> Cow<'a, str> {
+  if !input.contains('\t') {
+    *column = match input.rfind(is_line_break_char) {
+      Some(index) => input[index..].chars().count() - 1,
+      None => *column + input.chars().count(),
+    };
+    return Cow::Borrowed(input);
+  }
+
+  let mut output = String::with_capacity(input.len());
+
+  for ch in input.chars() {
+    match ch {
+      '\t' => {
+        let spaces = if tab_spaces == 0 {
+          0
+        } else {
+          tab_spaces - *column % tab_spaces
+        };
+        output.extend(std::iter::repeat_n(' ', spaces));
+        *column += spaces;
+      }
+      ch if is_line_break_char(ch) => {
+        output.push(ch);
+        *column = 0;
+      }
+      ch => {
+        output.push(ch);
+        *column += 1;
+      }
+    }
+  }
+
+  Cow::Owned(output)
+}
+
+/// Returns the largest grapheme cluster boundary in `input` at or before `index`.
+///
+/// Truncating text at the returned offset never splits a user-perceived character, such as a
+/// base letter and its combining marks, a flag, or an emoji ZWJ sequence.
+pub(crate) fn floor_grapheme_boundary(input: &str, index: usize) -> usize {
+  if index >= input.len() {
+    return input.len();
+  }
+
+  input
+    .grapheme_indices(true)
+    .map(|(start, _)| start)
+    .take_while(|start| *start <= index)
+    .last()
+    .unwrap_or(0)
+}
+
+fn is_line_break_char(ch: char) -> bool {
+  matches!(ch, '\n' | '\r' | '\x0B' | '\x0C' | '\u{2028}' | '\u{2029}')
+}
+
+/// Applies whitespace collapse rules to one text span of an inline formatting context
+/// according to `WhiteSpaceCollapse`.
+///
+/// `after_space` tells whether the preceding content ended with collapsible white space or is the
+/// start of the block, and is updated for the next span, so white space collapses across span
+/// boundaries instead of being trimmed from every span. Collapsible spaces at the end of the block
+/// (`at_end`) are removed; the ones at soft wrap points hang at the line end.
 pub(crate) fn apply_white_space_collapse<'a>(
   input: &'a str,
   collapse: WhiteSpaceCollapse,
+  after_space: &mut bool,
+  at_end: bool,
 ) -> Cow<'a, str> {
   match collapse {
-    WhiteSpaceCollapse::Preserve => Cow::Borrowed(input),
+    WhiteSpaceCollapse::Preserve => {
+      *after_space = false;
+      Cow::Borrowed(input)
+    }
 
-    // Collapse sequences of whitespace (spaces, tabs, line breaks) into a single space
-    // and trim leading/trailing spaces.
+    // Collapse sequences of whitespace (spaces, tabs, line breaks) into a single space.
     WhiteSpaceCollapse::Collapse => {
       let mut out = String::with_capacity(input.len());
-      let mut last_was_ws = false;
 
       for ch in input.chars() {
         if ch.is_whitespace() {
-          if !last_was_ws {
+          if !*after_space {
             out.push(' ');
-            last_was_ws = true;
+            *after_space = true;
           }
         } else {
           out.push(ch);
-          last_was_ws = false;
+          *after_space = false;
         }
       }
 
-      Cow::Owned(out.trim().to_string())
+      if at_end {
+        out.truncate(out.trim_end_matches(' ').len());
+      }
+
+      Cow::Owned(out)
     }
 
     // Preserve sequences of spaces/tabs but remove line breaks (replace them with a single space).
@@ -551,7 +1259,7 @@ pub(crate) fn apply_white_space_collapse<'a>(
 
       for ch in input.chars() {
         // treat common line break characters as breaks to be removed/replaced
-        if matches!(ch, '\n' | '\r' | '\x0B' | '\x0C' | '\u{2028}' | '\u{2029}') {
+        if is_line_break_char(ch) {
           if !last_was_space {
             out.push(' ');
             last_was_space = true;
@@ -562,6 +1270,7 @@ pub(crate) fn apply_white_space_collapse<'a>(
         }
       }
 
+      *after_space = false;
       Cow::Owned(out)
     }
 
@@ -569,29 +1278,24 @@ pub(crate) fn apply_white_space_collapse<'a>(
     // Also remove leading spaces after line breaks.
     WhiteSpaceCollapse::PreserveBreaks => {
       let mut out = String::with_capacity(input.len());
-      let mut last_was_space = false;
-      let mut last_was_line_break = false;
 
       for ch in input.chars() {
         if ch == ' ' || ch == '\t' {
-          // Skip leading spaces after line breaks
-          if last_was_line_break {
-            continue;
-          }
-          if !last_was_space {
+          if !*after_space {
             out.push(' ');
-            last_was_space = true;
+            *after_space = true;
           }
         } else {
           out.push(ch);
-          last_was_space = false;
-          // Track if we just processed a line break
-          last_was_line_break =
-            matches!(ch, '\n' | '\r' | '\x0B' | '\x0C' | '\u{2028}' | '\u{2029}');
+          *after_space = is_line_break_char(ch);
         }
       }
 
-      Cow::Owned(out.trim().to_string())
+      if at_end {
+        out.truncate(out.trim_end_matches(' ').len());
+      }
+
+      Cow::Owned(out)
     }
   }
 }
@@ -715,21 +1419,64 @@ mod tests {
   #[test]
   fn test_white_space_preserve() {
     let input = "  a \t b\n";
-    let out = apply_white_space_collapse(input, WhiteSpaceCollapse::Preserve);
+    let out = apply_white_space_collapse(input, WhiteSpaceCollapse::Preserve, &mut true, true);
     assert_eq!(out, input);
   }
 
   #[test]
   fn test_white_space_collapse() {
     let input = "  a \n\t b  c\n\n ";
-    let out = apply_white_space_collapse(input, WhiteSpaceCollapse::Collapse);
+    let out = apply_white_space_collapse(input, WhiteSpaceCollapse::Collapse, &mut true, true);
     assert_eq!(out, "a b c");
   }
 
+  #[test]
+  fn test_floor_grapheme_boundary_keeps_clusters_whole() {
+    let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+    let text = format!("ab{family}c");
+
+    // Any offset inside the ZWJ sequence falls back to its start.
+    for index in 3..2 + family.len() {
+      assert_eq!(floor_grapheme_boundary(&text, index), 2);
+    }
+    assert_eq!(
+      floor_grapheme_boundary(&text, 2 + family.len()),
+      2 + family.len()
+    );
+    assert_eq!(floor_grapheme_boundary(&text, text.len() + 4), text.len());
+
+    // Combining marks and regional indicator pairs stay with their base.
+    assert_eq!(floor_grapheme_boundary("e\u{301}x", 1), 0);
+    assert_eq!(floor_grapheme_boundary("\u{1F1EF}\u{1F1F5}", 4), 0);
+  }
+
+  #[test]
+  fn test_white_space_collapse_across_spans() {
+    let mut after_space = true;
+    let spans = [" Hello ", " world ", "again  "];
+
+    let out: Vec<_> = spans
+      .iter()
+      .enumerate()
+      .map(|(index, span)| {
+        apply_white_space_collapse(
+          span,
+          WhiteSpaceCollapse::Collapse,
+          &mut after_space,
+          index == spans.len() - 1,
+        )
+      })
+      .collect();
+
+    // Spaces between spans collapse into one instead of being trimmed away.
+    assert_eq!(out, ["Hello ", "world ", "again"]);
+  }
+
   #[test]
   fn test_white_space_preserve_spaces() {
     let input = "a \n b";
-    let out = apply_white_space_collapse(input, WhiteSpaceCollapse::PreserveSpaces);
+    let out =
+      apply_white_space_collapse(input, WhiteSpaceCollapse::PreserveSpaces, &mut true, true);
     // line break should be replaced with a single space; existing spaces preserved
     assert_eq!(out, "a  b");
   }
@@ -737,8 +1484,152 @@ mod tests {
   #[test]
   fn test_white_space_preserve_breaks() {
     let input = "a \n b\tc";
-    let out = apply_white_space_collapse(input, WhiteSpaceCollapse::PreserveBreaks);
+    let out =
+      apply_white_space_collapse(input, WhiteSpaceCollapse::PreserveBreaks, &mut true, true);
     // spaces and tabs collapsed to single space, line break preserved
     assert_eq!(out, "a \nb c");
   }
+
+  #[test]
+  fn test_line_break_strict_keeps_text() {
+    let input = "あいうえおっか";
+    assert!(matches!(
+      apply_line_break(input, LineBreak::Strict),
+      Cow::Borrowed(_)
+    ));
+  }
+
+  #[test]
+  fn test_line_break_normal_allows_small_kana_at_line_start() {
+    assert_eq!(
+      apply_line_break("おっか々", LineBreak::Normal),
+      "お\u{200B}っか々"
+    );
+    assert_eq!(apply_line_break("aっ", LineBreak::Normal), "aっ");
+  }
+
+  #[test]
+  fn test_line_break_loose_allows_iteration_marks_at_line_start() {
+    assert_eq!(
+      apply_line_break("おっか々", LineBreak::Loose),
+      "お\u{200B}っか\u{200B}々"
+    );
+  }
+
+  #[test]
+  fn test_url_breaks_after_separators() {
+    assert_eq!(
+      apply_url_breaks("https://takumi.kane.tw/docs", WordBreak::BreakUrl),
+      "https://\u{200B}takumi.\u{200B}kane.\u{200B}tw/\u{200B}docs"
+    );
+    assert_eq!(
+      apply_url_breaks("@kane50613 costs 1.5x.", WordBreak::BreakUrl),
+      "@kane50613 costs 1.5x."
+    );
+    assert!(matches!(
+      apply_url_breaks("a/b", WordBreak::Normal),
+      Cow::Borrowed(_)
+    ));
+  }
+
+  #[test]
+  fn test_text_autospace_no_autospace_keeps_text() {
+    assert!(matches!(
+      apply_text_autospace("日本語Web", None, TextAutospace::NoAutospace),
+      Cow::Borrowed(_)
+    ));
+  }
+
+  #[test]
+  fn test_text_autospace_normal_spaces_both_sides_of_latin() {
+    let spaced = apply_text_autospace("日本Web開発2024年", None, TextAutospace::Normal);
+
+    assert_eq!(spaced, "日本\u{200B}Web\u{200B}開発\u{200B}2024\u{200B}年");
+    assert_eq!(
+      text_autospace_spacers(&spaced, None, TextAutospace::Normal),
+      [6..9, 12..15, 21..24, 28..31]
+    );
+  }
+
+  #[test]
+  fn test_text_autospace_ideograph_alpha_skips_numerals() {
+    assert_eq!(
+      apply_text_autospace("第3版Web", None, TextAutospace::IdeographAlpha),
+      "第3版\u{200B}Web"
+    );
+    assert_eq!(
+      apply_text_autospace("第3版Web", None, TextAutospace::IdeographNumeric),
+      "第\u{200B}3\u{200B}版Web"
+    );
+  }
+
+  #[test]
+  fn test_text_autospace_ignores_punctuation_and_fullwidth_forms() {
+    assert!(matches!(
+      apply_text_autospace("日本、Ｗｅｂ。a", None, TextAutospace::Normal),
+      Cow::Borrowed(_)
+    ));
+  }
+
+  #[test]
+  fn test_text_autospace_uses_following_character() {
+    assert_eq!(
+      apply_text_autospace("日本", Some('W'), TextAutospace::Normal),
+      "日本\u{200B}"
+    );
+    assert_eq!(
+      text_autospace_spacers("日本\u{200B}", Some('W'), TextAutospace::Normal),
+      vec![Range { start: 6, end: 9 }]
+    );
+  }
+
+  #[test]
+  fn test_text_autospace_spacers_skip_line_break_opportunities() {
+    let text = apply_line_break("おっかWeb", LineBreak::Normal);
+    let spaced = apply_text_autospace(&text, None, TextAutospace::Normal);
+
+    assert_eq!(
+      text_autospace_spacers(&spaced, None, TextAutospace::Normal),
+      vec![Range { start: 12, end: 15 }]
+    );
+  }
+
+  #[test]
+  fn test_synthetic_small_caps_uppercases_lowercase_letters() {
+    let (text, ranges) = apply_synthetic_small_caps("Takumi 1.0", FontVariantCaps::SmallCaps);
+
+    assert_eq!(text, "TAKUMI 1.0");
+    assert_eq!(ranges, vec![Range { start: 1, end: 6 }]);
+  }
+
+  #[test]
+  fn test_synthetic_small_caps_keeps_byte_offsets() {
+    let (text, ranges) = apply_synthetic_small_caps("Straße", FontVariantCaps::AllSmallCaps);
+
+    // `ß` uppercases to `SS`, which would shift later offsets.
+    assert_eq!(text, "STRAßE");
+    assert_eq!(ranges, vec![0..4, 6..7]);
+  }
+
+  #[test]
+  fn test_tab_size_expands_to_next_tab_stop() {
+    let mut column = 0;
+
+    assert_eq!(apply_tab_size("a\tbc\td", 4, &mut column), "a   bc  d");
+    assert_eq!(column, 9);
+    assert_eq!(apply_tab_size("\n\tx", 2, &mut column), "\n  x");
+    assert_eq!(column, 3);
+  }
+
+  #[test]
+  fn test_tab_size_tracks_column_across_spans() {
+    let mut column = 0;
+
+    assert!(matches!(
+      apply_tab_size("ab", 4, &mut column),
+      Cow::Borrowed("ab")
+    ));
+    assert_eq!(apply_tab_size("\tc", 4, &mut column), "  c");
+    assert_eq!(apply_tab_size("x\t", 0, &mut column), "x");
+  }
 }
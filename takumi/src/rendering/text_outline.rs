@@ -0,0 +1,62 @@
+use std::fmt::Write;
+
+use serde::Serialize;
+use zeno::Command;
+
+/// A shaped glyph as a vector outline, as drawn by [`outline_text`](crate::rendering::outline_text).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GlyphOutline {
+  /// The glyph id within its font.
+  pub glyph_id: u32,
+  /// The transform from glyph space to output pixels, in the same layout as [`MeasuredNode::transform`](crate::rendering::MeasuredNode::transform).
+  pub transform: [f32; 6],
+  /// SVG path data of the glyph in pixels, with the origin on the baseline and y pointing down.
+  pub path: String,
+  /// The fill color as RGBA.
+  pub color: [u8; 4],
+}
+
+pub(crate) fn commands_to_svg_path(commands: &[Command]) -> String {
+  let mut path = String::with_capacity(commands.len() * 16);
+
+  for command in commands {
+    // Writing into a `String` can't fail.
+    let _ = match command {
+      Command::MoveTo(point) => write!(path, "M{} {}", point.x, point.y),
+      Command::LineTo(point) => write!(path, "L{} {}", point.x, point.y),
+      Command::QuadTo(control, point) => {
+        write!(path, "Q{} {} {} {}", control.x, control.y, point.x, point.y)
+      }
+      Command::CurveTo(control1, control2, point) => write!(
+        path,
+        "C{} {} {} {} {} {}",
+        control1.x, control1.y, control2.x, control2.y, point.x, point.y
+      ),
+      Command::Close => write!(path, "Z"),
+    };
+  }
+
+  path
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn serializes_commands_as_svg_path_data() {
+    let commands = [
+      Command::MoveTo((0.0, 0.0).into()),
+      Command::LineTo((10.0, 0.0).into()),
+      Command::QuadTo((10.0, 5.0).into(), (5.0, 10.0).into()),
+      Command::CurveTo((4.0, 10.0).into(), (1.0, 8.5).into(), (0.0, 5.0).into()),
+      Command::Close,
+    ];
+
+    assert_eq!(
+      commands_to_svg_path(&commands),
+      "M0 0L10 0Q10 5 5 10C4 10 1 8.5 0 5Z"
+    );
+  }
+}
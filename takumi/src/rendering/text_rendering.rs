@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use taffy::Point;
+
+use crate::layout::style::Affine;
+
+/// How glyphs are rasterized, set with
+/// [`GlobalContext::set_text_rendering`](crate::GlobalContext::set_text_rendering).
+///
+/// The defaults keep outlines unhinted at their exact positions and blend their coverage linearly.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TextRenderingOptions {
+  /// Whether outlines are hinted, fitting stems to the pixel grid so small text looks sharper.
+  pub hinting: bool,
+  /// How many horizontal positions per device pixel glyphs snap to, `0` keeps them unquantized.
+  ///
+  /// `1` snaps every glyph to whole pixels, `4` to quarter pixels. Only applies to text that is
+  /// translated, not scaled, rotated or skewed.
+  pub subpixel_positions: u8,
+  /// The gamma glyph coverage is corrected with before blending, `1.0` blends it linearly.
+  ///
+  /// Values above `1.0` darken the antialiased edges of thin stems.
+  pub gamma: f32,
+}
+
+impl Default for TextRenderingOptions {
+  fn default() -> Self {
+    Self {
+      hinting: false,
+      subpixel_positions: 0,
+      gamma: 1.0,
+    }
+  }
+}
+
+impl TextRenderingOptions {
+  /// Returns the corrected alpha of every coverage value, `None` when coverage is blended as is.
+  pub(crate) fn coverage_table(self) -> Option<[u8; 256]> {
+    if !self.gamma.is_finite() || self.gamma <= 0.0 || self.gamma == 1.0 {
+      return None;
+    }
+
+    let exponent = self.gamma.recip();
+
+    Some(std::array::from_fn(|coverage| {
+      ((coverage as f32 / 255.0).powf(exponent) * 255.0).round() as u8
+    }))
+  }
+
+  /// Moves the origin of a glyph drawn with `transform` onto the nearest allowed horizontal
+  /// position.
+  pub(crate) fn quantize_glyph_offset(self, transform: Affine, offset: Point<f32>) -> Point<f32> {
+    if self.subpixel_positions == 0 || !transform.only_translation() {
+      return offset;
+    }
+
+    let steps = self.subpixel_positions as f32;
+    let x = transform.decompose_translation().x + offset.x;
+
+    Point {
+      x: offset.x + (x * steps).round() / steps - x,
+      y: offset.y,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn linear_gamma_keeps_coverage() {
+    assert_eq!(TextRenderingOptions::default().coverage_table(), None);
+  }
+
+  #[test]
+  fn gamma_above_one_raises_partial_coverage() {
+    let table = TextRenderingOptions {
+      gamma: 2.2,
+      ..Default::default()
+    }
+    .coverage_table();
+
+    assert!(table.is_some());
+    let Some(table) = table else {
+      return;
+    };
+
+    assert_eq!(table[0], 0);
+    assert_eq!(table[255], 255);
+    assert!(table[128] > 128);
+  }
+
+  #[test]
+  fn quantizes_translated_glyphs_to_subpixel_positions() {
+    let options = TextRenderingOptions {
+      subpixel_positions: 4,
+      ..Default::default()
+    };
+
+    let offset =
+      options.quantize_glyph_offset(Affine::translation(10.1, 0.0), Point { x: 3.3, y: 7.7 });
+
+    assert!((offset.x - 3.4).abs() < 1e-4);
+    assert_eq!(offset.y, 7.7);
+  }
+
+  #[test]
+  fn leaves_scaled_glyphs_unquantized() {
+    let options = TextRenderingOptions {
+      subpixel_positions: 1,
+      ..Default::default()
+    };
+    let offset = Point { x: 3.3, y: 0.0 };
+
+    assert_eq!(
+      options.quantize_glyph_offset(Affine::scale(2.0, 2.0), offset),
+      offset
+    );
+  }
+}
@@ -8,7 +8,7 @@ use crate::{
   error::WebPError,
   rendering::{
     webp::{U24_MAX, has_any_alpha_pixel, strip_alpha_channel},
-    write::{AnimatedWebpOptions, AnimationFrame},
+    write::{AnimatedWebpOptions, AnimationFrame, EncodeOptions},
   },
 };
 
@@ -58,9 +58,9 @@ fn vp8_chunk_tag(buf: &[u8], payload_start: usize) -> Option<[u8; 4]> {
 pub(crate) fn write_webp(
   image: Cow<'_, RgbaImage>,
   destination: &mut impl Write,
-  quality: Option<u8>,
+  options: &EncodeOptions,
 ) -> Result<()> {
-  let quality = quality.unwrap_or(100);
+  let quality = options.quality.unwrap_or(100);
   let mut encoder = WebPEncoder::new(destination);
   let mut params = EncoderParams::default();
   params.use_predictor_transform = quality >= 75;
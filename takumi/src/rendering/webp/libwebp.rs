@@ -11,13 +11,12 @@ use crate::{
 };
 
 use super::{
-  super::write::{AnimatedWebpOptions, AnimationFrame},
+  super::write::{AnimatedWebpOptions, AnimationFrame, EncodeOptions},
   U24_MAX,
 };
 
-fn webp_config(quality: u8, speed: u8) -> Result<WebPConfig> {
+fn webp_config(quality: u8, is_lossless: bool, speed: u8) -> Result<WebPConfig> {
   let requested_quality = quality.clamp(0, 100);
-  let is_lossless = requested_quality == 100;
   let mut config = WebPConfig::new_with_preset(
     WebPPreset::WEBP_PRESET_TEXT,
     if is_lossless {
@@ -273,9 +272,13 @@ fn write_riff_container<W: Write>(
 pub(crate) fn write_webp(
   image: Cow<'_, RgbaImage>,
   destination: &mut impl Write,
-  quality: Option<u8>,
+  options: &EncodeOptions,
 ) -> Result<()> {
-  let config = webp_config(quality.unwrap_or(100), 1)?;
+  let config = webp_config(
+    options.quality.unwrap_or(100),
+    options.webp_lossless(),
+    options.webp_method(),
+  )?;
 
   let mut picture = import_rgba_picture(&image)?;
   let mut writer = MaybeUninit::<WebPMemoryWriter>::uninit();
@@ -383,7 +386,7 @@ pub fn encode_animated_webp<W: Write>(
   }
 
   let speed = options.speed.unwrap_or(1).clamp(0, 6);
-  let config = webp_config(options.quality, speed)?;
+  let config = webp_config(options.quality, options.quality >= 100, speed)?;
   let unique_frames = collect_unique_frames(&frames, frame_width, frame_height)?;
   let frame_data = encode_frames(&unique_frames, &config)?;
 
@@ -2,15 +2,23 @@ use std::{borrow::Cow, io::Write};
 
 use gif::{Encoder as GifEncoder, Frame as GifFrame, Repeat};
 use image::{ExtendedColorType, ImageEncoder, ImageFormat, RgbaImage, codecs::jpeg::JpegEncoder};
-use png::{ColorType, Compression};
+use png::{ColorType, Compression, Filter};
 use serde::Deserialize;
+
+#[cfg(feature = "avif")]
+use image::{
+  ImageError,
+  error::{EncodingError, ImageFormatHint},
+};
+#[cfg(feature = "avif")]
+use ravif::{BitDepth, Encoder as AvifEncoder, Img, RGBA8};
 use typed_builder::TypedBuilder;
 
 /// Encode a sequence of RGBA frames into an animated WebP and write to `destination`.
 pub use super::webp::encode_animated_webp;
 use super::webp::{has_any_alpha_pixel, strip_alpha_channel, write_webp};
 
-use crate::{Result, error::TakumiError};
+use crate::{Result, error::TakumiError, layout::style::Color, rendering::fast_div_255};
 
 /// Output format for rendered images.
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
@@ -26,6 +34,10 @@ pub enum ImageOutputFormat {
 
   /// JPEG image format, lossy and does not support transparency.
   Jpeg,
+
+  /// AVIF image format, compresses better than WebP at the cost of slower encoding.
+  #[cfg(feature = "avif")]
+  Avif,
 }
 
 impl ImageOutputFormat {
@@ -35,6 +47,8 @@ impl ImageOutputFormat {
       ImageOutputFormat::WebP => "image/webp",
       ImageOutputFormat::Png => "image/png",
       ImageOutputFormat::Jpeg => "image/jpeg",
+      #[cfg(feature = "avif")]
+      ImageOutputFormat::Avif => "image/avif",
     }
   }
 }
@@ -45,6 +59,8 @@ impl From<ImageOutputFormat> for ImageFormat {
       ImageOutputFormat::WebP => Self::WebP,
       ImageOutputFormat::Png => Self::Png,
       ImageOutputFormat::Jpeg => Self::Jpeg,
+      #[cfg(feature = "avif")]
+      ImageOutputFormat::Avif => Self::Avif,
     }
   }
 }
@@ -115,6 +131,11 @@ pub struct AnimatedPngOptions {
 pub struct AnimatedGifOptions {
   /// Number of times to loop; `None` means infinite loop.
   pub loop_count: Option<u16>,
+  /// Color each frame is composited onto before palette quantization.
+  ///
+  /// GIF pixels are either fully transparent or fully opaque, so without a matte
+  /// semi-transparent edges such as anti-aliasing and shadows are thresholded.
+  pub matte: Option<Color>,
 }
 
 fn duration_ms_to_gif_delay(duration_ms: u32) -> u16 {
@@ -125,12 +146,272 @@ fn duration_ms_to_gif_delay(duration_ms: u32) -> u16 {
   }
 }
 
+/// Composites straight-alpha RGBA `pixels` onto `matte` using source-over blending.
+fn composite_onto_matte(pixels: &mut [u8], matte: Color) {
+  let [matte_r, matte_g, matte_b, matte_a] = matte.0;
+
+  for pixel in pixels.chunks_exact_mut(4) {
+    let alpha = pixel[3] as u32;
+    if alpha == 255 {
+      continue;
+    }
+
+    // Matte coverage left uncovered by the source pixel, in 0..=255.
+    let backdrop = fast_div_255(matte_a as u32 * (255 - alpha)) as u32;
+    let out_alpha = alpha + backdrop;
+
+    if out_alpha == 0 {
+      pixel.fill(0);
+      continue;
+    }
+
+    for (channel, matte_channel) in pixel[..3].iter_mut().zip([matte_r, matte_g, matte_b]) {
+      let blended = *channel as u32 * alpha + matte_channel as u32 * backdrop;
+      *channel = ((blended + out_alpha / 2) / out_alpha) as u8;
+    }
+
+    pixel[3] = out_alpha as u8;
+  }
+}
+
+/// How much encoding time to trade for smaller files.
+///
+/// Presets only pick the values of the [`EncodeOptions`] knobs that aren't set explicitly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum EncodePreset {
+  /// Encode as fast as possible at the cost of larger files.
+  Fast,
+  /// Fast enough to encode on request while keeping files reasonably small.
+  #[default]
+  Balanced,
+  /// Spend much more time to produce smaller files, for outputs that are cached.
+  Small,
+}
+
+/// PNG deflate compression level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum PngCompression {
+  /// Store the pixels without compression.
+  None,
+  /// Extremely fast but light compression.
+  Fastest,
+  /// Fast compression with a decent ratio.
+  Fast,
+  /// Balances encoding speed and compression ratio.
+  Balanced,
+  /// Spend much more time for a slightly smaller file.
+  High,
+}
+
+impl From<PngCompression> for Compression {
+  fn from(compression: PngCompression) -> Self {
+    match compression {
+      PngCompression::None => Self::NoCompression,
+      PngCompression::Fastest => Self::Fastest,
+      PngCompression::Fast => Self::Fast,
+      PngCompression::Balanced => Self::Balanced,
+      PngCompression::High => Self::High,
+    }
+  }
+}
+
+/// PNG row filter strategy, applied before deflate compression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum PngFilter {
+  /// Leave rows unfiltered.
+  None,
+  /// Predict each pixel from its left neighbour.
+  Sub,
+  /// Predict each pixel from the pixel above.
+  Up,
+  /// Predict each pixel from the average of its left and upper neighbours.
+  Avg,
+  /// Predict each pixel with the Paeth predictor.
+  Paeth,
+  /// Pick the filter per row with a fast heuristic.
+  Adaptive,
+  /// Pick the filter per row that minimizes entropy, slower but usually smaller.
+  MinEntropy,
+}
+
+impl From<PngFilter> for Filter {
+  fn from(filter: PngFilter) -> Self {
+    match filter {
+      PngFilter::None => Self::NoFilter,
+      PngFilter::Sub => Self::Sub,
+      PngFilter::Up => Self::Up,
+      PngFilter::Avg => Self::Avg,
+      PngFilter::Paeth => Self::Paeth,
+      PngFilter::Adaptive => Self::Adaptive,
+      PngFilter::MinEntropy => Self::MinEntropy,
+    }
+  }
+}
+
+/// Bit depth of AVIF output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AvifBitDepth {
+  /// 8 bits per channel.
+  #[default]
+  Eight,
+  /// 10 bits per channel, which reduces banding in gradients at a small size cost.
+  Ten,
+}
+
+/// WebP specific encoding options.
+#[derive(Debug, Clone, Copy, Default, TypedBuilder)]
+#[builder(field_defaults(default))]
+#[non_exhaustive]
+pub struct WebpEncodeOptions {
+  /// Whether to encode losslessly. Defaults to lossless when the quality is `100` or unset.
+  ///
+  /// Only effective on native targets (libwebp). WASM always encodes losslessly.
+  pub lossless: Option<bool>,
+  /// Compression method in range `0..=6`; `0` is fastest, `6` produces the smallest files.
+  ///
+  /// Only effective on native targets (libwebp). Ignored on WASM.
+  pub method: Option<u8>,
+}
+
+/// AVIF specific encoding options.
+#[derive(Debug, Clone, Copy, Default, TypedBuilder)]
+#[builder(field_defaults(default))]
+#[non_exhaustive]
+pub struct AvifEncodeOptions {
+  /// Encoder speed in range `1..=10`; `1` is slowest with the smallest files, `10` is fastest.
+  pub speed: Option<u8>,
+  /// Bit depth per channel.
+  pub bit_depth: AvifBitDepth,
+}
+
+/// PNG specific encoding options.
+#[derive(Debug, Clone, Copy, Default, TypedBuilder)]
+#[builder(field_defaults(default))]
+#[non_exhaustive]
+pub struct PngEncodeOptions {
+  /// Deflate compression level.
+  pub compression: Option<PngCompression>,
+  /// Row filter strategy. Picked by the compression level when unset.
+  pub filter: Option<PngFilter>,
+}
+
+/// Encoding options for single images, see [`write_image_with_options`].
+#[derive(Debug, Clone, Copy, Default, TypedBuilder)]
+#[builder(field_defaults(default))]
+#[non_exhaustive]
+pub struct EncodeOptions {
+  /// Quality in range `0..=100` for JPEG, WebP and AVIF. PNG uses faster compression below `90`
+  /// unless a compression level is set.
+  pub quality: Option<u8>,
+  /// The speed and size tradeoff for the knobs below that aren't set.
+  pub preset: EncodePreset,
+  /// WebP specific options.
+  pub webp: WebpEncodeOptions,
+  /// AVIF specific options.
+  pub avif: AvifEncodeOptions,
+  /// PNG specific options.
+  pub png: PngEncodeOptions,
+}
+
+impl From<Option<u8>> for EncodeOptions {
+  fn from(quality: Option<u8>) -> Self {
+    Self::builder().quality(quality).build()
+  }
+}
+
+impl EncodeOptions {
+  #[cfg(not(target_arch = "wasm32"))]
+  pub(crate) fn webp_lossless(&self) -> bool {
+    self
+      .webp
+      .lossless
+      .unwrap_or_else(|| self.quality.unwrap_or(100) >= 100)
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  pub(crate) fn webp_method(&self) -> u8 {
+    self.webp.method.unwrap_or(match self.preset {
+      EncodePreset::Fast => 0,
+      EncodePreset::Balanced => 1,
+      EncodePreset::Small => 6,
+    })
+  }
+
+  #[cfg(feature = "avif")]
+  fn avif_speed(&self) -> u8 {
+    self.avif.speed.unwrap_or(match self.preset {
+      EncodePreset::Fast => 10,
+      EncodePreset::Balanced => AVIF_SPEED,
+      EncodePreset::Small => 4,
+    })
+  }
+
+  fn png_compression(&self) -> Compression {
+    if let Some(compression) = self.png.compression {
+      return compression.into();
+    }
+
+    match self.preset {
+      EncodePreset::Fast => Compression::Fastest,
+      // PNG is lossless, so default to the better-compression path unless the
+      // caller explicitly requests lower effort via `quality`.
+      EncodePreset::Balanced if self.quality.unwrap_or(100) >= 90 => Compression::Balanced,
+      EncodePreset::Balanced => Compression::Fast,
+      EncodePreset::Small => Compression::High,
+    }
+  }
+}
+
+/// Checks that a raw RGBA buffer of `len` bytes holds exactly `width * height` pixels.
+pub fn check_rgba_buffer(width: u32, height: u32, len: usize) -> Result<()> {
+  let expected = (width as usize)
+    .checked_mul(height as usize)
+    .and_then(|pixels| pixels.checked_mul(4));
+
+  if expected != Some(len) {
+    return Err(TakumiError::InvalidPixelBuffer { width, height, len });
+  }
+
+  Ok(())
+}
+
+/// Wraps raw RGBA pixels in an image, requiring exactly `width * height * 4` bytes.
+pub fn rgba_image_from_raw(width: u32, height: u32, pixels: Vec<u8>) -> Result<RgbaImage> {
+  let len = pixels.len();
+  check_rgba_buffer(width, height, len)?;
+
+  RgbaImage::from_raw(width, height, pixels).ok_or(TakumiError::InvalidPixelBuffer {
+    width,
+    height,
+    len,
+  })
+}
+
 /// Writes a single rendered image to `destination` using `format`.
+///
+/// Use [`write_image_with_options`] to tune the encoders beyond `quality`.
 pub fn write_image<'a, T: Write>(
   image: Cow<'a, RgbaImage>,
   destination: &mut T,
   format: ImageOutputFormat,
   quality: Option<u8>,
+) -> Result<()> {
+  write_image_with_options(image, destination, format, quality.into())
+}
+
+/// Writes a single rendered image to `destination` using `format` and encoder `options`.
+pub fn write_image_with_options<'a, T: Write>(
+  image: Cow<'a, RgbaImage>,
+  destination: &mut T,
+  format: ImageOutputFormat,
+  options: EncodeOptions,
 ) -> Result<()> {
   match format {
     ImageOutputFormat::Jpeg => {
@@ -138,13 +419,12 @@ pub fn write_image<'a, T: Write>(
       let height = image.height();
       let rgb = strip_alpha_channel(image);
 
-      let encoder = JpegEncoder::new_with_quality(destination, quality.unwrap_or(75));
+      let encoder = JpegEncoder::new_with_quality(destination, options.quality.unwrap_or(75));
       encoder.write_image(&rgb, width, height, ExtendedColorType::Rgb8)?;
     }
     ImageOutputFormat::Png => {
-      let mut encoder = png::Encoder::new(destination, image.width(), image.height());
-
       let has_alpha = has_any_alpha_pixel(&image);
+      let encoder = png_encoder(destination, &image, has_alpha, &options);
 
       let image_data = if has_alpha {
         Cow::Borrowed(image.as_raw())
@@ -152,33 +432,158 @@ pub fn write_image<'a, T: Write>(
         Cow::Owned(strip_alpha_channel(image))
       };
 
-      encoder.set_color(if has_alpha {
-        ColorType::Rgba
-      } else {
-        ColorType::Rgb
-      });
+      let mut writer = encoder.write_header()?;
+      writer.write_image_data(&image_data)?;
+      writer.finish()?;
+    }
+    ImageOutputFormat::WebP => {
+      write_webp(image, destination, &options)?;
+    }
+    #[cfg(feature = "avif")]
+    ImageOutputFormat::Avif => {
+      write_avif(&image, destination, &options)?;
+    }
+  }
 
-      // PNG is lossless, so default to the better-compression path unless the
-      // caller explicitly requests lower effort via `quality`.
-      let quality = quality.unwrap_or(100);
-      if quality >= 90 {
-        encoder.set_compression(Compression::Balanced);
+  Ok(())
+}
+
+/// Writes a single rendered image to `destination` using `format`, encoding it incrementally.
+///
+/// Unlike [`write_image`], PNG rows and JPEG blocks are encoded straight from `image` and
+/// flushed to `destination` as they are produced, so no intermediate copy of the raster or
+/// of the encoded output is kept. WebP still encodes the whole image before writing.
+pub fn write_image_streaming<W: Write>(
+  image: &RgbaImage,
+  destination: W,
+  format: ImageOutputFormat,
+  quality: Option<u8>,
+) -> Result<()> {
+  write_image_streaming_with_options(image, destination, format, quality.into())
+}
+
+/// Writes a single rendered image incrementally like [`write_image_streaming`], using encoder
+/// `options`.
+pub fn write_image_streaming_with_options<W: Write>(
+  image: &RgbaImage,
+  destination: W,
+  format: ImageOutputFormat,
+  options: EncodeOptions,
+) -> Result<()> {
+  match format {
+    ImageOutputFormat::Jpeg => {
+      let mut encoder = JpegEncoder::new_with_quality(destination, options.quality.unwrap_or(75));
+      encoder.encode_image(image)?;
+    }
+    ImageOutputFormat::Png => {
+      let has_alpha = has_any_alpha_pixel(image);
+      let encoder = png_encoder(destination, image, has_alpha, &options);
+
+      let mut writer = encoder.write_header()?;
+      let mut stream = writer.stream_writer()?;
+
+      if has_alpha {
+        for row in image.as_raw().chunks_exact(image.width() as usize * 4) {
+          stream.write_all(row)?;
+        }
       } else {
-        encoder.set_compression(Compression::Fast);
+        let mut rgb_row = Vec::with_capacity(image.width() as usize * 3);
+
+        for row in image.as_raw().chunks_exact(image.width() as usize * 4) {
+          rgb_row.clear();
+          for pixel in row.chunks_exact(4) {
+            rgb_row.extend_from_slice(&pixel[..3]);
+          }
+
+          stream.write_all(&rgb_row)?;
+        }
       }
 
-      let mut writer = encoder.write_header()?;
-      writer.write_image_data(&image_data)?;
+      stream.finish()?;
       writer.finish()?;
     }
     ImageOutputFormat::WebP => {
-      write_webp(image, destination, quality)?;
+      let mut destination = destination;
+      write_webp(Cow::Borrowed(image), &mut destination, &options)?;
+    }
+    #[cfg(feature = "avif")]
+    ImageOutputFormat::Avif => {
+      write_avif(image, destination, &options)?;
     }
   }
 
   Ok(())
 }
 
+/// rav1e speed preset used for AVIF, from `1` (slowest) to `10` (fastest).
+///
+/// Lower presets take several seconds even for social cards, which is too slow to render on
+/// request, while the size gained over this preset is small.
+#[cfg(feature = "avif")]
+const AVIF_SPEED: u8 = 8;
+
+#[cfg(feature = "avif")]
+fn write_avif<W: Write>(
+  image: &RgbaImage,
+  mut destination: W,
+  options: &EncodeOptions,
+) -> Result<()> {
+  let quality = f32::from(options.quality.unwrap_or(75).min(100));
+  let encoder = AvifEncoder::new()
+    .with_quality(quality)
+    .with_alpha_quality(quality)
+    .with_speed(options.avif_speed().clamp(1, 10))
+    .with_bit_depth(match options.avif.bit_depth {
+      AvifBitDepth::Eight => BitDepth::Eight,
+      AvifBitDepth::Ten => BitDepth::Ten,
+    });
+
+  let pixels: Vec<RGBA8> = image
+    .pixels()
+    .map(|pixel| {
+      let [r, g, b, a] = pixel.0;
+      RGBA8::new(r, g, b, a)
+    })
+    .collect();
+  let encoded = encoder
+    .encode_rgba(Img::new(
+      pixels.as_slice(),
+      image.width() as usize,
+      image.height() as usize,
+    ))
+    .map_err(|error| {
+      ImageError::Encoding(EncodingError::new(
+        ImageFormatHint::Exact(ImageFormat::Avif),
+        error,
+      ))
+    })?;
+
+  destination.write_all(&encoded.avif_file)?;
+  Ok(())
+}
+
+fn png_encoder<W: Write>(
+  destination: W,
+  image: &RgbaImage,
+  has_alpha: bool,
+  options: &EncodeOptions,
+) -> png::Encoder<'static, W> {
+  let mut encoder = png::Encoder::new(destination, image.width(), image.height());
+
+  encoder.set_color(if has_alpha {
+    ColorType::Rgba
+  } else {
+    ColorType::Rgb
+  });
+
+  encoder.set_compression(options.png_compression());
+  if let Some(filter) = options.png.filter {
+    encoder.set_filter(filter.into());
+  }
+
+  encoder
+}
+
 /// Encode a sequence of RGBA frames into an animated GIF and write to `destination`.
 pub fn encode_animated_gif<W: Write>(
   frames: Cow<'_, [AnimationFrame]>,
@@ -213,6 +618,10 @@ pub fn encode_animated_gif<W: Write>(
 
   for frame in frames.into_owned().into_iter() {
     let mut pixels = frame.image.into_raw();
+    if let Some(matte) = options.matte {
+      composite_onto_matte(&mut pixels, matte);
+    }
+
     let mut gif_frame = GifFrame::from_rgba_speed(width, height, &mut pixels, 28);
     gif_frame.delay = duration_ms_to_gif_delay(frame.duration_ms);
     encoder.write_frame(&gif_frame)?;
@@ -275,10 +684,31 @@ mod tests {
   use libwebp_sys::*;
 
   use super::{
-    AnimatedGifOptions, AnimatedPngOptions, AnimatedWebpOptions, AnimationFrame, ImageOutputFormat,
-    encode_animated_gif, encode_animated_png, encode_animated_webp, write_image,
+    AnimatedGifOptions, AnimatedPngOptions, AnimatedWebpOptions, AnimationFrame, EncodeOptions,
+    EncodePreset, ImageOutputFormat, PngCompression, PngEncodeOptions, PngFilter,
+    WebpEncodeOptions, check_rgba_buffer, encode_animated_gif, encode_animated_png,
+    encode_animated_webp, rgba_image_from_raw, write_image, write_image_streaming,
+    write_image_with_options,
+  };
+  use crate::{
+    layout::style::Color,
+    rendering::{DitheringAlgorithm, apply_dithering},
   };
-  use crate::rendering::{DitheringAlgorithm, apply_dithering};
+
+  #[test]
+  fn rgba_buffers_must_hold_exactly_width_by_height_pixels() {
+    assert!(check_rgba_buffer(2, 3, 24).is_ok());
+    assert!(check_rgba_buffer(2, 3, 23).is_err());
+    assert!(check_rgba_buffer(2, 3, 28).is_err());
+    assert!(check_rgba_buffer(u32::MAX, u32::MAX, 0).is_err());
+    assert!(rgba_image_from_raw(2, 3, vec![0; 28]).is_err());
+    assert_eq!(
+      rgba_image_from_raw(2, 3, vec![0; 24])
+        .map(|image| image.dimensions())
+        .ok(),
+      Some((2, 3))
+    );
+  }
 
   #[test]
   fn encode_animated_gif_writes_valid_animation_and_delays() {
@@ -309,6 +739,7 @@ mod tests {
       &mut bytes,
       AnimatedGifOptions {
         loop_count: Some(7),
+        matte: None,
       },
     );
     assert!(encode_result.is_ok(), "failed to encode animated gif");
@@ -354,6 +785,48 @@ mod tests {
     );
   }
 
+  #[test]
+  fn encode_animated_gif_composites_onto_matte() {
+    let frame = AnimationFrame::new(
+      RgbaImage::from_fn(2, 2, |x, _| {
+        if x == 0 {
+          image::Rgba([255, 0, 0, 128])
+        } else {
+          image::Rgba([0, 0, 0, 0])
+        }
+      }),
+      10,
+    );
+
+    let mut bytes = Vec::new();
+    let encode_result = encode_animated_gif(
+      Cow::Owned(vec![frame]),
+      &mut bytes,
+      AnimatedGifOptions {
+        loop_count: None,
+        matte: Some(Color::white()),
+      },
+    );
+    assert!(encode_result.is_ok(), "failed to encode animated gif");
+
+    let mut decoder_options = DecodeOptions::new();
+    decoder_options.set_color_output(ColorOutput::RGBA);
+    let mut decoder = decoder_options
+      .read_info(Cursor::new(&bytes))
+      .unwrap_or_else(|_| unreachable!());
+    let frame = decoder
+      .read_next_frame()
+      .unwrap_or_else(|_| unreachable!())
+      .unwrap_or_else(|| unreachable!());
+
+    assert_eq!(
+      frame.buffer.as_ref(),
+      [
+        255, 127, 127, 255, 255, 255, 255, 255, 255, 127, 127, 255, 255, 255, 255, 255
+      ]
+    );
+  }
+
   #[test]
   fn encode_animated_gif_rejects_mismatched_frame_dimensions() {
     let frame_a = AnimationFrame::new(
@@ -469,6 +942,164 @@ mod tests {
     assert_ne!(encoded_none, encoded_dithered);
   }
 
+  #[test]
+  fn write_image_streaming_png_round_trips_pixels() {
+    let opaque = RgbaImage::from_fn(5, 3, |x, y| {
+      image::Rgba([x as u8 * 40, y as u8 * 80, 7, 255])
+    });
+    let translucent = RgbaImage::from_fn(5, 3, |x, y| {
+      image::Rgba([x as u8 * 40, y as u8 * 80, 7, 128])
+    });
+
+    for source in [opaque, translucent] {
+      let mut encoded = Vec::new();
+      let result = write_image_streaming(&source, &mut encoded, ImageOutputFormat::Png, None);
+      assert!(result.is_ok(), "failed to stream png");
+
+      let decoded = image::load_from_memory(&encoded).map(|image| image.to_rgba8());
+      assert!(decoded.is_ok(), "failed to decode streamed png");
+      assert_eq!(decoded.unwrap_or_else(|_| unreachable!()), source);
+    }
+  }
+
+  #[test]
+  fn write_image_with_options_applies_png_knobs() {
+    let source = RgbaImage::from_fn(64, 64, |x, y| {
+      image::Rgba([x as u8 * 4, y as u8 * 4, (x ^ y) as u8, 255])
+    });
+
+    let encode = |options: EncodeOptions| {
+      let mut encoded = Vec::new();
+      let result = write_image_with_options(
+        Cow::Borrowed(&source),
+        &mut encoded,
+        ImageOutputFormat::Png,
+        options,
+      );
+      assert!(result.is_ok(), "failed to encode png");
+
+      let decoded = image::load_from_memory(&encoded).map(|image| image.to_rgba8());
+      assert_eq!(decoded.ok().as_ref(), Some(&source));
+      encoded.len()
+    };
+
+    let stored = encode(
+      EncodeOptions::builder()
+        .png(
+          PngEncodeOptions::builder()
+            .compression(Some(PngCompression::None))
+            .filter(Some(PngFilter::None))
+            .build(),
+        )
+        .build(),
+    );
+    let fast = encode(EncodeOptions::builder().preset(EncodePreset::Fast).build());
+    let small = encode(EncodeOptions::builder().preset(EncodePreset::Small).build());
+
+    assert!(small <= fast);
+    assert!(fast < stored);
+  }
+
+  #[test]
+  fn write_image_with_options_toggles_webp_lossless() {
+    let source = RgbaImage::from_fn(16, 16, |x, y| {
+      image::Rgba([x as u8 * 16, y as u8 * 16, 64, 255])
+    });
+
+    let chunk_tag = |webp: WebpEncodeOptions| {
+      let mut encoded = Vec::new();
+      let result = write_image_with_options(
+        Cow::Borrowed(&source),
+        &mut encoded,
+        ImageOutputFormat::WebP,
+        EncodeOptions::builder().webp(webp).build(),
+      );
+      assert!(result.is_ok(), "failed to encode webp");
+      encoded.get(12..16).map(<[u8]>::to_vec)
+    };
+
+    assert_eq!(
+      chunk_tag(WebpEncodeOptions::default()),
+      Some(b"VP8L".to_vec())
+    );
+    assert_eq!(
+      chunk_tag(
+        WebpEncodeOptions::builder()
+          .lossless(Some(false))
+          .method(Some(6))
+          .build()
+      ),
+      Some(b"VP8 ".to_vec())
+    );
+  }
+
+  #[cfg(feature = "avif")]
+  #[test]
+  fn write_image_encodes_ten_bit_avif() {
+    use super::{AvifBitDepth, AvifEncodeOptions};
+
+    let source = RgbaImage::from_pixel(12, 8, image::Rgba([20, 120, 220, 255]));
+    let mut encoded = Vec::new();
+    let result = write_image_with_options(
+      Cow::Borrowed(&source),
+      &mut encoded,
+      ImageOutputFormat::Avif,
+      EncodeOptions::builder()
+        .preset(EncodePreset::Fast)
+        .avif(
+          AvifEncodeOptions::builder()
+            .bit_depth(AvifBitDepth::Ten)
+            .build(),
+        )
+        .build(),
+    );
+
+    assert!(result.is_ok(), "failed to encode avif");
+    assert_eq!(encoded.get(4..12), Some(&b"ftypavif"[..]));
+  }
+
+  #[cfg(feature = "avif")]
+  #[test]
+  fn write_image_encodes_avif() {
+    let opaque = RgbaImage::from_pixel(12, 8, image::Rgba([20, 120, 220, 255]));
+    let translucent = RgbaImage::from_pixel(12, 8, image::Rgba([20, 120, 220, 128]));
+
+    for source in [opaque, translucent] {
+      let mut encoded = Vec::new();
+      let result = write_image(
+        Cow::Borrowed(&source),
+        &mut encoded,
+        ImageOutputFormat::Avif,
+        None,
+      );
+      assert!(result.is_ok(), "failed to encode avif");
+      assert_eq!(encoded.get(4..12), Some(&b"ftypavif"[..]));
+    }
+  }
+
+  #[test]
+  fn write_image_streaming_jpeg_matches_buffered_output() {
+    let source = RgbaImage::from_fn(17, 9, |x, y| {
+      image::Rgba([x as u8 * 15, y as u8 * 28, 90, 255])
+    });
+
+    let mut buffered = Vec::new();
+    let buffered_result = write_image(
+      Cow::Borrowed(&source),
+      &mut buffered,
+      ImageOutputFormat::Jpeg,
+      Some(80),
+    );
+    assert!(buffered_result.is_ok(), "failed to encode jpeg");
+
+    let mut streamed = Vec::new();
+    let streamed_result =
+      write_image_streaming(&source, &mut streamed, ImageOutputFormat::Jpeg, Some(80));
+    assert!(streamed_result.is_ok(), "failed to stream jpeg");
+
+    assert_eq!(streamed, buffered);
+  }
+
   #[test]
   fn encode_animated_webp_respects_blend_dispose_and_loop_count() {
     let frame_a = AnimationFrame::new(
@@ -0,0 +1,100 @@
+use std::{
+  collections::HashMap,
+  fmt::{self, Debug},
+  sync::{Arc, Mutex},
+};
+
+use crate::resources::image::ImageSource;
+
+/// Provides pictures for emoji clusters in place of font glyphs.
+///
+/// Register an implementation with [`EmojiStore::set_source`] on the
+/// [`GlobalContext`](crate::GlobalContext) to render emoji from a picture set such as Twemoji
+/// without bundling a color font.
+pub trait EmojiSource: Send + Sync {
+  /// Returns the encoded image (PNG, JPEG, WebP, GIF or SVG) for an emoji grapheme cluster,
+  /// or `None` to draw the glyph from the font instead.
+  fn load(&self, emoji: &str) -> Option<Vec<u8>>;
+}
+
+impl<F> EmojiSource for F
+where
+  F: Fn(&str) -> Option<Vec<u8>> + Send + Sync,
+{
+  fn load(&self, emoji: &str) -> Option<Vec<u8>> {
+    self(emoji)
+  }
+}
+
+/// Decoded pictures keyed by emoji cluster, `None` for clusters the source couldn't provide.
+type EmojiCache = HashMap<Box<str>, Option<Arc<ImageSource>>>;
+
+/// Holds the registered [`EmojiSource`] and the pictures decoded from it.
+#[derive(Default)]
+pub struct EmojiStore {
+  source: Option<Arc<dyn EmojiSource>>,
+  cache: Arc<Mutex<EmojiCache>>,
+}
+
+impl Debug for EmojiStore {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("EmojiStore")
+      .field("has_source", &self.source.is_some())
+      .finish_non_exhaustive()
+  }
+}
+
+impl EmojiStore {
+  /// Registers the emoji source, returning the source it replaced.
+  pub fn set_source(&mut self, source: impl EmojiSource + 'static) -> Option<Arc<dyn EmojiSource>> {
+    self.clear_cache();
+    self.source.replace(Arc::new(source))
+  }
+
+  /// Removes the emoji source, returning it if present.
+  pub fn remove_source(&mut self) -> Option<Arc<dyn EmojiSource>> {
+    self.clear_cache();
+    self.source.take()
+  }
+
+  /// Returns a store with the same source that shares the decoded pictures with this one.
+  pub(crate) fn share(&self) -> Self {
+    Self {
+      source: self.source.clone(),
+      cache: Arc::clone(&self.cache),
+    }
+  }
+
+  /// Returns true if an emoji source is registered.
+  pub fn has_source(&self) -> bool {
+    self.source.is_some()
+  }
+
+  /// Removes all decoded emoji pictures.
+  pub fn clear_cache(&self) {
+    if let Ok(mut cache) = self.cache.lock() {
+      cache.clear();
+    }
+  }
+
+  /// Returns the decoded picture for an emoji cluster, loading it from the source once.
+  ///
+  /// Clusters the source can't provide or decode are remembered as missing.
+  pub(crate) fn get(&self, emoji: &str) -> Option<Arc<ImageSource>> {
+    let source = self.source.as_ref()?;
+
+    if let Some(cached) = self.cache.lock().ok()?.get(emoji) {
+      return cached.clone();
+    }
+
+    let image = source
+      .load(emoji)
+      .and_then(|bytes| ImageSource::from_bytes(&bytes).ok());
+
+    if let Ok(mut cache) = self.cache.lock() {
+      cache.insert(emoji.into(), image.clone());
+    }
+
+    image
+  }
+}
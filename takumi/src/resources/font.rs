@@ -1,27 +1,34 @@
 use std::{
   borrow::Cow,
   collections::{HashMap, HashSet},
-  hash::Hash,
+  fmt::{self, Display, Formatter},
+  hash::{Hash, Hasher},
   iter::once,
   ops::{Deref, DerefMut},
-  sync::Arc,
+  sync::{Arc, Mutex},
 };
 
 use parley::{
   FontStyle, GenericFamily, GlyphRun, LayoutContext, TextStyle, TreeBuilder,
-  fontique::{Blob, Collection, CollectionOptions, FallbackKey, FontInfoOverride, Script},
+  fontique::{
+    Blob, Collection, CollectionOptions, FallbackKey, FamilyId, FontInfoOverride, Script,
+    SourceKind,
+  },
 };
+use serde::{Deserialize, Serialize};
 use swash::{
-  FontRef,
+  FontDataRef, FontRef, Tag,
   scale::{ScaleContext, StrikeWith, image::Image, outline::Outline},
+  tag_from_bytes,
 };
 use thiserror::Error;
-use xxhash_rust::xxh3::xxh3_64;
+use xxhash_rust::xxh3::{Xxh3, xxh3_64};
 use zeno::{Angle as ZenoAngle, Transform as ZenoTransform};
 
 use crate::{
-  Xxh3HashSet,
+  Xxh3HashMap,
   layout::inline::{InlineBrush, InlineLayout},
+  rendering::{GlyphCache, GlyphRunKey},
 };
 
 /// Represents a resolved glyph that can be either a bitmap image or an outline
@@ -44,27 +51,62 @@ pub(crate) fn synthesis_embolden_strength(font_size: f32) -> f32 {
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum FontError {
-  /// Error occurred during WOFF conversion
+  /// A WOFF or WOFF2 font failed to decompress
   #[cfg(any(feature = "woff", feature = "woff2"))]
-  #[error("Error occurred during WOFF conversion.")]
-  Woff(wuff::WuffErr),
-  /// Unsupported Font Format
-  #[error("Unsupported font format")]
+  #[error("Failed to decompress {format} font: {reason}")]
+  Woff {
+    /// The detected format
+    format: FontFormat,
+    /// Why decompression failed
+    reason: wuff::WuffErr,
+  },
+  /// The data doesn't start with the signature of any supported font format
+  #[error(
+    "Unsupported font format, expected TrueType, OpenType, TrueType Collection, WOFF or WOFF2"
+  )]
   UnsupportedFormat,
+  /// The format was recognized, but support for it is not compiled in
+  #[error("{0} fonts require the `{feature}` feature", feature = .0.feature())]
+  FormatDisabled(FontFormat),
+  /// The data has the signature of a font format but no font could be read from it
+  #[error("Failed to parse {0} font data")]
+  InvalidFont(FontFormat),
   /// Font index is invalid
   #[error("Font index is invalid")]
   InvalidFontIndex,
+  /// The face picked with [`FontResource::face_index`] is past the end of the collection
+  #[error("Face index {index} is out of range for a font with {count} faces")]
+  FaceIndexOutOfRange {
+    /// The requested face
+    index: u32,
+    /// Number of faces in the font data
+    count: u32,
+  },
+}
+
+/// What happens to text that no loaded font or fallback can draw.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum MissingFontPolicy {
+  /// Draw whatever the font has for missing glyphs, usually a `.notdef` box or nothing.
+  #[default]
+  Notdef,
+  /// Fail the render with [`Error::MissingGlyphs`](crate::Error::MissingGlyphs).
+  Error,
+  /// Leave the space of missing characters empty.
+  Skip,
+  /// Draw a hollow box in the text color for every missing character.
+  Replace,
 }
 
 /// Supported font formats for loading and processing
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub enum FontFormat {
-  #[cfg(feature = "woff")]
-  /// Web Open Font Format (WOFF) - compressed web font format
+  /// Web Open Font Format (WOFF) - compressed web font format, needs the `woff` feature
   Woff,
-  #[cfg(feature = "woff2")]
-  /// Web Open Font Format 2 (WOFF2) - improved compression web font format
+  /// Web Open Font Format 2 (WOFF2) - improved compression web font format, needs the `woff2` feature
   Woff2,
   /// TrueType Font format - standard desktop font format
   Ttf,
@@ -74,6 +116,31 @@ pub enum FontFormat {
   Ttc,
 }
 
+impl FontFormat {
+  /// Cargo feature of takumi that adds support for the format.
+  fn feature(self) -> &'static str {
+    match self {
+      Self::Woff => "woff",
+      Self::Woff2 => "woff2",
+      Self::Ttf | Self::Otf | Self::Ttc => "default",
+    }
+  }
+}
+
+impl Display for FontFormat {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Self::Woff => "WOFF",
+      Self::Woff2 => "WOFF2",
+      Self::Ttf => "TrueType",
+      Self::Otf => "OpenType",
+      Self::Ttc => "TrueType Collection",
+    })
+  }
+}
+
+const HEAD_TABLE: Tag = tag_from_bytes(b"head");
+
 fn load_font(source: Cow<'_, [u8]>, format_hint: Option<FontFormat>) -> Result<Vec<u8>, FontError> {
   let format = if let Some(format) = format_hint {
     format
@@ -81,19 +148,29 @@ fn load_font(source: Cow<'_, [u8]>, format_hint: Option<FontFormat>) -> Result<V
     guess_font_format(&source)?
   };
 
-  match format {
-    FontFormat::Ttf | FontFormat::Otf | FontFormat::Ttc => Ok(source.into_owned()),
+  let font = match format {
+    FontFormat::Ttf | FontFormat::Otf | FontFormat::Ttc => source.into_owned(),
     #[cfg(feature = "woff2")]
     FontFormat::Woff2 => {
-      let ttf = wuff::decompress_woff2(&source).map_err(FontError::Woff)?;
-      Ok(ttf)
+      wuff::decompress_woff2(&source).map_err(|reason| FontError::Woff { format, reason })?
     }
     #[cfg(feature = "woff")]
     FontFormat::Woff => {
-      let ttf = wuff::decompress_woff1(&source).map_err(FontError::Woff)?;
-      Ok(ttf)
+      wuff::decompress_woff1(&source).map_err(|reason| FontError::Woff { format, reason })?
     }
+    #[allow(unreachable_patterns)]
+    _ => return Err(FontError::FormatDisabled(format)),
+  };
+
+  // Every face needs at least the `head` table to be usable.
+  let faces = FontDataRef::new(&font).map_or(0, |data| data.len());
+  let is_readable =
+    |index| FontRef::from_index(&font, index).is_some_and(|face| face.table(HEAD_TABLE).is_some());
+  if faces == 0 || !(0..faces).all(is_readable) {
+    return Err(FontError::InvalidFont(format));
   }
+
+  Ok(font)
 }
 
 fn guess_font_format(source: &[u8]) -> Result<FontFormat, FontError> {
@@ -102,11 +179,9 @@ fn guess_font_format(source: &[u8]) -> Result<FontFormat, FontError> {
   }
 
   match &source[0..4] {
-    #[cfg(feature = "woff2")]
     b"wOF2" => Ok(FontFormat::Woff2),
-    #[cfg(feature = "woff")]
     b"wOFF" => Ok(FontFormat::Woff),
-    [0x00, 0x01, 0x00, 0x00] => Ok(FontFormat::Ttf),
+    [0x00, 0x01, 0x00, 0x00] | b"true" => Ok(FontFormat::Ttf),
     b"OTTO" => Ok(FontFormat::Otf),
     b"ttcf" => Ok(FontFormat::Ttc),
     _ => Err(FontError::UnsupportedFormat),
@@ -122,6 +197,7 @@ pub(crate) struct FontCacheKey {
   width: Option<u32>,
   axes: Option<Box<[(u32, u32)]>>,
   generic_family: Option<GenericFamily>,
+  face_index: Option<u32>,
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -141,11 +217,21 @@ impl From<FontStyle> for FontStyleHash {
   }
 }
 
+/// Font and shaper state reused across layouts, so font queries and shaping plans stay cached.
+struct ShapingContext {
+  fonts: parley::FontContext,
+  layout: LayoutContext<InlineBrush>,
+}
+
 /// A context for managing fonts in the rendering system.
-#[derive(Clone)]
 pub struct FontContext {
   inner: parley::FontContext,
-  cache: Xxh3HashSet<FontCacheKey>,
+  /// Loaded sources and the families they registered.
+  cache: Xxh3HashMap<FontCacheKey, Vec<FamilyId>>,
+  /// Fallback chains keyed by lowercased family name.
+  fallbacks: HashMap<Box<str>, Vec<Box<str>>>,
+  /// Idle shaping contexts, one per layout that ran concurrently.
+  shaping_pool: Mutex<Vec<ShapingContext>>,
 }
 
 impl Default for FontContext {
@@ -158,7 +244,20 @@ impl Default for FontContext {
         }),
         source_cache: Default::default(),
       },
-      cache: Xxh3HashSet::default(),
+      cache: Xxh3HashMap::default(),
+      fallbacks: HashMap::new(),
+      shaping_pool: Mutex::default(),
+    }
+  }
+}
+
+impl Clone for FontContext {
+  fn clone(&self) -> Self {
+    Self {
+      inner: self.inner.clone(),
+      cache: self.cache.clone(),
+      fallbacks: self.fallbacks.clone(),
+      shaping_pool: Mutex::default(),
     }
   }
 }
@@ -173,6 +272,8 @@ impl Deref for FontContext {
 
 impl DerefMut for FontContext {
   fn deref_mut(&mut self) -> &mut Self::Target {
+    // The pooled contexts are snapshots of the collection, which is about to change.
+    self.clear_shaping_pool();
     &mut self.inner
   }
 }
@@ -183,14 +284,14 @@ impl FontContext {
     run: &GlyphRun<'_, InlineBrush>,
     font_ref: FontRef,
     glyph_ids: impl Iterator<Item = u32> + Clone,
+    cache: &GlyphCache,
+    hinting: bool,
   ) -> HashMap<u32, ResolvedGlyph> {
     // Collect unique glyph IDs to avoid duplicate work
     let unique_glyph_ids: HashSet<u32> = glyph_ids.collect();
 
-    let mut result = HashMap::new();
-
     if unique_glyph_ids.is_empty() {
-      return result;
+      return HashMap::new();
     }
 
     let mut scale = ScaleContext::with_max_entries(0);
@@ -198,6 +299,7 @@ impl FontContext {
       .builder(font_ref)
       .size(run.run().font_size())
       .normalized_coords(run.run().normalized_coords())
+      .hint(hinting)
       .build();
 
     let has_emoji_cluster = run
@@ -212,16 +314,26 @@ impl FontContext {
     } else {
       None
     };
-    let skew = run
+    let skew_degrees = run
       .run()
       .synthesis()
       .skew()
       .filter(|_| !has_emoji_cluster)
-      .filter(|_| run.style().brush.font_synthesis.style.is_allowed())
+      .filter(|_| run.style().brush.font_synthesis.style.is_allowed());
+    let skew = skew_degrees
       .map(|degrees| ZenoTransform::skew(ZenoAngle::from_degrees(degrees), ZenoAngle::ZERO));
 
-    // Process each unique glyph ID
-    for &glyph_id in &unique_glyph_ids {
+    let run_key = GlyphRunKey {
+      font_id: run.run().font().data.id(),
+      font_index: run.run().font().index,
+      size: run.run().font_size().to_bits(),
+      normalized_coords: run.run().normalized_coords().iter().copied().collect(),
+      embolden: embolden.map(f32::to_bits),
+      skew: skew_degrees.map(f32::to_bits),
+      hinting,
+    };
+
+    cache.resolve(&run_key, unique_glyph_ids, |glyph_id| {
       let mut resolved = scaler
         .scale_color_bitmap(glyph_id as u16, StrikeWith::BestFit)
         .map(|image| (ResolvedGlyph::Image(image), false))
@@ -247,12 +359,8 @@ impl FontContext {
         outline.transform(skew_transform);
       }
 
-      if let Some((glyph, _)) = resolved {
-        result.insert(glyph_id, glyph);
-      }
-    }
-
-    result
+      resolved.map(|(glyph, _)| glyph)
+    })
   }
 
   /// Create an inline layout with the given root style and function
@@ -261,22 +369,149 @@ impl FontContext {
     root_style: TextStyle<'_, InlineBrush>,
     func: impl FnOnce(&mut TreeBuilder<'_, InlineBrush>),
   ) -> (InlineLayout, String) {
-    let mut font_context = self.clone();
-    let mut layout_context = LayoutContext::new();
-
-    let mut builder = layout_context.tree_builder(&mut font_context, 1.0, true, &root_style);
+    let mut shaping = self
+      .shaping_pool
+      .lock()
+      .ok()
+      .and_then(|mut pool| pool.pop())
+      .unwrap_or_else(|| ShapingContext {
+        fonts: self.inner.clone(),
+        layout: LayoutContext::new(),
+      });
+
+    let mut builder = shaping
+      .layout
+      .tree_builder(&mut shaping.fonts, 1.0, true, &root_style);
 
     func(&mut builder);
 
-    builder.build()
+    let result = builder.build();
+
+    if let Ok(mut pool) = self.shaping_pool.lock() {
+      pool.push(shaping);
+    }
+
+    result
+  }
+
+  /// Returns true if `text` shapes into at least one glyph run in the given style.
+  pub(crate) fn shapes_any_glyph(
+    &self,
+    root_style: TextStyle<'_, InlineBrush>,
+    text: &str,
+  ) -> bool {
+    let (mut layout, _) = self.tree_builder(root_style, |builder| {
+      builder.push_text(text);
+    });
+    layout.break_all_lines(None);
+
+    layout.lines().any(|line| line.runs().next().is_some())
+  }
+
+  /// Returns true if the font matched for Latin text in the given style provides an OpenType feature.
+  pub(crate) fn primary_font_has_feature(
+    &self,
+    root_style: TextStyle<'_, InlineBrush>,
+    tag: u32,
+  ) -> bool {
+    let (mut layout, _) = self.tree_builder(root_style, |builder| {
+      builder.push_text("x");
+    });
+    layout.break_all_lines(None);
+
+    let Some(run) = layout.lines().next().and_then(|line| line.runs().next()) else {
+      return false;
+    };
+    let font = run.font();
+
+    FontRef::from_index(font.data.as_ref(), font.index as usize)
+      .is_some_and(|font| font.features().any(|feature| feature.tag() == tag))
+  }
+
+  /// Sets the families tried, in order, for characters `family` has no glyphs for.
+  ///
+  /// The chain is inserted right after `family` wherever it appears in `font-family`,
+  /// ahead of the remaining families and the script fallbacks of every loaded font.
+  /// Family names are matched case-insensitively. An empty list removes the chain.
+  pub fn set_fallbacks<I, S>(&mut self, family: &str, fallbacks: I)
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<Box<str>>,
+  {
+    let fallbacks: Vec<_> = fallbacks.into_iter().map(Into::into).collect();
+    let key = family.to_ascii_lowercase().into_boxed_str();
+
+    if fallbacks.is_empty() {
+      self.fallbacks.remove(&key);
+    } else {
+      self.fallbacks.insert(key, fallbacks);
+    }
+  }
+
+  /// Appends a family to the end of the fallback chain of `family`.
+  pub fn append_fallback(&mut self, family: &str, fallback: impl Into<Box<str>>) {
+    let fallback = fallback.into();
+    let chain = self
+      .fallbacks
+      .entry(family.to_ascii_lowercase().into_boxed_str())
+      .or_default();
+
+    if !chain
+      .iter()
+      .any(|existing| existing.eq_ignore_ascii_case(&fallback))
+    {
+      chain.push(fallback);
+    }
+  }
+
+  /// Returns a hash of the loaded fonts and fallback chains that doesn't depend on the order
+  /// they were added in.
+  pub(crate) fn fingerprint(&self) -> u64 {
+    fn hash_one(value: impl Hash) -> u64 {
+      let mut hasher = Xxh3::new();
+      value.hash(&mut hasher);
+      hasher.finish()
+    }
+
+    let fonts = self.cache.keys().map(hash_one);
+    let fallbacks = self.fallbacks.iter().map(hash_one);
+
+    fonts.chain(fallbacks).fold(0, u64::wrapping_add)
+  }
+
+  /// Returns the fallback chain registered for `family`.
+  pub fn fallbacks(&self, family: &str) -> &[Box<str>] {
+    if self.fallbacks.is_empty() {
+      return &[];
+    }
+
+    self
+      .fallbacks
+      .get(family.to_ascii_lowercase().as_str())
+      .map_or(&[], Vec::as_slice)
+  }
+
+  fn clear_shaping_pool(&mut self) {
+    match self.shaping_pool.get_mut() {
+      Ok(pool) => pool.clear(),
+      Err(poisoned) => poisoned.into_inner().clear(),
+    }
   }
 
   /// Loads font into internal font db with caching
   pub fn load_and_store(&mut self, font: FontResource) -> Result<(), FontError> {
+    self.load(font).map(|_| ())
+  }
+
+  /// Loads a font like [`FontContext::load_and_store`], returning the id of its font data unless
+  /// the same font was already loaded.
+  pub(crate) fn load(&mut self, font: FontResource) -> Result<Option<u64>, FontError> {
     let FontResource {
       source,
       info_override,
       generic_family,
+      fallback_for,
+      face_index,
     } = font;
 
     let cache_key = FontCacheKey {
@@ -298,18 +533,65 @@ impl FontContext {
           .collect()
       }),
       generic_family,
+      face_index,
     };
 
-    if self.cache.contains(&cache_key) {
-      return Ok(());
+    if self.cache.contains_key(&cache_key) {
+      return Ok(None);
+    }
+
+    let blob = source.into_blob()?;
+    let blob_id = blob.id();
+
+    if let Some(index) = face_index {
+      let count = FontDataRef::new(blob.as_ref()).map_or(0, |data| data.len() as u32);
+
+      if index >= count {
+        return Err(FontError::FaceIndexOutOfRange { index, count });
+      }
+    }
+
+    let mut fonts = self.inner.collection.register_fonts(blob, info_override);
+
+    if let Some(index) = face_index {
+      for (family, faces) in &mut fonts {
+        let (kept, dropped): (Vec<_>, Vec<_>) =
+          faces.drain(..).partition(|face| face.index() == index);
+
+        for face in dropped {
+          // fontique unregisters by attributes, which would take the kept face along.
+          let shares_attributes = kept.iter().any(|kept| {
+            kept.width() == face.width()
+              && kept.style() == face.style()
+              && kept.weight() == face.weight()
+          });
+
+          if !shares_attributes {
+            self.inner.collection.unregister_font(
+              *family,
+              face.width(),
+              face.style(),
+              face.weight(),
+            );
+          }
+        }
+
+        *faces = kept;
+      }
+
+      fonts.retain(|(_, faces)| !faces.is_empty());
     }
 
-    let fonts = self
-      .inner
-      .collection
-      .register_fonts(source.into_blob()?, info_override);
+    let families = fonts.iter().map(|(family, _)| *family).collect();
 
     for (family, _) in fonts {
+      if let Some(fallback_for) = fallback_for
+        && let Some(name) = self.inner.collection.family_name(family)
+      {
+        let name = name.to_owned();
+        self.append_fallback(fallback_for, name);
+      }
+
       if let Some(generic_family) = generic_family {
         self
           .inner
@@ -325,9 +607,43 @@ impl FontContext {
       }
     }
 
-    self.cache.insert(cache_key);
+    self.cache.insert(cache_key, families);
+    self.clear_shaping_pool();
+
+    Ok(Some(blob_id))
+  }
+
+  /// Unregisters every font of `family`, returning the ids of the font data they were backed by.
+  pub(crate) fn unload(&mut self, family: &str) -> Option<Vec<u64>> {
+    let family_id = self.inner.collection.family_id(family)?;
+    let fonts = self.inner.collection.family(family_id)?.fonts().to_vec();
+
+    if fonts.is_empty() {
+      return None;
+    }
+
+    let mut font_ids = Vec::with_capacity(fonts.len());
+
+    for font in fonts {
+      // Fonts are only ever registered from memory, but fontique may be built with file sources.
+      #[allow(irrefutable_let_patterns)]
+      if let SourceKind::Memory(blob) = font.source().kind() {
+        font_ids.push(blob.id());
+      }
+
+      self
+        .inner
+        .collection
+        .unregister_font(family_id, font.width(), font.style(), font.weight());
+    }
+
+    // Loading the same data again has to register the family anew.
+    self
+      .cache
+      .retain(|_, families| !families.contains(&family_id));
+    self.clear_shaping_pool();
 
-    Ok(())
+    Some(font_ids)
   }
 }
 
@@ -390,6 +706,10 @@ pub struct FontResource<'a> {
   info_override: Option<FontInfoOverride<'a>>,
   /// Generic font family
   generic_family: Option<GenericFamily>,
+  /// Family whose fallback chain the font joins
+  fallback_for: Option<&'a str>,
+  /// The only face of a collection to load
+  face_index: Option<u32>,
 }
 
 impl<'a> FontResource<'a> {
@@ -399,6 +719,8 @@ impl<'a> FontResource<'a> {
       source: source.into(),
       info_override: None,
       generic_family: None,
+      fallback_for: None,
+      face_index: None,
     }
   }
 
@@ -418,6 +740,33 @@ impl<'a> FontResource<'a> {
     }
   }
 
+  /// Append the font's families to the fallback chain of `family`
+  ///
+  /// See [`FontContext::set_fallbacks`].
+  pub fn fallback_for(self, family: &'a str) -> Self {
+    Self {
+      fallback_for: Some(family),
+      ..self
+    }
+  }
+
+  /// Load only the face at `index` of a TrueType Collection, instead of every face in it
+  pub fn face_index(self, index: u32) -> Self {
+    Self {
+      face_index: Some(index),
+      ..self
+    }
+  }
+
+  /// Registers the font under `family` instead of the name it declares.
+  pub(crate) fn family_name(mut self, family: &'a str) -> Self {
+    self.info_override = Some(FontInfoOverride {
+      family_name: Some(family),
+      ..self.info_override.unwrap_or_default()
+    });
+    self
+  }
+
   /// Convert to resolved font resource
   /// Woff2 and Woff should be decompressed into raw buffer.
   pub fn into_resolved(self) -> Result<Self, FontError> {
@@ -426,6 +775,8 @@ impl<'a> FontResource<'a> {
       source,
       info_override: self.info_override,
       generic_family: self.generic_family,
+      fallback_for: self.fallback_for,
+      face_index: self.face_index,
     })
   }
 }
@@ -36,10 +36,17 @@ pub enum ImageSource {
   },
   /// A bitmap image source
   Bitmap(RgbaImage),
+  /// A bitmap shrunk while decoding, laid out at the size of the encoded image
+  ScaledBitmap {
+    /// The reduced bitmap.
+    bitmap: RgbaImage,
+    /// The dimensions of the encoded image.
+    intrinsic_size: (u32, u32),
+  },
 }
 
 /// Represents a persistent image store.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 #[non_exhaustive]
 pub struct PersistentImageStore {
   #[cfg(target_arch = "wasm32")]
@@ -102,6 +109,16 @@ impl ImageSource {
   ///   are parsed as an SVG using `resvg::usvg`.
   /// - Otherwise, the bytes are decoded as a raster image.
   pub fn from_bytes(bytes: &[u8]) -> ImageResult {
+    Self::from_bytes_with_max_size(bytes, 0)
+  }
+
+  /// Load an image source from raw bytes, shrinking bitmaps whose longer side exceeds
+  /// `max_size` pixels while decoding.
+  ///
+  /// Only the reduced bitmap is kept, which saves memory for large photos that are stored or
+  /// drawn repeatedly. The image keeps the size of the encoded image as its intrinsic size, so
+  /// layout is the same as without the limit. A `max_size` of `0` and SVG sources are left as is.
+  pub fn from_bytes_with_max_size(bytes: &[u8], max_size: u32) -> ImageResult {
     #[cfg(feature = "svg")]
     {
       use std::str::from_utf8;
@@ -113,19 +130,46 @@ impl ImageSource {
       }
     }
 
-    let img = image_decoder::decode_image(bytes).map_err(ImageResourceError::DecodeError)?;
-    Ok(Arc::new(img.into()))
+    let (bitmap, intrinsic_size) = image_decoder::decode_image_with_max_size(bytes, max_size)
+      .map_err(ImageResourceError::DecodeError)?;
+
+    if bitmap.dimensions() == intrinsic_size {
+      return Ok(Arc::new(bitmap.into()));
+    }
+
+    Ok(Arc::new(ImageSource::ScaledBitmap {
+      bitmap,
+      intrinsic_size,
+    }))
   }
 
-  /// Get the image size in device pixels for the current sizing context.
-  pub(crate) fn size(&self, sizing: &Sizing) -> (f32, f32) {
-    let (width, height) = match self {
+  /// Returns the decoded pixels of a bitmap source.
+  pub fn bitmap(&self) -> Option<&RgbaImage> {
+    match self {
+      ImageSource::Bitmap(bitmap) | ImageSource::ScaledBitmap { bitmap, .. } => Some(bitmap),
+      #[cfg(feature = "svg")]
+      ImageSource::Svg { .. } => None,
+    }
+  }
+
+  /// Get the intrinsic image size, as declared for SVGs.
+  pub fn intrinsic_size(&self) -> (f32, f32) {
+    match self {
       #[cfg(feature = "svg")]
       ImageSource::Svg { tree, .. } => (tree.size().width(), tree.size().height()),
       ImageSource::Bitmap(bitmap) => (bitmap.width() as f32, bitmap.height() as f32),
-    };
+      ImageSource::ScaledBitmap {
+        intrinsic_size: (width, height),
+        ..
+      } => (*width as f32, *height as f32),
+    }
+  }
+
+  /// Get the image size in device pixels for the current sizing context.
+  pub(crate) fn size(&self, sizing: &Sizing) -> (f32, f32) {
+    let (width, height) = self.intrinsic_size();
 
-    let dpr = sizing.viewport.device_pixel_ratio;
+    let dpr = sizing.pixel_ratio();
     (width * dpr, height * dpr)
   }
 
@@ -141,7 +185,7 @@ impl ImageSource {
     let _ = current_color;
 
     match self {
-      ImageSource::Bitmap(bitmap) => {
+      ImageSource::Bitmap(bitmap) | ImageSource::ScaledBitmap { bitmap, .. } => {
         if bitmap.width() == width && bitmap.height() == height {
           return Ok(Cow::Borrowed(bitmap));
         }
@@ -163,7 +207,7 @@ impl ImageSource {
         let options = Options {
           style_sheet: Some(format!("svg {{ color: {current_color}; }}")),
           image_rendering: image_rendering.into(),
-          ..Default::default()
+          ..svg_options()
         };
         let reparsed_tree =
           Tree::from_str(source, &options).map_err(ImageResourceError::SvgParseError)?;
@@ -191,6 +235,25 @@ impl ImageSource {
       }
     }
   }
+
+  /// Render the image source to an RGBA image at its intrinsic size.
+  ///
+  /// SVGs are rasterized at their declared size rounded up, with `currentColor` resolved to black.
+  /// Bitmaps shrunk while decoding are returned at their reduced size.
+  pub fn to_rgba_image(&self) -> Result<Cow<'_, RgbaImage>, ImageResourceError> {
+    if let Some(bitmap) = self.bitmap() {
+      return Ok(Cow::Borrowed(bitmap));
+    }
+
+    let (width, height) = self.intrinsic_size();
+
+    self.render_to_rgba_image(
+      (width.ceil() as u32).max(1),
+      (height.ceil() as u32).max(1),
+      ImageScalingAlgorithm::Auto,
+      Color::black(),
+    )
+  }
 }
 
 /// Check if the string looks like an SVG image.
@@ -260,14 +323,31 @@ fn strip_unsupported_svg_text_nodes(src: &str) -> String {
   stripped
 }
 
+/// Options for parsing untrusted SVG sources.
+///
+/// Nested `<image>` elements may only embed data URLs, `href`s pointing at local files are
+/// dropped instead of being read from disk.
+#[cfg(feature = "svg")]
+fn svg_options<'a>() -> resvg::usvg::Options<'a> {
+  use resvg::usvg::{ImageHrefResolver, Options};
+
+  Options {
+    image_href_resolver: ImageHrefResolver {
+      resolve_data: ImageHrefResolver::default_data_resolver(),
+      resolve_string: Box::new(|_, _| None),
+    },
+    ..Default::default()
+  }
+}
+
 #[cfg(feature = "svg")]
 /// Parse SVG from &str.
 pub fn parse_svg_str(src: &str) -> ImageResult {
   use resvg::usvg::Tree;
 
   let sanitized_svg = strip_unsupported_svg_text_nodes(src);
-  let tree = Tree::from_str(&sanitized_svg, &Default::default())
-    .map_err(ImageResourceError::SvgParseError)?;
+  let tree =
+    Tree::from_str(&sanitized_svg, &svg_options()).map_err(ImageResourceError::SvgParseError)?;
 
   Ok(Arc::new(ImageSource::Svg {
     source: Arc::from(sanitized_svg),
@@ -340,6 +420,17 @@ mod tests {
     Ok(())
   }
 
+  #[cfg(feature = "svg")]
+  #[test]
+  fn svg_to_rgba_image_uses_intrinsic_size() -> Result<(), ImageResourceError> {
+    let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="4.5" height="3"><rect x="0" y="0" width="4.5" height="3" fill="currentColor"/></svg>"#;
+    let image = parse_svg_str(svg)?.to_rgba_image()?.into_owned();
+
+    assert_eq!(image.dimensions(), (5, 3));
+    assert_eq!(rgba_at(&image, 1, 1), [0, 0, 0, 255]);
+    Ok(())
+  }
+
   #[cfg(feature = "svg")]
   #[test]
   fn svg_current_color_applies_alpha() -> Result<(), ImageResourceError> {
@@ -388,6 +479,86 @@ mod tests {
     Ok(())
   }
 
+  #[cfg(feature = "svg")]
+  #[test]
+  fn svg_images_only_load_data_urls() -> Result<(), ImageResourceError> {
+    let nested = r##"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"><rect width="4" height="4" fill="#ff0000"/></svg>"##;
+    let path = std::env::temp_dir().join(format!("takumi-svg-href-{}.svg", std::process::id()));
+    assert!(std::fs::write(&path, nested).is_ok());
+
+    let embed = |href: &str| {
+      format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"><image href="{href}" width="4" height="4"/></svg>"#
+      )
+    };
+    let render = |svg: &str| -> Result<[u8; 4], ImageResourceError> {
+      let image = parse_svg_str(svg)?
+        .render_to_rgba_image(4, 4, ImageScalingAlgorithm::Auto, Color::black())?
+        .into_owned();
+      Ok(rgba_at(&image, 2, 2))
+    };
+
+    let from_file = render(&embed(&path.to_string_lossy()));
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(from_file?, [0, 0, 0, 0]);
+
+    let data_url = nested
+      .bytes()
+      .fold(String::from("data:image/svg+xml,"), |mut url, byte| {
+        url.push_str(&format!("%{byte:02X}"));
+        url
+      });
+    assert_eq!(render(&embed(&data_url))?, [255, 0, 0, 255]);
+    Ok(())
+  }
+
+  #[test]
+  fn max_size_shrinks_large_bitmaps_keeping_intrinsic_size() -> Result<(), ImageResourceError> {
+    use std::io::Cursor;
+
+    let mut png = Vec::new();
+    let encoded = RgbaImage::from_pixel(400, 100, Rgba([0, 128, 255, 255]))
+      .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png);
+    assert!(encoded.is_ok());
+
+    let shrunk = ImageSource::from_bytes_with_max_size(&png, 100)?;
+    let ImageSource::ScaledBitmap {
+      bitmap,
+      intrinsic_size,
+    } = &*shrunk
+    else {
+      unreachable!()
+    };
+    assert_eq!(bitmap.dimensions(), (100, 25));
+    assert_eq!(*intrinsic_size, (400, 100));
+    assert_eq!(rgba_at(bitmap, 50, 12), [0, 128, 255, 255]);
+    assert_eq!(shrunk.intrinsic_size(), (400.0, 100.0));
+
+    let ImageSource::Bitmap(kept) = &*ImageSource::from_bytes_with_max_size(&png, 400)? else {
+      unreachable!()
+    };
+    assert_eq!(kept.dimensions(), (400, 100));
+    Ok(())
+  }
+
+  #[test]
+  fn max_size_scales_webp_while_decoding() -> Result<(), ImageResourceError> {
+    use std::io::Cursor;
+
+    let mut webp = Vec::new();
+    let encoded = RgbaImage::from_pixel(300, 600, Rgba([255, 0, 0, 255]))
+      .write_to(&mut Cursor::new(&mut webp), image::ImageFormat::WebP);
+    assert!(encoded.is_ok());
+
+    let shrunk = ImageSource::from_bytes_with_max_size(&webp, 60)?;
+    let Some(bitmap) = shrunk.bitmap() else {
+      unreachable!()
+    };
+    assert_eq!(bitmap.dimensions(), (30, 60));
+    assert_eq!(shrunk.intrinsic_size(), (300.0, 600.0));
+    Ok(())
+  }
+
   #[test]
   fn bitmap_is_not_affected_by_current_color() -> Result<(), ImageResourceError> {
     let mut bitmap = RgbaImage::new(2, 2);
@@ -0,0 +1,271 @@
+use std::{
+  collections::{BTreeMap, HashMap},
+  mem::size_of,
+  sync::{Arc, Mutex},
+};
+
+use xxhash_rust::xxh3::xxh3_128;
+
+use crate::resources::image::{ImageResult, ImageSource};
+
+/// Default byte budget of an [`ImageDecodeCache`].
+pub const DEFAULT_IMAGE_CACHE_BUDGET: usize = 32 * 1024 * 1024;
+
+/// Counters describing the state of an [`ImageDecodeCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImageCacheStats {
+  /// Number of cached images.
+  pub entries: usize,
+  /// Estimated memory held by the decoded images, in bytes.
+  pub bytes: usize,
+  /// Maximum number of bytes the cache may hold.
+  pub budget: usize,
+  /// Decodes served from the cache.
+  pub hits: u64,
+  /// Decodes that had to decode the bytes.
+  pub misses: u64,
+  /// Images dropped to stay within the budget.
+  pub evictions: u64,
+}
+
+/// Identifies decoded image bytes by their content rather than the URL they came from.
+///
+/// Different bytes must never share a key, or a render would draw another image, so the hash is
+/// 128 bits wide and paired with the byte length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ImageCacheKey {
+  content_hash: u128,
+  len: usize,
+  max_decode_size: Option<u32>,
+}
+
+struct ImageCacheEntry {
+  image: Arc<ImageSource>,
+  bytes: usize,
+  last_used: u64,
+}
+
+#[derive(Default)]
+struct ImageCacheState {
+  entries: HashMap<ImageCacheKey, ImageCacheEntry>,
+  /// Keys ordered from least to most recently used.
+  recency: BTreeMap<u64, ImageCacheKey>,
+  tick: u64,
+  stats: ImageCacheStats,
+}
+
+impl ImageCacheState {
+  fn touch(&mut self, key: &ImageCacheKey) -> Option<Arc<ImageSource>> {
+    self.tick += 1;
+    let tick = self.tick;
+
+    let entry = self.entries.get_mut(key)?;
+    let previous = std::mem::replace(&mut entry.last_used, tick);
+    let image = Arc::clone(&entry.image);
+
+    if let Some(key) = self.recency.remove(&previous) {
+      self.recency.insert(tick, key);
+    }
+
+    Some(image)
+  }
+
+  fn insert(&mut self, key: ImageCacheKey, image: Arc<ImageSource>) {
+    let bytes = estimate_image_bytes(&image);
+
+    if bytes > self.stats.budget {
+      return;
+    }
+
+    self.tick += 1;
+    self.recency.insert(self.tick, key);
+
+    if let Some(previous) = self.entries.insert(
+      key,
+      ImageCacheEntry {
+        image,
+        bytes,
+        last_used: self.tick,
+      },
+    ) {
+      self.recency.remove(&previous.last_used);
+      self.stats.bytes -= previous.bytes;
+    }
+
+    self.stats.bytes += bytes;
+    self.evict_to(self.stats.budget);
+  }
+
+  fn evict_to(&mut self, budget: usize) {
+    while self.stats.bytes > budget {
+      let Some((_, key)) = self.recency.pop_first() else {
+        break;
+      };
+
+      if let Some(entry) = self.entries.remove(&key) {
+        self.stats.bytes -= entry.bytes;
+        self.stats.evictions += 1;
+      }
+    }
+
+    self.stats.entries = self.entries.len();
+  }
+}
+
+/// Decoded images reused across renders, keyed by a hash of their bytes and bounded by a byte
+/// budget.
+///
+/// The same bytes fetched under different URLs are decoded once. The least recently used images
+/// are dropped first once the budget is exceeded, and a budget of `0` disables the cache.
+pub struct ImageDecodeCache {
+  state: Mutex<ImageCacheState>,
+}
+
+impl Default for ImageDecodeCache {
+  fn default() -> Self {
+    Self::new(DEFAULT_IMAGE_CACHE_BUDGET)
+  }
+}
+
+impl ImageDecodeCache {
+  /// Creates a cache holding at most `budget` bytes of decoded images.
+  pub fn new(budget: usize) -> Self {
+    Self {
+      state: Mutex::new(ImageCacheState {
+        stats: ImageCacheStats {
+          budget,
+          ..Default::default()
+        },
+        ..Default::default()
+      }),
+    }
+  }
+
+  /// Returns the current counters.
+  pub fn stats(&self) -> ImageCacheStats {
+    self
+      .state
+      .lock()
+      .map(|state| state.stats)
+      .unwrap_or_default()
+  }
+
+  /// Changes the byte budget, dropping the least recently used images if it shrank.
+  pub fn set_budget(&self, budget: usize) {
+    if let Ok(mut state) = self.state.lock() {
+      state.stats.budget = budget;
+      state.evict_to(budget);
+    }
+  }
+
+  /// Drops every cached image, keeping the budget and the hit and miss counters.
+  pub fn clear(&self) {
+    if let Ok(mut state) = self.state.lock() {
+      state.entries.clear();
+      state.recency.clear();
+      state.stats.bytes = 0;
+      state.stats.entries = 0;
+    }
+  }
+
+  /// Decodes image bytes, reusing an earlier decode of the same bytes.
+  ///
+  /// `max_decode_size` works like [`ImageSource::from_bytes_with_max_size`]. Bytes that fail to
+  /// decode aren't cached.
+  pub fn decode(&self, bytes: &[u8], max_decode_size: Option<u32>) -> ImageResult {
+    let key = ImageCacheKey {
+      content_hash: xxh3_128(bytes),
+      len: bytes.len(),
+      max_decode_size,
+    };
+
+    if let Ok(mut state) = self.state.lock() {
+      if let Some(image) = state.touch(&key) {
+        state.stats.hits += 1;
+        return Ok(image);
+      }
+
+      state.stats.misses += 1;
+    }
+
+    let image = match max_decode_size {
+      Some(max_size) => ImageSource::from_bytes_with_max_size(bytes, max_size),
+      None => ImageSource::from_bytes(bytes),
+    }?;
+
+    if let Ok(mut state) = self.state.lock() {
+      state.insert(key, Arc::clone(&image));
+    }
+
+    Ok(image)
+  }
+}
+
+/// Estimates the memory held by a decoded image, including its key and bookkeeping.
+fn estimate_image_bytes(image: &ImageSource) -> usize {
+  let data = match image {
+    ImageSource::Bitmap(bitmap) | ImageSource::ScaledBitmap { bitmap, .. } => bitmap.as_raw().len(),
+    // The parsed tree isn't measurable, so count its source a few times over.
+    #[cfg(feature = "svg")]
+    ImageSource::Svg { source, .. } => source.len() * 4,
+  };
+
+  data + size_of::<ImageCacheEntry>() + size_of::<ImageCacheKey>() * 2
+}
+
+#[cfg(test)]
+mod tests {
+  use image::{ImageFormat, Rgba, RgbaImage};
+
+  use super::*;
+
+  fn png(color: [u8; 4]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let Ok(()) = RgbaImage::from_pixel(4, 4, Rgba(color))
+      .write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+    else {
+      unreachable!()
+    };
+    bytes
+  }
+
+  #[test]
+  fn decodes_identical_bytes_once() {
+    let cache = ImageDecodeCache::default();
+    let bytes = png([255, 0, 0, 255]);
+
+    let Ok(first) = cache.decode(&bytes, None) else {
+      unreachable!()
+    };
+    let Ok(second) = cache.decode(&bytes.clone(), None) else {
+      unreachable!()
+    };
+
+    assert!(Arc::ptr_eq(&first, &second));
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+  }
+
+  #[test]
+  fn evicts_least_recently_used_images() {
+    let entry_bytes = 4 * 4 * 4 + size_of::<ImageCacheEntry>() + size_of::<ImageCacheKey>() * 2;
+    let cache = ImageDecodeCache::new(entry_bytes * 2);
+
+    for color in [
+      [1, 0, 0, 255],
+      [2, 0, 0, 255],
+      [1, 0, 0, 255],
+      [3, 0, 0, 255],
+    ] {
+      assert!(cache.decode(&png(color), None).is_ok());
+    }
+
+    let stats = cache.stats();
+    assert_eq!((stats.entries, stats.evictions), (2, 1));
+    assert_eq!(stats.hits, 1);
+
+    cache.set_budget(0);
+    assert_eq!(cache.stats().entries, 0);
+  }
+}
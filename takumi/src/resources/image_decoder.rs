@@ -1,26 +1,42 @@
 use std::io::{Cursor, Error as IoError, ErrorKind};
 
 use image::{
-  DynamicImage, ImageError, ImageFormat, ImageResult, RgbaImage,
+  DynamicImage, ImageDecoder, ImageError, ImageFormat, ImageResult, RgbaImage,
   codecs::{gif::GifDecoder, jpeg::JpegDecoder, png::PngDecoder},
   error::{DecodingError, ImageFormatHint, UnsupportedError, UnsupportedErrorKind},
 };
+use moxcms::{ColorProfile, DataColorSpace, Layout, TransformOptions};
 
 #[cfg(not(target_arch = "wasm32"))]
-use libwebp_sys::{WebPDecodeRGBA, WebPFree};
+use libwebp_sys::{
+  VP8StatusCode, WEBP_CSP_MODE, WebPDecode, WebPDecoderConfig, WebPFreeDecBuffer, WebPGetInfo,
+  WebPRGBABuffer,
+};
+
+use crate::layout::style::ImageScalingAlgorithm;
 
+#[cfg(target_arch = "wasm32")]
+use image::RgbImage;
 #[cfg(target_arch = "wasm32")]
 use image_webp::WebPDecoder;
 
 const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
 const JPEG_SIGNATURE: [u8; 3] = [0xFF, 0xD8, 0xFF];
 
-pub(crate) fn decode_image(bytes: &[u8]) -> ImageResult<RgbaImage> {
+/// A decoded bitmap along with the dimensions of the encoded image.
+pub(crate) type DecodedImage = (RgbaImage, (u32, u32));
+
+/// Decodes an image, shrinking it so its longer side is at most `max_size` pixels.
+///
+/// The dimensions are read from the header first, so a larger image is shrunk before it is
+/// expanded to RGBA, or while decoding for WebP, and a full size RGBA copy is never held.
+/// A `max_size` of `0` keeps the original size.
+pub(crate) fn decode_image_with_max_size(bytes: &[u8], max_size: u32) -> ImageResult<DecodedImage> {
   match detect_image_format(bytes) {
-    Some(DetectedImageFormat::Png) => decode_png(bytes),
-    Some(DetectedImageFormat::Jpeg) => decode_jpeg(bytes),
-    Some(DetectedImageFormat::Gif) => decode_gif(bytes),
-    Some(DetectedImageFormat::WebP) => decode_webp(bytes),
+    Some(DetectedImageFormat::Png) => decode_png(bytes, max_size),
+    Some(DetectedImageFormat::Jpeg) => decode_jpeg(bytes, max_size),
+    Some(DetectedImageFormat::Gif) => decode_gif(bytes, max_size),
+    Some(DetectedImageFormat::WebP) => decode_webp(bytes, max_size),
     None => Err(ImageError::Unsupported(
       UnsupportedError::from_format_and_kind(
         ImageFormatHint::Unknown,
@@ -58,24 +74,89 @@ enum DetectedImageFormat {
   WebP,
 }
 
-fn decode_with_image_crate(decoder: impl image::ImageDecoder) -> ImageResult<RgbaImage> {
-  Ok(DynamicImage::from_decoder(decoder)?.to_rgba8())
+fn decode_with_image_crate(
+  mut decoder: impl ImageDecoder,
+  max_size: u32,
+) -> ImageResult<DecodedImage> {
+  let icc_profile = decoder.icc_profile().ok().flatten();
+  let dimensions = decoder.dimensions();
+  let mut image = into_rgba_within(DynamicImage::from_decoder(decoder)?, max_size);
+
+  if let Some(icc_profile) = icc_profile {
+    convert_to_srgb(&mut image, &icc_profile);
+  }
+
+  Ok((image, dimensions))
+}
+
+/// Returns the size an image shrinks to so its longer side is `max_size`, or `None` if it
+/// already fits.
+fn shrunk_size((width, height): (u32, u32), max_size: u32) -> Option<(u32, u32)> {
+  let longer_side = width.max(height);
+  if max_size == 0 || longer_side <= max_size {
+    return None;
+  }
+
+  let scale = f64::from(max_size) / f64::from(longer_side);
+  Some((
+    ((f64::from(width) * scale).round() as u32).max(1),
+    ((f64::from(height) * scale).round() as u32).max(1),
+  ))
+}
+
+/// Converts a decoded image to RGBA, shrinking it in its own color type first so the full size
+/// image is never expanded to four channels.
+fn into_rgba_within(image: DynamicImage, max_size: u32) -> RgbaImage {
+  match shrunk_size((image.width(), image.height()), max_size) {
+    Some((width, height)) => image
+      .resize_exact(width, height, ImageScalingAlgorithm::Auto.into())
+      .into_rgba8(),
+    None => image.into_rgba8(),
+  }
+}
+
+/// Converts pixels from the embedded RGB color profile to sRGB, which is what rendering assumes.
+///
+/// Profiles that can't be parsed or aren't RGB, such as CMYK JPEGs that are already
+/// converted by the decoder, leave the pixels as they are.
+fn convert_to_srgb(image: &mut RgbaImage, icc_profile: &[u8]) {
+  let Ok(source) = ColorProfile::new_from_slice(icc_profile) else {
+    return;
+  };
+
+  if source.color_space != DataColorSpace::Rgb {
+    return;
+  }
+
+  let Ok(transform) = source.create_transform_8bit(
+    Layout::Rgba,
+    &ColorProfile::new_srgb(),
+    Layout::Rgba,
+    TransformOptions::default(),
+  ) else {
+    return;
+  };
+
+  let mut converted = vec![0; image.as_raw().len()];
+  if transform.transform(image.as_raw(), &mut converted).is_ok() {
+    image.copy_from_slice(&converted);
+  }
 }
 
-fn decode_png(bytes: &[u8]) -> ImageResult<RgbaImage> {
-  decode_with_image_crate(PngDecoder::new(Cursor::new(bytes))?)
+fn decode_png(bytes: &[u8], max_size: u32) -> ImageResult<DecodedImage> {
+  decode_with_image_crate(PngDecoder::new(Cursor::new(bytes))?, max_size)
 }
 
-fn decode_jpeg(bytes: &[u8]) -> ImageResult<RgbaImage> {
-  decode_with_image_crate(JpegDecoder::new(Cursor::new(bytes))?)
+fn decode_jpeg(bytes: &[u8], max_size: u32) -> ImageResult<DecodedImage> {
+  decode_with_image_crate(JpegDecoder::new(Cursor::new(bytes))?, max_size)
 }
 
-fn decode_gif(bytes: &[u8]) -> ImageResult<RgbaImage> {
-  decode_with_image_crate(GifDecoder::new(Cursor::new(bytes))?)
+fn decode_gif(bytes: &[u8], max_size: u32) -> ImageResult<DecodedImage> {
+  decode_with_image_crate(GifDecoder::new(Cursor::new(bytes))?, max_size)
 }
 
 #[cfg(target_arch = "wasm32")]
-fn decode_webp(bytes: &[u8]) -> ImageResult<RgbaImage> {
+fn decode_webp(bytes: &[u8], max_size: u32) -> ImageResult<DecodedImage> {
   let mut decoder = WebPDecoder::new(Cursor::new(bytes)).map_err(webp_decode_error)?;
   let (width, height) = decoder.dimensions();
   let has_alpha = decoder.has_alpha();
@@ -85,49 +166,78 @@ fn decode_webp(bytes: &[u8]) -> ImageResult<RgbaImage> {
     .read_image(&mut image_data)
     .map_err(webp_decode_error)?;
 
-  if has_alpha {
-    return RgbaImage::from_raw(width, height, image_data).ok_or_else(invalid_buffer_error);
+  let image = if has_alpha {
+    RgbaImage::from_raw(width, height, image_data).map(DynamicImage::ImageRgba8)
+  } else {
+    RgbImage::from_raw(width, height, image_data).map(DynamicImage::ImageRgb8)
   }
+  .ok_or_else(invalid_buffer_error)?;
 
-  let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
-  for rgb in image_data.chunks_exact(3) {
-    rgba.extend_from_slice(&[rgb[0], rgb[1], rgb[2], u8::MAX]);
-  }
-
-  RgbaImage::from_raw(width, height, rgba).ok_or_else(invalid_buffer_error)
+  Ok((into_rgba_within(image, max_size), (width, height)))
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn decode_webp(bytes: &[u8]) -> ImageResult<RgbaImage> {
+fn decode_webp(bytes: &[u8], max_size: u32) -> ImageResult<DecodedImage> {
+  let decode_failed = || {
+    webp_decode_error(IoError::new(
+      ErrorKind::InvalidData,
+      "libwebp failed to decode image",
+    ))
+  };
+
   let mut width = 0;
   let mut height = 0;
-  let decoded_ptr = unsafe {
-    // SAFETY: `bytes.as_ptr()` is valid for `bytes.len()` bytes for the duration of the call,
-    // and libwebp returns either a null pointer or an owned RGBA buffer freed with `WebPFree`.
-    WebPDecodeRGBA(bytes.as_ptr(), bytes.len(), &mut width, &mut height)
+  // SAFETY: `bytes.as_ptr()` is valid for `bytes.len()` bytes for the duration of the call.
+  if unsafe { WebPGetInfo(bytes.as_ptr(), bytes.len(), &mut width, &mut height) } == 0 {
+    return Err(decode_failed());
+  }
+
+  let dimensions = (
+    u32::try_from(width).map_err(|_| invalid_buffer_error())?,
+    u32::try_from(height).map_err(|_| invalid_buffer_error())?,
+  );
+  let scaled_size = shrunk_size(dimensions, max_size);
+  let (output_width, output_height) = scaled_size.unwrap_or(dimensions);
+
+  let stride = output_width
+    .checked_mul(4)
+    .ok_or_else(invalid_buffer_error)?;
+  let buffer_len = usize::try_from(u64::from(stride) * u64::from(output_height))
+    .map_err(|_| invalid_buffer_error())?;
+  let mut image_data = vec![0; buffer_len];
+
+  let mut config = WebPDecoderConfig::new().map_err(|()| decode_failed())?;
+  if scaled_size.is_some() {
+    // libwebp scales while decoding, so only the reduced image is ever allocated.
+    config.options.use_scaling = 1;
+    config.options.scaled_width = output_width as i32;
+    config.options.scaled_height = output_height as i32;
+  }
+  config.output.colorspace = WEBP_CSP_MODE::MODE_RGBA;
+  config.output.is_external_memory = 1;
+  config.output.u.RGBA = WebPRGBABuffer {
+    rgba: image_data.as_mut_ptr(),
+    stride: i32::try_from(stride).map_err(|_| invalid_buffer_error())?,
+    size: buffer_len,
   };
 
-  if decoded_ptr.is_null() {
-    return Err(webp_decode_error(IoError::new(
-      ErrorKind::InvalidData,
-      "libwebp failed to decode image",
-    )));
+  let status = unsafe {
+    // SAFETY: `bytes.as_ptr()` is valid for `bytes.len()` bytes, and the output buffer points to
+    // `image_data`, which holds `buffer_len` bytes and outlives the call. Freeing an external
+    // buffer only releases libwebp's own bookkeeping.
+    let status = WebPDecode(bytes.as_ptr(), bytes.len(), &mut config);
+    WebPFreeDecBuffer(&mut config.output);
+    status
+  };
+
+  if status != VP8StatusCode::VP8_STATUS_OK {
+    return Err(decode_failed());
   }
 
-  let pixel_count = width
-    .checked_mul(height)
-    .and_then(|pixels| pixels.checked_mul(4))
+  let image = RgbaImage::from_raw(output_width, output_height, image_data)
     .ok_or_else(invalid_buffer_error)?;
-  let buffer_len = usize::try_from(pixel_count).map_err(|_| invalid_buffer_error())?;
-  let image_data = unsafe {
-    // SAFETY: `decoded_ptr` points to a `buffer_len`-byte RGBA allocation returned by libwebp.
-    let slice = std::slice::from_raw_parts(decoded_ptr, buffer_len);
-    let owned = slice.to_vec();
-    WebPFree(decoded_ptr.cast());
-    owned
-  };
 
-  RgbaImage::from_raw(width as u32, height as u32, image_data).ok_or_else(invalid_buffer_error)
+  Ok((image, dimensions))
 }
 
 fn invalid_buffer_error() -> ImageError {
@@ -140,3 +250,55 @@ fn invalid_buffer_error() -> ImageError {
 fn webp_decode_error(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> ImageError {
   ImageError::Decoding(DecodingError::new(ImageFormat::WebP.into(), error))
 }
+
+#[cfg(test)]
+mod tests {
+  use image::{ExtendedColorType, ImageEncoder, Rgba, codecs::png::PngEncoder};
+
+  use super::*;
+
+  fn decode_image(bytes: &[u8]) -> ImageResult<RgbaImage> {
+    decode_image_with_max_size(bytes, 0).map(|(image, _)| image)
+  }
+
+  fn encode_png(image: &RgbaImage, icc_profile: Option<Vec<u8>>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut encoder = PngEncoder::new(&mut bytes);
+
+    if let Some(icc_profile) = icc_profile {
+      assert!(encoder.set_icc_profile(icc_profile).is_ok());
+    }
+
+    let result = encoder.write_image(
+      image.as_raw(),
+      image.width(),
+      image.height(),
+      ExtendedColorType::Rgba8,
+    );
+    assert!(result.is_ok());
+
+    bytes
+  }
+
+  #[test]
+  fn embedded_profiles_are_converted_to_srgb() {
+    let source = RgbaImage::from_pixel(2, 2, Rgba([40, 160, 90, 200]));
+    let display_p3 = ColorProfile::new_display_p3().encode();
+    assert!(display_p3.is_ok());
+    let Ok(display_p3) = display_p3 else {
+      unreachable!()
+    };
+
+    let untagged = decode_image(&encode_png(&source, None));
+    assert_eq!(untagged.as_ref().ok(), Some(&source));
+
+    let tagged = decode_image(&encode_png(&source, Some(display_p3)));
+    assert!(tagged.is_ok());
+    let Ok(tagged) = tagged else { unreachable!() };
+
+    let Rgba([red, green, blue, alpha]) = *tagged.get_pixel(0, 0);
+    // Display P3 green lies outside sRGB, so the same values map to a more saturated color.
+    assert!(red < 40 && green > 160 && blue < 90, "{red} {green} {blue}");
+    assert_eq!(alpha, 200);
+  }
+}
@@ -0,0 +1,145 @@
+//! Asynchronous loading of remote resources referenced by a node tree.
+//!
+//! Bindings hand pre-fetched bytes to [`RenderOptions`](crate::rendering::RenderOptions),
+//! while pure Rust users can implement [`ResourceLoader`] and let
+//! [`RenderOptions::fetch_resources`](crate::rendering::RenderOptions::fetch_resources)
+//! resolve every [`FetchTask`] before rendering.
+
+use std::{collections::HashMap, fmt::Display, future::Future, sync::Arc};
+
+use futures_util::future::join_all;
+
+use super::{image::ImageSource, task::FetchTask};
+use crate::{Error, Result};
+
+/// Loads the bytes of a remote resource, such as an image referenced by `src` or `url()`.
+pub trait ResourceLoader {
+  /// The error returned when a resource cannot be loaded.
+  type Error: Display;
+
+  /// Loads the raw bytes served at `url`.
+  fn load(&self, url: &str) -> impl Future<Output = std::result::Result<Vec<u8>, Self::Error>>;
+}
+
+/// Loads and decodes every task concurrently, keyed by the task URL.
+pub async fn load_resources<L: ResourceLoader>(
+  loader: &L,
+  tasks: impl IntoIterator<Item = FetchTask>,
+) -> Result<HashMap<Arc<str>, Arc<ImageSource>>> {
+  let loaded = join_all(tasks.into_iter().map(|url| async move {
+    let bytes = loader
+      .load(&url)
+      .await
+      .map_err(|error| Error::ResourceLoadError {
+        url: url.clone(),
+        reason: error.to_string(),
+      })?;
+    let image = ImageSource::from_bytes(&bytes)?;
+
+    Ok((url, image))
+  }))
+  .await;
+
+  loaded.into_iter().collect()
+}
+
+/// A [`ResourceLoader`] backed by a [`reqwest::Client`].
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestLoader {
+  client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestLoader {
+  /// Creates a loader that sends requests through an existing client.
+  pub fn new(client: reqwest::Client) -> Self {
+    Self { client }
+  }
+}
+
+#[cfg(feature = "reqwest")]
+impl ResourceLoader for ReqwestLoader {
+  type Error = reqwest::Error;
+
+  async fn load(&self, url: &str) -> std::result::Result<Vec<u8>, Self::Error> {
+    let response = self.client.get(url).send().await?.error_for_status()?;
+
+    Ok(response.bytes().await?.to_vec())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    future::{Future, ready},
+    io::Cursor,
+    pin::pin,
+    task::{Context, Poll, Waker},
+  };
+
+  use image::{ImageFormat, Rgba, RgbaImage};
+
+  use super::{ResourceLoader, load_resources};
+  use crate::{Error, error::ErrorCode};
+
+  struct StaticLoader;
+
+  impl ResourceLoader for StaticLoader {
+    type Error = &'static str;
+
+    fn load(&self, url: &str) -> impl Future<Output = Result<Vec<u8>, Self::Error>> {
+      if url.ends_with("missing.png") {
+        return ready(Err("not found"));
+      }
+
+      let mut bytes = Vec::new();
+      let image = RgbaImage::from_pixel(2, 2, Rgba([255, 0, 0, 255]));
+      let encoded = image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png);
+
+      ready(encoded.map(|()| bytes).map_err(|_| "encode failed"))
+    }
+  }
+
+  fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+
+    loop {
+      if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+        return output;
+      }
+    }
+  }
+
+  #[test]
+  fn load_resources_decodes_every_task() {
+    let tasks = [
+      "https://a.test/one.png".into(),
+      "https://a.test/two.png".into(),
+    ];
+
+    let loaded = block_on(load_resources(&StaticLoader, tasks));
+    assert!(loaded.is_ok());
+    let Ok(loaded) = loaded else { unreachable!() };
+
+    assert_eq!(loaded.len(), 2);
+    assert!(loaded.contains_key("https://a.test/one.png"));
+  }
+
+  #[test]
+  fn load_resources_reports_the_failing_url() {
+    let tasks = ["https://a.test/missing.png".into()];
+
+    let Err(error) = block_on(load_resources(&StaticLoader, tasks)) else {
+      unreachable!()
+    };
+
+    assert!(matches!(
+      &error,
+      Error::ResourceLoadError { url, reason }
+        if &**url == "https://a.test/missing.png" && reason == "not found"
+    ));
+    assert_eq!(error.code(), ErrorCode::ResourceFetchFailed);
+  }
+}
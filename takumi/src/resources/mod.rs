@@ -1,7 +1,17 @@
+/// Picture fallback for emoji clusters
+pub mod emoji;
 /// Font loading and processing functionality
 pub mod font;
 /// Image state and resource management
 pub mod image;
+/// Decoded images reused across renders
+pub mod image_cache;
 mod image_decoder;
+/// Asynchronous resource loading
+pub mod loader;
+/// Dominant color extraction
+pub mod palette;
+/// Low fidelity image placeholders
+pub mod placeholder;
 /// Resource resolving tasks
 pub mod task;
@@ -0,0 +1,277 @@
+use std::{
+  borrow::Cow,
+  collections::HashMap,
+  sync::{Arc, OnceLock},
+};
+
+use image::RgbaImage;
+
+use crate::{
+  layout::style::Color,
+  resources::image::{ImageResourceError, ImageSource},
+};
+
+/// The number of colors extracted when a count isn't given, and for `palette()` references in styles.
+pub const DEFAULT_PALETTE_SIZE: usize = 8;
+
+/// Images are sampled down to roughly this many pixels before quantizing.
+const SAMPLE_PIXELS: u32 = 64 * 64;
+
+/// Pixels with less alpha than this are ignored.
+const MIN_ALPHA: u8 = 128;
+
+/// Extracts up to `count` representative colors from an image, most common first.
+///
+/// Colors are found by repeatedly splitting the most spread out group of pixels from a
+/// downsampled copy of the image, ignoring mostly transparent pixels. Returns an empty palette for fully transparent images.
+pub fn extract_palette(image: &RgbaImage, count: usize) -> Vec<Color> {
+  let Some(step) = sample_step(image.width(), image.height(), image.as_raw().len()) else {
+    return Vec::new();
+  };
+
+  let pixels = (0..image.height())
+    .step_by(step as usize)
+    .flat_map(|y| {
+      (0..image.width())
+        .step_by(step as usize)
+        .map(move |x| image.get_pixel(x, y).0)
+    })
+    .filter(|pixel| pixel[3] >= MIN_ALPHA)
+    .map(|[r, g, b, _]| [r, g, b])
+    .collect::<Vec<_>>();
+
+  if pixels.is_empty() || count == 0 {
+    return Vec::new();
+  }
+
+  let mut boxes = vec![pixels];
+
+  while boxes.len() < count {
+    // Split the box whose colors spread the most, weighted by how many pixels it holds.
+    let Some((index, channel, split)) = boxes
+      .iter()
+      .enumerate()
+      .filter_map(|(index, pixels)| {
+        let (channel, min, max) = widest_channel(pixels);
+        (max > min).then(|| {
+          (
+            index,
+            channel,
+            min.midpoint(max),
+            (max - min) as usize * pixels.len(),
+          )
+        })
+      })
+      .max_by_key(|(.., weight)| *weight)
+      .map(|(index, channel, split, _)| (index, channel, split))
+    else {
+      break;
+    };
+
+    let (lower, upper) = boxes
+      .swap_remove(index)
+      .into_iter()
+      .partition(|pixel| pixel[channel] <= split);
+
+    boxes.push(lower);
+    boxes.push(upper);
+  }
+
+  boxes.sort_by_key(|pixels| std::cmp::Reverse(pixels.len()));
+
+  boxes.iter().map(|pixels| average(pixels)).collect()
+}
+
+/// Returns the distance between sampled pixels along each axis, or `None` if a buffer of `len`
+/// bytes can't hold `width * height` RGBA pixels.
+fn sample_step(width: u32, height: u32, len: usize) -> Option<u32> {
+  let pixel_count = (width as usize).checked_mul(height as usize)?;
+
+  if pixel_count.checked_mul(4)? > len {
+    return None;
+  }
+
+  Some((pixel_count as f64 / SAMPLE_PIXELS as f64).sqrt().max(1.0) as u32)
+}
+
+/// Returns the channel with the largest range and its bounds.
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8, u8) {
+  (0..3)
+    .map(|channel| {
+      let (min, max) = pixels.iter().fold((u8::MAX, u8::MIN), |(min, max), pixel| {
+        (min.min(pixel[channel]), max.max(pixel[channel]))
+      });
+
+      (channel, min, max)
+    })
+    .max_by_key(|(_, min, max)| max.saturating_sub(*min))
+    .unwrap_or_default()
+}
+
+fn average(pixels: &[[u8; 3]]) -> Color {
+  let sum = pixels.iter().fold([0u64; 3], |mut sum, pixel| {
+    for channel in 0..3 {
+      sum[channel] += pixel[channel] as u64;
+    }
+    sum
+  });
+  let len = pixels.len().max(1) as u64;
+
+  Color([
+    (sum[0] / len) as u8,
+    (sum[1] / len) as u8,
+    (sum[2] / len) as u8,
+    255,
+  ])
+}
+
+impl ImageSource {
+  /// Extracts up to `count` representative colors from the image, most common first.
+  ///
+  /// SVG images are rasterized at a small size first.
+  pub fn palette(&self, count: usize) -> Result<Vec<Color>, ImageResourceError> {
+    Ok(extract_palette(&*self.sample_bitmap()?, count))
+  }
+
+  /// Returns the bitmap to sample colors from, rasterizing SVG images at a small size.
+  pub(crate) fn sample_bitmap(&self) -> Result<Cow<'_, RgbaImage>, ImageResourceError> {
+    match self {
+      ImageSource::Bitmap(bitmap) | ImageSource::ScaledBitmap { bitmap, .. } => {
+        Ok(Cow::Borrowed(bitmap))
+      }
+      #[cfg(feature = "svg")]
+      ImageSource::Svg { tree, .. } => {
+        let size = tree.size();
+        let scale = (64.0 / size.width().max(size.height())).min(1.0);
+        self.render_to_rgba_image(
+          (size.width() * scale).ceil().max(1.0) as u32,
+          (size.height() * scale).ceil().max(1.0) as u32,
+          crate::layout::style::ImageScalingAlgorithm::Auto,
+          Color::black(),
+        )
+      }
+    }
+  }
+}
+
+#[derive(Debug)]
+struct LazyPalette {
+  image: Arc<ImageSource>,
+  colors: OnceLock<Vec<Color>>,
+}
+
+/// Images referenced by `palette()` in styles, with their palettes extracted on first use.
+#[derive(Debug, Default)]
+pub(crate) struct ImagePalettes {
+  images: HashMap<Arc<str>, LazyPalette>,
+}
+
+impl PartialEq for ImagePalettes {
+  fn eq(&self, other: &Self) -> bool {
+    self.images.len() == other.images.len()
+      && self.images.iter().all(|(src, palette)| {
+        other
+          .images
+          .get(src)
+          .is_some_and(|other| Arc::ptr_eq(&palette.image, &other.image))
+      })
+  }
+}
+
+impl ImagePalettes {
+  pub(crate) fn new(images: impl IntoIterator<Item = (Arc<str>, Arc<ImageSource>)>) -> Self {
+    Self {
+      images: images
+        .into_iter()
+        .map(|(src, image)| {
+          (
+            src,
+            LazyPalette {
+              image,
+              colors: OnceLock::new(),
+            },
+          )
+        })
+        .collect(),
+    }
+  }
+
+  /// Returns the palette color at `index` for the image, or `None` if either is missing.
+  pub(crate) fn color(&self, src: &str, index: usize) -> Option<Color> {
+    let palette = self.images.get(src)?;
+
+    palette
+      .colors
+      .get_or_init(|| {
+        palette
+          .image
+          .palette(DEFAULT_PALETTE_SIZE)
+          .unwrap_or_default()
+      })
+      .get(index)
+      .copied()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use image::Rgba;
+
+  use super::*;
+
+  #[test]
+  fn sample_step_handles_huge_dimensions() {
+    assert_eq!(sample_step(64, 64, 64 * 64 * 4), Some(1));
+    assert_eq!(sample_step(640, 640, 640 * 640 * 4), Some(10));
+    assert_eq!(sample_step(640, 640, 640 * 640 * 4 - 1), None);
+    assert_eq!(sample_step(u32::MAX, u32::MAX, usize::MAX), None);
+    #[cfg(target_pointer_width = "64")]
+    assert_eq!(sample_step(70_000, 70_000, usize::MAX), Some(1093));
+  }
+
+  #[test]
+  fn orders_colors_by_population() {
+    let image = RgbaImage::from_fn(100, 100, |x, _| {
+      if x < 70 {
+        Rgba([200, 30, 30, 255])
+      } else {
+        Rgba([20, 40, 220, 255])
+      }
+    });
+
+    assert_eq!(
+      extract_palette(&image, 2),
+      vec![Color([200, 30, 30, 255]), Color([20, 40, 220, 255])]
+    );
+  }
+
+  #[test]
+  fn ignores_transparent_pixels() {
+    let image = RgbaImage::from_fn(10, 10, |x, _| {
+      if x < 8 {
+        Rgba([255, 255, 255, 0])
+      } else {
+        Rgba([10, 200, 10, 255])
+      }
+    });
+
+    assert_eq!(extract_palette(&image, 4), vec![Color([10, 200, 10, 255])]);
+    assert!(extract_palette(&RgbaImage::new(4, 4), 4).is_empty());
+  }
+
+  #[test]
+  fn looks_up_palette_colors_by_index() {
+    let palettes = ImagePalettes::new([(
+      Arc::from("solid"),
+      Arc::new(ImageSource::Bitmap(RgbaImage::from_pixel(
+        4,
+        4,
+        Rgba([1, 2, 3, 255]),
+      ))),
+    )]);
+
+    assert_eq!(palettes.color("solid", 0), Some(Color([1, 2, 3, 255])));
+    assert_eq!(palettes.color("solid", 1), None);
+    assert_eq!(palettes.color("missing", 0), None);
+  }
+}
@@ -0,0 +1,240 @@
+use std::f32::consts::PI;
+
+use image::{RgbaImage, imageops};
+
+use crate::{
+  layout::style::Color,
+  resources::{
+    image::{ImageResourceError, ImageSource},
+    palette::extract_palette,
+  },
+};
+
+/// The number of horizontal and vertical blurhash components used when they aren't given.
+pub const DEFAULT_BLURHASH_COMPONENTS: (u32, u32) = (4, 3);
+
+/// Images are shrunk so their longer side is at most this many pixels before encoding a blurhash.
+const BLURHASH_SAMPLE_SIZE: u32 = 32;
+
+const BASE83: &[u8; 83] =
+  b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+/// The kind of placeholder generated by [`ImageSource::placeholder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlaceholderKind {
+  /// The dominant color, as a CSS color.
+  #[default]
+  Solid,
+  /// A top to bottom CSS `linear-gradient()` between the dominant colors of each half.
+  Gradient,
+  /// A [blurhash](https://blurha.sh) string with the given number of components, each clamped to `1..=9`.
+  Blurhash {
+    /// The number of horizontal components.
+    components_x: u32,
+    /// The number of vertical components.
+    components_y: u32,
+  },
+}
+
+impl ImageSource {
+  /// Generates a tiny placeholder to show while the image, or a render using it, is loading.
+  ///
+  /// Fully transparent images produce `transparent` for the CSS kinds.
+  pub fn placeholder(&self, kind: PlaceholderKind) -> Result<String, ImageResourceError> {
+    let image = self.sample_bitmap()?;
+
+    Ok(match kind {
+      PlaceholderKind::Solid => dominant_color(&image),
+      PlaceholderKind::Gradient => {
+        let half = image.height().div_ceil(2);
+        let top = imageops::crop_imm(&*image, 0, 0, image.width(), half).to_image();
+        let bottom =
+          imageops::crop_imm(&*image, 0, image.height() - half, image.width(), half).to_image();
+
+        format!(
+          "linear-gradient(to bottom, {}, {})",
+          dominant_color(&top),
+          dominant_color(&bottom)
+        )
+      }
+      PlaceholderKind::Blurhash {
+        components_x,
+        components_y,
+      } => encode_blurhash(&image, components_x, components_y),
+    })
+  }
+}
+
+fn dominant_color(image: &RgbaImage) -> String {
+  extract_palette(image, 1)
+    .first()
+    .map_or_else(|| "transparent".to_owned(), Color::to_string)
+}
+
+/// Encodes an image into a blurhash, ignoring alpha.
+pub fn encode_blurhash(image: &RgbaImage, components_x: u32, components_y: u32) -> String {
+  let components_x = components_x.clamp(1, 9);
+  let components_y = components_y.clamp(1, 9);
+
+  if image.width() == 0 || image.height() == 0 {
+    return String::new();
+  }
+
+  let image = if image.width().max(image.height()) > BLURHASH_SAMPLE_SIZE {
+    imageops::thumbnail(image, BLURHASH_SAMPLE_SIZE, BLURHASH_SAMPLE_SIZE)
+  } else {
+    image.clone()
+  };
+
+  let (width, height) = image.dimensions();
+  let linear = image
+    .pixels()
+    .map(|pixel| {
+      [
+        srgb_to_linear(pixel[0]),
+        srgb_to_linear(pixel[1]),
+        srgb_to_linear(pixel[2]),
+      ]
+    })
+    .collect::<Vec<_>>();
+
+  let factors = (0..components_y)
+    .flat_map(|j| (0..components_x).map(move |i| (i, j)))
+    .map(|(i, j)| {
+      let normalisation = if i == 0 && j == 0 { 1.0 } else { 2.0 };
+      let mut factor = [0.0f32; 3];
+
+      for y in 0..height {
+        let basis_y = (PI * j as f32 * y as f32 / height as f32).cos();
+
+        for x in 0..width {
+          let basis = basis_y * (PI * i as f32 * x as f32 / width as f32).cos();
+          let pixel = linear[(y * width + x) as usize];
+
+          for channel in 0..3 {
+            factor[channel] += basis * pixel[channel];
+          }
+        }
+      }
+
+      factor.map(|value| value * normalisation / (width * height) as f32)
+    })
+    .collect::<Vec<_>>();
+
+  let mut hash = String::with_capacity(4 + 2 * factors.len());
+  encode_base83((components_x - 1) + (components_y - 1) * 9, 1, &mut hash);
+
+  let (dc, ac) = factors.split_at(1);
+
+  let max_value = if ac.is_empty() {
+    encode_base83(0, 1, &mut hash);
+    1.0
+  } else {
+    let actual_max = ac
+      .iter()
+      .flatten()
+      .fold(0.0f32, |max, value| max.max(value.abs()));
+    let quantised_max = (actual_max * 166.0 - 0.5).floor().clamp(0.0, 82.0) as u32;
+
+    encode_base83(quantised_max, 1, &mut hash);
+    (quantised_max + 1) as f32 / 166.0
+  };
+
+  let [r, g, b] = dc[0].map(linear_to_srgb);
+  encode_base83((r << 16) + (g << 8) + b, 4, &mut hash);
+
+  for factor in ac {
+    let [r, g, b] = factor.map(|value| {
+      let value = value / max_value;
+      let value = value.signum() * value.abs().sqrt();
+      (value * 9.0 + 9.5).floor().clamp(0.0, 18.0) as u32
+    });
+
+    encode_base83(r * 19 * 19 + g * 19 + b, 2, &mut hash);
+  }
+
+  hash
+}
+
+fn encode_base83(value: u32, length: u32, hash: &mut String) {
+  for digit in (0..length).rev() {
+    let index = (value / 83u32.pow(digit)) % 83;
+    hash.push(BASE83[index as usize] as char);
+  }
+}
+
+fn srgb_to_linear(value: u8) -> f32 {
+  let value = value as f32 / 255.0;
+
+  if value <= 0.04045 {
+    value / 12.92
+  } else {
+    ((value + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+fn linear_to_srgb(value: f32) -> u32 {
+  let value = value.clamp(0.0, 1.0);
+
+  if value <= 0.003_130_8 {
+    (value * 12.92 * 255.0 + 0.5) as u32
+  } else {
+    ((1.055 * value.powf(1.0 / 2.4) - 0.055) * 255.0 + 0.5) as u32
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use image::Rgba;
+
+  use super::*;
+
+  #[test]
+  fn encodes_solid_blurhash() {
+    let image = RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]));
+
+    assert_eq!(encode_blurhash(&image, 1, 1), "00TI:j");
+    assert_eq!(
+      encode_blurhash(&image, 4, 3),
+      "LfTI:j|cfQ|c|csUfQsUfQfQfQfQ"
+    );
+  }
+
+  #[test]
+  fn encodes_two_tone_blurhash() {
+    let image = RgbaImage::from_fn(10, 10, |_, y| {
+      if y < 5 {
+        Rgba([200, 30, 30, 255])
+      } else {
+        Rgba([20, 40, 220, 255])
+      }
+    });
+
+    assert_eq!(
+      encode_blurhash(&image, 4, 3),
+      "L.G;#wsrfQsr|BsRfQsR#%jrfQjr"
+    );
+  }
+
+  #[test]
+  fn generates_css_placeholders() {
+    let image = ImageSource::Bitmap(RgbaImage::from_fn(10, 10, |_, y| {
+      if y < 5 {
+        Rgba([200, 30, 30, 255])
+      } else {
+        Rgba([20, 40, 220, 255])
+      }
+    }));
+
+    assert_eq!(
+      image.placeholder(PlaceholderKind::Gradient).ok(),
+      Some("linear-gradient(to bottom, rgb(200, 30, 30), rgb(20, 40, 220))".to_owned())
+    );
+    assert_eq!(
+      ImageSource::Bitmap(RgbaImage::new(4, 4))
+        .placeholder(PlaceholderKind::Solid)
+        .ok(),
+      Some("transparent".to_owned())
+    );
+  }
+}
@@ -11,8 +11,12 @@ pub type FetchTask = Arc<str>;
 pub struct FetchTaskCollection(SmallVec<[FetchTask; 8]>);
 
 impl FetchTaskCollection {
-  /// Insert a new task.
+  /// Insert a new task. `data:` URIs are decoded inline and never need fetching, so they are skipped.
   pub fn insert(&mut self, task: FetchTask) {
+    if task.starts_with("data:") {
+      return;
+    }
+
     if !self.0.contains(&task) {
       self.0.push(task);
     }
@@ -10,6 +10,8 @@ pub mod deep_nesting;
 pub mod inline;
 #[path = "fixtures/inline_vertical_align.rs"]
 pub mod inline_vertical_align;
+#[path = "fixtures/markdown.rs"]
+pub mod markdown;
 #[path = "fixtures/style_alignment.rs"]
 pub mod style_alignment;
 #[path = "fixtures/style_backdrop_filter.rs"]
@@ -40,12 +42,16 @@ pub mod style_overflow;
 pub mod style_padding;
 #[path = "fixtures/style_position.rs"]
 pub mod style_position;
+#[path = "fixtures/style_rhythmic_sizing.rs"]
+pub mod style_rhythmic_sizing;
 #[path = "fixtures/style_sizing.rs"]
 pub mod style_sizing;
 #[path = "fixtures/style_spacing.rs"]
 pub mod style_spacing;
 #[path = "fixtures/style_text_decoration.rs"]
 pub mod style_text_decoration;
+#[path = "fixtures/style_text_decoration_style.rs"]
+pub mod style_text_decoration_style;
 #[path = "fixtures/style_text_decoration_thickness.rs"]
 pub mod style_text_decoration_thickness;
 #[path = "fixtures/style_transform.rs"]
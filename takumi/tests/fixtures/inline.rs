@@ -84,7 +84,7 @@ fn inline_image() {
           BackgroundImages::from_str("linear-gradient(to right, red, blue)").ok(),
         ))
         .with(StyleDeclaration::background_clip(
-          BackgroundClip::BorderArea,
+          [BackgroundClip::BorderArea].into(),
         )),
     ),
     Node::text(" After".to_string())
@@ -590,3 +590,60 @@ fn inline_text_decorations() {
 
   run_fixture_test(node, "inline_text_decorations");
 }
+
+#[test]
+fn inline_opacity_group() {
+  let faded = Node::container([
+    Node::text("Overlapping ".to_string())
+      .with_style(Style::default().with(StyleDeclaration::display(Display::Inline))),
+    Node::text("glyphs".to_string()).with_style(
+      Style::default()
+        .with(StyleDeclaration::display(Display::Inline))
+        .with(StyleDeclaration::color(ColorInput::Value(Color([
+          0, 0, 255, 255,
+        ]))))
+        .with_text_decoration(
+          TextDecoration::builder()
+            .line(TextDecorationLines::UNDERLINE | TextDecorationLines::LINE_THROUGH)
+            .thickness(TextDecorationThickness::Length(Px(12.0)))
+            .build(),
+        ),
+    ),
+    Node::text("box".to_string()).with_style(
+      Style::default()
+        .with(StyleDeclaration::display(Display::InlineBlock))
+        .with(StyleDeclaration::background_color(ColorInput::Value(
+          Color([255, 200, 0, 255]),
+        )))
+        .with_padding(Sides([Px(8.0); 4])),
+    ),
+  ])
+  .with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Inline))
+      .with(StyleDeclaration::color(ColorInput::Value(Color([
+        255, 0, 0, 255,
+      ]))))
+      .with(StyleDeclaration::letter_spacing(Px(-12.0).into()))
+      .with(StyleDeclaration::opacity(PercentageNumber(0.5))),
+  );
+
+  let node = Node::container([
+    Node::text("Grouped: ".to_string())
+      .with_style(Style::default().with(StyleDeclaration::display(Display::Inline))),
+    faded,
+  ])
+  .with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Block))
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::height(Percentage(100.0)))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color::white(),
+      )))
+      .with_padding(Sides([Px(40.0); 4]))
+      .with(StyleDeclaration::font_size(Px(96.0).into())),
+  );
+
+  run_fixture_test(node, "inline_opacity_group");
+}
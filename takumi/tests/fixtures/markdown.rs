@@ -0,0 +1,46 @@
+use takumi::layout::{
+  node::{MarkdownData, Node},
+  style::{Length::*, *},
+};
+
+use crate::test_utils::run_fixture_test;
+
+const CHANGELOG: &str = r#"# Takumi 1.2
+
+Render **markdown** straight to images, with *emphasis*, ~~old syntax~~, `inline code` and [links](https://takumi.kane.tw).
+
+## Highlights
+
+- Headings, paragraphs and lists get sensible default styles
+- Generated nodes carry their HTML tag names, so stylesheets can restyle them
+
+1. Parse the changelog
+2. Render the OG image
+
+> Changelog and blog images are nearly always written in markdown.
+
+```
+takumi render changelog.md
+```"#;
+
+#[test]
+fn test_markdown() {
+  let markdown = Node::markdown(MarkdownData::new(CHANGELOG)).with_style(
+    Style::default()
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::height(Percentage(100.0)))
+      .with(StyleDeclaration::padding_top(Px(40.0)))
+      .with(StyleDeclaration::padding_bottom(Px(40.0)))
+      .with(StyleDeclaration::padding_left(Px(56.0)))
+      .with(StyleDeclaration::padding_right(Px(56.0)))
+      .with(StyleDeclaration::font_size(Px(24.0).into()))
+      .with(StyleDeclaration::color(ColorInput::Value(Color([
+        17, 24, 39, 255,
+      ]))))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color::white(),
+      ))),
+  );
+
+  run_fixture_test(markdown, "markdown");
+}
@@ -19,7 +19,7 @@ fn create_container_with_background_clip(
       .with(StyleDeclaration::background_color(ColorInput::Value(
         background_color,
       )))
-      .with(StyleDeclaration::background_clip(background_clip))
+      .with(StyleDeclaration::background_clip([background_clip].into()))
       .with_padding(Sides([Px(padding); 4]))
       .with_border_width(Sides([Px(border_width); 4]))
       .with(StyleDeclaration::border_style(BorderStyle::Solid))
@@ -99,7 +99,9 @@ fn test_style_background_clip_text_gradient() {
       .with(StyleDeclaration::background_repeat(
         BackgroundRepeats::from_str("no-repeat").unwrap(),
       ))
-      .with(StyleDeclaration::background_clip(BackgroundClip::Text))
+      .with(StyleDeclaration::background_clip(
+        [BackgroundClip::Text].into(),
+      ))
       .with(StyleDeclaration::color(ColorInput::Value(
         Color::transparent(),
       ))),
@@ -120,6 +122,58 @@ fn test_style_background_clip_text_gradient() {
   run_fixture_test(container, "style_background_clip_text_gradient");
 }
 
+#[test]
+fn test_style_background_clip_text_decoration() {
+  let gradient_images = BackgroundImages::from_str(
+    "linear-gradient(90deg, #ff3b30, #ffcc00, #34c759, #007aff, #5856d6)",
+  )
+  .unwrap();
+
+  let make_line = |label: &str, style: TextDecorationStyle| -> Node {
+    Node::text(label.to_string()).with_style(
+      Style::default()
+        .with(StyleDeclaration::background_image(Some(
+          gradient_images.clone(),
+        )))
+        .with(StyleDeclaration::background_clip(
+          [BackgroundClip::Text].into(),
+        ))
+        .with(StyleDeclaration::color(ColorInput::Value(
+          Color::transparent(),
+        )))
+        .with_text_decoration(
+          TextDecoration::builder()
+            .line(TextDecorationLines::UNDERLINE)
+            .style(style)
+            .thickness(TextDecorationThickness::Length(Px(6.0)))
+            .build(),
+        ),
+    )
+  };
+
+  let container = Node::container([
+    make_line("Gradient Underline", TextDecorationStyle::Solid),
+    make_line("Gradient Wave", TextDecorationStyle::Wavy),
+  ])
+  .with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::flex_direction(FlexDirection::Column))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color([240, 240, 240, 255]),
+      )))
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::height(Percentage(100.0)))
+      .with(StyleDeclaration::font_size(Px(72.0).into()))
+      .with(StyleDeclaration::align_items(AlignItems::Center))
+      .with(StyleDeclaration::justify_content(
+        JustifyContent::SpaceEvenly,
+      )),
+  );
+
+  run_fixture_test(container, "style_background_clip_text_decoration");
+}
+
 #[test]
 fn test_style_background_clip_text_radial_gradient() {
   let gradient_images =
@@ -135,7 +189,9 @@ fn test_style_background_clip_text_radial_gradient() {
       .with(StyleDeclaration::background_position(
         BackgroundPositions::from_str("center center").unwrap(),
       ))
-      .with(StyleDeclaration::background_clip(BackgroundClip::Text))
+      .with(StyleDeclaration::background_clip(
+        [BackgroundClip::Text].into(),
+      ))
       .with(StyleDeclaration::color(ColorInput::Value(
         Color::transparent(),
       ))),
@@ -168,7 +224,7 @@ fn test_style_background_clip_border_area() {
         Color([255, 165, 0, 255]),
       )))
       .with(StyleDeclaration::background_clip(
-        BackgroundClip::BorderArea,
+        [BackgroundClip::BorderArea].into(),
       ))
       .with_padding(Sides([Px(20.0); 4]))
       .with_border_width(Sides([Px(10.0); 4]))
@@ -210,7 +266,7 @@ fn test_style_background_clip_with_gradient_background() {
         BackgroundPositions::from_str("center center").unwrap(),
       ))
       .with(StyleDeclaration::background_clip(
-        BackgroundClip::PaddingBox,
+        [BackgroundClip::PaddingBox].into(),
       ))
       .with_padding(Sides([Px(30.0); 4]))
       .with_border_width(Sides([Px(15.0); 4]))
@@ -247,7 +303,7 @@ fn test_style_background_clip_text_multiline() {
             .with(StyleDeclaration::background_position(
               BackgroundPositions::from_str("center center").unwrap(),
             ))
-            .with(StyleDeclaration::background_clip(BackgroundClip::Text))
+            .with(StyleDeclaration::background_clip([BackgroundClip::Text].into()))
             .with(StyleDeclaration::color(ColorInput::Value(Color::transparent())))
             .with(StyleDeclaration::width(Percentage(100.0))),)
 
@@ -281,7 +337,9 @@ fn test_style_background_clip_comparison() {
         .with(StyleDeclaration::background_color(ColorInput::Value(
           Color([255, 0, 0, 255]),
         )))
-        .with(StyleDeclaration::background_clip(BackgroundClip::BorderBox))
+        .with(StyleDeclaration::background_clip(
+          [BackgroundClip::BorderBox].into(),
+        ))
         .with_padding(Sides([Px(15.0); 4]))
         .with_border_width(Sides([Px(8.0); 4]))
         .with(StyleDeclaration::border_style(BorderStyle::Solid))
@@ -305,7 +363,7 @@ fn test_style_background_clip_comparison() {
           Color([0, 128, 255, 255]),
         )))
         .with(StyleDeclaration::background_clip(
-          BackgroundClip::PaddingBox,
+          [BackgroundClip::PaddingBox].into(),
         ))
         .with_padding(Sides([Px(15.0); 4]))
         .with_border_width(Sides([Px(8.0); 4]))
@@ -330,7 +388,7 @@ fn test_style_background_clip_comparison() {
           Color([34, 197, 94, 255]),
         )))
         .with(StyleDeclaration::background_clip(
-          BackgroundClip::ContentBox,
+          [BackgroundClip::ContentBox].into(),
         ))
         .with_padding(Sides([Px(15.0); 4]))
         .with_border_width(Sides([Px(8.0); 4]))
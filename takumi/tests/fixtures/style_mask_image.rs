@@ -160,3 +160,27 @@ fn test_style_mask_image_corner_fade() {
 
   run_fixture_test(container, "style_mask_image_corner_fade");
 }
+
+#[test]
+fn test_style_mask_mode_luminance() {
+  let mask_image = BackgroundImages::from_str(
+    "linear-gradient(to right, black, white), radial-gradient(circle, black 30%, transparent 30%)",
+  )
+  .unwrap();
+
+  let container = Node::container([]).with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::height(Percentage(100.0)))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color([99, 102, 241, 255]),
+      )))
+      .with(StyleDeclaration::mask_image(Some(mask_image)))
+      .with(StyleDeclaration::mask_mode(
+        MaskModes::from_str("luminance, alpha").unwrap(),
+      )),
+  );
+
+  run_fixture_test(container, "style_mask_mode_luminance");
+}
@@ -134,3 +134,129 @@ fn test_text_overflow_mixed_axes() {
 
   run_fixture_test(container, "style_overflow_hidden_visible_text");
 }
+
+#[test]
+fn test_overflow_rounded_transformed_children() {
+  let child = |style: Style| {
+    Node::image("assets/images/yeecord.png").with_style(
+      style
+        .with(StyleDeclaration::width(Px(300.0)))
+        .with(StyleDeclaration::height(Px(300.0))),
+    )
+  };
+
+  let card = |child: Node| {
+    Node::container([child]).with_style(
+      Style::default()
+        .with(StyleDeclaration::display(Display::Flex))
+        .with(StyleDeclaration::align_items(AlignItems::Center))
+        .with(StyleDeclaration::justify_content(JustifyContent::Center))
+        .with(StyleDeclaration::width(Px(200.0)))
+        .with(StyleDeclaration::height(Px(200.0)))
+        .with(StyleDeclaration::rotate(Some(Angle::new(15.0))))
+        .with_border_radius(Box::new(BorderRadius(Sides(
+          [SpacePair::from_single(Px(48.0)); 4],
+        ))))
+        .with_border_width(Sides([Px(4.0); 4]))
+        .with(StyleDeclaration::border_style(BorderStyle::Solid))
+        .with(StyleDeclaration::border_color(
+          Color([255, 0, 0, 255]).into(),
+        ))
+        .with_overflow(SpacePair::from_single(Overflow::Hidden)),
+    )
+  };
+
+  let container = Node::container([
+    card(child(
+      Style::default().with(StyleDeclaration::rotate(Some(Angle::new(30.0)))),
+    )),
+    card(child(
+      Style::default().with(StyleDeclaration::filter(vec![Filter::Blur(Px(12.0))])),
+    )),
+  ])
+  .with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::height(Percentage(100.0)))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color::white(),
+      )))
+      .with(StyleDeclaration::align_items(AlignItems::Center))
+      .with(StyleDeclaration::justify_content(
+        JustifyContent::SpaceEvenly,
+      )),
+  );
+
+  run_fixture_test(container, "style_overflow_rounded_transformed_children");
+}
+
+#[test]
+fn style_overflow_fade_edges() {
+  let chip = |label: &str| {
+    Node::text(label.to_string()).with_style(
+      Style::default()
+        .with(StyleDeclaration::flex_shrink(Some(FlexGrow(0.0))))
+        .with(StyleDeclaration::font_size(Px(28.0).into()))
+        .with(StyleDeclaration::color(ColorInput::Value(Color([
+          30, 41, 59, 255,
+        ]))))
+        .with(StyleDeclaration::background_color(ColorInput::Value(
+          Color([224, 231, 255, 255]),
+        )))
+        .with_padding(Sides([Px(8.0), Px(20.0), Px(8.0), Px(20.0)]))
+        .with_border_radius(Box::new(BorderRadius::from_str("9999px").unwrap())),
+    )
+  };
+
+  // Only the first row overflows, so the second one is not faded.
+  let row = |labels: &[&str]| {
+    Node::container(labels.iter().map(|label| chip(label)).collect::<Vec<_>>()).with_style(
+      Style::default()
+        .with(StyleDeclaration::display(Display::Flex))
+        .with(StyleDeclaration::column_gap(Px(12.0)))
+        .with(StyleDeclaration::width(Px(560.0)))
+        .with(StyleDeclaration::fade_edges(
+          FadeEdges::from_str("x 96px").unwrap(),
+        ))
+        .with_overflow(SpacePair::from_single(Overflow::Hidden)),
+    )
+  };
+
+  let card = Node::container([
+    row(&[
+      "Rust",
+      "TypeScript",
+      "WebAssembly",
+      "Node.js",
+      "Edge",
+      "Images",
+    ]),
+    row(&["Rust", "TypeScript"]),
+  ])
+  .with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::flex_direction(FlexDirection::Column))
+      .with(StyleDeclaration::row_gap(Px(24.0)))
+      .with_padding(Sides([Px(32.0); 4]))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color::white(),
+      )))
+      .with_border_radius(Box::new(BorderRadius::from_str("24px").unwrap())),
+  );
+
+  let container = Node::container([card]).with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::height(Percentage(100.0)))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color([241, 245, 249, 255]),
+      )))
+      .with(StyleDeclaration::align_items(AlignItems::Center))
+      .with(StyleDeclaration::justify_content(JustifyContent::Center)),
+  );
+
+  run_fixture_test(container, "style_overflow_fade_edges");
+}
@@ -0,0 +1,68 @@
+use takumi::layout::{
+  node::Node,
+  style::{Length::*, *},
+};
+
+use crate::test_utils::run_fixture_test;
+
+#[test]
+fn test_style_rhythmic_sizing() {
+  let block = |font_size: f32, text: &str| {
+    Node::text(text.to_string()).with_style(
+      Style::default()
+        .with(StyleDeclaration::display(Display::Block))
+        .with(StyleDeclaration::font_size(Px(font_size).into())),
+    )
+  };
+
+  let card = Node::container([block(
+    18.0,
+    "Cards round their height up to the grid with block-step-size.",
+  )])
+  .with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Block))
+      .with(StyleDeclaration::padding_top(Px(6.0)))
+      .with(StyleDeclaration::padding_bottom(Px(6.0)))
+      .with(StyleDeclaration::padding_left(Px(12.0)))
+      .with(StyleDeclaration::padding_right(Px(12.0)))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color([224, 231, 255, 200]),
+      )))
+      .with(StyleDeclaration::block_step_size(Some(Px(24.0)))),
+  );
+
+  let container = Node::container([
+    block(40.0, "Vertical Rhythm"),
+    block(
+      18.0,
+      "Every line box snaps to the 24px baseline grid with line-height-step, whatever its font size.",
+    ),
+    card,
+    block(28.0, "Mixed sizes stay on the grid"),
+  ])
+  .with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Block))
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::height(Percentage(100.0)))
+      .with(StyleDeclaration::padding_top(Px(24.0)))
+      .with(StyleDeclaration::padding_left(Px(48.0)))
+      .with(StyleDeclaration::padding_right(Px(48.0)))
+      .with(StyleDeclaration::line_height_step(Px(24.0)))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color([255, 255, 255, 255]),
+      )))
+      .with(StyleDeclaration::background_image(Some(
+        BackgroundImages::from_str(
+          "linear-gradient(to bottom, #94a3b8 1px, transparent 1px)",
+        )
+        .unwrap(),
+      )))
+      .with(StyleDeclaration::background_size(
+        BackgroundSizes::from_str("100% 24px").unwrap(),
+      )),
+  );
+
+  run_fixture_test(container, "style_rhythmic_sizing");
+}
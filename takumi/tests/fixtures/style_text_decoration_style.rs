@@ -0,0 +1,51 @@
+use takumi::layout::{
+  node::Node,
+  style::{Length::*, *},
+};
+
+use crate::test_utils::run_fixture_test;
+
+#[test]
+fn test_style_text_decoration_style() {
+  let make_line = |label: &str, style: TextDecorationStyle| -> Node {
+    Node::text(format!("{label}: parapsychologists")).with_style(
+      Style::default()
+        .with(StyleDeclaration::display(Display::Flex))
+        .with(StyleDeclaration::width(Percentage(100.0)))
+        .with(StyleDeclaration::display(Display::Block))
+        .with(StyleDeclaration::text_align(TextAlign::Center))
+        .with(StyleDeclaration::font_size(Px(48.0).into()))
+        .with_text_decoration(
+          TextDecoration::builder()
+            .line(TextDecorationLines::UNDERLINE | TextDecorationLines::LINE_THROUGH)
+            .style(style)
+            .color(ColorInput::Value(Color([255, 0, 0, 255])))
+            .thickness(TextDecorationThickness::Length(Px(3.0)))
+            .build(),
+        ),
+    )
+  };
+
+  let container = Node::container([
+    make_line("solid", TextDecorationStyle::Solid),
+    make_line("double", TextDecorationStyle::Double),
+    make_line("dotted", TextDecorationStyle::Dotted),
+    make_line("dashed", TextDecorationStyle::Dashed),
+    make_line("wavy", TextDecorationStyle::Wavy),
+  ])
+  .with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color([240, 240, 240, 255]),
+      )))
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::flex_direction(FlexDirection::Column))
+      .with(StyleDeclaration::row_gap(Px(20.0)))
+      .with(StyleDeclaration::padding_top(Px(40.0)))
+      .with(StyleDeclaration::padding_bottom(Px(40.0))),
+  );
+
+  run_fixture_test(container, "style_text_decoration_style");
+}
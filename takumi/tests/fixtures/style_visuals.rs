@@ -392,3 +392,88 @@ fn test_style_outline() {
 
   run_fixture_test(container, "style_outline");
 }
+
+#[test]
+fn test_style_corner_smoothing() {
+  let tile = |smoothing: f32| {
+    Node::container([]).with_style(
+      Style::default()
+        .with(StyleDeclaration::width(Px(160.0)))
+        .with(StyleDeclaration::height(Px(160.0)))
+        .with(StyleDeclaration::background_color(ColorInput::Value(
+          Color([59, 130, 246, 255]),
+        )))
+        .with_border_radius(Box::new(BorderRadius::from_str("48px").unwrap()))
+        .with(StyleDeclaration::corner_smoothing(CornerSmoothing(
+          smoothing,
+        )))
+        .with_border_width(Sides([Px(6.0); 4]))
+        .with(StyleDeclaration::border_style(BorderStyle::Solid))
+        .with(StyleDeclaration::border_color(ColorInput::Value(Color([
+          15, 23, 42, 255,
+        ]))))
+        .with(StyleDeclaration::box_shadow(
+          BoxShadows::from_str("0 8px 16px rgba(0, 0, 0, 0.35)").ok(),
+        )),
+    )
+  };
+
+  let container = Node::container([tile(0.0), tile(0.6), tile(1.0)]).with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::height(Percentage(100.0)))
+      .with(StyleDeclaration::align_items(AlignItems::Center))
+      .with(StyleDeclaration::justify_content(JustifyContent::Center))
+      .with_gap(SpacePair::from_single(Px(40.0)))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color::white(),
+      ))),
+  );
+
+  run_fixture_test(container, "style_corner_smoothing");
+}
+
+#[test]
+fn test_style_border_styles() {
+  let tile = |border_style: BorderStyle, radius: &str| {
+    Node::container([]).with_style(
+      Style::default()
+        .with(StyleDeclaration::width(Px(160.0)))
+        .with(StyleDeclaration::height(Px(120.0)))
+        .with(StyleDeclaration::background_color(ColorInput::Value(
+          Color([219, 234, 254, 255]),
+        )))
+        .with_border_radius(Box::new(BorderRadius::from_str(radius).unwrap()))
+        .with_border_width(Sides([Px(12.0); 4]))
+        .with(StyleDeclaration::border_style(border_style))
+        .with(StyleDeclaration::border_color(ColorInput::Value(Color([
+          220, 38, 38, 255,
+        ])))),
+    )
+  };
+
+  let container = Node::container([
+    tile(BorderStyle::Dashed, "0"),
+    tile(BorderStyle::Dotted, "0"),
+    tile(BorderStyle::Double, "0"),
+    tile(BorderStyle::Dashed, "32px"),
+    tile(BorderStyle::Dotted, "32px"),
+    tile(BorderStyle::Double, "32px"),
+  ])
+  .with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::flex_wrap(FlexWrap::Wrap))
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::height(Percentage(100.0)))
+      .with(StyleDeclaration::align_content(JustifyContent::Center))
+      .with(StyleDeclaration::justify_content(JustifyContent::Center))
+      .with_gap(SpacePair::from_single(Px(40.0)))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color::white(),
+      ))),
+  );
+
+  run_fixture_test(container, "style_border_styles");
+}
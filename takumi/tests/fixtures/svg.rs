@@ -158,3 +158,41 @@ fn test_twemoji_svg() {
 
   run_fixture_test(node, "svg_twemoji");
 }
+
+#[test]
+fn test_svg_node_object_fit() {
+  let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20" viewBox="0 0 40 20"><rect width="40" height="20" rx="6" fill="#3b82f6"/><circle cx="20" cy="10" r="8" fill="#f8fafc"/></svg>"##;
+
+  let tile = |object_fit: ObjectFit| {
+    Node::svg(svg).with_style(
+      Style::default()
+        .with(StyleDeclaration::width(Px(160.0)))
+        .with(StyleDeclaration::height(Px(160.0)))
+        .with(StyleDeclaration::object_fit(object_fit))
+        .with(StyleDeclaration::background_color(ColorInput::Value(
+          Color([226, 232, 240, 255]),
+        ))),
+    )
+  };
+
+  let node = Node::container([
+    tile(ObjectFit::Fill),
+    tile(ObjectFit::Contain),
+    tile(ObjectFit::Cover),
+    Node::svg(svg),
+  ])
+  .with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::height(Percentage(100.0)))
+      .with(StyleDeclaration::align_items(AlignItems::Center))
+      .with(StyleDeclaration::justify_content(JustifyContent::Center))
+      .with_gap(SpacePair::from_single(Px(24.0)))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color::white(),
+      ))),
+  );
+
+  run_fixture_test(node, "svg_node_object_fit");
+}
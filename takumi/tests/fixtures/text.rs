@@ -149,7 +149,7 @@ fn text_typography_letter_spacing_2px() {
         Color([240, 240, 240, 255]),
       )))
       .with(StyleDeclaration::font_size(Px(24.0).into()))
-      .with(StyleDeclaration::letter_spacing(Px(2.0))),
+      .with(StyleDeclaration::letter_spacing(Px(2.0).into())),
   );
 
   run_fixture_test(text, "text_typography_letter_spacing_2px");
@@ -319,7 +319,9 @@ fn text_mask_image_gradient_and_emoji() {
         .with(StyleDeclaration::background_repeat(
           BackgroundRepeats::from_str("no-repeat").unwrap(),
         ))
-        .with(StyleDeclaration::background_clip(BackgroundClip::Text))
+        .with(StyleDeclaration::background_clip(
+          [BackgroundClip::Text].into(),
+        ))
         .with(StyleDeclaration::color(ColorInput::Value(
           Color::transparent(),
         ))),
@@ -365,6 +367,71 @@ fn text_stroke_black_red() {
   run_fixture_test(text, "text_stroke_black_red");
 }
 
+#[test]
+fn text_stroke_image_gradient() {
+  let Ok(gradient) =
+    BackgroundImages::from_str("linear-gradient(90deg, #f43f5e, #f59e0b 50%, #8b5cf6)")
+  else {
+    unreachable!()
+  };
+
+  let text = Node::text("Gradient Outline".to_string()).with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::height(Percentage(100.0)))
+      .with(StyleDeclaration::align_items(AlignItems::Center))
+      .with(StyleDeclaration::justify_content(JustifyContent::Center))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color([17, 24, 39, 255]),
+      )))
+      .with(StyleDeclaration::color(ColorInput::Value(Color([
+        17, 24, 39, 255,
+      ]))))
+      .with(StyleDeclaration::font_size(Px(112.0).into()))
+      .with(StyleDeclaration::font_weight(FontWeight::from(800.0)))
+      .with(StyleDeclaration::webkit_text_stroke_width(Some(Px(6.0))))
+      .with(StyleDeclaration::text_stroke_image(Some(gradient))),
+  );
+
+  run_fixture_test(text, "text_stroke_image_gradient");
+}
+
+#[test]
+fn text_stroke_inline_spans() {
+  let span = |text: &str, width: f32, color: Color| {
+    Node::text(text.to_string()).with_style(
+      Style::default()
+        .with(StyleDeclaration::display(Display::Inline))
+        .with(StyleDeclaration::webkit_text_stroke_width(Some(Px(width))))
+        .with(StyleDeclaration::webkit_text_stroke_color(Some(
+          ColorInput::Value(color),
+        ))),
+    )
+  };
+
+  let container = Node::container([
+    span("Plain ", 0.0, Color::black()),
+    span("Outlined ", 3.0, Color([220, 38, 38, 255])),
+    span("Heavy", 8.0, Color([37, 99, 235, 255])),
+  ])
+  .with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Block))
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::height(Percentage(100.0)))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color([240, 240, 240, 255]),
+      )))
+      .with(StyleDeclaration::color(ColorInput::Value(Color::white())))
+      .with(StyleDeclaration::font_size(Px(96.0).into()))
+      .with(StyleDeclaration::font_weight(FontWeight::from(700.0)))
+      .with_padding(Sides([Px(24.0); 4])),
+  );
+
+  run_fixture_test(container, "text_stroke_inline_spans");
+}
+
 #[test]
 fn text_stroke_background_clip() {
   let gradient_images = BackgroundImages::from_str(
@@ -379,7 +446,9 @@ fn text_stroke_background_clip() {
       .with(StyleDeclaration::background_position(
         BackgroundPositions::from_str("center center").unwrap(),
       ))
-      .with(StyleDeclaration::background_clip(BackgroundClip::Text))
+      .with(StyleDeclaration::background_clip(
+        [BackgroundClip::Text].into(),
+      ))
       .with(StyleDeclaration::color(ColorInput::Value(Color::white())))
       .with(StyleDeclaration::font_size(Px(96.0).into()))
       .with(StyleDeclaration::font_weight(FontWeight::from(700.0)))
@@ -449,6 +518,104 @@ fn text_shadow_no_blur_radius() {
   run_fixture_test(text, "text_shadow_no_blur_radius");
 }
 
+#[test]
+fn text_shadow_inset() {
+  // inset 0 3px 2px rgba(0, 0, 0, 0.6), 0 1px 0 #ffffff
+  let shadows = [
+    TextShadow::builder()
+      .offset_y(Px(3.0))
+      .blur_radius(Px(2.0))
+      .color(ColorInput::Value(Color([0, 0, 0, 153])))
+      .inset(true)
+      .build(),
+    TextShadow::builder()
+      .offset_y(Px(1.0))
+      .color(ColorInput::Value(Color([255, 255, 255, 255])))
+      .build(),
+  ];
+
+  let text = Node::text("Letterpress".to_string()).with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color([210, 214, 220, 255]),
+      )))
+      .with(StyleDeclaration::color(ColorInput::Value(Color([
+        150, 158, 170, 255,
+      ]))))
+      .with(StyleDeclaration::font_size(Px(96.0).into()))
+      .with(StyleDeclaration::font_weight(FontWeight::from(700.0)))
+      .with(StyleDeclaration::text_shadow(Some(shadows.into()))),
+  );
+
+  run_fixture_test(text, "text_shadow_inset");
+}
+
+#[test]
+fn text_emboss() {
+  let line = |text: &str, emboss: TextEmboss| {
+    Node::text(text.to_string()).with_style(
+      Style::default()
+        .with(StyleDeclaration::display(Display::Block))
+        .with(StyleDeclaration::text_emboss(emboss)),
+    )
+  };
+
+  let container = Node::container([
+    line("Raised", TextEmboss::Raised(Px(3.0))),
+    line("Pressed", TextEmboss::Pressed(Px(3.0))),
+  ])
+  .with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::flex_direction(FlexDirection::Column))
+      .with(StyleDeclaration::width(Percentage(100.0)))
+      .with(StyleDeclaration::height(Percentage(100.0)))
+      .with(StyleDeclaration::align_items(AlignItems::Center))
+      .with(StyleDeclaration::justify_content(JustifyContent::Center))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color([210, 214, 220, 255]),
+      )))
+      .with(StyleDeclaration::color(ColorInput::Value(Color([
+        150, 158, 170, 255,
+      ]))))
+      .with(StyleDeclaration::font_size(Px(120.0).into()))
+      .with(StyleDeclaration::font_weight(FontWeight::from(700.0))),
+  );
+
+  run_fixture_test(container, "text_emboss");
+}
+
+#[test]
+fn text_shadow_multiple_layered() {
+  // 4px 4px 0 #ff3366, 8px 8px 0 #33ccff, 12px 12px 0 #ffcc00
+  let shadows = [
+    ([255, 51, 102, 255], 4.0),
+    ([51, 204, 255, 255], 8.0),
+    ([255, 204, 0, 255], 12.0),
+  ]
+  .map(|(color, offset)| {
+    TextShadow::builder()
+      .offset_x(Px(offset))
+      .offset_y(Px(offset))
+      .color(ColorInput::Value(Color(color)))
+      .build()
+  });
+
+  let text = Node::text("Layered".to_string()).with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color([240, 240, 240, 255]),
+      )))
+      .with(StyleDeclaration::font_size(Px(96.0).into()))
+      .with(StyleDeclaration::font_weight(FontWeight::from(700.0)))
+      .with(StyleDeclaration::text_shadow(Some(shadows.into()))),
+  );
+
+  run_fixture_test(text, "text_shadow_multiple_layered");
+}
+
 #[test]
 fn text_wrap_nowrap() {
   let long_text = "This is a very long piece of text that should demonstrate text wrapping behavior when it exceeds the container width. The quick brown fox jumps over the lazy dog.";
@@ -606,7 +773,9 @@ fn text_super_bold_stroke_background_clip() {
       .with(StyleDeclaration::background_position(
         BackgroundPositions::from_str("center center").unwrap(),
       ))
-      .with(StyleDeclaration::background_clip(BackgroundClip::Text))
+      .with(StyleDeclaration::background_clip(
+        [BackgroundClip::Text].into(),
+      ))
       .with(StyleDeclaration::display(Display::Block))
       .with(StyleDeclaration::color(ColorInput::Value(Color::white())))
       .with(StyleDeclaration::font_size(Px(120.0).into()))
@@ -934,3 +1103,32 @@ fn text_devanagari_noto_sans() {
 
   run_fixture_test(container, "text_devanagari_noto_sans");
 }
+
+#[test]
+fn text_word_break_url() {
+  let url = || {
+    Node::text(
+      "preview.takumi-renderer.kane50613.dev?utm_source=newsletter&utm_campaign=launch_week"
+        .to_string(),
+    )
+  };
+
+  let container = Node::container([
+    url().with_style(Style::default().with(StyleDeclaration::word_break(WordBreak::Normal))),
+    url().with_style(Style::default().with(StyleDeclaration::word_break(WordBreak::BreakUrl))),
+  ])
+  .with_style(
+    Style::default()
+      .with(StyleDeclaration::display(Display::Flex))
+      .with(StyleDeclaration::flex_direction(FlexDirection::Column))
+      .with(StyleDeclaration::background_color(ColorInput::Value(
+        Color::white(),
+      )))
+      .with(StyleDeclaration::font_size(Px(40.0).into()))
+      .with(StyleDeclaration::width(Px(480.0)))
+      .with_gap(SpacePair::from_single(Px(40.0)))
+      .with_padding(Sides([Px(20.0); 4])),
+  );
+
+  run_fixture_test(container, "text_word_break_url");
+}
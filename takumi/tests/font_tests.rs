@@ -2,11 +2,27 @@ use std::{
   fs::File,
   io::Read,
   path::{Path, PathBuf},
+  sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+  },
 };
 
 use takumi::{
   GlobalContext,
-  resources::font::{FontError, FontResource},
+  layout::{
+    Viewport,
+    node::{CustomCanvas, CustomData, CustomDraw, Node},
+    style::{
+      Color, ColorInput, Display, FlexDirection, FontFamily, FromCss, Length::Px, Style,
+      StyleDeclaration, TextAlign,
+    },
+  },
+  rendering::{
+    RenderOptions, SequentialScene, TextRenderingOptions, measure_layout, render, render_hash,
+    render_sequence_animation, warmup,
+  },
+  resources::font::{FontError, FontFormat, FontResource, MissingFontPolicy},
 };
 
 fn font_path(path: &str) -> PathBuf {
@@ -52,6 +68,41 @@ fn test_ttc_font_loading() {
   );
 }
 
+#[test]
+fn test_ttc_face_index() {
+  let mut font_data = Vec::new();
+  File::open(font_path("ubuntu/Ubuntu.ttc"))
+    .unwrap()
+    .read_to_end(&mut font_data)
+    .unwrap();
+
+  let mut context = GlobalContext::default();
+  context
+    .font_context_mut()
+    .load_and_store(FontResource::new(font_data.as_slice()).face_index(1))
+    .unwrap();
+
+  let loaded_faces: usize = context
+    .font_context_mut()
+    .collection
+    .family_names()
+    .map(str::to_owned)
+    .collect::<Vec<_>>()
+    .iter()
+    .filter_map(|name| context.font_context_mut().collection.family_by_name(name))
+    .map(|family| family.fonts().len())
+    .sum();
+  assert_eq!(loaded_faces, 1);
+
+  let result = context
+    .font_context_mut()
+    .load_and_store(FontResource::new(font_data.as_slice()).face_index(99));
+  assert!(matches!(
+    result,
+    Err(FontError::FaceIndexOutOfRange { index: 99, .. })
+  ));
+}
+
 #[test]
 fn test_woff2_font_loading() {
   let mut context = GlobalContext::default();
@@ -82,6 +133,38 @@ fn test_invalid_format_detection() {
   assert!(matches!(result, Err(FontError::UnsupportedFormat)));
 }
 
+#[test]
+fn test_invalid_font_data_names_format() {
+  let mut context = GlobalContext::default();
+
+  let result = context
+    .font_context_mut()
+    .load_and_store(FontResource::new(vec![0x00, 0x01, 0x00, 0x00, 0xff, 0xff]));
+  assert!(matches!(
+    result,
+    Err(FontError::InvalidFont(FontFormat::Ttf))
+  ));
+
+  let result = context
+    .font_context_mut()
+    .load_and_store(FontResource::new(&b"wOF2 truncated"[..]));
+  let Err(error) = result else {
+    panic!("truncated WOFF2 data loaded");
+  };
+  assert!(matches!(
+    error,
+    FontError::Woff {
+      format: FontFormat::Woff2,
+      ..
+    }
+  ));
+  assert!(
+    error
+      .to_string()
+      .starts_with("Failed to decompress WOFF2 font")
+  );
+}
+
 #[test]
 fn test_empty_data() {
   // Test with empty data
@@ -105,3 +188,527 @@ fn test_too_short_data() {
     .load_and_store(FontResource::new(short_data));
   assert!(matches!(result, Err(FontError::UnsupportedFormat)));
 }
+
+#[test]
+fn test_warmup_keeps_layout_identical() {
+  let mut font_data = Vec::new();
+  File::open(font_path("geist/Geist[wght].woff2"))
+    .unwrap()
+    .read_to_end(&mut font_data)
+    .unwrap();
+
+  let mut cold = GlobalContext::default();
+  cold
+    .font_context_mut()
+    .load_and_store(FontResource::new(font_data.clone()))
+    .unwrap();
+
+  let mut warm = GlobalContext::default();
+  warm
+    .font_context_mut()
+    .load_and_store(FontResource::new(font_data))
+    .unwrap();
+
+  let style = Style::default().with(StyleDeclaration::font_size(Px(48.0).into()));
+  warmup(&warm, &["Hello, world!"], std::slice::from_ref(&style)).unwrap();
+
+  let measure = |global: &GlobalContext| {
+    measure_layout(
+      RenderOptions::builder()
+        .global(global)
+        .viewport(Viewport::new((400, 200)))
+        .node(Node::text("Hello, world!").with_style(style.clone()))
+        .build(),
+    )
+    .unwrap()
+  };
+
+  assert_eq!(measure(&warm), measure(&cold));
+}
+
+#[test]
+fn test_fallback_chain_picks_registered_family() {
+  let read = |path: &str| {
+    let mut data = Vec::new();
+    File::open(font_path(path))
+      .unwrap()
+      .read_to_end(&mut data)
+      .unwrap();
+    data
+  };
+  let geist = read("geist/Geist[wght].woff2");
+  let noto = read("noto-sans/noto-sans-devanagari-v30-devanagari-regular.woff2");
+  let poppins = read("poppins/poppins-v24-devanagari_latin-regular.woff2");
+
+  let load = |fallback_for: Option<&str>| {
+    let mut global = GlobalContext::default();
+    let fonts = global.font_context_mut();
+    fonts
+      .load_and_store(FontResource::new(geist.as_slice()))
+      .unwrap();
+    fonts
+      .load_and_store(FontResource::new(noto.as_slice()))
+      .unwrap();
+
+    let poppins = FontResource::new(poppins.as_slice());
+    fonts
+      .load_and_store(match fallback_for {
+        Some(family) => poppins.fallback_for(family),
+        None => poppins,
+      })
+      .unwrap();
+
+    global
+  };
+
+  let measure = |global: &GlobalContext, font_family: &str| {
+    measure_layout(
+      RenderOptions::builder()
+        .global(global)
+        .viewport(Viewport::new((400, 200)))
+        .node(
+          Node::text("नमस्ते दुनिया").with_style(
+            Style::default()
+              .with(StyleDeclaration::font_size(Px(48.0).into()))
+              .with(StyleDeclaration::font_family(
+                FontFamily::from_str(font_family).unwrap(),
+              )),
+          ),
+        )
+        .build(),
+    )
+    .unwrap()
+  };
+
+  let chained = load(Some("Geist"));
+  let plain = load(None);
+
+  assert_eq!(
+    chained.font_context().fallbacks("geist"),
+    ["Poppins".into()]
+  );
+  assert_eq!(
+    measure(&chained, "Geist"),
+    measure(&plain, "Geist, Poppins")
+  );
+  assert_ne!(measure(&plain, "Geist"), measure(&plain, "Geist, Poppins"));
+}
+
+#[test]
+fn test_emoji_source_replaces_emoji_glyphs() {
+  let mut font_data = Vec::new();
+  File::open(font_path("geist/Geist[wght].woff2"))
+    .unwrap()
+    .read_to_end(&mut font_data)
+    .unwrap();
+
+  let mut global = GlobalContext::default();
+  global
+    .font_context_mut()
+    .load_and_store(FontResource::new(font_data))
+    .unwrap();
+
+  let loads = Arc::new(AtomicUsize::new(0));
+  let source_loads = loads.clone();
+  global.emoji_store_mut().set_source(move |emoji: &str| {
+    source_loads.fetch_add(1, Ordering::Relaxed);
+    (emoji == "\u{1F600}").then(|| {
+      br#"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="16"><rect width="16" height="16" fill="red"/></svg>"#.to_vec()
+    })
+  });
+
+  let node = Node::text("\u{1F600}\u{1F600}").with_style(
+    Style::default()
+      .with(StyleDeclaration::font_size(Px(40.0).into()))
+      .with(StyleDeclaration::line_height(Px(40.0).into())),
+  );
+
+  let image = render(
+    RenderOptions::builder()
+      .global(&global)
+      .viewport(Viewport::new((100, 40)))
+      .node(node)
+      .build(),
+  )
+  .unwrap();
+
+  assert_eq!(image.get_pixel(20, 20).0, [255, 0, 0, 255]);
+  assert_eq!(loads.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn test_sequence_animation_repaints_changed_text_like_full_renders() {
+  let mut font_data = Vec::new();
+  File::open(font_path("geist/Geist[wght].woff2"))
+    .unwrap()
+    .read_to_end(&mut font_data)
+    .unwrap();
+
+  let mut global = GlobalContext::default();
+  global
+    .font_context_mut()
+    .load_and_store(FontResource::new(font_data))
+    .unwrap();
+
+  let options = |count: &str| {
+    let text = |text: &str| {
+      Node::text(text.to_string()).with_style(
+        Style::default()
+          .with(StyleDeclaration::font_size(Px(24.0).into()))
+          .with(StyleDeclaration::text_align(TextAlign::Center))
+          .with(StyleDeclaration::color(ColorInput::Value(Color([
+            0, 0, 0, 255,
+          ])))),
+      )
+    };
+
+    RenderOptions::builder()
+      .global(&global)
+      .viewport(Viewport::new((120, 80)))
+      .node(
+        Node::container([text("Visitors"), text(count)]).with_style(
+          Style::default()
+            .with(StyleDeclaration::display(Display::Flex))
+            .with(StyleDeclaration::flex_direction(FlexDirection::Column))
+            .with(StyleDeclaration::width(Px(120.0)))
+            .with(StyleDeclaration::height(Px(80.0)))
+            .with(StyleDeclaration::background_color(ColorInput::Value(
+              Color([255, 255, 255, 255]),
+            ))),
+        ),
+      )
+      .build()
+  };
+
+  let counts = ["8", "9", "10", "10", "111"];
+  let scenes = counts
+    .iter()
+    .map(|count| {
+      SequentialScene::builder()
+        .duration_ms(100)
+        .options(options(count))
+        .build()
+    })
+    .collect::<Vec<_>>();
+
+  let frames = render_sequence_animation(&scenes, 10).unwrap();
+  assert_eq!(frames.len(), counts.len());
+
+  for (frame, count) in frames.iter().zip(counts) {
+    assert_eq!(
+      frame.image,
+      render(options(count)).unwrap(),
+      "frame {count} differs"
+    );
+  }
+}
+
+struct CaptionDraw;
+
+impl CustomDraw for CaptionDraw {
+  fn draw(&self, canvas: &mut CustomCanvas<'_>) -> takumi::Result<()> {
+    let caption = || {
+      Node::text("Caption").with_style(
+        Style::default()
+          .with(StyleDeclaration::font_size(Px(20.0).into()))
+          .with(StyleDeclaration::color(ColorInput::Value(Color([
+            255, 0, 0, 255,
+          ])))),
+      )
+    };
+
+    let measured = canvas.measure_text(caption(), None);
+    let drawn = canvas.draw_text(caption(), 10.0, 10.0, None)?;
+
+    assert!(measured.width > 0.0 && measured.height > 0.0);
+    assert_eq!(measured, drawn);
+
+    Ok(())
+  }
+}
+
+#[test]
+fn test_custom_canvas_draws_styled_text() {
+  let mut font_data = Vec::new();
+  File::open(font_path("geist/Geist[wght].woff2"))
+    .unwrap()
+    .read_to_end(&mut font_data)
+    .unwrap();
+
+  let mut global = GlobalContext::default();
+  global
+    .font_context_mut()
+    .load_and_store(FontResource::new(font_data))
+    .unwrap();
+
+  let image = render(
+    RenderOptions::builder()
+      .global(&global)
+      .viewport(Viewport::new((120, 40)))
+      .node(
+        Node::custom(CustomData::with_drawer(Arc::new(CaptionDraw))).with_style(
+          Style::default()
+            .with(StyleDeclaration::width(Px(120.0)))
+            .with(StyleDeclaration::height(Px(40.0))),
+        ),
+      )
+      .build(),
+  )
+  .unwrap();
+
+  let red_pixels = image
+    .pixels()
+    .filter(|pixel| pixel.0[0] > 200 && pixel.0[1] < 50 && pixel.0[3] > 200)
+    .count();
+
+  assert!(red_pixels > 20);
+  // Nothing is drawn left of the requested origin.
+  assert!((0..40).all(|y| (0..8).all(|x| image.get_pixel(x, y).0[3] == 0)));
+}
+
+#[test]
+fn test_missing_font_policy() {
+  let mut font_data = Vec::new();
+  File::open(font_path("geist/Geist[wght].woff2"))
+    .unwrap()
+    .read_to_end(&mut font_data)
+    .unwrap();
+
+  let global = GlobalContext::builder()
+    .font(FontResource::new(font_data))
+    .missing_font_policy(MissingFontPolicy::Error)
+    .build()
+    .unwrap();
+
+  let render_with = |global: &GlobalContext, policy: Option<MissingFontPolicy>| {
+    render(
+      RenderOptions::builder()
+        .global(global)
+        .viewport(Viewport::new((100, 40)))
+        .node(
+          Node::text("\u{4E2D}").with_style(
+            Style::default()
+              .with(StyleDeclaration::font_size(Px(40.0).into()))
+              .with(StyleDeclaration::line_height(Px(40.0).into())),
+          ),
+        )
+        .missing_font_policy(policy)
+        .build(),
+    )
+  };
+
+  assert!(matches!(
+    render_with(&global, None),
+    Err(takumi::Error::MissingGlyphs { text }) if text == "\u{4E2D}"
+  ));
+  assert!(matches!(
+    render_with(&GlobalContext::default(), Some(MissingFontPolicy::Error)),
+    Err(takumi::Error::MissingGlyphs { .. })
+  ));
+
+  let skipped = render_with(&global, Some(MissingFontPolicy::Skip)).unwrap();
+  assert!(skipped.pixels().all(|pixel| pixel.0[3] == 0));
+
+  let replaced = render_with(&global, Some(MissingFontPolicy::Replace)).unwrap();
+  assert!(replaced.pixels().any(|pixel| pixel.0[3] == 255));
+}
+
+#[test]
+fn test_text_rendering_options() {
+  let mut font_data = Vec::new();
+  File::open(font_path("geist/Geist[wght].woff2"))
+    .unwrap()
+    .read_to_end(&mut font_data)
+    .unwrap();
+
+  let mut global = GlobalContext::builder()
+    .font(FontResource::new(font_data))
+    .build()
+    .unwrap();
+
+  fn options(global: &GlobalContext) -> RenderOptions<'_> {
+    RenderOptions::builder()
+      .global(global)
+      .viewport(Viewport::new((120, 24)))
+      .node(
+        Node::text("Small text")
+          .with_style(Style::default().with(StyleDeclaration::font_size(Px(11.0).into()))),
+      )
+      .build()
+  }
+  let coverage = |global: &GlobalContext| {
+    render(options(global))
+      .unwrap()
+      .pixels()
+      .map(|pixel| pixel.0[3] as u64)
+      .sum::<u64>()
+  };
+
+  let linear = render(options(&global)).unwrap();
+  let linear_coverage = coverage(&global);
+  let linear_hash = render_hash(options(&global)).unwrap();
+
+  global.set_text_rendering(TextRenderingOptions {
+    gamma: 2.2,
+    ..Default::default()
+  });
+  assert!(coverage(&global) > linear_coverage);
+  assert_ne!(render_hash(options(&global)).unwrap(), linear_hash);
+
+  global.set_text_rendering(TextRenderingOptions {
+    hinting: true,
+    subpixel_positions: 1,
+    ..Default::default()
+  });
+  assert_ne!(render(options(&global)).unwrap(), linear);
+}
+
+#[test]
+fn test_unload_and_replace_font_family() {
+  let mut font_data = Vec::new();
+  File::open(font_path("geist/Geist[wght].woff2"))
+    .unwrap()
+    .read_to_end(&mut font_data)
+    .unwrap();
+
+  let mut global = GlobalContext::builder()
+    .font(FontResource::new(font_data))
+    .missing_font_policy(MissingFontPolicy::Error)
+    .build()
+    .unwrap();
+
+  let render_with = |global: &GlobalContext| {
+    render(
+      RenderOptions::builder()
+        .global(global)
+        .viewport(Viewport::new((200, 80)))
+        .node(
+          Node::text("Hi").with_style(
+            Style::default()
+              .with(StyleDeclaration::font_family(
+                FontFamily::from_str("Geist").unwrap(),
+              ))
+              .with(StyleDeclaration::font_size(Px(32.0).into())),
+          ),
+        )
+        .build(),
+    )
+  };
+
+  assert!(render_with(&global).is_ok());
+  assert!(global.cache_stats().glyphs.entries > 0);
+
+  assert!(global.unload_font_family("Geist"));
+  assert!(!global.unload_font_family("Geist"));
+  assert_eq!(global.cache_stats().glyphs.entries, 0);
+  assert!(matches!(
+    render_with(&global),
+    Err(takumi::Error::MissingGlyphs { .. })
+  ));
+
+  let mut replacement = Vec::new();
+  File::open(font_path("archivo/Archivo-VariableFont_wdth,wght.ttf"))
+    .unwrap()
+    .read_to_end(&mut replacement)
+    .unwrap();
+
+  global
+    .replace_font_family("Geist", FontResource::new(replacement))
+    .unwrap();
+
+  assert!(render_with(&global).is_ok());
+  let family = global
+    .font_context_mut()
+    .collection
+    .family_by_name("Geist")
+    .unwrap();
+  assert!(!family.fonts().is_empty());
+  assert!(
+    global
+      .replace_font_family("Geist", FontResource::new(&b"not a font"[..]))
+      .is_err()
+  );
+  assert!(render_with(&global).is_ok());
+}
+
+#[test]
+fn test_scoped_fonts() {
+  let mut font_data = Vec::new();
+  File::open(font_path("geist/Geist[wght].woff2"))
+    .unwrap()
+    .read_to_end(&mut font_data)
+    .unwrap();
+
+  let global = GlobalContext::builder()
+    .missing_font_policy(MissingFontPolicy::Error)
+    .build()
+    .unwrap();
+
+  let render_with = |global: &GlobalContext| {
+    render(
+      RenderOptions::builder()
+        .global(global)
+        .viewport(Viewport::new((200, 80)))
+        .node(
+          Node::text("Hi").with_style(
+            Style::default()
+              .with(StyleDeclaration::font_family(
+                FontFamily::from_str("Geist").unwrap(),
+              ))
+              .with(StyleDeclaration::font_size(Px(32.0).into())),
+          ),
+        )
+        .build(),
+    )
+  };
+
+  let scoped = global
+    .with_scoped_fonts([FontResource::new(font_data)])
+    .unwrap();
+
+  assert!(render_with(&scoped).is_ok());
+  assert!(global.cache_stats().glyphs.entries > 0);
+  assert!(matches!(
+    render_with(&global),
+    Err(takumi::Error::MissingGlyphs { .. })
+  ));
+
+  drop(scoped);
+  assert_eq!(global.cache_stats().glyphs.entries, 0);
+  assert!(
+    global
+      .with_scoped_fonts([FontResource::new(&b"not a font"[..])])
+      .is_err()
+  );
+}
+
+#[test]
+fn test_global_context_builder() {
+  let mut font_data = Vec::new();
+  File::open(font_path("geist/Geist[wght].woff2"))
+    .unwrap()
+    .read_to_end(&mut font_data)
+    .unwrap();
+
+  let context = GlobalContext::builder()
+    .font(FontResource::new(font_data))
+    .fallbacks("Geist", ["Noto Sans"])
+    .build();
+
+  assert!(context.is_ok());
+  let Ok(context) = context else { unreachable!() };
+
+  assert_eq!(
+    context.font_context().fallbacks("geist"),
+    [Box::<str>::from("Noto Sans")]
+  );
+}
+
+#[test]
+fn test_global_context_builder_reports_font_errors() {
+  let result = GlobalContext::builder()
+    .font(FontResource::new(vec![0x00, 0x01, 0x02, 0x03]))
+    .build();
+
+  assert!(matches!(result, Err(FontError::UnsupportedFormat)));
+}
@@ -1,15 +1,21 @@
 mod test_utils;
 
+use std::str::FromStr;
+
 use takumi::{
   layout::{
     Viewport,
-    node::Node,
+    node::{Node, SvgData},
     style::{
-      Affine, AlignItems, BorderStyle, Color, ColorInput, Display, FlexDirection, JustifyContent,
-      Length::*, Position, Sides, Style, StyleDeclaration,
+      Affine, AlignItems, BorderStyle, Color, ColorInput, Display, FlexDirection, FontKerning,
+      FontVariantCaps, JustifyContent, Length::*, LineBreak, Position, Sides, Style,
+      StyleDeclaration, StyleDeclarationBlock, TextAutospace,
     },
   },
-  rendering::{MeasuredNode, MeasuredTextRun, RenderOptions, measure_layout},
+  rendering::{
+    MeasuredNode, MeasuredTextLine, MeasuredTextRun, RenderOptions, measure_layout, outline_text,
+    render,
+  },
 };
 use test_utils::CONTEXT;
 
@@ -72,6 +78,7 @@ fn test_measure_simple_container() {
       transform: Affine::IDENTITY.to_cols_array(),
       children: Vec::new(),
       runs: Vec::new(),
+      lines: Vec::new(),
     }
   );
 }
@@ -112,8 +119,17 @@ fn test_measure_text_node() {
           width: 105.46001,
           height: 26.0,
         }],
+        lines: vec![MeasuredTextLine {
+          x: 0.0,
+          y: 0.0,
+          width: 105.46001,
+          height: 26.0,
+          baseline: 20.0,
+          cap_height: 14.2,
+        }],
       }],
       runs: Vec::new(),
+      lines: Vec::new(),
     }
   )
 }
@@ -266,12 +282,31 @@ fn test_measure_inline_layout() {
           height: 26.0,
         },
       ],
+      lines: vec![
+        MeasuredTextLine {
+          x: 0.0,
+          y: -3.0,
+          width: 361.26,
+          height: 134.0,
+          baseline: 125.0,
+          cap_height: 14.2,
+        },
+        MeasuredTextLine {
+          x: 0.0,
+          y: 128.0,
+          width: 85.71999,
+          height: 26.0,
+          baseline: 148.0,
+          cap_height: 14.2,
+        },
+      ],
       children: vec![MeasuredNode {
         width: 128.0,
         height: 128.0,
         transform: [1.0, 0.0, 0.0, 1.0, 105.46001, -3.0],
         children: Vec::new(),
         runs: Vec::new(),
+        lines: Vec::new(),
       }],
     }
   )
@@ -318,7 +353,7 @@ fn test_measure_inline_layout_preserves_text_span_boundaries() {
       .iter()
       .map(|run| run.text.as_str())
       .collect::<Vec<_>>(),
-    vec!["STEAM", "education can", "for everyone."]
+    vec!["STEAM ", "education can", " for everyone."]
   );
 }
 
@@ -608,3 +643,532 @@ fn test_measure_img_svg_attribute_sizing_cases() {
     assert_eq!(image.height, expected_height, "case {} height", case_index);
   }
 }
+
+#[test]
+fn test_outline_text_returns_positioned_glyph_paths() {
+  let node = Node::container([Node::text("Hi")]).with_style(
+    Style::default()
+      .with_padding(Sides([Px(10.0); 4]))
+      .with(StyleDeclaration::color(ColorInput::Value(Color([
+        255, 0, 0, 255,
+      ])))),
+  );
+
+  let outlines = outline_text(
+    RenderOptions::builder()
+      .viewport(Viewport::new((200, 100)))
+      .node(node)
+      .global(&CONTEXT)
+      .build(),
+  )
+  .unwrap();
+
+  assert_eq!(outlines.len(), 2);
+  assert_ne!(outlines[0].glyph_id, outlines[1].glyph_id);
+
+  for outline in &outlines {
+    assert!(outline.path.starts_with('M'));
+    assert_eq!(outline.color, [255, 0, 0, 255]);
+  }
+
+  // Glyphs are placed inside the padding box, left to right.
+  assert!(outlines[0].transform[4] >= 10.0);
+  assert!(outlines[1].transform[4] > outlines[0].transform[4]);
+}
+
+fn styled(css: &str, children: impl Into<Vec<Node>>) -> Node {
+  let declarations = StyleDeclarationBlock::from_str(css).unwrap();
+  Node::container(children).with_style(declarations.into())
+}
+
+/// A scene mixing relative, absolute and font-relative units, borders and shadows.
+fn scaled_units_scene(root_css: &str) -> Node {
+  styled(
+    &format!(
+      "display: flex; flex-direction: column; align-items: flex-start; gap: 6px; padding: 1pc; {root_css}"
+    ),
+    [
+      styled("width: 30pt; height: 2rem; background-color: red", []),
+      styled(
+        "font-size: 12px; width: 5em; height: 1in; padding: 3px; border: 4px solid blue; border-radius: 5px",
+        [],
+      ),
+      styled(
+        "width: 2cm; height: 8mm; margin-left: 1em; box-shadow: 2px 3px 4px 1px black; outline: 2px solid green",
+        [],
+      ),
+    ],
+  )
+}
+
+/// Asserts the layout scaled proportionally, allowing for layout rounding to whole device pixels.
+fn assert_scaled(scaled: &MeasuredNode, base: &MeasuredNode, scale: f32) {
+  let pairs = [
+    (scaled.width, base.width),
+    (scaled.height, base.height),
+    (scaled.transform[4], base.transform[4]),
+    (scaled.transform[5], base.transform[5]),
+  ];
+
+  for (actual, expected) in pairs {
+    assert!(
+      (actual - expected * scale).abs() <= 1.0,
+      "expected {}, got {actual}",
+      expected * scale
+    );
+  }
+
+  assert_eq!(scaled.children.len(), base.children.len());
+
+  for (scaled, base) in scaled.children.iter().zip(&base.children) {
+    assert_scaled(scaled, base, scale);
+  }
+}
+
+#[test]
+fn test_measure_scales_lengths_consistently_at_fractional_dpr() {
+  let base = measure(scaled_units_scene(""), create_measure_viewport());
+
+  for device_pixel_ratio in [1.5, 2.25] {
+    let scaled = measure(
+      scaled_units_scene(""),
+      create_measure_viewport_with_dpr(device_pixel_ratio),
+    );
+
+    // The root fills the viewport, whose size is rounded to whole pixels.
+    assert_eq!(scaled.children.len(), base.children.len());
+
+    for (scaled, base) in scaled.children.iter().zip(&base.children) {
+      assert_scaled(scaled, base, device_pixel_ratio);
+    }
+  }
+}
+
+#[test]
+fn test_measure_em_font_size_on_root_applies_device_pixel_ratio() {
+  let result = measure(
+    styled(
+      "display: flex",
+      [styled("font-size: 2em; width: 1em; height: 1em", [])],
+    ),
+    create_measure_viewport_with_dpr(1.5),
+  );
+
+  assert_close(result.children[0].width, 48.0);
+  assert_close(result.children[0].height, 48.0);
+}
+
+#[test]
+fn test_zoom_matches_device_pixel_ratio() {
+  let dpr_viewport = create_measure_viewport_with_dpr(1.5);
+  let zoom_viewport = Viewport::new((1800, 945));
+
+  assert_eq!(
+    measure(scaled_units_scene(""), dpr_viewport),
+    measure(scaled_units_scene("zoom: 1.5"), zoom_viewport),
+  );
+
+  let render_scene = |root_css: &str, viewport: Viewport| {
+    render(
+      RenderOptions::builder()
+        .viewport(viewport)
+        .node(scaled_units_scene(root_css))
+        .global(&CONTEXT)
+        .build(),
+    )
+    .unwrap()
+  };
+
+  assert_eq!(
+    render_scene("", dpr_viewport),
+    render_scene("zoom: 150%", zoom_viewport)
+  );
+}
+
+#[test]
+fn test_zoom_compounds_and_leaves_percentages_alone() {
+  let result = measure(
+    styled(
+      "display: flex; width: 100%; height: 100%; zoom: 2",
+      [styled(
+        "display: flex; zoom: 0.5; width: 50%; height: 10px",
+        [styled("width: 10px; height: 10px", [])],
+      )],
+    ),
+    create_measure_viewport(),
+  );
+
+  // The net zoom is 1, so percentages and lengths resolve as if unzoomed.
+  let zoomed = &result.children[0];
+  assert_close(zoomed.width, 600.0);
+  assert_close(zoomed.height, 10.0);
+  assert_close(zoomed.children[0].width, 10.0);
+}
+
+#[test]
+fn test_math_functions_resolve_against_containing_block() {
+  let clamped = |container_width: &str| {
+    measure(
+      styled(
+        &format!("display: flex; width: {container_width}; height: 100px"),
+        [styled(
+          "width: clamp(200px, 50%, 480px); height: calc(max(10px, 20%) - min(2px, 1%))",
+          [],
+        )],
+      ),
+      create_measure_viewport(),
+    )
+    .children
+    .remove(0)
+  };
+
+  assert_close(clamped("300px").width, 200.0);
+  assert_close(clamped("600px").width, 300.0);
+  assert_close(clamped("1200px").width, 480.0);
+  assert_close(clamped("600px").height, 19.0);
+}
+
+#[test]
+fn test_position_anchor_attaches_to_sibling_edges() {
+  let result = measure(
+    styled(
+      "display: flex; padding: 20px",
+      [
+        styled("width: 100px; height: 40px; margin-left: 30px", []).with_id("target"),
+        styled(
+          "position: absolute; position-anchor: target; left: 100%; top: -4px; width: 10px; height: 10px",
+          [],
+        ),
+        styled(
+          "position: absolute; position-anchor: #target; right: 0; bottom: 100%; width: 10px; height: 10px",
+          [],
+        ),
+        styled(
+          "position: absolute; position-anchor: missing; left: 5px; top: 5px; width: 10px; height: 10px",
+          [],
+        ),
+      ],
+    ),
+    create_measure_viewport(),
+  );
+
+  let badge = &result.children[1];
+  assert_close(badge.transform[4], 150.0);
+  assert_close(badge.transform[5], 16.0);
+
+  let callout = &result.children[2];
+  assert_close(callout.transform[4], 140.0);
+  assert_close(callout.transform[5], 10.0);
+
+  // Without a matching anchor, insets resolve against the containing block as usual.
+  let unanchored = &result.children[3];
+  assert_close(unanchored.transform[4], 5.0);
+  assert_close(unanchored.transform[5], 5.0);
+}
+
+#[test]
+fn test_position_anchor_follows_anchor_in_another_subtree() {
+  let result = measure(
+    styled(
+      "display: flex; flex-direction: column; padding: 10px",
+      [
+        styled(
+          "display: flex; padding-left: 40px",
+          [styled("width: 60px; height: 20px", []).with_id("label")],
+        ),
+        styled(
+          "display: flex; position: relative; margin-top: 50px; width: 200px; height: 50px",
+          [styled(
+            "position: absolute; position-anchor: label; left: 0; top: 100%; width: 100%; height: 2px",
+            [],
+          )],
+        ),
+      ],
+    ),
+    create_measure_viewport(),
+  );
+
+  let underline = &result.children[1].children[0];
+  assert_close(underline.transform[4], 50.0);
+  assert_close(underline.transform[5], 30.0);
+  // Percentage sizes still resolve against the containing block.
+  assert_close(underline.width, 200.0);
+}
+
+#[test]
+fn test_measure_svg_node_scales_intrinsic_size_with_device_pixel_ratio() {
+  let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20"><rect width="40" height="20"/></svg>"#;
+
+  let result = measure(
+    Node::container([
+      Node::svg(svg),
+      Node::svg(SvgData::from_bytes(svg.as_bytes()).with_size(Some(60.0), None)),
+    ])
+    .with_style(
+      Style::default()
+        .with(StyleDeclaration::display(Display::Flex))
+        .with(StyleDeclaration::align_items(AlignItems::FlexStart)),
+    ),
+    create_measure_viewport_with_dpr(2.0),
+  );
+
+  assert_close(result.children[0].width, 80.0);
+  assert_close(result.children[0].height, 40.0);
+  assert_close(result.children[1].width, 120.0);
+  assert_close(result.children[1].height, 60.0);
+}
+
+fn text_runs(node: Node) -> Vec<MeasuredTextRun> {
+  fn collect_runs(node: &MeasuredNode, runs: &mut Vec<MeasuredTextRun>) {
+    runs.extend(node.runs.iter().cloned());
+    for child in &node.children {
+      collect_runs(child, runs);
+    }
+  }
+
+  let mut runs = Vec::new();
+  collect_runs(&measure(node, create_measure_viewport()), &mut runs);
+  runs
+}
+
+fn first_line_text(node: Node) -> String {
+  let runs = text_runs(node);
+
+  let first_line_y = runs[0].y;
+  runs
+    .iter()
+    .filter(|run| (run.y - first_line_y).abs() < 1.0)
+    .map(|run| run.text.as_str())
+    .collect()
+}
+
+#[test]
+fn test_measure_line_break_controls_small_kana_at_line_start() {
+  let node = |line_break: LineBreak| {
+    Node::text("あいうえおっか".to_string()).with_style(
+      Style::default()
+        .with(StyleDeclaration::display(Display::Flex))
+        .with(StyleDeclaration::width(Px(100.0)))
+        .with(StyleDeclaration::font_size(Px(20.0).into()))
+        .with(StyleDeclaration::line_break(line_break)),
+    )
+  };
+
+  // `っ` may not start a line, so `お` moves down with it.
+  assert_eq!(first_line_text(node(LineBreak::Strict)), "あいうえ");
+  assert_eq!(first_line_text(node(LineBreak::Normal)), "あいうえお");
+}
+
+#[test]
+fn test_measure_text_autospace_spaces_ideographs_from_latin() {
+  let runs = |autospace: TextAutospace| {
+    text_runs(
+      Node::text("日本Web開発".to_string()).with_style(
+        Style::default()
+          .with(StyleDeclaration::display(Display::Flex))
+          .with(StyleDeclaration::font_size(Px(20.0).into()))
+          .with(StyleDeclaration::text_autospace(autospace)),
+      ),
+    )
+  };
+  let width = |runs: &[MeasuredTextRun]| runs.iter().map(|run| run.width).sum::<f32>();
+  let text = |runs: &[MeasuredTextRun]| {
+    runs
+      .iter()
+      .flat_map(|run| run.text.chars())
+      .filter(|ch| *ch != '\u{200B}')
+      .collect::<String>()
+  };
+
+  let plain = runs(TextAutospace::NoAutospace);
+  let spaced = runs(TextAutospace::Normal);
+
+  // Both `本|W` and `b|開` gain 1/8 of the font size through zero width spacers.
+  assert_close(width(&spaced) - width(&plain), 5.0);
+  assert_eq!(text(&spaced), text(&plain));
+}
+
+#[test]
+fn test_grid_area_places_items_into_named_template_areas() {
+  let result = measure(
+    styled(
+      r#"display: grid; width: 300px; height: 200px; grid-template-columns: 100px 1fr; grid-template-rows: 50px 1fr 30px; grid-template-areas: "header header" "sidebar main" "footer footer""#,
+      [
+        styled("grid-area: footer", []),
+        styled("grid-area: main", []),
+        styled("grid-area: header", []),
+        styled(
+          "grid-row: sidebar-start / sidebar-end; grid-column: sidebar",
+          [],
+        ),
+      ],
+    ),
+    create_measure_viewport(),
+  );
+
+  let placements = result
+    .children
+    .iter()
+    .map(|child| {
+      (
+        child.transform[4],
+        child.transform[5],
+        child.width,
+        child.height,
+      )
+    })
+    .collect::<Vec<_>>();
+
+  assert_eq!(
+    placements,
+    [
+      (0.0, 170.0, 300.0, 30.0),
+      (100.0, 50.0, 200.0, 120.0),
+      (0.0, 0.0, 300.0, 50.0),
+      (0.0, 50.0, 100.0, 120.0),
+    ]
+  );
+}
+
+fn outlined_glyph_count(css: &str, text: &str) -> usize {
+  let declarations = StyleDeclarationBlock::from_str(css).unwrap();
+  let node = Node::container([Node::text(text.to_string())]).with_style(declarations.into());
+
+  outline_text(
+    RenderOptions::builder()
+      .viewport(Viewport::new((400, 100)))
+      .node(node)
+      .global(&CONTEXT)
+      .build(),
+  )
+  .unwrap()
+  .len()
+}
+
+#[test]
+fn test_font_variant_ligatures_none_disables_ligatures() {
+  let ligated = outlined_glyph_count("font-size: 32px", "office");
+  let unligated = outlined_glyph_count("font-size: 32px; font-variant-ligatures: none", "office");
+
+  // `ffi` is a single ligature glyph unless ligatures are disabled.
+  assert_eq!(unligated, 6);
+  assert!(ligated < unligated);
+
+  // `font-feature-settings` takes precedence over `font-variant-ligatures`.
+  let reenabled = outlined_glyph_count(
+    "font-size: 32px; font-variant-ligatures: none; font-feature-settings: \"liga\" 1",
+    "office",
+  );
+  assert_eq!(reenabled, ligated);
+}
+
+#[test]
+fn test_font_kerning_none_disables_kerning() {
+  let width = |kerning: FontKerning| {
+    text_runs(
+      Node::text("AVAVAVTo".to_string()).with_style(
+        Style::default()
+          .with(StyleDeclaration::display(Display::Flex))
+          .with(StyleDeclaration::font_size(Px(32.0).into()))
+          .with(StyleDeclaration::font_kerning(kerning)),
+      ),
+    )
+    .iter()
+    .map(|run| run.width)
+    .sum::<f32>()
+  };
+
+  assert_close(width(FontKerning::Normal), width(FontKerning::Auto));
+  assert!(width(FontKerning::None) > width(FontKerning::Auto));
+}
+
+#[test]
+fn test_font_variant_caps_synthesizes_small_caps() {
+  let width = |text: &str, caps: FontVariantCaps| {
+    text_runs(
+      Node::text(text.to_string()).with_style(
+        Style::default()
+          .with(StyleDeclaration::display(Display::Flex))
+          .with(StyleDeclaration::font_size(Px(40.0).into()))
+          .with(StyleDeclaration::font_kerning(FontKerning::None))
+          .with(StyleDeclaration::font_variant_caps(caps)),
+      ),
+    )
+    .iter()
+    .map(|run| run.width)
+    .sum::<f32>()
+  };
+
+  let capitals = width("HELLO", FontVariantCaps::Normal);
+
+  // Geist has no `smcp` feature, so lowercase letters become capitals at 70% of the size.
+  assert_close(width("hello", FontVariantCaps::SmallCaps), capitals * 0.7);
+  assert_close(width("HELLO", FontVariantCaps::SmallCaps), capitals);
+  assert_close(
+    width("Hello", FontVariantCaps::AllSmallCaps),
+    capitals * 0.7,
+  );
+}
+
+#[test]
+fn test_tab_size_aligns_tabs_to_tab_stops() {
+  let width = |css: &str, text: &str| {
+    let declarations = StyleDeclarationBlock::from_str(&format!(
+      "display: flex; font-family: Geist Mono; font-size: 20px; white-space: pre; {css}"
+    ))
+    .unwrap();
+
+    text_runs(Node::text(text.to_string()).with_style(declarations.into()))
+      .iter()
+      .map(|run| run.width)
+      .sum::<f32>()
+  };
+
+  let spaced = width("", "ab  cd");
+
+  assert_close(width("tab-size: 4", "ab\tcd"), spaced);
+  assert_close(width("tab-size: 24px", "ab\tcd"), spaced);
+  assert_close(width("", "ab\tcd"), width("", "ab      cd"));
+}
+
+fn shrink_to_fit_width(css: &str, children: Vec<Node>) -> f32 {
+  let declarations = StyleDeclarationBlock::from_str(css).unwrap();
+  let node = Node::container([Node::container(children).with_style(declarations.into())])
+    .with_style(
+      Style::default()
+        .with(StyleDeclaration::display(Display::Flex))
+        .with(StyleDeclaration::font_size(Px(20.0).into())),
+    );
+
+  measure(node, create_measure_viewport()).children[0].width
+}
+
+#[test]
+fn test_white_space_collapses_across_inline_spans() {
+  let joined = shrink_to_fit_width("", vec![Node::text("Hello world".to_string())]);
+  let spans = shrink_to_fit_width(
+    "",
+    vec![
+      Node::text(" Hello  ".to_string()),
+      Node::text(" world ".to_string()),
+    ],
+  );
+
+  assert_close(spans, joined);
+}
+
+#[test]
+fn test_trailing_spaces_hang_at_line_end() {
+  let word = shrink_to_fit_width("", vec![Node::text("Hello".to_string())]);
+
+  assert_close(
+    shrink_to_fit_width(
+      "white-space: pre-wrap",
+      vec![Node::text("Hello   ".to_string())],
+    ),
+    word,
+  );
+
+  // `white-space: pre` never wraps, so its spaces stay part of the line.
+  assert!(shrink_to_fit_width("white-space: pre", vec![Node::text("Hello   ".to_string())]) > word);
+}